@@ -0,0 +1,91 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::theme::theme;
+
+/// A one-line yes/no question, e.g. before deleting something.
+///
+/// A tool keeps an `Option<ConfirmPrompt>`, sends it every key while it is
+/// open and drops it once `handle_key` resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmPrompt {
+    message: String,
+}
+
+impl ConfirmPrompt {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// The question, without the `(y/n)` hint.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// `Some(true)` for `y`, `Some(false)` for `n` or `Esc`, `None` for any
+    /// other key (the prompt stays open).
+    pub fn handle_key(&self, key: KeyEvent) -> Option<bool> {
+        match key.code {
+            KeyCode::Char('y' | 'Y') => Some(true),
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Render the question and a `(y/n)` hint on the first row of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let area = Rect { height: 1, ..area };
+        let line = Line::from(vec![
+            Span::styled(
+                format!("{} ", self.message),
+                Style::default()
+                    .fg(theme().error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("(y/n)", Style::default().add_modifier(Modifier::DIM)),
+        ]);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let prompt = ConfirmPrompt::new("Delete it?");
+        assert_eq!(prompt.handle_key(key(KeyCode::Char('y'))), Some(true));
+        assert_eq!(prompt.handle_key(key(KeyCode::Char('Y'))), Some(true));
+        assert_eq!(prompt.handle_key(key(KeyCode::Char('n'))), Some(false));
+        assert_eq!(prompt.handle_key(key(KeyCode::Esc)), Some(false));
+        assert_eq!(prompt.handle_key(key(KeyCode::Char('j'))), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut terminal = Terminal::new(TestBackend::new(24, 2)).unwrap();
+        let prompt = ConfirmPrompt::new("Delete it?");
+        terminal
+            .draw(|frame| prompt.render(frame, frame.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..24).map(|x| buffer[(x, 0)].symbol()).collect();
+        assert_eq!(row, "Delete it? (y/n)        ");
+        assert_eq!(buffer[(0, 1)].symbol(), " ");
+    }
+}
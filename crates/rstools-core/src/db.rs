@@ -2,10 +2,21 @@ use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-/// Returns the path to the shared rstools database.
-/// Location: `~/.local/share/rstools/rstools.db` (XDG-compliant)
+/// How long a write waits for another connection's write to finish before
+/// failing with "database is locked". Every tool has its own connection.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Environment variable overriding where the shared database lives.
+pub const DB_PATH_ENV: &str = "RSTOOLS_DB";
+
+/// Returns the path to the shared rstools database: `$RSTOOLS_DB` if set,
+/// else `~/.local/share/rstools/rstools.db` (XDG-compliant).
 pub fn db_path() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os(DB_PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
     let dirs =
         ProjectDirs::from("", "", "rstools").context("Could not determine data directory")?;
     let data_dir = dirs.data_dir();
@@ -21,9 +32,14 @@ pub fn open_db() -> Result<Connection> {
     open_db_at(&path)
 }
 
-/// Opens (or creates) a SQLite database at a specific path.
-/// Enables WAL mode and foreign keys.
+/// Opens (or creates) a SQLite database at a specific path, creating its
+/// parent directories if needed. Enables WAL mode, foreign keys and a
+/// `BUSY_TIMEOUT` so concurrent writes from other connections are retried.
 pub fn open_db_at(path: &Path) -> Result<Connection> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
     let conn = Connection::open(path)
         .with_context(|| format!("Failed to open database at {}", path.display()))?;
 
@@ -31,6 +47,8 @@ pub fn open_db_at(path: &Path) -> Result<Connection> {
     conn.pragma_update(None, "journal_mode", "WAL")?;
     // Enable foreign keys
     conn.pragma_update(None, "foreign_keys", "ON")?;
+    // Wait for the other tools' writes instead of failing
+    conn.busy_timeout(BUSY_TIMEOUT)?;
 
     Ok(conn)
 }
@@ -41,3 +59,233 @@ pub fn open_memory_db() -> Result<Connection> {
     conn.pragma_update(None, "foreign_keys", "ON")?;
     Ok(conn)
 }
+
+/// One step of a tool's schema history. A tool keeps its steps in a list,
+/// oldest first, and `migrate` runs the ones its database has not seen yet;
+/// a step's position in the list (from 1) is the version it leads to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Migration {
+    /// `ALTER TABLE table ADD COLUMN column definition`. Skipped when the
+    /// column already exists (databases from before schema versioning).
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+    /// Any other change, run as one batch.
+    Sql(&'static str),
+}
+
+impl Migration {
+    fn apply(&self, conn: &Connection) -> Result<()> {
+        match *self {
+            Migration::AddColumn {
+                table,
+                column,
+                definition,
+            } => {
+                if !has_column(conn, table, column)? {
+                    conn.execute_batch(&format!(
+                        "ALTER TABLE {table} ADD COLUMN {column} {definition};"
+                    ))?;
+                }
+            }
+            Migration::Sql(sql) => conn.execute_batch(sql)?,
+        }
+        Ok(())
+    }
+}
+
+/// Whether `table` has a column named `column`.
+pub fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2")?
+        .exists(rusqlite::params![table, column])?;
+    Ok(exists)
+}
+
+/// Bring `tool`'s tables up to date: run the steps of `migrations` past the
+/// version recorded in `schema_version`, each in a transaction with its
+/// version bump. Call it from `init_db` after creating the base tables;
+/// running it again is a no-op.
+pub fn migrate(conn: &Connection, tool: &str, migrations: &[Migration]) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            tool    TEXT PRIMARY KEY,
+            version INTEGER NOT NULL
+        );",
+    )?;
+    let current = schema_version(conn, tool)?;
+    for (index, migration) in migrations.iter().enumerate().skip(current) {
+        let tx = conn.unchecked_transaction()?;
+        migration
+            .apply(&tx)
+            .with_context(|| format!("Migration {} of {tool} failed", index + 1))?;
+        tx.execute(
+            "INSERT INTO schema_version (tool, version) VALUES (?1, ?2)
+             ON CONFLICT(tool) DO UPDATE SET version = excluded.version",
+            rusqlite::params![tool, (index + 1) as i64],
+        )?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// The schema version recorded for `tool` (0 before its first migration).
+fn schema_version(conn: &Connection, tool: &str) -> Result<usize> {
+    let result = conn.query_row(
+        "SELECT version FROM schema_version WHERE tool = ?1",
+        rusqlite::params![tool],
+        |row| row.get::<_, i64>(0),
+    );
+    match result {
+        Ok(version) => Ok(version as usize),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Create the hub's key/value state table (last active tool, ...).
+pub fn init_app_state(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_state (
+            key   TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+/// Read a value from the hub state table.
+pub fn get_app_state(conn: &Connection, key: &str) -> Result<Option<String>> {
+    let result = conn.query_row(
+        "SELECT value FROM app_state WHERE key = ?1",
+        rusqlite::params![key],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write a value to the hub state table.
+pub fn set_app_state(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO app_state (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        rusqlite::params![key, value],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_db_at_creates_parent_dirs() {
+        let dir = std::env::temp_dir().join(format!("rstools-db-{}", std::process::id()));
+        let path = dir.join("profiles/work/rstools.db");
+        let conn = open_db_at(&path).unwrap();
+        init_app_state(&conn).unwrap();
+        assert!(path.is_file());
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_writes_wait() {
+        let dir = std::env::temp_dir().join(format!("rstools-db-busy-{}", std::process::id()));
+        let path = dir.join("rstools.db");
+        let first = open_db_at(&path).unwrap();
+        let second = open_db_at(&path).unwrap();
+        init_app_state(&first).unwrap();
+        let mode: String = first
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+
+        // `first` holds the write lock while `second` tries to write
+        first.execute_batch("BEGIN IMMEDIATE").unwrap();
+        set_app_state(&first, "writer", "first").unwrap();
+        let writer = std::thread::spawn(move || set_app_state(&second, "other", "second"));
+        std::thread::sleep(Duration::from_millis(200));
+        first.execute_batch("COMMIT").unwrap();
+
+        writer.join().unwrap().unwrap();
+        assert_eq!(
+            get_app_state(&first, "other").unwrap().as_deref(),
+            Some("second")
+        );
+        drop(first);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_preserves_rows() {
+        const MIGRATIONS: &[Migration] = &[
+            Migration::AddColumn {
+                table: "items",
+                column: "done",
+                definition: "INTEGER NOT NULL DEFAULT 0",
+            },
+            Migration::Sql("CREATE INDEX idx_items_done ON items(done);"),
+        ];
+        let conn = open_memory_db().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO items (name) VALUES ('old');",
+        )
+        .unwrap();
+
+        migrate(&conn, "items", &MIGRATIONS[..1]).unwrap();
+        assert_eq!(schema_version(&conn, "items").unwrap(), 1);
+        migrate(&conn, "items", MIGRATIONS).unwrap();
+        migrate(&conn, "items", MIGRATIONS).unwrap();
+        assert_eq!(schema_version(&conn, "items").unwrap(), 2);
+        assert_eq!(schema_version(&conn, "other").unwrap(), 0);
+
+        let row: (String, i64) = conn
+            .query_row("SELECT name, done FROM items", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(row, ("old".to_string(), 0));
+    }
+
+    #[test]
+    fn test_migrate_adopts_unversioned_columns() {
+        let conn = open_memory_db().unwrap();
+        // Column added before schema versioning existed
+        conn.execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY, done INTEGER);")
+            .unwrap();
+        let add_done = Migration::AddColumn {
+            table: "items",
+            column: "done",
+            definition: "INTEGER",
+        };
+        migrate(&conn, "items", &[add_done]).unwrap();
+        assert!(has_column(&conn, "items", "done").unwrap());
+        assert!(!has_column(&conn, "items", "missing").unwrap());
+
+        let broken = Migration::Sql("ALTER TABLE nope ADD COLUMN x;");
+        assert!(migrate(&conn, "items", &[add_done, broken]).is_err());
+        assert_eq!(schema_version(&conn, "items").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_app_state_roundtrip() {
+        let conn = open_memory_db().unwrap();
+        init_app_state(&conn).unwrap();
+        assert_eq!(get_app_state(&conn, "last_tool").unwrap(), None);
+
+        set_app_state(&conn, "last_tool", "Notes").unwrap();
+        set_app_state(&conn, "last_tool", "HTTP").unwrap();
+        assert_eq!(
+            get_app_state(&conn, "last_tool").unwrap().as_deref(),
+            Some("HTTP")
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
@@ -6,6 +7,9 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph},
 };
 
+use crate::keybinds::{KeyAction, KeyBinding, keymap};
+use crate::telescope::TelescopeItem;
+
 /// A single entry in the help popup.
 #[derive(Debug, Clone)]
 pub struct HelpEntry {
@@ -148,7 +152,7 @@ impl HelpPopup {
         // Footer
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            " Esc/q/?  close    j/k  scroll",
+            " Esc/q/?  close    j/k  scroll    /  search",
             Style::default().add_modifier(Modifier::DIM),
         )));
 
@@ -157,35 +161,182 @@ impl HelpPopup {
 }
 
 /// Returns the global keybind help entries (shown when no tool or on dashboard).
+/// Keys follow the installed keymap.
 pub fn global_help_entries() -> Vec<HelpEntry> {
+    let key = help_key;
+    let leader = key(KeyAction::Leader);
+    let section = format!("Leader ({})", leader.trim_matches(['<', '>']));
+    let goto = key(KeyAction::GotoPrefix);
+    let delete = key(KeyAction::Delete);
     vec![
-        HelpEntry::with_section("Navigation", "j / k", "Move down / up"),
-        HelpEntry::with_section("Navigation", "gg", "Go to top"),
-        HelpEntry::with_section("Navigation", "G", "Go to bottom"),
-        HelpEntry::with_section("Navigation", "Ctrl-d / Ctrl-u", "Half-page down / up"),
-        HelpEntry::with_section("Navigation", "gt / gT", "Next / previous tool tab"),
-        HelpEntry::with_section("Actions", "Enter", "Confirm / select / toggle"),
-        HelpEntry::with_section("Actions", "dd", "Delete item"),
-        HelpEntry::with_section("Actions", "a / o", "Add item / add below"),
-        HelpEntry::with_section("Actions", "e", "Edit item"),
-        HelpEntry::with_section("Actions", "i", "Enter Insert mode"),
-        HelpEntry::with_section("Actions", "/", "Search / filter"),
-        HelpEntry::with_section("Leader (Space)", "<Space>", "Open leader menu"),
-        HelpEntry::with_section("Leader (Space)", "<Space><Space>", "Tool picker"),
-        HelpEntry::with_section("Leader (Space)", "<Space>f", "Find (telescope)"),
-        HelpEntry::with_section("Leader (Space)", "<Space>t", "Todo"),
-        HelpEntry::with_section("Leader (Space)", "<Space>1-9", "Switch to tool"),
-        HelpEntry::with_section("Leader (Space)", "<Space>q", "Quit"),
-        HelpEntry::with_section("Other", ":", "Command mode"),
+        HelpEntry::with_section(
+            "Navigation",
+            format!("{} / {}", key(KeyAction::MoveDown), key(KeyAction::MoveUp)),
+            "Move down / up",
+        ),
+        HelpEntry::with_section("Navigation", format!("{goto}{goto}"), "Go to top"),
+        HelpEntry::with_section("Navigation", key(KeyAction::GotoBottom), "Go to bottom"),
+        HelpEntry::with_section(
+            "Navigation",
+            format!(
+                "{} / {}",
+                key(KeyAction::HalfPageDown),
+                key(KeyAction::HalfPageUp)
+            ),
+            "Half-page down / up",
+        ),
+        HelpEntry::with_section(
+            "Navigation",
+            format!("{goto}t / {goto}T"),
+            "Next / previous tool tab",
+        ),
+        HelpEntry::with_section(
+            "Actions",
+            key(KeyAction::Confirm),
+            "Confirm / select / toggle",
+        ),
+        HelpEntry::with_section("Actions", format!("{delete}{delete}"), "Delete item"),
+        HelpEntry::with_section(
+            "Actions",
+            format!("{} / {}", key(KeyAction::Add), key(KeyAction::AddBelow)),
+            "Add item / add below",
+        ),
+        HelpEntry::with_section("Actions", key(KeyAction::Edit), "Edit item"),
+        HelpEntry::with_section("Actions", key(KeyAction::Insert), "Enter Insert mode"),
+        HelpEntry::with_section("Actions", key(KeyAction::Search), "Search / filter"),
+        HelpEntry::with_section(&section, leader.clone(), "Open leader menu"),
+        HelpEntry::with_section(
+            &section,
+            format!("{leader}{}", key(KeyAction::ToolPicker)),
+            "Tool picker",
+        ),
+        HelpEntry::with_section(
+            &section,
+            format!("{leader}{}", key(KeyAction::Telescope)),
+            "Find (telescope)",
+        ),
+        HelpEntry::with_section(&section, format!("{leader}t"), "Todo"),
+        HelpEntry::with_section(&section, format!("{leader}1-9"), "Switch to tool"),
+        HelpEntry::with_section(&section, format!("{leader}q"), "Quit"),
+        HelpEntry::with_section("Other", key(KeyAction::Command), "Command mode"),
         HelpEntry::with_section("Other", ":wq", "Save and close tool / quit"),
         HelpEntry::with_section("Other", ":wqa", "Save current tool and quit all"),
         HelpEntry::with_section("Other", ":q", "Close tool / quit"),
         HelpEntry::with_section("Other", ":qa", "Quit all"),
-        HelpEntry::with_section("Other", "?", "This help"),
+        HelpEntry::with_section("Other", key(KeyAction::Help), "This help"),
+        HelpEntry::with_section("Other", ":help", "Search help and run a keybind"),
         HelpEntry::with_section("Other", "Ctrl-c", "Force quit"),
     ]
 }
 
+/// The key bound to `action`, written the way help entries write keys
+/// (`Ctrl-d`, `Enter`, `<Space>`).
+pub fn help_key(action: KeyAction) -> String {
+    let label = keymap().label(action);
+    match label.strip_prefix("<C-").and_then(|l| l.strip_suffix('>')) {
+        Some(key) => format!("Ctrl-{key}"),
+        None if label == "<Enter>" => "Enter".to_string(),
+        None => label,
+    }
+}
+
+// ── Command palette ──────────────────────────────────────────────────
+
+/// Prefix of the telescope ids produced by `palette_items`; the rest of the
+/// id is the entry's key.
+pub const PALETTE_ID_PREFIX: &str = "help:";
+
+/// Longest key sequence the palette replays; longer keys are notation
+/// (`@date`) rather than something to press.
+const MAX_PALETTE_KEYS: usize = 3;
+
+/// Help entries as telescope items for the searchable `:help` palette.
+pub fn palette_items(entries: &[HelpEntry]) -> Vec<TelescopeItem> {
+    entries
+        .iter()
+        .map(|entry| TelescopeItem {
+            label: entry.description.clone(),
+            description: match &entry.section {
+                Some(section) => format!("{}  ({})", entry.key, section),
+                None => entry.key.clone(),
+            },
+            id: format!("{PALETTE_ID_PREFIX}{}", entry.key),
+        })
+        .collect()
+}
+
+/// The key presses that run the help entry written as `key`, or `None` if
+/// it does not name one concrete action (ranges, placeholders, notation).
+/// Of alternatives (`a / o`, `j/k`) the first is used; `:cmd` entries are
+/// typed into command mode and submitted.
+pub fn entry_keys(key: &str) -> Option<Vec<KeyEvent>> {
+    let key = key.split(" / ").next()?.trim();
+
+    if let Some(cmd) = key.strip_prefix(':')
+        && !cmd.is_empty()
+    {
+        if cmd.contains(['<', '[', ' ']) {
+            return None;
+        }
+        let mut keys = vec![plain(':')];
+        keys.extend(cmd.chars().map(plain));
+        keys.push(KeyEvent::from(KeyCode::Enter));
+        return Some(keys);
+    }
+
+    let key = match key.split_once('/') {
+        Some((first, _)) if key.len() > 1 && !first.is_empty() => first,
+        _ => key,
+    };
+
+    let mut keys = Vec::new();
+    for word in key.split_whitespace() {
+        let named = word
+            .replace("Ctrl-", "C-")
+            .replace("Alt-", "A-")
+            .replace("Shift-", "S-");
+        if word.chars().count() > 1
+            && let Some(binding) = KeyBinding::parse(&format!("<{named}>"))
+        {
+            keys.push(KeyEvent::new(binding.code, binding.modifiers));
+            continue;
+        }
+
+        let mut rest = word;
+        while !rest.is_empty() {
+            if rest.starts_with('<')
+                && let Some(end) = rest.find('>')
+            {
+                let binding = KeyBinding::parse(&rest[..=end])?;
+                keys.push(KeyEvent::new(binding.code, binding.modifiers));
+                rest = &rest[end + 1..];
+                continue;
+            }
+            let c = rest.chars().next()?;
+            if c == '-' && word.len() > 1 {
+                return None;
+            }
+            keys.push(plain(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    // Entries are written with the default leader; replay the configured one
+    if let Some(rest) = key.strip_prefix("<Space>") {
+        let leader = keymap().binding(KeyAction::Leader)?;
+        keys[0] = leader.to_event();
+        if rest == "<Space>" {
+            keys[1] = keymap().binding(KeyAction::ToolPicker)?.to_event();
+        }
+    }
+
+    (!keys.is_empty() && keys.len() <= MAX_PALETTE_KEYS).then_some(keys)
+}
+
+fn plain(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
 /// Helper to create a centered rect within a given area.
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
@@ -194,3 +345,51 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let [area] = horizontal.areas(area);
     area
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(key: &str) -> Option<Vec<(KeyCode, KeyModifiers)>> {
+        entry_keys(key).map(|keys| keys.iter().map(|k| (k.code, k.modifiers)).collect())
+    }
+
+    #[test]
+    fn test_entry_keys() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            codes("dd"),
+            Some(vec![(KeyCode::Char('d'), none), (KeyCode::Char('d'), none)])
+        );
+        assert_eq!(codes("a / o"), Some(vec![(KeyCode::Char('a'), none)]));
+        assert_eq!(codes("j/k"), Some(vec![(KeyCode::Char('j'), none)]));
+        assert_eq!(codes("/"), Some(vec![(KeyCode::Char('/'), none)]));
+        assert_eq!(
+            codes("Ctrl-d / Ctrl-u"),
+            Some(vec![(KeyCode::Char('d'), KeyModifiers::CONTROL)])
+        );
+        assert_eq!(codes("Enter"), Some(vec![(KeyCode::Enter, none)]));
+        assert_eq!(
+            codes("<Space>st"),
+            Some(vec![
+                (KeyCode::Char(' '), none),
+                (KeyCode::Char('s'), none),
+                (KeyCode::Char('t'), none),
+            ])
+        );
+        assert_eq!(codes(":qa").map(|k| k.len()), Some(4));
+
+        // Ranges, placeholders and notation are not runnable
+        assert_eq!(codes("<Space>1-9"), None);
+        assert_eq!(codes(":export <path>"), None);
+        assert_eq!(codes("@date"), None);
+    }
+
+    #[test]
+    fn test_palette_items() {
+        let items = palette_items(&[HelpEntry::with_section("Todo", "dd", "Delete todo")]);
+        assert_eq!(items[0].label, "Delete todo");
+        assert_eq!(items[0].description, "dd  (Todo)");
+        assert_eq!(items[0].id, "help:dd");
+    }
+}
@@ -1,3 +1,9 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 /// Input modes, modeled after vim.
@@ -86,83 +92,483 @@ pub enum Action {
 /// Pending key state for multi-key sequences like `gg`, `dd`, `gt`, `gT`.
 #[derive(Debug, Default, Clone)]
 pub struct KeyState {
-    /// Whether the leader key (Space) was just pressed.
-    pub leader_active: bool,
     /// Pending first key of a two-key sequence (e.g., 'g' for gg/gt/gT, 'd' for dd).
     pub pending_key: Option<char>,
 }
 
 impl KeyState {
     pub fn reset(&mut self) {
-        self.leader_active = false;
         self.pending_key = None;
     }
+
+    /// The keys typed so far of an unfinished sequence, for the status bar.
+    pub fn pending_keys(&self) -> String {
+        self.pending_key.map(String::from).unwrap_or_default()
+    }
 }
 
-/// Process a key event in Normal mode, accounting for multi-key sequences.
-/// Returns an Action and whether the key_state was consumed/reset.
-pub fn process_normal_key(key: KeyEvent, state: &mut KeyState) -> Action {
-    // If leader is active, process leader sequences
-    if state.leader_active {
-        state.leader_active = false;
-        return match key.code {
-            KeyCode::Char(' ') => Action::ToolPicker,
-            KeyCode::Char('f') => {
-                // Start of <Space>f sequence — next key matters
-                // For now, treat <Space>f as telescope trigger
-                // (sub-sequences like ff, fg will be handled later)
-                Action::Telescope
-            }
-            KeyCode::Char(c @ '1'..='9') => {
-                let idx = (c as u8 - b'1') as usize;
-                Action::SwitchTool(idx)
+// ── Keymap ───────────────────────────────────────────────────────────
+
+/// A remappable key action. Its config name is `as_str()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    Leader,
+    MoveDown,
+    MoveUp,
+    /// First key of `gg` (top), `gt` and `gT` (next/previous tool).
+    GotoPrefix,
+    GotoBottom,
+    HalfPageDown,
+    HalfPageUp,
+    Confirm,
+    Search,
+    Add,
+    AddBelow,
+    Edit,
+    Insert,
+    /// Pressed twice to delete.
+    Delete,
+    Command,
+    Help,
+    Quit,
+    /// After the leader: open the tool picker.
+    ToolPicker,
+    /// After the leader: open telescope.
+    Telescope,
+}
+
+impl KeyAction {
+    /// Every action with its config name and default key.
+    pub const ALL: [(KeyAction, &'static str, &'static str); 19] = [
+        (KeyAction::Leader, "leader", "<Space>"),
+        (KeyAction::MoveDown, "move_down", "j"),
+        (KeyAction::MoveUp, "move_up", "k"),
+        (KeyAction::GotoPrefix, "goto_prefix", "g"),
+        (KeyAction::GotoBottom, "goto_bottom", "G"),
+        (KeyAction::HalfPageDown, "half_page_down", "<C-d>"),
+        (KeyAction::HalfPageUp, "half_page_up", "<C-u>"),
+        (KeyAction::Confirm, "confirm", "<Enter>"),
+        (KeyAction::Search, "search", "/"),
+        (KeyAction::Add, "add", "a"),
+        (KeyAction::AddBelow, "add_below", "o"),
+        (KeyAction::Edit, "edit", "e"),
+        (KeyAction::Insert, "insert", "i"),
+        (KeyAction::Delete, "delete", "d"),
+        (KeyAction::Command, "command", ":"),
+        (KeyAction::Help, "help", "?"),
+        (KeyAction::Quit, "quit", "q"),
+        (KeyAction::ToolPicker, "tool_picker", "<Space>"),
+        (KeyAction::Telescope, "telescope", "f"),
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(a, _, _)| a == self)
+            .map(|(_, name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(a, _, _)| *a)
+    }
+
+    /// Whether the action is read from the key pressed after the leader.
+    pub fn follows_leader(&self) -> bool {
+        matches!(self, KeyAction::ToolPicker | KeyAction::Telescope)
+    }
+}
+
+/// A single key with modifiers, written vim-style in the config:
+/// `j`, `G`, `<Space>`, `<Enter>`, `<C-d>`, `<A-x>`, `<F2>`, `<S-Tab>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Self {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE,
+            });
+        }
+
+        let mut inner = s.strip_prefix('<')?.strip_suffix('>')?;
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = inner.to_ascii_lowercase();
+            let modifier = if lower.starts_with("c-") {
+                KeyModifiers::CONTROL
+            } else if lower.starts_with("a-") || lower.starts_with("m-") {
+                KeyModifiers::ALT
+            } else if lower.starts_with("s-") {
+                KeyModifiers::SHIFT
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            inner = &inner[2..];
+        }
+
+        let mut chars = inner.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
             }
-            KeyCode::Char('q') => Action::Quit,
-            KeyCode::Char(c) => Action::LeaderSequence(c),
-            KeyCode::Esc => Action::None,
-            _ => Action::None,
+            (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+            _ => match inner.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "cr" | "return" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "bs" | "backspace" => KeyCode::Backspace,
+                "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                other => KeyCode::F(other.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self {
+            code,
+            modifiers: modifiers - KeyModifiers::SHIFT,
+        })
+    }
+
+    /// Whether `key` is this binding. Shift is ignored (it is already part
+    /// of the character), Ctrl and Alt must match.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        key.code == self.code && (key.modifiers & relevant) == (self.modifiers & relevant)
+    }
+
+    pub fn to_event(self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+}
+
+/// Vim notation, as accepted by `parse`: `j`, `<Space>`, `<C-d>`.
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => return write!(f, "{c}"),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::Backspace => "BS".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "Up".to_string(),
+            KeyCode::Down => "Down".to_string(),
+            KeyCode::Left => "Left".to_string(),
+            KeyCode::Right => "Right".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PageUp".to_string(),
+            KeyCode::PageDown => "PageDown".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            other => format!("{other:?}"),
+        };
+        let ctrl = if self.modifiers.contains(KeyModifiers::CONTROL) {
+            "C-"
+        } else {
+            ""
+        };
+        let alt = if self.modifiers.contains(KeyModifiers::ALT) {
+            "A-"
+        } else {
+            ""
+        };
+        write!(f, "<{ctrl}{alt}{name}>")
+    }
+}
+
+/// Maps actions to keys. Built from defaults, overridden by the `[keys]`
+/// table of the user config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyAction, KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = KeyAction::ALL
+            .iter()
+            .filter_map(|(action, _, key)| Some((*action, KeyBinding::parse(key)?)))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Parse a config file. Unknown actions and invalid keys are reported
+    /// as warnings and keep their default, and so is a key bound to more
+    /// than one action; invalid TOML is an error.
+    pub fn from_toml(text: &str) -> anyhow::Result<(Self, Vec<String>)> {
+        let table: toml::Table = text.parse()?;
+        let mut keymap = Self::default();
+        let mut warnings = Vec::new();
+
+        let Some(keys) = table.get("keys") else {
+            return Ok((keymap, warnings));
+        };
+        let Some(keys) = keys.as_table() else {
+            anyhow::bail!("[keys] must be a table");
         };
+        for (name, value) in keys {
+            let Some(action) = KeyAction::from_name(name) else {
+                warnings.push(format!("Unknown action `{name}`"));
+                continue;
+            };
+            match value.as_str().and_then(KeyBinding::parse) {
+                Some(binding) => {
+                    keymap.bindings.insert(action, binding);
+                }
+                None => warnings.push(format!("Invalid key {value} for `{name}`")),
+            }
+        }
+
+        // Keys after the leader only clash with each other
+        let mut seen: Vec<(KeyAction, KeyBinding)> = Vec::new();
+        for (action, name, _) in KeyAction::ALL {
+            let Some(binding) = keymap.binding(action) else {
+                continue;
+            };
+            if let Some((other, _)) = seen
+                .iter()
+                .find(|(a, b)| a.follows_leader() == action.follows_leader() && *b == binding)
+            {
+                warnings.push(format!(
+                    "Key {binding} is bound to both `{}` and `{name}`",
+                    other.as_str()
+                ));
+            }
+            seen.push((action, binding));
+        }
+        Ok((keymap, warnings))
+    }
+
+    pub fn binding(&self, action: KeyAction) -> Option<KeyBinding> {
+        self.bindings.get(&action).copied()
+    }
+
+    /// The key bound to `action` in vim notation, for hints and help.
+    pub fn label(&self, action: KeyAction) -> String {
+        self.binding(action)
+            .map(|b| b.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether `key` triggers `action`.
+    pub fn matches(&self, action: KeyAction, key: &KeyEvent) -> bool {
+        self.binding(action).is_some_and(|b| b.matches(key))
+    }
+
+    /// The Normal-mode action bound to `key` (leader follow-ups excluded).
+    pub fn action_for(&self, key: &KeyEvent) -> Option<KeyAction> {
+        KeyAction::ALL
+            .iter()
+            .map(|(action, _, _)| *action)
+            .find(|action| !action.follows_leader() && self.matches(*action, key))
+    }
+}
+
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+thread_local! {
+    static THREAD_KEYMAP: RefCell<Option<Keymap>> = const { RefCell::new(None) };
+}
+
+/// Install the keymap used by `process_normal_key` and the tools. Only the
+/// first call has an effect; call it once at startup.
+pub fn install_keymap(keymap: Keymap) {
+    let _ = KEYMAP.set(keymap);
+}
+
+/// Override the keymap for the current thread only, so tests can remap
+/// keys without affecting tests running on other threads.
+pub fn install_thread_keymap(keymap: Keymap) {
+    THREAD_KEYMAP.set(Some(keymap));
+}
+
+/// The active keymap (defaults until `install_keymap` is called). Only a
+/// thread override is cloned; the global keymap is borrowed.
+pub fn keymap() -> Cow<'static, Keymap> {
+    match THREAD_KEYMAP.with_borrow(Clone::clone) {
+        Some(keymap) => Cow::Owned(keymap),
+        None => Cow::Borrowed(KEYMAP.get_or_init(Keymap::default)),
     }
+}
+
+/// Process a key event in Normal mode, accounting for multi-key sequences.
+/// Returns an Action and whether the key_state was consumed/reset.
+pub fn process_normal_key(key: KeyEvent, state: &mut KeyState) -> Action {
+    let keymap = keymap();
 
     // If there's a pending key, handle two-key sequences
     if let Some(pending) = state.pending_key.take() {
         return match (pending, key.code) {
-            ('g', KeyCode::Char('g')) => Action::GotoTop,
+            ('g', _) if keymap.matches(KeyAction::GotoPrefix, &key) => Action::GotoTop,
             ('g', KeyCode::Char('t')) => Action::NextTool,
             ('g', KeyCode::Char('T')) => Action::PrevTool,
-            ('d', KeyCode::Char('d')) => Action::Delete,
+            ('d', _) if keymap.matches(KeyAction::Delete, &key) => Action::Delete,
             _ => Action::None, // Invalid sequence, ignore
         };
     }
 
     // Single key processing
-    match key.code {
-        KeyCode::Char(' ') => {
-            state.leader_active = true;
-            Action::LeaderKey
-        }
-        KeyCode::Char('j') => Action::MoveDown(1),
-        KeyCode::Char('k') => Action::MoveUp(1),
-        KeyCode::Char('G') => Action::GotoBottom,
-        KeyCode::Char('g') => {
+    let Some(action) = keymap.action_for(&key) else {
+        return Action::None;
+    };
+    match action {
+        KeyAction::Leader => Action::LeaderKey,
+        KeyAction::MoveDown => Action::MoveDown(1),
+        KeyAction::MoveUp => Action::MoveUp(1),
+        KeyAction::GotoBottom => Action::GotoBottom,
+        KeyAction::GotoPrefix => {
             state.pending_key = Some('g');
             Action::None
         }
-        KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => Action::HalfPageDown,
-        KeyCode::Char('u') if key.modifiers == KeyModifiers::CONTROL => Action::HalfPageUp,
-        KeyCode::Char('d') => {
+        KeyAction::HalfPageDown => Action::HalfPageDown,
+        KeyAction::HalfPageUp => Action::HalfPageUp,
+        KeyAction::Delete => {
             state.pending_key = Some('d');
             Action::None
         }
-        KeyCode::Enter => Action::Confirm,
-        KeyCode::Char('/') => Action::Search,
-        KeyCode::Char('a') => Action::Add,
-        KeyCode::Char('o') => Action::AddBelow,
-        KeyCode::Char('e') => Action::Edit,
-        KeyCode::Char('i') => Action::SetMode(InputMode::Insert),
-        KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-        KeyCode::Char('?') => Action::Help,
-        KeyCode::Char('q') => Action::Quit,
-        _ => Action::None,
+        KeyAction::Confirm => Action::Confirm,
+        KeyAction::Search => Action::Search,
+        KeyAction::Add => Action::Add,
+        KeyAction::AddBelow => Action::AddBelow,
+        KeyAction::Edit => Action::Edit,
+        KeyAction::Insert => Action::SetMode(InputMode::Insert),
+        KeyAction::Command => Action::SetMode(InputMode::Command),
+        KeyAction::Help => Action::Help,
+        KeyAction::Quit => Action::Quit,
+        KeyAction::ToolPicker | KeyAction::Telescope => Action::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_key_binding() {
+        let parse = |s| KeyBinding::parse(s).map(|b| (b.code, b.modifiers));
+        assert_eq!(parse("j"), Some((KeyCode::Char('j'), KeyModifiers::NONE)));
+        assert_eq!(
+            parse("<C-d>"),
+            Some((KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse("<space>"),
+            Some((KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse("<S-Tab>"),
+            Some((KeyCode::BackTab, KeyModifiers::NONE))
+        );
+        assert_eq!(parse("<F5>"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(parse("jk"), None);
+        assert_eq!(parse("<Nope>"), None);
+
+        for text in [
+            ",", "G", "<Space>", "<C-d>", "<A-x>", "<S-Tab>", "<F2>", "<Enter>",
+        ] {
+            assert_eq!(KeyBinding::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_keymap_from_toml() {
+        let (keymap, warnings) = Keymap::from_toml(
+            r#"
+            [keys]
+            leader = ","
+            move_down = "n"
+            half_page_down = "<C-f>"
+            jump = "x"
+            help = "<Bogus>"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(keymap.label(KeyAction::Leader), ",");
+
+        let n = key(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(keymap.action_for(&n), Some(KeyAction::MoveDown));
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('f'), KeyModifiers::CONTROL)),
+            Some(KeyAction::HalfPageDown)
+        );
+        // Missing and invalid mappings keep their default
+        assert!(keymap.matches(
+            KeyAction::Help,
+            &key(KeyCode::Char('?'), KeyModifiers::SHIFT)
+        ));
+        assert!(keymap.matches(
+            KeyAction::ToolPicker,
+            &key(KeyCode::Char(' '), KeyModifiers::NONE)
+        ));
+
+        // A key used twice is reported; leader follow-ups are separate
+        let (_, warnings) =
+            Keymap::from_toml("[keys]\nmove_down = \"d\"\ntelescope = \"j\"\n").unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Key d is bound to both `move_down` and `delete`".to_string()]
+        );
+        let (_, warnings) = Keymap::from_toml("[keys]\ntelescope = \"<Space>\"\n").unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Key <Space> is bound to both `tool_picker` and `telescope`".to_string()]
+        );
+
+        assert!(Keymap::from_toml("not toml [").is_err());
+        assert_eq!(Keymap::from_toml("").unwrap().0, Keymap::default());
+    }
+
+    #[test]
+    fn test_default_sequences() {
+        let mut state = KeyState::default();
+        let press = |state: &mut KeyState, c: char| {
+            process_normal_key(key(KeyCode::Char(c), KeyModifiers::NONE), state)
+        };
+        assert_eq!(press(&mut state, 'g'), Action::None);
+        assert_eq!(state.pending_keys(), "g");
+        assert_eq!(press(&mut state, 'g'), Action::GotoTop);
+        assert_eq!(state.pending_keys(), "");
+        assert_eq!(press(&mut state, 'd'), Action::None);
+        assert_eq!(press(&mut state, 'd'), Action::Delete);
+        // The hub reads the key after the leader through which-key
+        assert_eq!(press(&mut state, ' '), Action::LeaderKey);
+        assert_eq!(state.pending_keys(), "");
+        assert_eq!(
+            process_normal_key(key(KeyCode::Char('d'), KeyModifiers::CONTROL), &mut state),
+            Action::HalfPageDown
+        );
     }
 }
@@ -1,8 +1,12 @@
+pub mod confirm;
 pub mod crypto;
 pub mod db;
 pub mod help_popup;
 pub mod keybinds;
+pub mod paths;
 pub mod telescope;
+pub mod theme;
+pub mod toast;
 pub mod tool;
 pub mod tree_sidebar;
 pub mod ui;
@@ -0,0 +1,22 @@
+/// Expand a leading `~` to the home directory.
+pub fn expand_tilde(path: &str) -> String {
+    if (path == "~" || path.starts_with("~/"))
+        && let Ok(home) = std::env::var("HOME")
+    {
+        return format!("{}{}", home, &path[1..]);
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tilde() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(expand_tilde("~/out.json"), format!("{home}/out.json"));
+        assert_eq!(expand_tilde("~"), home);
+        assert_eq!(expand_tilde("/tmp/~x"), "/tmp/~x");
+    }
+}
@@ -3,9 +3,11 @@ use ratatui::{
     layout::{Constraint, Flex, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+use crate::theme::theme;
+
 /// A single item that can appear in the telescope results.
 #[derive(Debug, Clone)]
 pub struct TelescopeItem {
@@ -34,6 +36,10 @@ pub struct Telescope {
     pub list_state: ListState,
     /// Title for the telescope window.
     pub title: String,
+    /// Whether a preview pane is shown right of the results.
+    pub preview_enabled: bool,
+    /// Preview of the selected item, filled in by the owner of the items.
+    pub preview: Option<String>,
 }
 
 impl Default for Telescope {
@@ -46,6 +52,8 @@ impl Default for Telescope {
             filtered: Vec::new(),
             list_state: ListState::default(),
             title: String::from("Find"),
+            preview_enabled: false,
+            preview: None,
         }
     }
 }
@@ -62,12 +70,21 @@ impl Telescope {
         self.query.clear();
         self.cursor = 0;
         self.items = items;
+        self.preview_enabled = false;
+        self.preview = None;
         self.filter();
         if !self.filtered.is_empty() {
             self.list_state.select(Some(0));
         }
     }
 
+    /// Open the telescope with a preview pane; the caller keeps `preview`
+    /// up to date with the selected item.
+    pub fn open_with_preview(&mut self, title: impl Into<String>, items: Vec<TelescopeItem>) {
+        self.open(title, items);
+        self.preview_enabled = true;
+    }
+
     /// Close and reset the telescope.
     pub fn close(&mut self) {
         self.visible = false;
@@ -76,6 +93,8 @@ impl Telescope {
         self.items.clear();
         self.filtered.clear();
         self.list_state.select(None);
+        self.preview_enabled = false;
+        self.preview = None;
     }
 
     /// Insert a character at the cursor position.
@@ -134,46 +153,42 @@ impl Telescope {
         Some(&self.items[idx].id)
     }
 
-    /// Simple case-insensitive substring matching.
-    /// Can be upgraded to proper fuzzy matching later (e.g., with nucleo).
+    /// Fuzzy-match the items against the query, best matches first.
+    /// An empty query keeps the input order.
     fn filter(&mut self) {
-        let query_lower = self.query.to_lowercase();
-        self.filtered = self
-            .items
-            .iter()
-            .enumerate()
-            .filter(|(_, item)| {
-                if query_lower.is_empty() {
-                    return true;
-                }
-                item.label.to_lowercase().contains(&query_lower)
-                    || item.description.to_lowercase().contains(&query_lower)
-            })
-            .map(|(i, _)| i)
-            .collect();
+        let query = fold_query(&self.query);
+        if query.is_empty() {
+            self.filtered = (0..self.items.len()).collect();
+        } else {
+            let mut scored: Vec<(usize, i64)> = self
+                .items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    let score = score_match(&query, &item.label, None).or_else(|| {
+                        score_match(&query, &item.description, None)
+                            .map(|s| s - DESCRIPTION_PENALTY)
+                    })?;
+                    Some((i, score))
+                })
+                .collect();
+            // Stable, so equal scores keep the input order
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+            self.filtered = scored.into_iter().map(|(i, _)| i).collect();
+        }
 
-        // Keep selection in bounds
+        // The ranking changed: start again from the best match
         if self.filtered.is_empty() {
             self.list_state.select(None);
         } else {
-            match self.list_state.selected() {
-                None => self.list_state.select(Some(0)),
-                Some(sel) if sel >= self.filtered.len() => {
-                    self.list_state.select(Some(self.filtered.len() - 1));
-                }
-                _ => {}
-            }
+            self.list_state.select(Some(0));
         }
     }
 
-    /// Render the telescope overlay.
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        if !self.visible {
-            return;
-        }
-
-        // Size: 60% width, 60% height, centered
-        let popup_width = (area.width * 60 / 100)
+    /// The overlay rect within `area`: 60% of it, or 80% wide with a preview.
+    pub fn popup_area(&self, area: Rect) -> Rect {
+        let width_percent = if self.preview_enabled { 80 } else { 60 };
+        let popup_width = (area.width * width_percent / 100)
             .max(40)
             .min(area.width.saturating_sub(4));
         let popup_height = (area.height * 60 / 100)
@@ -184,12 +199,38 @@ impl Telescope {
         let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
         let [popup_area] = vertical.areas(area);
         let [popup_area] = horizontal.areas(popup_area);
+        popup_area
+    }
 
-        frame.render_widget(Clear, popup_area);
-
-        // Split into search input (2 lines) + results
+    /// Split the overlay into search input, results and (optional) preview.
+    fn areas(&self, area: Rect) -> (Rect, Rect, Option<Rect>) {
+        let popup_area = self.popup_area(area);
+        let (main_area, preview_area) = if self.preview_enabled {
+            let [main, preview] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(popup_area);
+            (main, Some(preview))
+        } else {
+            (popup_area, None)
+        };
         let [input_area, results_area] =
-            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(popup_area);
+            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(main_area);
+        (input_area, results_area, preview_area)
+    }
+
+    /// The results list rect (including its side and bottom borders).
+    pub fn results_area(&self, area: Rect) -> Rect {
+        self.areas(area).1
+    }
+
+    /// Render the telescope overlay.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        frame.render_widget(Clear, self.popup_area(area));
+        let (input_area, results_area, preview_area) = self.areas(area);
 
         // Search input
         let input_block = Block::default()
@@ -210,23 +251,28 @@ impl Telescope {
         ));
 
         // Results list
+        let query = fold_query(&self.query);
+        let mut indices = Vec::new();
         let items: Vec<ListItem> = self
             .filtered
             .iter()
             .map(|&idx| {
                 let item = &self.items[idx];
-                let line = if item.description.is_empty() {
-                    Line::from(Span::raw(&item.label))
+                indices.clear();
+                score_match(&query, &item.label, Some(&mut indices));
+                let label_style = if item.description.is_empty() {
+                    Style::default()
                 } else {
-                    Line::from(vec![
-                        Span::styled(&item.label, Style::default().add_modifier(Modifier::BOLD)),
-                        Span::styled(
-                            format!("  {}", item.description),
-                            Style::default().add_modifier(Modifier::DIM),
-                        ),
-                    ])
+                    Style::default().add_modifier(Modifier::BOLD)
                 };
-                ListItem::new(line)
+                let mut spans = highlight_spans(&item.label, &indices, label_style);
+                if !item.description.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  {}", item.description),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -239,5 +285,232 @@ impl Telescope {
             .highlight_symbol("> ");
 
         frame.render_stateful_widget(results, results_area, &mut self.list_state);
+
+        if let Some(preview_area) = preview_area {
+            let preview = Paragraph::new(self.preview.as_deref().unwrap_or("No preview"))
+                .style(if self.preview.is_some() {
+                    Style::default()
+                } else {
+                    Style::default().add_modifier(Modifier::DIM)
+                })
+                .wrap(Wrap { trim: false })
+                .block(Block::default().title(" Preview ").borders(Borders::ALL));
+            frame.render_widget(preview, preview_area);
+        }
+    }
+}
+
+/// Lines of a long text kept for a preview pane.
+pub const PREVIEW_MAX_LINES: usize = 40;
+
+/// The first `PREVIEW_MAX_LINES` lines of `text`.
+pub fn preview_lines(text: &str) -> String {
+    text.lines()
+        .take(PREVIEW_MAX_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A fuzzy match of a query against a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i64,
+    /// Char indices (not byte offsets) of the matched characters in the text.
+    pub positions: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 12;
+const WORD_START_BONUS: i64 = 10;
+const GAP_PENALTY: i64 = 1;
+const MAX_LEADING_PENALTY: i64 = 8;
+/// Matches only found in the description rank below label matches.
+const DESCRIPTION_PENALTY: i64 = 50;
+
+/// Match `query` as a case-insensitive subsequence of `text`.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let mut indices = Vec::new();
+    let score = score_match(&fold_query(query), text, Some(&mut indices))?;
+    Some(FuzzyMatch {
+        score,
+        positions: indices,
+    })
+}
+
+fn fold_query(query: &str) -> Vec<char> {
+    query.chars().map(fold).collect()
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Whether `c` starts a word: after a separator or a lower-to-upper change.
+fn is_boundary(prev: Option<char>, c: char) -> bool {
+    match prev {
+        None => true,
+        Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+    }
+}
+
+/// Score `query` (already folded) against `text`, optionally collecting the
+/// matched char indices. Finds the leftmost match, then tightens its start
+/// by matching backwards from its end, so `"ab"` in `"a_xab"` picks the
+/// compact `ab`. Allocates nothing besides the pushed indices.
+fn score_match(query: &[char], text: &str, mut indices: Option<&mut Vec<usize>>) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    // Forward: where the leftmost match ends
+    let mut qi = 0;
+    let mut end = 0;
+    for (i, c) in text.chars().enumerate() {
+        if fold(c) == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = i;
+                break;
+            }
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    // Backward from the end: the latest start that still matches
+    let mut qi = query.len();
+    let mut start = end;
+    let total = text.chars().count();
+    for (k, c) in text.chars().rev().skip(total - end - 1).enumerate() {
+        if fold(c) == query[qi - 1] {
+            qi -= 1;
+            if qi == 0 {
+                start = end - k;
+                break;
+            }
+        }
+    }
+
+    // Score the match within start..=end
+    let mut score = -(start as i64).min(MAX_LEADING_PENALTY);
+    let mut qi = 0;
+    let mut prev = None;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in text.chars().enumerate().take(end + 1) {
+        if i >= start && qi < query.len() && fold(c) == query[qi] {
+            score += MATCH_SCORE;
+            if is_boundary(prev, c) {
+                score += WORD_START_BONUS;
+            }
+            match last_match {
+                Some(last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+                Some(last) => score -= (i - last - 1) as i64 * GAP_PENALTY,
+                None => {}
+            }
+            if let Some(indices) = indices.as_deref_mut() {
+                indices.push(i);
+            }
+            last_match = Some(i);
+            qi += 1;
+        }
+        prev = Some(c);
+    }
+    Some(score)
+}
+
+/// Split `text` into spans, highlighting the chars at `indices` (sorted).
+fn highlight_spans<'a>(text: &'a str, indices: &[usize], style: Style) -> Vec<Span<'a>> {
+    if indices.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+    let matched = style.fg(theme().accent);
+    let mut spans = Vec::new();
+    let mut run_start = 0;
+    let mut run_matched = false;
+    let mut next = indices.iter().peekable();
+    for (i, (byte, _)) in text.char_indices().enumerate() {
+        let is_match = next.next_if(|&&m| m == i).is_some();
+        if is_match != run_matched {
+            if byte > run_start {
+                let style = if run_matched { matched } else { style };
+                spans.push(Span::styled(&text[run_start..byte], style));
+            }
+            run_start = byte;
+            run_matched = is_match;
+        }
+    }
+    let style = if run_matched { matched } else { style };
+    spans.push(Span::styled(&text[run_start..], style));
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str) -> TelescopeItem {
+        TelescopeItem {
+            label: label.to_string(),
+            description: String::new(),
+            id: label.to_string(),
+        }
+    }
+
+    fn labels(t: &Telescope) -> Vec<&str> {
+        t.filtered
+            .iter()
+            .map(|&i| t.items[i].label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_match_indices() {
+        let m = fuzzy_match("gst", "git status").unwrap();
+        assert_eq!(m.positions, vec![0, 4, 5]);
+        // Tightened to the compact occurrence
+        assert_eq!(fuzzy_match("ab", "a_xab").unwrap().positions, vec![3, 4]);
+        assert_eq!(
+            fuzzy_match("HTTP", "http").unwrap().positions,
+            vec![0, 1, 2, 3]
+        );
+        assert!(fuzzy_match("xyz", "http").is_none());
+        assert!(fuzzy_match("tt", "t").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_ranking() {
+        let score = |q, t| fuzzy_match(q, t).unwrap().score;
+        // Consecutive beats scattered
+        assert!(score("note", "notes") > score("note", "n_o_t_e"));
+        // Word starts beat mid-word matches
+        assert!(score("kp", "KeePass") > score("kp", "backpack"));
+        assert!(score("db", "my_db") > score("db", "mydb"));
+        assert!(score("work", "work.kdbx") > score("work", "w-o-r-k.kdbx"));
+        assert!(score("GIT", "GitHub") > score("git", "digit"));
+    }
+
+    #[test]
+    fn test_filter_sorts_and_keeps_order_for_empty_query() {
+        let mut t = Telescope::new();
+        t.open(
+            "Find",
+            vec![item("Merge"), item("Todo"), item("Notes"), item("KeePass")],
+        );
+        assert_eq!(labels(&t), vec!["Merge", "Todo", "Notes", "KeePass"]);
+
+        t.insert_char('o');
+        assert_eq!(labels(&t), vec!["Todo", "Notes"]);
+        t.insert_char('e');
+        assert_eq!(labels(&t), vec!["Notes"]);
+        assert_eq!(t.selected_id(), Some("Notes"));
+    }
+
+    #[test]
+    fn test_highlight_spans() {
+        let spans = highlight_spans("git status", &[0, 4, 5], Style::default());
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["g", "it ", "st", "atus"]);
     }
 }
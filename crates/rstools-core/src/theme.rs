@@ -0,0 +1,192 @@
+use std::sync::OnceLock;
+
+use ratatui::style::Color;
+
+/// Colors shared by the hub and every tool's render functions.
+///
+/// `dark` is the original look; `light` suits terminals with a light
+/// background. The hub picks one from the `[theme]` table of
+/// `config.toml` at startup; render code reads it through `theme()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Titles, prompts, search matches and other highlights.
+    pub accent: Color,
+    /// Border of the focused panel.
+    pub border: Color,
+    /// Unfocused borders, hints and secondary text.
+    pub muted: Color,
+    /// Background of the selected row.
+    pub selection_bg: Color,
+    /// Regular foreground text.
+    pub text: Color,
+    /// Informational labels (keys, field names, counts).
+    pub info: Color,
+    /// Success and "ok" states.
+    pub success: Color,
+    /// Errors, warnings and destructive prompts.
+    pub error: Color,
+}
+
+impl Theme {
+    /// Names accepted by `Theme::by_name`.
+    pub const NAMES: [&'static str; 2] = ["dark", "light"];
+
+    pub const fn dark() -> Self {
+        Self {
+            accent: Color::Yellow,
+            border: Color::Blue,
+            muted: Color::DarkGray,
+            selection_bg: Color::Gray,
+            text: Color::White,
+            info: Color::Cyan,
+            success: Color::Green,
+            error: Color::Red,
+        }
+    }
+
+    pub const fn light() -> Self {
+        Self {
+            accent: Color::Rgb(0xa0, 0x5a, 0x00),
+            border: Color::Blue,
+            muted: Color::DarkGray,
+            selection_bg: Color::Rgb(0xd0, 0xd7, 0xe5),
+            text: Color::Black,
+            info: Color::Rgb(0x00, 0x6b, 0x8f),
+            success: Color::Rgb(0x1a, 0x7f, 0x37),
+            error: Color::Rgb(0xc0, 0x1c, 0x28),
+        }
+    }
+
+    /// A built-in theme by name.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    /// Parse the `[theme]` table of a config file: `name` picks a built-in
+    /// theme (default `dark`) and any color key overrides one of its colors
+    /// (`accent = "magenta"`, `selection_bg = "#303050"`). Unknown names,
+    /// keys and colors are reported as warnings and ignored.
+    pub fn from_toml(text: &str) -> anyhow::Result<(Self, Vec<String>)> {
+        let mut warnings = Vec::new();
+        let value: toml::Table = text.parse()?;
+        let Some(table) = value.get("theme") else {
+            return Ok((Self::default(), warnings));
+        };
+        let Some(table) = table.as_table() else {
+            anyhow::bail!("[theme] must be a table");
+        };
+
+        let mut theme = Self::default();
+        if let Some(name) = table.get("name") {
+            match name.as_str().and_then(Self::by_name) {
+                Some(named) => theme = named,
+                None => warnings.push(format!(
+                    "Unknown theme {name} (available: {})",
+                    Self::NAMES.join(", ")
+                )),
+            }
+        }
+        for (key, value) in table.iter().filter(|(key, _)| *key != "name") {
+            let Some(slot) = theme.color_mut(key) else {
+                warnings.push(format!("Unknown theme color `{key}`"));
+                continue;
+            };
+            match value.as_str().and_then(|s| s.parse::<Color>().ok()) {
+                Some(color) => *slot = color,
+                None => warnings.push(format!("Invalid color {value} for `{key}`")),
+            }
+        }
+        Ok((theme, warnings))
+    }
+
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        Some(match key {
+            "accent" => &mut self.accent,
+            "border" => &mut self.border,
+            "muted" => &mut self.muted,
+            "selection_bg" => &mut self.selection_bg,
+            "text" => &mut self.text,
+            "info" => &mut self.info,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Install the theme used by every render function. Only the first call
+/// has an effect; call it once at startup.
+pub fn install_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The active theme (`Theme::dark` until `install_theme` is called).
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Black or white, whichever reads better on `bg`. Used for text drawn on a
+/// theme color (toasts, search matches, the status bar warning).
+pub fn contrast_fg(bg: Color) -> Color {
+    let light = match bg {
+        Color::Rgb(r, g, b) => 299 * r as u32 + 587 * g as u32 + 114 * b as u32 > 128_000,
+        Color::Green
+        | Color::Yellow
+        | Color::Cyan
+        | Color::Gray
+        | Color::White
+        | Color::LightRed
+        | Color::LightGreen
+        | Color::LightYellow
+        | Color::LightBlue
+        | Color::LightMagenta
+        | Color::LightCyan => true,
+        _ => false,
+    };
+    if light { Color::Black } else { Color::White }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let (theme, warnings) = Theme::from_toml("[keys]\nup = \"k\"").unwrap();
+        assert_eq!(theme, Theme::dark());
+        assert!(warnings.is_empty());
+
+        let text = "[theme]\nname = \"light\"\naccent = \"magenta\"\nmuted = \"#808080\"";
+        let (theme, warnings) = Theme::from_toml(text).unwrap();
+        assert_eq!(theme.accent, Color::Magenta);
+        assert_eq!(theme.muted, Color::Rgb(0x80, 0x80, 0x80));
+        assert_eq!(theme.text, Theme::light().text);
+        assert!(warnings.is_empty());
+
+        let text = "[theme]\nname = \"neon\"\nglow = \"red\"\nerror = \"nope\"";
+        let (theme, warnings) = Theme::from_toml(text).unwrap();
+        assert_eq!(theme, Theme::dark());
+        assert_eq!(warnings.len(), 3);
+        assert!(Theme::from_toml("theme = 1").is_err());
+    }
+
+    #[test]
+    fn test_contrast_fg() {
+        assert_eq!(contrast_fg(Color::Yellow), Color::Black);
+        assert_eq!(contrast_fg(Color::Red), Color::White);
+        assert_eq!(contrast_fg(Theme::light().error), Color::White);
+        assert_eq!(contrast_fg(Theme::light().selection_bg), Color::Black);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::theme::{contrast_fg, theme};
+
+/// How long a toast stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Toasts shown at once; older ones are dropped when more arrive.
+const MAX_TOASTS: usize = 5;
+
+/// How a toast is colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Error,
+}
+
+impl Severity {
+    fn style(self) -> Style {
+        let bg = match self {
+            Severity::Info => theme().info,
+            Severity::Success => theme().success,
+            Severity::Error => theme().error,
+        };
+        Style::default()
+            .fg(contrast_fg(bg))
+            .bg(bg)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// A transient message ("Saved", "Copied password", "Request failed").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub message: String,
+    pub severity: Severity,
+    pub created_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>, severity: Severity) -> Self {
+        Self {
+            message: message.into(),
+            severity,
+            created_at: Instant::now(),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(message, Severity::Info)
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(message, Severity::Success)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(message, Severity::Error)
+    }
+}
+
+/// The toasts on screen, oldest first. The hub owns one, fills it from
+/// `Tool::take_toasts` and expires it on every tick.
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a toast, dropping the oldest one if the stack is full.
+    pub fn push(&mut self, toast: Toast) {
+        self.toasts.push_back(toast);
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Drop toasts shown for `TOAST_DURATION` or longer.
+    pub fn expire(&mut self, now: Instant) {
+        self.toasts
+            .retain(|t| now.saturating_duration_since(t.created_at) < TOAST_DURATION);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+
+    /// Render the toasts stacked in the top-right corner of `area`, newest
+    /// at the top.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.toasts.iter().rev().zip(area.y + 1..area.bottom());
+        for (toast, y) in rows {
+            let text = format!(" {} ", toast.message);
+            let width = (text.width() as u16).min(area.width.saturating_sub(2));
+            let toast_area = Rect {
+                x: area.right().saturating_sub(width + 1),
+                y,
+                width,
+                height: 1,
+            };
+            frame.render_widget(Clear, toast_area);
+            let line = Line::from(Span::styled(text, toast.severity.style()));
+            frame.render_widget(Paragraph::new(line), toast_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+
+    #[test]
+    fn test_stack_and_expire() {
+        let mut queue = ToastQueue::new();
+        for i in 0..7 {
+            queue.push(Toast::info(format!("toast {i}")));
+        }
+        let messages: Vec<_> = queue.iter().map(|t| t.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["toast 2", "toast 3", "toast 4", "toast 5", "toast 6"]
+        );
+
+        let now = Instant::now();
+        queue.expire(now);
+        assert_eq!(queue.iter().count(), 5);
+        queue.expire(now + TOAST_DURATION);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_render_newest_first() {
+        let mut queue = ToastQueue::new();
+        queue.push(Toast::success("Saved"));
+        queue.push(Toast::error("Failed"));
+        let mut terminal = Terminal::new(TestBackend::new(12, 4)).unwrap();
+        terminal
+            .draw(|frame| queue.render(frame, frame.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..12).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(1), "    Failed  ");
+        assert_eq!(row(2), "     Saved  ");
+        assert_eq!(buffer[(4, 1)].bg, Color::Red);
+        assert_eq!(buffer[(5, 2)].bg, Color::Green);
+    }
+
+    #[test]
+    fn test_render_wide_characters() {
+        let mut queue = ToastQueue::new();
+        queue.push(Toast::error("日本"));
+        let mut terminal = Terminal::new(TestBackend::new(12, 3)).unwrap();
+        terminal
+            .draw(|frame| queue.render(frame, frame.area()))
+            .unwrap();
+
+        // " 日本 " is six cells wide, ending one cell from the edge
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(6, 1)].symbol(), "日");
+        assert_eq!(buffer[(5, 1)].bg, Color::Red);
+        assert_ne!(buffer[(4, 1)].bg, Color::Red);
+        assert_ne!(buffer[(11, 1)].bg, Color::Red);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::help_popup::HelpEntry;
 use crate::keybinds::{Action, InputMode};
 use crate::telescope::TelescopeItem;
+use crate::toast::Toast;
 use crate::which_key::WhichKeyEntry;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{Frame, layout::Rect};
@@ -18,9 +19,27 @@ pub trait Tool {
     /// The tool's current input mode (for status bar display).
     fn mode(&self) -> InputMode;
 
+    /// The mode name shown in the status bar. Tools with a vim editor
+    /// override it to name the editor's Replace mode.
+    fn mode_label(&self) -> &'static str {
+        self.mode().label()
+    }
+
     /// Initialize the tool's database tables if they don't exist.
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()>;
 
+    /// A warning shown in red in the status bar while the tool is active
+    /// (e.g. a security setting that is relaxed for the session).
+    fn status_warning(&self) -> Option<String> {
+        None
+    }
+
+    /// Keys of an unfinished multi-key command (`g`, `"a`, `2d`...), shown
+    /// in the status bar so the user can see what the tool is waiting for.
+    fn pending_keys(&self) -> String {
+        String::new()
+    }
+
     /// Which-key entries for this tool's leader group.
     /// These appear when the user presses `<Space><tool_key>`.
     fn which_key_entries(&self) -> Vec<WhichKeyEntry>;
@@ -28,6 +47,12 @@ pub trait Tool {
     /// Items this tool contributes to telescope search.
     fn telescope_items(&self) -> Vec<TelescopeItem>;
 
+    /// Preview text for one of this tool's telescope items, shown beside
+    /// the results while it is highlighted. `None` if the ID is not ours.
+    fn telescope_preview(&self, _id: &str) -> Option<String> {
+        None
+    }
+
     /// Handle selecting an item from global telescope search.
     /// Return true if this tool handled the ID and navigated to it.
     fn handle_telescope_selection(&mut self, _id: &str) -> bool {
@@ -67,6 +92,12 @@ pub trait Tool {
     /// Use this for async polling, spinner animations, etc.
     fn tick(&mut self) {}
 
+    /// Toasts raised since the last call. The hub drains every tool after
+    /// each tick and shows them stacked until they expire.
+    fn take_toasts(&mut self) -> Vec<Toast> {
+        Vec::new()
+    }
+
     /// Handle a command-mode command (e.g., ":w"). Returns true if handled.
     fn handle_command(&mut self, _cmd: &str) -> bool {
         false
@@ -78,9 +109,26 @@ pub trait Tool {
         Action::None
     }
 
+    /// Called on Ctrl-c before it quits the app. Return true when the tool
+    /// used it to stop something (e.g. a running request); the app then
+    /// keeps running.
+    fn interrupt(&mut self) -> bool {
+        false
+    }
+
     /// Called when the tool becomes the active view.
     fn on_focus(&mut self) {}
 
     /// Called when the tool loses focus.
     fn on_blur(&mut self) {}
+
+    /// Whether the tool holds edits that closing it or quitting would lose.
+    /// The hub then asks to save (through the `w` command), discard or cancel.
+    fn is_dirty(&self) -> bool {
+        false
+    }
+
+    /// Drop unsaved edits, going back to what was last saved. Called when the
+    /// user chooses to quit without saving.
+    fn discard_changes(&mut self) {}
 }
@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use ratatui::{
     Frame,
     layout::Rect,
@@ -7,6 +9,9 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
+use crate::confirm::ConfirmPrompt;
+use crate::theme::theme;
+
 // ── TreeEntry trait ──────────────────────────────────────────────────
 
 /// Trait that any entry type must implement to be used with TreeSidebar.
@@ -16,6 +21,11 @@ pub trait TreeEntry: Clone {
     fn name(&self) -> &str;
     fn is_folder(&self) -> bool;
     fn is_expanded(&self) -> bool;
+    /// Manual sort position among siblings of the same kind. Entries with
+    /// equal positions (e.g. all 0) sort alphabetically.
+    fn position(&self) -> i64 {
+        0
+    }
 }
 
 // ── TreeNode ─────────────────────────────────────────────────────────
@@ -28,6 +38,22 @@ pub struct TreeNode<T: TreeEntry> {
     pub expanded: bool,
 }
 
+impl<T: TreeEntry> TreeNode<T> {
+    /// Number of non-folder entries below this node, at any depth.
+    pub fn leaf_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| {
+                if child.entry.is_folder() {
+                    child.leaf_count()
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+}
+
 // ── FlatEntry ────────────────────────────────────────────────────────
 
 /// A flattened entry for rendering — one visible line in the sidebar.
@@ -95,6 +121,8 @@ pub struct TreeSidebar<T: TreeEntry> {
     pub input_cursor: usize,
     /// Whether the sidebar is visible.
     pub visible: bool,
+    /// Rows shown at the last render; sizes `Ctrl-d` / `Ctrl-u`.
+    pub visible_lines: Cell<usize>,
 }
 
 impl<T: TreeEntry> TreeSidebar<T> {
@@ -108,6 +136,7 @@ impl<T: TreeEntry> TreeSidebar<T> {
             input_buffer: String::new(),
             input_cursor: 0,
             visible: true,
+            visible_lines: Cell::new(20),
         }
     }
 
@@ -183,14 +212,14 @@ impl<T: TreeEntry> TreeSidebar<T> {
     }
 
     /// Half-page down.
-    pub fn half_page_down(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_down(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = (self.selected + half).min(self.max_selectable());
     }
 
     /// Half-page up.
-    pub fn half_page_up(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_up(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = self.selected.saturating_sub(half);
     }
 
@@ -280,6 +309,23 @@ impl<T: TreeEntry> TreeSidebar<T> {
         }
     }
 
+    /// The question asked before deleting the selected entry, without the
+    /// `(y/n)` hint. Folders mention how many non-folder entries would be
+    /// deleted with them.
+    pub fn delete_prompt(&self) -> String {
+        let Some(entry) = self.selected_entry() else {
+            return "Delete ??".to_string();
+        };
+        let count = find_node(&self.roots, entry.entry_id)
+            .map(|node| node.leaf_count())
+            .unwrap_or(0);
+        match count {
+            0 => format!("Delete '{}'?", entry.name),
+            1 => format!("Delete '{}' and 1 item?", entry.name),
+            n => format!("Delete '{}' and {} items?", entry.name, n),
+        }
+    }
+
     /// Cancel any active input.
     pub fn cancel_input(&mut self) {
         self.input_mode = SidebarInput::None;
@@ -383,6 +429,27 @@ impl<T: TreeEntry> TreeSidebar<T> {
         changed
     }
 
+    /// IDs of the siblings of an entry that are of the same kind (folders or
+    /// leaves), in display order, including the entry itself.
+    pub fn sibling_ids(&self, entry_id: i64) -> Vec<i64> {
+        let Some(target) = find_node(&self.roots, entry_id) else {
+            return Vec::new();
+        };
+        let is_folder = target.entry.is_folder();
+        let siblings = match find_parent_id(&self.roots, entry_id) {
+            Some(parent_id) => match find_node(&self.roots, parent_id) {
+                Some(parent) => &parent.children,
+                None => return Vec::new(),
+            },
+            None => &self.roots,
+        };
+        siblings
+            .iter()
+            .filter(|n| n.entry.is_folder() == is_folder)
+            .map(|n| n.entry.id())
+            .collect()
+    }
+
     /// Select the entry with the given ID, if it's visible.
     pub fn select_entry(&mut self, entry_id: i64) {
         if let Some(pos) = self.flat_view.iter().position(|e| e.entry_id == entry_id) {
@@ -409,7 +476,8 @@ fn build_tree<T: TreeEntry>(entries: &[T], parent_id: Option<i64>) -> Vec<TreeNo
         .collect()
 }
 
-/// Sort tree nodes: folders first, then leaves, both alphabetically. Recursive.
+/// Sort tree nodes: folders first, then leaves, each by position and then
+/// alphabetically. Recursive.
 fn sort_tree<T: TreeEntry>(nodes: &mut Vec<TreeNode<T>>) {
     nodes.sort_by(|a, b| {
         let type_ord = match (a.entry.is_folder(), b.entry.is_folder()) {
@@ -417,12 +485,14 @@ fn sort_tree<T: TreeEntry>(nodes: &mut Vec<TreeNode<T>>) {
             (false, true) => std::cmp::Ordering::Greater,
             _ => std::cmp::Ordering::Equal,
         };
-        type_ord.then_with(|| {
-            a.entry
-                .name()
-                .to_lowercase()
-                .cmp(&b.entry.name().to_lowercase())
-        })
+        type_ord
+            .then_with(|| a.entry.position().cmp(&b.entry.position()))
+            .then_with(|| {
+                a.entry
+                    .name()
+                    .to_lowercase()
+                    .cmp(&b.entry.name().to_lowercase())
+            })
     });
     for node in nodes.iter_mut() {
         sort_tree(&mut node.children);
@@ -525,9 +595,6 @@ fn collect_ancestors<T: TreeEntry>(nodes: &[TreeNode<T>], target_id: i64) -> Vec
 
 // ── Rendering ────────────────────────────────────────────────────────
 
-const GUIDE_STYLE: Style = Style::new().fg(Color::DarkGray);
-const SELECTED_BG: Color = Color::Gray;
-
 /// Configuration for rendering a tree sidebar.
 pub struct TreeSidebarRenderConfig<'a> {
     /// The title to display in the sidebar border.
@@ -541,6 +608,17 @@ pub struct TreeSidebarRenderConfig<'a> {
     pub leaf_style: Option<Style>,
     /// Style for folder entries when not selected. Default: Blue.
     pub folder_style: Option<Style>,
+    /// Per-entry decoration of leaf entries, looked up by entry id.
+    pub leaf_decoration: Option<&'a dyn Fn(i64) -> LeafDecoration>,
+}
+
+/// Extra styling of a single leaf entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeafDecoration {
+    /// Color of the leaf icon (None = same style as the name).
+    pub icon_color: Option<Color>,
+    /// Muted text shown after the name.
+    pub suffix: Option<String>,
 }
 
 /// Render the tree sidebar into the given area.
@@ -551,9 +629,9 @@ pub fn render_tree_sidebar<T: TreeEntry>(
     config: &TreeSidebarRenderConfig<'_>,
 ) {
     let border_color = if config.focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -600,15 +678,16 @@ fn render_tree_entries<T: TreeEntry>(
     sidebar: &TreeSidebar<T>,
     config: &TreeSidebarRenderConfig<'_>,
 ) {
+    sidebar.visible_lines.set(area.height as usize);
     if sidebar.flat_view.is_empty() {
         if sidebar.selected == 0 {
-            let highlight = Style::default().bg(SELECTED_BG);
+            let highlight = Style::default().bg(theme().selection_bg);
             let blank = Line::from(Span::styled(" ".repeat(area.width as usize), highlight));
             let widget = Paragraph::new(vec![blank]);
             frame.render_widget(widget, area);
         } else {
             let empty = Paragraph::new("  No entries yet. Press 'a' to add.")
-                .style(Style::default().fg(Color::DarkGray));
+                .style(Style::default().fg(theme().muted));
             frame.render_widget(empty, area);
         }
         return;
@@ -634,7 +713,7 @@ fn render_tree_entries<T: TreeEntry>(
         } else {
             let is_selected = item_idx == sidebar.selected;
             if is_selected {
-                let highlight = Style::default().bg(SELECTED_BG);
+                let highlight = Style::default().bg(theme().selection_bg);
                 lines.push(Line::from(Span::styled(
                     " ".repeat(area.width as usize),
                     highlight,
@@ -665,19 +744,19 @@ fn render_entry_line<T: TreeEntry>(
 
     let folder_style = config
         .folder_style
-        .unwrap_or(Style::default().fg(Color::Blue));
+        .unwrap_or(Style::default().fg(theme().border));
     let leaf_style = config
         .leaf_style
-        .unwrap_or(Style::default().fg(Color::White));
+        .unwrap_or(Style::default().fg(theme().text));
 
     let base_style = if is_selected {
         Style::default()
-            .bg(SELECTED_BG)
+            .bg(theme().selection_bg)
             .fg(Color::Black)
             .add_modifier(Modifier::BOLD)
     } else if is_cut {
         Style::default()
-            .fg(Color::DarkGray)
+            .fg(theme().muted)
             .add_modifier(Modifier::DIM)
     } else if entry.is_folder {
         folder_style
@@ -691,9 +770,9 @@ fn render_entry_line<T: TreeEntry>(
         let has_guide = entry.guide_depths.get(d).copied().unwrap_or(false);
         if has_guide {
             let guide_style = if is_selected {
-                GUIDE_STYLE.bg(SELECTED_BG)
+                Style::new().fg(theme().muted).bg(theme().selection_bg)
             } else {
-                GUIDE_STYLE
+                Style::new().fg(theme().muted)
             };
             spans.push(Span::styled("\u{2502} ", guide_style));
         } else {
@@ -710,8 +789,24 @@ fn render_entry_line<T: TreeEntry>(
     } else {
         config.leaf_icon.unwrap_or("\u{25CF} ")
     };
-    spans.push(Span::styled(icon.to_string(), base_style));
+    let decoration = match config.leaf_decoration {
+        Some(decorate) if !entry.is_folder => decorate(entry.entry_id),
+        _ => LeafDecoration::default(),
+    };
+    let icon_style = match decoration.icon_color {
+        Some(color) if !is_cut => base_style.fg(color),
+        _ => base_style,
+    };
+    spans.push(Span::styled(icon.to_string(), icon_style));
     spans.push(Span::styled(entry.name.clone(), base_style));
+    if let Some(suffix) = decoration.suffix {
+        let suffix_style = if is_selected {
+            base_style.remove_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().muted)
+        };
+        spans.push(Span::styled(format!(" {suffix}"), suffix_style));
+    }
 
     if is_selected {
         let content_width: usize = spans.iter().map(|s| s.content.width()).sum();
@@ -719,7 +814,7 @@ fn render_entry_line<T: TreeEntry>(
         if remaining > 0 {
             spans.push(Span::styled(
                 " ".repeat(remaining),
-                Style::default().bg(SELECTED_BG),
+                Style::default().bg(theme().selection_bg),
             ));
         }
     }
@@ -732,17 +827,7 @@ fn render_input_prompt<T: TreeEntry>(frame: &mut Frame, area: Rect, sidebar: &Tr
         SidebarInput::Adding => ("New: ", &sidebar.input_buffer),
         SidebarInput::Renaming => ("Name: ", &sidebar.input_buffer),
         SidebarInput::ConfirmDelete => {
-            let name = sidebar
-                .selected_entry()
-                .map(|e| e.name.as_str())
-                .unwrap_or("?");
-            let prompt = format!("Delete {}? (y/n)", name);
-            let line = Line::from(vec![Span::styled(
-                prompt,
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            )]);
-            let widget = Paragraph::new(line);
-            frame.render_widget(widget, area);
+            ConfirmPrompt::new(sidebar.delete_prompt()).render(frame, area);
             return;
         }
         SidebarInput::None => return,
@@ -752,7 +837,7 @@ fn render_input_prompt<T: TreeEntry>(frame: &mut Frame, area: Rect, sidebar: &Tr
         Span::styled(
             label,
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(input_text.clone()),
@@ -782,6 +867,7 @@ mod tests {
         name: String,
         folder: bool,
         expanded: bool,
+        position: i64,
     }
 
     impl TreeEntry for TestEntry {
@@ -800,6 +886,9 @@ mod tests {
         fn is_expanded(&self) -> bool {
             self.expanded
         }
+        fn position(&self) -> i64 {
+            self.position
+        }
     }
 
     fn entry(
@@ -815,6 +904,7 @@ mod tests {
             name: name.to_string(),
             folder,
             expanded,
+            position: 0,
         }
     }
 
@@ -899,6 +989,76 @@ mod tests {
         assert!(sidebar.selected_entry().is_some());
     }
 
+    #[test]
+    fn test_half_page_uses_rendered_height() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
+        let entries: Vec<_> = (1..=30)
+            .map(|id| entry(id, None, &format!("e{id}"), false, false))
+            .collect();
+        sidebar.reload_from_entries(&entries);
+
+        // 10 rows inside the border
+        let mut terminal = Terminal::new(TestBackend::new(20, 12)).unwrap();
+        let config = TreeSidebarRenderConfig {
+            title: "Test",
+            focused: true,
+            leaf_icon: None,
+            leaf_style: None,
+            folder_style: None,
+            leaf_decoration: None,
+        };
+        terminal
+            .draw(|frame| render_tree_sidebar(frame, frame.area(), &sidebar, &config))
+            .unwrap();
+        assert_eq!(sidebar.visible_lines.get(), 10);
+
+        sidebar.half_page_down();
+        assert_eq!(sidebar.selected, 5);
+        sidebar.half_page_down();
+        assert_eq!(sidebar.selected, 10);
+        sidebar.half_page_up();
+        assert_eq!(sidebar.selected, 5);
+    }
+
+    #[test]
+    fn test_leaf_decoration() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
+        sidebar.reload_from_entries(&[
+            entry(1, None, "dir", true, false),
+            entry(2, None, "ok", false, false),
+        ]);
+        sidebar.selected = 0;
+
+        let decorate = |id: i64| LeafDecoration {
+            icon_color: Some(Color::Green),
+            suffix: Some(format!("{id}ms")),
+        };
+        let config = TreeSidebarRenderConfig {
+            title: "Test",
+            focused: true,
+            leaf_icon: None,
+            leaf_style: None,
+            folder_style: None,
+            leaf_decoration: Some(&decorate),
+        };
+        let mut terminal = Terminal::new(TestBackend::new(20, 4)).unwrap();
+        terminal
+            .draw(|frame| render_tree_sidebar(frame, frame.area(), &sidebar, &config))
+            .unwrap();
+
+        // Folders are left alone; the leaf's icon is colored and suffixed
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String { (1..19).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert_eq!(row(1).trim_end(), "\u{25B6} dir");
+        assert_eq!(row(2).trim_end(), "\u{25CF} ok 2ms");
+        assert_eq!(buffer[(1, 2)].fg, Color::Green);
+        assert_eq!(buffer[(6, 2)].fg, theme().muted);
+    }
+
     #[test]
     fn test_sort_folders_first() {
         let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
@@ -920,6 +1080,32 @@ mod tests {
         assert!(!sidebar.flat_view[3].is_folder);
     }
 
+    #[test]
+    fn test_sort_by_position_and_siblings() {
+        let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
+        let mut entries = vec![
+            entry(1, None, "api", true, true),
+            entry(2, Some(1), "a-query", false, false),
+            entry(3, Some(1), "b-query", false, false),
+            entry(4, Some(1), "c-query", false, false),
+            entry(5, Some(1), "folder", true, false),
+        ];
+        entries[1].position = 2;
+        entries[2].position = 1;
+        sidebar.reload_from_entries(&entries);
+
+        // Folders still come first; equal positions fall back to the name
+        let names: Vec<&str> = sidebar.flat_view.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["api", "folder", "c-query", "b-query", "a-query"]
+        );
+        assert_eq!(sidebar.sibling_ids(3), vec![4, 3, 2]);
+        assert_eq!(sidebar.sibling_ids(5), vec![5]);
+        assert_eq!(sidebar.sibling_ids(1), vec![1]);
+        assert!(sidebar.sibling_ids(99).is_empty());
+    }
+
     #[test]
     fn test_input_buffer_operations() {
         let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
@@ -999,6 +1185,27 @@ mod tests {
         assert!(sidebar.selected_entry().is_some());
     }
 
+    #[test]
+    fn test_delete_prompt_counts_descendants() {
+        let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
+        let entries = vec![
+            entry(1, None, "api", true, true),
+            entry(2, Some(1), "users", true, false),
+            entry(3, Some(2), "get", false, false),
+            entry(4, Some(2), "list", false, false),
+            entry(5, Some(1), "health", false, false),
+            entry(6, None, "empty", true, false),
+        ];
+        sidebar.reload_from_entries(&entries);
+
+        // Only non-folder entries are counted
+        assert_eq!(sidebar.delete_prompt(), "Delete 'api' and 3 items?");
+        sidebar.move_down();
+        assert_eq!(sidebar.delete_prompt(), "Delete 'users' and 2 items?");
+        sidebar.select_entry(6);
+        assert_eq!(sidebar.delete_prompt(), "Delete 'empty'?");
+    }
+
     #[test]
     fn test_clipboard_operations() {
         let mut sidebar: TreeSidebar<TestEntry> = TreeSidebar::new();
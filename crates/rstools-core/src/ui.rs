@@ -1,4 +1,3 @@
-use crate::keybinds::InputMode;
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
@@ -7,6 +6,8 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Tabs},
 };
 
+use crate::theme::{contrast_fg, theme};
+
 /// Render the top tab bar showing open tools.
 /// `tools` is a list of tool names, `active` is the index of the active tool.
 pub fn render_tab_bar(frame: &mut Frame, area: Rect, tools: &[&str], active: usize) {
@@ -21,30 +22,61 @@ pub fn render_tab_bar(frame: &mut Frame, area: Rect, tools: &[&str], active: usi
     frame.render_widget(tabs, area);
 }
 
-/// Render the bottom status bar showing the current mode and optional info.
+/// Render the bottom status bar showing the current mode (`mode`, its
+/// label) and optional info.
+/// A `warning` is shown in red right after the tool name. The right side
+/// shows the keys of an unfinished sequence (`pending`) and the time.
 pub fn render_status_bar(
     frame: &mut Frame,
     area: Rect,
-    mode: InputMode,
+    mode: &str,
     tool_name: &str,
+    warning: Option<&str>,
     info: &str,
+    pending: &str,
 ) {
-    let mode_style = match mode {
-        InputMode::Normal => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
-        InputMode::Insert => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
-        InputMode::Command => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
-    };
+    let mode_style = Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED);
 
-    let line = Line::from(vec![
-        Span::styled(format!(" {} ", mode.label()), mode_style),
+    let mut spans = vec![
+        Span::styled(format!(" {mode} "), mode_style),
         Span::raw(" "),
         Span::styled(tool_name, Style::default().add_modifier(Modifier::BOLD)),
         Span::raw("  "),
-        Span::styled(info, Style::default().add_modifier(Modifier::DIM)),
-    ]);
+    ];
+    if let Some(warning) = warning {
+        spans.push(Span::styled(
+            format!(" {} ", warning),
+            Style::default()
+                .fg(contrast_fg(theme().error))
+                .bg(theme().error)
+                .add_modifier(Modifier::BOLD)
+                .remove_modifier(Modifier::REVERSED),
+        ));
+        spans.push(Span::raw("  "));
+    }
+    spans.push(Span::styled(
+        info,
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+    let line = Line::from(spans);
+
+    let mut right = vec![];
+    if !pending.is_empty() {
+        right.push(Span::styled(
+            pending,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        right.push(Span::raw("  "));
+    }
+    right.push(Span::raw(chrono::Local::now().format("%H:%M ").to_string()));
+    let right = Line::from(right);
 
-    let bar = Paragraph::new(line).style(Style::default().add_modifier(Modifier::REVERSED));
-    frame.render_widget(bar, area);
+    let [left_area, right_area] =
+        Layout::horizontal([Constraint::Min(0), Constraint::Length(right.width() as u16)])
+            .areas(area);
+    let style = Style::default().add_modifier(Modifier::REVERSED);
+    frame.render_widget(Paragraph::new(line).style(style), left_area);
+    frame.render_widget(Paragraph::new(right).style(style), right_area);
 }
 
 /// Render the command-line input at the bottom of the screen.
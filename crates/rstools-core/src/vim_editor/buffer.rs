@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 /// A multi-line text buffer with a 2D cursor.
 ///
 /// Lines are stored as `Vec<String>`, where each string is one line of text
@@ -13,6 +15,10 @@ pub struct TextBuffer {
     pub desired_col: usize,
     /// Whether the buffer has been modified since last save.
     pub dirty: bool,
+    /// Named (row, col) positions (`m<a-z>`, and `` ` `` for the last jump).
+    /// Line inserts and deletes keep them on their line; a deleted line
+    /// takes its marks along.
+    pub marks: HashMap<char, (usize, usize)>,
 }
 
 impl TextBuffer {
@@ -23,6 +29,7 @@ impl TextBuffer {
             cursor_col: 0,
             desired_col: 0,
             dirty: false,
+            marks: HashMap::new(),
         }
     }
 
@@ -45,6 +52,7 @@ impl TextBuffer {
             cursor_col: 0,
             desired_col: 0,
             dirty: false,
+            marks: HashMap::new(),
         }
     }
 
@@ -67,6 +75,7 @@ impl TextBuffer {
         self.cursor_col = 0;
         self.desired_col = 0;
         self.dirty = false;
+        self.marks.clear();
     }
 
     /// Number of lines in the buffer.
@@ -191,6 +200,7 @@ impl TextBuffer {
         let (before, after) = current_line.split_at(self.cursor_col);
         self.lines[self.cursor_row] = before.to_string();
         self.lines.insert(self.cursor_row + 1, after.to_string());
+        self.lines_inserted(self.cursor_row + 1, 1);
         self.cursor_row += 1;
         self.cursor_col = 0;
         self.desired_col = 0;
@@ -213,6 +223,7 @@ impl TextBuffer {
         } else if self.cursor_row > 0 {
             // Merge with previous line
             let current = self.lines.remove(self.cursor_row);
+            self.lines_removed(self.cursor_row, self.cursor_row + 1);
             self.cursor_row -= 1;
             self.cursor_col = self.lines[self.cursor_row].len();
             self.lines[self.cursor_row].push_str(&current);
@@ -230,6 +241,7 @@ impl TextBuffer {
         } else if self.cursor_row < self.lines.len() - 1 {
             // Merge next line into current
             let next = self.lines.remove(self.cursor_row + 1);
+            self.lines_removed(self.cursor_row + 1, self.cursor_row + 2);
             self.lines[self.cursor_row].push_str(&next);
             self.dirty = true;
         }
@@ -240,6 +252,7 @@ impl TextBuffer {
     /// Delete the current line. Returns the deleted line content.
     pub fn delete_line(&mut self) -> String {
         let deleted = self.lines.remove(self.cursor_row);
+        self.lines_removed(self.cursor_row, self.cursor_row + 1);
         if self.lines.is_empty() {
             self.lines.push(String::new());
         }
@@ -256,6 +269,7 @@ impl TextBuffer {
     pub fn delete_lines(&mut self, count: usize) -> String {
         let end = (self.cursor_row + count).min(self.lines.len());
         let deleted: Vec<String> = self.lines.drain(self.cursor_row..end).collect();
+        self.lines_removed(self.cursor_row, end);
         if self.lines.is_empty() {
             self.lines.push(String::new());
         }
@@ -270,6 +284,7 @@ impl TextBuffer {
     /// Insert a line below the current line and move cursor there.
     pub fn open_line_below(&mut self) {
         self.lines.insert(self.cursor_row + 1, String::new());
+        self.lines_inserted(self.cursor_row + 1, 1);
         self.cursor_row += 1;
         self.cursor_col = 0;
         self.desired_col = 0;
@@ -279,6 +294,19 @@ impl TextBuffer {
     /// Insert a line above the current line and move cursor there.
     pub fn open_line_above(&mut self) {
         self.lines.insert(self.cursor_row, String::new());
+        self.lines_inserted(self.cursor_row, 1);
+        self.cursor_col = 0;
+        self.desired_col = 0;
+        self.dirty = true;
+    }
+
+    /// Insert an empty line at `row` (appended past the end) and put the
+    /// cursor on it, as `cc` / `S` leave after deleting the lines.
+    pub fn open_blank_line(&mut self, row: usize) {
+        let row = row.min(self.lines.len());
+        self.lines.insert(row, String::new());
+        self.lines_inserted(row, 1);
+        self.cursor_row = row;
         self.cursor_col = 0;
         self.desired_col = 0;
         self.dirty = true;
@@ -288,6 +316,7 @@ impl TextBuffer {
     pub fn join_lines(&mut self) {
         if self.cursor_row < self.lines.len() - 1 {
             let next = self.lines.remove(self.cursor_row + 1);
+            self.lines_removed(self.cursor_row + 1, self.cursor_row + 2);
             let trimmed = next.trim_start();
             let join_col = self.lines[self.cursor_row].len();
             if !self.lines[self.cursor_row].is_empty() && !trimmed.is_empty() {
@@ -300,6 +329,173 @@ impl TextBuffer {
         }
     }
 
+    /// Reflow rows `start_row..=end_row` so no line is longer than `width`
+    /// characters (vim `gq`). Each paragraph (run of non-blank lines) is
+    /// re-split on whitespace and keeps its first line's indent; blank lines
+    /// are left alone. A word longer than `width` gets a line of its own.
+    /// The cursor moves to the start of the last formatted line.
+    pub fn reflow_lines(&mut self, start_row: usize, end_row: usize, width: usize) {
+        let end_row = end_row.min(self.lines.len() - 1);
+        let mut formatted = Vec::new();
+        let mut paragraph: Vec<&str> = Vec::new();
+        for line in self.lines[start_row..=end_row]
+            .iter()
+            .map(Some)
+            .chain([None])
+        {
+            match line {
+                Some(line) if !line.trim().is_empty() => paragraph.push(line),
+                _ => {
+                    if let Some(first) = paragraph.first() {
+                        let indent = &first[..first.len() - first.trim_start().len()];
+                        let words = paragraph.iter().flat_map(|l| l.split_whitespace());
+                        formatted.extend(fill_words(indent, words, width));
+                        paragraph.clear();
+                    }
+                    formatted.extend(line.cloned());
+                }
+            }
+        }
+
+        let last_row = start_row + formatted.len().saturating_sub(1);
+        self.lines_replaced(start_row, end_row + 1 - start_row, formatted.len());
+        self.lines.splice(start_row..=end_row, formatted);
+        self.cursor_row = last_row;
+        self.cursor_col = self.lines[last_row].len() - self.lines[last_row].trim_start().len();
+        self.desired_col = self.cursor_col;
+        self.dirty = true;
+    }
+
+    /// Indent rows `start_row..=end_row` by one level: `width` spaces, or a
+    /// tab without `expand_tab`. Empty lines are left alone. The cursor
+    /// moves to the first non-blank of the first row.
+    pub fn indent_lines(
+        &mut self,
+        start_row: usize,
+        end_row: usize,
+        width: usize,
+        expand_tab: bool,
+    ) {
+        let level = if expand_tab {
+            " ".repeat(width)
+        } else {
+            "\t".to_string()
+        };
+        let end_row = end_row.min(self.lines.len() - 1);
+        for line in &mut self.lines[start_row..=end_row] {
+            if !line.is_empty() {
+                line.insert_str(0, &level);
+            }
+        }
+        self.finish_shift(start_row);
+    }
+
+    /// Remove up to `width` columns of leading whitespace (a tab counts as a
+    /// whole level) from rows `start_row..=end_row`.
+    pub fn dedent_lines(&mut self, start_row: usize, end_row: usize, width: usize) {
+        let end_row = end_row.min(self.lines.len() - 1);
+        for line in &mut self.lines[start_row..=end_row] {
+            let mut cols = 0;
+            let mut removed = 0;
+            for ch in line.chars() {
+                match ch {
+                    ' ' if cols < width => cols += 1,
+                    '\t' if cols < width => cols = width,
+                    _ => break,
+                }
+                removed += 1;
+            }
+            line.drain(..removed);
+        }
+        self.finish_shift(start_row);
+    }
+
+    /// Sort lines `start_row..=end_row`. The sort is stable; the cursor stays
+    /// on its row (clamped if `unique` removed lines). Returns the number of
+    /// lines left in the range.
+    pub fn sort_lines(&mut self, start_row: usize, end_row: usize, options: SortOptions) -> usize {
+        let end_row = end_row.min(self.lines.len() - 1);
+        let mut sorted: Vec<String> = self.lines[start_row..=end_row].to_vec();
+        if options.numeric {
+            sorted.sort_by_key(|line| first_number(line));
+        } else {
+            sorted.sort();
+        }
+        if options.reverse {
+            sorted.reverse();
+        }
+        if options.unique {
+            sorted.dedup();
+        }
+        let count = sorted.len();
+        self.lines_replaced(start_row, end_row + 1 - start_row, count);
+        self.lines.splice(start_row..=end_row, sorted);
+        self.cursor_row = self.cursor_row.min(self.lines.len() - 1);
+        self.clamp_cursor_col(false);
+        self.cursor_col = snap_to_char_boundary(self.current_line(), self.cursor_col);
+        self.desired_col = self.cursor_col;
+        self.dirty = true;
+        count
+    }
+
+    /// Strip trailing whitespace from every line and end a non-empty buffer
+    /// with exactly one newline (a single empty last line). Returns whether
+    /// anything changed.
+    pub fn tidy_whitespace(&mut self) -> bool {
+        let mut lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|l| l.trim_end().to_string())
+            .collect();
+        while lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        if lines.len() > 1 || !lines[0].is_empty() {
+            lines.push(String::new());
+        }
+        if lines == self.lines {
+            return false;
+        }
+        // Only trailing blank lines go away
+        if lines.len() < self.lines.len() {
+            self.lines_removed(lines.len(), self.lines.len());
+        }
+        self.lines = lines;
+        self.cursor_row = self.cursor_row.min(self.lines.len() - 1);
+        self.clamp_cursor_col(false);
+        self.desired_col = self.cursor_col;
+        self.dirty = true;
+        true
+    }
+
+    /// Replace lines `start_row..=end_row` with the lines of `text`, ignoring
+    /// one trailing newline. The buffer always keeps at least one line.
+    pub fn replace_lines(&mut self, start_row: usize, end_row: usize, text: &str) {
+        let end_row = end_row.min(self.lines.len() - 1);
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let replacement: Vec<String> = if text.is_empty() {
+            Vec::new()
+        } else {
+            text.split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+                .collect()
+        };
+        self.lines_replaced(start_row, end_row + 1 - start_row, replacement.len());
+        self.lines.splice(start_row..=end_row, replacement);
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.finish_shift(start_row.min(self.lines.len() - 1));
+    }
+
+    fn finish_shift(&mut self, row: usize) {
+        let line = &self.lines[row];
+        self.cursor_row = row;
+        self.cursor_col = line.len() - line.trim_start().len();
+        self.desired_col = self.cursor_col;
+        self.dirty = true;
+    }
+
     // ── Range operations ─────────────────────────────────────────────
 
     /// Delete text in a range of (row, col) positions.
@@ -364,6 +560,7 @@ impl TextBuffer {
                 let drain_end = (start_row + 1 + remove_count).min(self.lines.len());
                 if drain_start < drain_end {
                     self.lines.drain(drain_start..drain_end);
+                    self.lines_removed(drain_start, drain_end);
                 }
             }
 
@@ -384,6 +581,7 @@ impl TextBuffer {
         let s = start_row.min(self.lines.len() - 1);
         let e = (end_row + 1).min(self.lines.len());
         let deleted: Vec<String> = self.lines.drain(s..e).collect();
+        self.lines_removed(s, e);
         if self.lines.is_empty() {
             self.lines.push(String::new());
         }
@@ -457,6 +655,7 @@ impl TextBuffer {
         for (i, line) in new_lines.iter().enumerate() {
             self.lines.insert(insert_at + i, line.clone());
         }
+        self.lines_inserted(insert_at, new_lines.len());
         self.cursor_row = insert_at;
         self.cursor_col = 0;
         self.desired_col = 0;
@@ -472,6 +671,7 @@ impl TextBuffer {
         for (i, line) in new_lines.iter().enumerate() {
             self.lines.insert(self.cursor_row + i, line.clone());
         }
+        self.lines_inserted(self.cursor_row, new_lines.len());
         // Cursor stays at first inserted line
         self.cursor_col = 0;
         self.desired_col = 0;
@@ -494,6 +694,78 @@ impl TextBuffer {
         }
     }
 
+    /// Overwrite the character under the cursor with `c`, or append it at
+    /// the end of the line, and move past it (Replace mode). Returns the
+    /// character that was overwritten.
+    pub fn overwrite_char(&mut self, c: char) -> Option<char> {
+        let line = &mut self.lines[self.cursor_row];
+        let original = line[self.cursor_col..].chars().next();
+        if let Some(original) = original {
+            line.drain(self.cursor_col..self.cursor_col + original.len_utf8());
+        }
+        line.insert(self.cursor_col, c);
+        self.cursor_col += c.len_utf8();
+        self.desired_col = self.cursor_col;
+        self.dirty = true;
+        original
+    }
+
+    /// Undo an `overwrite_char`: step back over the previous character and
+    /// put `original` back, or remove the character if it was appended.
+    pub fn restore_char(&mut self, original: Option<char>) {
+        if self.cursor_col == 0 {
+            return;
+        }
+        self.cursor_left();
+        let line = &mut self.lines[self.cursor_row];
+        if let Some(typed) = line[self.cursor_col..].chars().next() {
+            line.drain(self.cursor_col..self.cursor_col + typed.len_utf8());
+        }
+        if let Some(original) = original {
+            line.insert(self.cursor_col, original);
+        }
+        self.dirty = true;
+    }
+
+    /// Replace the literal `pattern` with `replacement` on rows
+    /// `start_row..=end_row`: the first occurrence per line, or all of them
+    /// when `global`. Returns the number of replacements. The cursor moves
+    /// to the start of the last changed line.
+    pub fn substitute(
+        &mut self,
+        start_row: usize,
+        end_row: usize,
+        pattern: &str,
+        replacement: &str,
+        global: bool,
+    ) -> usize {
+        if pattern.is_empty() || self.lines.is_empty() {
+            return 0;
+        }
+        let end_row = end_row.min(self.lines.len() - 1);
+        let mut count = 0;
+        for row in start_row..=end_row {
+            let line = &self.lines[row];
+            let hits = if global {
+                line.matches(pattern).count()
+            } else {
+                usize::from(line.contains(pattern))
+            };
+            if hits == 0 {
+                continue;
+            }
+            self.lines[row] = line.replacen(pattern, replacement, hits);
+            count += hits;
+            self.cursor_row = row;
+            self.cursor_col = 0;
+            self.desired_col = 0;
+        }
+        if count > 0 {
+            self.dirty = true;
+        }
+        count
+    }
+
     /// Create a snapshot of the buffer state for undo.
     pub fn snapshot(&self) -> BufferSnapshot {
         BufferSnapshot {
@@ -504,13 +776,83 @@ impl TextBuffer {
         }
     }
 
-    /// Restore from a snapshot.
+    /// Restore from a snapshot. Marks below the rows that differ move with
+    /// them; marks on rows that went away are dropped.
     pub fn restore(&mut self, snapshot: &BufferSnapshot) {
-        self.lines = snapshot.lines.clone();
+        let before = std::mem::replace(&mut self.lines, snapshot.lines.clone());
+        if !self.marks.is_empty() {
+            self.shift_marks(&before);
+        }
         self.cursor_row = snapshot.cursor_row;
         self.cursor_col = snapshot.cursor_col;
         self.desired_col = snapshot.desired_col;
     }
+
+    // ── Marks ────────────────────────────────────────────────────────
+
+    /// `count` lines were inserted at `row`: marks from there on move down.
+    fn lines_inserted(&mut self, row: usize, count: usize) {
+        for (mark_row, _) in self.marks.values_mut() {
+            if *mark_row >= row {
+                *mark_row += count;
+            }
+        }
+    }
+
+    /// Rows `start..end` were removed: their marks are dropped and the
+    /// ones below move up.
+    fn lines_removed(&mut self, start: usize, end: usize) {
+        self.marks.retain(|_, (row, _)| {
+            if *row >= end {
+                *row -= end - start;
+                true
+            } else {
+                *row < start
+            }
+        });
+    }
+
+    /// `old` rows from `start` were replaced by `new` rows.
+    fn lines_replaced(&mut self, start: usize, old: usize, new: usize) {
+        if new < old {
+            self.lines_removed(start + new, start + old);
+        } else {
+            self.lines_inserted(start + old, new - old);
+        }
+    }
+
+    /// Move marks after the `before` lines were swapped for the current
+    /// ones wholesale (undo / redo): only the rows between the unchanged
+    /// head and tail count as replaced.
+    fn shift_marks(&mut self, before: &[String]) {
+        let after = &self.lines;
+        if before.len() == after.len() {
+            return;
+        }
+        let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+        let suffix = before
+            .iter()
+            .rev()
+            .zip(after.iter().rev())
+            .take(before.len().min(after.len()) - prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let old = before.len() - suffix - prefix;
+        let new = after.len() - suffix - prefix;
+        self.lines_replaced(prefix, old, new);
+    }
+}
+
+/// How `TextBuffer::sort_lines` orders lines (`:sort`, `:sort!`, `:sort u`,
+/// `:sort n`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Descending instead of ascending.
+    pub reverse: bool,
+    /// Drop lines equal to the one before them after sorting.
+    pub unique: bool,
+    /// Compare the first integer on each line; lines without one come first.
+    pub numeric: bool,
 }
 
 /// A snapshot of the buffer state for undo/redo.
@@ -537,6 +879,43 @@ fn snap_to_char_boundary(s: &str, target: usize) -> usize {
         .unwrap_or(0)
 }
 
+/// The first integer on `line`, with an optional leading `-`.
+fn first_number(line: &str) -> Option<i64> {
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let len = line[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(line.len() - start);
+    let negative = line[..start].ends_with('-');
+    let value: i64 = line[start..start + len].parse().unwrap_or(i64::MAX);
+    Some(if negative { -value } else { value })
+}
+
+/// Greedily fill `words` into lines of at most `width` characters, each
+/// starting with `indent`.
+fn fill_words<'a>(indent: &str, words: impl Iterator<Item = &'a str>, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut len = 0;
+    for word in words {
+        let word_len = word.chars().count();
+        if len > 0 && len + 1 + word_len > width {
+            lines.push(std::mem::take(&mut line));
+            len = 0;
+        }
+        if len == 0 {
+            line.push_str(indent);
+            len = indent.chars().count();
+        } else {
+            line.push(' ');
+            len += 1;
+        }
+        line.push_str(word);
+        len += word_len;
+    }
+    lines.push(line);
+    lines
+}
+
 // ── Word boundary helpers ────────────────────────────────────────────
 
 /// Classify a character for word movement.
@@ -941,6 +1320,109 @@ mod tests {
         assert_eq!(buf.text(), "Hello");
     }
 
+    #[test]
+    fn test_sort_lines() {
+        let mut buf = TextBuffer::from_text("top\npear\napple\npear\nFig");
+        buf.cursor_row = 4;
+        let count = buf.sort_lines(1, 4, SortOptions::default());
+        assert_eq!(count, 4);
+        assert_eq!(buf.text(), "top\nFig\napple\npear\npear");
+
+        let unique = SortOptions {
+            reverse: true,
+            unique: true,
+            ..SortOptions::default()
+        };
+        assert_eq!(buf.sort_lines(1, 4, unique), 3);
+        assert_eq!(buf.text(), "top\npear\napple\nFig");
+        assert_eq!(buf.cursor_row, 3);
+
+        let mut buf = TextBuffer::from_text("- 10 b\nnone\n2 a\nitem -3");
+        let numeric = SortOptions {
+            numeric: true,
+            ..SortOptions::default()
+        };
+        buf.sort_lines(0, 3, numeric);
+        assert_eq!(buf.text(), "none\nitem -3\n2 a\n- 10 b");
+    }
+
+    #[test]
+    fn test_tidy_whitespace() {
+        let mut buf = TextBuffer::from_text("a  \n\tb\t\n\n\n");
+        buf.cursor_row = 3;
+        assert!(buf.tidy_whitespace());
+        assert_eq!(buf.text(), "a\n\tb\n");
+        assert_eq!(buf.cursor_row, 2);
+        assert!(!buf.tidy_whitespace());
+
+        let mut buf = TextBuffer::from_text("  ");
+        assert!(buf.tidy_whitespace());
+        assert_eq!(buf.text(), "");
+    }
+
+    #[test]
+    fn test_replace_lines() {
+        let mut buf = TextBuffer::from_text("a\n  b\nc");
+        buf.replace_lines(1, 1, "  x\n  y\n");
+        assert_eq!(buf.text(), "a\n  x\n  y\nc");
+        assert_eq!((buf.cursor_row, buf.cursor_col), (1, 2));
+        buf.replace_lines(0, 3, "");
+        assert_eq!(buf.lines, vec![String::new()]);
+    }
+
+    #[test]
+    fn test_indent_and_dedent_lines() {
+        let mut buf = TextBuffer::from_text("a\n\n  b");
+        buf.indent_lines(0, 2, 4, true);
+        assert_eq!(buf.text(), "    a\n\n      b");
+        assert_eq!((buf.cursor_row, buf.cursor_col), (0, 4));
+        buf.indent_lines(0, 0, 4, false);
+        assert_eq!(buf.lines[0], "\t    a");
+
+        // At most one level, never past column zero
+        buf.dedent_lines(0, 2, 4);
+        assert_eq!(buf.text(), "    a\n\n  b");
+        buf.dedent_lines(0, 2, 4);
+        buf.dedent_lines(0, 2, 4);
+        assert_eq!(buf.text(), "a\n\nb");
+    }
+
+    #[test]
+    fn test_overwrite_and_restore_char() {
+        let mut buf = TextBuffer::from_text("ab");
+        assert_eq!(buf.overwrite_char('é'), Some('a'));
+        assert_eq!(buf.overwrite_char('x'), Some('b'));
+        assert_eq!(buf.overwrite_char('y'), None);
+        assert_eq!(buf.text(), "éxy");
+        buf.restore_char(None);
+        buf.restore_char(Some('b'));
+        buf.restore_char(Some('a'));
+        assert_eq!(buf.text(), "ab");
+        assert_eq!(buf.cursor_col, 0);
+    }
+
+    #[test]
+    fn test_marks_follow_line_changes() {
+        let mut buf = TextBuffer::from_text("a\nb\nc\nd");
+        buf.marks.insert('a', (1, 0));
+        buf.marks.insert('b', (3, 0));
+        let row = |buf: &TextBuffer, name| buf.marks.get(&name).map(|&(row, _)| row);
+
+        buf.insert_lines_above("x\ny");
+        assert_eq!((row(&buf, 'a'), row(&buf, 'b')), (Some(3), Some(5)));
+        buf.replace_lines(0, 1, "z");
+        assert_eq!((row(&buf, 'a'), row(&buf, 'b')), (Some(2), Some(4)));
+
+        // A deleted line takes its mark along
+        let before = buf.snapshot();
+        buf.delete_line_range(1, 2);
+        assert_eq!((row(&buf, 'a'), row(&buf, 'b')), (None, Some(2)));
+
+        // Undo moves the remaining marks back down
+        buf.restore(&before);
+        assert_eq!(row(&buf, 'b'), Some(4));
+    }
+
     #[test]
     fn test_join_lines() {
         let mut buf = TextBuffer::from_text("hello\n  world");
@@ -949,6 +1431,34 @@ mod tests {
         assert_eq!(buf.text(), "hello world");
     }
 
+    #[test]
+    fn test_reflow_lines() {
+        let mut buf =
+            TextBuffer::from_text("  one two three\nfour five\n\nsix seven eight nine\nx");
+        buf.reflow_lines(0, 3, 10);
+        assert_eq!(
+            buf.text(),
+            "  one two\n  three\n  four\n  five\n\nsix seven\neight nine\nx"
+        );
+        assert_eq!((buf.cursor_row, buf.cursor_col), (6, 0));
+
+        // Short lines are joined; a word wider than the width stays whole
+        let mut buf = TextBuffer::from_text("a\nb\nabcdefghijkl c");
+        buf.reflow_lines(0, 2, 5);
+        assert_eq!(buf.text(), "a b\nabcdefghijkl\nc");
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut buf = TextBuffer::from_text("a-a\nb\na-a");
+        assert_eq!(buf.substitute(0, 0, "a", "x", false), 1);
+        assert_eq!(buf.text(), "x-a\nb\na-a");
+        assert_eq!(buf.substitute(0, 2, "a", "yy", true), 3);
+        assert_eq!(buf.text(), "x-yy\nb\nyy-yy");
+        assert_eq!(buf.cursor_row, 2);
+        assert_eq!(buf.substitute(0, 2, "zzz", "q", true), 0);
+    }
+
     #[test]
     fn test_open_line_below() {
         let mut buf = TextBuffer::from_text("hello\nworld");
@@ -1,11 +1,13 @@
 pub mod buffer;
 pub mod history;
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
 use buffer::{
-    CharClass, TextBuffer, char_class, find_char_backward, find_char_forward, find_till_backward,
-    find_till_forward, find_word_backward, find_word_end, find_word_forward,
+    CharClass, SortOptions, TextBuffer, char_class, find_char_backward, find_char_forward,
+    find_till_backward, find_till_forward, find_word_backward, find_word_end, find_word_forward,
 };
 use history::History;
 
@@ -13,10 +15,13 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
     layout::Rect,
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::Paragraph,
 };
+use unicode_width::UnicodeWidthChar;
+
+use crate::theme::{contrast_fg, theme};
 
 // ── Vim modes ────────────────────────────────────────────────────────
 
@@ -26,6 +31,10 @@ pub enum VimMode {
     Insert,
     Visual,
     VisualLine,
+    /// Rectangular selection (`Ctrl-v`).
+    VisualBlock,
+    /// Overtyping (`R`): typed characters replace the ones under the cursor.
+    Replace,
 }
 
 // ── Register (clipboard) ─────────────────────────────────────────────
@@ -45,6 +54,42 @@ impl Register {
     }
 }
 
+impl Default for Register {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of numbered yank registers (`"0`–`"9`).
+const YANK_RING_SIZE: usize = 10;
+
+/// The unnamed register, named registers `"a`–`"z` and a numbered yank
+/// ring where `"0` is the last yank and `"9` the oldest.
+#[derive(Debug, Clone, Default)]
+struct Registers {
+    unnamed: Register,
+    named: HashMap<char, Register>,
+    yanks: VecDeque<Register>,
+}
+
+impl Registers {
+    /// Read a register: the unnamed one for `None`, a named or numbered
+    /// one otherwise.
+    fn get(&self, name: Option<char>) -> Option<&Register> {
+        match name {
+            None => Some(&self.unnamed),
+            Some(c @ 'a'..='z') => self.named.get(&c),
+            Some(c @ '0'..='9') => self.yanks.get(c as usize - '0' as usize),
+            Some(_) => None,
+        }
+    }
+
+    fn push_yank(&mut self, register: Register) {
+        self.yanks.push_front(register);
+        self.yanks.truncate(YANK_RING_SIZE);
+    }
+}
+
 // ── Editor action result ─────────────────────────────────────────────
 
 /// Actions that the editor can request from its parent.
@@ -65,6 +110,29 @@ enum Operator {
     Delete,
     Change,
     Yank,
+    /// `gq`: reflow the lines to `text_width`.
+    Format,
+    /// `>` / `<`: shift the lines by `shift_width`.
+    Indent,
+    Dedent,
+}
+
+impl Operator {
+    fn keys(self) -> &'static str {
+        match self {
+            Operator::Delete => "d",
+            Operator::Change => "c",
+            Operator::Yank => "y",
+            Operator::Format => "gq",
+            Operator::Indent => ">",
+            Operator::Dedent => "<",
+        }
+    }
+
+    /// `gq`, `>` and `<` rewrite whole lines in place, whatever the motion.
+    fn is_linewise_edit(self) -> bool {
+        matches!(self, Operator::Format | Operator::Indent | Operator::Dedent)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -126,27 +194,126 @@ enum ParseState {
     },
     /// Waiting for char after r.
     PendingReplace { count: usize },
+    /// Waiting for the register name after `"`.
+    PendingRegister,
+    /// Waiting for the register to record into after `q`.
+    PendingMacroRecord,
+    /// Waiting for the register to play after `@`.
+    PendingMacroPlay { count: usize },
+    /// Waiting for the mark name after `m`.
+    PendingMark,
+    /// Waiting for the mark to jump to after `` ` `` (`exact`) or `'`.
+    PendingMarkJump { exact: bool },
     /// Waiting for text object target after 'i' or 'a'.
     PendingTextObject {
         op: Option<Operator>,
         count: usize,
         inner: bool,
     },
+    /// Typing a `/` or `?` search pattern. The cursor follows the first
+    /// match from `origin` and returns there if the search is cancelled.
+    Search {
+        forward: bool,
+        query: String,
+        origin: (usize, usize),
+    },
+}
+
+/// A pending visual-block insert (`I` / `A`).
+#[derive(Debug, Clone, Copy)]
+struct BlockInsert {
+    /// Rows below the top row that receive the text.
+    first_row: usize,
+    last_row: usize,
+    /// Character column the text is inserted at.
+    col: usize,
+    /// `A`: pad short lines with spaces instead of skipping them.
+    pad: bool,
+    /// Byte column and length of the top line when insertion started.
+    start_col: usize,
+    start_len: usize,
 }
 
+/// Undo steps kept per buffer.
+const UNDO_LEVELS: usize = 200;
+
+/// Two clicks on the same spot within this time make a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
 // ── VimEditor ────────────────────────────────────────────────────────
 
 pub struct VimEditor {
     pub buffer: TextBuffer,
     pub mode: VimMode,
     history: History,
-    register: Register,
+    registers: Registers,
+    /// Register chosen with a `"x` prefix for the next yank, delete or paste.
+    selected_register: Option<char>,
     parse_state: ParseState,
     /// Anchor position for visual mode.
     visual_anchor_row: usize,
     visual_anchor_col: usize,
     /// Visible height (updated each render for half-page calculations).
     visible_height: Cell<usize>,
+    /// Text area of the last render, to map mouse positions back to the
+    /// buffer and to size display lines for `gj` / `gk`.
+    last_view: Cell<Rect>,
+    /// Buffer row and first character column shown on each screen row of
+    /// the last render.
+    view_rows: RefCell<Vec<(usize, usize)>>,
+    /// Buffer position where the left mouse button went down.
+    drag_anchor: Option<(usize, usize)>,
+    /// Time and position of the last click, for double-click detection.
+    last_click: Option<(Instant, (usize, usize))>,
+    /// Last confirmed search pattern (repeated with n/N, highlighted).
+    last_search: Option<String>,
+    /// Direction of the last search (`/` forward, `?` backward).
+    last_search_forward: bool,
+    /// Keys of the command in progress, from Normal/Idle back to it
+    /// (including any Insert or Visual session in between).
+    change_keys: Vec<KeyEvent>,
+    /// Whether the command in progress modified the buffer.
+    change_made: bool,
+    /// Keys of the last buffer-modifying command, replayed by `.`.
+    last_change: Vec<KeyEvent>,
+    /// Set while `.` replays `last_change` (nothing is recorded).
+    replaying: bool,
+    /// Recorded macros (`q<reg>` … `q`), played back with `@<reg>`.
+    macros: HashMap<char, Vec<KeyEvent>>,
+    /// Block `I` / `A` in progress: the typed text is copied to the other
+    /// selected lines when Insert mode ends.
+    block_insert: Option<BlockInsert>,
+    /// Characters overwritten in the current Replace session, oldest first
+    /// (`None` for ones typed past the line end); Backspace restores them.
+    replaced: Vec<Option<char>>,
+    /// Copies of the Insert session a counted `o` / `O` makes on `Esc`.
+    insert_repeat: usize,
+    /// Keys typed in that Insert session, replayed for each copy.
+    insert_keys: Vec<KeyEvent>,
+    /// Register and keys of the macro being recorded.
+    recording: Option<(char, Vec<KeyEvent>)>,
+    /// Registers of the macros being played, innermost last. A macro that
+    /// is already playing is not started again (no infinite recursion).
+    playing_macros: Vec<char>,
+    /// Last played macro register, for `@@`.
+    last_macro: Option<char>,
+    /// Show line numbers in the gutter (`:set nu`).
+    pub line_numbers: bool,
+    /// Show distances from the cursor line in the gutter (`:set rnu`); the
+    /// cursor line keeps its absolute number.
+    pub relative_numbers: bool,
+    /// Soft-wrap long lines to the panel width (`:set wrap`). Only the
+    /// display changes: `j` / `k` still move by buffer line.
+    pub wrap: bool,
+    /// Line width `gq` formats to (`:set tw`).
+    pub text_width: usize,
+    /// Close brackets and quotes as they are typed in Insert mode
+    /// (`:set autopairs`).
+    pub auto_pairs: bool,
+    /// Columns per indent level for `>` / `<` (`:set sw`).
+    pub shift_width: usize,
+    /// Indent with spaces rather than tabs (`:set et`).
+    pub expand_tab: bool,
 }
 
 impl VimEditor {
@@ -154,12 +321,38 @@ impl VimEditor {
         Self {
             buffer: TextBuffer::new(),
             mode: VimMode::Normal,
-            history: History::new(200),
-            register: Register::new(),
+            history: History::new(UNDO_LEVELS),
+            registers: Registers::default(),
+            selected_register: None,
             parse_state: ParseState::Idle,
             visual_anchor_row: 0,
             visual_anchor_col: 0,
             visible_height: Cell::new(20),
+            last_view: Cell::new(Rect::default()),
+            view_rows: RefCell::new(Vec::new()),
+            drag_anchor: None,
+            last_click: None,
+            last_search: None,
+            last_search_forward: true,
+            change_keys: Vec::new(),
+            change_made: false,
+            last_change: Vec::new(),
+            replaying: false,
+            block_insert: None,
+            replaced: Vec::new(),
+            insert_repeat: 1,
+            insert_keys: Vec::new(),
+            macros: HashMap::new(),
+            recording: None,
+            playing_macros: Vec::new(),
+            last_macro: None,
+            line_numbers: true,
+            relative_numbers: true,
+            wrap: false,
+            text_width: 80,
+            auto_pairs: false,
+            shift_width: 4,
+            expand_tab: true,
         }
     }
 
@@ -180,6 +373,17 @@ impl VimEditor {
         self.parse_state = ParseState::Idle;
     }
 
+    /// Hand out the undo history, leaving an empty one. Together with
+    /// `set_history` this lets a caller keep history across `set_text`.
+    pub fn take_history(&mut self) -> History {
+        std::mem::replace(&mut self.history, History::new(UNDO_LEVELS))
+    }
+
+    /// Install a history taken earlier for the same text.
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
     pub fn is_dirty(&self) -> bool {
         self.buffer.dirty
     }
@@ -197,7 +401,7 @@ impl VimEditor {
         }
         self.save_undo();
 
-        let was_insert = self.mode == VimMode::Insert;
+        let was_insert = self.is_typing();
         if !was_insert {
             // Enter insert mode temporarily
             self.mode = VimMode::Insert;
@@ -215,10 +419,91 @@ impl VimEditor {
         }
     }
 
+    /// Substitute the literal `pattern` with `replacement` (`:s`) on the
+    /// cursor line, or on every line when `whole_buffer` (`:%s`). `global`
+    /// replaces every occurrence per line. An undo snapshot is saved only if
+    /// something matches. Returns the number of replacements.
+    pub fn substitute(
+        &mut self,
+        pattern: &str,
+        replacement: &str,
+        whole_buffer: bool,
+        global: bool,
+    ) -> usize {
+        let (start_row, end_row) = if whole_buffer {
+            (0, self.buffer.line_count().saturating_sub(1))
+        } else {
+            (self.buffer.cursor_row, self.buffer.cursor_row)
+        };
+        let matches = !pattern.is_empty()
+            && self.buffer.lines[start_row..=end_row]
+                .iter()
+                .any(|line| line.contains(pattern));
+        if !matches {
+            return 0;
+        }
+        self.save_undo();
+        self.buffer
+            .substitute(start_row, end_row, pattern, replacement, global)
+    }
+
+    /// Replace lines `start_row..=end_row` with `text` as one undoable change
+    /// (used by `:!` filters). A trailing newline in `text` is ignored and
+    /// empty text deletes the lines. The cursor lands on the first new line.
+    pub fn replace_lines(&mut self, start_row: usize, end_row: usize, text: &str) {
+        self.save_undo();
+        self.buffer.replace_lines(start_row, end_row, text);
+        self.mode = VimMode::Normal;
+        self.parse_state = ParseState::Idle;
+    }
+
+    /// Strip trailing whitespace and fix up the final newline (see
+    /// `TextBuffer::tidy_whitespace`) as one undoable change. Returns
+    /// whether the text changed.
+    pub fn tidy_whitespace(&mut self) -> bool {
+        let snapshot = self.buffer.snapshot();
+        if !self.buffer.tidy_whitespace() {
+            return false;
+        }
+        self.history.push(snapshot);
+        true
+    }
+
+    /// Sort lines `start_row..=end_row` as one undoable change. Returns the
+    /// number of lines left in the range.
+    pub fn sort_lines(&mut self, start_row: usize, end_row: usize, options: SortOptions) -> usize {
+        self.save_undo();
+        self.mode = VimMode::Normal;
+        self.parse_state = ParseState::Idle;
+        self.buffer.sort_lines(start_row, end_row, options)
+    }
+
+    /// Rows covered by the Visual selection (any Visual mode), if one is
+    /// active. Used as the range of a command typed from Visual mode.
+    pub fn selected_rows(&self) -> Option<(usize, usize)> {
+        matches!(
+            self.mode,
+            VimMode::Visual | VimMode::VisualLine | VimMode::VisualBlock
+        )
+        .then(|| self.visual_line_range())
+    }
+
+    /// The register a macro is being recorded into, if any.
+    pub fn recording_macro(&self) -> Option<char> {
+        self.recording.as_ref().map(|(reg, _)| *reg)
+    }
+
+    /// Whether a `/` or `?` search pattern is being typed. Parents should
+    /// pass every key to the editor while this is true.
+    pub fn is_searching(&self) -> bool {
+        matches!(self.parse_state, ParseState::Search { .. })
+    }
+
     /// Save snapshot before a modification for undo.
     fn save_undo(&mut self) {
         let snapshot = self.buffer.snapshot();
         self.history.push(snapshot);
+        self.change_made = true;
     }
 
     /// Reset parse state.
@@ -226,17 +511,269 @@ impl VimEditor {
         self.parse_state = ParseState::Idle;
     }
 
+    /// Store yanked or deleted text in the unnamed register and, with a
+    /// `"a`–`"z` prefix, in that named register. Yanks without a prefix
+    /// also go to the numbered ring (`"0`). Numbered registers are read-only.
+    fn set_register(&mut self, content: String, linewise: bool, yank: bool) {
+        // Yank paths save an undo snapshot too, but they are not changes for `.`
+        if yank {
+            self.change_made = false;
+        }
+        let register = Register { content, linewise };
+        match self.selected_register.take() {
+            Some(name @ 'a'..='z') => {
+                self.registers.named.insert(name, register.clone());
+            }
+            _ if yank => self.registers.push_yank(register.clone()),
+            _ => {}
+        }
+        self.registers.unnamed = register;
+    }
+
+    /// The register to paste from: the `"x` prefix if any, else unnamed.
+    fn paste_register(&mut self) -> Option<Register> {
+        let name = self.selected_register.take();
+        self.registers
+            .get(name)
+            .filter(|r| !r.content.is_empty())
+            .cloned()
+    }
+
     // ── Key handling ─────────────────────────────────────────────────
 
     pub fn handle_key(&mut self, key: KeyEvent) -> EditorAction {
+        if self.replaying {
+            return self.dispatch_key(key);
+        }
+
+        // Keys replayed from a macro are not recorded again; the `@x` that
+        // started them already was
+        if self.playing_macros.is_empty()
+            && let Some((_, ref mut keys)) = self.recording
+        {
+            keys.push(key);
+        }
+
+        // A new command starts in Normal mode with nothing pending
+        if self.is_idle() {
+            if key.code == KeyCode::Char('.') && key.modifiers == KeyModifiers::NONE {
+                self.repeat_last_change();
+                return EditorAction::None;
+            }
+            self.change_keys.clear();
+            self.change_made = false;
+        }
+
+        self.change_keys.push(key);
+        let action = self.dispatch_key(key);
+        if self.is_typing() {
+            self.change_made = true;
+        }
+
+        // The command is complete: keep it for `.` if it changed the buffer
+        if self.is_idle() && self.change_made {
+            self.last_change = std::mem::take(&mut self.change_keys);
+            self.change_made = false;
+        }
+        action
+    }
+
+    fn dispatch_key(&mut self, key: KeyEvent) -> EditorAction {
         match self.mode {
             VimMode::Normal => self.handle_normal_key(key),
             VimMode::Insert => self.handle_insert_key(key),
-            VimMode::Visual | VimMode::VisualLine => self.handle_visual_key(key),
+            VimMode::Replace => self.handle_replace_key(key),
+            VimMode::Visual | VimMode::VisualLine | VimMode::VisualBlock => {
+                self.handle_visual_key(key)
+            }
+        }
+    }
+
+    /// Buffer (row, byte col) shown at screen cell (`column`, `row`) in the
+    /// last render, or `None` outside the text area.
+    pub fn position_at(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let area = self.last_view.get();
+        let inside = column >= area.x
+            && column < area.x + area.width
+            && row >= area.y
+            && row < area.y + area.height;
+        inside.then(|| self.clamped_position(column, row))
+    }
+
+    /// Like `position_at`, but clamped to the text for cells outside it
+    /// (dragging past the last line or the end of a line).
+    fn clamped_position(&self, column: u16, row: u16) -> (usize, usize) {
+        let area = self.last_view.get();
+        let view_rows = self.view_rows.borrow();
+        let index = row.saturating_sub(area.y) as usize;
+        let Some(&(buf_row, start)) = view_rows.get(index).or(view_rows.last()) else {
+            return (0, 0);
+        };
+        let line = &self.buffer.lines[buf_row];
+        let cells = column.saturating_sub(area.x) as usize;
+        let past_end = if self.is_typing() {
+            line.len()
+        } else {
+            line.char_indices().next_back().map_or(0, |(i, _)| i)
+        };
+        // Cells past a wrapped display line stay on its last character
+        let end = view_rows
+            .iter()
+            .find(|&&(r, s)| r == buf_row && s > start)
+            .map_or(usize::MAX, |&(_, next)| next - 1);
+        let byte_col = char_at_cell(line, start, cells)
+            .map(|col| col.min(end))
+            .and_then(|col| line.char_indices().nth(col))
+            .map_or(past_end, |(i, _)| i);
+        (buf_row, byte_col)
+    }
+
+    /// Left mouse button pressed at a screen cell: move the cursor there and
+    /// start a drag selection. A second click on the same spot selects the
+    /// word under it. Returns false if the cell is outside the text.
+    pub fn mouse_down(&mut self, column: u16, row: u16) -> bool {
+        let Some(pos) = self.position_at(column, row) else {
+            return false;
+        };
+        let double_click = self
+            .last_click
+            .is_some_and(|(at, last)| last == pos && at.elapsed() < DOUBLE_CLICK);
+        self.last_click = Some((Instant::now(), pos));
+        (self.buffer.cursor_row, self.buffer.cursor_col) = pos;
+        if self.is_typing() {
+            self.replaced.clear();
+            self.insert_repeat = 1;
+            return true;
+        }
+
+        self.mode = VimMode::Normal;
+        self.reset_parse();
+        self.drag_anchor = Some(pos);
+        if double_click
+            && let Some((sr, sc, er, ec)) = self.compute_text_object(TextObject::InnerWord)
+        {
+            self.start_mouse_visual((sr, sc), &['v', 'i', 'w']);
+            self.buffer.cursor_row = er;
+            self.buffer.cursor_col = ec.saturating_sub(1);
+            self.drag_anchor = None;
+            self.last_click = None;
+        }
+        true
+    }
+
+    /// Mouse moved with the left button held: extend a Visual selection from
+    /// where the button went down. Returns false if no drag is in progress.
+    pub fn mouse_drag(&mut self, column: u16, row: u16) -> bool {
+        let Some(anchor) = self.drag_anchor else {
+            return false;
+        };
+        let pos = self.clamped_position(column, row);
+        if self.mode == VimMode::Normal && pos != anchor {
+            self.start_mouse_visual(anchor, &['v']);
+        }
+        (self.buffer.cursor_row, self.buffer.cursor_col) = pos;
+        true
+    }
+
+    /// Left mouse button released; the selection stays for `y`, `d`, ...
+    pub fn mouse_up(&mut self) {
+        self.drag_anchor = None;
+    }
+
+    /// Enter Visual mode anchored at `anchor`, as if `keys` had been typed
+    /// (so `.` after an operator on the selection replays them).
+    fn start_mouse_visual(&mut self, anchor: (usize, usize), keys: &[char]) {
+        (self.visual_anchor_row, self.visual_anchor_col) = anchor;
+        self.mode = VimMode::Visual;
+        self.change_keys = keys
+            .iter()
+            .map(|&c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .collect();
+        self.change_made = false;
+    }
+
+    /// The keys of a partially typed command (`2d`, `"a`, `gU`...), for a
+    /// showcmd-style indicator. Empty when nothing is pending; a `/` search
+    /// is not included since its pattern is shown on its own.
+    pub fn pending_keys(&self) -> String {
+        let count = |n: usize| if n > 1 { n.to_string() } else { String::new() };
+        let op = |op: Option<Operator>| op.map(|o| o.keys().to_string()).unwrap_or_default();
+        let mut keys = self
+            .selected_register
+            .map(|r| format!("\"{r}"))
+            .unwrap_or_default();
+        let state = match &self.parse_state {
+            ParseState::Idle | ParseState::Search { .. } => String::new(),
+            ParseState::Count(n) => n.to_string(),
+            ParseState::OperatorPending { op, count: n } => format!("{}{}", count(*n), op.keys()),
+            ParseState::OperatorCount { op, count1, count2 } => {
+                format!("{}{}{count2}", count(*count1), op.keys())
+            }
+            ParseState::PendingG { count: n } => format!("{}g", count(*n)),
+            ParseState::PendingFind {
+                count: n,
+                op: o,
+                forward,
+                till,
+            } => {
+                let key = match (forward, till) {
+                    (true, false) => 'f',
+                    (false, false) => 'F',
+                    (true, true) => 't',
+                    (false, true) => 'T',
+                };
+                format!("{}{}{key}", count(*n), op(*o))
+            }
+            ParseState::PendingReplace { count: n } => format!("{}r", count(*n)),
+            ParseState::PendingRegister => "\"".to_string(),
+            ParseState::PendingMacroRecord => "q".to_string(),
+            ParseState::PendingMacroPlay { count: n } => format!("{}@", count(*n)),
+            ParseState::PendingMark => "m".to_string(),
+            ParseState::PendingMarkJump { exact } => if *exact { "`" } else { "'" }.to_string(),
+            ParseState::PendingTextObject {
+                op: o,
+                count: n,
+                inner,
+            } => format!("{}{}{}", count(*n), op(*o), if *inner { 'i' } else { 'a' }),
+        };
+        keys.push_str(&state);
+        keys
+    }
+
+    /// Insert or Replace mode: keys type text and the cursor may sit past
+    /// the end of the line.
+    fn is_typing(&self) -> bool {
+        matches!(self.mode, VimMode::Insert | VimMode::Replace)
+    }
+
+    /// Normal mode with no partially typed command.
+    fn is_idle(&self) -> bool {
+        self.mode == VimMode::Normal && matches!(self.parse_state, ParseState::Idle)
+    }
+
+    /// `.`: replay the keys of the last buffer-modifying command, including
+    /// the text typed in its Insert session.
+    fn repeat_last_change(&mut self) {
+        let keys = self.last_change.clone();
+        self.replaying = true;
+        for key in keys {
+            self.dispatch_key(key);
         }
+        self.replaying = false;
+        self.change_made = false;
     }
 
     fn handle_normal_key(&mut self, key: KeyEvent) -> EditorAction {
+        let selecting_register = matches!(self.parse_state, ParseState::PendingRegister);
+        let action = self.dispatch_normal_key(key);
+        // A `"x` prefix only applies to the command that directly follows it
+        if !selecting_register && matches!(self.parse_state, ParseState::Idle) {
+            self.selected_register = None;
+        }
+        action
+    }
+
+    fn dispatch_normal_key(&mut self, key: KeyEvent) -> EditorAction {
         // Process based on current parse state
         match self.parse_state.clone() {
             ParseState::Idle => self.handle_normal_idle(key),
@@ -255,9 +792,19 @@ impl VimEditor {
                 till,
             } => self.handle_pending_find(key, count, op, forward, till),
             ParseState::PendingReplace { count } => self.handle_pending_replace(key, count),
+            ParseState::PendingRegister => self.handle_pending_register(key),
+            ParseState::PendingMacroRecord => self.handle_pending_macro_record(key),
+            ParseState::PendingMacroPlay { count } => self.handle_pending_macro_play(key, count),
+            ParseState::PendingMark => self.handle_pending_mark(key),
+            ParseState::PendingMarkJump { exact } => self.handle_pending_mark_jump(key, exact),
             ParseState::PendingTextObject { op, count, inner } => {
                 self.handle_pending_text_object(key, op, count, inner)
             }
+            ParseState::Search {
+                forward,
+                query,
+                origin,
+            } => self.handle_search_key(key, forward, query, origin),
         }
     }
 
@@ -380,6 +927,17 @@ impl VimEditor {
                 };
                 EditorAction::None
             }
+            KeyCode::Char(c @ ('>' | '<')) => {
+                self.parse_state = ParseState::OperatorPending {
+                    op: if c == '>' {
+                        Operator::Indent
+                    } else {
+                        Operator::Dedent
+                    },
+                    count: 1,
+                };
+                EditorAction::None
+            }
 
             // Standalone commands
             KeyCode::Char('x') => {
@@ -403,10 +961,7 @@ impl VimEditor {
                         self.buffer.cursor_row,
                         len,
                     );
-                    self.register = Register {
-                        content: deleted,
-                        linewise: false,
-                    };
+                    self.set_register(deleted, false, false);
                 }
                 self.buffer.clamp_cursor_col(false);
                 EditorAction::None
@@ -422,10 +977,7 @@ impl VimEditor {
                         self.buffer.cursor_row,
                         len,
                     );
-                    self.register = Register {
-                        content: deleted,
-                        linewise: false,
-                    };
+                    self.set_register(deleted, false, false);
                 }
                 self.mode = VimMode::Insert;
                 return EditorAction::ModeChanged(VimMode::Insert);
@@ -433,10 +985,7 @@ impl VimEditor {
             KeyCode::Char('Y') => {
                 // Yank current line
                 let line = self.buffer.current_line().to_string();
-                self.register = Register {
-                    content: line,
-                    linewise: true,
-                };
+                self.set_register(line, true, true);
                 EditorAction::None
             }
             KeyCode::Char('J') => {
@@ -446,12 +995,12 @@ impl VimEditor {
             }
             KeyCode::Char('p') => {
                 self.save_undo();
-                self.paste_after();
+                self.paste_after(1);
                 EditorAction::None
             }
             KeyCode::Char('P') => {
                 self.save_undo();
-                self.paste_before();
+                self.paste_before(1);
                 EditorAction::None
             }
             KeyCode::Char('u') if key.modifiers == KeyModifiers::NONE => {
@@ -489,17 +1038,20 @@ impl VimEditor {
                 self.mode = VimMode::Insert;
                 EditorAction::ModeChanged(VimMode::Insert)
             }
-            KeyCode::Char('o') => {
+            KeyCode::Char('R') => {
+                // One undo step for the whole session
                 self.save_undo();
-                self.buffer.open_line_below();
-                self.mode = VimMode::Insert;
-                EditorAction::ModeChanged(VimMode::Insert)
+                self.replaced.clear();
+                self.mode = VimMode::Replace;
+                EditorAction::ModeChanged(VimMode::Replace)
             }
-            KeyCode::Char('O') => {
-                self.save_undo();
-                self.buffer.open_line_above();
-                self.mode = VimMode::Insert;
-                EditorAction::ModeChanged(VimMode::Insert)
+            KeyCode::Char('o') => self.open_line(true, 1),
+            KeyCode::Char('O') => self.open_line(false, 1),
+            KeyCode::Char('v') if key.modifiers == KeyModifiers::CONTROL => {
+                self.visual_anchor_row = self.buffer.cursor_row;
+                self.visual_anchor_col = self.buffer.cursor_col;
+                self.mode = VimMode::VisualBlock;
+                EditorAction::ModeChanged(VimMode::VisualBlock)
             }
             KeyCode::Char('v') => {
                 self.visual_anchor_row = self.buffer.cursor_row;
@@ -514,6 +1066,55 @@ impl VimEditor {
                 EditorAction::ModeChanged(VimMode::VisualLine)
             }
             KeyCode::Char(':') => EditorAction::EnterCommandMode,
+
+            // Register prefix
+            KeyCode::Char('"') => {
+                self.parse_state = ParseState::PendingRegister;
+                EditorAction::None
+            }
+
+            // Marks
+            KeyCode::Char('m') => {
+                self.parse_state = ParseState::PendingMark;
+                EditorAction::None
+            }
+            KeyCode::Char(c @ ('`' | '\'')) => {
+                self.parse_state = ParseState::PendingMarkJump { exact: c == '`' };
+                EditorAction::None
+            }
+
+            // Macros
+            KeyCode::Char('q') if key.modifiers == KeyModifiers::NONE => {
+                if let Some((reg, mut keys)) = self.recording.take() {
+                    keys.pop(); // the `q` that stops the recording
+                    self.macros.insert(reg, keys);
+                } else {
+                    self.parse_state = ParseState::PendingMacroRecord;
+                }
+                EditorAction::None
+            }
+            KeyCode::Char('@') => {
+                self.parse_state = ParseState::PendingMacroPlay { count: 1 };
+                EditorAction::None
+            }
+
+            // Search
+            KeyCode::Char(c @ ('/' | '?')) => {
+                self.parse_state = ParseState::Search {
+                    forward: c == '/',
+                    query: String::new(),
+                    origin: (self.buffer.cursor_row, self.buffer.cursor_col),
+                };
+                EditorAction::None
+            }
+            KeyCode::Char('n') => {
+                self.repeat_search(false);
+                EditorAction::None
+            }
+            KeyCode::Char('N') => {
+                self.repeat_search(true);
+                EditorAction::None
+            }
             KeyCode::Esc => {
                 self.reset_parse();
                 EditorAction::None
@@ -522,6 +1123,131 @@ impl VimEditor {
         }
     }
 
+    fn handle_search_key(
+        &mut self,
+        key: KeyEvent,
+        forward: bool,
+        mut query: String,
+        origin: (usize, usize),
+    ) -> EditorAction {
+        match key.code {
+            KeyCode::Esc => {
+                self.set_cursor(origin);
+                self.reset_parse();
+                return EditorAction::None;
+            }
+            KeyCode::Enter => {
+                // An empty pattern repeats the last search in the new direction
+                if query.is_empty() {
+                    query = self.last_search.clone().unwrap_or_default();
+                }
+                self.reset_parse();
+                if query.is_empty() {
+                    return EditorAction::None;
+                }
+                let target = find_match(&self.buffer.lines, &query, origin, forward);
+                if target.is_some() {
+                    self.buffer.marks.insert('`', origin);
+                }
+                self.set_cursor(target.unwrap_or(origin));
+                self.last_search = Some(query);
+                self.last_search_forward = forward;
+                return EditorAction::None;
+            }
+            // Backspace on an empty pattern cancels, like vim
+            KeyCode::Backspace if query.is_empty() => {
+                self.set_cursor(origin);
+                self.reset_parse();
+                return EditorAction::None;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                query.push(c);
+            }
+            _ => {}
+        }
+
+        // Incremental: jump to the first match from where the search started
+        let target = if query.is_empty() {
+            None
+        } else {
+            find_match(&self.buffer.lines, &query, origin, forward)
+        };
+        self.set_cursor(target.unwrap_or(origin));
+        self.parse_state = ParseState::Search {
+            forward,
+            query,
+            origin,
+        };
+        EditorAction::None
+    }
+
+    /// `n` / `N`: jump to the next match of the last search, in its
+    /// direction or (`reverse`) the opposite one.
+    fn repeat_search(&mut self, reverse: bool) {
+        let Some(ref pattern) = self.last_search else {
+            return;
+        };
+        let forward = self.last_search_forward != reverse;
+        let from = (self.buffer.cursor_row, self.buffer.cursor_col);
+        if let Some(target) = find_match(&self.buffer.lines, pattern, from, forward) {
+            self.remember_jump();
+            self.set_cursor(target);
+        }
+    }
+
+    // ── Marks ────────────────────────────────────────────────────────
+
+    /// `m<a-z>`: mark the cursor position.
+    fn handle_pending_mark(&mut self, key: KeyEvent) -> EditorAction {
+        if let KeyCode::Char(name @ 'a'..='z') = key.code {
+            let pos = (self.buffer.cursor_row, self.buffer.cursor_col);
+            self.buffer.marks.insert(name, pos);
+        }
+        self.reset_parse();
+        EditorAction::None
+    }
+
+    /// `` `<mark> `` jumps to the marked position, `'<mark>` to the first
+    /// non-blank of its line. `` ` `` / `'` as the mark is the last jump.
+    fn handle_pending_mark_jump(&mut self, key: KeyEvent, exact: bool) -> EditorAction {
+        self.reset_parse();
+        let name = match key.code {
+            KeyCode::Char(name @ 'a'..='z') => name,
+            KeyCode::Char('`' | '\'') => '`',
+            _ => return EditorAction::None,
+        };
+        let Some(&(row, col)) = self.buffer.marks.get(&name) else {
+            return EditorAction::None;
+        };
+        self.remember_jump();
+        self.buffer.cursor_row = row.min(self.buffer.line_count() - 1);
+        let line = self.buffer.current_line();
+        self.buffer.cursor_col = if exact {
+            line.floor_char_boundary(col)
+        } else {
+            line.len() - line.trim_start().len()
+        };
+        self.buffer.clamp_cursor_col(false);
+        self.buffer.desired_col = self.buffer.cursor_col;
+        EditorAction::None
+    }
+
+    /// Set the `` ` `` mark to the cursor before a jump (`gg`, `G`, searches,
+    /// mark jumps), so `` `` `` returns there.
+    fn remember_jump(&mut self) {
+        let pos = (self.buffer.cursor_row, self.buffer.cursor_col);
+        self.buffer.marks.insert('`', pos);
+    }
+
+    fn set_cursor(&mut self, (row, col): (usize, usize)) {
+        self.buffer.cursor_row = row;
+        self.buffer.cursor_col = col;
+        self.buffer.desired_col = col;
+    }
+
     fn handle_normal_count(&mut self, key: KeyEvent, n: usize) -> EditorAction {
         match key.code {
             KeyCode::Char(c @ '0'..='9') => {
@@ -566,6 +1292,7 @@ impl VimEditor {
             }
             KeyCode::Char('G') => {
                 // nG = goto line n
+                self.remember_jump();
                 let target = (n - 1).min(self.buffer.lines.len() - 1);
                 self.buffer.cursor_row = target;
                 self.buffer.cursor_col = 0;
@@ -599,6 +1326,17 @@ impl VimEditor {
                 };
                 EditorAction::None
             }
+            KeyCode::Char(c @ ('>' | '<')) => {
+                self.parse_state = ParseState::OperatorPending {
+                    op: if c == '>' {
+                        Operator::Indent
+                    } else {
+                        Operator::Dedent
+                    },
+                    count: n,
+                };
+                EditorAction::None
+            }
             KeyCode::Char('x') => {
                 self.save_undo();
                 for _ in 0..n {
@@ -648,6 +1386,24 @@ impl VimEditor {
                 self.parse_state = ParseState::PendingReplace { count: n };
                 EditorAction::None
             }
+            KeyCode::Char('@') => {
+                self.parse_state = ParseState::PendingMacroPlay { count: n };
+                EditorAction::None
+            }
+            KeyCode::Char(c @ ('p' | 'P')) => {
+                self.save_undo();
+                if c == 'p' {
+                    self.paste_after(n);
+                } else {
+                    self.paste_before(n);
+                }
+                self.reset_parse();
+                EditorAction::None
+            }
+            KeyCode::Char(c @ ('o' | 'O')) => {
+                self.reset_parse();
+                self.open_line(c == 'o', n)
+            }
             KeyCode::Esc => {
                 self.reset_parse();
                 EditorAction::None
@@ -685,6 +1441,21 @@ impl VimEditor {
                 self.reset_parse();
                 return EditorAction::ModeChanged(VimMode::Insert);
             }
+            KeyCode::Char('q') if op == Operator::Format => {
+                self.execute_line_op(op, count);
+                self.reset_parse();
+                EditorAction::None
+            }
+            KeyCode::Char('>') if op == Operator::Indent => {
+                self.execute_line_op(op, count);
+                self.reset_parse();
+                EditorAction::None
+            }
+            KeyCode::Char('<') if op == Operator::Dedent => {
+                self.execute_line_op(op, count);
+                self.reset_parse();
+                EditorAction::None
+            }
             // Count after operator
             KeyCode::Char(c @ '1'..='9') => {
                 self.parse_state = ParseState::OperatorCount {
@@ -789,11 +1560,13 @@ impl VimEditor {
                 let end_row = self.buffer.lines.len() - 1;
                 let start_row = self.buffer.cursor_row;
                 self.save_undo();
+                if op.is_linewise_edit() {
+                    self.apply_line_operator(op, start_row, end_row);
+                    self.reset_parse();
+                    return EditorAction::None;
+                }
                 let deleted = self.buffer.delete_line_range(start_row, end_row);
-                self.register = Register {
-                    content: deleted,
-                    linewise: true,
-                };
+                self.set_register(deleted, true, false);
                 if op == Operator::Change {
                     self.mode = VimMode::Insert;
                     self.reset_parse();
@@ -883,6 +1656,7 @@ impl VimEditor {
             KeyCode::Char('g') => {
                 if count > 1 {
                     // ngg = goto line n
+                    self.remember_jump();
                     let target = (count - 1).min(self.buffer.lines.len() - 1);
                     self.buffer.cursor_row = target;
                     self.buffer.cursor_col = 0;
@@ -893,20 +1667,91 @@ impl VimEditor {
                 self.reset_parse();
                 EditorAction::None
             }
-            _ => {
+            KeyCode::Char(c @ ('j' | 'k')) => {
+                for _ in 0..count {
+                    self.move_display_line(c == 'j');
+                }
                 self.reset_parse();
                 EditorAction::None
             }
-        }
-    }
-
-    fn handle_pending_find(
-        &mut self,
-        key: KeyEvent,
-        count: usize,
-        op: Option<Operator>,
-        forward: bool,
-        till: bool,
+            KeyCode::Char('q')
+                if matches!(
+                    self.mode,
+                    VimMode::Visual | VimMode::VisualLine | VimMode::VisualBlock
+                ) =>
+            {
+                self.execute_visual_op(Operator::Format);
+                self.mode = VimMode::Normal;
+                self.reset_parse();
+                EditorAction::ModeChanged(VimMode::Normal)
+            }
+            KeyCode::Char('q') => {
+                self.parse_state = ParseState::OperatorPending {
+                    op: Operator::Format,
+                    count,
+                };
+                EditorAction::None
+            }
+            _ => {
+                self.reset_parse();
+                EditorAction::None
+            }
+        }
+    }
+
+    /// `gj` / `gk`: move one display line down or up, keeping the column
+    /// within the display line. Same as `j` / `k` without `wrap`.
+    fn move_display_line(&mut self, down: bool) {
+        let width = self.last_view.get().width as usize;
+        if !self.wrap || width == 0 {
+            let motion = if down { Motion::Down } else { Motion::Up };
+            self.execute_motion(motion, 1);
+            return;
+        }
+
+        let row = self.buffer.cursor_row;
+        let col = char_col(&self.buffer.lines[row], self.buffer.cursor_col);
+        let starts = self.display_starts(row, width);
+        let sub = self.display_row_of(row, col, width);
+        let cells = cell_width(&self.buffer.lines[row], starts[sub], col);
+        let target = if down {
+            if sub + 1 < starts.len() {
+                Some((row, sub + 1))
+            } else if row + 1 < self.buffer.line_count() {
+                Some((row + 1, 0))
+            } else {
+                None
+            }
+        } else if sub > 0 {
+            Some((row, sub - 1))
+        } else if row > 0 {
+            let prev_rows = self.display_starts(row - 1, width).len();
+            Some((row - 1, prev_rows - 1))
+        } else {
+            None
+        };
+
+        if let Some((row, sub)) = target {
+            let starts = self.display_starts(row, width);
+            let line = &self.buffer.lines[row];
+            // Same screen cell, within the target display line
+            let col = char_at_cell(line, starts[sub], cells)
+                .unwrap_or(usize::MAX)
+                .min(starts.get(sub + 1).map_or(usize::MAX, |next| next - 1));
+            self.buffer.cursor_row = row;
+            self.buffer.cursor_col = line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+            self.buffer.clamp_cursor_col(self.is_typing());
+            self.buffer.desired_col = self.buffer.cursor_col;
+        }
+    }
+
+    fn handle_pending_find(
+        &mut self,
+        key: KeyEvent,
+        count: usize,
+        op: Option<Operator>,
+        forward: bool,
+        till: bool,
     ) -> EditorAction {
         match key.code {
             KeyCode::Char(c) => {
@@ -939,6 +1784,61 @@ impl VimEditor {
         }
     }
 
+    fn handle_pending_register(&mut self, key: KeyEvent) -> EditorAction {
+        match key.code {
+            KeyCode::Char(c @ ('a'..='z' | '0'..='9')) => {
+                self.selected_register = Some(c);
+                self.parse_state = ParseState::Idle;
+            }
+            _ => self.reset_parse(),
+        }
+        EditorAction::None
+    }
+
+    fn handle_pending_macro_record(&mut self, key: KeyEvent) -> EditorAction {
+        if let KeyCode::Char(c @ 'a'..='z') = key.code {
+            self.recording = Some((c, Vec::new()));
+        }
+        self.reset_parse();
+        EditorAction::None
+    }
+
+    fn handle_pending_macro_play(&mut self, key: KeyEvent, count: usize) -> EditorAction {
+        self.reset_parse();
+        let reg = match key.code {
+            KeyCode::Char(c @ 'a'..='z') => c,
+            KeyCode::Char('@') => match self.last_macro {
+                Some(c) => c,
+                None => return EditorAction::None,
+            },
+            _ => return EditorAction::None,
+        };
+        self.play_macro(reg, count);
+        // Playback may end in any mode; report it so the parent can sync
+        match self.mode {
+            VimMode::Normal => EditorAction::None,
+            mode => EditorAction::ModeChanged(mode),
+        }
+    }
+
+    /// Feed a recorded macro through `handle_key`, `count` times.
+    fn play_macro(&mut self, reg: char, count: usize) {
+        if self.playing_macros.contains(&reg) {
+            return;
+        }
+        let Some(keys) = self.macros.get(&reg).cloned() else {
+            return;
+        };
+        self.last_macro = Some(reg);
+        self.playing_macros.push(reg);
+        for _ in 0..count {
+            for &key in &keys {
+                self.handle_key(key);
+            }
+        }
+        self.playing_macros.pop();
+    }
+
     fn handle_pending_replace(&mut self, key: KeyEvent, _count: usize) -> EditorAction {
         match key.code {
             KeyCode::Char(c) => {
@@ -1060,9 +1960,42 @@ impl VimEditor {
 
     // ── Insert mode ──────────────────────────────────────────────────
 
+    /// `o` / `O`: open a line below / above and start Insert mode. With a
+    /// count, the text typed before `Esc` is repeated on that many lines.
+    fn open_line(&mut self, below: bool, count: usize) -> EditorAction {
+        self.save_undo();
+        if below {
+            self.buffer.open_line_below();
+        } else {
+            self.buffer.open_line_above();
+        }
+        self.insert_repeat = count.max(1);
+        self.insert_keys.clear();
+        self.mode = VimMode::Insert;
+        EditorAction::ModeChanged(VimMode::Insert)
+    }
+
+    /// On `Esc` after a counted `o` / `O`: type the session again on new
+    /// lines below.
+    fn repeat_opened_lines(&mut self) {
+        let copies = std::mem::replace(&mut self.insert_repeat, 1);
+        let keys = std::mem::take(&mut self.insert_keys);
+        for _ in 1..copies {
+            self.buffer.open_line_below();
+            for &key in &keys {
+                self.handle_insert_key(key);
+            }
+        }
+    }
+
     fn handle_insert_key(&mut self, key: KeyEvent) -> EditorAction {
+        if self.insert_repeat > 1 && key.code != KeyCode::Esc {
+            self.insert_keys.push(key);
+        }
         match key.code {
             KeyCode::Esc => {
+                self.repeat_opened_lines();
+                self.finish_block_insert();
                 // Move cursor back one (vim convention)
                 if self.buffer.cursor_col > 0 {
                     self.buffer.cursor_left();
@@ -1070,9 +2003,23 @@ impl VimEditor {
                 self.mode = VimMode::Normal;
                 EditorAction::ModeChanged(VimMode::Normal)
             }
+            KeyCode::Char(c)
+                if self.auto_pairs && self.char_after_cursor() == Some(c) && is_pair_close(c) =>
+            {
+                // Type over the closing half that is already there
+                self.buffer.cursor_right();
+                EditorAction::None
+            }
             KeyCode::Char(c) => {
                 self.save_undo();
                 self.buffer.insert_char(c);
+                if self.auto_pairs
+                    && let Some(close) = pair_close(c)
+                    && self.should_pair(c)
+                {
+                    self.buffer.insert_char(close);
+                    self.buffer.cursor_left();
+                }
                 EditorAction::None
             }
             KeyCode::Enter => {
@@ -1082,6 +2029,15 @@ impl VimEditor {
             }
             KeyCode::Backspace => {
                 self.save_undo();
+                // Backspace in an empty pair removes both halves
+                let line = self.buffer.current_line();
+                let before = line[..self.buffer.cursor_col].chars().next_back();
+                if self.auto_pairs
+                    && before.and_then(pair_close).is_some()
+                    && before.and_then(pair_close) == self.char_after_cursor()
+                {
+                    self.buffer.delete_char_at_cursor();
+                }
                 self.buffer.backspace();
                 EditorAction::None
             }
@@ -1118,6 +2074,71 @@ impl VimEditor {
         }
     }
 
+    fn handle_replace_key(&mut self, key: KeyEvent) -> EditorAction {
+        match key.code {
+            KeyCode::Esc => {
+                if self.buffer.cursor_col > 0 {
+                    self.buffer.cursor_left();
+                }
+                self.mode = VimMode::Normal;
+                EditorAction::ModeChanged(VimMode::Normal)
+            }
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                let original = self.buffer.overwrite_char(c);
+                self.replaced.push(original);
+                EditorAction::None
+            }
+            KeyCode::Backspace => {
+                // Past the replaced text Backspace only moves, like vim
+                match self.replaced.pop() {
+                    Some(original) => self.buffer.restore_char(original),
+                    None => self.buffer.cursor_left(),
+                }
+                EditorAction::None
+            }
+            KeyCode::Enter => {
+                self.buffer.insert_newline();
+                self.replaced.clear();
+                EditorAction::None
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                match key.code {
+                    KeyCode::Left => self.buffer.cursor_left(),
+                    KeyCode::Right => self.buffer.cursor_right(),
+                    KeyCode::Up => self.buffer.cursor_up(),
+                    _ => self.buffer.cursor_down(),
+                }
+                self.replaced.clear();
+                EditorAction::None
+            }
+            _ => EditorAction::None,
+        }
+    }
+
+    fn char_after_cursor(&self) -> Option<char> {
+        self.buffer.current_line()[self.buffer.cursor_col..]
+            .chars()
+            .next()
+    }
+
+    /// Whether typing the opening `c` should add its closing half. Quotes
+    /// right after a word character (`don't`) or before one are left single.
+    fn should_pair(&self, c: char) -> bool {
+        if c != '"' && c != '\'' {
+            return true;
+        }
+        let line = self.buffer.current_line();
+        let col = self.buffer.cursor_col;
+        // The quote was just typed: look before it and after the cursor
+        let before = line[..col].chars().rev().nth(1);
+        let after = self.char_after_cursor();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    }
+
     // ── Visual mode ──────────────────────────────────────────────────
 
     fn handle_visual_key(&mut self, key: KeyEvent) -> EditorAction {
@@ -1139,6 +2160,15 @@ impl VimEditor {
                 EditorAction::ModeChanged(VimMode::Normal)
             }
             // Toggle between visual modes
+            KeyCode::Char('v') if key.modifiers == KeyModifiers::CONTROL => {
+                if self.mode == VimMode::VisualBlock {
+                    self.mode = VimMode::Normal;
+                    EditorAction::ModeChanged(VimMode::Normal)
+                } else {
+                    self.mode = VimMode::VisualBlock;
+                    EditorAction::ModeChanged(VimMode::VisualBlock)
+                }
+            }
             KeyCode::Char('v') => {
                 if self.mode == VimMode::Visual {
                     self.mode = VimMode::Normal;
@@ -1202,8 +2232,13 @@ impl VimEditor {
                 self.parse_state = ParseState::PendingG { count: 1 };
                 EditorAction::None
             }
+            // Block insert / append on every selected line
+            KeyCode::Char(c @ ('I' | 'A')) if self.mode == VimMode::VisualBlock => {
+                self.start_block_insert(self.visual_block_bounds(), c == 'A');
+                EditorAction::ModeChanged(VimMode::Insert)
+            }
             // Text objects in visual mode
-            KeyCode::Char('i') => {
+            KeyCode::Char('i') if self.mode != VimMode::VisualBlock => {
                 self.parse_state = ParseState::PendingTextObject {
                     op: None,
                     count: 1,
@@ -1225,6 +2260,13 @@ impl VimEditor {
                 self.mode = VimMode::Normal;
                 EditorAction::ModeChanged(VimMode::Normal)
             }
+            KeyCode::Char('c') if self.mode == VimMode::VisualBlock => {
+                // Deleting the block moves the cursor, so keep its bounds
+                let bounds = self.visual_block_bounds();
+                self.execute_visual_op(Operator::Change);
+                self.start_block_insert(bounds, false);
+                EditorAction::ModeChanged(VimMode::Insert)
+            }
             KeyCode::Char('c') => {
                 self.execute_visual_op(Operator::Change);
                 self.mode = VimMode::Insert;
@@ -1235,6 +2277,15 @@ impl VimEditor {
                 self.mode = VimMode::Normal;
                 EditorAction::ModeChanged(VimMode::Normal)
             }
+            KeyCode::Char(c @ ('>' | '<')) => {
+                self.execute_visual_op(if c == '>' {
+                    Operator::Indent
+                } else {
+                    Operator::Dedent
+                });
+                self.mode = VimMode::Normal;
+                EditorAction::ModeChanged(VimMode::Normal)
+            }
             KeyCode::Char('J') => {
                 self.save_undo();
                 let (sr, er) = self.visual_line_range();
@@ -1252,6 +2303,9 @@ impl VimEditor {
     // ── Motion execution ─────────────────────────────────────────────
 
     fn execute_motion(&mut self, motion: Motion, count: usize) {
+        if matches!(motion, Motion::FileTop | Motion::FileBottom) {
+            self.remember_jump();
+        }
         for _ in 0..count {
             match motion {
                 Motion::Left => self.buffer.cursor_left(),
@@ -1470,30 +2524,24 @@ impl VimEditor {
         match op {
             Operator::Delete => {
                 let deleted = self.buffer.delete_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: deleted,
-                    linewise: false,
-                };
+                self.set_register(deleted, false, false);
                 self.buffer.clamp_cursor_col(false);
             }
             Operator::Change => {
                 let deleted = self.buffer.delete_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: deleted,
-                    linewise: false,
-                };
+                self.set_register(deleted, false, false);
                 self.mode = VimMode::Insert;
             }
             Operator::Yank => {
                 let yanked = self.buffer.get_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: yanked,
-                    linewise: false,
-                };
+                self.set_register(yanked, false, true);
                 // Cursor goes to start of yanked range
                 self.buffer.cursor_row = sr;
                 self.buffer.cursor_col = sc;
             }
+            Operator::Format | Operator::Indent | Operator::Dedent => {
+                self.apply_line_operator(op, sr, er)
+            }
         }
     }
 
@@ -1512,41 +2560,28 @@ impl VimEditor {
         match op {
             Operator::Delete => {
                 let deleted = self.buffer.delete_line_range(start, end);
-                self.register = Register {
-                    content: deleted,
-                    linewise: true,
-                };
+                self.set_register(deleted, true, false);
             }
             Operator::Change => {
                 let deleted = self.buffer.delete_line_range(start, end);
-                self.register = Register {
-                    content: deleted,
-                    linewise: true,
-                };
+                self.set_register(deleted, true, false);
                 // Insert a blank line for editing
-                if start >= self.buffer.lines.len() {
-                    self.buffer.lines.push(String::new());
-                    self.buffer.cursor_row = self.buffer.lines.len() - 1;
-                } else {
-                    self.buffer.lines.insert(start, String::new());
-                    self.buffer.cursor_row = start;
-                }
-                self.buffer.cursor_col = 0;
+                self.buffer.open_blank_line(start);
                 self.mode = VimMode::Insert;
             }
             Operator::Yank => {
                 let yanked = self.buffer.get_line_range(start, end);
-                self.register = Register {
-                    content: yanked,
-                    linewise: true,
-                };
+                self.set_register(yanked, true, true);
                 self.buffer.cursor_row = start;
                 self.buffer.cursor_col = 0;
             }
+            Operator::Format | Operator::Indent | Operator::Dedent => {
+                self.apply_line_operator(op, start, end)
+            }
         }
     }
 
-    /// Execute a line-wise operation (dd, yy, cc).
+    /// Execute a line-wise operation (dd, yy, cc, gqq).
     fn execute_line_op(&mut self, op: Operator, count: usize) {
         let cur_row = self.buffer.cursor_row;
         let end_row = (cur_row + count - 1).min(self.buffer.lines.len() - 1);
@@ -1555,35 +2590,41 @@ impl VimEditor {
         match op {
             Operator::Delete => {
                 let deleted = self.buffer.delete_line_range(cur_row, end_row);
-                self.register = Register {
-                    content: deleted,
-                    linewise: true,
-                };
+                self.set_register(deleted, true, false);
             }
             Operator::Yank => {
                 let yanked = self.buffer.get_line_range(cur_row, end_row);
-                self.register = Register {
-                    content: yanked,
-                    linewise: true,
-                };
+                self.set_register(yanked, true, true);
             }
             Operator::Change => {
                 let deleted = self.buffer.delete_line_range(cur_row, end_row);
-                self.register = Register {
-                    content: deleted,
-                    linewise: true,
-                };
+                self.set_register(deleted, true, false);
                 // Insert blank line for editing
-                if cur_row >= self.buffer.lines.len() {
-                    self.buffer.lines.push(String::new());
-                    self.buffer.cursor_row = self.buffer.lines.len() - 1;
-                } else {
-                    self.buffer.lines.insert(cur_row, String::new());
-                    self.buffer.cursor_row = cur_row;
-                }
-                self.buffer.cursor_col = 0;
+                self.buffer.open_blank_line(cur_row);
                 self.mode = VimMode::Insert;
             }
+            Operator::Format => {
+                // The whole paragraph around the lines, not just the lines
+                let (start, end) = self
+                    .compute_text_object(TextObject::InnerParagraph)
+                    .map_or((cur_row, end_row), |(sr, _, er, _)| (sr, er.max(end_row)));
+                self.buffer.reflow_lines(start, end, self.text_width);
+            }
+            Operator::Indent | Operator::Dedent => self.apply_line_operator(op, cur_row, end_row),
+        }
+    }
+
+    /// Run a linewise edit (`Operator::is_linewise_edit`) on rows
+    /// `start..=end`.
+    fn apply_line_operator(&mut self, op: Operator, start: usize, end: usize) {
+        match op {
+            Operator::Format => self.buffer.reflow_lines(start, end, self.text_width),
+            Operator::Indent => {
+                self.buffer
+                    .indent_lines(start, end, self.shift_width, self.expand_tab)
+            }
+            Operator::Dedent => self.buffer.dedent_lines(start, end, self.shift_width),
+            Operator::Delete | Operator::Change | Operator::Yank => {}
         }
     }
 
@@ -1797,29 +2838,23 @@ impl VimEditor {
         match op {
             Operator::Delete => {
                 let deleted = self.buffer.delete_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: deleted,
-                    linewise: false,
-                };
+                self.set_register(deleted, false, false);
                 self.buffer.clamp_cursor_col(false);
             }
             Operator::Change => {
                 let deleted = self.buffer.delete_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: deleted,
-                    linewise: false,
-                };
+                self.set_register(deleted, false, false);
                 self.mode = VimMode::Insert;
             }
             Operator::Yank => {
                 let yanked = self.buffer.get_range(sr, sc, er, ec);
-                self.register = Register {
-                    content: yanked,
-                    linewise: false,
-                };
+                self.set_register(yanked, false, true);
                 self.buffer.cursor_row = sr;
                 self.buffer.cursor_col = sc;
             }
+            Operator::Format | Operator::Indent | Operator::Dedent => {
+                self.apply_line_operator(op, sr, er)
+            }
         }
     }
 
@@ -1863,89 +2898,193 @@ impl VimEditor {
         (ar.min(cr), ar.max(cr))
     }
 
+    /// The visual block as (top row, bottom row, left char col, right char
+    /// col), all inclusive.
+    fn visual_block_bounds(&self) -> (usize, usize, usize, usize) {
+        let lines = &self.buffer.lines;
+        let anchor_col = char_col(&lines[self.visual_anchor_row], self.visual_anchor_col);
+        let cursor_col = char_col(&lines[self.buffer.cursor_row], self.buffer.cursor_col);
+        (
+            self.visual_anchor_row.min(self.buffer.cursor_row),
+            self.visual_anchor_row.max(self.buffer.cursor_row),
+            anchor_col.min(cursor_col),
+            anchor_col.max(cursor_col),
+        )
+    }
+
+    /// Remove (or just copy) the block's text from every selected line.
+    /// Returns the pieces joined with newlines.
+    fn take_block(&mut self, delete: bool) -> String {
+        let (top, bottom, left, right) = self.visual_block_bounds();
+        let mut pieces = Vec::new();
+        for row in top..=bottom {
+            let line = &mut self.buffer.lines[row];
+            let (start, end) = block_byte_range(line, left, right);
+            pieces.push(line[start..end].to_string());
+            if delete {
+                line.drain(start..end);
+            }
+        }
+        if delete {
+            self.buffer.dirty = true;
+        }
+        self.buffer.cursor_row = top;
+        self.buffer.cursor_col = block_byte_range(&self.buffer.lines[top], left, left).0;
+        pieces.join("\n")
+    }
+
+    /// Enter Insert mode at the left edge (`I`) or after the right edge
+    /// (`A`) of the block with `bounds` (see `visual_block_bounds`), on its
+    /// top line.
+    fn start_block_insert(&mut self, bounds: (usize, usize, usize, usize), append: bool) {
+        let (top, bottom, left, right) = bounds;
+        let col = if append { right + 1 } else { left };
+        if append {
+            pad_to_char_col(&mut self.buffer.lines[top], col);
+        }
+        let line = &self.buffer.lines[top];
+        let start_col = block_byte_range(line, col, col).0;
+        self.block_insert = Some(BlockInsert {
+            first_row: top + 1,
+            last_row: bottom,
+            col,
+            pad: append,
+            start_col,
+            start_len: line.len(),
+        });
+        self.buffer.cursor_row = top;
+        self.buffer.cursor_col = start_col;
+        self.mode = VimMode::Insert;
+    }
+
+    /// Copy the text typed during a block insert onto the other lines.
+    fn finish_block_insert(&mut self) {
+        let Some(block) = self.block_insert.take() else {
+            return;
+        };
+        let top = block.first_row - 1;
+        let line = &self.buffer.lines[top];
+        // Only single-line insertions at the block column are repeated
+        if self.buffer.cursor_row != top || line.len() <= block.start_len {
+            return;
+        }
+        let Some(text) = line
+            .get(block.start_col..block.start_col + line.len() - block.start_len)
+            .map(str::to_string)
+        else {
+            return;
+        };
+
+        self.save_undo();
+        for row in block.first_row..=block.last_row.min(self.buffer.lines.len() - 1) {
+            let line = &mut self.buffer.lines[row];
+            if block.pad {
+                pad_to_char_col(line, block.col);
+            } else if line.chars().count() <= block.col {
+                continue;
+            }
+            let at = block_byte_range(line, block.col, block.col).0;
+            line.insert_str(at, &text);
+        }
+    }
+
     fn execute_visual_op(&mut self, op: Operator) {
         self.save_undo();
 
+        if self.mode == VimMode::VisualBlock && !op.is_linewise_edit() {
+            let delete = op != Operator::Yank;
+            let text = self.take_block(delete);
+            self.set_register(text, false, !delete);
+            self.buffer.clamp_cursor_col(op == Operator::Change);
+            return;
+        }
+
         if self.mode == VimMode::VisualLine {
             let (start, end) = self.visual_line_range();
             match op {
                 Operator::Delete | Operator::Change => {
                     let deleted = self.buffer.delete_line_range(start, end);
-                    self.register = Register {
-                        content: deleted,
-                        linewise: true,
-                    };
+                    self.set_register(deleted, true, false);
                     if op == Operator::Change {
-                        if start >= self.buffer.lines.len() {
-                            self.buffer.lines.push(String::new());
-                            self.buffer.cursor_row = self.buffer.lines.len() - 1;
-                        } else {
-                            self.buffer.lines.insert(start, String::new());
-                            self.buffer.cursor_row = start;
-                        }
-                        self.buffer.cursor_col = 0;
+                        self.buffer.open_blank_line(start);
                     }
                 }
                 Operator::Yank => {
                     let yanked = self.buffer.get_line_range(start, end);
-                    self.register = Register {
-                        content: yanked,
-                        linewise: true,
-                    };
+                    self.set_register(yanked, true, true);
                     self.buffer.cursor_row = start;
                     self.buffer.cursor_col = 0;
                 }
+                Operator::Format | Operator::Indent | Operator::Dedent => {
+                    self.apply_line_operator(op, start, end)
+                }
             }
         } else {
             let (sr, sc, er, ec) = self.visual_char_range();
             match op {
                 Operator::Delete | Operator::Change => {
                     let deleted = self.buffer.delete_range(sr, sc, er, ec);
-                    self.register = Register {
-                        content: deleted,
-                        linewise: false,
-                    };
+                    self.set_register(deleted, false, false);
                     self.buffer.clamp_cursor_col(op == Operator::Change);
                 }
                 Operator::Yank => {
                     let yanked = self.buffer.get_range(sr, sc, er, ec);
-                    self.register = Register {
-                        content: yanked,
-                        linewise: false,
-                    };
+                    self.set_register(yanked, false, true);
                     self.buffer.cursor_row = sr;
                     self.buffer.cursor_col = sc;
                 }
+                // Whole lines, also for a block selection
+                Operator::Format | Operator::Indent | Operator::Dedent => {
+                    self.apply_line_operator(op, sr, er)
+                }
             }
         }
     }
 
     // ── Paste ────────────────────────────────────────────────────────
 
-    fn paste_after(&mut self) {
-        if self.register.content.is_empty() {
-            return;
+    /// Register to paste, its content repeated `count` times.
+    fn counted_paste_register(&mut self, count: usize) -> Option<Register> {
+        let mut register = self.paste_register()?;
+        if count > 1 {
+            register.content = if register.linewise {
+                register
+                    .content
+                    .lines()
+                    .collect::<Vec<_>>()
+                    .repeat(count)
+                    .join("\n")
+            } else {
+                register.content.repeat(count)
+            };
         }
-        if self.register.linewise {
-            self.buffer.insert_lines_below(&self.register.content);
+        Some(register)
+    }
+
+    fn paste_after(&mut self, count: usize) {
+        let Some(register) = self.counted_paste_register(count) else {
+            return;
+        };
+        if register.linewise {
+            self.buffer.insert_lines_below(&register.content);
         } else {
             // Paste after cursor position
             self.buffer.cursor_right();
-            self.buffer.insert_text(&self.register.content);
+            self.buffer.insert_text(&register.content);
             if self.buffer.cursor_col > 0 {
                 self.buffer.cursor_left();
             }
         }
     }
 
-    fn paste_before(&mut self) {
-        if self.register.content.is_empty() {
+    fn paste_before(&mut self, count: usize) {
+        let Some(register) = self.counted_paste_register(count) else {
             return;
-        }
-        if self.register.linewise {
-            self.buffer.insert_lines_above(&self.register.content);
+        };
+        if register.linewise {
+            self.buffer.insert_lines_above(&register.content);
         } else {
-            self.buffer.insert_text(&self.register.content);
+            self.buffer.insert_text(&register.content);
             if self.buffer.cursor_col > 0 {
                 self.buffer.cursor_left();
             }
@@ -1954,16 +3093,61 @@ impl VimEditor {
 
     // ── Rendering ────────────────────────────────────────────────────
 
+    /// Width of the line-number gutter: wide enough for the last line
+    /// number plus padding, or 0 when both `nu` and `rnu` are off.
+    pub fn gutter_width(&self) -> u16 {
+        if self.line_numbers || self.relative_numbers {
+            self.buffer.line_count().to_string().len() as u16 + 2 // " N "
+        } else {
+            0
+        }
+    }
+
+    /// Gutter text for buffer line `row`, right-aligned to `gutter_width`.
+    fn gutter_label(&self, row: usize) -> Option<String> {
+        let width = (self.gutter_width() as usize).checked_sub(2)?;
+        let cursor = self.buffer.cursor_row;
+        let number = if self.relative_numbers && row != cursor {
+            row.abs_diff(cursor)
+        } else {
+            row + 1
+        };
+        Some(format!("{number:>width$} "))
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect, focused: bool) {
         if area.height == 0 || area.width == 0 {
             return;
         }
 
+        // The search prompt takes the bottom row while a pattern is typed
+        let mut area = area;
+        if let ParseState::Search {
+            forward, ref query, ..
+        } = self.parse_state
+        {
+            let prompt_area = Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            };
+            area.height -= 1;
+            let prefix = if forward { '/' } else { '?' };
+            frame.render_widget(Paragraph::new(format!("{prefix}{query}")), prompt_area);
+            if focused {
+                let x = prompt_area.x + 1 + query.chars().count() as u16;
+                if x < prompt_area.x + prompt_area.width {
+                    frame.set_cursor_position((x, prompt_area.y));
+                }
+            }
+            if area.height == 0 {
+                return;
+            }
+        }
+
         self.visible_height.set(area.height as usize);
 
-        // Line number gutter width (relative line numbers)
-        let max_line_num = self.buffer.line_count();
-        let gutter_width: u16 = format!("{}", max_line_num).len() as u16 + 2; // " N "
+        let gutter_width = self.gutter_width();
         let text_area = Rect {
             x: area.x + gutter_width,
             width: area.width.saturating_sub(gutter_width),
@@ -1975,17 +3159,26 @@ impl VimEditor {
         };
 
         let visible_lines = area.height as usize;
-
-        // Scrolling: keep cursor vertically centered (like vim scrolloff=999).
-        // The cursor row sits at the middle of the viewport when possible.
-        let scroll_offset = if visible_lines == 0 {
-            0
-        } else {
-            let half = visible_lines / 2;
-            self.buffer.cursor_row.saturating_sub(half)
-        };
+        let wrap_width = self.wrap_width(text_area.width);
+
+        // Screen rows: (buffer row, first character column) for each row
+        let mut view_rows = Vec::with_capacity(visible_lines);
+        let (mut row, mut skip) = self.scroll_position(visible_lines, wrap_width);
+        while view_rows.len() < visible_lines && row < self.buffer.line_count() {
+            let starts = self.display_starts(row, wrap_width);
+            for &start in starts.iter().skip(skip) {
+                if view_rows.len() == visible_lines {
+                    break;
+                }
+                view_rows.push((row, start));
+            }
+            skip = 0;
+            row += 1;
+        }
+        self.last_view.set(text_area);
 
         // Visual selection range
+        let block = (self.mode == VimMode::VisualBlock).then(|| self.visual_block_bounds());
         let visual_range = match self.mode {
             VimMode::Visual => Some(self.visual_char_range()),
             VimMode::VisualLine => {
@@ -1995,70 +3188,160 @@ impl VimEditor {
             _ => None,
         };
 
+        // Matches of the pattern being typed, or of the last search
+        let highlight = match self.parse_state {
+            ParseState::Search { ref query, .. } => Some(query.as_str()),
+            _ => self.last_search.as_deref(),
+        }
+        .filter(|p| !p.is_empty());
+
         let mut gutter_lines: Vec<Line> = Vec::new();
         let mut text_lines: Vec<Line> = Vec::new();
 
-        for i in scroll_offset..self.buffer.line_count().min(scroll_offset + visible_lines) {
+        let mut full_line: Option<(usize, Line<'static>)> = None;
+        for (index, &(i, start)) in view_rows.iter().enumerate() {
+            // Characters up to the next display line of the same buffer line
+            let len = match view_rows.get(index + 1) {
+                Some(&(next, next_start)) if next == i => next_start - start,
+                _ => usize::MAX,
+            };
             let is_current = i == self.buffer.cursor_row;
 
-            // Relative line numbers
-            let line_num_display = if is_current {
-                format!("{:>width$} ", i + 1, width = gutter_width as usize - 2)
-            } else {
-                let rel = if i > self.buffer.cursor_row {
-                    i - self.buffer.cursor_row
+            if start > 0 {
+                // Continuation of a wrapped line: blank gutter
+                if gutter_width > 0 {
+                    gutter_lines.push(Line::from(" ".repeat(gutter_width as usize)));
+                }
+            } else if let Some(label) = self.gutter_label(i) {
+                let gutter_style = if is_current && focused {
+                    Style::default().fg(theme().accent)
                 } else {
-                    self.buffer.cursor_row - i
+                    Style::default().fg(theme().muted)
                 };
-                format!("{:>width$} ", rel, width = gutter_width as usize - 2)
-            };
+                gutter_lines.push(Line::from(Span::styled(label, gutter_style)));
+            }
 
-            let gutter_style = if is_current && focused {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::DarkGray)
-            };
-            gutter_lines.push(Line::from(Span::styled(line_num_display, gutter_style)));
+            if let Some((row, ref line)) = full_line
+                && row == i
+            {
+                text_lines.push(slice_line(line, start, len));
+                continue;
+            }
 
             // Text content with visual selection highlighting
             let line_text = &self.buffer.lines[i];
-            if let Some((vsr, vsc, ver, vec_)) = visual_range {
-                let line = render_line_with_selection(
-                    line_text, i, vsr, vsc, ver, vec_, is_current, focused,
-                );
-                text_lines.push(line);
+            let line = if let Some((top, bottom, left, right)) = block {
+                // Each block line is its own single-line selection
+                let (block_start, block_end) = if (top..=bottom).contains(&i) {
+                    block_byte_range(line_text, left, right)
+                } else {
+                    (0, 0)
+                };
+                render_line_with_selection(
+                    line_text,
+                    i,
+                    i,
+                    block_start,
+                    i,
+                    block_end,
+                    is_current,
+                    focused,
+                )
+            } else if let Some((vsr, vsc, ver, vec_)) = visual_range {
+                render_line_with_selection(line_text, i, vsr, vsc, ver, vec_, is_current, focused)
             } else {
                 let text_style = if is_current && focused {
-                    Style::default().fg(Color::White)
+                    Style::default().fg(theme().text)
                 } else {
-                    Style::default().fg(Color::Gray)
+                    Style::default()
                 };
-                text_lines.push(Line::from(Span::styled(line_text.clone(), text_style)));
-            }
+                render_line_with_matches(line_text, highlight, text_style)
+            };
+            text_lines.push(slice_line(&line, start, len));
+            full_line = Some((i, line));
         }
 
         // Fill remaining lines with ~ (like vim)
-        for _ in self.buffer.line_count().saturating_sub(scroll_offset)..visible_lines {
-            gutter_lines.push(Line::from(Span::styled(
-                format!("{:>width$} ", "~", width = gutter_width as usize - 2),
-                Style::default().fg(Color::DarkGray),
-            )));
+        for _ in view_rows.len()..visible_lines {
+            if gutter_width > 0 {
+                gutter_lines.push(Line::from(Span::styled(
+                    format!("{:>width$} ", "~", width = gutter_width as usize - 2),
+                    Style::default().fg(theme().muted),
+                )));
+            }
             text_lines.push(Line::from(""));
         }
 
         frame.render_widget(Paragraph::new(gutter_lines), gutter_area);
         frame.render_widget(Paragraph::new(text_lines), text_area);
 
-        // Show cursor
-        if focused {
-            let visible_row = self.buffer.cursor_row.saturating_sub(scroll_offset);
-            let cursor_x = text_area.x + self.buffer.cursor_col as u16;
-            let cursor_y = text_area.y + visible_row as u16;
-            if cursor_x < text_area.x + text_area.width && cursor_y < text_area.y + text_area.height
-            {
-                frame.set_cursor_position((cursor_x, cursor_y));
+        // Show cursor (the search prompt owns it while typing a pattern)
+        if focused && !self.is_searching() {
+            let row = self.buffer.cursor_row;
+            let col = char_col(&self.buffer.lines[row], self.buffer.cursor_col);
+            let screen_row = view_rows
+                .iter()
+                .rposition(|&(r, start)| r == row && start <= col);
+            if let Some(screen_row) = screen_row {
+                let start = view_rows[screen_row].1;
+                let cells = cell_width(&self.buffer.lines[row], start, col);
+                let cursor_x = text_area.x + cells as u16;
+                let cursor_y = text_area.y + screen_row as u16;
+                if cursor_x < text_area.x + text_area.width {
+                    frame.set_cursor_position((cursor_x, cursor_y));
+                }
             }
         }
+        *self.view_rows.borrow_mut() = view_rows;
+    }
+
+    /// Screen cells per display line: the text width when wrapping,
+    /// unlimited otherwise.
+    fn wrap_width(&self, text_width: u16) -> usize {
+        if self.wrap {
+            (text_width as usize).max(1)
+        } else {
+            usize::MAX
+        }
+    }
+
+    /// First character of each display line of buffer line `row`. The
+    /// cursor line counts the Insert-mode cursor past its end, which may
+    /// need a row of its own.
+    fn display_starts(&self, row: usize, wrap_width: usize) -> Vec<usize> {
+        let line = &self.buffer.lines[row];
+        let cursor_past_end = row == self.buffer.cursor_row && self.buffer.cursor_col >= line.len();
+        wrap_starts(line, wrap_width, cursor_past_end)
+    }
+
+    /// Index of the display line of buffer line `row` holding character `col`.
+    fn display_row_of(&self, row: usize, col: usize, wrap_width: usize) -> usize {
+        let starts = self.display_starts(row, wrap_width);
+        starts.iter().rposition(|&start| start <= col).unwrap_or(0)
+    }
+
+    /// First buffer row shown and how many of its display lines are
+    /// scrolled off, keeping the cursor vertically centered (like vim
+    /// scrolloff=999).
+    fn scroll_position(&self, visible_lines: usize, wrap_width: usize) -> (usize, usize) {
+        let half = visible_lines / 2;
+        let row = self.buffer.cursor_row;
+        let col = char_col(&self.buffer.lines[row], self.buffer.cursor_col);
+        let cursor_sub = self.display_row_of(row, col, wrap_width);
+        if cursor_sub >= half {
+            return (row, cursor_sub - half);
+        }
+        let mut above = half - cursor_sub;
+        let mut row = row;
+        while above > 0 && row > 0 {
+            row -= 1;
+            let rows = self.display_starts(row, wrap_width).len();
+            if rows >= above {
+                return (row, rows - above);
+            }
+            above -= rows;
+        }
+        (row, 0)
     }
 }
 
@@ -2077,6 +3360,169 @@ fn offset_to_pos(lines: &[String], offset: usize) -> (usize, usize) {
     (last, lines[last].len())
 }
 
+/// Character column of byte offset `col` in `line`.
+fn char_col(line: &str, col: usize) -> usize {
+    line.get(..col)
+        .map_or(line.chars().count(), |s| s.chars().count())
+}
+
+/// Screen cells taken by characters `start..end` of `line`; a missing
+/// character (the Insert cursor past the end) counts as one cell.
+fn cell_width(line: &str, start: usize, end: usize) -> usize {
+    let mut chars = line.chars().skip(start);
+    (start..end)
+        .map(|_| chars.next().map_or(1, |c| c.width().unwrap_or(0)))
+        .sum()
+}
+
+/// First character of each display line when `line` is wrapped at
+/// `width` screen cells. `cursor_past_end` reserves one more cell for
+/// the Insert cursor after the last character.
+fn wrap_starts(line: &str, width: usize, cursor_past_end: bool) -> Vec<usize> {
+    let mut starts = vec![0];
+    if width == usize::MAX {
+        return starts;
+    }
+    let mut used = 0;
+    let cells = line.chars().map(|c| c.width().unwrap_or(0));
+    let cursor = cursor_past_end.then_some(1);
+    for (i, cell) in cells.chain(cursor).enumerate() {
+        if used + cell > width && used > 0 {
+            starts.push(i);
+            used = 0;
+        }
+        used += cell;
+    }
+    starts
+}
+
+/// Character covering screen cell `cells` when `line` is drawn from
+/// character `start`, or `None` past the end of the line.
+fn char_at_cell(line: &str, start: usize, cells: usize) -> Option<usize> {
+    let mut used = 0;
+    for (i, c) in line.chars().enumerate().skip(start) {
+        used += c.width().unwrap_or(0);
+        if used > cells {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Byte range of character columns `left..=right` in `line`, clamped to
+/// the line's end.
+fn block_byte_range(line: &str, left: usize, right: usize) -> (usize, usize) {
+    let byte_at = |col: usize| line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+    (byte_at(left), byte_at(right + 1))
+}
+
+/// Pad `line` with spaces so it is at least `col` characters long.
+fn pad_to_char_col(line: &mut String, col: usize) {
+    let len = line.chars().count();
+    if len < col {
+        line.extend(std::iter::repeat_n(' ', col - len));
+    }
+}
+
+/// Find the first occurrence of `pattern` strictly after (`forward`) or
+/// before `from`, wrapping around the end of the buffer. Returns the
+/// (row, byte col) of the match.
+fn find_match(
+    lines: &[String],
+    pattern: &str,
+    from: (usize, usize),
+    forward: bool,
+) -> Option<(usize, usize)> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut matches = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| line.match_indices(pattern).map(move |(col, _)| (row, col)));
+    if forward {
+        let all: Vec<_> = matches.collect();
+        all.iter().find(|&&pos| pos > from).or(all.first()).copied()
+    } else {
+        let mut before = None;
+        let mut last = None;
+        for pos in matches.by_ref() {
+            if pos < from {
+                before = Some(pos);
+            }
+            last = Some(pos);
+        }
+        before.or(last)
+    }
+}
+
+/// Closing half of an auto-paired opening character.
+fn pair_close(c: char) -> Option<char> {
+    match c {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        _ => None,
+    }
+}
+
+fn is_pair_close(c: char) -> bool {
+    matches!(c, ')' | ']' | '}' | '"' | '\'')
+}
+
+/// Characters `start..start + len` of a rendered line, keeping styles.
+fn slice_line(line: &Line<'static>, start: usize, len: usize) -> Line<'static> {
+    if start == 0 && len == usize::MAX {
+        return line.clone();
+    }
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in &line.spans {
+        let count = span.content.chars().count();
+        let from = start.saturating_sub(offset).min(count);
+        let to = start.saturating_add(len).saturating_sub(offset).min(count);
+        if from < to {
+            let text: String = span.content.chars().skip(from).take(to - from).collect();
+            spans.push(Span::styled(text, span.style));
+        }
+        offset += count;
+    }
+    Line::from(spans)
+}
+
+/// Render a line with every occurrence of `pattern` highlighted.
+fn render_line_with_matches(
+    line_text: &str,
+    pattern: Option<&str>,
+    normal_style: Style,
+) -> Line<'static> {
+    let Some(pattern) = pattern else {
+        return Line::from(Span::styled(line_text.to_string(), normal_style));
+    };
+    let match_style = Style::default()
+        .bg(theme().accent)
+        .fg(contrast_fg(theme().accent));
+
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, matched) in line_text.match_indices(pattern) {
+        if start > last {
+            spans.push(Span::styled(
+                line_text[last..start].to_string(),
+                normal_style,
+            ));
+        }
+        spans.push(Span::styled(matched.to_string(), match_style));
+        last = start + matched.len();
+    }
+    if last < line_text.len() || spans.is_empty() {
+        spans.push(Span::styled(line_text[last..].to_string(), normal_style));
+    }
+    Line::from(spans)
+}
+
 /// Render a line with visual selection highlighting.
 fn render_line_with_selection(
     line_text: &str,
@@ -2089,13 +3535,13 @@ fn render_line_with_selection(
     focused: bool,
 ) -> Line<'static> {
     let normal_style = if is_current && focused {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme().text)
     } else {
-        Style::default().fg(Color::Gray)
+        Style::default()
     };
     let selected_style = Style::default()
-        .bg(Color::Rgb(68, 68, 120))
-        .fg(Color::White);
+        .bg(theme().selection_bg)
+        .fg(contrast_fg(theme().selection_bg));
 
     // Determine selection range on this line
     let (sel_start, sel_end) = if line_row < sel_start_row || line_row > sel_end_row {
@@ -2160,6 +3606,28 @@ mod tests {
         KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)
     }
 
+    #[test]
+    fn test_pending_keys() {
+        let mut ed = VimEditor::new();
+        ed.set_text("one two three");
+        assert_eq!(ed.pending_keys(), "");
+        ed.handle_key(key('"'));
+        assert_eq!(ed.pending_keys(), "\"");
+        ed.handle_key(key('a'));
+        ed.handle_key(key('2'));
+        ed.handle_key(key('d'));
+        assert_eq!(ed.pending_keys(), "\"a2d");
+        ed.handle_key(key('t'));
+        assert_eq!(ed.pending_keys(), "\"a2dt");
+        ed.handle_key(key('e'));
+        assert_eq!(ed.pending_keys(), "");
+
+        ed.handle_key(key('g'));
+        assert_eq!(ed.pending_keys(), "g");
+        ed.handle_key(esc());
+        assert_eq!(ed.pending_keys(), "");
+    }
+
     #[test]
     fn test_basic_insert() {
         let mut ed = VimEditor::new();
@@ -2217,14 +3685,346 @@ mod tests {
         assert_eq!(ed.text(), "hello ");
     }
 
+    #[test]
+    fn test_counted_paste_and_open() {
+        let mut ed = VimEditor::from_text("ab\nend");
+        let keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+        keys(&mut ed, "yl3p");
+        assert_eq!(ed.text(), "aaaab\nend");
+        keys(&mut ed, "0yy2P");
+        assert_eq!(ed.text(), "aaaab\naaaab\naaaab\nend");
+        keys(&mut ed, "u");
+        assert_eq!(ed.text(), "aaaab\nend");
+
+        // `2o` types the Insert session twice; `.` repeats it with the count
+        keys(&mut ed, "2o-x");
+        ed.handle_key(esc());
+        assert_eq!(ed.text(), "aaaab\n-x\n-x\nend");
+        assert_eq!(ed.buffer.cursor_row, 2);
+        keys(&mut ed, "G.");
+        assert_eq!(ed.text(), "aaaab\n-x\n-x\nend\n-x\n-x");
+        keys(&mut ed, "gg2Oy");
+        ed.handle_key(esc());
+        assert_eq!(ed.text(), "y\ny\naaaab\n-x\n-x\nend\n-x\n-x");
+    }
+
+    #[test]
+    fn test_indent_operators() {
+        let mut ed = VimEditor::from_text("a\nb\nc");
+        let keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+        keys(&mut ed, ">>");
+        assert_eq!(ed.text(), "    a\nb\nc");
+        keys(&mut ed, "2>>");
+        assert_eq!(ed.text(), "        a\n    b\nc");
+        keys(&mut ed, "<j");
+        assert_eq!(ed.text(), "    a\nb\nc");
+
+        // Visual selection, `.` and tabs
+        keys(&mut ed, "Vj>");
+        assert_eq!(ed.text(), "        a\n    b\nc");
+        keys(&mut ed, "G.");
+        assert_eq!(ed.text(), "        a\n    b\n    c");
+        ed.expand_tab = false;
+        keys(&mut ed, ">G");
+        assert_eq!(ed.text(), "        a\n    b\n\t    c");
+        keys(&mut ed, "<<<<<<");
+        assert_eq!(ed.text(), "        a\n    b\nc");
+    }
+
+    #[test]
+    fn test_auto_pairs() {
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+        let mut ed = VimEditor::new();
+        ed.handle_key(key('i'));
+        for c in "f(x".chars() {
+            ed.handle_key(key(c));
+        }
+        // Off by default
+        assert_eq!(ed.text(), "f(x");
+
+        ed.set_text("");
+        ed.auto_pairs = true;
+        ed.handle_key(key('i'));
+        for c in "f(a, [\"b\"])".chars() {
+            ed.handle_key(key(c));
+        }
+        assert_eq!(ed.text(), "f(a, [\"b\"])");
+        assert_eq!(ed.buffer.cursor_col, ed.text().len());
+
+        // Backspace in an empty pair removes both halves
+        ed.handle_key(key('{'));
+        assert_eq!(ed.text(), "f(a, [\"b\"]){}");
+        ed.handle_key(backspace);
+        assert_eq!(ed.text(), "f(a, [\"b\"])");
+
+        // No pair for an apostrophe inside a word
+        ed.set_text("");
+        ed.handle_key(key('i'));
+        for c in "don't".chars() {
+            ed.handle_key(key(c));
+        }
+        assert_eq!(ed.text(), "don't");
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut ed = VimEditor::from_text("one\n  two x\nthree\nfour");
+        let keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+        let cursor = |ed: &VimEditor| (ed.buffer.cursor_row, ed.buffer.cursor_col);
+
+        keys(&mut ed, "j$ma");
+        assert_eq!(ed.pending_keys(), "");
+        keys(&mut ed, "gg`a");
+        assert_eq!(cursor(&ed), (1, 6));
+        keys(&mut ed, "gg'a");
+        assert_eq!(cursor(&ed), (1, 2));
+
+        // `` returns to where the last jump started
+        keys(&mut ed, "G``");
+        assert_eq!(cursor(&ed), (1, 2));
+        keys(&mut ed, "``");
+        assert_eq!(cursor(&ed), (3, 0));
+
+        // Marks follow their line; a deleted line takes its marks along
+        keys(&mut ed, "ggOzero");
+        ed.handle_key(esc());
+        keys(&mut ed, "`a");
+        assert_eq!(cursor(&ed), (2, 6));
+        keys(&mut ed, "jmbkdd`b");
+        assert_eq!(cursor(&ed), (2, 4));
+        keys(&mut ed, "gg`a");
+        assert_eq!(cursor(&ed), (0, 0));
+        // Undo brings the line back, but not its marks
+        keys(&mut ed, "u");
+        let restored = cursor(&ed);
+        keys(&mut ed, "gg`a");
+        assert_eq!(cursor(&ed), (0, 0));
+        assert_eq!(ed.buffer.lines[restored.0], "  two x");
+    }
+
+    #[test]
+    fn test_replace_mode() {
+        let mut ed = VimEditor::from_text("abc\nabc");
+        assert_eq!(
+            ed.handle_key(key('R')),
+            EditorAction::ModeChanged(VimMode::Replace)
+        );
+        for c in "xyzw".chars() {
+            ed.handle_key(key(c));
+        }
+        assert_eq!(ed.text(), "xyzw\nabc");
+
+        // Keys with Ctrl or Alt don't overtype
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        ed.handle_key(ctrl_w);
+        assert_eq!(ed.text(), "xyzw\nabc");
+
+        // Backspace restores what was overtyped, then only moves
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+        for _ in 0..3 {
+            ed.handle_key(backspace);
+        }
+        assert_eq!(ed.text(), "xbc\nabc");
+        ed.handle_key(key('y'));
+        ed.handle_key(esc());
+        assert_eq!(ed.mode, VimMode::Normal);
+        assert_eq!(ed.text(), "xyc\nabc");
+
+        // `.` repeats the session, `u` undoes it in one step
+        ed.handle_key(key('j'));
+        ed.handle_key(key('0'));
+        ed.handle_key(key('.'));
+        assert_eq!(ed.text(), "xyc\nxyc");
+        ed.handle_key(key('u'));
+        assert_eq!(ed.text(), "xyc\nabc");
+        ed.handle_key(key('u'));
+        assert_eq!(ed.text(), "abc\nabc");
+    }
+
+    #[test]
+    fn test_format_operator() {
+        let text = "aa bb\ncc dd ee\n\nff gg hh";
+        let mut ed = VimEditor::from_text(text);
+        ed.text_width = 8;
+        ed.handle_key(key('g'));
+        ed.handle_key(key('q'));
+        assert_eq!(ed.pending_keys(), "gq");
+
+        // gqq formats the whole paragraph, as one undo step
+        ed.handle_key(key('q'));
+        assert_eq!(ed.text(), "aa bb cc\ndd ee\n\nff gg hh");
+        ed.handle_key(key('u'));
+        assert_eq!(ed.text(), text);
+
+        // gqip / Visual gq
+        ed.text_width = 5;
+        for c in ['G', 'g', 'q', 'i', 'p'] {
+            ed.handle_key(key(c));
+        }
+        assert_eq!(ed.text(), "aa bb\ncc dd ee\n\nff gg\nhh");
+        for c in ['g', 'g', 'V', 'j', 'g', 'q'] {
+            ed.handle_key(key(c));
+        }
+        assert_eq!(ed.mode, VimMode::Normal);
+        assert_eq!(ed.text(), "aa bb\ncc dd\nee\n\nff gg\nhh");
+    }
+
+    #[test]
+    fn test_soft_wrap() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut ed = VimEditor::from_text("abcdefghij klm\nxy");
+        ed.line_numbers = false;
+        ed.relative_numbers = false;
+        ed.wrap = true;
+        let mut terminal = Terminal::new(TestBackend::new(5, 4)).unwrap();
+        let mut draw = |ed: &VimEditor| {
+            terminal
+                .draw(|frame| ed.render(frame, frame.area(), true))
+                .unwrap()
+                .buffer
+                .content()
+                .chunks(5)
+                .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(&ed), ["abcde", "fghij", " klm ", "xy   "]);
+
+        // Clicks map through the wrapped rows
+        assert_eq!(ed.position_at(1, 1), Some((0, 6)));
+
+        // gj / gk move by display line, j / k by buffer line
+        ed.handle_key(key('g'));
+        ed.handle_key(key('j'));
+        assert_eq!(ed.buffer.cursor_col, 5);
+        ed.handle_key(key('2'));
+        ed.handle_key(key('g'));
+        ed.handle_key(key('j'));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (1, 0));
+        ed.handle_key(key('g'));
+        ed.handle_key(key('k'));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (0, 10));
+        ed.handle_key(key('k'));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (0, 10));
+        ed.handle_key(key('j'));
+        assert_eq!(ed.buffer.cursor_row, 1);
+
+        ed.wrap = false;
+        assert_eq!(draw(&ed)[..2], ["abcde", "xy   "]);
+    }
+
+    #[test]
+    fn test_soft_wrap_wide_characters() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut ed = VimEditor::from_text("日本語ab");
+        ed.line_numbers = false;
+        ed.relative_numbers = false;
+        ed.wrap = true;
+        let mut terminal = Terminal::new(TestBackend::new(5, 3)).unwrap();
+        let mut draw = |ed: &VimEditor| {
+            terminal
+                .draw(|frame| ed.render(frame, frame.area(), true))
+                .unwrap();
+            let cursor = terminal.get_cursor_position().unwrap();
+            ((cursor.x, cursor.y), terminal.backend().buffer().clone())
+        };
+
+        // A wide character that doesn't fit starts the next display line
+        let (_, buffer) = draw(&ed);
+        assert_eq!(buffer[(2, 0)].symbol(), "本");
+        assert_eq!(buffer[(4, 0)].symbol(), " ");
+        assert_eq!(buffer[(0, 1)].symbol(), "語");
+        assert_eq!(*ed.view_rows.borrow(), [(0, 0), (0, 2)]);
+
+        // Clicks past the first display line stay on it
+        assert_eq!(ed.position_at(4, 0), Some((0, 3)));
+        assert_eq!(ed.position_at(2, 1), Some((0, 9)));
+
+        // gj keeps the screen column: from 本 (cells 2-3) to a (cell 2)
+        ed.handle_key(key('l'));
+        ed.handle_key(key('g'));
+        ed.handle_key(key('j'));
+        assert_eq!(ed.buffer.cursor_col, 9);
+        assert_eq!(draw(&ed).0, (2, 1));
+    }
+
+    #[test]
+    fn test_mouse_selection() {
+        let mut ed = VimEditor::from_text("hello world\nfoo");
+        ed.last_view.set(Rect::new(2, 1, 20, 5));
+        *ed.view_rows.borrow_mut() = vec![(0, 0), (1, 0)];
+
+        // Click moves the cursor; outside the text area is ignored
+        assert!(ed.mouse_down(8, 1));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (0, 6));
+        assert!(!ed.mouse_down(0, 1));
+        ed.mouse_up();
+
+        // Drag selects, then `y` yanks the selection
+        ed.mouse_down(2, 1);
+        ed.mouse_drag(6, 1);
+        assert_eq!(ed.mode, VimMode::Visual);
+        ed.mouse_up();
+        ed.handle_key(key('y'));
+        assert_eq!(ed.registers.unnamed.content, "hello");
+
+        // Double click selects the word under the cursor
+        ed.mouse_down(10, 1);
+        ed.mouse_up();
+        ed.mouse_down(10, 1);
+        assert_eq!(ed.mode, VimMode::Visual);
+        ed.handle_key(key('d'));
+        assert_eq!(ed.text(), "hello \nfoo");
+    }
+
+    #[test]
+    fn test_mouse_on_wide_characters() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut ed = VimEditor::from_text("日本語 ok");
+        ed.last_view.set(Rect::new(0, 0, 20, 5));
+        *ed.view_rows.borrow_mut() = vec![(0, 0)];
+
+        // Both cells of a wide character map to it
+        assert_eq!(ed.position_at(2, 0), Some((0, 3)));
+        assert_eq!(ed.position_at(3, 0), Some((0, 3)));
+        assert_eq!(ed.position_at(7, 0), Some((0, 10)));
+        // Past the end clamps to the last character
+        assert_eq!(ed.position_at(15, 0), Some((0, 11)));
+
+        // The cursor is drawn on the clicked character
+        ed.line_numbers = false;
+        ed.relative_numbers = false;
+        ed.mouse_down(7, 0);
+        let mut terminal = Terminal::new(TestBackend::new(20, 2)).unwrap();
+        terminal
+            .draw(|frame| ed.render(frame, frame.area(), true))
+            .unwrap();
+        let cursor = terminal.get_cursor_position().unwrap();
+        assert_eq!((cursor.x, cursor.y), (7, 0));
+    }
+
     #[test]
     fn test_yank_and_paste() {
         let mut ed = VimEditor::from_text("hello\nworld");
         // yy (yank line)
         ed.handle_key(key('y'));
         ed.handle_key(key('y'));
-        assert!(ed.register.linewise);
-        assert_eq!(ed.register.content, "hello");
+        assert!(ed.registers.unnamed.linewise);
+        assert_eq!(ed.registers.unnamed.content, "hello");
         // p (paste below)
         ed.handle_key(key('p'));
         assert_eq!(ed.text(), "hello\nhello\nworld");
@@ -2262,6 +4062,207 @@ mod tests {
         assert_eq!(ed.text(), "");
     }
 
+    #[test]
+    fn test_substitute_is_undoable() {
+        let mut ed = VimEditor::from_text("foo foo\nfoo");
+        assert_eq!(ed.substitute("foo", "bar", false, false), 1);
+        assert_eq!(ed.text(), "bar foo\nfoo");
+        assert_eq!(ed.substitute("foo", "bar", true, true), 2);
+        assert_eq!(ed.text(), "bar bar\nbar");
+        assert_eq!(ed.substitute("nope", "x", true, true), 0);
+
+        ed.handle_key(key('u'));
+        assert_eq!(ed.text(), "bar foo\nfoo");
+        ed.handle_key(key('u'));
+        assert_eq!(ed.text(), "foo foo\nfoo");
+    }
+
+    #[test]
+    fn test_incremental_search() {
+        let mut ed = VimEditor::from_text("alpha\nbeta alpha\ngamma");
+        ed.handle_key(key('/'));
+        assert!(ed.is_searching());
+        ed.handle_key(key('a'));
+        ed.handle_key(key('l'));
+        // Moves as the pattern is typed
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (1, 5));
+        ed.handle_key(enter());
+        assert!(!ed.is_searching());
+
+        // n wraps around, N goes back
+        ed.handle_key(key('n'));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (0, 0));
+        ed.handle_key(key('N'));
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (1, 5));
+
+        // ? searches backward; Esc restores the cursor
+        ed.handle_key(key('?'));
+        ed.handle_key(key('g'));
+        assert_eq!(ed.buffer.cursor_row, 2);
+        ed.handle_key(esc());
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (1, 5));
+        assert_eq!(ed.last_search.as_deref(), Some("al"));
+
+        // A pattern without matches leaves the cursor in place
+        ed.handle_key(key('/'));
+        ed.handle_key(key('z'));
+        ed.handle_key(enter());
+        assert_eq!((ed.buffer.cursor_row, ed.buffer.cursor_col), (1, 5));
+    }
+
+    #[test]
+    fn test_named_registers_and_yank_ring() {
+        let mut ed = VimEditor::from_text("one\ntwo\nthree");
+        let keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+        // "a gets "one" (not the ring); then "two" and "three" are yanked
+        keys(&mut ed, "\"ayyjyyjyy");
+        // Deleting "two" only touches the unnamed register
+        keys(&mut ed, "kdd");
+        assert_eq!(ed.text(), "one\nthree");
+
+        keys(&mut ed, "\"ap");
+        assert_eq!(ed.text(), "one\nthree\none");
+        // "1 is the yank before the last one, "0 the last one
+        keys(&mut ed, "\"1p");
+        assert_eq!(ed.text(), "one\nthree\none\ntwo");
+        keys(&mut ed, "\"0p");
+        assert_eq!(ed.text(), "one\nthree\none\ntwo\nthree");
+        // The prefix is used up: plain p pastes the unnamed register (the dd)
+        keys(&mut ed, "p");
+        assert_eq!(ed.text(), "one\nthree\none\ntwo\nthree\ntwo");
+
+        // An empty named register pastes nothing
+        keys(&mut ed, "\"qp");
+        assert_eq!(ed.buffer.line_count(), 6);
+    }
+
+    #[test]
+    fn test_dot_repeat() {
+        let type_keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+
+        let mut ed = VimEditor::from_text("a\nb\nc\nd");
+        type_keys(&mut ed, "dd.");
+        assert_eq!(ed.text(), "c\nd");
+
+        let mut ed = VimEditor::from_text("one two three four");
+        type_keys(&mut ed, "dw");
+        // Motions in between are not changes
+        type_keys(&mut ed, "w.");
+        assert_eq!(ed.text(), "two four");
+
+        let mut ed = VimEditor::from_text("abcd");
+        type_keys(&mut ed, "x..");
+        assert_eq!(ed.text(), "d");
+
+        // cw + typed text is replayed with the text
+        let mut ed = VimEditor::from_text("foo bar baz");
+        type_keys(&mut ed, "cwqux ");
+        ed.handle_key(esc());
+        type_keys(&mut ed, "ww.");
+        assert_eq!(ed.text(), "qux bar qux ");
+        assert_eq!(ed.mode, VimMode::Normal);
+
+        // o + text, then a yank does not replace the last change
+        let mut ed = VimEditor::from_text("x");
+        type_keys(&mut ed, "oy");
+        ed.handle_key(esc());
+        type_keys(&mut ed, "yy.");
+        assert_eq!(ed.text(), "x\ny\ny");
+    }
+
+    #[test]
+    fn test_macros() {
+        let type_keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+
+        // Record "append ; and go down", then replay with a count and @@
+        let mut ed = VimEditor::from_text("a\nb\nc\nd\ne");
+        type_keys(&mut ed, "qaA;");
+        ed.handle_key(esc());
+        type_keys(&mut ed, "jq");
+        assert_eq!(ed.recording_macro(), None);
+        type_keys(&mut ed, "2@a");
+        type_keys(&mut ed, "@@");
+        assert_eq!(ed.text(), "a;\nb;\nc;\nd;\ne");
+        assert_eq!(ed.mode, VimMode::Normal);
+
+        // A macro calling itself stops instead of recursing forever
+        let mut ed = VimEditor::from_text("abcdef");
+        type_keys(&mut ed, "qbx@bq");
+        type_keys(&mut ed, "@b");
+        assert_eq!(ed.text(), "cdef");
+
+        // Unknown registers do nothing
+        type_keys(&mut ed, "@z");
+        assert_eq!(ed.text(), "cdef");
+    }
+
+    #[test]
+    fn test_visual_block() {
+        let type_keys = |ed: &mut VimEditor, keys: &str| {
+            for c in keys.chars() {
+                ed.handle_key(key(c));
+            }
+        };
+
+        // Delete a 2x2 block
+        let mut ed = VimEditor::from_text("abcd\nefgh\nijkl");
+        ed.handle_key(key('l'));
+        ed.handle_key(ctrl('v'));
+        assert_eq!(ed.mode, VimMode::VisualBlock);
+        type_keys(&mut ed, "jld");
+        assert_eq!(ed.text(), "ad\neh\nijkl");
+        assert_eq!(ed.mode, VimMode::Normal);
+        assert_eq!(ed.registers.unnamed.content, "bc\nfg");
+
+        // Yank leaves the text alone
+        let mut ed = VimEditor::from_text("abcd\nefgh");
+        ed.handle_key(ctrl('v'));
+        type_keys(&mut ed, "jy");
+        assert_eq!(ed.registers.unnamed.content, "a\ne");
+        assert_eq!(ed.text(), "abcd\nefgh");
+
+        // I inserts on every line, skipping lines that are too short
+        let mut ed = VimEditor::from_text("| a\n|\n| c");
+        type_keys(&mut ed, "l");
+        ed.handle_key(ctrl('v'));
+        type_keys(&mut ed, "jjI> ");
+        ed.handle_key(esc());
+        assert_eq!(ed.text(), "|>  a\n|\n|>  c");
+
+        // A appends after the block, padding short lines
+        let mut ed = VimEditor::from_text("ab\na\nab");
+        type_keys(&mut ed, "l");
+        ed.handle_key(ctrl('v'));
+        type_keys(&mut ed, "jjA|");
+        ed.handle_key(esc());
+        assert_eq!(ed.text(), "ab|\na |\nab|");
+        assert_eq!(ed.mode, VimMode::Normal);
+
+        // c replaces the block on every line, whichever corner the cursor is on
+        for (start, select) in [("l", "jjl"), ("jjll", "kkh")] {
+            let mut ed = VimEditor::from_text("abcd\nefgh\nijkl");
+            type_keys(&mut ed, start);
+            ed.handle_key(ctrl('v'));
+            type_keys(&mut ed, select);
+            type_keys(&mut ed, "cXY");
+            ed.handle_key(esc());
+            assert_eq!(ed.text(), "aXYd\neXYh\niXYl", "{start} {select}");
+            assert_eq!(ed.registers.unnamed.content, "bc\nfg\njk");
+        }
+    }
+
     #[test]
     fn test_visual_mode_delete() {
         let mut ed = VimEditor::from_text("hello world");
@@ -2281,8 +4282,8 @@ mod tests {
         assert_eq!(ed.mode, VimMode::VisualLine);
         ed.handle_key(key('j'));
         ed.handle_key(key('y'));
-        assert!(ed.register.linewise);
-        assert_eq!(ed.register.content, "hello\nworld");
+        assert!(ed.registers.unnamed.linewise);
+        assert_eq!(ed.registers.unnamed.content, "hello\nworld");
         assert_eq!(ed.mode, VimMode::Normal);
     }
 
@@ -2295,7 +4296,7 @@ mod tests {
         ed.handle_key(key('i'));
         ed.handle_key(key('w'));
         ed.handle_key(key('y'));
-        assert_eq!(ed.register.content, "hello");
+        assert_eq!(ed.registers.unnamed.content, "hello");
         assert_eq!(ed.mode, VimMode::Normal);
     }
 
@@ -2420,4 +4421,27 @@ mod tests {
         ed.handle_key(key('j'));
         assert_eq!(ed.buffer.cursor_row, 3);
     }
+
+    #[test]
+    fn test_line_number_gutter() {
+        let text = (1..=12)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut ed = VimEditor::from_text(&text);
+        ed.buffer.cursor_row = 9;
+        assert_eq!(ed.gutter_width(), 4);
+
+        // Relative by default, the cursor line shows its absolute number
+        assert_eq!(ed.gutter_label(9).as_deref(), Some("10 "));
+        assert_eq!(ed.gutter_label(7).as_deref(), Some(" 2 "));
+        assert_eq!(ed.gutter_label(11).as_deref(), Some(" 2 "));
+
+        ed.relative_numbers = false;
+        assert_eq!(ed.gutter_label(7).as_deref(), Some(" 8 "));
+
+        ed.line_numbers = false;
+        assert_eq!(ed.gutter_width(), 0);
+        assert_eq!(ed.gutter_label(7), None);
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use ratatui::{
     layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Modifier, Style},
@@ -6,6 +8,12 @@ use ratatui::{
     Frame,
 };
 
+use crate::keybinds::{KeyAction, keymap};
+
+/// How long after the leader the popup appears. Sequences typed faster
+/// than this run without it flashing on screen.
+pub const WHICH_KEY_DELAY: Duration = Duration::from_millis(300);
+
 /// A single entry in the which-key menu.
 #[derive(Debug, Clone)]
 pub struct WhichKeyEntry {
@@ -15,6 +23,8 @@ pub struct WhichKeyEntry {
     pub description: String,
     /// Whether this entry is a group (has sub-entries) or a leaf action.
     pub is_group: bool,
+    /// The group's sub-entries, listed once the group key is pressed.
+    pub entries: Vec<WhichKeyEntry>,
 }
 
 impl WhichKeyEntry {
@@ -23,6 +33,7 @@ impl WhichKeyEntry {
             key: key.into(),
             description: description.into(),
             is_group: false,
+            entries: Vec::new(),
         }
     }
 
@@ -31,19 +42,31 @@ impl WhichKeyEntry {
             key: key.into(),
             description: description.into(),
             is_group: true,
+            entries: Vec::new(),
         }
     }
+
+    /// Set the sub-entries of a group.
+    pub fn with_entries(mut self, entries: Vec<WhichKeyEntry>) -> Self {
+        self.entries = entries;
+        self
+    }
 }
 
 /// The which-key popup state.
 #[derive(Debug, Default)]
 pub struct WhichKey {
-    /// Whether the popup is currently visible.
-    pub visible: bool,
+    /// Whether a leader sequence is in progress; the hub sends it the next key.
+    pub active: bool,
     /// Current entries to display.
     pub entries: Vec<WhichKeyEntry>,
     /// Title for the current level (e.g., "Leader", "Find").
     pub title: String,
+    /// Whether the current level is a tool's group: its next key goes back
+    /// to the tool, which is waiting for it.
+    pub in_group: bool,
+    /// When the popup is drawn (`None`: right away).
+    reveal_at: Option<Instant>,
 }
 
 impl WhichKey {
@@ -53,21 +76,63 @@ impl WhichKey {
 
     /// Show the which-key popup with the given entries.
     pub fn show(&mut self, title: impl Into<String>, entries: Vec<WhichKeyEntry>) {
-        self.visible = true;
+        self.show_after(title, entries, Duration::ZERO);
+    }
+
+    /// Start a leader sequence now but draw the popup only after `delay`.
+    pub fn show_after(
+        &mut self,
+        title: impl Into<String>,
+        entries: Vec<WhichKeyEntry>,
+        delay: Duration,
+    ) {
+        self.active = true;
+        self.in_group = false;
         self.title = title.into();
         self.entries = entries;
+        self.reveal_at = Some(Instant::now() + delay);
+    }
+
+    /// The group entry bound to `key` at the current level, if it has
+    /// sub-entries.
+    pub fn group(&self, key: char) -> Option<&WhichKeyEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.is_group && !e.entries.is_empty() && e.key == key.to_string())
+    }
+
+    /// Descend into `group`, keeping the popup's reveal time: a group opened
+    /// before the delay elapsed shows up when the leader popup would have.
+    pub fn open_group(&mut self, group: WhichKeyEntry, reveal_at: Option<Instant>) {
+        self.active = true;
+        self.in_group = true;
+        self.title = group.description;
+        self.entries = group.entries;
+        self.reveal_at = reveal_at;
+    }
+
+    /// When the popup is (or was) drawn.
+    pub fn reveal_at(&self) -> Option<Instant> {
+        self.reveal_at
+    }
+
+    /// Whether the popup is drawn at `now`.
+    pub fn is_revealed(&self, now: Instant) -> bool {
+        self.active && self.reveal_at.is_none_or(|at| now >= at)
     }
 
     /// Hide the which-key popup.
     pub fn hide(&mut self) {
-        self.visible = false;
+        self.active = false;
+        self.in_group = false;
         self.entries.clear();
         self.title.clear();
+        self.reveal_at = None;
     }
 
-    /// Render the which-key popup centered on screen.
+    /// Render the which-key popup centered on screen, once its delay is over.
     pub fn render(&self, frame: &mut Frame, area: Rect) {
-        if !self.visible || self.entries.is_empty() {
+        if !self.is_revealed(Instant::now()) || self.entries.is_empty() {
             return;
         }
 
@@ -119,11 +184,13 @@ impl WhichKey {
     }
 }
 
-/// Returns the top-level which-key entries for the hub.
+/// Returns the top-level which-key entries for the hub. Picker keys follow
+/// the installed keymap.
 pub fn hub_leader_entries() -> Vec<WhichKeyEntry> {
+    let key = |action| keymap().label(action);
     vec![
         WhichKeyEntry::action("d", "Database"),
-        WhichKeyEntry::group("f", "Find"),
+        WhichKeyEntry::group(key(KeyAction::Telescope), "Find"),
         WhichKeyEntry::action("h", "HTTP"),
         WhichKeyEntry::group("k", "KeePass"),
         WhichKeyEntry::action("m", "Merge"),
@@ -132,7 +199,7 @@ pub fn hub_leader_entries() -> Vec<WhichKeyEntry> {
         WhichKeyEntry::action("q", "Quit"),
         WhichKeyEntry::action("?", "Help"),
         WhichKeyEntry::action("1-9", "Switch to tool"),
-        WhichKeyEntry::action("<Space>", "Tool picker"),
+        WhichKeyEntry::action(key(KeyAction::ToolPicker), "Tool picker"),
     ]
 }
 
@@ -152,3 +219,47 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let [area] = horizontal.areas(area);
     area
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reveal_after_delay() {
+        let mut which_key = WhichKey::new();
+        which_key.show_after("Leader", hub_leader_entries(), WHICH_KEY_DELAY);
+        let now = Instant::now();
+        assert!(which_key.active);
+        assert!(!which_key.is_revealed(now));
+        assert!(which_key.is_revealed(now + WHICH_KEY_DELAY));
+
+        which_key.hide();
+        assert!(!which_key.is_revealed(now + WHICH_KEY_DELAY));
+        which_key.show("Leader", hub_leader_entries());
+        assert!(which_key.is_revealed(Instant::now()));
+    }
+
+    #[test]
+    fn test_open_group_lists_sub_entries() {
+        let search = WhichKeyEntry::group("s", "Search")
+            .with_entries(vec![WhichKeyEntry::action("g", "Grep")]);
+        let mut which_key = WhichKey::new();
+        which_key.show_after(
+            "Leader",
+            vec![search, WhichKeyEntry::group("x", "Empty")],
+            WHICH_KEY_DELAY,
+        );
+        assert!(which_key.group('x').is_none());
+        assert!(which_key.group('g').is_none());
+
+        let group = which_key.group('s').cloned().unwrap();
+        let reveal_at = which_key.reveal_at();
+        which_key.hide();
+        which_key.open_group(group, reveal_at);
+        assert!(which_key.in_group);
+        assert_eq!(which_key.title, "Search");
+        assert_eq!(which_key.entries.len(), 1);
+        assert_eq!(which_key.entries[0].key, "g");
+        assert_eq!(which_key.reveal_at(), reveal_at);
+    }
+}
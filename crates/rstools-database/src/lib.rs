@@ -7,11 +7,11 @@ pub mod ui;
 
 use std::sync::mpsc;
 use std::thread;
-use std::time::Instant;
 
 use rstools_core::help_popup::HelpEntry;
-use rstools_core::keybinds::{Action, InputMode, KeyState, process_normal_key};
+use rstools_core::keybinds::{Action, InputMode, KeyAction, KeyState, keymap, process_normal_key};
 use rstools_core::telescope::TelescopeItem;
+use rstools_core::toast::Toast;
 use rstools_core::tool::Tool;
 use rstools_core::vim_editor::{EditorAction, VimEditor, VimMode};
 use rstools_core::which_key::WhichKeyEntry;
@@ -354,10 +354,8 @@ pub struct DatabaseTool {
     spinner_frame: u8,
     /// When true, the next QueryResult should be appended instead of replacing.
     pending_load_more: bool,
-    error_message: Option<String>,
-    error_shown_at: Option<Instant>,
-    status_message: Option<String>,
-    status_shown_at: Option<Instant>,
+    /// Toasts raised since the hub last drained them.
+    toasts: Vec<Toast>,
 }
 
 impl DatabaseTool {
@@ -390,10 +388,7 @@ impl DatabaseTool {
             loading: false,
             spinner_frame: 0,
             pending_load_more: false,
-            error_message: None,
-            error_shown_at: None,
-            status_message: None,
-            status_shown_at: None,
+            toasts: Vec::new(),
         };
         tool.rebuild_sidebar();
         Ok(tool)
@@ -623,8 +618,8 @@ impl DatabaseTool {
                 DbResult::Connected(version) => {
                     self.loading = false;
                     self.connected_version = Some(version.clone());
-                    self.status_message = Some(format!("Connected: {version}"));
-                    self.status_shown_at = Some(Instant::now());
+                    self.toasts
+                        .push(Toast::success(format!("Connected: {version}")));
                     // Fetch tables
                     self.executor.send(DbCommand::GetTables);
                 }
@@ -674,8 +669,7 @@ impl DatabaseTool {
                         );
                         continue;
                     }
-                    self.error_message = Some(msg);
-                    self.error_shown_at = Some(Instant::now());
+                    self.toasts.push(Toast::error(msg));
                 }
                 DbResult::Disconnected => {
                     self.active_connection_id = None;
@@ -869,8 +863,8 @@ impl DatabaseTool {
     }
 
     fn handle_query_editor_key(&mut self, key: KeyEvent) -> Action {
-        // Normal-mode special keys
-        if self.query_editor.mode == VimMode::Normal {
+        // Normal-mode special keys (not while typing a search pattern)
+        if self.query_editor.mode == VimMode::Normal && !self.query_editor.is_searching() {
             match key.code {
                 KeyCode::Char('j') | KeyCode::Down
                     if key.modifiers == KeyModifiers::NONE
@@ -897,7 +891,7 @@ impl DatabaseTool {
 
         let action = self.query_editor.handle_key(key);
         match action {
-            EditorAction::ModeChanged(VimMode::Insert) => {
+            EditorAction::ModeChanged(VimMode::Insert | VimMode::Replace) => {
                 self.mode = InputMode::Insert;
                 Action::SetMode(InputMode::Insert)
             }
@@ -915,9 +909,9 @@ impl DatabaseTool {
                 self.execute_query();
                 return Action::None;
             }
-            KeyCode::Char('k') | KeyCode::Up
-                if key.modifiers == KeyModifiers::NONE
-                    && self.query_results.selected_row == 0 =>
+            _ if (key.code == KeyCode::Up || keymap().matches(KeyAction::MoveUp, &key))
+                && key.modifiers == KeyModifiers::NONE
+                && self.query_results.selected_row == 0 =>
             {
                 self.focus = Focus::QueryEditor;
                 return Action::None;
@@ -981,8 +975,7 @@ impl DatabaseTool {
                         self.focus = Focus::Sidebar;
                         self.mode = InputMode::Normal;
                         self.reload_connections();
-                        self.status_message = Some("Connection saved".to_string());
-                        self.status_shown_at = Some(Instant::now());
+                        self.toasts.push(Toast::success("Connection saved"));
                     }
                     Err(e) => {
                         if let Some(ref mut f) = self.connection_form {
@@ -1040,6 +1033,22 @@ impl Tool for DatabaseTool {
         self.mode
     }
 
+    fn mode_label(&self) -> &'static str {
+        if self.focus == Focus::QueryEditor && self.query_editor.mode == VimMode::Replace {
+            "REPLACE"
+        } else {
+            self.mode.label()
+        }
+    }
+
+    fn pending_keys(&self) -> String {
+        let mut keys = self.key_state.pending_keys();
+        if self.focus == Focus::QueryEditor {
+            keys.push_str(&self.query_editor.pending_keys());
+        }
+        keys
+    }
+
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()> {
         model::init_db(conn)
     }
@@ -1065,6 +1074,20 @@ impl Tool for DatabaseTool {
             .collect()
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let conn_id = id.strip_prefix("db:")?.parse::<i64>().ok()?;
+        let c = self.connections.iter().find(|c| c.id == conn_id)?;
+        Some(format!(
+            "{}\n{}@{}:{}/{}\nSSL: {}",
+            c.provider,
+            c.username,
+            c.host,
+            c.port,
+            c.database_name,
+            if c.ssl_enabled { "on" } else { "off" }
+        ))
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         if let Some(id_str) = id.strip_prefix("db:") {
             if let Ok(id) = id_str.parse::<i64>() {
@@ -1088,6 +1111,9 @@ impl Tool for DatabaseTool {
             HelpEntry::with_section("Query Editor", "j/Tab", "Focus results"),
             HelpEntry::with_section("Query Editor", "Enter", "Execute query (in results)"),
             HelpEntry::with_section("Query Editor", "k", "Focus editor (from results)"),
+            HelpEntry::with_section("Query Editor", "/ / ?", "Search forward / backward"),
+            HelpEntry::with_section("Query Editor", "n / N", "Next / previous match"),
+            HelpEntry::with_section("Query Editor", ":set wrap / nowrap", "Soft-wrap long lines"),
             HelpEntry::with_section("Table View", "j/k", "Navigate rows"),
             HelpEntry::with_section("Table View", "h/l", "Scroll columns"),
             HelpEntry::with_section("Table View", "n/p", "Next/previous page"),
@@ -1098,20 +1124,6 @@ impl Tool for DatabaseTool {
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Action {
-        // Clear stale messages
-        if let Some(shown_at) = self.error_shown_at {
-            if shown_at.elapsed().as_secs() > 5 {
-                self.error_message = None;
-                self.error_shown_at = None;
-            }
-        }
-        if let Some(shown_at) = self.status_shown_at {
-            if shown_at.elapsed().as_secs() > 3 {
-                self.status_message = None;
-                self.status_shown_at = None;
-            }
-        }
-
         match self.focus {
             Focus::PinPrompt => self.handle_pin_key(key),
             Focus::ConnectionForm => self.handle_connection_form_key(key),
@@ -1220,6 +1232,10 @@ impl Tool for DatabaseTool {
         }
     }
 
+    fn take_toasts(&mut self) -> Vec<Toast> {
+        std::mem::take(&mut self.toasts)
+    }
+
     fn handle_command(&mut self, cmd: &str) -> bool {
         match cmd {
             "sql" | "query" => {
@@ -1229,6 +1245,14 @@ impl Tool for DatabaseTool {
                 }
                 true
             }
+            "set wrap" => {
+                self.query_editor.wrap = true;
+                true
+            }
+            "set nowrap" => {
+                self.query_editor.wrap = false;
+                true
+            }
             "browse" => {
                 self.view_mode = ViewMode::Browse;
                 if self.active_table.is_some() {
@@ -1250,8 +1274,8 @@ impl Tool for DatabaseTool {
                 let _ = model::init_db(&self.conn);
                 self.connections.clear();
                 self.rebuild_sidebar();
-                self.status_message = Some("db_connections table reset".to_string());
-                self.status_shown_at = Some(Instant::now());
+                self.toasts
+                    .push(Toast::success("db_connections table reset"));
                 true
             }
             _ => false,
@@ -1266,7 +1290,7 @@ impl Tool for DatabaseTool {
         } else if self.focus == Focus::QueryEditor {
             self.query_editor.paste_text(text);
             match self.query_editor.mode {
-                VimMode::Insert => {
+                VimMode::Insert | VimMode::Replace => {
                     self.mode = InputMode::Insert;
                     return Action::SetMode(InputMode::Insert);
                 }
@@ -1282,3 +1306,41 @@ impl Tool for DatabaseTool {
         self.reload_connections();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::DbConnectionInput;
+    use rstools_core::db::open_memory_db;
+
+    #[test]
+    fn test_telescope_preview() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let input = DbConnectionInput {
+            name: "app".to_string(),
+            host: "localhost".to_string(),
+            port: 5432,
+            database_name: "app_dev".to_string(),
+            username: "dev".to_string(),
+            ssl_enabled: true,
+            ..Default::default()
+        };
+        let id = model::add_connection(&conn, &input).unwrap();
+        let tool = DatabaseTool::new(conn).unwrap();
+
+        let preview = tool.telescope_preview(&format!("db:{id}")).unwrap();
+        assert_eq!(preview.lines().nth(1), Some("dev@localhost:5432/app_dev"));
+        assert_eq!(preview.lines().nth(2), Some("SSL: on"));
+        assert_eq!(tool.telescope_preview("db:999"), None);
+    }
+
+    #[test]
+    fn test_set_wrap() {
+        let mut tool = DatabaseTool::new(open_memory_db().unwrap()).unwrap();
+        assert!(tool.handle_command("set wrap"));
+        assert!(tool.query_editor.wrap);
+        assert!(tool.handle_command("set nowrap"));
+        assert!(!tool.query_editor.wrap);
+    }
+}
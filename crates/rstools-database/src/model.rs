@@ -1,4 +1,5 @@
 use anyhow::Result;
+use rstools_core::db::{self, Migration};
 use rusqlite::Connection;
 
 // ── Data model ───────────────────────────────────────────────────────
@@ -55,6 +56,14 @@ pub struct DbConnectionInput {
 
 // ── Database schema ──────────────────────────────────────────────────
 
+/// Schema changes made after the tables below were first released, oldest
+/// first. Append new steps; never edit or reorder released ones.
+const MIGRATIONS: &[Migration] = &[Migration::AddColumn {
+    table: "db_connections",
+    column: "password",
+    definition: "TEXT NOT NULL DEFAULT ''",
+}];
+
 pub fn init_db(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS db_connections (
@@ -91,16 +100,7 @@ pub fn init_db(conn: &Connection) -> Result<()> {
         END;",
     )?;
 
-    // Migration: add password column if missing (table existed before this column was added)
-    let has_password_col: bool = conn
-        .prepare("SELECT COUNT(*) FROM pragma_table_info('db_connections') WHERE name = 'password'")?
-        .query_row([], |row| row.get::<_, i64>(0))
-        .map(|count| count > 0)?;
-    if !has_password_col {
-        conn.execute_batch("ALTER TABLE db_connections ADD COLUMN password TEXT NOT NULL DEFAULT '';")?;
-    }
-
-    Ok(())
+    db::migrate(conn, "database", MIGRATIONS)
 }
 
 // ── CRUD operations ──────────────────────────────────────────────────
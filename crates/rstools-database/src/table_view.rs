@@ -1,6 +1,7 @@
 use std::cell::Cell;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rstools_core::keybinds::{KeyAction, keymap};
 
 use crate::driver::{ColumnInfo, FilterOp, QueryFilter, QueryResult, SortDirection};
 
@@ -151,11 +152,11 @@ impl TableView {
         }
 
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
+            _ if key.code == KeyCode::Down || keymap().matches(KeyAction::MoveDown, &key) => {
                 self.move_down(1);
                 TableAction::None
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            _ if key.code == KeyCode::Up || keymap().matches(KeyAction::MoveUp, &key) => {
                 self.move_up(1);
                 TableAction::None
             }
@@ -173,11 +174,11 @@ impl TableView {
                 }
                 TableAction::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.selected_row = 0;
                 TableAction::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 self.selected_row = self.max_row();
                 TableAction::None
             }
@@ -10,9 +10,9 @@ use unicode_width::UnicodeWidthStr;
 use crate::driver::SortDirection;
 use crate::table_view::TableView;
 use crate::{DatabaseTool, Focus, ViewMode};
+use rstools_core::theme::theme;
 
 const SPINNER: &[char] = &['|', '/', '-', '\\'];
-const SELECTED_BG: Color = Color::Gray;
 
 pub fn render(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
     let sidebar_width = area.width.min(30);
@@ -38,9 +38,9 @@ pub fn render(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
 fn render_sidebar(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
     let focused = tool.focus == Focus::Sidebar;
     let border_style = if focused {
-        Style::default().fg(Color::Blue)
+        Style::default().fg(theme().border)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     let title = if let Some(ref version) = tool.connected_version {
@@ -64,7 +64,7 @@ fn render_sidebar(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
 
     if tool.sidebar_entries.is_empty() {
         let hint = Paragraph::new("Press 'a' to add a connection")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme().muted))
             .alignment(Alignment::Center);
         frame.render_widget(hint, inner);
         return;
@@ -105,7 +105,7 @@ fn render_sidebar(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
                 Line::from(Span::styled(
                     format!("  {}", entry.label),
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(theme().accent)
                         .add_modifier(Modifier::BOLD),
                 ))
             } else {
@@ -122,11 +122,11 @@ fn render_sidebar(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
 
                 let style = if selected {
                     Style::default()
-                        .bg(Color::DarkGray)
-                        .fg(Color::White)
+                        .bg(theme().muted)
+                        .fg(theme().text)
                         .add_modifier(Modifier::BOLD)
                 } else if is_active_conn || is_active_table {
-                    Style::default().fg(Color::Green)
+                    Style::default().fg(theme().success)
                 } else {
                     Style::default()
                 };
@@ -177,9 +177,9 @@ fn render_main_query(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
     // ── Editor panel ────────────────────────────────────────────────
     let editor_focused = tool.focus == Focus::QueryEditor;
     let editor_border = if editor_focused {
-        Style::default().fg(Color::Blue)
+        Style::default().fg(theme().border)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     let editor_block = Block::default()
@@ -196,25 +196,25 @@ fn render_main_query(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
         let results_block = Block::default()
             .title(" Results ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red));
+            .border_style(Style::default().fg(theme().error));
         let inner = results_block.inner(results_area);
         frame.render_widget(results_block, results_area);
 
         let error_text = Paragraph::new(Line::from(Span::styled(
             format!("Error: {err}"),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme().error),
         )));
         frame.render_widget(error_text, inner);
     } else if tool.query_results.columns.is_empty() {
         let results_block = Block::default()
             .title(" Results ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray));
+            .border_style(Style::default().fg(theme().muted));
         let inner = results_block.inner(results_area);
         frame.render_widget(results_block, results_area);
 
         let hint = Paragraph::new("Write a query and press Ctrl+Enter to execute")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme().muted))
             .alignment(Alignment::Center);
         let [_, centered, _] = Layout::vertical([
             Constraint::Fill(1),
@@ -247,14 +247,14 @@ fn render_main_query(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
     };
 
     let line = Line::from(vec![
-        Span::styled(row_info, Style::default().fg(Color::DarkGray)),
-        Span::styled(loading, Style::default().fg(Color::Yellow)),
+        Span::styled(row_info, Style::default().fg(theme().muted)),
+        Span::styled(loading, Style::default().fg(theme().accent)),
     ]);
     frame.render_widget(Paragraph::new(line), status_area);
 }
 
 fn render_welcome(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
-    let block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray));
+    let block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme().muted));
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -271,27 +271,17 @@ fn render_welcome(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
         let spinner = SPINNER[tool.spinner_frame as usize % SPINNER.len()];
         lines.push(Line::from(Span::styled(
             format!(" {spinner} Connecting..."),
-            Style::default().fg(Color::Yellow),
-        )));
-    } else if let Some(ref err) = tool.error_message {
-        lines.push(Line::from(Span::styled(
-            format!(" Error: {err}"),
-            Style::default().fg(Color::Red),
-        )));
-    } else if let Some(ref msg) = tool.status_message {
-        lines.push(Line::from(Span::styled(
-            format!(" {msg}"),
-            Style::default().fg(Color::Green),
+            Style::default().fg(theme().accent),
         )));
     } else if tool.connections.is_empty() {
         lines.push(Line::from(Span::styled(
             "Press 'a' to add your first connection",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )));
     } else {
         lines.push(Line::from(Span::styled(
             "Select a connection and press Enter",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )));
     }
 
@@ -309,9 +299,9 @@ fn render_welcome(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
 
 fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rect) {
     let border_style = if focused {
-        Style::default().fg(Color::Blue)
+        Style::default().fg(theme().border)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     let block = Block::default().borders(Borders::ALL).border_style(border_style);
@@ -320,7 +310,7 @@ fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rec
 
     if tv.columns.is_empty() {
         let hint = Paragraph::new("No data")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme().muted))
             .alignment(Alignment::Center);
         frame.render_widget(hint, inner);
         return;
@@ -351,7 +341,7 @@ fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rec
             let selected = i == tv.selected_col && focused;
             let style = if selected {
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme().info)
                     .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
             } else {
                 Style::default().add_modifier(Modifier::BOLD)
@@ -389,7 +379,7 @@ fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rec
                     if is_selected_row && col_idx == tv.selected_col {
                         cell = cell.style(
                             Style::default()
-                                .bg(Color::White)
+                                .bg(theme().text)
                                 .fg(Color::Black)
                                 .add_modifier(Modifier::BOLD),
                         );
@@ -415,12 +405,12 @@ fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rec
         let selected = load_more_idx == tv.selected_row && focused;
         let style = if selected {
             Style::default()
-                .bg(SELECTED_BG)
+                .bg(theme().selection_bg)
                 .fg(Color::Black)
                 .add_modifier(Modifier::BOLD)
         } else {
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::DIM)
         };
         let label = format!("  ↓ Load more ({remaining} remaining) — press Enter");
@@ -455,7 +445,7 @@ fn render_table_view(tv: &TableView, focused: bool, frame: &mut Frame, area: Rec
             height: 1,
         };
         let line = Line::from(vec![
-            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::styled("/", Style::default().fg(theme().accent)),
             Span::raw(filter_text),
         ]);
         frame.render_widget(Clear, filter_area);
@@ -487,9 +477,9 @@ fn render_table_status(tool: &DatabaseTool, frame: &mut Frame, area: Rect) {
     let line = Line::from(vec![
         Span::styled(
             format!("{page_info}{filter_info}"),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         ),
-        Span::styled(loading, Style::default().fg(Color::Yellow)),
+        Span::styled(loading, Style::default().fg(theme().accent)),
     ]);
 
     frame.render_widget(Paragraph::new(line), area);
@@ -597,7 +587,7 @@ fn render_connection_form(
         .title(" New Connection ")
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue));
+        .border_style(Style::default().fg(theme().border));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -612,14 +602,14 @@ fn render_connection_form(
 
         let label_style = if is_focused {
             Style::default()
-                .fg(Color::Blue)
+                .fg(theme().border)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         };
 
         let value_style = if is_focused {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme().text)
         } else {
             Style::default()
         };
@@ -628,10 +618,10 @@ fn render_connection_form(
             let btn_style = if is_focused {
                 Style::default()
                     .fg(Color::Black)
-                    .bg(Color::Blue)
+                    .bg(theme().border)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Blue)
+                Style::default().fg(theme().border)
             };
             lines.push(Line::from(vec![
                 Span::raw("  "),
@@ -658,13 +648,13 @@ fn render_connection_form(
             Ok(version) => {
                 lines.push(Line::from(Span::styled(
                     format!("  OK: {version}"),
-                    Style::default().fg(Color::Green),
+                    Style::default().fg(theme().success),
                 )));
             }
             Err(msg) => {
                 lines.push(Line::from(Span::styled(
                     format!("  Error: {msg}"),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(theme().error),
                 )));
             }
         }
@@ -675,7 +665,7 @@ fn render_connection_form(
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             format!("  {spinner} Testing..."),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme().accent),
         )));
     }
 
@@ -693,7 +683,7 @@ fn render_pin_prompt(prompt: &crate::PinPrompt, frame: &mut Frame, area: Rect) {
         .title(" Enter PIN ")
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(theme().accent));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -706,8 +696,8 @@ fn render_pin_prompt(prompt: &crate::PinPrompt, frame: &mut Frame, area: Rect) {
         Line::from(""),
         Line::from(vec![
             Span::raw("  PIN: "),
-            Span::styled(&dots, Style::default().fg(Color::Yellow)),
-            Span::styled(placeholder, Style::default().fg(Color::DarkGray)),
+            Span::styled(&dots, Style::default().fg(theme().accent)),
+            Span::styled(placeholder, Style::default().fg(theme().muted)),
         ]),
     ];
 
@@ -715,7 +705,7 @@ fn render_pin_prompt(prompt: &crate::PinPrompt, frame: &mut Frame, area: Rect) {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             format!("  {error}"),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme().error),
         )));
     }
 
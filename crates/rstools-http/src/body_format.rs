@@ -0,0 +1,363 @@
+use std::path::Path;
+
+/// The language a response body is displayed as, derived from `Content-Type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyLanguage {
+    Json,
+    Xml,
+    Html,
+    #[default]
+    Text,
+    Binary,
+}
+
+impl BodyLanguage {
+    /// Detect the body language from the `Content-Type` header value.
+    ///
+    /// Without a content type the raw body is sniffed: JSON-looking text stays
+    /// JSON (as before), text with NUL/replacement characters is binary.
+    pub fn detect(content_type: Option<&str>, raw: &str) -> Self {
+        let Some(content_type) = content_type else {
+            let trimmed = raw.trim_start();
+            return if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                BodyLanguage::Json
+            } else if raw.contains('\0') || raw.contains('\u{FFFD}') {
+                BodyLanguage::Binary
+            } else {
+                BodyLanguage::Text
+            };
+        };
+
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        let (kind, subtype) = mime.split_once('/').unwrap_or((mime.as_str(), ""));
+
+        if subtype == "json" || subtype.ends_with("+json") {
+            BodyLanguage::Json
+        } else if subtype == "html" || subtype == "xhtml+xml" {
+            BodyLanguage::Html
+        } else if subtype == "xml" || subtype.ends_with("+xml") {
+            BodyLanguage::Xml
+        } else if kind == "text"
+            || matches!(
+                subtype,
+                "javascript"
+                    | "ecmascript"
+                    | "x-www-form-urlencoded"
+                    | "yaml"
+                    | "x-yaml"
+                    | "graphql"
+                    | "csv"
+                    | "sql"
+            )
+        {
+            BodyLanguage::Text
+        } else {
+            BodyLanguage::Binary
+        }
+    }
+}
+
+/// Find the `Content-Type` value in a list of response headers.
+pub fn content_type(headers: &[(String, String)]) -> Option<&str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.as_str())
+}
+
+/// The content type to send a body file with, guessed from its extension.
+pub fn content_type_for_path(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "html" | "htm" => "text/html",
+        "txt" | "md" => "text/plain",
+        "csv" => "text/csv",
+        "yaml" | "yml" => "application/yaml",
+        "js" => "text/javascript",
+        "graphql" | "gql" => "application/graphql",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Detect the language of response body bytes and format them for display.
+/// Bodies that are not valid UTF-8 are binary whatever their content type.
+pub fn format_response(content_type: Option<&str>, raw: &[u8]) -> (BodyLanguage, String) {
+    match std::str::from_utf8(raw) {
+        Ok(text) => (
+            BodyLanguage::detect(content_type, text),
+            format_body(content_type, text),
+        ),
+        Err(_) => (BodyLanguage::Binary, hex_dump(raw)),
+    }
+}
+
+/// Bytes per line of a hex dump.
+const HEX_ROW: usize = 16;
+
+/// `offset | bytes | ascii` lines, 16 bytes each, like `hexdump -C`.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(HEX_ROW)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut hex = String::with_capacity(HEX_ROW * 3 + 1);
+            for (j, byte) in chunk.iter().enumerate() {
+                if j == HEX_ROW / 2 {
+                    hex.push(' ');
+                }
+                hex.push_str(&format!("{byte:02x} "));
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:08x} | {hex:<49}| {ascii}", i * HEX_ROW)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format a raw response body for display according to its content type.
+///
+/// JSON is pretty-printed, XML is indented, HTML only gets its line endings
+/// normalized, and binary bodies are shown as a hex dump.
+/// Anything that fails to parse is returned unchanged.
+pub fn format_body(content_type: Option<&str>, raw: &str) -> String {
+    match BodyLanguage::detect(content_type, raw) {
+        BodyLanguage::Json => match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(json) => serde_json::to_string_pretty(&json).unwrap_or_else(|_| raw.to_string()),
+            Err(_) => raw.to_string(),
+        },
+        BodyLanguage::Xml => indent_xml(raw).unwrap_or_else(|| raw.to_string()),
+        BodyLanguage::Html => raw
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        BodyLanguage::Text => raw.to_string(),
+        BodyLanguage::Binary => hex_dump(raw.as_bytes()),
+    }
+}
+
+/// Re-indent XML with two spaces per nesting level.
+///
+/// Elements that only wrap text stay on one line (`<a>text</a>`). Returns
+/// `None` when the markup is unbalanced so the caller can show it verbatim.
+fn indent_xml(raw: &str) -> Option<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut depth: usize = 0;
+    let mut rest = raw.trim();
+
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = if rest.starts_with("<!--") {
+                rest.find("-->")? + 3
+            } else if rest.starts_with("<![CDATA[") {
+                rest.find("]]>")? + 3
+            } else {
+                rest.find('>')? + 1
+            };
+            let tag = &rest[..end];
+            rest = rest[end..].trim_start();
+
+            if tag.starts_with("</") {
+                depth = depth.checked_sub(1)?;
+                out.push(format!("{}{}", "  ".repeat(depth), tag));
+            } else if tag.starts_with("<?") || tag.starts_with("<!") || tag.ends_with("/>") {
+                out.push(format!("{}{}", "  ".repeat(depth), tag));
+            } else {
+                // Keep `<a>text</a>` on a single line
+                let text_end = rest.find('<').unwrap_or(rest.len());
+                let text = rest[..text_end].trim();
+                let after = &rest[text_end..];
+                if !text.is_empty() && after.starts_with("</") {
+                    let close_end = after.find('>')? + 1;
+                    out.push(format!(
+                        "{}{}{}{}",
+                        "  ".repeat(depth),
+                        tag,
+                        text,
+                        &after[..close_end]
+                    ));
+                    rest = after[close_end..].trim_start();
+                } else {
+                    out.push(format!("{}{}", "  ".repeat(depth), tag));
+                    depth += 1;
+                }
+            }
+        } else {
+            let text_end = rest.find('<').unwrap_or(rest.len());
+            let text = rest[..text_end].trim();
+            if !text.is_empty() {
+                out.push(format!("{}{}", "  ".repeat(depth), text));
+            }
+            rest = &rest[text_end..];
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+    Some(out.join("\n"))
+}
+
+/// Look up a dotted path such as `data.items.0.id` in a JSON value.
+/// Numeric segments index arrays; an empty path returns the root.
+pub fn json_path_lookup<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    path.trim()
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            serde_json::Value::Object(map) => map.get(segment),
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            BodyLanguage::detect(Some("application/json; charset=utf-8"), ""),
+            BodyLanguage::Json
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("application/problem+json"), ""),
+            BodyLanguage::Json
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("text/xml"), ""),
+            BodyLanguage::Xml
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("application/atom+xml"), ""),
+            BodyLanguage::Xml
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("text/html; charset=UTF-8"), ""),
+            BodyLanguage::Html
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("text/plain"), ""),
+            BodyLanguage::Text
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("image/png"), ""),
+            BodyLanguage::Binary
+        );
+        assert_eq!(
+            BodyLanguage::detect(Some("application/octet-stream"), ""),
+            BodyLanguage::Binary
+        );
+        assert_eq!(BodyLanguage::detect(None, " {\"a\":1}"), BodyLanguage::Json);
+        assert_eq!(BodyLanguage::detect(None, "hello"), BodyLanguage::Text);
+        assert_eq!(
+            BodyLanguage::detect(None, "\u{FFFD}PNG"),
+            BodyLanguage::Binary
+        );
+    }
+
+    #[test]
+    fn test_format_json_and_binary() {
+        assert_eq!(
+            format_body(Some("application/json"), r#"{"a":1}"#),
+            "{\n  \"a\": 1\n}"
+        );
+        assert_eq!(format_body(Some("application/json"), "{oops"), "{oops");
+        assert_eq!(format_body(Some("image/png"), "12345"), hex_dump(b"12345"));
+    }
+
+    #[test]
+    fn test_hex_dump_and_invalid_utf8() {
+        let bytes: Vec<u8> = (0x3c..0x4e).chain([0x00, 0xff]).collect();
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000 | 3c 3d 3e 3f 40 41 42 43  44 45 46 47 48 49 4a 4b | <=>?@ABCDEFGHIJK\n\
+             00000010 | 4c 4d 00 ff                                      | LM.."
+        );
+        assert_eq!(hex_dump(&[]), "");
+
+        // Invalid UTF-8 is binary even when the server calls it text
+        let (language, body) = format_response(Some("text/plain"), &[0x89, b'P', b'N', b'G']);
+        assert_eq!(language, BodyLanguage::Binary);
+        assert!(body.ends_with("| .PNG"));
+        let (language, body) = format_response(Some("application/json"), br#"{"a":1}"#);
+        assert_eq!(language, BodyLanguage::Json);
+        assert_eq!(body, "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn test_format_xml() {
+        let raw = r#"<?xml version="1.0"?><root><!-- c --><item id="1">one</item><empty/><list><a>x</a></list></root>"#;
+        assert_eq!(
+            format_body(Some("application/xml"), raw),
+            "<?xml version=\"1.0\"?>\n\
+             <root>\n\
+             \x20 <!-- c -->\n\
+             \x20 <item id=\"1\">one</item>\n\
+             \x20 <empty/>\n\
+             \x20 <list>\n\
+             \x20   <a>x</a>\n\
+             \x20 </list>\n\
+             </root>"
+        );
+
+        // Unbalanced markup is shown verbatim
+        assert_eq!(format_body(Some("text/xml"), "<a><b></a>"), "<a><b></a>");
+    }
+
+    #[test]
+    fn test_json_path_lookup() {
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"data": {"token": "abc", "items": [{"id": 1}, {"id": 2}]}}"#)
+                .unwrap();
+
+        assert_eq!(json_path_lookup(&json, "data.token").unwrap(), "abc");
+        assert_eq!(json_path_lookup(&json, "data.items.1.id").unwrap(), 2);
+        assert_eq!(json_path_lookup(&json, "").unwrap(), &json);
+        assert!(json_path_lookup(&json, "data.missing").is_none());
+        assert!(json_path_lookup(&json, "data.items.5").is_none());
+        assert!(json_path_lookup(&json, "data.items.x").is_none());
+        assert!(json_path_lookup(&json, "data.token.length").is_none());
+    }
+
+    #[test]
+    fn test_format_html_is_minimal() {
+        let raw = "<html>  \r\n<body><p>hi</p></body>\r\n</html>";
+        assert_eq!(
+            format_body(Some("text/html"), raw),
+            "<html>\n<body><p>hi</p></body>\n</html>"
+        );
+    }
+}
@@ -0,0 +1,477 @@
+//! Export and import of the whole query tree as a portable JSON document.
+//!
+//! ```json
+//! { "format": "rstools-http-collection", "version": 1,
+//!   "entries": [ { "type": "folder", "name": "api", "children": [
+//!       { "type": "query", "name": "get-user", "request": { "method": "GET", ... } } ] } ] }
+//! ```
+//!
+//! Database IDs are never written; importing assigns fresh ones.
+
+use anyhow::{Context, Result, bail};
+use rusqlite::Connection;
+use serde_json::{Value, json};
+
+use crate::model::{self, AuthType, BodyMode, EntryType, HttpAuth, HttpMethod};
+
+/// Value of the `format` field identifying a collection file.
+pub const FORMAT: &str = "rstools-http-collection";
+/// Current collection file version.
+pub const VERSION: u64 = 1;
+
+/// A folder or query of a collection, with everything needed to recreate it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectionNode {
+    Folder {
+        name: String,
+        /// Headers and variables the folder passes down, as (key, value,
+        /// enabled) rows.
+        headers: Vec<(String, String, bool)>,
+        vars: Vec<(String, String, bool)>,
+        children: Vec<CollectionNode>,
+    },
+    Query {
+        name: String,
+        request: Box<CollectionRequest>,
+    },
+}
+
+/// The saved request of a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub body: String,
+    pub timeout_ms: Option<u64>,
+    pub body_mode: BodyMode,
+    pub graphql_variables: String,
+    pub use_default_headers: bool,
+    /// (key, value, enabled) rows, in display order.
+    pub headers: Vec<(String, String, bool)>,
+    pub query_params: Vec<(String, String, bool)>,
+    pub form_fields: Vec<(String, String, bool)>,
+    pub auth: HttpAuth,
+}
+
+// ── Export ───────────────────────────────────────────────────────────
+
+/// Number of queries in `nodes`, counting into folders.
+pub fn query_count(nodes: &[CollectionNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            CollectionNode::Folder { children, .. } => query_count(children),
+            CollectionNode::Query { .. } => 1,
+        })
+        .sum()
+}
+
+/// Read the entire tree from the database.
+pub fn load_tree(conn: &Connection) -> Result<Vec<CollectionNode>> {
+    let entries = model::list_entries(conn)?;
+    load_children(conn, &entries, None)
+}
+
+/// Read one entry and everything below it.
+pub fn load_subtree(conn: &Connection, entry_id: i64) -> Result<CollectionNode> {
+    let entries = model::list_entries(conn)?;
+    let entry = entries
+        .iter()
+        .find(|e| e.id == entry_id)
+        .context("entry not found")?;
+    load_node(conn, &entries, entry)
+}
+
+fn load_children(
+    conn: &Connection,
+    entries: &[model::HttpEntry],
+    parent_id: Option<i64>,
+) -> Result<Vec<CollectionNode>> {
+    entries
+        .iter()
+        .filter(|e| e.parent_id == parent_id)
+        .map(|e| load_node(conn, entries, e))
+        .collect()
+}
+
+fn load_node(
+    conn: &Connection,
+    entries: &[model::HttpEntry],
+    entry: &model::HttpEntry,
+) -> Result<CollectionNode> {
+    Ok(match entry.entry_type {
+        EntryType::Folder => CollectionNode::Folder {
+            name: entry.name.clone(),
+            headers: folder_rows(model::load_folder_headers(conn, entry.id)?),
+            vars: folder_rows(model::load_folder_vars(conn, entry.id)?),
+            children: load_children(conn, entries, Some(entry.id))?,
+        },
+        EntryType::Query => CollectionNode::Query {
+            name: entry.name.clone(),
+            request: Box::new(load_request(conn, entry.id)?),
+        },
+    })
+}
+
+fn folder_rows(values: Vec<model::HttpFolderValue>) -> Vec<(String, String, bool)> {
+    values
+        .into_iter()
+        .map(|v| (v.key, v.value, v.enabled))
+        .collect()
+}
+
+fn load_request(conn: &Connection, entry_id: i64) -> Result<CollectionRequest> {
+    let Some(req) = model::load_request(conn, entry_id)? else {
+        // A query that was never saved exports with the defaults
+        return Ok(parse_request(&Value::Null));
+    };
+    Ok(CollectionRequest {
+        method: req.method,
+        url: req.url,
+        body: req.body,
+        timeout_ms: req.timeout_ms,
+        body_mode: req.body_mode,
+        graphql_variables: req.graphql_variables,
+        use_default_headers: req.use_default_headers,
+        headers: model::load_headers(conn, req.id)?
+            .into_iter()
+            .map(|h| (h.key, h.value, h.enabled))
+            .collect(),
+        query_params: model::load_query_params(conn, req.id)?
+            .into_iter()
+            .map(|p| (p.key, p.value, p.enabled))
+            .collect(),
+        form_fields: model::load_form_fields(conn, req.id)?
+            .into_iter()
+            .map(|f| (f.key, f.value, f.enabled))
+            .collect(),
+        auth: model::load_auth(conn, req.id)?,
+    })
+}
+
+/// Serialize a tree into a collection document.
+pub fn to_json(nodes: &[CollectionNode]) -> Value {
+    json!({
+        "format": FORMAT,
+        "version": VERSION,
+        "entries": nodes.iter().map(node_to_json).collect::<Vec<_>>(),
+    })
+}
+
+fn node_to_json(node: &CollectionNode) -> Value {
+    match node {
+        CollectionNode::Folder {
+            name,
+            headers,
+            vars,
+            children,
+        } => json!({
+            "type": "folder",
+            "name": name,
+            "headers": rows_to_json(headers),
+            "vars": rows_to_json(vars),
+            "children": children.iter().map(node_to_json).collect::<Vec<_>>(),
+        }),
+        CollectionNode::Query { name, request } => json!({
+            "type": "query",
+            "name": name,
+            "request": request_to_json(request),
+        }),
+    }
+}
+
+fn rows_to_json(rows: &[(String, String, bool)]) -> Vec<Value> {
+    rows.iter()
+        .map(|(key, value, enabled)| json!({"key": key, "value": value, "enabled": enabled}))
+        .collect()
+}
+
+fn request_to_json(req: &CollectionRequest) -> Value {
+    json!({
+        "method": req.method.as_str(),
+        "url": req.url,
+        "body": req.body,
+        "timeout_ms": req.timeout_ms,
+        "body_mode": req.body_mode.as_str(),
+        "graphql_variables": req.graphql_variables,
+        "use_default_headers": req.use_default_headers,
+        "headers": rows_to_json(&req.headers),
+        "query_params": rows_to_json(&req.query_params),
+        "form_fields": rows_to_json(&req.form_fields),
+        "auth": {
+            "type": req.auth.auth_type.as_str(),
+            "token": req.auth.token,
+            "username": req.auth.username,
+            "password": req.auth.password,
+            "api_key_header": req.auth.api_key_header,
+            "api_key_value": req.auth.api_key_value,
+        },
+    })
+}
+
+// ── Import ───────────────────────────────────────────────────────────
+
+/// Parse a collection document. The whole document is validated before
+/// anything is written, so a malformed file never half-imports.
+pub fn from_json(doc: &Value) -> Result<Vec<CollectionNode>> {
+    if doc.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        bail!("not an rstools HTTP collection");
+    }
+    let version = doc.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > VERSION {
+        bail!("unsupported collection version {version}");
+    }
+    parse_nodes(doc.get("entries"))
+}
+
+fn parse_nodes(value: Option<&Value>) -> Result<Vec<CollectionNode>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .context("entries must be a list")?
+        .iter()
+        .map(parse_node)
+        .collect()
+}
+
+fn parse_node(value: &Value) -> Result<CollectionNode> {
+    let name = str_field(value, "name");
+    if name.trim().is_empty() || name.contains('/') {
+        bail!("invalid entry name {name:?}");
+    }
+    match value.get("type").and_then(Value::as_str) {
+        Some("folder") => Ok(CollectionNode::Folder {
+            headers: parse_rows(value.get("headers")),
+            vars: parse_rows(value.get("vars")),
+            children: parse_nodes(value.get("children"))
+                .with_context(|| format!("in folder {name:?}"))?,
+            name,
+        }),
+        Some("query") => Ok(CollectionNode::Query {
+            request: Box::new(parse_request(value.get("request").unwrap_or(&Value::Null))),
+            name,
+        }),
+        other => bail!("unknown entry type {other:?} for {name:?}"),
+    }
+}
+
+fn parse_request(value: &Value) -> CollectionRequest {
+    let auth = value.get("auth").unwrap_or(&Value::Null);
+    CollectionRequest {
+        method: HttpMethod::from_str(&str_field(value, "method")),
+        url: str_field(value, "url"),
+        body: str_field(value, "body"),
+        timeout_ms: value.get("timeout_ms").and_then(Value::as_u64),
+        body_mode: BodyMode::parse(&str_field(value, "body_mode")),
+        graphql_variables: str_field(value, "graphql_variables"),
+        use_default_headers: value
+            .get("use_default_headers")
+            .and_then(Value::as_bool)
+            .unwrap_or(true),
+        headers: parse_rows(value.get("headers")),
+        query_params: parse_rows(value.get("query_params")),
+        form_fields: parse_rows(value.get("form_fields")),
+        auth: HttpAuth {
+            auth_type: AuthType::parse(&str_field(auth, "type")),
+            token: str_field(auth, "token"),
+            username: str_field(auth, "username"),
+            password: str_field(auth, "password"),
+            api_key_header: str_field(auth, "api_key_header"),
+            api_key_value: str_field(auth, "api_key_value"),
+        },
+    }
+}
+
+fn parse_rows(value: Option<&Value>) -> Vec<(String, String, bool)> {
+    value
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    (
+                        str_field(row, "key"),
+                        str_field(row, "value"),
+                        row.get("enabled").and_then(Value::as_bool).unwrap_or(true),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A string field of a JSON object, empty when missing.
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Create the nodes under `parent_id` with new IDs. Returns the number of
+/// queries created.
+pub fn import_tree(
+    conn: &Connection,
+    nodes: &[CollectionNode],
+    parent_id: Option<i64>,
+) -> Result<usize> {
+    // All or nothing: a failing node must not leave half a tree behind
+    let tx = conn.unchecked_transaction()?;
+    let mut queries = 0;
+    for node in nodes {
+        import_node(&tx, node, parent_id, &mut queries)?;
+    }
+    tx.commit()?;
+    Ok(queries)
+}
+
+/// Create one node (and its children) under `parent_id`. Returns the new
+/// entry's ID; `queries` is incremented for every query created.
+pub fn import_node(
+    conn: &Connection,
+    node: &CollectionNode,
+    parent_id: Option<i64>,
+    queries: &mut usize,
+) -> Result<i64> {
+    match node {
+        CollectionNode::Folder {
+            name,
+            headers,
+            vars,
+            children,
+        } => {
+            let id = model::add_entry(conn, parent_id, name, EntryType::Folder)?;
+            model::replace_folder_headers(conn, id, headers)?;
+            model::replace_folder_vars(conn, id, vars)?;
+            for child in children {
+                import_node(conn, child, Some(id), queries)?;
+            }
+            Ok(id)
+        }
+        CollectionNode::Query { name, request } => {
+            let id = model::add_entry(conn, parent_id, name, EntryType::Query)?;
+            save_request(conn, id, request)?;
+            *queries += 1;
+            Ok(id)
+        }
+    }
+}
+
+fn save_request(conn: &Connection, entry_id: i64, req: &CollectionRequest) -> Result<()> {
+    let request_id = model::ensure_request(conn, entry_id)?;
+    model::save_request(conn, request_id, req.method, &req.url, &req.body)?;
+    model::set_request_timeout(conn, request_id, req.timeout_ms)?;
+    model::set_request_body_mode(conn, request_id, req.body_mode)?;
+    model::set_request_graphql_variables(conn, request_id, &req.graphql_variables)?;
+    model::set_request_use_default_headers(conn, request_id, req.use_default_headers)?;
+    model::replace_headers(conn, request_id, &req.headers)?;
+    model::replace_query_params(conn, request_id, &req.query_params)?;
+    model::replace_form_fields(conn, request_id, &req.form_fields)?;
+    model::save_auth(conn, request_id, &req.auth)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::db::open_memory_db;
+
+    fn setup() -> Connection {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        conn
+    }
+
+    fn sample_request() -> CollectionRequest {
+        CollectionRequest {
+            method: HttpMethod::Post,
+            url: "https://api.example.com/users".to_string(),
+            body: "{\n  \"name\": \"a\"\n}".to_string(),
+            timeout_ms: Some(1500),
+            body_mode: BodyMode::Raw,
+            graphql_variables: String::new(),
+            use_default_headers: false,
+            headers: vec![
+                ("Accept".to_string(), "application/json".to_string(), true),
+                ("X-Off".to_string(), "1".to_string(), false),
+            ],
+            query_params: vec![("page".to_string(), "2".to_string(), true)],
+            form_fields: vec![("a".to_string(), "b c".to_string(), true)],
+            auth: HttpAuth {
+                auth_type: AuthType::Bearer,
+                token: "${TOKEN}".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source = setup();
+        let api = model::add_entry(&source, None, "api", EntryType::Folder).unwrap();
+        let users = model::add_entry(&source, Some(api), "users", EntryType::Folder).unwrap();
+        let header = ("X-Team".to_string(), "core".to_string(), true);
+        model::replace_folder_headers(&source, api, std::slice::from_ref(&header)).unwrap();
+        let var = ("HOST".to_string(), "api.example.com".to_string(), false);
+        model::replace_folder_vars(&source, users, std::slice::from_ref(&var)).unwrap();
+        let create = model::add_entry(&source, Some(users), "create", EntryType::Query).unwrap();
+        save_request(&source, create, &sample_request()).unwrap();
+        model::add_entry(&source, None, "empty", EntryType::Query).unwrap();
+
+        let tree = load_tree(&source).unwrap();
+        assert_eq!(query_count(&tree), 2);
+        let CollectionNode::Folder {
+            headers, children, ..
+        } = &tree[0]
+        else {
+            panic!("expected the api folder");
+        };
+        assert_eq!(headers, &vec![header]);
+        assert!(matches!(&children[0], CollectionNode::Folder { vars, .. } if *vars == vec![var]));
+        let text = serde_json::to_string_pretty(&to_json(&tree)).unwrap();
+        let parsed = from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(parsed, tree);
+
+        // Import under an existing folder of another database
+        let target = setup();
+        let existing = model::add_entry(&target, None, "shared", EntryType::Folder).unwrap();
+        assert_eq!(import_tree(&target, &parsed, Some(existing)).unwrap(), 2);
+
+        let imported = load_tree(&target).unwrap();
+        let CollectionNode::Folder { name, children, .. } = &imported[0] else {
+            panic!("expected the shared folder");
+        };
+        assert_eq!(name, "shared");
+        assert_eq!(children, &tree);
+    }
+
+    #[test]
+    fn test_import_rejects_malformed_documents() {
+        let bad = [
+            json!({"entries": []}),
+            json!({"format": FORMAT, "version": VERSION + 1, "entries": []}),
+            json!({"format": FORMAT, "entries": {}}),
+            json!({"format": FORMAT, "entries": [{"type": "file", "name": "x"}]}),
+            json!({"format": FORMAT, "entries": [{"type": "query", "name": "a/b"}]}),
+            json!({"format": FORMAT, "entries": [
+                {"type": "folder", "name": "ok", "children": [{"type": "query"}]}
+            ]}),
+        ];
+        for doc in bad {
+            assert!(from_json(&doc).is_err(), "{doc}");
+        }
+
+        // Missing request fields fall back to defaults
+        let doc = json!({"format": FORMAT, "version": 1, "entries": [
+            {"type": "query", "name": "bare"}
+        ]});
+        let nodes = from_json(&doc).unwrap();
+        let CollectionNode::Query { request, .. } = &nodes[0] else {
+            panic!("expected a query");
+        };
+        assert_eq!(request.method, HttpMethod::Get);
+        assert!(request.use_default_headers);
+    }
+}
@@ -1,8 +1,11 @@
+use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::model::HttpMethod;
+use tokio::sync::oneshot;
+
+use crate::model::{AuthType, HttpAuth, HttpMethod};
 
 /// Command sent from the UI thread to the executor thread.
 #[derive(Debug)]
@@ -11,6 +14,11 @@ pub struct HttpRequestCmd {
     pub url: String,
     pub headers: Vec<(String, String)>,
     pub body: String,
+    /// Send this file's contents as the body instead of `body`; it is read
+    /// when the request runs.
+    pub body_file: Option<PathBuf>,
+    /// Abort the request after this many milliseconds (None = no timeout).
+    pub timeout_ms: Option<u64>,
 }
 
 /// Result received from the executor thread.
@@ -21,30 +29,91 @@ pub struct HttpResponseResult {
     pub elapsed_ms: u128,
     pub size_bytes: usize,
     pub headers: Vec<(String, String)>,
-    pub body: String,
+    /// The body bytes exactly as received (may not be UTF-8).
+    pub raw_body: Vec<u8>,
+}
+
+/// What kind of failure an `HttpError` represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpErrorKind {
+    /// The request exceeded its configured timeout.
+    Timeout,
+    /// The connection could not be established.
+    Connect,
+    /// The request was aborted with `HttpExecutor::cancel`.
+    Cancelled,
+    /// Any other failure (invalid URL, body read error, ...).
+    Other,
 }
 
 /// Error from a failed request.
 #[derive(Debug)]
-pub struct HttpRequestError {
+pub struct HttpError {
+    pub kind: HttpErrorKind,
     pub message: String,
 }
 
 /// The result type sent back from the executor.
-pub type ExecutorResult = Result<HttpResponseResult, HttpRequestError>;
+pub type ExecutorResult = Result<HttpResponseResult, HttpError>;
+
+/// Message sent from the UI thread to the executor thread.
+#[derive(Debug)]
+pub enum ExecutorMsg {
+    /// Run a request and send back its result; a message on the receiver
+    /// aborts it with a `Cancelled` error.
+    Request(Box<HttpRequestCmd>, oneshot::Receiver<()>),
+    /// Rebuild the client with a new configuration.
+    Configure(ClientConfig),
+}
+
+/// Settings the worker's `reqwest::Client` is built with.
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfig {
+    pub proxy: ProxyConfig,
+    /// Skip TLS certificate verification. Session-only, never persisted.
+    pub insecure: bool,
+}
+
+impl ClientConfig {
+    /// Build a client that only uses this configuration (never the system proxy).
+    fn build_client(&self) -> reqwest::Client {
+        let mut builder = reqwest::Client::builder()
+            .no_proxy()
+            .danger_accept_invalid_certs(self.insecure);
+        if self.proxy.is_enabled() {
+            let proxy = self.proxy.clone();
+            builder = builder.proxy(reqwest::Proxy::custom(move |url| {
+                proxy.proxy_for(url.as_str()).map(str::to_string)
+            }));
+        }
+        builder.build().unwrap_or_default()
+    }
+}
 
 /// Sender/Receiver pair for communicating with the executor.
 pub struct HttpExecutor {
-    pub sender: mpsc::Sender<HttpRequestCmd>,
+    pub sender: mpsc::Sender<ExecutorMsg>,
     pub receiver: mpsc::Receiver<ExecutorResult>,
+    /// Configuration the worker's client was last built with.
+    config: ClientConfig,
+    /// Aborts the request sent last, if it is still running.
+    cancel: Option<oneshot::Sender<()>>,
 }
 
 impl HttpExecutor {
     /// Spawn the background executor thread with a tokio runtime.
+    ///
+    /// The proxy is read from `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY`;
+    /// certificate verification always starts enabled.
     pub fn spawn() -> Self {
-        let (cmd_tx, cmd_rx) = mpsc::channel::<HttpRequestCmd>();
+        let config = ClientConfig {
+            proxy: ProxyConfig::from_env(),
+            insecure: false,
+        };
+        let (cmd_tx, cmd_rx) = mpsc::channel::<ExecutorMsg>();
         let (result_tx, result_rx) = mpsc::channel::<ExecutorResult>();
 
+        let initial_config = config.clone();
         thread::spawn(move || {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
@@ -52,10 +121,23 @@ impl HttpExecutor {
                 .expect("Failed to create tokio runtime");
 
             rt.block_on(async move {
-                while let Ok(cmd) = cmd_rx.recv() {
-                    let result = execute_request(cmd).await;
-                    if result_tx.send(result).is_err() {
-                        break; // Main thread dropped the receiver
+                let mut client = initial_config.build_client();
+                while let Ok(msg) = cmd_rx.recv() {
+                    match msg {
+                        ExecutorMsg::Request(cmd, cancel) => {
+                            // Dropping the request future aborts the transfer
+                            let result = tokio::select! {
+                                result = execute_request(&client, *cmd) => result,
+                                Ok(()) = cancel => Err(HttpError {
+                                    kind: HttpErrorKind::Cancelled,
+                                    message: "Cancelled".to_string(),
+                                }),
+                            };
+                            if result_tx.send(result).is_err() {
+                                break; // Main thread dropped the receiver
+                            }
+                        }
+                        ExecutorMsg::Configure(config) => client = config.build_client(),
                     }
                 }
             });
@@ -64,12 +146,50 @@ impl HttpExecutor {
         Self {
             sender: cmd_tx,
             receiver: result_rx,
+            config,
+            cancel: None,
         }
     }
 
     /// Send a request command (non-blocking).
-    pub fn send(&self, cmd: HttpRequestCmd) -> Result<(), mpsc::SendError<HttpRequestCmd>> {
-        self.sender.send(cmd)
+    pub fn send(&mut self, cmd: HttpRequestCmd) -> Result<(), mpsc::SendError<ExecutorMsg>> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        self.cancel = Some(cancel_tx);
+        self.sender
+            .send(ExecutorMsg::Request(Box::new(cmd), cancel_rx))
+    }
+
+    /// Abort the request sent last. Its result arrives as a `Cancelled`
+    /// error, unless it had already finished.
+    pub fn cancel(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+
+    /// The proxy configuration requests are currently sent with.
+    pub fn proxy(&self) -> &ProxyConfig {
+        &self.config.proxy
+    }
+
+    /// Replace the proxy configuration; the worker rebuilds its client
+    /// before handling the next request.
+    pub fn set_proxy(&mut self, proxy: ProxyConfig) -> Result<(), mpsc::SendError<ExecutorMsg>> {
+        self.config.proxy = proxy;
+        self.sender
+            .send(ExecutorMsg::Configure(self.config.clone()))
+    }
+
+    /// Whether TLS certificate verification is disabled.
+    pub fn insecure(&self) -> bool {
+        self.config.insecure
+    }
+
+    /// Enable or disable TLS certificate verification for this session.
+    pub fn set_insecure(&mut self, insecure: bool) -> Result<(), mpsc::SendError<ExecutorMsg>> {
+        self.config.insecure = insecure;
+        self.sender
+            .send(ExecutorMsg::Configure(self.config.clone()))
     }
 
     /// Try to receive a result (non-blocking).
@@ -78,10 +198,104 @@ impl HttpExecutor {
     }
 }
 
-/// Execute an HTTP request using reqwest.
-async fn execute_request(cmd: HttpRequestCmd) -> ExecutorResult {
-    let client = reqwest::Client::new();
+// ── Proxy ────────────────────────────────────────────────────────────
+
+/// Proxies used for outgoing requests, per URL scheme.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy for `http://` URLs.
+    pub http: Option<String>,
+    /// Proxy for `https://` URLs.
+    pub https: Option<String>,
+    /// Hosts (or domain suffixes) that bypass the proxy; `*` matches all.
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Read `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` (or their lowercase forms).
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build the configuration from a variable lookup (upper case wins).
+    pub fn from_vars(get: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            get(name)
+                .or_else(|| get(&name.to_ascii_lowercase()))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            http: var("HTTP_PROXY"),
+            https: var("HTTPS_PROXY"),
+            no_proxy: var("NO_PROXY")
+                .map(|v| {
+                    v.split(',')
+                        .map(|h| h.trim().to_ascii_lowercase())
+                        .filter(|h| !h.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Route both schemes through `url`, or disable proxying with `None`.
+    /// `NO_PROXY` exclusions are kept.
+    pub fn with_override(&self, url: Option<&str>) -> Self {
+        Self {
+            http: url.map(str::to_string),
+            https: url.map(str::to_string),
+            no_proxy: self.no_proxy.clone(),
+        }
+    }
+
+    /// Whether any proxy is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.http.is_some() || self.https.is_some()
+    }
+
+    /// Whether `host` matches a `NO_PROXY` entry.
+    pub fn is_excluded(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.no_proxy.iter().any(|entry| {
+            // Entries may carry a port (`localhost:8080`) or a leading dot
+            let entry = entry.split_once(':').map_or(entry.as_str(), |(h, _)| h);
+            let entry = entry.trim_start_matches('.');
+            entry == "*"
+                || host.eq_ignore_ascii_case(entry)
+                || host.to_ascii_lowercase().ends_with(&format!(".{entry}"))
+        })
+    }
+
+    /// The proxy a request to `url` goes through, if any.
+    pub fn proxy_for(&self, url: &str) -> Option<&str> {
+        let url = reqwest::Url::parse(url).ok()?;
+        if self.is_excluded(url.host_str()?) {
+            return None;
+        }
+        match url.scheme() {
+            "http" => self.http.as_deref(),
+            "https" => self.https.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Short description for notifications.
+    pub fn describe(&self) -> String {
+        match (&self.http, &self.https) {
+            (None, None) => "off".to_string(),
+            (Some(http), Some(https)) if http == https => http.clone(),
+            (http, https) => format!(
+                "http {} / https {}",
+                http.as_deref().unwrap_or("-"),
+                https.as_deref().unwrap_or("-")
+            ),
+        }
+    }
+}
 
+/// Execute an HTTP request using reqwest.
+async fn execute_request(client: &reqwest::Client, cmd: HttpRequestCmd) -> ExecutorResult {
     let method = match cmd.method {
         HttpMethod::Get => reqwest::Method::GET,
         HttpMethod::Post => reqwest::Method::POST,
@@ -98,10 +312,24 @@ async fn execute_request(cmd: HttpRequestCmd) -> ExecutorResult {
         builder = builder.header(key, value);
     }
 
-    if !cmd.body.is_empty() {
+    if let Some(path) = &cmd.body_file {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => builder = builder.body(bytes),
+            Err(e) => {
+                return Err(HttpError {
+                    kind: HttpErrorKind::Other,
+                    message: format!("Failed to read body file {}: {e}", path.display()),
+                });
+            }
+        }
+    } else if !cmd.body.is_empty() {
         builder = builder.body(cmd.body);
     }
 
+    if let Some(ms) = cmd.timeout_ms {
+        builder = builder.timeout(Duration::from_millis(ms));
+    }
+
     let start = Instant::now();
 
     match builder.send().await {
@@ -121,26 +349,520 @@ async fn execute_request(cmd: HttpRequestCmd) -> ExecutorResult {
                 .collect();
 
             match response.bytes().await {
-                Ok(bytes) => {
-                    let size_bytes = bytes.len();
-                    let body = String::from_utf8_lossy(&bytes).to_string();
-
-                    Ok(HttpResponseResult {
-                        status_code,
-                        status_text,
-                        elapsed_ms: elapsed,
-                        size_bytes,
-                        headers,
-                        body,
-                    })
-                }
-                Err(e) => Err(HttpRequestError {
-                    message: format!("Failed to read response body: {e}"),
+                Ok(bytes) => Ok(HttpResponseResult {
+                    status_code,
+                    status_text,
+                    elapsed_ms: elapsed,
+                    size_bytes: bytes.len(),
+                    headers,
+                    raw_body: bytes.to_vec(),
                 }),
+                Err(e) => Err(request_error(
+                    e,
+                    cmd.timeout_ms,
+                    "Failed to read response body: ",
+                )),
             }
         }
-        Err(e) => Err(HttpRequestError {
-            message: format!("{e}"),
-        }),
+        Err(e) => Err(request_error(e, cmd.timeout_ms, "")),
+    }
+}
+
+/// Classify a reqwest error into an `HttpError`.
+fn request_error(e: reqwest::Error, timeout_ms: Option<u64>, prefix: &str) -> HttpError {
+    if e.is_timeout() {
+        let message = match timeout_ms {
+            Some(ms) => format!("Request timed out after {ms} ms"),
+            None => "Request timed out".to_string(),
+        };
+        return HttpError {
+            kind: HttpErrorKind::Timeout,
+            message,
+        };
+    }
+
+    let kind = if e.is_connect() {
+        HttpErrorKind::Connect
+    } else {
+        HttpErrorKind::Other
+    };
+    HttpError {
+        kind,
+        message: format!("{prefix}{e}"),
+    }
+}
+
+// ── cURL import ──────────────────────────────────────────────────────
+
+/// A request parsed from a cURL command line.
+#[derive(Debug)]
+pub struct CurlCommand {
+    pub request: HttpRequestCmd,
+    /// Basic auth from `-u`/`--user`, if given.
+    pub auth: Option<HttpAuth>,
+}
+
+/// Flags taking no value that `parse_curl` skips.
+const CURL_SWITCHES: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-L",
+    "--location",
+    "-k",
+    "--insecure",
+    "-i",
+    "--include",
+    "-v",
+    "--verbose",
+    "-f",
+    "--fail",
+    "-g",
+    "--globoff",
+    "-N",
+    "--no-buffer",
+    "--compressed",
+    "--http1.1",
+    "--http2",
+];
+
+/// Flags taking a value that `parse_curl` skips.
+const CURL_SKIPPED_OPTIONS: &[&str] = &[
+    "-o",
+    "--output",
+    "-A",
+    "--user-agent",
+    "-b",
+    "--cookie",
+    "-c",
+    "--cookie-jar",
+    "-e",
+    "--referer",
+    "-x",
+    "--proxy",
+    "-w",
+    "--write-out",
+    "--connect-timeout",
+    "--retry",
+    "--cacert",
+    "--cert",
+    "--key",
+    "--resolve",
+];
+
+/// Body flags `parse_curl` cannot represent; importing them is an error
+/// rather than a request with a different body.
+const CURL_UNSUPPORTED_OPTIONS: &[&str] = &[
+    "-F",
+    "--form",
+    "--form-string",
+    "--data-urlencode",
+    "-T",
+    "--upload-file",
+    "--json",
+];
+
+/// Parse a cURL command line (as copied from browser devtools) into a request.
+///
+/// Understands `-X`/`--request`, `-H`/`--header`, `-d`/`--data`,
+/// `--data-raw`, `--data-binary`, `-m`/`--max-time`, `-u`/`--user`,
+/// `--url` and a bare URL argument. Known flags in `CURL_SWITCHES` and
+/// `CURL_SKIPPED_OPTIONS` are ignored; anything else (including the body
+/// flags in `CURL_UNSUPPORTED_OPTIONS`) is an error, as is input that is
+/// not a `curl` command or has no URL.
+pub fn parse_curl(input: &str) -> Result<CurlCommand, String> {
+    let tokens = split_shell_words(input).ok_or("unterminated quote")?;
+    let mut tokens = tokens.into_iter();
+    if tokens.next().as_deref() != Some("curl") {
+        return Err("not a curl command".to_string());
+    }
+
+    let mut method = None;
+    let mut url = None;
+    let mut headers = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut timeout_ms = None;
+    let mut auth = None;
+
+    while let Some(token) = tokens.next() {
+        // Long options may carry their value inline: `--data=...`
+        let (flag, inline) = match token.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (token.clone(), None),
+        };
+        let mut value = |inline: Option<&str>| match inline {
+            Some(v) => Ok(v.to_string()),
+            None => tokens.next().ok_or(format!("{flag} needs a value")),
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(parse_method(&value(inline)?)?),
+            "-H" | "--header" => headers.push(parse_header(&value(inline)?)?),
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => {
+                data.push(value(inline)?);
+            }
+            "--url" => url = Some(value(inline)?),
+            "-m" | "--max-time" => {
+                let secs = value(inline)?;
+                let secs: f64 = secs
+                    .parse()
+                    .map_err(|_| format!("invalid --max-time {secs}"))?;
+                timeout_ms = Some((secs * 1000.0) as u64);
+            }
+            "-u" | "--user" => {
+                let user = value(inline)?;
+                let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+                auth = Some(HttpAuth {
+                    auth_type: AuthType::Basic,
+                    username: username.to_string(),
+                    password: password.to_string(),
+                    ..HttpAuth::default()
+                });
+            }
+            f if CURL_SWITCHES.contains(&f) => {}
+            f if CURL_SKIPPED_OPTIONS.contains(&f) => {
+                value(inline)?;
+            }
+            f if CURL_UNSUPPORTED_OPTIONS.contains(&f) => {
+                return Err(format!("{f} is not supported"));
+            }
+            // Short flags with the value attached: `-XPOST`, `-HAccept: x`
+            f if f.starts_with("-X") && f.len() > 2 => {
+                method = Some(parse_method(&f[2..])?);
+            }
+            f if f.starts_with("-H") && f.len() > 2 => headers.push(parse_header(&f[2..])?),
+            // Bundled short switches: `-sSL`
+            f if f.len() > 2
+                && !f.starts_with("--")
+                && f[1..]
+                    .chars()
+                    .all(|c| CURL_SWITCHES.contains(&format!("-{c}").as_str())) => {}
+            f if f.starts_with('-') => return Err(format!("unknown option {f}")),
+            _ => url = Some(token),
+        }
+    }
+
+    let url = url.ok_or("missing URL")?;
+    let body = data.join("&");
+    let method = method.unwrap_or(if body.is_empty() {
+        HttpMethod::Get
+    } else {
+        HttpMethod::Post
+    });
+
+    Ok(CurlCommand {
+        request: HttpRequestCmd {
+            method,
+            url,
+            headers,
+            body,
+            body_file: None,
+            timeout_ms,
+        },
+        auth,
+    })
+}
+
+/// Strict method parsing: unlike `HttpMethod::from_str`, unknown methods
+/// are rejected instead of falling back to GET.
+fn parse_method(s: &str) -> Result<HttpMethod, String> {
+    let method = HttpMethod::from_str(s);
+    if method.as_str() == s.to_uppercase() {
+        Ok(method)
+    } else {
+        Err(format!("unknown method {s}"))
+    }
+}
+
+/// Split a `Key: value` header argument.
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    let (key, val) = header
+        .split_once(':')
+        .ok_or(format!("invalid header {header}"))?;
+    Ok((key.trim().to_string(), val.trim().to_string()))
+}
+
+/// Split a command line into words using POSIX shell quoting rules:
+/// single quotes, double quotes, backslash escapes and `\` line
+/// continuations. Returns None on an unterminated quote.
+fn split_shell_words(input: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        ch => current.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            ch @ ('"' | '\\' | '$' | '`') => current.push(ch),
+                            '\n' => {}
+                            ch => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                        },
+                        ch => current.push(ch),
+                    }
+                }
+            }
+            // A backslash before whitespace is a line continuation (possibly
+            // with the newline already folded away by a single-line input).
+            '\\' => match chars.next() {
+                Some(ch) if !ch.is_whitespace() => {
+                    in_word = true;
+                    current.push(ch);
+                }
+                Some(_) if in_word => {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+                _ => {}
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_curl_devtools() {
+        let input = "curl 'https://api.example.com/users?page=2' \\\n  -X 'PUT' \\\n  -H 'Accept: application/json' \\\n  -H \"Authorization: Bearer abc\" \\\n  --data-raw '{\"name\":\"Ada\"}' \\\n  --compressed";
+        let cmd = parse_curl(input).unwrap().request;
+        assert_eq!(cmd.method, HttpMethod::Put);
+        assert_eq!(cmd.url, "https://api.example.com/users?page=2");
+        assert_eq!(
+            cmd.headers,
+            vec![
+                ("Accept".to_string(), "application/json".to_string()),
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+            ]
+        );
+        assert_eq!(cmd.body, r#"{"name":"Ada"}"#);
+
+        // Same command pasted into a single-line input
+        let folded = parse_curl(&input.replace('\n', "")).unwrap().request;
+        assert_eq!(folded.url, cmd.url);
+        assert_eq!(folded.headers, cmd.headers);
+        assert_eq!(folded.body, cmd.body);
+    }
+
+    #[test]
+    fn test_parse_curl_defaults_and_short_forms() {
+        let cmd = parse_curl("curl https://example.com").unwrap().request;
+        assert_eq!(cmd.method, HttpMethod::Get);
+        assert!(cmd.body.is_empty());
+
+        // Data without -X implies POST; repeated -d joins with '&'
+        let cmd = parse_curl("curl -d a=1 --data=b=2 https://example.com")
+            .unwrap()
+            .request;
+        assert_eq!(cmd.method, HttpMethod::Post);
+        assert_eq!(cmd.body, "a=1&b=2");
+
+        let cmd = parse_curl("curl -XDELETE --url https://example.com/1")
+            .unwrap()
+            .request;
+        assert_eq!(cmd.method, HttpMethod::Delete);
+        assert_eq!(cmd.url, "https://example.com/1");
+        assert_eq!(cmd.timeout_ms, None);
+
+        let cmd = parse_curl("curl -m 2.5 https://example.com")
+            .unwrap()
+            .request;
+        assert_eq!(cmd.timeout_ms, Some(2500));
+    }
+
+    #[test]
+    fn test_parse_curl_skipped_options_and_auth() {
+        // Skipped options consume their value instead of taking the URL's place
+        let parsed = parse_curl(
+            "curl -sSL -x http://proxy:3128 -w '%{http_code}' -o out.json -k https://example.com",
+        )
+        .unwrap();
+        assert_eq!(parsed.request.url, "https://example.com");
+        assert!(parsed.auth.is_none());
+
+        let parsed = parse_curl("curl -u 'ada:s3cr:et' https://example.com").unwrap();
+        let auth = parsed.auth.unwrap();
+        assert_eq!(auth.auth_type, AuthType::Basic);
+        assert_eq!(auth.username, "ada");
+        assert_eq!(auth.password, "s3cr:et");
+
+        let auth = parse_curl("curl --user=ada https://example.com")
+            .unwrap()
+            .auth
+            .unwrap();
+        assert_eq!(auth.username, "ada");
+        assert_eq!(auth.password, "");
+    }
+
+    #[test]
+    fn test_timeout_error_kind() {
+        // A server that accepts connections but never responds
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut executor = HttpExecutor::spawn();
+        executor
+            .send(HttpRequestCmd {
+                method: HttpMethod::Get,
+                url: format!("http://{addr}/"),
+                headers: Vec::new(),
+                body: String::new(),
+                body_file: None,
+                timeout_ms: Some(100),
+            })
+            .unwrap();
+
+        let result = executor
+            .receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap();
+        let err = result.unwrap_err();
+        assert_eq!(err.kind, HttpErrorKind::Timeout);
+        assert_eq!(err.message, "Request timed out after 100 ms");
+        drop(listener);
+    }
+
+    #[test]
+    fn test_cancel_request() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut executor = HttpExecutor::spawn();
+        executor
+            .send(HttpRequestCmd {
+                method: HttpMethod::Get,
+                url: format!("http://{addr}/"),
+                headers: Vec::new(),
+                body: String::new(),
+                body_file: None,
+                timeout_ms: None,
+            })
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        executor.cancel();
+
+        let result = executor
+            .receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(result.unwrap_err().kind, HttpErrorKind::Cancelled);
+        // Nothing left to cancel
+        executor.cancel();
+        drop(listener);
+    }
+
+    #[test]
+    fn test_proxy_config() {
+        let vars = [
+            ("HTTP_PROXY", "http://proxy:3128"),
+            ("https_proxy", "http://secure:3128"),
+            ("NO_PROXY", "localhost, .internal.example,127.0.0.1:8080"),
+        ];
+        let config = ProxyConfig::from_vars(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        });
+
+        assert_eq!(
+            config.proxy_for("http://api.example.com/x"),
+            Some("http://proxy:3128")
+        );
+        assert_eq!(
+            config.proxy_for("https://api.example.com"),
+            Some("http://secure:3128")
+        );
+        assert_eq!(config.proxy_for("http://localhost:3000/"), None);
+        assert_eq!(config.proxy_for("http://127.0.0.1/"), None);
+        assert_eq!(config.proxy_for("https://db.internal.example"), None);
+        assert_eq!(config.proxy_for("https://internal.example"), None);
+        assert_eq!(
+            config.proxy_for("https://notinternal.example"),
+            Some("http://secure:3128")
+        );
+        assert_eq!(config.proxy_for("not a url"), None);
+
+        // A runtime override keeps the exclusions
+        let overridden = config.with_override(Some("http://other:8080"));
+        assert_eq!(
+            overridden.proxy_for("https://api.example.com"),
+            Some("http://other:8080")
+        );
+        assert_eq!(overridden.proxy_for("http://localhost/"), None);
+        assert_eq!(overridden.describe(), "http://other:8080");
+
+        let off = config.with_override(None);
+        assert!(!off.is_enabled());
+        assert_eq!(off.proxy_for("http://api.example.com"), None);
+        assert_eq!(off.describe(), "off");
+
+        let all = ProxyConfig {
+            no_proxy: vec!["*".to_string()],
+            ..overridden
+        };
+        assert_eq!(all.proxy_for("http://api.example.com"), None);
+    }
+
+    #[test]
+    fn test_parse_curl_malformed() {
+        assert_eq!(parse_curl("").unwrap_err(), "not a curl command");
+        assert!(parse_curl("wget https://example.com").is_err());
+        assert_eq!(parse_curl("curl -X POST").unwrap_err(), "missing URL");
+        assert_eq!(
+            parse_curl("curl 'https://example.com").unwrap_err(),
+            "unterminated quote"
+        );
+        assert!(parse_curl("curl -X FETCH https://example.com").is_err());
+        assert!(parse_curl("curl -H 'NoColon' https://example.com").is_err());
+        assert_eq!(
+            parse_curl("curl https://example.com -H").unwrap_err(),
+            "-H needs a value"
+        );
+
+        // Bodies that can't be represented are refused, not guessed
+        assert_eq!(
+            parse_curl("curl https://x -F a=b").unwrap_err(),
+            "-F is not supported"
+        );
+        assert!(parse_curl("curl --data-urlencode q=a https://x").is_err());
+        assert!(parse_curl("curl -T file.bin https://x").is_err());
+        assert_eq!(
+            parse_curl("curl --frobnicate https://x").unwrap_err(),
+            "unknown option --frobnicate"
+        );
     }
 }
@@ -1,15 +1,21 @@
+pub mod body_format;
+pub mod collection;
 pub mod executor;
 pub mod model;
+pub mod postman;
 pub mod request_panel;
 pub mod sidebar;
 pub mod ui;
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::time::Instant;
 
+use rstools_core::confirm::ConfirmPrompt;
 use rstools_core::help_popup::HelpEntry;
-use rstools_core::keybinds::{Action, InputMode, KeyState, process_normal_key};
-use rstools_core::telescope::TelescopeItem;
+use rstools_core::keybinds::{Action, InputMode, KeyAction, KeyState, keymap, process_normal_key};
+use rstools_core::paths::expand_tilde;
+use rstools_core::telescope::{self, TelescopeItem};
+use rstools_core::toast::Toast;
 use rstools_core::tool::Tool;
 use rstools_core::which_key::WhichKeyEntry;
 
@@ -17,16 +23,106 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
 use ratatui::{Frame, layout::Rect};
 use rusqlite::Connection;
 
-use executor::{HttpExecutor, HttpRequestCmd};
+use body_format::BodyLanguage;
+use executor::{HttpErrorKind, HttpExecutor, HttpRequestCmd, parse_curl};
 use model::EntryType;
-use request_panel::{KvField, PanelFocus, RequestPanel, ResponseData, ResponseSection, Section};
+use request_panel::{
+    AuthField, KvField, KvRow, PanelFocus, RequestPanel, ResponseData, ResponseSection, Section,
+    WARNING_SEPARATOR,
+};
 use sidebar::{ClipboardMode, HttpSidebarExt, SidebarInput, SidebarState};
 
+/// Shown when `:savebody`, `yb` or `P` need the bytes of a history response
+/// stored before raw bodies were kept.
+const RAW_BODY_MISSING: &str = "Raw body not stored for this response";
+
 /// Cached response data for a query, keyed by entry_id.
-/// Allows restoring the last response when switching back to a previously-run query.
+/// Allows restoring the last response and where the user was in the panel
+/// (focus and response scroll) when switching back to a query.
 struct CachedResponse {
     response: Option<ResponseData>,
     error_message: Option<String>,
+    cancelled: bool,
+    focused_section: Section,
+    panel_focus: PanelFocus,
+}
+
+/// The different input prompts the tool can show.
+pub enum InputPrompt {
+    /// Paste a cURL command to import into the current request.
+    CurlImport { buffer: String },
+    /// Dotted path (`data.items.0.id`) of a response JSON value to copy.
+    JsonPath { buffer: String },
+}
+
+impl InputPrompt {
+    /// The text typed into the prompt.
+    pub fn buffer(&self) -> &str {
+        match self {
+            InputPrompt::CurlImport { buffer } | InputPrompt::JsonPath { buffer } => buffer,
+        }
+    }
+
+    fn buffer_mut(&mut self) -> &mut String {
+        match self {
+            InputPrompt::CurlImport { buffer } | InputPrompt::JsonPath { buffer } => buffer,
+        }
+    }
+}
+
+/// State of the response history overlay for the current query.
+pub struct ResponseHistory {
+    /// Stored responses, newest first.
+    pub records: Vec<model::HttpResponseRecord>,
+    pub selected: usize,
+}
+
+/// The most recently deleted sidebar entry, kept for a one-step undo.
+struct DeletedEntry {
+    parent_id: Option<i64>,
+    node: collection::CollectionNode,
+}
+
+/// Which key/value set the overlay edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KvSet {
+    /// The global default headers.
+    DefaultHeaders,
+    /// The headers a folder passes down to its queries.
+    FolderHeaders { folder_id: i64, folder: String },
+    /// The variables a folder passes down to its queries.
+    FolderVars { folder_id: i64, folder: String },
+}
+
+impl KvSet {
+    pub fn title(&self) -> String {
+        match self {
+            KvSet::DefaultHeaders => "Default Headers".to_string(),
+            KvSet::FolderHeaders { folder, .. } => format!("Headers of {folder}"),
+            KvSet::FolderVars { folder, .. } => format!("Variables of {folder}"),
+        }
+    }
+
+    /// Text shown when the set has no rows.
+    pub fn empty_hint(&self) -> &'static str {
+        match self {
+            KvSet::DefaultHeaders => "No default headers. Press 'a' to add.",
+            KvSet::FolderHeaders { .. } => "No folder headers. Press 'a' to add.",
+            KvSet::FolderVars { .. } => "No folder variables. Press 'a' to add.",
+        }
+    }
+}
+
+/// State of the key/value overlay (default headers, folder headers/variables).
+pub struct KvSetEditor {
+    pub target: KvSet,
+    pub rows: Vec<KvRow>,
+    pub selected: usize,
+    /// Field being edited, or None when navigating. The cursor is the row's
+    /// `cursor`.
+    pub editing: Option<KvField>,
+    /// Confirmation shown before `d` removes the selected row.
+    pub delete_prompt: Option<ConfirmPrompt>,
 }
 
 pub struct HttpTool {
@@ -40,9 +136,24 @@ pub struct HttpTool {
     sidebar_focused: bool,
     /// In-memory cache of the last response per query (keyed by entry_id).
     response_cache: HashMap<i64, CachedResponse>,
+    /// Status and duration of each query's last stored response, shown in
+    /// the sidebar.
+    last_runs: HashMap<i64, model::LastRun>,
+    /// Telescope previews by entry_id, loaded once per telescope opening.
+    preview_cache: RefCell<HashMap<i64, String>>,
     clipboard: Option<arboard::Clipboard>,
-    notification: Option<String>,
-    notification_shown_at: Option<Instant>,
+    /// Toasts raised since the hub last drained them.
+    toasts: Vec<Toast>,
+    /// Current input prompt overlay.
+    input_prompt: Option<InputPrompt>,
+    /// Response history overlay (None = hidden).
+    history: Option<ResponseHistory>,
+    /// Default headers / folder values overlay (None = hidden).
+    kv_set_editor: Option<KvSetEditor>,
+    /// Query most recently sent this session, for `<Space>r`.
+    last_sent_entry_id: Option<i64>,
+    /// Snapshot of the last deleted subtree, restored with `u` in the sidebar.
+    last_deleted: Option<DeletedEntry>,
 }
 
 impl HttpTool {
@@ -52,39 +163,249 @@ impl HttpTool {
         HttpSidebarExt::reload(&mut sidebar, &conn)?;
         let executor = HttpExecutor::spawn();
         let clipboard = arboard::Clipboard::new().ok();
+        let mut panel = RequestPanel::new();
+        if let Some(env) = model::active_environment(&conn)? {
+            let vars = model::load_env_vars(&conn, env.id)?;
+            panel.set_environment(
+                Some(env.name),
+                vars.into_iter().map(|v| (v.key, v.value)).collect(),
+            );
+        }
+        panel.set_default_headers(enabled_default_headers(&conn)?);
+        panel.proxy = executor.proxy().clone();
+        let last_runs = model::last_runs(&conn)?;
         Ok(Self {
             sidebar,
-            panel: RequestPanel::new(),
+            panel,
             mode: InputMode::Normal,
             key_state: KeyState::default(),
             conn,
             executor,
             sidebar_focused: true,
             response_cache: HashMap::new(),
+            last_runs,
+            preview_cache: RefCell::new(HashMap::new()),
             clipboard,
-            notification: None,
-            notification_shown_at: None,
+            toasts: Vec::new(),
+            input_prompt: None,
+            history: None,
+            kv_set_editor: None,
+            last_sent_entry_id: None,
+            last_deleted: None,
         })
     }
 
     fn show_notification(&mut self, message: impl Into<String>) {
-        self.notification = Some(message.into());
-        self.notification_shown_at = Some(Instant::now());
+        self.toasts.push(Toast::info(message));
+    }
+
+    /// Activate the named environment (creating it if needed), or clear the
+    /// active environment when `name` is None.
+    fn select_environment(&mut self, name: Option<&str>) -> anyhow::Result<()> {
+        let Some(name) = name else {
+            model::set_active_environment(&self.conn, None)?;
+            self.panel.set_environment(None, Vec::new());
+            self.show_notification("Environment cleared");
+            return Ok(());
+        };
+
+        let env_id = model::ensure_environment(&self.conn, name)?;
+        model::set_active_environment(&self.conn, Some(env_id))?;
+        self.reload_environment()?;
+        self.show_notification(format!("Environment: {name}"));
+        Ok(())
     }
 
-    fn copy_response_body_to_clipboard(&mut self) {
+    /// Reload the active environment's variables into the panel.
+    fn reload_environment(&mut self) -> anyhow::Result<()> {
+        match model::active_environment(&self.conn)? {
+            Some(env) => {
+                let vars = model::load_env_vars(&self.conn, env.id)?;
+                self.panel.set_environment(
+                    Some(env.name),
+                    vars.into_iter().map(|v| (v.key, v.value)).collect(),
+                );
+            }
+            None => self.panel.set_environment(None, Vec::new()),
+        }
+        Ok(())
+    }
+
+    /// Set (`value = Some`) or remove a variable in the active environment.
+    fn set_environment_var(&mut self, key: &str, value: Option<&str>) -> anyhow::Result<()> {
+        let Some(env) = model::active_environment(&self.conn)? else {
+            self.show_notification("No active environment (use :env <name>)");
+            return Ok(());
+        };
+
+        match value {
+            Some(value) => model::set_env_var(&self.conn, env.id, key, value)?,
+            None => model::delete_env_var(&self.conn, env.id, key)?,
+        }
+        self.reload_environment()
+    }
+
+    /// Open the cURL import prompt for the current query.
+    fn open_curl_prompt(&mut self) {
+        if !self.panel.is_active() {
+            self.show_notification("Open a query to import into");
+            return;
+        }
+        self.input_prompt = Some(InputPrompt::CurlImport {
+            buffer: String::new(),
+        });
+    }
+
+    /// Parse a cURL command into the current query; the leading `curl` may
+    /// be left out. On malformed input the panel is left untouched and an
+    /// error is shown.
+    fn import_curl(&mut self, text: &str) {
+        if !self.panel.is_active() {
+            self.show_notification("Open a query to import into");
+            return;
+        }
+
+        let command = match text.split_whitespace().next() {
+            Some("curl") => text.to_string(),
+            _ => format!("curl {text}"),
+        };
+        match parse_curl(&command) {
+            Ok(curl) => {
+                self.panel.apply_request_cmd(curl.request);
+                if let Some(auth) = curl.auth {
+                    self.panel.auth = auth;
+                }
+                self.show_notification("Imported cURL command");
+            }
+            Err(e) => {
+                self.panel.error_message = Some(format!("Could not parse cURL command: {e}"));
+            }
+        }
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) -> Action {
+        let Some(mut prompt) = self.input_prompt.take() else {
+            return Action::None;
+        };
+
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => match prompt {
+                InputPrompt::CurlImport { buffer } => self.import_curl(&buffer),
+                InputPrompt::JsonPath { buffer } => self.copy_json_path(&buffer),
+            },
+            KeyCode::Char(c) => {
+                prompt.buffer_mut().push(c);
+                self.input_prompt = Some(prompt);
+            }
+            KeyCode::Backspace => {
+                prompt.buffer_mut().pop();
+                self.input_prompt = Some(prompt);
+            }
+            _ => {
+                self.input_prompt = Some(prompt);
+            }
+        }
+        Action::None
+    }
+
+    /// Open the JSON path prompt for the current response.
+    fn open_json_path_prompt(&mut self) {
+        if self.panel.response.is_none() {
+            self.show_notification("No response to extract from");
+            return;
+        }
+        self.input_prompt = Some(InputPrompt::JsonPath {
+            buffer: String::new(),
+        });
+    }
+
+    /// Resolve a dotted path against the response JSON and copy the value.
+    /// Strings are copied without quotes; objects and arrays as pretty JSON.
+    fn copy_json_path(&mut self, path: &str) {
+        let Some(response) = self.panel.response.as_ref() else {
+            return;
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&response.body) else {
+            self.show_notification("Response body is not JSON");
+            return;
+        };
+        let Some(value) = body_format::json_path_lookup(&json, path) else {
+            self.show_notification(format!("No value at path '{}'", path.trim()));
+            return;
+        };
+
+        let text = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Object(_) | serde_json::Value::Array(_) => {
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            }
+            other => other.to_string(),
+        };
+
+        if let Some(ref mut clipboard) = self.clipboard {
+            if clipboard.set_text(text).is_ok() {
+                self.show_notification(format!("Copied {}", path.trim()));
+            } else {
+                self.show_notification("Failed to copy value");
+            }
+        } else {
+            self.show_notification("Clipboard unavailable");
+        }
+    }
+
+    /// Copy the focused response section to the clipboard: the full body,
+    /// or the headers as `Name: value` lines.
+    fn copy_response_to_clipboard(&mut self) {
         let Some(response) = self.panel.response.as_ref() else {
             return;
         };
 
-        if response.body.trim().is_empty() {
+        let (text, what, empty) = match response.focused_section {
+            ResponseSection::Body => (
+                response.shown_body().to_string(),
+                "body",
+                "Response body is empty",
+            ),
+            ResponseSection::Headers => (response.headers_text(), "headers", "No response headers"),
+        };
+        if text.trim().is_empty() {
+            self.show_notification(empty);
+            return;
+        }
+
+        if let Some(ref mut clipboard) = self.clipboard {
+            if clipboard.set_text(text).is_ok() {
+                self.show_notification(format!("Copied {what}"));
+            } else {
+                self.show_notification(format!("Failed to copy response {what}"));
+            }
+        } else {
+            self.show_notification("Clipboard unavailable");
+        }
+    }
+
+    /// Copy the unformatted response body as base64, for binary bodies the
+    /// clipboard cannot hold as text.
+    fn copy_body_base64(&mut self) {
+        use base64::Engine;
+
+        let Some(response) = self.panel.response.as_ref() else {
+            return;
+        };
+        let Some(raw_body) = response.raw_body.as_ref() else {
+            self.show_notification(RAW_BODY_MISSING);
+            return;
+        };
+        if raw_body.is_empty() {
             self.show_notification("Response body is empty");
             return;
         }
 
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw_body);
         if let Some(ref mut clipboard) = self.clipboard {
-            if clipboard.set_text(response.body.clone()).is_ok() {
-                self.show_notification("Copied response body");
+            if clipboard.set_text(encoded).is_ok() {
+                self.show_notification("Copied body as base64");
             } else {
                 self.show_notification("Failed to copy response body");
             }
@@ -93,6 +414,140 @@ impl HttpTool {
         }
     }
 
+    /// Set the current query's timeout from a `:timeout` argument
+    /// (milliseconds, or `off`/`0` to disable). Marks the panel dirty.
+    fn set_timeout(&mut self, arg: &str) {
+        if !self.panel.is_active() {
+            self.show_notification("Open a query to set its timeout");
+            return;
+        }
+
+        if arg.is_empty() {
+            let current = match self.panel.timeout_ms {
+                Some(ms) => format!("Timeout: {ms} ms"),
+                None => "Timeout: off".to_string(),
+            };
+            self.show_notification(current);
+            return;
+        }
+
+        let timeout = match arg {
+            "off" | "0" => None,
+            _ => match arg.parse::<u64>() {
+                Ok(ms) => Some(ms),
+                Err(_) => {
+                    self.show_notification("Usage: :timeout <ms> | off");
+                    return;
+                }
+            },
+        };
+
+        if self.panel.timeout_ms != timeout {
+            self.panel.timeout_ms = timeout;
+            self.panel.dirty = true;
+        }
+    }
+
+    fn copy_request_as_curl(&mut self) {
+        let curl = self.panel.to_curl(self.executor.insecure());
+
+        if let Some(ref mut clipboard) = self.clipboard {
+            if clipboard.set_text(curl).is_ok() {
+                self.show_notification("Copied cURL");
+            } else {
+                self.show_notification("Failed to copy cURL");
+            }
+        } else {
+            self.show_notification("Clipboard unavailable");
+        }
+    }
+
+    /// Write the current response body, unformatted, to `path` (`~` expanded).
+    /// Returns false when there is no response to save.
+    fn save_response_body(&mut self, path: &str) -> bool {
+        let Some(ref response) = self.panel.response else {
+            return false;
+        };
+        if path.is_empty() {
+            self.show_notification("Usage: :savebody <path>");
+            return true;
+        }
+
+        let Some(raw_body) = response.raw_body.as_ref() else {
+            self.show_notification(RAW_BODY_MISSING);
+            return true;
+        };
+
+        let path = expand_tilde(path);
+        let len = raw_body.len();
+        match std::fs::write(&path, raw_body) {
+            Ok(()) => self.show_notification(format!("Saved {len} bytes to {path}")),
+            Err(e) => self.show_notification(format!("Failed to save body: {e}")),
+        }
+        true
+    }
+
+    /// Show the proxy (no argument), disable it (`off`) or route all requests
+    /// through `arg`. `NO_PROXY` exclusions from the environment still apply.
+    fn set_proxy(&mut self, arg: &str) {
+        if arg.is_empty() {
+            let message = format!("Proxy: {}", self.executor.proxy().describe());
+            self.show_notification(message);
+            return;
+        }
+
+        let url = match arg {
+            "off" | "none" => None,
+            _ => match reqwest::Url::parse(arg) {
+                Ok(url) if url.has_host() => Some(arg),
+                _ => {
+                    self.show_notification("Usage: :proxy <url> | off");
+                    return;
+                }
+            },
+        };
+
+        let proxy = self.executor.proxy().with_override(url);
+        if self.executor.set_proxy(proxy.clone()).is_err() {
+            self.panel.error_message = Some("Failed to reconfigure proxy".to_string());
+            return;
+        }
+        self.show_notification(format!("Proxy: {}", proxy.describe()));
+        self.panel.proxy = proxy;
+    }
+
+    /// Turn TLS certificate verification off (`on`) or back on (`off`) for
+    /// this session; without an argument the current state is shown.
+    fn set_insecure(&mut self, arg: &str) {
+        let insecure = match arg {
+            "" => {
+                let state = if self.executor.insecure() {
+                    "on"
+                } else {
+                    "off"
+                };
+                self.show_notification(format!("Insecure TLS: {state}"));
+                return;
+            }
+            "on" => true,
+            "off" => false,
+            _ => {
+                self.show_notification("Usage: :insecure on | off");
+                return;
+            }
+        };
+
+        if self.executor.set_insecure(insecure).is_err() {
+            self.panel.error_message = Some("Failed to reconfigure TLS".to_string());
+            return;
+        }
+        self.show_notification(if insecure {
+            "TLS certificate verification disabled for this session"
+        } else {
+            "TLS certificate verification enabled"
+        });
+    }
+
     /// Send the current request via the executor.
     fn send_request(&mut self) {
         if !self.panel.is_active() || self.panel.request_in_flight {
@@ -105,34 +560,82 @@ impl HttpTool {
             return;
         }
 
+        if self.panel.is_graphql_body()
+            && let Err(e) = self.panel.graphql_variables()
+        {
+            self.panel.error_message = Some(e);
+            return;
+        }
+
+        if let Some(Err(e)) = self.panel.body_file_size() {
+            self.panel.error_message = Some(e);
+            return;
+        }
+
         let cmd = HttpRequestCmd {
-            method: self.panel.method,
+            method: self.panel.effective_method(),
             url,
             headers: self.panel.enabled_headers(),
-            body: self.panel.body_text(),
+            body: self.panel.request_body(),
+            body_file: self.panel.body_file(),
+            timeout_ms: self.panel.timeout_ms,
         };
 
+        // Unresolved placeholders are sent literally and a GET/HEAD body may
+        // be dropped by the server; keep warnings around so they are shown
+        // alongside the response.
+        let mut warnings = Vec::new();
+        let unresolved = self.panel.unresolved_vars();
+        if !unresolved.is_empty() {
+            let names: Vec<String> = unresolved.iter().map(|n| format!("${{{n}}}")).collect();
+            warnings.push(format!("Unresolved variables: {}", names.join(", ")));
+        }
+        warnings.extend(self.panel.body_method_warning());
+        self.panel.validate_body_json();
+        if !self.panel.body_json_valid {
+            warnings.push("Body is not valid JSON".to_string());
+        }
+        let warning = (!warnings.is_empty()).then(|| warnings.join(WARNING_SEPARATOR));
+
         if self.executor.send(cmd).is_ok() {
+            self.last_sent_entry_id = self.panel.active_entry_id;
             self.panel.request_in_flight = true;
-            self.panel.error_message = None;
+            self.panel.cancelled = false;
+            self.panel.error_message = warning;
             self.panel.response = None;
             self.panel.spinner_frame = 0;
         }
     }
 
+    /// Re-send the query that was sent last, opening it first if another
+    /// query is active.
+    fn rerun_last_request(&mut self) {
+        let Some(entry_id) = self.last_sent_entry_id else {
+            self.show_notification("No request sent yet");
+            return;
+        };
+
+        if self.panel.active_entry_id != Some(entry_id) && !self.select_query_by_entry_id(entry_id)
+        {
+            // The query was deleted since it was sent
+            self.last_sent_entry_id = None;
+            self.show_notification("Last sent query no longer exists");
+            return;
+        }
+        self.send_request();
+    }
+
     /// Check for async response results.
     fn poll_response(&mut self) {
         if let Some(result) = self.executor.try_recv() {
             self.panel.request_in_flight = false;
             match result {
                 Ok(resp) => {
-                    // Pretty-print JSON if possible
-                    let body =
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&resp.body) {
-                            serde_json::to_string_pretty(&json).unwrap_or(resp.body)
-                        } else {
-                            resp.body
-                        };
+                    // Pretty-print based on the Content-Type (JSON, XML, ...),
+                    // binary bodies become a hex dump
+                    let content_type = body_format::content_type(&resp.headers);
+                    let (language, body) =
+                        body_format::format_response(content_type, &resp.raw_body);
 
                     let response_data = ResponseData {
                         status_code: resp.status_code,
@@ -141,55 +644,417 @@ impl HttpTool {
                         size_bytes: resp.size_bytes,
                         headers: resp.headers,
                         body,
+                        raw_body: Some(resp.raw_body),
+                        language,
+                        pretty: true,
                         body_scroll: 0,
                         headers_scroll: 0,
                         focused_section: ResponseSection::Body,
+                        body_width: Cell::new(0),
                     };
 
-                    self.panel.response = Some(response_data.clone());
-                    self.panel.error_message = None;
-
-                    // Cache the response for this query
-                    if let Some(entry_id) = self.panel.active_entry_id {
-                        self.response_cache.insert(
-                            entry_id,
-                            CachedResponse {
-                                response: self.panel.response.clone(),
-                                error_message: None,
+                    // Persist the run in the history of the query that was
+                    // sent, which may no longer be the open one
+                    if let Some(entry_id) = self.last_sent_entry_id {
+                        let saved = model::add_response_history(
+                            &self.conn,
+                            &model::HttpResponseRecord {
+                                id: 0,
+                                entry_id,
+                                status_code: response_data.status_code,
+                                status_text: response_data.status_text.clone(),
+                                elapsed_ms: response_data.elapsed_ms,
+                                size_bytes: response_data.size_bytes,
+                                headers: response_data.headers.clone(),
+                                body: response_data.body.clone(),
+                                raw_body: response_data.raw_body.clone(),
+                                created_at: String::new(),
                             },
                         );
+                        // A query deleted while in flight keeps no run
+                        if saved.is_ok() {
+                            self.last_runs.insert(
+                                entry_id,
+                                model::LastRun {
+                                    status_code: response_data.status_code,
+                                    elapsed_ms: response_data.elapsed_ms,
+                                },
+                            );
+                        }
                     }
+
+                    // Any error_message left at this point is a send-time warning
+                    self.store_result(|response, _, _| *response = Some(response_data));
+                }
+                Err(e) if e.kind == HttpErrorKind::Cancelled => {
+                    // Drop send-time warnings; there is no response they apply to
+                    self.store_result(|_, error_message, cancelled| {
+                        *error_message = None;
+                        *cancelled = true;
+                    });
                 }
                 Err(e) => {
-                    self.panel.error_message = Some(e.message.clone());
-
-                    // Cache the error for this query
-                    if let Some(entry_id) = self.panel.active_entry_id {
-                        self.response_cache.insert(
-                            entry_id,
-                            CachedResponse {
-                                response: None,
-                                error_message: Some(e.message),
-                            },
-                        );
+                    self.store_result(|_, error_message, _| *error_message = Some(e.message));
+                }
+            }
+        }
+    }
+
+    /// Apply a finished request's outcome (response, error message,
+    /// cancelled flag) to the query that sent it: the panel while it still
+    /// shows that query, otherwise its cached response, seen when it is
+    /// reopened. A query deleted in the meantime gets nothing.
+    fn store_result(
+        &mut self,
+        update: impl FnOnce(&mut Option<ResponseData>, &mut Option<String>, &mut bool),
+    ) {
+        match self.last_sent_entry_id {
+            Some(entry_id) if self.panel.active_entry_id != Some(entry_id) => {
+                if let Some(cached) = self.response_cache.get_mut(&entry_id) {
+                    update(
+                        &mut cached.response,
+                        &mut cached.error_message,
+                        &mut cached.cancelled,
+                    );
+                }
+            }
+            _ => {
+                update(
+                    &mut self.panel.response,
+                    &mut self.panel.error_message,
+                    &mut self.panel.cancelled,
+                );
+                self.cache_current_response();
+            }
+        }
+    }
+
+    // ── Response history ─────────────────────────────────────────────
+
+    fn open_history(&mut self) {
+        let Some(entry_id) = self.panel.active_entry_id else {
+            return;
+        };
+
+        match model::list_response_history(&self.conn, entry_id) {
+            Ok(records) if records.is_empty() => {
+                self.show_notification("No response history");
+            }
+            Ok(records) => {
+                self.history = Some(ResponseHistory {
+                    records,
+                    selected: 0,
+                });
+            }
+            Err(e) => self.show_notification(format!("History error: {e}")),
+        }
+    }
+
+    /// Load the selected history record into the response panel.
+    fn confirm_history_selection(&mut self) {
+        let Some(history) = self.history.take() else {
+            return;
+        };
+        let Some(record) = history.records.into_iter().nth(history.selected) else {
+            return;
+        };
+
+        self.show_notification(format!("Loaded response from {}", record.created_at));
+        let content_type = body_format::content_type(&record.headers);
+        let (language, body) = match record.raw_body.as_deref() {
+            Some(raw) => body_format::format_response(content_type, raw),
+            // Rows stored before raw bodies were kept: only the formatted body
+            None => (
+                BodyLanguage::detect(content_type, &record.body),
+                record.body,
+            ),
+        };
+        self.panel.response = Some(ResponseData {
+            status_code: record.status_code,
+            status_text: record.status_text,
+            elapsed_ms: record.elapsed_ms,
+            size_bytes: record.size_bytes,
+            headers: record.headers,
+            raw_body: record.raw_body,
+            body,
+            language,
+            pretty: true,
+            body_scroll: 0,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(0),
+        });
+        self.panel.error_message = None;
+        self.cache_current_response();
+    }
+
+    fn handle_history_key(&mut self, key: KeyEvent) -> Action {
+        let Some(history) = self.history.as_mut() else {
+            return Action::None;
+        };
+        let len = history.records.len();
+        if len == 0 {
+            self.history = None;
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.history = None,
+            KeyCode::Enter => self.confirm_history_selection(),
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
+                history.selected = (history.selected + 1) % len;
+            }
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => {
+                history.selected = (history.selected + len - 1) % len;
+            }
+            KeyCode::Char('g') => history.selected = 0,
+            KeyCode::Char('G') => history.selected = len - 1,
+            _ => {}
+        }
+        Action::None
+    }
+
+    // ── Key/value sets ───────────────────────────────────────────────
+
+    fn open_default_headers(&mut self) {
+        let rows = model::load_default_headers(&self.conn).map(|headers| {
+            headers
+                .into_iter()
+                .map(|h| KvRow {
+                    db_id: h.id,
+                    key: h.key,
+                    value: h.value,
+                    enabled: h.enabled,
+                    cursor: 0,
+                })
+                .collect()
+        });
+        self.open_kv_set_editor(KvSet::DefaultHeaders, rows);
+    }
+
+    /// Open the overlay on the headers (`vars == false`) or variables of the
+    /// folder the sidebar selection or the open query belongs to.
+    fn open_folder_values(&mut self, vars: bool) {
+        let Some((folder_id, folder)) = self.command_folder() else {
+            self.show_notification("No folder selected".to_string());
+            return;
+        };
+        let (target, values) = if vars {
+            (
+                KvSet::FolderVars { folder_id, folder },
+                model::load_folder_vars(&self.conn, folder_id),
+            )
+        } else {
+            (
+                KvSet::FolderHeaders { folder_id, folder },
+                model::load_folder_headers(&self.conn, folder_id),
+            )
+        };
+        let rows = values.map(|values| {
+            values
+                .into_iter()
+                .map(|v| KvRow {
+                    db_id: v.id,
+                    key: v.key,
+                    value: v.value,
+                    enabled: v.enabled,
+                    cursor: 0,
+                })
+                .collect()
+        });
+        self.open_kv_set_editor(target, rows);
+    }
+
+    /// The folder folder-level commands act on: the selected folder, or the
+    /// parent of the selected (or, with the panel focused, the open) query.
+    fn command_folder(&self) -> Option<(i64, String)> {
+        let selected = self
+            .sidebar
+            .selected_entry()
+            .filter(|_| self.sidebar_focused || !self.panel.is_active());
+        let folder_id = match selected {
+            Some(entry) if entry.is_folder => entry.entry_id,
+            Some(entry) => sidebar::find_parent_id(&self.sidebar.roots, entry.entry_id)?,
+            None => sidebar::find_parent_id(&self.sidebar.roots, self.panel.active_entry_id?)?,
+        };
+        let node = sidebar::find_node(&self.sidebar.roots, folder_id)?;
+        Some((folder_id, node.entry.name.clone()))
+    }
+
+    fn open_kv_set_editor(&mut self, target: KvSet, rows: anyhow::Result<Vec<KvRow>>) {
+        match rows {
+            Ok(rows) => {
+                self.kv_set_editor = Some(KvSetEditor {
+                    target,
+                    rows,
+                    selected: 0,
+                    editing: None,
+                    delete_prompt: None,
+                });
+            }
+            Err(e) => self.show_notification(format!("{} error: {e}", target.title())),
+        }
+    }
+
+    /// Persist the overlay rows and apply them to the panel.
+    fn close_kv_set_editor(&mut self) {
+        let Some(editor) = self.kv_set_editor.take() else {
+            return;
+        };
+        let rows: Vec<(String, String, bool)> = editor
+            .rows
+            .into_iter()
+            .filter(|r| !r.key.trim().is_empty())
+            .map(|r| (r.key.trim().to_string(), r.value, r.enabled))
+            .collect();
+
+        let result = match editor.target {
+            KvSet::DefaultHeaders => model::replace_default_headers(&self.conn, &rows)
+                .and_then(|_| enabled_default_headers(&self.conn))
+                .map(|headers| self.panel.set_default_headers(headers)),
+            KvSet::FolderHeaders { folder_id, .. } => {
+                model::replace_folder_headers(&self.conn, folder_id, &rows)
+                    .and_then(|_| self.panel.load_folder_values(&self.conn))
+            }
+            KvSet::FolderVars { folder_id, .. } => {
+                model::replace_folder_vars(&self.conn, folder_id, &rows)
+                    .and_then(|_| self.panel.load_folder_values(&self.conn))
+            }
+        };
+        if let Err(e) = result {
+            self.show_notification(format!("{} error: {e}", editor.target.title()));
+        }
+    }
+
+    fn handle_kv_set_key(&mut self, key: KeyEvent) -> Action {
+        let Some(editor) = self.kv_set_editor.as_mut() else {
+            return Action::None;
+        };
+
+        if let Some(prompt) = &editor.delete_prompt {
+            if let Some(confirmed) = prompt.handle_key(key) {
+                editor.delete_prompt = None;
+                if confirmed && editor.selected < editor.rows.len() {
+                    editor.rows.remove(editor.selected);
+                    editor.selected = editor.selected.min(editor.rows.len().saturating_sub(1));
+                }
+            }
+            return Action::None;
+        }
+
+        if let Some(field) = editor.editing {
+            let Some(row) = editor.rows.get_mut(editor.selected) else {
+                editor.editing = None;
+                return Action::None;
+            };
+            let text = match field {
+                KvField::Key => &mut row.key,
+                KvField::Value => &mut row.value,
+            };
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => editor.editing = None,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    let other = match field {
+                        KvField::Key => KvField::Value,
+                        KvField::Value => KvField::Key,
+                    };
+                    row.cursor = match other {
+                        KvField::Key => row.key.len(),
+                        KvField::Value => row.value.len(),
+                    };
+                    editor.editing = Some(other);
+                }
+                KeyCode::Char(c) => {
+                    text.insert(row.cursor, c);
+                    row.cursor += c.len_utf8();
+                }
+                KeyCode::Backspace => {
+                    if let Some(prev) = text[..row.cursor].chars().last() {
+                        row.cursor -= prev.len_utf8();
+                        text.remove(row.cursor);
+                    }
+                }
+                KeyCode::Delete if row.cursor < text.len() => {
+                    text.remove(row.cursor);
+                }
+                KeyCode::Left => {
+                    if let Some(prev) = text[..row.cursor].chars().last() {
+                        row.cursor -= prev.len_utf8();
                     }
                 }
+                KeyCode::Right => {
+                    if let Some(next) = text[row.cursor..].chars().next() {
+                        row.cursor += next.len_utf8();
+                    }
+                }
+                KeyCode::Home => row.cursor = 0,
+                KeyCode::End => row.cursor = text.len(),
+                _ => {}
             }
+            return Action::None;
         }
+
+        let len = editor.rows.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.close_kv_set_editor(),
+            KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+                editor.selected = (editor.selected + 1) % len;
+            }
+            KeyCode::Char('k') | KeyCode::Up if len > 0 => {
+                editor.selected = (editor.selected + len - 1) % len;
+            }
+            KeyCode::Char('a') => {
+                editor.rows.push(KvRow::new_empty());
+                editor.selected = editor.rows.len() - 1;
+                editor.editing = Some(KvField::Key);
+            }
+            KeyCode::Char('i') | KeyCode::Enter if len > 0 => {
+                let row = &mut editor.rows[editor.selected];
+                row.cursor = row.key.len();
+                editor.editing = Some(KvField::Key);
+            }
+            KeyCode::Char('d') if len > 0 => {
+                let message = match editor.rows[editor.selected].key.as_str() {
+                    "" => "Delete this row?".to_string(),
+                    key => format!("Delete '{key}'?"),
+                };
+                editor.delete_prompt = Some(ConfirmPrompt::new(message));
+            }
+            KeyCode::Char('x') if len > 0 => {
+                let row = &mut editor.rows[editor.selected];
+                row.enabled = !row.enabled;
+            }
+            _ => {}
+        }
+        Action::None
     }
 
-    /// Save the current panel's response/error into the cache before switching away.
+    /// Drop the current response and error (and its cached copy), returning
+    /// the response view to the "no response yet" state.
+    fn clear_response(&mut self) {
+        // The scroll positions live on the response and go with it
+        self.panel.response = None;
+        self.panel.error_message = None;
+        self.panel.cancelled = false;
+        if let Some(entry_id) = self.panel.active_entry_id {
+            self.response_cache.remove(&entry_id);
+        }
+    }
+
+    /// Save the current panel's response/error and focus into the cache
+    /// before switching away.
     fn cache_current_response(&mut self) {
         if let Some(entry_id) = self.panel.active_entry_id {
-            if self.panel.response.is_some() || self.panel.error_message.is_some() {
-                self.response_cache.insert(
-                    entry_id,
-                    CachedResponse {
-                        response: self.panel.response.clone(),
-                        error_message: self.panel.error_message.clone(),
-                    },
-                );
-            }
+            self.response_cache.insert(
+                entry_id,
+                CachedResponse {
+                    response: self.panel.response.clone(),
+                    error_message: self.panel.error_message.clone(),
+                    cancelled: self.panel.cancelled,
+                    focused_section: self.panel.focused_section,
+                    panel_focus: self.panel.panel_focus,
+                },
+            );
         }
     }
 
@@ -200,16 +1065,16 @@ impl HttpTool {
 
         let _ = self.panel.load(entry_id, name, &self.conn);
 
-        // Restore cached response if available (load() resets response to None)
+        // Restore the cached response, scroll and focus if available
+        // (load() resets them; new queries keep the URL focus)
         if let Some(cached) = self.response_cache.get(&entry_id) {
-            self.panel.response = cached.response.clone().map(|mut r| {
-                // Reset scroll positions when restoring
-                r.body_scroll = 0;
-                r.headers_scroll = 0;
-                r.focused_section = ResponseSection::Body;
-                r
-            });
+            self.panel.response = cached.response.clone();
             self.panel.error_message = cached.error_message.clone();
+            self.panel.cancelled = cached.cancelled;
+            self.panel.focused_section = cached.focused_section;
+            if cached.panel_focus == PanelFocus::Request || self.panel.response.is_some() {
+                self.panel.panel_focus = cached.panel_focus;
+            }
         }
 
         self.sidebar_focused = false;
@@ -228,25 +1093,9 @@ impl HttpTool {
     /// Returns Some(action) if the key was handled, None if it should fall through.
     fn handle_sidebar_normal_key(&mut self, key: KeyEvent) -> Action {
         // Handle pending two-key sequences first (gg, gt, gT, dd is NOT used here)
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
-        }
-
         if let Some(pending) = self.key_state.pending_key.take() {
             return match (pending, key.code) {
-                ('g', KeyCode::Char('g')) => {
+                ('g', _) if keymap().matches(KeyAction::GotoPrefix, &key) => {
                     self.sidebar.goto_top();
                     Action::None
                 }
@@ -259,11 +1108,11 @@ impl HttpTool {
         // Sidebar-specific keys (neo-tree style)
         match key.code {
             // Navigation
-            KeyCode::Char('j') => {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.sidebar.move_down();
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.sidebar.move_up();
                 Action::None
             }
@@ -293,20 +1142,20 @@ impl HttpTool {
                 }
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 self.sidebar.goto_bottom();
                 Action::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
             KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => {
-                self.sidebar.half_page_down(20); // approximate visible lines
+                self.sidebar.half_page_down();
                 Action::None
             }
             KeyCode::Char('u') if key.modifiers == KeyModifiers::CONTROL => {
-                self.sidebar.half_page_up(20);
+                self.sidebar.half_page_up();
                 Action::None
             }
 
@@ -340,15 +1189,28 @@ impl HttpTool {
                 self.execute_paste();
                 Action::None
             }
+            KeyCode::Char('c') => {
+                self.duplicate_selected_query();
+                Action::None
+            }
+            KeyCode::Char('u') => {
+                self.undo_delete();
+                Action::None
+            }
+            KeyCode::Char('J') => {
+                self.move_selected_entry(true);
+                Action::None
+            }
+            KeyCode::Char('K') => {
+                self.move_selected_entry(false);
+                Action::None
+            }
 
             // Hub-level actions
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
 
             _ => Action::None,
         }
@@ -418,7 +1280,7 @@ impl HttpTool {
                 if !input.is_empty() {
                     if let Some(entry_id) = self.sidebar.selected_entry_id() {
                         let _ = model::rename_entry(&self.conn, entry_id, &input);
-                        let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
+                        self.reload_after_edit();
                     }
                 }
             }
@@ -473,7 +1335,7 @@ impl HttpTool {
             }
         }
 
-        let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
+        self.reload_after_edit();
 
         // Expand parent folders so the new entry is visible
         self.expand_path_to_parent(parent_id);
@@ -534,9 +1396,23 @@ impl HttpTool {
         }
     }
 
+    /// Reload the sidebar after an edit other than a delete. Undo only
+    /// applies right after a delete, so the snapshot is dropped.
+    fn reload_after_edit(&mut self) {
+        self.last_deleted = None;
+        let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
+    }
+
     /// Execute the delete operation on the selected entry.
+    /// The subtree is snapshotted first so `u` can restore it.
     fn execute_delete(&mut self) {
         if let Some(entry_id) = self.sidebar.selected_entry_id() {
+            self.last_deleted = collection::load_subtree(&self.conn, entry_id)
+                .ok()
+                .map(|node| DeletedEntry {
+                    parent_id: sidebar::find_parent_id(&self.sidebar.roots, entry_id),
+                    node,
+                });
             let _ = model::delete_entry(&self.conn, entry_id);
             // If we just deleted the clipboard source, clear the clipboard
             if let Some(ref clip) = self.sidebar.clipboard {
@@ -546,10 +1422,79 @@ impl HttpTool {
             }
             // Remove cached response for deleted entry
             self.response_cache.remove(&entry_id);
+            // The history of every deleted query went with it
+            if let Ok(runs) = model::last_runs(&self.conn) {
+                self.last_runs = runs;
+            }
             let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
         }
     }
 
+    /// Recreate the last deleted subtree under its former parent. Entries
+    /// get new IDs; their response history is not restored.
+    fn undo_delete(&mut self) {
+        let Some(deleted) = self.last_deleted.take() else {
+            self.show_notification("Nothing to undo");
+            return;
+        };
+
+        let mut queries = 0;
+        match collection::import_node(&self.conn, &deleted.node, deleted.parent_id, &mut queries) {
+            Ok(new_id) => {
+                let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
+                self.expand_path_to_parent(deleted.parent_id);
+                self.sidebar.select_entry(new_id);
+                self.show_notification("Restored deleted entry");
+            }
+            Err(e) => self.show_notification(format!("Undo failed: {e}")),
+        }
+    }
+
+    /// Swap the selected entry with its previous/next sibling of the same
+    /// kind and persist the resulting order.
+    fn move_selected_entry(&mut self, down: bool) {
+        let Some(entry_id) = self.sidebar.selected_entry_id() else {
+            return;
+        };
+        let mut ids = self.sidebar.sibling_ids(entry_id);
+        let Some(index) = ids.iter().position(|&id| id == entry_id) else {
+            return;
+        };
+        let target = if down {
+            index + 1
+        } else {
+            match index.checked_sub(1) {
+                Some(target) => target,
+                None => return,
+            }
+        };
+        if target >= ids.len() {
+            return;
+        }
+
+        ids.swap(index, target);
+        let _ = model::set_entry_positions(&self.conn, &ids);
+        self.reload_after_edit();
+        self.sidebar.select_entry(entry_id);
+    }
+
+    /// Sort the current folder (same rule as adding an entry) alphabetically,
+    /// dropping any manual order.
+    fn sort_current_folder(&mut self) {
+        let parent_id = self.get_creation_parent_id();
+        let selected = self.sidebar.selected_entry_id();
+        match model::reset_entry_positions(&self.conn, parent_id) {
+            Ok(()) => {
+                self.reload_after_edit();
+                if let Some(entry_id) = selected {
+                    self.sidebar.select_entry(entry_id);
+                }
+                self.show_notification("Sorted alphabetically");
+            }
+            Err(e) => self.show_notification(format!("Sort failed: {e}")),
+        }
+    }
+
     /// Execute the paste operation from the clipboard.
     fn execute_paste(&mut self) {
         let clipboard = match self.sidebar.clipboard.take() {
@@ -572,48 +1517,140 @@ impl HttpTool {
             }
         }
 
-        let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
+        self.reload_after_edit();
         self.expand_path_to_parent(target_parent_id);
     }
 
+    /// Clone the selected query, request included, as a `<name> copy` sibling.
+    fn duplicate_selected_query(&mut self) {
+        let Some(entry) = self.sidebar.selected_entry() else {
+            return;
+        };
+        if entry.is_folder {
+            self.show_notification("Only queries can be duplicated");
+            return;
+        }
+        let source_id = entry.entry_id;
+        let name = format!("{} copy", entry.name);
+
+        match model::duplicate_query(&self.conn, source_id) {
+            Ok(new_id) => {
+                self.reload_after_edit();
+                self.sidebar.select_entry(new_id);
+                self.show_notification(format!("Duplicated as {name}"));
+            }
+            Err(e) => self.show_notification(format!("Duplicate failed: {e}")),
+        }
+    }
+
+    // ── Collection export / import ─────────────────────────────────
+
+    /// Write the whole tree, with every query's request, to a JSON file.
+    fn export_collection(&mut self, path: &str) {
+        if path.is_empty() {
+            self.show_notification("Usage: :export <path>");
+            return;
+        }
+
+        let path = expand_tilde(path);
+        let result = collection::load_tree(&self.conn).and_then(|tree| {
+            let text = serde_json::to_string_pretty(&collection::to_json(&tree))?;
+            std::fs::write(&path, text)?;
+            Ok(tree)
+        });
+        match result {
+            Ok(tree) => self.show_notification(format!(
+                "Exported {} queries to {path}",
+                collection::query_count(&tree)
+            )),
+            Err(e) => self.show_notification(format!("Export failed: {e}")),
+        }
+    }
+
+    /// Write the whole tree as a Postman v2.1 collection named after the file.
+    fn export_postman(&mut self, path: &str) {
+        if path.is_empty() {
+            self.show_notification("Usage: :export-postman <path>");
+            return;
+        }
+
+        let path = expand_tilde(path);
+        let name = std::path::Path::new(&path)
+            .file_stem()
+            .map_or("rstools".into(), |stem| stem.to_string_lossy());
+        let result = collection::load_tree(&self.conn).and_then(|tree| {
+            let defaults = enabled_default_headers(&self.conn)?;
+            let doc = postman::to_json(&name, &tree, &defaults);
+            std::fs::write(&path, serde_json::to_string_pretty(&doc)?)?;
+            Ok(tree)
+        });
+        match result {
+            Ok(tree) => self.show_notification(format!(
+                "Exported {} queries to {path} (Postman)",
+                collection::query_count(&tree)
+            )),
+            Err(e) => self.show_notification(format!("Export failed: {e}")),
+        }
+    }
+
+    /// Read a collection file and recreate its entries under the current
+    /// sidebar selection (same rule as adding an entry).
+    fn import_collection(&mut self, path: &str) {
+        if path.is_empty() {
+            self.show_notification("Usage: :import <path>");
+            return;
+        }
+
+        let path = expand_tilde(path);
+        let parent_id = self.get_creation_parent_id();
+        let result = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(serde_json::from_str::<serde_json::Value>(&text)?))
+            .and_then(|doc| collection::from_json(&doc))
+            .and_then(|tree| collection::import_tree(&self.conn, &tree, parent_id));
+        match result {
+            Ok(count) => {
+                self.reload_after_edit();
+                self.expand_path_to_parent(parent_id);
+                self.show_notification(format!("Imported {count} queries from {path}"));
+            }
+            Err(e) => self.show_notification(format!("Import failed: {e}")),
+        }
+    }
+
     // ── Content panel key handling ─────────────────────────────────
 
     /// Handle key events when the content panel is focused in Normal mode.
     fn handle_panel_normal_key(&mut self, key: KeyEvent) -> Action {
         // Handle pending two-key sequences first
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char('s') => {
-                    self.send_request();
-                    Action::None
-                }
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
-        }
-
         if let Some(pending) = self.key_state.pending_key.take() {
             return match (pending, key.code) {
-                ('g', KeyCode::Char('g')) => {
+                ('g', _) if keymap().matches(KeyAction::GotoPrefix, &key) => {
                     self.panel_goto_top();
                     Action::None
                 }
                 ('g', KeyCode::Char('t')) => Action::NextTool,
                 ('g', KeyCode::Char('T')) => Action::PrevTool,
+                ('y', KeyCode::Char('c')) if self.panel.panel_focus == PanelFocus::Request => {
+                    self.copy_request_as_curl();
+                    Action::None
+                }
+                ('y', KeyCode::Char('y')) if self.panel.panel_focus == PanelFocus::Response => {
+                    self.copy_response_to_clipboard();
+                    Action::None
+                }
+                ('y', KeyCode::Char('j')) if self.panel.panel_focus == PanelFocus::Response => {
+                    self.open_json_path_prompt();
+                    Action::None
+                }
+                ('y', KeyCode::Char('b')) if self.panel.panel_focus == PanelFocus::Response => {
+                    self.copy_body_base64();
+                    Action::None
+                }
                 ('d', KeyCode::Char('d')) => {
                     // Delete row in kv sections
-                    match self.panel.focused_section {
-                        Section::Headers | Section::Params => self.panel.kv_delete_row(),
-                        _ => {}
+                    if self.panel.in_kv_section() {
+                        self.panel.kv_delete_row();
                     }
                     Action::None
                 }
@@ -659,8 +1696,9 @@ impl HttpTool {
                 KeyCode::Char('d') => {
                     // Half page down in response body
                     if self.panel.panel_focus == PanelFocus::Response {
+                        let wrap = self.panel.wrap_response;
                         if let Some(ref mut resp) = self.panel.response {
-                            resp.scroll_body_down(10);
+                            resp.scroll_body_down(10, wrap);
                         }
                     }
                     return Action::None;
@@ -684,15 +1722,42 @@ impl HttpTool {
             return Action::None;
         }
 
+        // `yc` copies the request as a cURL command
+        if key.code == KeyCode::Char('y')
+            && key.modifiers.is_empty()
+            && self.panel.panel_focus == PanelFocus::Request
+        {
+            self.key_state.pending_key = Some('y');
+            return Action::None;
+        }
+
         // Response-focused keys
         if self.panel.panel_focus == PanelFocus::Response {
             return self.handle_response_key(key);
         }
 
+        // `t` in the Body section cycles raw text / form fields / GraphQL / file,
+        // `v` switches between the GraphQL Query and Variables editors
+        if self.panel.focused_section == Section::Body && key.modifiers.is_empty() {
+            match key.code {
+                KeyCode::Char('t') => {
+                    self.panel.cycle_body_mode();
+                    return Action::None;
+                }
+                KeyCode::Char('v') if self.panel.is_graphql_body() => {
+                    self.panel.toggle_graphql_pane();
+                    return Action::None;
+                }
+                _ => {}
+            }
+        }
+
         // Request section-specific keys
         match self.panel.focused_section {
             Section::Url => self.handle_url_normal_key(key),
             Section::Params | Section::Headers => self.handle_kv_normal_key(key),
+            Section::Body if self.panel.is_form_body() => self.handle_kv_normal_key(key),
+            Section::Auth => self.handle_auth_normal_key(key),
             Section::Body => self.handle_body_normal_key(key),
         }
     }
@@ -734,36 +1799,33 @@ impl HttpTool {
                 Action::None
             }
             // Hub-level
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
             _ => Action::None,
         }
     }
 
     fn handle_kv_normal_key(&mut self, key: KeyEvent) -> Action {
         match key.code {
-            KeyCode::Char('j') => {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.panel.kv_move_down();
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.panel.kv_move_up();
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 self.panel.kv_goto_bottom();
                 Action::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
@@ -789,10 +1851,11 @@ impl HttpTool {
                 self.panel.kv_toggle_enabled();
                 Action::None
             }
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
+            KeyCode::Char('D') if self.panel.focused_section == Section::Headers => {
+                self.panel.toggle_default_headers();
+                Action::None
             }
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
             KeyCode::Tab => {
                 self.panel.next_section();
                 Action::None
@@ -801,9 +1864,59 @@ impl HttpTool {
                 self.panel.prev_section();
                 Action::None
             }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
+            _ => Action::None,
+        }
+    }
+
+    fn handle_auth_normal_key(&mut self, key: KeyEvent) -> Action {
+        let on_type_row = self.panel.auth_selected_field() == AuthField::Type;
+        match key.code {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
+                self.panel.auth_move_down();
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
+                self.panel.auth_move_up();
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
+                self.panel.auth_selected = self.panel.auth_fields().len() - 1;
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
+                self.key_state.pending_key = Some('g');
+                Action::None
+            }
+            // On the type row, Enter / l / h cycle the auth type
+            KeyCode::Enter | KeyCode::Char('l') if on_type_row => {
+                self.panel.auth_cycle_type_forward();
+                Action::None
+            }
+            KeyCode::Char('h') if on_type_row => {
+                self.panel.auth_cycle_type_backward();
+                Action::None
+            }
+            KeyCode::Char('i') | KeyCode::Char('a') | KeyCode::Enter => {
+                if self.panel.auth_start_edit() {
+                    self.mode = InputMode::Insert;
+                }
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            KeyCode::Tab => {
+                self.panel.next_section();
+                Action::None
+            }
+            KeyCode::BackTab => {
+                self.panel.prev_section();
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
             _ => Action::None,
         }
     }
@@ -850,11 +1963,11 @@ impl HttpTool {
                 self.mode = InputMode::Insert;
                 Action::None
             }
-            KeyCode::Char('j') => {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.panel.body_cursor_down();
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.panel.body_cursor_up();
                 Action::None
             }
@@ -874,11 +1987,11 @@ impl HttpTool {
                 self.panel.body_cursor_end();
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 self.panel.body_goto_bottom();
                 Action::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
@@ -890,13 +2003,10 @@ impl HttpTool {
                 self.panel.prev_section();
                 Action::None
             }
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
             _ => Action::None,
         }
     }
@@ -904,19 +2014,42 @@ impl HttpTool {
     fn handle_response_key(&mut self, key: KeyEvent) -> Action {
         match key.code {
             KeyCode::Char('y') => {
-                self.copy_response_body_to_clipboard();
+                // `yy` copies the body (or headers), `yj` copies a JSON path,
+                // `yb` copies the raw body as base64
+                self.key_state.pending_key = Some('y');
+                Action::None
+            }
+            KeyCode::Char('H') => {
+                self.open_history();
+                Action::None
+            }
+            KeyCode::Char('w') => {
+                self.panel.toggle_response_wrap();
+                Action::None
+            }
+            KeyCode::Char('P') => {
+                if let Some(ref mut resp) = self.panel.response
+                    && !resp.toggle_pretty()
+                {
+                    self.show_notification(RAW_BODY_MISSING);
+                }
                 Action::None
             }
-            KeyCode::Char('j') => {
+            KeyCode::Char('X') => {
+                self.clear_response();
+                Action::None
+            }
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
+                let wrap = self.panel.wrap_response;
                 if let Some(ref mut resp) = self.panel.response {
                     match resp.focused_section {
-                        ResponseSection::Body => resp.scroll_body_down(1),
+                        ResponseSection::Body => resp.scroll_body_down(1, wrap),
                         ResponseSection::Headers => resp.scroll_headers_down(1),
                     }
                 }
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 if let Some(ref mut resp) = self.panel.response {
                     match resp.focused_section {
                         ResponseSection::Body => resp.scroll_body_up(1),
@@ -925,11 +2058,12 @@ impl HttpTool {
                 }
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
+                let wrap = self.panel.wrap_response;
                 if let Some(ref mut resp) = self.panel.response {
                     match resp.focused_section {
                         ResponseSection::Body => {
-                            let max = resp.body_line_count().saturating_sub(1);
+                            let max = resp.body_line_count(wrap).saturating_sub(1);
                             resp.body_scroll = max;
                         }
                         ResponseSection::Headers => {
@@ -939,7 +2073,7 @@ impl HttpTool {
                 }
                 Action::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
@@ -949,13 +2083,10 @@ impl HttpTool {
                 }
                 Action::None
             }
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
             _ => Action::None,
         }
     }
@@ -972,12 +2103,15 @@ impl HttpTool {
             KeyCode::Esc => {
                 self.panel.editing = false;
                 self.panel.kv_stop_edit();
+                self.panel.validate_body_json();
                 self.mode = InputMode::Normal;
                 Action::SetMode(InputMode::Normal)
             }
             _ => match self.panel.focused_section {
                 Section::Url => self.handle_url_insert_key(key),
                 Section::Params | Section::Headers => self.handle_kv_insert_key(key),
+                Section::Body if self.panel.is_form_body() => self.handle_kv_insert_key(key),
+                Section::Auth => self.handle_auth_insert_key(key),
                 Section::Body => self.handle_body_insert_key(key),
             },
         }
@@ -1050,6 +2184,33 @@ impl HttpTool {
         }
     }
 
+    fn handle_auth_insert_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char(c) => {
+                self.panel.auth_insert_char(c);
+                Action::None
+            }
+            KeyCode::Backspace => {
+                self.panel.auth_backspace();
+                Action::None
+            }
+            KeyCode::Left => {
+                self.panel.auth_cursor_left();
+                Action::None
+            }
+            KeyCode::Right => {
+                self.panel.auth_cursor_right();
+                Action::None
+            }
+            KeyCode::Enter => {
+                self.panel.editing = false;
+                self.mode = InputMode::Normal;
+                Action::SetMode(InputMode::Normal)
+            }
+            _ => Action::None,
+        }
+    }
+
     fn handle_body_insert_key(&mut self, key: KeyEvent) -> Action {
         match key.code {
             KeyCode::Char(c) => {
@@ -1210,14 +2371,17 @@ impl HttpTool {
             // Click on section tabs: determine which tab
             let inner_x = request_area.x + 1;
             let col_in_tabs = mouse.column.saturating_sub(inner_x);
-            // Tabs layout: " Params │ Headers │ Body"
-            // " " = 1, "Params" = 6, " │ " = 3, "Headers" = 7, " │ " = 3, "Body" = 4
+            // Tabs layout: " Params │ Headers │ Auth │ Body"
+            // " " = 1, "Params" = 6, " │ " = 3, "Headers" = 7, " │ " = 3, "Auth" = 4, ...
             if col_in_tabs < 7 {
                 // " Params" region
                 self.panel.focused_section = Section::Params;
             } else if col_in_tabs < 17 {
                 // " │ Headers" region
                 self.panel.focused_section = Section::Headers;
+            } else if col_in_tabs < 24 {
+                // " │ Auth" region
+                self.panel.focused_section = Section::Auth;
             } else {
                 // " │ Body" region
                 self.panel.focused_section = Section::Body;
@@ -1253,6 +2417,22 @@ impl HttpTool {
                         self.panel.headers_selected = clicked_idx;
                     }
                 }
+                Section::Auth if content_row < self.panel.auth_fields().len() => {
+                    self.panel.auth_selected = content_row;
+                }
+                // Row 0 of the form body shows the mode line
+                Section::Body if self.panel.is_form_body() && content_row > 0 => {
+                    let visible_lines = request_area.height.saturating_sub(5) as usize;
+                    let scroll_offset = if self.panel.form_selected >= visible_lines {
+                        self.panel.form_selected - visible_lines + 1
+                    } else {
+                        0
+                    };
+                    let clicked_idx = scroll_offset + content_row - 1;
+                    if clicked_idx < self.panel.form_fields.len() {
+                        self.panel.form_selected = clicked_idx;
+                    }
+                }
                 _ => {}
             }
         }
@@ -1308,13 +2488,16 @@ impl HttpTool {
             PanelFocus::Request => match self.panel.focused_section {
                 Section::Params => self.panel.kv_move_down(),
                 Section::Headers => self.panel.kv_move_down(),
+                Section::Body if self.panel.is_form_body() => self.panel.kv_move_down(),
+                Section::Auth => self.panel.auth_move_down(),
                 Section::Body => self.panel.body_cursor_down(),
                 Section::Url => {}
             },
             PanelFocus::Response => {
+                let wrap = self.panel.wrap_response;
                 if let Some(ref mut resp) = self.panel.response {
                     match resp.focused_section {
-                        ResponseSection::Body => resp.scroll_body_down(3),
+                        ResponseSection::Body => resp.scroll_body_down(3, wrap),
                         ResponseSection::Headers => resp.scroll_headers_down(1),
                     }
                 }
@@ -1328,6 +2511,8 @@ impl HttpTool {
             PanelFocus::Request => match self.panel.focused_section {
                 Section::Params => self.panel.kv_move_up(),
                 Section::Headers => self.panel.kv_move_up(),
+                Section::Body if self.panel.is_form_body() => self.panel.kv_move_up(),
+                Section::Auth => self.panel.auth_move_up(),
                 Section::Body => self.panel.body_cursor_up(),
                 Section::Url => {}
             },
@@ -1346,6 +2531,8 @@ impl HttpTool {
         match self.panel.panel_focus {
             PanelFocus::Request => match self.panel.focused_section {
                 Section::Headers | Section::Params => self.panel.kv_goto_top(),
+                Section::Body if self.panel.is_form_body() => self.panel.kv_goto_top(),
+                Section::Auth => self.panel.auth_selected = 0,
                 Section::Body => self.panel.body_goto_top(),
                 _ => {}
             },
@@ -1432,6 +2619,16 @@ impl Tool for HttpTool {
         self.mode
     }
 
+    fn pending_keys(&self) -> String {
+        self.key_state.pending_keys()
+    }
+
+    fn status_warning(&self) -> Option<String> {
+        self.executor
+            .insecure()
+            .then(|| "INSECURE: TLS verification off".to_string())
+    }
+
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()> {
         model::init_db(conn)
     }
@@ -1439,15 +2636,36 @@ impl Tool for HttpTool {
     fn which_key_entries(&self) -> Vec<WhichKeyEntry> {
         vec![
             WhichKeyEntry::action('s', "Send request"),
+            WhichKeyEntry::action('r', "Rerun last request"),
             WhichKeyEntry::action('e', "Toggle sidebar"),
             WhichKeyEntry::action('m', "Cycle method"),
+            WhichKeyEntry::action('i', "Import cURL"),
         ]
     }
 
     fn telescope_items(&self) -> Vec<TelescopeItem> {
+        // A new listing: previews may have changed since the last one
+        self.preview_cache.borrow_mut().clear();
         self.collect_telescope_items()
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let entry_id = id.strip_prefix("http:")?.parse::<i64>().ok()?;
+        let mut cache = self.preview_cache.borrow_mut();
+        let text = cache.entry(entry_id).or_insert_with(|| {
+            let Some(request) = model::load_request(&self.conn, entry_id).ok().flatten() else {
+                return "No request saved yet".to_string();
+            };
+            let mut text = format!("{} {}", request.method.as_str(), request.url);
+            if !request.body.is_empty() {
+                text.push_str("\n\n");
+                text.push_str(&telescope::preview_lines(&request.body));
+            }
+            text
+        });
+        Some(text.clone())
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         let Some(raw_id) = id.strip_prefix("http:") else {
             return false;
@@ -1466,9 +2684,20 @@ impl Tool for HttpTool {
             HelpEntry::with_section("Sidebar", "a", "Add entry (path with / for nesting)"),
             HelpEntry::with_section("Sidebar", "r", "Rename selected entry"),
             HelpEntry::with_section("Sidebar", "d", "Delete selected entry"),
+            HelpEntry::with_section("Sidebar", "u", "Undo the last delete"),
             HelpEntry::with_section("Sidebar", "y", "Copy selected entry"),
             HelpEntry::with_section("Sidebar", "x", "Cut selected entry"),
             HelpEntry::with_section("Sidebar", "p", "Paste entry"),
+            HelpEntry::with_section("Sidebar", "c", "Duplicate query (with its request)"),
+            HelpEntry::with_section("Sidebar", "J / K", "Move entry down / up"),
+            HelpEntry::with_section("Sidebar", ":sort", "Sort current folder alphabetically"),
+            HelpEntry::with_section("Sidebar", ":export <path>", "Export all queries to JSON"),
+            HelpEntry::with_section(
+                "Sidebar",
+                ":export-postman <path>",
+                "Export all queries as a Postman collection",
+            ),
+            HelpEntry::with_section("Sidebar", ":import <path>", "Import a collection here"),
             HelpEntry::with_section("Sidebar", "h", "Collapse folder / go to parent"),
             HelpEntry::with_section("Sidebar", "l / Enter", "Expand folder / open query"),
             HelpEntry::with_section("Sidebar", "j / k", "Navigate up / down"),
@@ -1478,14 +2707,29 @@ impl Tool for HttpTool {
             HelpEntry::with_section(
                 "Request",
                 "Tab / S-Tab",
-                "Cycle sections (URL/Params/Headers/Body)",
+                "Cycle sections (URL/Params/Headers/Auth/Body)",
             ),
             HelpEntry::with_section("Request", "Ctrl-h/j/k/l", "Navigate between panels"),
             HelpEntry::with_section("Request", "Ctrl-Enter", "Send request"),
+            HelpEntry::with_section("Request", "Ctrl-c", "Cancel the running request"),
             HelpEntry::with_section("Request", "f", "Toggle fullscreen panel"),
             HelpEntry::with_section("Request", "<Space>s", "Send request"),
+            HelpEntry::with_section("Request", "<Space>r", "Rerun the last sent request"),
             HelpEntry::with_section("Request", ":w", "Save request to database"),
             HelpEntry::with_section("Request", "m / M", "Cycle method forward / backward"),
+            HelpEntry::with_section("Request", "yc", "Copy request as cURL"),
+            HelpEntry::with_section("Request", "<Space>i", "Import request from cURL"),
+            HelpEntry::with_section("Request", ":curl [cmd]", "Import cURL (prompt if no cmd)"),
+            HelpEntry::with_section("Request", ":timeout <ms>", "Set request timeout (or off)"),
+            HelpEntry::with_section("Request", ":env <name>", "Switch environment (none clears)"),
+            HelpEntry::with_section("Request", ":envset K V", "Set var in active environment"),
+            HelpEntry::with_section("Request", ":envunset K", "Remove variable from environment"),
+            HelpEntry::with_section("Request", ":proxy <url|off>", "Set or clear the proxy"),
+            HelpEntry::with_section(
+                "Request",
+                ":insecure on|off",
+                "Skip TLS verification (session)",
+            ),
             // URL section
             HelpEntry::with_section("URL", "i / a", "Edit URL"),
             // Params / Headers
@@ -1494,21 +2738,53 @@ impl Tool for HttpTool {
             HelpEntry::with_section("Key-Value", "dd", "Delete selected row"),
             HelpEntry::with_section("Key-Value", "x", "Toggle row enabled/disabled"),
             HelpEntry::with_section("Key-Value", "Tab (edit)", "Switch between key/value fields"),
+            HelpEntry::with_section("Key-Value", "D", "Toggle default headers for this query"),
+            HelpEntry::with_section("Key-Value", ":defaults", "Edit global default headers"),
+            HelpEntry::with_section("Key-Value", ":folderheaders", "Edit shared folder headers"),
+            HelpEntry::with_section("Key-Value", ":foldervars", "Edit shared folder variables"),
+            // Auth
+            HelpEntry::with_section("Auth", "j / k", "Select field"),
+            HelpEntry::with_section("Auth", "h / l / Enter", "Cycle auth type (on Type row)"),
+            HelpEntry::with_section("Auth", "i / Enter", "Edit selected field"),
             // Body
             HelpEntry::with_section("Body", "i / a / A / I", "Enter insert mode"),
             HelpEntry::with_section("Body", "o / O", "Insert line below / above"),
             HelpEntry::with_section("Body", "hjkl", "Cursor movement"),
+            HelpEntry::with_section("Body", "t", "Cycle body mode (raw / form / GraphQL)"),
+            HelpEntry::with_section("Body", "v", "Switch GraphQL Query / Variables"),
             // Response
             HelpEntry::with_section("Response", "j / k", "Scroll response"),
             HelpEntry::with_section("Response", "gg / G", "Go to top / bottom"),
             HelpEntry::with_section("Response", "Tab", "Switch Body / Headers"),
-            HelpEntry::with_section("Response", "y", "Copy response body"),
+            HelpEntry::with_section("Response", "w", "Toggle wrapping of long body lines"),
+            HelpEntry::with_section("Response", "P", "Toggle pretty / raw body"),
+            HelpEntry::with_section("Response", "yy", "Copy response body / headers"),
+            HelpEntry::with_section("Response", "yj", "Copy a JSON value by path (a.0.b)"),
+            HelpEntry::with_section("Response", "yb", "Copy the raw body as base64"),
+            HelpEntry::with_section("Response", "H", "Browse response history"),
+            HelpEntry::with_section("Response", "X", "Clear response and error"),
+            HelpEntry::with_section("Response", ":savebody <path>", "Save raw body to a file"),
             // General
             HelpEntry::with_section("General", "<Space>e", "Toggle explorer sidebar"),
         ]
     }
 
-    fn handle_key(&mut self, key: KeyEvent) -> Action {
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        // Handle input prompts first
+        if self.input_prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        // Handle history overlay
+        if self.history.is_some() {
+            return self.handle_history_key(key);
+        }
+
+        // Handle default headers / folder values overlay
+        if self.kv_set_editor.is_some() {
+            return self.handle_kv_set_key(key);
+        }
+
         match self.mode {
             InputMode::Normal => {
                 if self.sidebar.visible && self.sidebar_focused {
@@ -1575,6 +2851,10 @@ impl Tool for HttpTool {
     }
 
     fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) -> Action {
+        if self.input_prompt.is_some() || self.history.is_some() || self.kv_set_editor.is_some() {
+            return Action::None;
+        }
+
         // Don't handle mouse in Insert mode (except scroll)
         let is_scroll = matches!(
             mouse.kind,
@@ -1644,6 +2924,14 @@ impl Tool for HttpTool {
                 self.send_request();
                 Some(Action::None)
             }
+            'r' => {
+                self.rerun_last_request();
+                Some(Action::None)
+            }
+            'i' => {
+                self.open_curl_prompt();
+                Some(Action::None)
+            }
             _ => None,
         }
     }
@@ -1654,21 +2942,28 @@ impl Tool for HttpTool {
             area,
             &self.sidebar,
             &self.panel,
+            &self.last_runs,
             self.sidebar_focused,
-            self.notification.as_deref(),
         );
+
+        if let Some(ref history) = self.history {
+            ui::render_history_overlay(frame, area, history);
+        }
+        if let Some(ref editor) = self.kv_set_editor {
+            ui::render_kv_set_overlay(frame, area, editor);
+        }
+        if let Some(ref prompt) = self.input_prompt {
+            ui::render_input_prompt(frame, area, prompt);
+        }
     }
 
     fn tick(&mut self) {
         self.poll_response();
         self.panel.tick_spinner();
+    }
 
-        if let Some(shown_at) = self.notification_shown_at {
-            if shown_at.elapsed().as_secs() >= 2 {
-                self.notification = None;
-                self.notification_shown_at = None;
-            }
-        }
+    fn take_toasts(&mut self) -> Vec<Toast> {
+        std::mem::take(&mut self.toasts)
     }
 
     fn reset_key_state(&mut self) {
@@ -1676,13 +2971,40 @@ impl Tool for HttpTool {
     }
 
     fn handle_paste(&mut self, text: &str) -> Action {
+        if let Some(prompt) = self.input_prompt.as_mut() {
+            // Fold multi-line commands onto one line; `\` continuations
+            // are handled by the parser.
+            let folded = text.replace(['\n', '\r'], " ");
+            prompt.buffer_mut().push_str(&folded);
+            return Action::None;
+        }
+
+        if let Some(editor) = self.kv_set_editor.as_mut() {
+            if let Some(field) = editor.editing
+                && let Some(row) = editor.rows.get_mut(editor.selected)
+            {
+                let target = match field {
+                    KvField::Key => &mut row.key,
+                    KvField::Value => &mut row.value,
+                };
+                let text: String = text.chars().filter(|c| *c != '\n' && *c != '\r').collect();
+                target.insert_str(row.cursor, &text);
+                row.cursor += text.len();
+            }
+            return Action::None;
+        }
+
         if self.mode == InputMode::Insert && self.panel.is_active() {
             // If in insert mode on the body section, paste into the body
-            if self.panel.focused_section == Section::Body {
+            if self.panel.focused_section == Section::Body && !self.panel.is_form_body() {
                 self.panel.body_insert_text(text);
-            } else if let Some(Section::Params | Section::Headers) =
-                Some(self.panel.focused_section)
-            {
+            } else if self.panel.focused_section == Section::Auth {
+                for c in text.chars() {
+                    if c != '\n' && c != '\r' {
+                        self.panel.auth_insert_char(c);
+                    }
+                }
+            } else if self.panel.in_kv_section() {
                 // For KV sections, insert into the active field (strip newlines)
                 for c in text.chars() {
                     if c != '\n' && c != '\r' {
@@ -1715,14 +3037,127 @@ impl Tool for HttpTool {
         let _ = HttpSidebarExt::reload(&mut self.sidebar, &self.conn);
     }
 
+    fn on_blur(&mut self) {
+        self.cache_current_response();
+    }
+
+    fn interrupt(&mut self) -> bool {
+        if !self.panel.request_in_flight {
+            return false;
+        }
+        // `poll_response` resets the flag when the cancelled result arrives
+        self.executor.cancel();
+        true
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.panel.is_active() && self.panel.dirty
+    }
+
+    fn discard_changes(&mut self) {
+        if let Some(entry_id) = self.panel.active_entry_id {
+            let name = self.panel.active_entry_name.clone();
+            let _ = self.panel.load(entry_id, &name, &self.conn);
+        }
+    }
+
     fn handle_command(&mut self, cmd: &str) -> bool {
-        match cmd.trim() {
+        let cmd = cmd.trim();
+        let (name, args) = match cmd.split_once(' ') {
+            Some((name, args)) => (name, args.trim()),
+            None => (cmd, ""),
+        };
+
+        match name {
             "w" | "write" => self.save_panel(),
+            "curl" => {
+                if args.is_empty() {
+                    self.open_curl_prompt();
+                } else {
+                    self.import_curl(args);
+                }
+                true
+            }
+            "timeout" => {
+                self.set_timeout(args);
+                true
+            }
+            "env" => {
+                let target = if args.is_empty() { None } else { Some(args) };
+                if let Err(e) = self.select_environment(target) {
+                    self.show_notification(format!("Environment error: {e}"));
+                }
+                true
+            }
+            "envset" => {
+                let Some((key, value)) = args.split_once(' ') else {
+                    self.show_notification("Usage: :envset <KEY> <value>");
+                    return true;
+                };
+                if let Err(e) = self.set_environment_var(key, Some(value.trim())) {
+                    self.show_notification(format!("Environment error: {e}"));
+                }
+                true
+            }
+            "envunset" if !args.is_empty() => {
+                if let Err(e) = self.set_environment_var(args, None) {
+                    self.show_notification(format!("Environment error: {e}"));
+                }
+                true
+            }
+            "savebody" => self.save_response_body(args),
+            "defaults" => {
+                self.open_default_headers();
+                true
+            }
+            "folderheaders" => {
+                self.open_folder_values(false);
+                true
+            }
+            "foldervars" => {
+                self.open_folder_values(true);
+                true
+            }
+            "proxy" => {
+                self.set_proxy(args);
+                true
+            }
+            "insecure" => {
+                self.set_insecure(args);
+                true
+            }
+            "export" => {
+                self.export_collection(args);
+                true
+            }
+            "export-postman" => {
+                self.export_postman(args);
+                true
+            }
+            "import" => {
+                self.import_collection(args);
+                true
+            }
+            "sort" => {
+                self.sort_current_folder();
+                true
+            }
             _ => false,
         }
     }
 }
 
+// ── Utility functions ────────────────────────────────────────────────
+
+/// Load the enabled global default headers as (key, value) pairs.
+fn enabled_default_headers(conn: &Connection) -> anyhow::Result<Vec<(String, String)>> {
+    Ok(model::load_default_headers(conn)?
+        .into_iter()
+        .filter(|h| h.enabled)
+        .map(|h| (h.key, h.value))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1733,6 +3168,10 @@ mod tests {
         HttpTool::new(conn).unwrap()
     }
 
+    fn last_toast(tool: &HttpTool) -> Option<&str> {
+        tool.toasts.last().map(|t| t.message.as_str())
+    }
+
     #[test]
     fn test_create_simple_query() {
         let mut tool = setup_tool();
@@ -1975,4 +3414,910 @@ mod tests {
         assert!(labels.contains(&"post-user"));
         assert!(labels.contains(&"health-check"));
     }
+
+    #[test]
+    fn test_telescope_preview_is_cached_per_listing() {
+        let mut tool = setup_tool();
+        let query = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        let request_id = model::ensure_request(&tool.conn, query).unwrap();
+        let save = |url: &str| {
+            let method = model::HttpMethod::Get;
+            model::save_request(&tool.conn, request_id, method, url, "").unwrap();
+        };
+        save("https://x/users");
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+
+        let id = format!("http:{query}");
+        tool.telescope_items();
+        let preview = tool.telescope_preview(&id);
+        assert_eq!(preview.as_deref(), Some("GET https://x/users"));
+
+        // Moving the highlight back reuses the loaded preview
+        save("https://x/accounts");
+        assert_eq!(tool.telescope_preview(&id), preview);
+
+        tool.telescope_items();
+        let preview = tool.telescope_preview(&id);
+        assert_eq!(preview.as_deref(), Some("GET https://x/accounts"));
+        assert_eq!(tool.telescope_preview("http:nope"), None);
+    }
+
+    #[test]
+    fn test_env_commands() {
+        let mut tool = setup_tool();
+        assert!(tool.handle_command("env staging"));
+        assert_eq!(tool.panel.active_env.as_deref(), Some("staging"));
+
+        assert!(tool.handle_command("envset HOST staging.example.com"));
+        assert_eq!(
+            tool.panel.resolve("https://${HOST}/"),
+            "https://staging.example.com/"
+        );
+
+        assert!(tool.handle_command("envunset HOST"));
+        assert!(tool.panel.env_vars.is_empty());
+
+        assert!(tool.handle_command("env"));
+        assert!(tool.panel.active_env.is_none());
+    }
+
+    #[test]
+    fn test_curl_import() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "imported", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "imported");
+
+        // Malformed input leaves the panel untouched
+        assert!(tool.handle_command("curl 'https://unterminated"));
+        assert!(tool.panel.error_message.is_some());
+        assert!(tool.panel.url.is_empty());
+        assert!(!tool.panel.dirty);
+
+        assert!(tool.handle_command(
+            "curl -X PATCH -H 'Content-Type: application/json' -d '{}' 'https://example.com/a?x=1'"
+        ));
+        assert!(tool.panel.error_message.is_none());
+        assert!(tool.panel.dirty);
+        assert_eq!(tool.panel.method, model::HttpMethod::Patch);
+        assert_eq!(tool.panel.url, "https://example.com/a");
+        assert_eq!(tool.panel.query_params[0].key, "x");
+        assert_eq!(tool.panel.headers[0].value, "application/json");
+        assert_eq!(tool.panel.body_text(), "{}");
+
+        // A pasted command keeps working after `:curl`
+        assert!(tool.handle_command("curl curl https://example.com/c"));
+        assert_eq!(tool.panel.url, "https://example.com/c");
+        assert_eq!(tool.panel.method, model::HttpMethod::Get);
+
+        // Without arguments the prompt opens and Enter imports the buffer
+        assert!(tool.handle_command("curl"));
+        assert!(tool.input_prompt.is_some());
+        tool.handle_paste("curl \\\n  https://example.com/b");
+        tool.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tool.input_prompt.is_none());
+        assert_eq!(tool.panel.url, "https://example.com/b");
+        assert_eq!(tool.panel.method, model::HttpMethod::Get);
+
+        // `-u` becomes Basic auth; unsupported body flags are reported
+        assert!(tool.handle_command("curl -u ada:pw https://example.com/d"));
+        assert_eq!(tool.panel.auth.auth_type, model::AuthType::Basic);
+        assert_eq!(tool.panel.auth.password, "pw");
+        assert!(tool.handle_command("curl https://example.com/e -F a=b"));
+        assert_eq!(
+            tool.panel.error_message.as_deref(),
+            Some("Could not parse cURL command: -F is not supported")
+        );
+        assert_eq!(tool.panel.url, "https://example.com/d");
+    }
+
+    #[test]
+    fn test_timeout_command() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "slow", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "slow");
+
+        assert!(tool.handle_command("timeout 5000"));
+        assert_eq!(tool.panel.timeout_ms, Some(5000));
+        assert!(tool.panel.dirty);
+
+        // Invalid values leave the timeout unchanged
+        assert!(tool.handle_command("timeout soon"));
+        assert_eq!(tool.panel.timeout_ms, Some(5000));
+
+        assert!(tool.handle_command("w"));
+        let req = model::load_request(&tool.conn, entry_id).unwrap().unwrap();
+        assert_eq!(req.timeout_ms, Some(5000));
+
+        assert!(tool.handle_command("timeout off"));
+        assert_eq!(tool.panel.timeout_ms, None);
+    }
+
+    #[test]
+    fn test_response_history_overlay() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "users");
+
+        // Nothing stored yet: the overlay stays closed
+        tool.open_history();
+        assert!(tool.history.is_none());
+
+        for (status, body) in [(500, "boom"), (200, "ok")] {
+            model::add_response_history(
+                &tool.conn,
+                &model::HttpResponseRecord {
+                    id: 0,
+                    entry_id,
+                    status_code: status,
+                    status_text: String::new(),
+                    elapsed_ms: 10,
+                    size_bytes: body.len(),
+                    headers: Vec::new(),
+                    body: body.to_string(),
+                    raw_body: Some(body.as_bytes().to_vec()),
+                    created_at: String::new(),
+                },
+            )
+            .unwrap();
+        }
+
+        tool.open_history();
+        assert_eq!(tool.history.as_ref().unwrap().records.len(), 2);
+
+        // Newest first; move to the older 500 and load it
+        tool.handle_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        tool.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tool.history.is_none());
+        let response = tool.panel.response.as_ref().unwrap();
+        assert_eq!(response.status_code, 500);
+        assert_eq!(response.body, "boom");
+    }
+
+    #[test]
+    fn test_history_response_keeps_raw_body() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "users");
+        tool.clipboard = None;
+
+        let raw = br#"{"id":1}"#.to_vec();
+        let mut record = model::HttpResponseRecord {
+            id: 0,
+            entry_id,
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 10,
+            size_bytes: raw.len(),
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: "{\n  \"id\": 1\n}".to_string(),
+            raw_body: Some(raw.clone()),
+            created_at: String::new(),
+        };
+        model::add_response_history(&tool.conn, &record).unwrap();
+
+        let press = |tool: &mut HttpTool, code: KeyCode| {
+            tool.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+        tool.open_history();
+        press(&mut tool, KeyCode::Enter);
+        tool.panel.focus_response();
+
+        // The received bytes are saved, copied and shown raw, not the
+        // formatted body
+        let path = std::env::temp_dir().join(format!("rstools-history-{}", std::process::id()));
+        assert!(tool.handle_command(&format!("savebody {}", path.display())));
+        assert_eq!(std::fs::read(&path).unwrap(), raw);
+        std::fs::remove_file(&path).unwrap();
+
+        press(&mut tool, KeyCode::Char('y'));
+        press(&mut tool, KeyCode::Char('b'));
+        assert_eq!(last_toast(&tool), Some("Clipboard unavailable"));
+
+        press(&mut tool, KeyCode::Char('P'));
+        let response = tool.panel.response.as_ref().unwrap();
+        assert!(!response.pretty);
+        assert_eq!(response.shown_body(), r#"{"id":1}"#);
+
+        // Older rows without raw bytes refuse instead of using the formatted body
+        record.raw_body = None;
+        model::add_response_history(&tool.conn, &record).unwrap();
+        tool.open_history();
+        press(&mut tool, KeyCode::Enter);
+
+        press(&mut tool, KeyCode::Char('P'));
+        assert!(tool.panel.response.as_ref().unwrap().pretty);
+        assert_eq!(last_toast(&tool), Some(RAW_BODY_MISSING));
+
+        tool.toasts.clear();
+        press(&mut tool, KeyCode::Char('y'));
+        press(&mut tool, KeyCode::Char('b'));
+        assert_eq!(last_toast(&tool), Some(RAW_BODY_MISSING));
+
+        tool.toasts.clear();
+        assert!(tool.handle_command(&format!("savebody {}", path.display())));
+        assert_eq!(last_toast(&tool), Some(RAW_BODY_MISSING));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_default_headers_overlay() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "users");
+
+        assert!(tool.handle_command("defaults"));
+        assert!(tool.kv_set_editor.is_some());
+
+        // Add `Accept: text/plain`, typed then pasted
+        let press = |tool: &mut HttpTool, code: KeyCode| {
+            tool.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+        press(&mut tool, KeyCode::Char('a'));
+        for c in "Accept".chars() {
+            press(&mut tool, KeyCode::Char(c));
+        }
+        press(&mut tool, KeyCode::Tab);
+        tool.handle_paste("text/plain\n");
+        press(&mut tool, KeyCode::Enter);
+
+        // A second, disabled row is stored but not applied
+        press(&mut tool, KeyCode::Char('a'));
+        press(&mut tool, KeyCode::Char('X'));
+        press(&mut tool, KeyCode::Esc);
+        press(&mut tool, KeyCode::Char('x'));
+        press(&mut tool, KeyCode::Char('q'));
+        assert!(tool.kv_set_editor.is_none());
+
+        let stored = model::load_default_headers(&tool.conn).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].value, "text/plain");
+        assert!(!stored[1].enabled);
+        assert_eq!(
+            tool.panel.enabled_headers(),
+            vec![("Accept".to_string(), "text/plain".to_string())]
+        );
+
+        // Defaults survive switching queries
+        let other = model::add_entry(&tool.conn, None, "other", EntryType::Query).unwrap();
+        tool.open_query(other, "other");
+        assert_eq!(tool.panel.applied_default_headers().len(), 1);
+    }
+
+    #[test]
+    fn test_kv_set_overlay_cursor_and_delete() {
+        let mut tool = setup_tool();
+        model::replace_default_headers(
+            &tool.conn,
+            &[("Acept".to_string(), "json".to_string(), true)],
+        )
+        .unwrap();
+        assert!(tool.handle_command("defaults"));
+
+        let press = |tool: &mut HttpTool, code: KeyCode| {
+            tool.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+
+        // Fix the typo in the middle of the key, then prefix the value
+        press(&mut tool, KeyCode::Char('i'));
+        for _ in 0..3 {
+            press(&mut tool, KeyCode::Left);
+        }
+        press(&mut tool, KeyCode::Char('c'));
+        press(&mut tool, KeyCode::Tab);
+        press(&mut tool, KeyCode::Home);
+        tool.handle_paste("application/");
+        press(&mut tool, KeyCode::End);
+        press(&mut tool, KeyCode::Backspace);
+        press(&mut tool, KeyCode::Char('n'));
+        press(&mut tool, KeyCode::Enter);
+        let row = &tool.kv_set_editor.as_ref().unwrap().rows[0];
+        assert_eq!(row.key, "Accept");
+        assert_eq!(row.value, "application/json");
+
+        // `d` asks first; `n` keeps the row, `y` removes it
+        press(&mut tool, KeyCode::Char('d'));
+        let editor = tool.kv_set_editor.as_ref().unwrap();
+        assert_eq!(
+            editor.delete_prompt.as_ref().map(|p| p.message()),
+            Some("Delete 'Accept'?")
+        );
+        press(&mut tool, KeyCode::Char('n'));
+        assert_eq!(tool.kv_set_editor.as_ref().unwrap().rows.len(), 1);
+        press(&mut tool, KeyCode::Char('d'));
+        press(&mut tool, KeyCode::Char('y'));
+        let editor = tool.kv_set_editor.as_ref().unwrap();
+        assert!(editor.rows.is_empty());
+        assert!(editor.delete_prompt.is_none());
+    }
+
+    #[test]
+    fn test_folder_vars_command() {
+        let mut tool = setup_tool();
+        assert!(tool.handle_command("foldervars"));
+        assert!(tool.kv_set_editor.is_none());
+
+        let folder = model::add_entry(&tool.conn, None, "api", EntryType::Folder).unwrap();
+        let query = model::add_entry(&tool.conn, Some(folder), "users", EntryType::Query).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+        tool.open_query(query, "users");
+        tool.sidebar_focused = false;
+
+        // Acts on the open query's folder and applies on close
+        assert!(tool.handle_command("foldervars"));
+        let editor = tool.kv_set_editor.as_ref().unwrap();
+        assert_eq!(editor.target.title(), "Variables of api");
+        let press = |tool: &mut HttpTool, code: KeyCode| {
+            tool.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+        press(&mut tool, KeyCode::Char('a'));
+        press(&mut tool, KeyCode::Char('V'));
+        press(&mut tool, KeyCode::Tab);
+        press(&mut tool, KeyCode::Char('1'));
+        press(&mut tool, KeyCode::Enter);
+        press(&mut tool, KeyCode::Char('q'));
+        assert!(tool.kv_set_editor.is_none());
+        assert_eq!(tool.panel.resolve("${V}"), "1");
+        assert_eq!(
+            model::load_folder_vars(&tool.conn, folder).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_proxy_command() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "users");
+        tool.panel.url = "https://api.example.com/users".to_string();
+
+        assert!(tool.handle_command("proxy http://proxy.local:3128"));
+        assert_eq!(tool.panel.active_proxy(), Some("http://proxy.local:3128"));
+        assert_eq!(
+            tool.executor.proxy().http.as_deref(),
+            Some("http://proxy.local:3128")
+        );
+
+        // Invalid URLs leave the proxy unchanged
+        assert!(tool.handle_command("proxy not-a-url"));
+        assert_eq!(tool.panel.active_proxy(), Some("http://proxy.local:3128"));
+
+        assert!(tool.handle_command("proxy off"));
+        assert_eq!(tool.panel.active_proxy(), None);
+        assert!(!tool.executor.proxy().is_enabled());
+    }
+
+    #[test]
+    fn test_insecure_command() {
+        let mut tool = setup_tool();
+        assert!(!tool.executor.insecure());
+        assert_eq!(tool.status_warning(), None);
+
+        assert!(tool.handle_command("insecure on"));
+        assert!(tool.executor.insecure());
+        assert!(tool.status_warning().unwrap().contains("INSECURE"));
+
+        // Unknown arguments leave the flag alone
+        assert!(tool.handle_command("insecure maybe"));
+        assert!(tool.executor.insecure());
+
+        assert!(tool.handle_command("insecure off"));
+        assert_eq!(tool.status_warning(), None);
+    }
+
+    #[test]
+    fn test_export_import_commands() {
+        let mut tool = setup_tool();
+        let folder = model::add_entry(&tool.conn, None, "api", EntryType::Folder).unwrap();
+        let query = model::add_entry(&tool.conn, Some(folder), "users", EntryType::Query).unwrap();
+        let request_id = model::ensure_request(&tool.conn, query).unwrap();
+        model::save_request(
+            &tool.conn,
+            request_id,
+            model::HttpMethod::Put,
+            "https://x/users",
+            "{}",
+        )
+        .unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rstools-http-collection-{}.json",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap().to_string();
+        assert!(tool.handle_command(&format!("export {path}")));
+        assert_eq!(
+            last_toast(&tool),
+            Some(format!("Exported 1 queries to {path}").as_str())
+        );
+
+        // Folders are not counted in the Postman export either
+        let postman = format!("{path}.postman");
+        assert!(tool.handle_command(&format!("export-postman {postman}")));
+        assert_eq!(
+            last_toast(&tool),
+            Some(format!("Exported 1 queries to {postman} (Postman)").as_str())
+        );
+        std::fs::remove_file(&postman).unwrap();
+
+        // Import next to the selected (collapsed) "api" folder: a second copy
+        tool.sidebar.select_entry(folder);
+        assert!(tool.handle_command(&format!("import {path}")));
+        std::fs::remove_file(&path).unwrap();
+
+        let entries = model::list_entries(&tool.conn).unwrap();
+        let copies: Vec<_> = entries.iter().filter(|e| e.name == "users").collect();
+        assert_eq!(copies.len(), 2);
+        let copy = copies.iter().find(|e| e.id != query).unwrap();
+        assert_ne!(copy.parent_id, Some(folder));
+        let request = model::load_request(&tool.conn, copy.id).unwrap().unwrap();
+        assert_eq!(request.method, model::HttpMethod::Put);
+        assert_eq!(request.url, "https://x/users");
+
+        // Missing files only show a notification
+        tool.toasts.clear();
+        assert!(tool.handle_command("import /nonexistent/collection.json"));
+        assert!(last_toast(&tool).unwrap().starts_with("Import failed"));
+    }
+
+    #[test]
+    fn test_dirty_and_discard_changes() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "users", EntryType::Query).unwrap();
+        assert!(!tool.is_dirty());
+        tool.open_query(entry_id, "users");
+        assert!(!tool.is_dirty());
+
+        for c in "http://a".chars() {
+            tool.panel.url_insert_char(c);
+        }
+        assert!(tool.is_dirty());
+        tool.discard_changes();
+        assert!(!tool.is_dirty());
+        assert_eq!(tool.panel.url, "");
+
+        for c in "http://b".chars() {
+            tool.panel.url_insert_char(c);
+        }
+        assert!(tool.handle_command("w"));
+        assert!(!tool.is_dirty());
+        tool.discard_changes();
+        assert_eq!(tool.panel.url, "http://b");
+    }
+
+    #[test]
+    fn test_missing_body_file_aborts_send() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "upload", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "upload");
+        tool.panel.url = "http://127.0.0.1:9/".to_string();
+        tool.panel.body_mode = model::BodyMode::File;
+        tool.panel.body_insert_text("/nonexistent/payload.json");
+
+        tool.send_request();
+        assert!(!tool.panel.request_in_flight);
+        assert_eq!(
+            tool.panel.error_message.as_deref(),
+            Some("Body file not found: /nonexistent/payload.json")
+        );
+        assert_eq!(tool.last_sent_entry_id, None);
+    }
+
+    #[test]
+    fn test_interrupt_cancels_request() {
+        let mut tool = setup_tool();
+        assert!(!tool.interrupt());
+
+        // Accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let entry_id = model::add_entry(&tool.conn, None, "slow", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "slow");
+        tool.panel.url = format!("http://{}/", listener.local_addr().unwrap());
+        tool.send_request();
+        assert!(tool.interrupt());
+
+        let started = std::time::Instant::now();
+        while tool.panel.request_in_flight && started.elapsed().as_secs() < 10 {
+            tool.poll_response();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!tool.panel.request_in_flight);
+        assert!(tool.panel.cancelled);
+        assert_eq!(tool.panel.error_message, None);
+        assert!(!tool.interrupt());
+
+        // Cancelled after switching away: the open query keeps its own state
+        let other = model::add_entry(&tool.conn, None, "other", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "slow");
+        tool.panel.url = format!("http://{}/", listener.local_addr().unwrap());
+        tool.send_request();
+        tool.open_query(other, "other");
+        tool.panel.error_message = Some("URL is empty".to_string());
+        assert!(tool.interrupt());
+        wait_for_response(&mut tool);
+        assert!(!tool.panel.cancelled);
+        assert_eq!(tool.panel.error_message.as_deref(), Some("URL is empty"));
+        tool.open_query(entry_id, "slow");
+        assert!(tool.panel.cancelled);
+        drop(listener);
+    }
+
+    /// Answer one request on `listener` with `200 OK` and a short body.
+    fn serve_once(listener: std::net::TcpListener) -> std::thread::JoinHandle<()> {
+        use std::io::{Read, Write};
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+            stream.write_all(response.as_bytes()).unwrap();
+        })
+    }
+
+    fn wait_for_response(tool: &mut HttpTool) {
+        let started = std::time::Instant::now();
+        while tool.panel.request_in_flight && started.elapsed().as_secs() < 10 {
+            tool.poll_response();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!tool.panel.request_in_flight);
+    }
+
+    #[test]
+    fn test_last_run_follows_the_sent_query() {
+        let mut tool = setup_tool();
+        let sent = model::add_entry(&tool.conn, None, "sent", EntryType::Query).unwrap();
+        let other = model::add_entry(&tool.conn, None, "other", EntryType::Query).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        tool.open_query(sent, "sent");
+        tool.panel.url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+        tool.send_request();
+
+        // Switching queries before the response arrives
+        tool.open_query(other, "other");
+        wait_for_response(&mut tool);
+        server.join().unwrap();
+        assert!(tool.panel.response.is_none());
+        assert_eq!(tool.last_runs.get(&sent).map(|r| r.status_code), Some(200));
+        assert!(!tool.last_runs.contains_key(&other));
+        let history = model::list_response_history(&tool.conn, sent).unwrap();
+        assert_eq!(history.len(), 1);
+        let history = model::list_response_history(&tool.conn, other).unwrap();
+        assert!(history.is_empty());
+
+        // The response waits in the sent query's cache
+        tool.open_query(sent, "sent");
+        let response = tool.panel.response.as_ref().unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, "ok");
+
+        // Deleting the query drops its run
+        tool.sidebar.select_entry(sent);
+        tool.execute_delete();
+        assert!(tool.last_runs.is_empty());
+    }
+
+    #[test]
+    fn test_rerun_last_request() {
+        let mut tool = setup_tool();
+        let first = model::add_entry(&tool.conn, None, "first", EntryType::Query).unwrap();
+        let second = model::add_entry(&tool.conn, None, "second", EntryType::Query).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+
+        // Nothing sent yet
+        assert!(tool.handle_leader_action('r').is_some());
+        assert_eq!(last_toast(&tool), Some("No request sent yet"));
+
+        // Nothing listens on port 9; the request just fails fast
+        tool.open_query(first, "first");
+        tool.panel.url = "http://127.0.0.1:9/".to_string();
+        assert!(tool.save_panel());
+        tool.send_request();
+        assert_eq!(tool.last_sent_entry_id, Some(first));
+        let started = std::time::Instant::now();
+        while tool.panel.request_in_flight && started.elapsed().as_secs() < 10 {
+            tool.poll_response();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        // From another query and the sidebar, <Space>r reopens and resends `first`
+        tool.open_query(second, "second");
+        tool.sidebar_focused = true;
+        tool.handle_leader_action('r');
+        assert_eq!(tool.panel.active_entry_id, Some(first));
+        assert!(tool.panel.request_in_flight);
+
+        // A deleted query clears the shortcut
+        model::delete_entry(&tool.conn, first).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+        tool.open_query(second, "second");
+        tool.handle_leader_action('r');
+        assert_eq!(tool.last_sent_entry_id, None);
+    }
+
+    #[test]
+    fn test_delete_and_undo_subtree() {
+        let mut tool = setup_tool();
+        let api = model::add_entry(&tool.conn, None, "api", EntryType::Folder).unwrap();
+        let users = model::add_entry(&tool.conn, Some(api), "users", EntryType::Folder).unwrap();
+        let get = model::add_entry(&tool.conn, Some(users), "get", EntryType::Query).unwrap();
+        let request_id = model::ensure_request(&tool.conn, get).unwrap();
+        model::save_request(
+            &tool.conn,
+            request_id,
+            model::HttpMethod::Delete,
+            "https://x/u/1",
+            "",
+        )
+        .unwrap();
+        model::add_entry(&tool.conn, Some(api), "health", EntryType::Query).unwrap();
+        let auth = (
+            "Authorization".to_string(),
+            "Bearer ${TOKEN}".to_string(),
+            true,
+        );
+        model::replace_folder_headers(&tool.conn, users, std::slice::from_ref(&auth)).unwrap();
+        let var = ("TOKEN".to_string(), "abc".to_string(), false);
+        model::replace_folder_vars(&tool.conn, users, std::slice::from_ref(&var)).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+        tool.sidebar.expand_to_entry(users);
+        tool.sidebar.select_entry(users);
+
+        let press = |tool: &mut HttpTool, c: char| {
+            tool.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        };
+        press(&mut tool, 'd');
+        assert_eq!(tool.sidebar.delete_prompt(), "Delete 'users' and 1 item?");
+        press(&mut tool, 'y');
+        let names = |tool: &HttpTool| -> Vec<String> {
+            model::list_entries(&tool.conn)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        };
+        assert_eq!(names(&tool), vec!["api", "health"]);
+
+        press(&mut tool, 'u');
+        assert_eq!(names(&tool), vec!["api", "users", "get", "health"]);
+        let restored = model::list_entries(&tool.conn)
+            .unwrap()
+            .into_iter()
+            .find(|e| e.name == "get")
+            .unwrap();
+        let request = model::load_request(&tool.conn, restored.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.method, model::HttpMethod::Delete);
+        assert_eq!(request.url, "https://x/u/1");
+        assert_eq!(
+            tool.sidebar.selected_entry().map(|e| e.name.as_str()),
+            Some("users")
+        );
+        // The folder's headers and variables come back with it
+        let users = restored.parent_id.unwrap();
+        let rows = |values: Vec<model::HttpFolderValue>| -> Vec<(String, String, bool)> {
+            values
+                .into_iter()
+                .map(|v| (v.key, v.value, v.enabled))
+                .collect()
+        };
+        let headers = model::load_folder_headers(&tool.conn, users).unwrap();
+        assert_eq!(rows(headers), vec![auth]);
+        let vars = model::load_folder_vars(&tool.conn, users).unwrap();
+        assert_eq!(rows(vars), vec![var]);
+
+        // Undo is one step only
+        tool.toasts.clear();
+        press(&mut tool, 'u');
+        assert_eq!(last_toast(&tool), Some("Nothing to undo"));
+
+        // A later edit drops the snapshot
+        tool.sidebar.select_entry(api);
+        press(&mut tool, 'd');
+        press(&mut tool, 'y');
+        tool.create_entries_from_path("other");
+        tool.toasts.clear();
+        press(&mut tool, 'u');
+        assert_eq!(last_toast(&tool), Some("Nothing to undo"));
+        assert_eq!(names(&tool), vec!["other"]);
+    }
+
+    #[test]
+    fn test_savebody_command() {
+        let mut tool = setup_tool();
+
+        // No response loaded: the hub reports the command as failed
+        assert!(!tool.handle_command("savebody /tmp/unused"));
+
+        let raw = vec![0x89, b'P', b'N', b'G', 0xff, 0x00];
+        tool.panel.response = Some(ResponseData {
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 1,
+            size_bytes: raw.len(),
+            headers: vec![("content-type".to_string(), "image/png".to_string())],
+            body: body_format::hex_dump(&raw),
+            raw_body: Some(raw.clone()),
+            language: BodyLanguage::Binary,
+            pretty: true,
+            body_scroll: 0,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(0),
+        });
+
+        let path = std::env::temp_dir().join(format!("rstools-savebody-{}", std::process::id()));
+        assert!(tool.handle_command(&format!("savebody {}", path.display())));
+        assert_eq!(std::fs::read(&path).unwrap(), raw);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_json_path_prompt() {
+        let mut tool = setup_tool();
+        let entry_id = model::add_entry(&tool.conn, None, "login", EntryType::Query).unwrap();
+        tool.open_query(entry_id, "login");
+        tool.clipboard = None;
+
+        let body = r#"{"data": {"token": "abc"}}"#;
+        tool.panel.response = Some(ResponseData {
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 1,
+            size_bytes: body.len(),
+            headers: Vec::new(),
+            body: body.to_string(),
+            raw_body: Some(body.as_bytes().to_vec()),
+            language: BodyLanguage::Json,
+            pretty: true,
+            body_scroll: 0,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(0),
+        });
+        tool.panel.focus_response();
+
+        let press = |tool: &mut HttpTool, c: char| {
+            tool.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        };
+        press(&mut tool, 'y');
+        press(&mut tool, 'j');
+        assert!(matches!(
+            tool.input_prompt,
+            Some(InputPrompt::JsonPath { .. })
+        ));
+
+        // An unknown path reports an error
+        for c in "data.nope".chars() {
+            press(&mut tool, c);
+        }
+        tool.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tool.input_prompt.is_none());
+        assert_eq!(last_toast(&tool), Some("No value at path 'data.nope'"));
+
+        // A resolved path reaches the clipboard step
+        tool.copy_json_path("data.token");
+        assert_eq!(last_toast(&tool), Some("Clipboard unavailable"));
+
+        // `yy` copies the focused section; there are no headers to copy here
+        press(&mut tool, 'y');
+        press(&mut tool, 'y');
+        assert_eq!(last_toast(&tool), Some("Clipboard unavailable"));
+        tool.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        press(&mut tool, 'y');
+        press(&mut tool, 'y');
+        assert_eq!(last_toast(&tool), Some("No response headers"));
+    }
+
+    #[test]
+    fn test_open_query_restores_focus_and_scroll() {
+        let mut tool = setup_tool();
+        let a = model::add_entry(&tool.conn, None, "a", EntryType::Query).unwrap();
+        let b = model::add_entry(&tool.conn, None, "b", EntryType::Query).unwrap();
+
+        tool.open_query(a, "a");
+        let body = "1\n2\n3\n4\n5".to_string();
+        tool.panel.response = Some(ResponseData {
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 1,
+            size_bytes: body.len(),
+            headers: Vec::new(),
+            raw_body: Some(body.as_bytes().to_vec()),
+            body,
+            language: BodyLanguage::Text,
+            pretty: true,
+            body_scroll: 3,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(0),
+        });
+        tool.panel.focused_section = Section::Headers;
+        tool.panel.focus_response();
+
+        // A query never opened before starts on the URL
+        tool.open_query(b, "b");
+        assert_eq!(tool.panel.focused_section, Section::Url);
+        assert_eq!(tool.panel.panel_focus, PanelFocus::Request);
+        tool.panel.focused_section = Section::Body;
+
+        tool.open_query(a, "a");
+        assert_eq!(tool.panel.focused_section, Section::Headers);
+        assert_eq!(tool.panel.panel_focus, PanelFocus::Response);
+        assert_eq!(tool.panel.response.as_ref().unwrap().body_scroll, 3);
+
+        // Focus is remembered even without a response
+        tool.open_query(b, "b");
+        assert_eq!(tool.panel.focused_section, Section::Body);
+        assert!(tool.panel.response.is_none());
+    }
+
+    #[test]
+    fn test_clear_response() {
+        let mut tool = setup_tool();
+        let a = model::add_entry(&tool.conn, None, "a", EntryType::Query).unwrap();
+        let b = model::add_entry(&tool.conn, None, "b", EntryType::Query).unwrap();
+
+        tool.open_query(a, "a");
+        tool.panel.error_message = Some("Connection refused".to_string());
+        tool.panel.focus_response();
+        tool.handle_key(KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT));
+        assert!(tool.panel.response.is_none());
+        assert!(tool.panel.error_message.is_none());
+
+        // The stale error does not come back from the cache
+        tool.open_query(b, "b");
+        tool.open_query(a, "a");
+        assert!(tool.panel.error_message.is_none());
+    }
+
+    #[test]
+    fn test_reorder_and_sort_entries() {
+        let mut tool = setup_tool();
+        for name in ["a", "b", "c"] {
+            model::add_entry(&tool.conn, None, name, EntryType::Query).unwrap();
+        }
+        model::add_entry(&tool.conn, None, "folder", EntryType::Folder).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+        let names = |tool: &HttpTool| -> Vec<String> {
+            tool.sidebar
+                .flat_view
+                .iter()
+                .map(|e| e.name.clone())
+                .collect()
+        };
+        let press = |tool: &mut HttpTool, c: char| {
+            tool.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT));
+        };
+
+        // Queries move among queries only; the folder stays first
+        tool.sidebar.select_entry(3);
+        press(&mut tool, 'K');
+        press(&mut tool, 'K');
+        press(&mut tool, 'K');
+        assert_eq!(names(&tool), vec!["folder", "c", "a", "b"]);
+        assert_eq!(tool.sidebar.selected_entry_id(), Some(3));
+        press(&mut tool, 'J');
+        assert_eq!(names(&tool), vec!["folder", "a", "c", "b"]);
+
+        // The order survives a reload, and new entries go after it
+        model::add_entry(&tool.conn, None, "0-new", EntryType::Query).unwrap();
+        HttpSidebarExt::reload(&mut tool.sidebar, &tool.conn).unwrap();
+        assert_eq!(names(&tool), vec!["folder", "a", "c", "b", "0-new"]);
+
+        assert!(tool.handle_command("sort"));
+        assert_eq!(names(&tool), vec!["folder", "0-new", "a", "b", "c"]);
+        assert_eq!(tool.sidebar.selected_entry_id(), Some(3));
+    }
 }
@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
+use rstools_core::db::{self, Migration};
 use rusqlite::Connection;
 
 // ── Entry types ──────────────────────────────────────────────────────
@@ -84,6 +87,66 @@ pub struct HttpRequest {
     pub method: HttpMethod,
     pub url: String,
     pub body: String,
+    /// Request timeout in milliseconds (None = no timeout).
+    pub timeout_ms: Option<u64>,
+    pub body_mode: BodyMode,
+    /// GraphQL variables (JSON text); the query itself is stored in `body`.
+    pub graphql_variables: String,
+    /// Whether the global default headers are sent with this request.
+    pub use_default_headers: bool,
+}
+
+/// How the request body is edited and sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyMode {
+    /// Free-form text sent as-is.
+    #[default]
+    Raw,
+    /// Key/value rows sent as `application/x-www-form-urlencoded`.
+    FormUrlEncoded,
+    /// A GraphQL query plus JSON variables, sent as a JSON POST.
+    GraphQl,
+    /// The body text is a file path; the file is read when the request is sent.
+    File,
+}
+
+impl BodyMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BodyMode::Raw => "raw",
+            BodyMode::FormUrlEncoded => "form",
+            BodyMode::GraphQl => "graphql",
+            BodyMode::File => "file",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "form" => BodyMode::FormUrlEncoded,
+            "graphql" => BodyMode::GraphQl,
+            "file" => BodyMode::File,
+            _ => BodyMode::Raw,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            BodyMode::Raw => "Raw",
+            BodyMode::FormUrlEncoded => "Form URL-Encoded",
+            BodyMode::GraphQl => "GraphQL",
+            BodyMode::File => "From File",
+        }
+    }
+
+    /// Returns the next body mode in cycle order.
+    pub fn next(self) -> Self {
+        match self {
+            BodyMode::Raw => BodyMode::FormUrlEncoded,
+            BodyMode::FormUrlEncoded => BodyMode::GraphQl,
+            BodyMode::GraphQl => BodyMode::File,
+            BodyMode::File => BodyMode::Raw,
+        }
+    }
 }
 
 /// A single header row for a request.
@@ -108,6 +171,47 @@ pub struct HttpQueryParam {
     pub sort_order: i64,
 }
 
+/// A global header sent with every request (unless overridden per request).
+#[derive(Debug, Clone)]
+pub struct HttpDefaultHeader {
+    pub id: i64,
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    pub sort_order: i64,
+}
+
+/// A header or variable row set on a folder, inherited by the queries below it.
+#[derive(Debug, Clone)]
+pub struct HttpFolderValue {
+    pub id: i64,
+    pub entry_id: i64,
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    pub sort_order: i64,
+}
+
+/// An enabled folder header or variable as seen from a query below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InheritedValue {
+    pub key: String,
+    pub value: String,
+    /// Name of the folder the value comes from.
+    pub folder: String,
+}
+
+/// A single form field row of a form-urlencoded request body.
+#[derive(Debug, Clone)]
+pub struct HttpFormField {
+    pub id: i64,
+    pub request_id: i64,
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    pub sort_order: i64,
+}
+
 impl EntryType {
     pub fn as_str(&self) -> &str {
         match self {
@@ -133,10 +237,184 @@ pub struct HttpEntry {
     pub name: String,
     pub entry_type: EntryType,
     pub expanded: bool,
+    /// Manual order among siblings of the same kind (0 = alphabetical).
+    pub position: i64,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// A named set of variables substituted into requests via `${VAR}` placeholders.
+#[derive(Debug, Clone)]
+pub struct HttpEnvironment {
+    pub id: i64,
+    pub name: String,
+    pub active: bool,
+}
+
+/// A single variable belonging to an environment.
+#[derive(Debug, Clone)]
+pub struct HttpEnvVar {
+    pub id: i64,
+    pub environment_id: i64,
+    pub key: String,
+    pub value: String,
+}
+
+/// Authentication scheme applied to a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthType {
+    #[default]
+    None,
+    Bearer,
+    Basic,
+    ApiKey,
+}
+
+impl AuthType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AuthType::None => "none",
+            AuthType::Bearer => "bearer",
+            AuthType::Basic => "basic",
+            AuthType::ApiKey => "apikey",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "bearer" => AuthType::Bearer,
+            "basic" => AuthType::Basic,
+            "apikey" => AuthType::ApiKey,
+            _ => AuthType::None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            AuthType::None => "None",
+            AuthType::Bearer => "Bearer",
+            AuthType::Basic => "Basic",
+            AuthType::ApiKey => "API Key",
+        }
+    }
+
+    /// Returns the next auth type in cycle order.
+    pub fn next(self) -> Self {
+        match self {
+            AuthType::None => AuthType::Bearer,
+            AuthType::Bearer => AuthType::Basic,
+            AuthType::Basic => AuthType::ApiKey,
+            AuthType::ApiKey => AuthType::None,
+        }
+    }
+
+    /// Returns the previous auth type in cycle order.
+    pub fn prev(self) -> Self {
+        match self {
+            AuthType::None => AuthType::ApiKey,
+            AuthType::Bearer => AuthType::None,
+            AuthType::Basic => AuthType::Bearer,
+            AuthType::ApiKey => AuthType::Basic,
+        }
+    }
+}
+
+/// Per-request authentication settings. Only the fields relevant to
+/// `auth_type` are used; the others are kept so switching types is lossless.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpAuth {
+    pub auth_type: AuthType,
+    pub token: String,
+    pub username: String,
+    pub password: String,
+    pub api_key_header: String,
+    pub api_key_value: String,
+}
+
+impl HttpAuth {
+    /// The header this auth config adds to the request, if any.
+    pub fn header(&self) -> Option<(String, String)> {
+        use base64::Engine;
+
+        match self.auth_type {
+            AuthType::None => None,
+            AuthType::Bearer if !self.token.is_empty() => Some((
+                "Authorization".to_string(),
+                format!("Bearer {}", self.token),
+            )),
+            AuthType::Basic if !self.username.is_empty() || !self.password.is_empty() => {
+                let credentials = format!("{}:{}", self.username, self.password);
+                let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+                Some(("Authorization".to_string(), format!("Basic {encoded}")))
+            }
+            AuthType::ApiKey if !self.api_key_header.is_empty() => {
+                Some((self.api_key_header.clone(), self.api_key_value.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Maximum number of stored responses kept per query.
+pub const RESPONSE_HISTORY_LIMIT: usize = 20;
+
+/// A stored response from a past run of a query.
+#[derive(Debug, Clone)]
+pub struct HttpResponseRecord {
+    pub id: i64,
+    pub entry_id: i64,
+    pub status_code: u16,
+    pub status_text: String,
+    pub elapsed_ms: u128,
+    pub size_bytes: usize,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// The body bytes as received. `None` for rows stored before raw bodies
+    /// were kept.
+    pub raw_body: Option<Vec<u8>>,
+    pub created_at: String,
+}
+
+/// Schema changes made after the tables below were first released, oldest
+/// first. Append new steps; never edit or reorder released ones.
+const MIGRATIONS: &[Migration] = &[
+    Migration::AddColumn {
+        table: "http_entries",
+        column: "expanded",
+        definition: "INTEGER NOT NULL DEFAULT 0",
+    },
+    Migration::AddColumn {
+        table: "http_entries",
+        column: "position",
+        definition: "INTEGER NOT NULL DEFAULT 0",
+    },
+    Migration::AddColumn {
+        table: "http_requests",
+        column: "timeout_ms",
+        definition: "INTEGER",
+    },
+    Migration::AddColumn {
+        table: "http_requests",
+        column: "body_mode",
+        definition: "TEXT NOT NULL DEFAULT 'raw'",
+    },
+    Migration::AddColumn {
+        table: "http_requests",
+        column: "graphql_variables",
+        definition: "TEXT NOT NULL DEFAULT ''",
+    },
+    Migration::AddColumn {
+        table: "http_requests",
+        column: "use_default_headers",
+        definition: "INTEGER NOT NULL DEFAULT 1",
+    },
+    Migration::AddColumn {
+        table: "http_response_history",
+        column: "raw_body",
+        definition: "BLOB",
+    },
+];
+
 /// Initialize the database tables for the HTTP tool.
 pub fn init_db(conn: &Connection) -> Result<()> {
     conn.execute_batch(
@@ -187,28 +465,100 @@ pub fn init_db(conn: &Connection) -> Result<()> {
             value TEXT NOT NULL,
             enabled INTEGER NOT NULL DEFAULT 1,
             sort_order INTEGER NOT NULL DEFAULT 0
-        );",
-    )?;
+        );
 
-    // Migration: add expanded column to http_entries if it doesn't exist yet.
-    let has_expanded: bool = conn
-        .prepare("SELECT 1 FROM pragma_table_info('http_entries') WHERE name = 'expanded'")?
-        .exists([])?;
-    if !has_expanded {
-        conn.execute_batch(
-            "ALTER TABLE http_entries ADD COLUMN expanded INTEGER NOT NULL DEFAULT 0;",
-        )?;
-    }
+        CREATE TABLE IF NOT EXISTS http_form_fields (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request_id INTEGER NOT NULL REFERENCES http_requests(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        );
 
-    Ok(())
+        CREATE TABLE IF NOT EXISTS http_default_headers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS http_folder_headers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL REFERENCES http_entries(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS http_folder_vars (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL REFERENCES http_entries(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS http_environments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            active INTEGER NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TRIGGER IF NOT EXISTS http_environments_updated_at
+        AFTER UPDATE ON http_environments
+        BEGIN
+            UPDATE http_environments SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+        END;
+
+        CREATE TABLE IF NOT EXISTS http_environment_vars (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            environment_id INTEGER NOT NULL REFERENCES http_environments(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            UNIQUE(environment_id, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS http_request_auth (
+            request_id INTEGER PRIMARY KEY REFERENCES http_requests(id) ON DELETE CASCADE,
+            auth_type TEXT NOT NULL DEFAULT 'none',
+            token TEXT NOT NULL DEFAULT '',
+            username TEXT NOT NULL DEFAULT '',
+            password TEXT NOT NULL DEFAULT '',
+            api_key_header TEXT NOT NULL DEFAULT '',
+            api_key_value TEXT NOT NULL DEFAULT ''
+        );
+
+        CREATE TABLE IF NOT EXISTS http_response_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL REFERENCES http_entries(id) ON DELETE CASCADE,
+            status_code INTEGER NOT NULL,
+            status_text TEXT NOT NULL DEFAULT '',
+            elapsed_ms INTEGER NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            headers TEXT NOT NULL DEFAULT '[]',
+            body TEXT NOT NULL DEFAULT '',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_http_response_history_entry
+            ON http_response_history(entry_id);",
+    )?;
+
+    db::migrate(conn, "http", MIGRATIONS)
 }
 
 /// List all entries from the database.
 pub fn list_entries(conn: &Connection) -> Result<Vec<HttpEntry>> {
     let mut stmt = conn.prepare(
-        "SELECT id, parent_id, name, entry_type, expanded, created_at, updated_at
+        "SELECT id, parent_id, name, entry_type, expanded, created_at, updated_at, position
          FROM http_entries
-         ORDER BY entry_type ASC, name ASC",
+         ORDER BY entry_type ASC, position ASC, name ASC",
     )?;
     let entries = stmt
         .query_map([], |row| {
@@ -219,6 +569,7 @@ pub fn list_entries(conn: &Connection) -> Result<Vec<HttpEntry>> {
                 name: row.get(2)?,
                 entry_type: EntryType::from_str(&entry_type_str).unwrap_or(EntryType::Query),
                 expanded: row.get::<_, i64>(4)? != 0,
+                position: row.get(7)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
             })
@@ -234,13 +585,50 @@ pub fn add_entry(
     name: &str,
     entry_type: EntryType,
 ) -> Result<i64> {
+    let position = next_position(conn, parent_id, entry_type)?;
     conn.execute(
-        "INSERT INTO http_entries (parent_id, name, entry_type) VALUES (?1, ?2, ?3)",
-        rusqlite::params![parent_id, name, entry_type.as_str()],
+        "INSERT INTO http_entries (parent_id, name, entry_type, position)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![parent_id, name, entry_type.as_str(), position],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Position for an entry joining `parent_id`: appended after manually
+/// ordered siblings, or 0 (alphabetical) when the folder was never reordered.
+fn next_position(conn: &Connection, parent_id: Option<i64>, entry_type: EntryType) -> Result<i64> {
+    let max: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(position), 0) FROM http_entries
+         WHERE parent_id IS ?1 AND entry_type = ?2",
+        rusqlite::params![parent_id, entry_type.as_str()],
+        |row| row.get(0),
+    )?;
+    Ok(if max > 0 { max + 1 } else { 0 })
+}
+
+/// Persist a manual order: each entry's position becomes its index in `ids`.
+pub fn set_entry_positions(conn: &Connection, ids: &[i64]) -> Result<()> {
+    // One transaction, so a failure cannot leave a half-renumbered folder
+    let tx = conn.unchecked_transaction()?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE http_entries SET position = ?1 WHERE id = ?2",
+            rusqlite::params![position as i64, id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Drop the manual order of a folder's children so they sort alphabetically.
+pub fn reset_entry_positions(conn: &Connection, parent_id: Option<i64>) -> Result<()> {
+    conn.execute(
+        "UPDATE http_entries SET position = 0 WHERE parent_id IS ?1",
+        rusqlite::params![parent_id],
+    )?;
+    Ok(())
+}
+
 /// Update the expanded state of a folder entry.
 pub fn set_entry_expanded(conn: &Connection, id: i64, expanded: bool) -> Result<()> {
     conn.execute(
@@ -272,9 +660,16 @@ pub fn delete_entry(conn: &Connection, id: i64) -> Result<()> {
 
 /// Move an entry to a new parent.
 pub fn move_entry(conn: &Connection, id: i64, new_parent_id: Option<i64>) -> Result<()> {
+    let entry_type: String = conn.query_row(
+        "SELECT entry_type FROM http_entries WHERE id = ?1",
+        rusqlite::params![id],
+        |row| row.get(0),
+    )?;
+    let entry_type = EntryType::from_str(&entry_type).unwrap_or(EntryType::Query);
+    let position = next_position(conn, new_parent_id, entry_type)?;
     conn.execute(
-        "UPDATE http_entries SET parent_id = ?1 WHERE id = ?2",
-        rusqlite::params![new_parent_id, id],
+        "UPDATE http_entries SET parent_id = ?1, position = ?2 WHERE id = ?3",
+        rusqlite::params![new_parent_id, position, id],
     )?;
     Ok(())
 }
@@ -288,7 +683,7 @@ pub fn copy_entry_recursive(
 ) -> Result<i64> {
     // Get the source entry
     let source: HttpEntry = conn.query_row(
-        "SELECT id, parent_id, name, entry_type, expanded, created_at, updated_at
+        "SELECT id, parent_id, name, entry_type, expanded, created_at, updated_at, position
          FROM http_entries WHERE id = ?1",
         rusqlite::params![source_id],
         |row| {
@@ -299,6 +694,7 @@ pub fn copy_entry_recursive(
                 name: row.get(2)?,
                 entry_type: EntryType::from_str(&entry_type_str).unwrap_or(EntryType::Query),
                 expanded: row.get::<_, i64>(4)? != 0,
+                position: row.get(7)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
             })
@@ -322,6 +718,64 @@ pub fn copy_entry_recursive(
     Ok(new_id)
 }
 
+/// Duplicate a query entry as a sibling named `<name> copy`, including its
+/// request (method, url, body, params, headers, form fields, auth).
+/// Returns the ID of the new entry.
+pub fn duplicate_query(conn: &Connection, source_id: i64) -> Result<i64> {
+    let (parent_id, name, entry_type): (Option<i64>, String, String) = conn.query_row(
+        "SELECT parent_id, name, entry_type FROM http_entries WHERE id = ?1",
+        rusqlite::params![source_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    if EntryType::from_str(&entry_type) != Some(EntryType::Query) {
+        anyhow::bail!("only queries can be duplicated");
+    }
+
+    let new_id = add_entry(conn, parent_id, &format!("{name} copy"), EntryType::Query)?;
+
+    let source_request: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM http_requests WHERE entry_id = ?1",
+            rusqlite::params![source_id],
+            |row| row.get(0),
+        )
+        .ok();
+    let Some(source_request) = source_request else {
+        return Ok(new_id);
+    };
+
+    conn.execute(
+        "INSERT INTO http_requests
+             (entry_id, method, url, body, timeout_ms, body_mode, graphql_variables,
+              use_default_headers)
+         SELECT ?1, method, url, body, timeout_ms, body_mode, graphql_variables,
+                use_default_headers
+         FROM http_requests WHERE id = ?2",
+        rusqlite::params![new_id, source_request],
+    )?;
+    let new_request = conn.last_insert_rowid();
+
+    for table in ["http_headers", "http_query_params", "http_form_fields"] {
+        conn.execute(
+            &format!(
+                "INSERT INTO {table} (request_id, key, value, enabled, sort_order)
+                 SELECT ?1, key, value, enabled, sort_order FROM {table}
+                 WHERE request_id = ?2 ORDER BY sort_order ASC, id ASC"
+            ),
+            rusqlite::params![new_request, source_request],
+        )?;
+    }
+    conn.execute(
+        "INSERT INTO http_request_auth
+             (request_id, auth_type, token, username, password, api_key_header, api_key_value)
+         SELECT ?1, auth_type, token, username, password, api_key_header, api_key_value
+         FROM http_request_auth WHERE request_id = ?2",
+        rusqlite::params![new_request, source_request],
+    )?;
+
+    Ok(new_id)
+}
+
 // ── Request CRUD ─────────────────────────────────────────────────────
 
 /// Ensure a request row exists for the given entry. Creates a default one if missing.
@@ -350,7 +804,9 @@ pub fn ensure_request(conn: &Connection, entry_id: i64) -> Result<i64> {
 /// Load a request by entry_id. Returns None if no request row exists.
 pub fn load_request(conn: &Connection, entry_id: i64) -> Result<Option<HttpRequest>> {
     let result = conn.query_row(
-        "SELECT id, entry_id, method, url, body FROM http_requests WHERE entry_id = ?1",
+        "SELECT id, entry_id, method, url, body, timeout_ms, body_mode, graphql_variables,
+                use_default_headers
+         FROM http_requests WHERE entry_id = ?1",
         rusqlite::params![entry_id],
         |row| {
             let method_str: String = row.get(2)?;
@@ -360,6 +816,10 @@ pub fn load_request(conn: &Connection, entry_id: i64) -> Result<Option<HttpReque
                 method: HttpMethod::from_str(&method_str),
                 url: row.get(3)?,
                 body: row.get(4)?,
+                timeout_ms: row.get::<_, Option<i64>>(5)?.map(|ms| ms as u64),
+                body_mode: BodyMode::parse(&row.get::<_, String>(6)?),
+                graphql_variables: row.get(7)?,
+                use_default_headers: row.get::<_, i64>(8)? != 0,
             })
         },
     );
@@ -386,6 +846,54 @@ pub fn save_request(
     Ok(())
 }
 
+/// Set or clear the timeout of a request by request ID.
+pub fn set_request_timeout(
+    conn: &Connection,
+    request_id: i64,
+    timeout_ms: Option<u64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE http_requests SET timeout_ms = ?1 WHERE id = ?2",
+        rusqlite::params![timeout_ms.map(|ms| ms as i64), request_id],
+    )?;
+    Ok(())
+}
+
+/// Set the GraphQL variables (JSON text) of a request by request ID.
+pub fn set_request_graphql_variables(
+    conn: &Connection,
+    request_id: i64,
+    variables: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE http_requests SET graphql_variables = ?1 WHERE id = ?2",
+        rusqlite::params![variables, request_id],
+    )?;
+    Ok(())
+}
+
+/// Enable or disable the global default headers for a request by request ID.
+pub fn set_request_use_default_headers(
+    conn: &Connection,
+    request_id: i64,
+    enabled: bool,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE http_requests SET use_default_headers = ?1 WHERE id = ?2",
+        rusqlite::params![enabled as i64, request_id],
+    )?;
+    Ok(())
+}
+
+/// Set the body mode of a request by request ID.
+pub fn set_request_body_mode(conn: &Connection, request_id: i64, mode: BodyMode) -> Result<()> {
+    conn.execute(
+        "UPDATE http_requests SET body_mode = ?1 WHERE id = ?2",
+        rusqlite::params![mode.as_str(), request_id],
+    )?;
+    Ok(())
+}
+
 // ── Header CRUD ──────────────────────────────────────────────────────
 
 /// Load all headers for a request, ordered by sort_order.
@@ -558,25 +1066,490 @@ pub fn replace_query_params(
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rstools_core::db::open_memory_db;
-
-    #[test]
-    fn test_init_db() {
-        let conn = open_memory_db().unwrap();
-        init_db(&conn).unwrap();
-        // Should be idempotent
-        init_db(&conn).unwrap();
-    }
+// ── Default Header CRUD ──────────────────────────────────────────────
 
-    #[test]
-    fn test_add_and_list_entries() {
-        let conn = open_memory_db().unwrap();
-        init_db(&conn).unwrap();
+/// Load the global default headers, ordered by sort_order.
+pub fn load_default_headers(conn: &Connection) -> Result<Vec<HttpDefaultHeader>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, key, value, enabled, sort_order
+         FROM http_default_headers
+         ORDER BY sort_order ASC, id ASC",
+    )?;
+    let headers = stmt
+        .query_map([], |row| {
+            Ok(HttpDefaultHeader {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                value: row.get(2)?,
+                enabled: row.get::<_, i64>(3)? != 0,
+                sort_order: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(headers)
+}
 
-        add_entry(&conn, None, "folder-a", EntryType::Folder).unwrap();
+/// Replace the global default headers (used for bulk save).
+pub fn replace_default_headers(
+    conn: &Connection,
+    headers: &[(String, String, bool)],
+) -> Result<()> {
+    conn.execute("DELETE FROM http_default_headers", [])?;
+    for (i, (key, value, enabled)) in headers.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO http_default_headers (key, value, enabled, sort_order) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![key, value, *enabled as i64, i as i64],
+        )?;
+    }
+    Ok(())
+}
+
+// ── Folder Header / Variable CRUD ────────────────────────────────────
+
+/// Load the headers set on a folder, ordered by sort_order.
+pub fn load_folder_headers(conn: &Connection, folder_id: i64) -> Result<Vec<HttpFolderValue>> {
+    load_folder_values(conn, "http_folder_headers", folder_id)
+}
+
+/// Replace the headers set on a folder (used for bulk save).
+pub fn replace_folder_headers(
+    conn: &Connection,
+    folder_id: i64,
+    headers: &[(String, String, bool)],
+) -> Result<()> {
+    replace_folder_values(conn, "http_folder_headers", folder_id, headers)
+}
+
+/// Load the variables set on a folder, ordered by sort_order.
+pub fn load_folder_vars(conn: &Connection, folder_id: i64) -> Result<Vec<HttpFolderValue>> {
+    load_folder_values(conn, "http_folder_vars", folder_id)
+}
+
+/// Replace the variables set on a folder (used for bulk save).
+pub fn replace_folder_vars(
+    conn: &Connection,
+    folder_id: i64,
+    vars: &[(String, String, bool)],
+) -> Result<()> {
+    replace_folder_values(conn, "http_folder_vars", folder_id, vars)
+}
+
+/// The enabled folder headers `entry_id` inherits from the folders above it.
+/// Header names match case-insensitively; the nearest folder wins.
+pub fn inherited_folder_headers(conn: &Connection, entry_id: i64) -> Result<Vec<InheritedValue>> {
+    inherited_values(conn, "http_folder_headers", entry_id, |a, b| {
+        a.eq_ignore_ascii_case(b)
+    })
+}
+
+/// The enabled folder variables `entry_id` inherits from the folders above
+/// it; the nearest folder wins.
+pub fn inherited_folder_vars(conn: &Connection, entry_id: i64) -> Result<Vec<InheritedValue>> {
+    inherited_values(conn, "http_folder_vars", entry_id, |a, b| a == b)
+}
+
+fn load_folder_values(
+    conn: &Connection,
+    table: &str,
+    folder_id: i64,
+) -> Result<Vec<HttpFolderValue>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, entry_id, key, value, enabled, sort_order
+         FROM {table}
+         WHERE entry_id = ?1
+         ORDER BY sort_order ASC, id ASC"
+    ))?;
+    let values = stmt
+        .query_map(rusqlite::params![folder_id], |row| {
+            Ok(HttpFolderValue {
+                id: row.get(0)?,
+                entry_id: row.get(1)?,
+                key: row.get(2)?,
+                value: row.get(3)?,
+                enabled: row.get::<_, i64>(4)? != 0,
+                sort_order: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values)
+}
+
+fn replace_folder_values(
+    conn: &Connection,
+    table: &str,
+    folder_id: i64,
+    values: &[(String, String, bool)],
+) -> Result<()> {
+    // One transaction, so a failure cannot leave the folder without its
+    // rows. Inside a caller's transaction (an import) the rows join it.
+    let tx = if conn.is_autocommit() {
+        Some(conn.unchecked_transaction()?)
+    } else {
+        None
+    };
+    conn.execute(
+        &format!("DELETE FROM {table} WHERE entry_id = ?1"),
+        rusqlite::params![folder_id],
+    )?;
+    for (i, (key, value, enabled)) in values.iter().enumerate() {
+        conn.execute(
+            &format!(
+                "INSERT INTO {table} (entry_id, key, value, enabled, sort_order) VALUES (?1, ?2, ?3, ?4, ?5)"
+            ),
+            rusqlite::params![folder_id, key, value, *enabled as i64, i as i64],
+        )?;
+    }
+    if let Some(tx) = tx {
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Walk from `entry_id`'s parent up to the root, collecting the enabled rows
+/// of `table`. A key already set by a nearer folder is skipped.
+fn inherited_values(
+    conn: &Connection,
+    table: &str,
+    entry_id: i64,
+    same_key: impl Fn(&str, &str) -> bool,
+) -> Result<Vec<InheritedValue>> {
+    let parent_of = |id: i64| -> Result<Option<(i64, String)>> {
+        let parent: Option<i64> = conn.query_row(
+            "SELECT parent_id FROM http_entries WHERE id = ?1",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?;
+        let Some(parent) = parent else {
+            return Ok(None);
+        };
+        let name: String = conn.query_row(
+            "SELECT name FROM http_entries WHERE id = ?1",
+            rusqlite::params![parent],
+            |row| row.get(0),
+        )?;
+        Ok(Some((parent, name)))
+    };
+
+    let mut inherited: Vec<InheritedValue> = Vec::new();
+    let mut current = entry_id;
+    while let Some((folder_id, folder)) = parent_of(current)? {
+        for row in load_folder_values(conn, table, folder_id)? {
+            if row.enabled
+                && !row.key.is_empty()
+                && !inherited.iter().any(|v| same_key(&v.key, &row.key))
+            {
+                inherited.push(InheritedValue {
+                    key: row.key,
+                    value: row.value,
+                    folder: folder.clone(),
+                });
+            }
+        }
+        current = folder_id;
+    }
+    Ok(inherited)
+}
+
+// ── Form Field CRUD ──────────────────────────────────────────────────
+
+/// Load all form fields for a request, ordered by sort_order.
+pub fn load_form_fields(conn: &Connection, request_id: i64) -> Result<Vec<HttpFormField>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, request_id, key, value, enabled, sort_order
+         FROM http_form_fields
+         WHERE request_id = ?1
+         ORDER BY sort_order ASC, id ASC",
+    )?;
+    let fields = stmt
+        .query_map(rusqlite::params![request_id], |row| {
+            Ok(HttpFormField {
+                id: row.get(0)?,
+                request_id: row.get(1)?,
+                key: row.get(2)?,
+                value: row.get(3)?,
+                enabled: row.get::<_, i64>(4)? != 0,
+                sort_order: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fields)
+}
+
+/// Replace all form fields for a request (used for bulk save).
+pub fn replace_form_fields(
+    conn: &Connection,
+    request_id: i64,
+    fields: &[(String, String, bool)],
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM http_form_fields WHERE request_id = ?1",
+        rusqlite::params![request_id],
+    )?;
+    for (i, (key, value, enabled)) in fields.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO http_form_fields (request_id, key, value, enabled, sort_order) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![request_id, key, value, *enabled as i64, i as i64],
+        )?;
+    }
+    Ok(())
+}
+
+// ── Environment CRUD ─────────────────────────────────────────────────
+
+/// List all environments, ordered by name.
+pub fn list_environments(conn: &Connection) -> Result<Vec<HttpEnvironment>> {
+    let mut stmt =
+        conn.prepare("SELECT id, name, active FROM http_environments ORDER BY name ASC")?;
+    let envs = stmt
+        .query_map([], |row| {
+            Ok(HttpEnvironment {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                active: row.get::<_, i64>(2)? != 0,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(envs)
+}
+
+/// Ensure an environment with the given name exists. Returns its ID.
+pub fn ensure_environment(conn: &Connection, name: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT OR IGNORE INTO http_environments (name) VALUES (?1)",
+        rusqlite::params![name],
+    )?;
+    let id = conn.query_row(
+        "SELECT id FROM http_environments WHERE name = ?1",
+        rusqlite::params![name],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+/// Get the currently active environment, if any.
+pub fn active_environment(conn: &Connection) -> Result<Option<HttpEnvironment>> {
+    Ok(list_environments(conn)?.into_iter().find(|e| e.active))
+}
+
+/// Mark the given environment as active (or deactivate all with `None`).
+pub fn set_active_environment(conn: &Connection, id: Option<i64>) -> Result<()> {
+    conn.execute(
+        "UPDATE http_environments SET active = (id IS ?1)",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
+
+/// Load all variables of an environment, ordered by key.
+pub fn load_env_vars(conn: &Connection, environment_id: i64) -> Result<Vec<HttpEnvVar>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, environment_id, key, value
+         FROM http_environment_vars
+         WHERE environment_id = ?1
+         ORDER BY key ASC",
+    )?;
+    let vars = stmt
+        .query_map(rusqlite::params![environment_id], |row| {
+            Ok(HttpEnvVar {
+                id: row.get(0)?,
+                environment_id: row.get(1)?,
+                key: row.get(2)?,
+                value: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(vars)
+}
+
+/// Set a variable in an environment, replacing any existing value.
+pub fn set_env_var(conn: &Connection, environment_id: i64, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO http_environment_vars (environment_id, key, value) VALUES (?1, ?2, ?3)
+         ON CONFLICT(environment_id, key) DO UPDATE SET value = excluded.value",
+        rusqlite::params![environment_id, key, value],
+    )?;
+    Ok(())
+}
+
+/// Remove a variable from an environment.
+pub fn delete_env_var(conn: &Connection, environment_id: i64, key: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM http_environment_vars WHERE environment_id = ?1 AND key = ?2",
+        rusqlite::params![environment_id, key],
+    )?;
+    Ok(())
+}
+
+// ── Auth CRUD ────────────────────────────────────────────────────────
+
+/// Load the auth settings of a request (defaults to no auth).
+pub fn load_auth(conn: &Connection, request_id: i64) -> Result<HttpAuth> {
+    let result = conn.query_row(
+        "SELECT auth_type, token, username, password, api_key_header, api_key_value
+         FROM http_request_auth WHERE request_id = ?1",
+        rusqlite::params![request_id],
+        |row| {
+            let auth_type: String = row.get(0)?;
+            Ok(HttpAuth {
+                auth_type: AuthType::parse(&auth_type),
+                token: row.get(1)?,
+                username: row.get(2)?,
+                password: row.get(3)?,
+                api_key_header: row.get(4)?,
+                api_key_value: row.get(5)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(auth) => Ok(auth),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(HttpAuth::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Save the auth settings of a request, replacing any existing ones.
+pub fn save_auth(conn: &Connection, request_id: i64, auth: &HttpAuth) -> Result<()> {
+    conn.execute(
+        "INSERT INTO http_request_auth
+            (request_id, auth_type, token, username, password, api_key_header, api_key_value)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(request_id) DO UPDATE SET
+            auth_type = excluded.auth_type,
+            token = excluded.token,
+            username = excluded.username,
+            password = excluded.password,
+            api_key_header = excluded.api_key_header,
+            api_key_value = excluded.api_key_value",
+        rusqlite::params![
+            request_id,
+            auth.auth_type.as_str(),
+            auth.token,
+            auth.username,
+            auth.password,
+            auth.api_key_header,
+            auth.api_key_value,
+        ],
+    )?;
+    Ok(())
+}
+
+// ── Response history ─────────────────────────────────────────────────
+
+/// Store a completed response for a query and prune the query's history to
+/// the newest `RESPONSE_HISTORY_LIMIT` rows. `id` and `created_at` of the
+/// record are ignored. Returns the new row id.
+pub fn add_response_history(conn: &Connection, record: &HttpResponseRecord) -> Result<i64> {
+    let headers = serde_json::to_string(&record.headers)?;
+    conn.execute(
+        "INSERT INTO http_response_history
+            (entry_id, status_code, status_text, elapsed_ms, size_bytes, headers, body,
+             raw_body)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            record.entry_id,
+            record.status_code,
+            record.status_text,
+            record.elapsed_ms as i64,
+            record.size_bytes as i64,
+            headers,
+            record.body,
+            record.raw_body,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    conn.execute(
+        "DELETE FROM http_response_history
+         WHERE entry_id = ?1 AND id NOT IN (
+             SELECT id FROM http_response_history
+             WHERE entry_id = ?1
+             ORDER BY id DESC
+             LIMIT ?2
+         )",
+        rusqlite::params![record.entry_id, RESPONSE_HISTORY_LIMIT as i64],
+    )?;
+
+    Ok(id)
+}
+
+/// Status and duration of a query's most recent stored response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastRun {
+    pub status_code: u16,
+    pub elapsed_ms: u128,
+}
+
+/// The most recent stored response of every query that has one, keyed by
+/// entry id.
+pub fn last_runs(conn: &Connection) -> Result<HashMap<i64, LastRun>> {
+    let mut stmt = conn.prepare(
+        "SELECT entry_id, status_code, elapsed_ms
+         FROM http_response_history
+         WHERE id IN (SELECT MAX(id) FROM http_response_history GROUP BY entry_id)",
+    )?;
+    let runs = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                LastRun {
+                    status_code: row.get(1)?,
+                    elapsed_ms: row.get::<_, i64>(2)? as u128,
+                },
+            ))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(runs)
+}
+
+/// List the stored responses of a query, newest first.
+pub fn list_response_history(conn: &Connection, entry_id: i64) -> Result<Vec<HttpResponseRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entry_id, status_code, status_text, elapsed_ms, size_bytes, headers, body,
+                raw_body, created_at
+         FROM http_response_history
+         WHERE entry_id = ?1
+         ORDER BY id DESC",
+    )?;
+    let records = stmt
+        .query_map(rusqlite::params![entry_id], |row| {
+            let headers: String = row.get(6)?;
+            Ok(HttpResponseRecord {
+                id: row.get(0)?,
+                entry_id: row.get(1)?,
+                status_code: row.get(2)?,
+                status_text: row.get(3)?,
+                elapsed_ms: row.get::<_, i64>(4)? as u128,
+                size_bytes: row.get::<_, i64>(5)? as usize,
+                headers: serde_json::from_str(&headers).unwrap_or_default(),
+                body: row.get(7)?,
+                raw_body: row.get(8)?,
+                created_at: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::db::open_memory_db;
+
+    #[test]
+    fn test_init_db() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+        // Should be idempotent
+        init_db(&conn).unwrap();
+    }
+
+    #[test]
+    fn test_add_and_list_entries() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        add_entry(&conn, None, "folder-a", EntryType::Folder).unwrap();
         add_entry(&conn, None, "query-b", EntryType::Query).unwrap();
 
         let entries = list_entries(&conn).unwrap();
@@ -672,6 +1645,55 @@ mod tests {
         assert_eq!(copied_children.len(), 2);
     }
 
+    #[test]
+    fn test_duplicate_query() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let folder = add_entry(&conn, None, "api", EntryType::Folder).unwrap();
+        let source = add_entry(&conn, Some(folder), "create-user", EntryType::Query).unwrap();
+        let req_id = ensure_request(&conn, source).unwrap();
+        save_request(&conn, req_id, HttpMethod::Post, "https://x.dev/users", "{}").unwrap();
+        set_request_timeout(&conn, req_id, Some(2500)).unwrap();
+        add_header(&conn, req_id, "Accept", "application/json", 0).unwrap();
+        add_query_param(&conn, req_id, "dry", "1", 0).unwrap();
+        save_auth(
+            &conn,
+            req_id,
+            &HttpAuth {
+                auth_type: AuthType::Bearer,
+                token: "t".to_string(),
+                ..HttpAuth::default()
+            },
+        )
+        .unwrap();
+
+        let copy = duplicate_query(&conn, source).unwrap();
+        assert_ne!(copy, source);
+
+        let entries = list_entries(&conn).unwrap();
+        let copied = entries.iter().find(|e| e.id == copy).unwrap();
+        assert_eq!(copied.name, "create-user copy");
+        assert_eq!(copied.parent_id, Some(folder));
+
+        let request = load_request(&conn, copy).unwrap().unwrap();
+        assert_ne!(request.id, req_id);
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.url, "https://x.dev/users");
+        assert_eq!(request.body, "{}");
+        assert_eq!(request.timeout_ms, Some(2500));
+        assert_eq!(load_headers(&conn, request.id).unwrap()[0].key, "Accept");
+        assert_eq!(load_query_params(&conn, request.id).unwrap()[0].key, "dry");
+        assert_eq!(load_auth(&conn, request.id).unwrap().token, "t");
+
+        // Editing the copy leaves the source untouched
+        replace_headers(&conn, request.id, &[]).unwrap();
+        assert_eq!(load_headers(&conn, req_id).unwrap().len(), 1);
+
+        // Folders cannot be duplicated this way
+        assert!(duplicate_query(&conn, folder).is_err());
+    }
+
     // ── Request tests ────────────────────────────────────────────────
 
     #[test]
@@ -716,6 +1738,106 @@ mod tests {
         assert_eq!(req.body, "{\"key\": \"val\"}");
     }
 
+    #[test]
+    fn test_request_timeout() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let entry_id = add_entry(&conn, None, "slow", EntryType::Query).unwrap();
+        let req_id = ensure_request(&conn, entry_id).unwrap();
+        assert_eq!(
+            load_request(&conn, entry_id).unwrap().unwrap().timeout_ms,
+            None
+        );
+
+        set_request_timeout(&conn, req_id, Some(5000)).unwrap();
+        assert_eq!(
+            load_request(&conn, entry_id).unwrap().unwrap().timeout_ms,
+            Some(5000)
+        );
+
+        set_request_timeout(&conn, req_id, None).unwrap();
+        assert_eq!(
+            load_request(&conn, entry_id).unwrap().unwrap().timeout_ms,
+            None
+        );
+    }
+
+    #[test]
+    fn test_body_mode_and_form_fields() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let entry_id = add_entry(&conn, None, "login", EntryType::Query).unwrap();
+        let req_id = ensure_request(&conn, entry_id).unwrap();
+        assert_eq!(
+            load_request(&conn, entry_id).unwrap().unwrap().body_mode,
+            BodyMode::Raw
+        );
+
+        set_request_body_mode(&conn, req_id, BodyMode::FormUrlEncoded).unwrap();
+        assert_eq!(
+            load_request(&conn, entry_id).unwrap().unwrap().body_mode,
+            BodyMode::FormUrlEncoded
+        );
+
+        replace_form_fields(
+            &conn,
+            req_id,
+            &[
+                ("user".to_string(), "ada".to_string(), true),
+                ("debug".to_string(), "1".to_string(), false),
+            ],
+        )
+        .unwrap();
+        let fields = load_form_fields(&conn, req_id).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].key, "user");
+        assert!(!fields[1].enabled);
+
+        set_request_body_mode(&conn, req_id, BodyMode::GraphQl).unwrap();
+        set_request_graphql_variables(&conn, req_id, r#"{"id": 1}"#).unwrap();
+        let req = load_request(&conn, entry_id).unwrap().unwrap();
+        assert_eq!(req.body_mode, BodyMode::GraphQl);
+        assert_eq!(req.graphql_variables, r#"{"id": 1}"#);
+    }
+
+    #[test]
+    fn test_default_headers() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+        assert!(load_default_headers(&conn).unwrap().is_empty());
+
+        replace_default_headers(
+            &conn,
+            &[
+                ("Accept".to_string(), "application/json".to_string(), true),
+                ("X-Trace".to_string(), "on".to_string(), false),
+            ],
+        )
+        .unwrap();
+        let headers = load_default_headers(&conn).unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].key, "Accept");
+        assert!(!headers[1].enabled);
+
+        let entry_id = add_entry(&conn, None, "q", EntryType::Query).unwrap();
+        let req_id = ensure_request(&conn, entry_id).unwrap();
+        assert!(
+            load_request(&conn, entry_id)
+                .unwrap()
+                .unwrap()
+                .use_default_headers
+        );
+        set_request_use_default_headers(&conn, req_id, false).unwrap();
+        assert!(
+            !load_request(&conn, entry_id)
+                .unwrap()
+                .unwrap()
+                .use_default_headers
+        );
+    }
+
     #[test]
     fn test_load_request_nonexistent() {
         let conn = open_memory_db().unwrap();
@@ -870,6 +1992,93 @@ mod tests {
         assert_eq!(HttpMethod::from_str("unknown"), HttpMethod::Get);
     }
 
+    // ── Environment tests ────────────────────────────────────────────
+
+    #[test]
+    fn test_environments_crud() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let dev = ensure_environment(&conn, "dev").unwrap();
+        let prod = ensure_environment(&conn, "prod").unwrap();
+        // Idempotent
+        assert_eq!(ensure_environment(&conn, "dev").unwrap(), dev);
+        assert!(active_environment(&conn).unwrap().is_none());
+
+        set_active_environment(&conn, Some(prod)).unwrap();
+        assert_eq!(active_environment(&conn).unwrap().unwrap().name, "prod");
+        set_active_environment(&conn, Some(dev)).unwrap();
+        assert_eq!(active_environment(&conn).unwrap().unwrap().name, "dev");
+        set_active_environment(&conn, None).unwrap();
+        assert!(active_environment(&conn).unwrap().is_none());
+
+        set_env_var(&conn, dev, "HOST", "localhost").unwrap();
+        set_env_var(&conn, dev, "HOST", "127.0.0.1").unwrap();
+        set_env_var(&conn, dev, "TOKEN", "abc").unwrap();
+        let vars = load_env_vars(&conn, dev).unwrap();
+        assert_eq!(vars.len(), 2);
+        assert_eq!(vars[0].key, "HOST");
+        assert_eq!(vars[0].value, "127.0.0.1");
+
+        delete_env_var(&conn, dev, "TOKEN").unwrap();
+        assert_eq!(load_env_vars(&conn, dev).unwrap().len(), 1);
+        assert!(load_env_vars(&conn, prod).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_folder_values_inherited() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let api = add_entry(&conn, None, "api", EntryType::Folder).unwrap();
+        let users = add_entry(&conn, Some(api), "users", EntryType::Folder).unwrap();
+        let list = add_entry(&conn, Some(users), "list", EntryType::Query).unwrap();
+        let row = |k: &str, v: &str, enabled| (k.to_string(), v.to_string(), enabled);
+        replace_folder_headers(
+            &conn,
+            api,
+            &[
+                row("X-Team", "core", true),
+                row("Accept", "text/plain", true),
+            ],
+        )
+        .unwrap();
+        replace_folder_headers(
+            &conn,
+            users,
+            &[
+                row("accept", "application/json", true),
+                row("X-Off", "1", false),
+            ],
+        )
+        .unwrap();
+        replace_folder_vars(&conn, api, &[row("HOST", "api.example.com", true)]).unwrap();
+        assert_eq!(load_folder_headers(&conn, users).unwrap().len(), 2);
+
+        // The nearest folder wins, whatever the case of the header name
+        let inherited = |key: &str, value: &str, folder: &str| InheritedValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            folder: folder.to_string(),
+        };
+        assert_eq!(
+            inherited_folder_headers(&conn, list).unwrap(),
+            vec![
+                inherited("accept", "application/json", "users"),
+                inherited("X-Team", "core", "api"),
+            ]
+        );
+        assert_eq!(
+            inherited_folder_vars(&conn, list).unwrap(),
+            vec![inherited("HOST", "api.example.com", "api")]
+        );
+        // A folder's own values only apply below it
+        assert!(inherited_folder_vars(&conn, api).unwrap().is_empty());
+
+        delete_entry(&conn, users).unwrap();
+        assert!(load_folder_headers(&conn, users).unwrap().is_empty());
+    }
+
     // ── Cascade delete tests ─────────────────────────────────────────
 
     #[test]
@@ -888,4 +2097,128 @@ mod tests {
         let req = load_request(&conn, entry_id).unwrap();
         assert!(req.is_none());
     }
+
+    #[test]
+    fn test_response_history() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let entry_id = add_entry(&conn, None, "users", EntryType::Query).unwrap();
+        let other_id = add_entry(&conn, None, "other", EntryType::Query).unwrap();
+
+        let record = |entry_id: i64, status_code: u16| HttpResponseRecord {
+            id: 0,
+            entry_id,
+            status_code,
+            status_text: "OK".to_string(),
+            elapsed_ms: 42,
+            size_bytes: 2,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: "{}".to_string(),
+            raw_body: Some(b"{}".to_vec()),
+            created_at: String::new(),
+        };
+
+        add_response_history(&conn, &record(other_id, 500)).unwrap();
+        for i in 0..RESPONSE_HISTORY_LIMIT + 5 {
+            add_response_history(&conn, &record(entry_id, 200 + i as u16)).unwrap();
+        }
+
+        // Pruned to the newest rows, newest first
+        let history = list_response_history(&conn, entry_id).unwrap();
+        assert_eq!(history.len(), RESPONSE_HISTORY_LIMIT);
+        assert_eq!(
+            history[0].status_code,
+            200 + RESPONSE_HISTORY_LIMIT as u16 + 4
+        );
+        assert_eq!(history.last().unwrap().status_code, 205);
+        assert_eq!(history[0].elapsed_ms, 42);
+        assert_eq!(history[0].headers[0].1, "application/json");
+        assert_eq!(history[0].raw_body.as_deref(), Some(&b"{}"[..]));
+        assert!(!history[0].created_at.is_empty());
+
+        // Other queries' history is untouched
+        assert_eq!(list_response_history(&conn, other_id).unwrap().len(), 1);
+
+        // The newest run of each query
+        let runs = last_runs(&conn).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[&other_id].status_code, 500);
+        assert_eq!(
+            runs[&entry_id],
+            LastRun {
+                status_code: 200 + RESPONSE_HISTORY_LIMIT as u16 + 4,
+                elapsed_ms: 42,
+            }
+        );
+
+        // Deleting the entry removes its history
+        delete_entry(&conn, entry_id).unwrap();
+        assert!(list_response_history(&conn, entry_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_auth_header() {
+        let mut auth = HttpAuth::default();
+        assert_eq!(auth.header(), None);
+
+        auth.auth_type = AuthType::Bearer;
+        assert_eq!(auth.header(), None);
+        auth.token = "abc123".to_string();
+        assert_eq!(
+            auth.header(),
+            Some(("Authorization".to_string(), "Bearer abc123".to_string()))
+        );
+
+        auth.auth_type = AuthType::Basic;
+        auth.username = "Aladdin".to_string();
+        auth.password = "open sesame".to_string();
+        assert_eq!(
+            auth.header().unwrap().1,
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+
+        // UTF-8 credentials are encoded byte-wise
+        auth.username = "jürgen".to_string();
+        auth.password = "pässwörd€".to_string();
+        assert_eq!(
+            auth.header().unwrap().1,
+            "Basic asO8cmdlbjpww6Rzc3fDtnJk4oKs"
+        );
+
+        auth.auth_type = AuthType::ApiKey;
+        auth.api_key_header = "X-Api-Key".to_string();
+        auth.api_key_value = "secret".to_string();
+        assert_eq!(
+            auth.header(),
+            Some(("X-Api-Key".to_string(), "secret".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_auth_crud() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+
+        let entry_id = add_entry(&conn, None, "secure", EntryType::Query).unwrap();
+        let req_id = ensure_request(&conn, entry_id).unwrap();
+        assert_eq!(load_auth(&conn, req_id).unwrap(), HttpAuth::default());
+
+        let auth = HttpAuth {
+            auth_type: AuthType::Basic,
+            username: "user".to_string(),
+            password: "pass".to_string(),
+            ..HttpAuth::default()
+        };
+        save_auth(&conn, req_id, &auth).unwrap();
+        assert_eq!(load_auth(&conn, req_id).unwrap(), auth);
+
+        let auth = HttpAuth {
+            auth_type: AuthType::Bearer,
+            token: "t".to_string(),
+            ..auth
+        };
+        save_auth(&conn, req_id, &auth).unwrap();
+        assert_eq!(load_auth(&conn, req_id).unwrap(), auth);
+    }
 }
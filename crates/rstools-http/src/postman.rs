@@ -0,0 +1,330 @@
+//! Export of the query tree as a Postman v2.1 collection.
+//!
+//! Folders become item groups and queries become requests. `${VAR}`
+//! placeholders are rewritten to Postman's `{{VAR}}` so environments carry
+//! over. The global default headers a query applies are written into its
+//! header list, since Postman has no equivalent.
+
+use serde_json::{Map, Value, json};
+
+use crate::collection::{CollectionNode, CollectionRequest};
+use crate::model::{AuthType, BodyMode};
+
+/// Schema URL identifying a Postman v2.1 collection.
+pub const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Serialize a tree into a Postman collection named `name`.
+/// `default_headers` are the enabled global default headers.
+pub fn to_json(
+    name: &str,
+    nodes: &[CollectionNode],
+    default_headers: &[(String, String)],
+) -> Value {
+    json!({
+        "info": {
+            "name": name,
+            "schema": SCHEMA,
+        },
+        "item": items(nodes, default_headers),
+    })
+}
+
+fn items(nodes: &[CollectionNode], default_headers: &[(String, String)]) -> Vec<Value> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            CollectionNode::Folder { name, children, .. } => json!({
+                "name": name,
+                "item": items(children, default_headers),
+            }),
+            CollectionNode::Query { name, request } => json!({
+                "name": name,
+                "request": request_to_json(request, default_headers),
+            }),
+        })
+        .collect()
+}
+
+fn request_to_json(req: &CollectionRequest, default_headers: &[(String, String)]) -> Value {
+    let mut headers = Vec::new();
+    if req.use_default_headers {
+        // Same rule as the panel: an enabled request header overrides a default
+        for (key, value) in default_headers {
+            let overridden = req
+                .headers
+                .iter()
+                .any(|(k, _, enabled)| *enabled && k.eq_ignore_ascii_case(key));
+            if !key.is_empty() && !overridden {
+                headers.push(row(key, value, true));
+            }
+        }
+    }
+    headers.extend(
+        req.headers
+            .iter()
+            .map(|(k, v, enabled)| row(k, v, *enabled)),
+    );
+
+    let mut request = Map::new();
+    request.insert("method".into(), json!(req.method.as_str()));
+    request.insert("header".into(), Value::Array(headers));
+    request.insert("url".into(), url_to_json(&req.url, &req.query_params));
+    if let Some(body) = body_to_json(req) {
+        request.insert("body".into(), body);
+    }
+    if let Some(auth) = auth_to_json(req) {
+        request.insert("auth".into(), auth);
+    }
+    Value::Object(request)
+}
+
+/// A key/value row; Postman only writes `disabled` for disabled rows.
+fn row(key: &str, value: &str, enabled: bool) -> Value {
+    let mut row = json!({"key": vars(key), "value": vars(value)});
+    if !enabled {
+        row["disabled"] = json!(true);
+    }
+    row
+}
+
+/// The URL object: `raw` is what would be sent (enabled params appended),
+/// `query` lists every param, including the ones already in the URL.
+fn url_to_json(url: &str, params: &[(String, String, bool)]) -> Value {
+    let url = vars(url);
+    let (base, inline_query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url.as_str(), None),
+    };
+
+    let mut query: Vec<Value> = inline_query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            row(key, value, true)
+        })
+        .collect();
+    let mut raw = url.clone();
+    for (key, value, enabled) in params.iter().filter(|(key, ..)| !key.is_empty()) {
+        if *enabled {
+            raw.push(if raw.contains('?') { '&' } else { '?' });
+            raw.push_str(&format!("{}={}", vars(key), vars(value)));
+        }
+        query.push(row(key, value, *enabled));
+    }
+
+    let (protocol, rest) = match base.split_once("://") {
+        Some((protocol, rest)) => (Some(protocol), rest),
+        None => (None, base),
+    };
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+
+    let mut object = Map::new();
+    object.insert("raw".into(), json!(raw));
+    if let Some(protocol) = protocol {
+        object.insert("protocol".into(), json!(protocol));
+    }
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    object.insert("host".into(), json!(host.split('.').collect::<Vec<_>>()));
+    object.insert("path".into(), json!(segments));
+    if !query.is_empty() {
+        object.insert("query".into(), Value::Array(query));
+    }
+    Value::Object(object)
+}
+
+fn body_to_json(req: &CollectionRequest) -> Option<Value> {
+    match req.body_mode {
+        BodyMode::Raw if req.body.is_empty() => None,
+        BodyMode::Raw => {
+            let language = if serde_json::from_str::<Value>(&req.body).is_ok() {
+                "json"
+            } else {
+                "text"
+            };
+            Some(json!({
+                "mode": "raw",
+                "raw": vars(&req.body),
+                "options": {"raw": {"language": language}},
+            }))
+        }
+        BodyMode::FormUrlEncoded => Some(json!({
+            "mode": "urlencoded",
+            "urlencoded": req
+                .form_fields
+                .iter()
+                .filter(|(key, ..)| !key.is_empty())
+                .map(|(k, v, enabled)| row(k, v, *enabled))
+                .collect::<Vec<_>>(),
+        })),
+        BodyMode::GraphQl => Some(json!({
+            "mode": "graphql",
+            "graphql": {
+                "query": vars(&req.body),
+                "variables": vars(&req.graphql_variables),
+            },
+        })),
+        BodyMode::File if req.body.trim().is_empty() => None,
+        BodyMode::File => Some(json!({
+            "mode": "file",
+            "file": {"src": vars(req.body.trim())},
+        })),
+    }
+}
+
+fn auth_to_json(req: &CollectionRequest) -> Option<Value> {
+    let auth = &req.auth;
+    let param =
+        |key: &str, value: &str| json!({"key": key, "value": vars(value), "type": "string"});
+    match auth.auth_type {
+        AuthType::None => None,
+        AuthType::Bearer => Some(json!({
+            "type": "bearer",
+            "bearer": [param("token", &auth.token)],
+        })),
+        AuthType::Basic => Some(json!({
+            "type": "basic",
+            "basic": [param("username", &auth.username), param("password", &auth.password)],
+        })),
+        AuthType::ApiKey => Some(json!({
+            "type": "apikey",
+            "apikey": [
+                param("key", &auth.api_key_header),
+                param("value", &auth.api_key_value),
+                param("in", "header"),
+            ],
+        })),
+    }
+}
+
+/// Rewrite `${VAR}` placeholders as Postman `{{VAR}}` variables.
+fn vars(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&format!("{{{{{}}}}}", &after[..end]));
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HttpAuth, HttpMethod};
+
+    fn request() -> CollectionRequest {
+        CollectionRequest {
+            method: HttpMethod::Post,
+            url: "https://${HOST}/v1/users?sort=asc".to_string(),
+            body: "{\"name\": \"${NAME}\"}".to_string(),
+            timeout_ms: None,
+            body_mode: BodyMode::Raw,
+            graphql_variables: String::new(),
+            use_default_headers: true,
+            headers: vec![
+                ("accept".to_string(), "text/plain".to_string(), true),
+                ("X-Off".to_string(), "1".to_string(), false),
+            ],
+            query_params: vec![
+                ("page".to_string(), "2".to_string(), true),
+                ("debug".to_string(), "1".to_string(), false),
+            ],
+            form_fields: Vec::new(),
+            auth: HttpAuth {
+                auth_type: AuthType::Bearer,
+                token: "${TOKEN}".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_export_folders_and_request() {
+        let tree = vec![CollectionNode::Folder {
+            name: "api".to_string(),
+            headers: Vec::new(),
+            vars: Vec::new(),
+            children: vec![CollectionNode::Query {
+                name: "create".to_string(),
+                request: Box::new(request()),
+            }],
+        }];
+        let defaults = vec![
+            ("Accept".to_string(), "application/json".to_string()),
+            ("X-Client".to_string(), "rstools".to_string()),
+        ];
+        let doc = to_json("team", &tree, &defaults);
+        assert_eq!(doc["info"]["name"], "team");
+        assert_eq!(doc["info"]["schema"], SCHEMA);
+
+        let folder = &doc["item"][0];
+        assert_eq!(folder["name"], "api");
+        let request = &folder["item"][0]["request"];
+        assert_eq!(request["method"], "POST");
+        assert_eq!(
+            request["header"],
+            json!([
+                {"key": "X-Client", "value": "rstools"},
+                {"key": "accept", "value": "text/plain"},
+                {"key": "X-Off", "value": "1", "disabled": true},
+            ])
+        );
+
+        let url = &request["url"];
+        assert_eq!(url["raw"], "https://{{HOST}}/v1/users?sort=asc&page=2");
+        assert_eq!(url["protocol"], "https");
+        assert_eq!(url["host"], json!(["{{HOST}}"]));
+        assert_eq!(url["path"], json!(["v1", "users"]));
+        assert_eq!(
+            url["query"],
+            json!([
+                {"key": "sort", "value": "asc"},
+                {"key": "page", "value": "2"},
+                {"key": "debug", "value": "1", "disabled": true},
+            ])
+        );
+
+        assert_eq!(request["body"]["mode"], "raw");
+        assert_eq!(request["body"]["raw"], "{\"name\": \"{{NAME}}\"}");
+        assert_eq!(request["body"]["options"]["raw"]["language"], "json");
+        assert_eq!(request["auth"]["type"], "bearer");
+        assert_eq!(request["auth"]["bearer"][0]["value"], "{{TOKEN}}");
+    }
+
+    #[test]
+    fn test_export_body_modes() {
+        let mut form = request();
+        form.body_mode = BodyMode::FormUrlEncoded;
+        form.form_fields = vec![("a".to_string(), "b c".to_string(), true)];
+        form.use_default_headers = false;
+        form.auth = HttpAuth::default();
+        let value = request_to_json(&form, &[("X-Client".to_string(), "x".to_string())]);
+        assert_eq!(value["header"].as_array().unwrap().len(), 2);
+        assert_eq!(value["body"]["mode"], "urlencoded");
+        assert_eq!(
+            value["body"]["urlencoded"],
+            json!([{"key": "a", "value": "b c"}])
+        );
+        assert!(value.get("auth").is_none());
+
+        let mut graphql = request();
+        graphql.body_mode = BodyMode::GraphQl;
+        graphql.body = "{ me { id } }".to_string();
+        graphql.graphql_variables = "{}".to_string();
+        let value = request_to_json(&graphql, &[]);
+        assert_eq!(value["body"]["graphql"]["query"], "{ me { id } }");
+
+        let mut empty = request();
+        empty.body.clear();
+        assert!(request_to_json(&empty, &[]).get("body").is_none());
+        assert_eq!(vars("${A}/${B"), "{{A}}/${B");
+    }
+}
@@ -1,7 +1,14 @@
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 use rusqlite::Connection;
+use unicode_width::UnicodeWidthChar;
 
-use crate::model::{self, HttpMethod};
+use crate::body_format::{self, BodyLanguage};
+use crate::executor::{HttpRequestCmd, ProxyConfig};
+use crate::model::{self, AuthType, BodyMode, HttpAuth, HttpMethod};
 
 // ── Section / focus enums ────────────────────────────────────────────
 
@@ -11,6 +18,7 @@ pub enum Section {
     Url,
     Params,
     Headers,
+    Auth,
     Body,
 }
 
@@ -19,7 +27,8 @@ impl Section {
         match self {
             Section::Url => Section::Params,
             Section::Params => Section::Headers,
-            Section::Headers => Section::Body,
+            Section::Headers => Section::Auth,
+            Section::Auth => Section::Body,
             Section::Body => Section::Url,
         }
     }
@@ -29,7 +38,8 @@ impl Section {
             Section::Url => Section::Body,
             Section::Params => Section::Url,
             Section::Headers => Section::Params,
-            Section::Body => Section::Headers,
+            Section::Auth => Section::Headers,
+            Section::Body => Section::Auth,
         }
     }
 
@@ -38,11 +48,37 @@ impl Section {
             Section::Url => "URL",
             Section::Params => "Params",
             Section::Headers => "Headers",
+            Section::Auth => "Auth",
             Section::Body => "Body",
         }
     }
 }
 
+/// A row of the auth form. The first row always selects the auth type; the
+/// remaining rows depend on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthField {
+    Type,
+    Token,
+    Username,
+    Password,
+    ApiKeyHeader,
+    ApiKeyValue,
+}
+
+impl AuthField {
+    pub fn label(self) -> &'static str {
+        match self {
+            AuthField::Type => "Type",
+            AuthField::Token => "Token",
+            AuthField::Username => "Username",
+            AuthField::Password => "Password",
+            AuthField::ApiKeyHeader => "Header",
+            AuthField::ApiKeyValue => "Value",
+        }
+    }
+}
+
 /// Which sub-section of the response panel is focused.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResponseSection {
@@ -57,6 +93,13 @@ pub enum KvField {
     Value,
 }
 
+/// Which editor of a GraphQL body is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphqlPane {
+    Query,
+    Variables,
+}
+
 /// Where focus is within the content panel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PanelFocus {
@@ -90,6 +133,23 @@ impl KvRow {
     }
 }
 
+/// Suffix of the warning shown when a GET/HEAD request has a body.
+const BODY_METHOD_WARNING: &str = " with body may be ignored";
+
+/// Separator between the warnings shown together in `error_message`.
+pub const WARNING_SEPARATOR: &str = "; ";
+
+/// How long the body file status shown in the Body section is reused
+/// before the file is checked again.
+const BODY_FILE_RECHECK: Duration = Duration::from_secs(1);
+
+/// A `body_file_size` result kept by `cached_body_file_size`.
+struct BodyFileStatus {
+    path: Option<PathBuf>,
+    checked_at: Instant,
+    result: Result<u64, String>,
+}
+
 // ── Response data ────────────────────────────────────────────────────
 
 /// Holds the result of an HTTP request.
@@ -100,19 +160,84 @@ pub struct ResponseData {
     pub elapsed_ms: u128,
     pub size_bytes: usize,
     pub headers: Vec<(String, String)>,
+    /// The body as formatted for display (pretty-printed, hex dump, ...).
     pub body: String,
+    /// The unformatted body bytes, used by `:savebody`, `yb` and `P`. `None`
+    /// for history responses stored before raw bodies were kept.
+    pub raw_body: Option<Vec<u8>>,
+    /// Detected body language, used to pick a highlighter.
+    pub language: BodyLanguage,
+    /// Show the formatted `body` (the default) rather than the body text
+    /// exactly as received. Toggled with `P`.
+    pub pretty: bool,
+    /// First visible display row of the body (a wrapped line spans several).
     pub body_scroll: usize,
     pub headers_scroll: usize,
     pub focused_section: ResponseSection,
+    /// Width of the body area, recorded while rendering so that wrapped
+    /// scrolling can count display rows.
+    pub body_width: Cell<usize>,
 }
 
 impl ResponseData {
-    pub fn body_line_count(&self) -> usize {
-        self.body.lines().count().max(1)
+    /// The body text being shown: `body`, or the received text when `pretty`
+    /// is off. Bodies that are not UTF-8 always show as `body` (hex dump).
+    pub fn shown_body(&self) -> &str {
+        match self.raw_body.as_deref() {
+            Some(raw) if !self.pretty => std::str::from_utf8(raw).unwrap_or(&self.body),
+            _ => &self.body,
+        }
+    }
+
+    /// Switch between the formatted and the raw body. Lines differ between
+    /// the two, so the body scrolls back to the top. Returns false, leaving
+    /// the formatted body shown, when the raw bytes are not available.
+    pub fn toggle_pretty(&mut self) -> bool {
+        if self.raw_body.is_none() {
+            return false;
+        }
+        self.pretty = !self.pretty;
+        self.body_scroll = 0;
+        true
+    }
+
+    /// Number of display rows each body line takes up.
+    fn body_row_heights(&self, wrap: bool) -> impl Iterator<Item = usize> + '_ {
+        let width = self.body_width.get();
+        self.shown_body().lines().map(move |l| {
+            if wrap && width > 0 {
+                wrap_breaks(l, width).len() + 1
+            } else {
+                1
+            }
+        })
+    }
+
+    /// Number of display rows in the body, counting wrapped rows when `wrap`.
+    pub fn body_line_count(&self, wrap: bool) -> usize {
+        self.body_row_heights(wrap).sum::<usize>().max(1)
     }
 
-    pub fn scroll_body_down(&mut self, amount: usize) {
-        let max = self.body_line_count().saturating_sub(1);
+    /// The body line containing display row `row`, and the row's offset
+    /// within that line.
+    pub fn body_row_to_line(&self, row: usize, wrap: bool) -> (usize, usize) {
+        let mut start = 0;
+        for (i, h) in self.body_row_heights(wrap).enumerate() {
+            if row < start + h {
+                return (i, row - start);
+            }
+            start += h;
+        }
+        (self.shown_body().lines().count().saturating_sub(1), 0)
+    }
+
+    /// The first display row of body line `line`.
+    pub fn body_line_to_row(&self, line: usize, wrap: bool) -> usize {
+        self.body_row_heights(wrap).take(line).sum()
+    }
+
+    pub fn scroll_body_down(&mut self, amount: usize, wrap: bool) {
+        let max = self.body_line_count(wrap).saturating_sub(1);
         self.body_scroll = (self.body_scroll + amount).min(max);
     }
 
@@ -120,6 +245,15 @@ impl ResponseData {
         self.body_scroll = self.body_scroll.saturating_sub(amount);
     }
 
+    /// The response headers as `Name: value` lines.
+    pub fn headers_text(&self) -> String {
+        self.headers
+            .iter()
+            .map(|(k, v)| format!("{k}: {v}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn scroll_headers_down(&mut self, amount: usize) {
         let max = self.headers.len().saturating_sub(1);
         self.headers_scroll = (self.headers_scroll + amount).min(max);
@@ -137,6 +271,23 @@ impl ResponseData {
     }
 }
 
+/// Byte offsets at which `line` starts a new display row when soft-wrapped
+/// at `width` columns. Wrapping is per character; empty when the line fits.
+pub fn wrap_breaks(line: &str, width: usize) -> Vec<usize> {
+    let width = width.max(1);
+    let mut breaks = Vec::new();
+    let mut col = 0;
+    for (i, c) in line.char_indices() {
+        let w = c.width().unwrap_or(0);
+        if col > 0 && col + w > width {
+            breaks.push(i);
+            col = 0;
+        }
+        col += w;
+    }
+    breaks
+}
+
 // ── Request panel state ──────────────────────────────────────────────
 
 /// The full in-memory state of the request content panel.
@@ -162,6 +313,27 @@ pub struct RequestPanel {
     pub body_lines: Vec<String>,
     pub body_cursor_row: usize,
     pub body_cursor_col: usize,
+    /// False when a raw body labeled as JSON does not parse; recomputed by
+    /// `validate_body_json` when leaving insert mode.
+    pub body_json_valid: bool,
+
+    pub body_mode: BodyMode,
+    /// Form fields edited in the Body section when `body_mode` is form-urlencoded.
+    pub form_fields: Vec<KvRow>,
+    pub form_selected: usize,
+    /// The GraphQL editor currently backed by `body_lines`. The other pane's
+    /// lines and cursor are parked in `graphql_stash` until it is focused.
+    pub graphql_pane: GraphqlPane,
+    graphql_stash: Vec<String>,
+    graphql_stash_cursor: (usize, usize),
+
+    /// Request timeout in milliseconds (None = no timeout).
+    pub timeout_ms: Option<u64>,
+
+    pub auth: HttpAuth,
+    pub auth_selected: usize,
+    /// Cursor position within the edited auth field.
+    pub auth_cursor: usize,
 
     // Focus
     pub focused_section: Section,
@@ -181,8 +353,37 @@ pub struct RequestPanel {
     // Response
     pub response: Option<ResponseData>,
     pub request_in_flight: bool,
+    /// The last send was aborted before a response arrived.
+    pub cancelled: bool,
     pub spinner_frame: u8,
     pub error_message: Option<String>,
+
+    // Environment
+    /// Name of the active environment (None = no substitution).
+    pub active_env: Option<String>,
+    /// Variables of the active environment, used to resolve `${VAR}` placeholders.
+    pub env_vars: Vec<(String, String)>,
+
+    // Default headers
+    /// Enabled global default headers, merged into every request.
+    pub default_headers: Vec<(String, String)>,
+    /// Per-query opt-out of `default_headers`.
+    pub use_default_headers: bool,
+
+    // Folder values
+    /// Headers inherited from the folders above the query, nearest first.
+    pub folder_headers: Vec<model::InheritedValue>,
+    /// Variables inherited from the folders above the query; they take
+    /// precedence over the active environment.
+    pub folder_vars: Vec<model::InheritedValue>,
+
+    /// Proxy configuration of the executor, shown in the panel title.
+    pub proxy: ProxyConfig,
+
+    /// Soft-wrap long response body lines (session-wide, off by default).
+    pub wrap_response: bool,
+    /// Last body file check, reused while rendering.
+    body_file_status: RefCell<Option<BodyFileStatus>>,
 }
 
 impl RequestPanel {
@@ -201,6 +402,17 @@ impl RequestPanel {
             body_lines: vec![String::new()],
             body_cursor_row: 0,
             body_cursor_col: 0,
+            body_json_valid: true,
+            body_mode: BodyMode::Raw,
+            form_fields: Vec::new(),
+            form_selected: 0,
+            graphql_pane: GraphqlPane::Query,
+            graphql_stash: vec![String::new()],
+            graphql_stash_cursor: (0, 0),
+            timeout_ms: None,
+            auth: HttpAuth::default(),
+            auth_selected: 0,
+            auth_cursor: 0,
             focused_section: Section::Url,
             panel_focus: PanelFocus::Request,
             editing: false,
@@ -209,8 +421,18 @@ impl RequestPanel {
             dirty: false,
             response: None,
             request_in_flight: false,
+            cancelled: false,
             spinner_frame: 0,
             error_message: None,
+            active_env: None,
+            env_vars: Vec::new(),
+            default_headers: Vec::new(),
+            use_default_headers: true,
+            folder_headers: Vec::new(),
+            folder_vars: Vec::new(),
+            proxy: ProxyConfig::default(),
+            wrap_response: false,
+            body_file_status: RefCell::new(None),
         }
     }
 
@@ -227,6 +449,8 @@ impl RequestPanel {
         let req = model::load_request(conn, entry_id)?.unwrap();
         let db_headers = model::load_headers(conn, req_id)?;
         let db_params = model::load_query_params(conn, req_id)?;
+        let db_form_fields = model::load_form_fields(conn, req_id)?;
+        let auth = model::load_auth(conn, req_id)?;
 
         self.active_entry_id = Some(entry_id);
         self.request_db_id = Some(req_id);
@@ -267,6 +491,26 @@ impl RequestPanel {
         };
         self.body_cursor_row = 0;
         self.body_cursor_col = 0;
+        self.body_mode = req.body_mode;
+        self.form_fields = db_form_fields
+            .into_iter()
+            .map(|f| KvRow {
+                db_id: f.id,
+                key: f.key,
+                value: f.value,
+                enabled: f.enabled,
+                cursor: 0,
+            })
+            .collect();
+        self.form_selected = 0;
+        self.graphql_pane = GraphqlPane::Query;
+        self.graphql_stash = text_to_lines(&req.graphql_variables);
+        self.graphql_stash_cursor = (0, 0);
+        self.timeout_ms = req.timeout_ms;
+        self.use_default_headers = req.use_default_headers;
+        self.auth = auth;
+        self.auth_selected = 0;
+        self.auth_cursor = 0;
 
         self.focused_section = Section::Url;
         self.panel_focus = PanelFocus::Request;
@@ -275,10 +519,23 @@ impl RequestPanel {
         self.dirty = false;
         self.response = None;
         self.error_message = None;
+        self.cancelled = false;
+        self.validate_body_json();
+        self.load_folder_values(conn)?;
 
         Ok(())
     }
 
+    /// Reload the headers and variables inherited from the parent folders.
+    pub fn load_folder_values(&mut self, conn: &Connection) -> Result<()> {
+        let Some(entry_id) = self.active_entry_id else {
+            return Ok(());
+        };
+        self.folder_headers = model::inherited_folder_headers(conn, entry_id)?;
+        self.folder_vars = model::inherited_folder_vars(conn, entry_id)?;
+        Ok(())
+    }
+
     /// Save the current request data to the database.
     pub fn save(&mut self, conn: &Connection) -> Result<()> {
         let req_id = match self.request_db_id {
@@ -286,8 +543,15 @@ impl RequestPanel {
             None => return Ok(()),
         };
 
-        let body = self.body_lines.join("\n");
+        let body = self.graphql_query_lines().join("\n");
         model::save_request(conn, req_id, self.method, &self.url, &body)?;
+        model::set_request_graphql_variables(
+            conn,
+            req_id,
+            &self.graphql_variables_lines().join("\n"),
+        )?;
+        model::set_request_timeout(conn, req_id, self.timeout_ms)?;
+        model::set_request_use_default_headers(conn, req_id, self.use_default_headers)?;
 
         let headers: Vec<(String, String, bool)> = self
             .headers
@@ -303,27 +567,205 @@ impl RequestPanel {
             .collect();
         model::replace_query_params(conn, req_id, &params)?;
 
+        let form_fields: Vec<(String, String, bool)> = self
+            .form_fields
+            .iter()
+            .map(|f| (f.key.clone(), f.value.clone(), f.enabled))
+            .collect();
+        model::replace_form_fields(conn, req_id, &form_fields)?;
+        model::set_request_body_mode(conn, req_id, self.body_mode)?;
+        model::save_auth(conn, req_id, &self.auth)?;
+
         self.dirty = false;
         Ok(())
     }
 
-    /// Clear the panel (no query selected).
+    /// Clear the panel (no query selected). The active environment, the
+    /// default headers and the proxy are kept.
     pub fn clear(&mut self) {
+        let active_env = self.active_env.take();
+        let env_vars = std::mem::take(&mut self.env_vars);
+        let default_headers = std::mem::take(&mut self.default_headers);
+        let proxy = std::mem::take(&mut self.proxy);
+        let wrap_response = self.wrap_response;
         *self = Self::new();
+        self.wrap_response = wrap_response;
+        self.active_env = active_env;
+        self.env_vars = env_vars;
+        self.default_headers = default_headers;
+        self.proxy = proxy;
+    }
+
+    // ── Environment ──────────────────────────────────────────────────
+
+    /// Set the active environment and its variables.
+    pub fn set_environment(&mut self, name: Option<String>, vars: Vec<(String, String)>) {
+        self.active_env = name;
+        self.env_vars = vars;
+    }
+
+    /// Resolve `${VAR}` placeholders against the folder variables and the
+    /// active environment.
+    pub fn resolve(&self, text: &str) -> String {
+        substitute_vars(text, &self.vars()).0
+    }
+
+    /// The variables placeholders resolve against: folder variables first,
+    /// so they shadow environment variables of the same name.
+    fn vars(&self) -> Vec<(String, String)> {
+        self.folder_vars
+            .iter()
+            .map(|v| (v.key.clone(), v.value.clone()))
+            .chain(self.env_vars.iter().cloned())
+            .collect()
+    }
+
+    /// Names of placeholders in the outgoing request that neither the folder
+    /// variables nor the active environment resolve (deduplicated, in order
+    /// of appearance).
+    pub fn unresolved_vars(&self) -> Vec<String> {
+        let mut sources: Vec<&str> = vec![&self.url];
+        let form_fields = if self.is_form_body() {
+            &self.form_fields[..]
+        } else {
+            &[]
+        };
+        for row in self
+            .query_params
+            .iter()
+            .chain(self.headers.iter())
+            .chain(form_fields)
+        {
+            if row.enabled && !row.key.is_empty() {
+                sources.push(&row.key);
+                sources.push(&row.value);
+            }
+        }
+        let defaults = self.applied_default_headers();
+        for (key, value) in &defaults {
+            sources.push(key);
+            sources.push(value);
+        }
+        for header in self.applied_folder_headers() {
+            sources.push(&header.key);
+            sources.push(&header.value);
+        }
+        for field in self.auth_fields() {
+            sources.extend(self.auth_value(field));
+        }
+        let body = match self.body_mode {
+            BodyMode::Raw | BodyMode::File => self.body_text(),
+            BodyMode::FormUrlEncoded => String::new(),
+            BodyMode::GraphQl => format!(
+                "{}\n{}",
+                self.graphql_query_lines().join("\n"),
+                self.graphql_variables_lines().join("\n")
+            ),
+        };
+        sources.push(&body);
+
+        let vars = self.vars();
+        let mut missing: Vec<String> = Vec::new();
+        for source in sources {
+            for name in substitute_vars(source, &vars).1 {
+                if !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+        }
+        missing
+    }
+
+    // ── Default headers ──────────────────────────────────────────────
+
+    /// Set the enabled global default headers.
+    pub fn set_default_headers(&mut self, headers: Vec<(String, String)>) {
+        self.default_headers = headers;
+    }
+
+    /// Toggle whether this query sends the default headers.
+    pub fn toggle_default_headers(&mut self) {
+        self.use_default_headers = !self.use_default_headers;
+        self.dirty = true;
+    }
+
+    /// Default headers that will be merged into this request: none when the
+    /// query opted out, and none whose name an enabled request header overrides.
+    pub fn applied_default_headers(&self) -> Vec<(String, String)> {
+        if !self.use_default_headers {
+            return Vec::new();
+        }
+        self.default_headers
+            .iter()
+            .filter(|(key, _)| {
+                !key.is_empty()
+                    && !self.overrides_header(key)
+                    && !self
+                        .folder_headers
+                        .iter()
+                        .any(|h| h.key.eq_ignore_ascii_case(key))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Folder headers that apply to this query: those not overridden by one
+    /// of its own enabled headers.
+    pub fn applied_folder_headers(&self) -> Vec<&model::InheritedValue> {
+        self.folder_headers
+            .iter()
+            .filter(|h| !self.overrides_header(&h.key))
+            .collect()
+    }
+
+    /// Whether the query has an enabled header named `key`.
+    fn overrides_header(&self, key: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|h| h.enabled && h.key.eq_ignore_ascii_case(key))
     }
 
     // ── Method ───────────────────────────────────────────────────────
 
     pub fn cycle_method_forward(&mut self) {
         self.method = self.method.next();
+        self.clear_body_method_warning();
         self.dirty = true;
     }
 
     pub fn cycle_method_backward(&mut self) {
         self.method = self.method.prev();
+        self.clear_body_method_warning();
         self.dirty = true;
     }
 
+    /// A warning when the method usually carries no body (GET/HEAD) but the
+    /// request has one. The request is still sent as is.
+    pub fn body_method_warning(&self) -> Option<String> {
+        let method = self.effective_method();
+        if matches!(method, HttpMethod::Get | HttpMethod::Head)
+            && (self.body_file().is_some() || !self.request_body().trim().is_empty())
+        {
+            Some(format!("{}{BODY_METHOD_WARNING}", method.as_str()))
+        } else {
+            None
+        }
+    }
+
+    /// Drop a stale `body_method_warning` from `error_message`, keeping any
+    /// other warnings shown alongside it.
+    fn clear_body_method_warning(&mut self) {
+        if let Some(message) = self.error_message.take() {
+            let rest: Vec<&str> = message
+                .split(WARNING_SEPARATOR)
+                .filter(|w| !w.ends_with(BODY_METHOD_WARNING))
+                .collect();
+            if !rest.is_empty() {
+                self.error_message = Some(rest.join(WARNING_SEPARATOR));
+            }
+        }
+    }
+
     // ── URL editing ──────────────────────────────────────────────────
 
     pub fn url_insert_char(&mut self, c: char) {
@@ -382,13 +824,28 @@ impl RequestPanel {
         self.url_cursor = self.url.len();
     }
 
-    // ── Key-value (headers / params) editing ─────────────────────────
+    // ── Key-value (headers / params / form) editing ──────────────────
+
+    /// Whether the Body section is edited as form fields.
+    pub fn is_form_body(&self) -> bool {
+        self.body_mode == BodyMode::FormUrlEncoded
+    }
+
+    /// Whether the focused section is edited as a key-value table.
+    pub fn in_kv_section(&self) -> bool {
+        match self.focused_section {
+            Section::Params | Section::Headers => true,
+            Section::Body => self.is_form_body(),
+            _ => false,
+        }
+    }
 
     /// Access the active kv row mutably.
     fn kv_selected_row_mut(&mut self) -> Option<&mut KvRow> {
         match self.focused_section {
             Section::Headers => self.headers.get_mut(self.headers_selected),
             Section::Params => self.query_params.get_mut(self.params_selected),
+            Section::Body if self.is_form_body() => self.form_fields.get_mut(self.form_selected),
             _ => None,
         }
     }
@@ -397,6 +854,7 @@ impl RequestPanel {
         match self.focused_section {
             Section::Headers => &mut self.headers_selected,
             Section::Params => &mut self.params_selected,
+            Section::Body => &mut self.form_selected,
             _ => &mut self.headers_selected,
         }
     }
@@ -405,6 +863,7 @@ impl RequestPanel {
         match self.focused_section {
             Section::Headers => self.headers.len(),
             Section::Params => self.query_params.len(),
+            Section::Body if self.is_form_body() => self.form_fields.len(),
             _ => 0,
         }
     }
@@ -457,6 +916,16 @@ impl RequestPanel {
                 self.params_selected = idx;
                 idx
             }
+            Section::Body if self.is_form_body() => {
+                let idx = if self.form_fields.is_empty() {
+                    0
+                } else {
+                    self.form_selected + 1
+                };
+                self.form_fields.insert(idx, KvRow::new_empty());
+                self.form_selected = idx;
+                idx
+            }
             _ => return,
         };
         let _ = sel;
@@ -485,6 +954,13 @@ impl RequestPanel {
                     self.dirty = true;
                 }
             }
+            Section::Body if self.is_form_body() && !self.form_fields.is_empty() => {
+                self.form_fields.remove(self.form_selected);
+                if self.form_selected >= self.form_fields.len() && !self.form_fields.is_empty() {
+                    self.form_selected = self.form_fields.len() - 1;
+                }
+                self.dirty = true;
+            }
             _ => {}
         }
     }
@@ -606,8 +1082,191 @@ impl RequestPanel {
         self.editing = false;
     }
 
+    // ── Auth form ────────────────────────────────────────────────────
+
+    /// The rows of the auth form for the current auth type.
+    pub fn auth_fields(&self) -> Vec<AuthField> {
+        let mut fields = vec![AuthField::Type];
+        match self.auth.auth_type {
+            AuthType::None => {}
+            AuthType::Bearer => fields.push(AuthField::Token),
+            AuthType::Basic => fields.extend([AuthField::Username, AuthField::Password]),
+            AuthType::ApiKey => fields.extend([AuthField::ApiKeyHeader, AuthField::ApiKeyValue]),
+        }
+        fields
+    }
+
+    pub fn auth_selected_field(&self) -> AuthField {
+        self.auth_fields()
+            .get(self.auth_selected)
+            .copied()
+            .unwrap_or(AuthField::Type)
+    }
+
+    /// The text value behind an auth field (None for the type selector).
+    pub fn auth_value(&self, field: AuthField) -> Option<&str> {
+        match field {
+            AuthField::Type => None,
+            AuthField::Token => Some(&self.auth.token),
+            AuthField::Username => Some(&self.auth.username),
+            AuthField::Password => Some(&self.auth.password),
+            AuthField::ApiKeyHeader => Some(&self.auth.api_key_header),
+            AuthField::ApiKeyValue => Some(&self.auth.api_key_value),
+        }
+    }
+
+    fn auth_value_mut(&mut self, field: AuthField) -> Option<&mut String> {
+        match field {
+            AuthField::Type => None,
+            AuthField::Token => Some(&mut self.auth.token),
+            AuthField::Username => Some(&mut self.auth.username),
+            AuthField::Password => Some(&mut self.auth.password),
+            AuthField::ApiKeyHeader => Some(&mut self.auth.api_key_header),
+            AuthField::ApiKeyValue => Some(&mut self.auth.api_key_value),
+        }
+    }
+
+    pub fn auth_move_down(&mut self) {
+        if self.auth_selected + 1 < self.auth_fields().len() {
+            self.auth_selected += 1;
+        }
+    }
+
+    pub fn auth_move_up(&mut self) {
+        self.auth_selected = self.auth_selected.saturating_sub(1);
+    }
+
+    pub fn auth_cycle_type_forward(&mut self) {
+        self.auth.auth_type = self.auth.auth_type.next();
+        self.auth_selected = 0;
+        self.dirty = true;
+    }
+
+    pub fn auth_cycle_type_backward(&mut self) {
+        self.auth.auth_type = self.auth.auth_type.prev();
+        self.auth_selected = 0;
+        self.dirty = true;
+    }
+
+    /// Start editing the selected auth field. Returns false for the type row.
+    pub fn auth_start_edit(&mut self) -> bool {
+        let field = self.auth_selected_field();
+        match self.auth_value(field) {
+            Some(value) => {
+                self.auth_cursor = value.len();
+                self.editing = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn auth_insert_char(&mut self, c: char) {
+        let field = self.auth_selected_field();
+        let cursor = self.auth_cursor;
+        if let Some(value) = self.auth_value_mut(field) {
+            value.insert(cursor, c);
+            self.auth_cursor += c.len_utf8();
+            self.dirty = true;
+        }
+    }
+
+    pub fn auth_backspace(&mut self) {
+        let field = self.auth_selected_field();
+        let cursor = self.auth_cursor;
+        if let Some(value) = self.auth_value_mut(field)
+            && let Some(prev) = value[..cursor].chars().last()
+        {
+            let at = cursor - prev.len_utf8();
+            value.remove(at);
+            self.auth_cursor = at;
+            self.dirty = true;
+        }
+    }
+
+    pub fn auth_cursor_left(&mut self) {
+        let field = self.auth_selected_field();
+        if let Some(prev) = self
+            .auth_value(field)
+            .and_then(|v| v[..self.auth_cursor].chars().last())
+        {
+            self.auth_cursor -= prev.len_utf8();
+        }
+    }
+
+    pub fn auth_cursor_right(&mut self) {
+        let field = self.auth_selected_field();
+        if let Some(next) = self
+            .auth_value(field)
+            .and_then(|v| v[self.auth_cursor..].chars().next())
+        {
+            self.auth_cursor += next.len_utf8();
+        }
+    }
+
     // ── Body editing ─────────────────────────────────────────────────
 
+    /// Cycle the body mode (raw → form-urlencoded → GraphQL).
+    pub fn cycle_body_mode(&mut self) {
+        self.focus_graphql_pane(GraphqlPane::Query);
+        self.body_mode = self.body_mode.next();
+        self.editing = false;
+        self.validate_body_json();
+        self.dirty = true;
+    }
+
+    /// Whether the Body section is a GraphQL query + variables pair.
+    pub fn is_graphql_body(&self) -> bool {
+        self.body_mode == BodyMode::GraphQl
+    }
+
+    /// Make `pane` the editor backed by `body_lines`, parking the other one.
+    pub fn focus_graphql_pane(&mut self, pane: GraphqlPane) {
+        if self.graphql_pane == pane {
+            return;
+        }
+        std::mem::swap(&mut self.body_lines, &mut self.graphql_stash);
+        let cursor = (self.body_cursor_row, self.body_cursor_col);
+        (self.body_cursor_row, self.body_cursor_col) = self.graphql_stash_cursor;
+        self.graphql_stash_cursor = cursor;
+        self.graphql_pane = pane;
+    }
+
+    /// Switch between the GraphQL Query and Variables editors.
+    pub fn toggle_graphql_pane(&mut self) {
+        self.focus_graphql_pane(match self.graphql_pane {
+            GraphqlPane::Query => GraphqlPane::Variables,
+            GraphqlPane::Variables => GraphqlPane::Query,
+        });
+    }
+
+    /// Lines of the main body editor (the GraphQL query in GraphQL mode).
+    pub fn graphql_query_lines(&self) -> &[String] {
+        match self.graphql_pane {
+            GraphqlPane::Query => &self.body_lines,
+            GraphqlPane::Variables => &self.graphql_stash,
+        }
+    }
+
+    /// Lines of the GraphQL variables editor.
+    pub fn graphql_variables_lines(&self) -> &[String] {
+        match self.graphql_pane {
+            GraphqlPane::Query => &self.graphql_stash,
+            GraphqlPane::Variables => &self.body_lines,
+        }
+    }
+
+    /// Parse the resolved GraphQL variables. Empty text means no variables.
+    pub fn graphql_variables(&self) -> Result<Option<serde_json::Value>, String> {
+        let text = self.resolve(&self.graphql_variables_lines().join("\n"));
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("Invalid GraphQL variables: {e}"))
+    }
+
     pub fn body_insert_char(&mut self, c: char) {
         if let Some(line) = self.body_lines.get_mut(self.body_cursor_row) {
             line.insert(self.body_cursor_col, c);
@@ -789,10 +1448,91 @@ impl RequestPanel {
         FRAMES[self.spinner_frame as usize]
     }
 
+    // ── Import ───────────────────────────────────────────────────────
+
+    /// Replace the request fields with an imported request (e.g. from cURL).
+    /// The URL's query string is split out into query params. Marks the
+    /// panel dirty so the import is persisted on the next save.
+    pub fn apply_request_cmd(&mut self, cmd: HttpRequestCmd) {
+        let (url, query) = match cmd.url.split_once('?') {
+            Some((url, query)) => (url.to_string(), query),
+            None => (cmd.url, ""),
+        };
+
+        self.method = cmd.method;
+        self.url = url;
+        self.url_cursor = self.url.len();
+
+        self.query_params = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                KvRow {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    ..KvRow::new_empty()
+                }
+            })
+            .collect();
+        self.params_selected = 0;
+
+        self.headers = cmd
+            .headers
+            .into_iter()
+            .map(|(key, value)| KvRow {
+                key,
+                value,
+                ..KvRow::new_empty()
+            })
+            .collect();
+        self.headers_selected = 0;
+
+        self.focus_graphql_pane(GraphqlPane::Query);
+        self.body_lines = if cmd.body.is_empty() {
+            vec![String::new()]
+        } else {
+            cmd.body.lines().map(|l| l.to_string()).collect()
+        };
+        self.body_cursor_row = 0;
+        self.body_cursor_col = 0;
+        if !cmd.body.is_empty() {
+            self.body_mode = BodyMode::Raw;
+        }
+
+        // Only override the timeout when the import specifies one
+        if cmd.timeout_ms.is_some() {
+            self.timeout_ms = cmd.timeout_ms;
+        }
+
+        self.editing = false;
+        self.error_message = None;
+        self.dirty = true;
+    }
+
+    /// Toggle soft-wrapping of the response body, keeping the top visible
+    /// body line in place.
+    pub fn toggle_response_wrap(&mut self) {
+        let wrap = self.wrap_response;
+        self.wrap_response = !wrap;
+        if let Some(ref mut resp) = self.response {
+            let (line, _) = resp.body_row_to_line(resp.body_scroll, wrap);
+            resp.body_scroll = resp.body_line_to_row(line, !wrap);
+        }
+    }
+
     // ── Build request URL with params ────────────────────────────────
 
+    /// The proxy the current request would be routed through, if any.
+    pub fn active_proxy(&self) -> Option<&str> {
+        self.proxy.proxy_for(&self.build_url_with_params())
+    }
+
     /// Build the full URL with enabled query params appended.
+    /// `${VAR}` placeholders are resolved against the folder variables and
+    /// the active environment.
     pub fn build_url_with_params(&self) -> String {
+        let url = self.resolve(&self.url);
         let enabled_params: Vec<_> = self
             .query_params
             .iter()
@@ -800,31 +1540,306 @@ impl RequestPanel {
             .collect();
 
         if enabled_params.is_empty() {
-            return self.url.clone();
+            return url;
         }
 
-        let separator = if self.url.contains('?') { "&" } else { "?" };
+        let separator = if url.contains('?') { "&" } else { "?" };
         let params_str: Vec<String> = enabled_params
             .iter()
-            .map(|p| format!("{}={}", p.key, p.value))
+            .map(|p| format!("{}={}", self.resolve(&p.key), self.resolve(&p.value)))
             .collect();
 
-        format!("{}{}{}", self.url, separator, params_str.join("&"))
+        format!("{}{}{}", url, separator, params_str.join("&"))
     }
 
-    /// Collect enabled headers as (key, value) pairs.
+    /// Collect enabled headers as (key, value) pairs, with placeholders resolved.
+    /// Non-overridden default headers come first, then the inherited folder
+    /// headers; the header computed from the auth settings is appended.
     pub fn enabled_headers(&self) -> Vec<(String, String)> {
-        self.headers
+        let mut headers: Vec<(String, String)> = self
+            .applied_default_headers()
             .iter()
-            .filter(|h| h.enabled && !h.key.is_empty())
-            .map(|h| (h.key.clone(), h.value.clone()))
-            .collect()
+            .map(|(key, value)| (self.resolve(key), self.resolve(value)))
+            .collect();
+        headers.extend(
+            self.applied_folder_headers()
+                .into_iter()
+                .map(|h| (self.resolve(&h.key), self.resolve(&h.value))),
+        );
+        headers.extend(
+            self.headers
+                .iter()
+                .filter(|h| h.enabled && !h.key.is_empty())
+                .map(|h| (self.resolve(&h.key), self.resolve(&h.value))),
+        );
+        if let Some(content_type) = self.implied_content_type() {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+        headers.extend(self.resolved_auth().header());
+        headers
+    }
+
+    /// The `Content-Type` added on send because none of the applied default,
+    /// folder or request headers sets one. Shown greyed out under Headers.
+    pub fn implied_content_type(&self) -> Option<&'static str> {
+        let is_content_type = |key: &str| self.resolve(key).eq_ignore_ascii_case("content-type");
+        let user_set = self
+            .applied_default_headers()
+            .iter()
+            .any(|(key, _)| is_content_type(key))
+            || self
+                .applied_folder_headers()
+                .iter()
+                .any(|h| is_content_type(&h.key))
+            || self
+                .headers
+                .iter()
+                .any(|h| h.enabled && is_content_type(&h.key));
+        if user_set {
+            return None;
+        }
+        match self.body_mode {
+            BodyMode::Raw => self.raw_content_type(),
+            BodyMode::FormUrlEncoded => Some("application/x-www-form-urlencoded"),
+            BodyMode::GraphQl => Some("application/json"),
+            BodyMode::File => self
+                .body_file()
+                .map(|path| body_format::content_type_for_path(&path)),
+        }
+    }
+
+    /// Recompute `body_json_valid`. Only raw bodies sent with an explicit
+    /// JSON `Content-Type` are checked, so arbitrary text is never flagged
+    /// (an unlabeled body is only implied to be JSON when it parses).
+    pub fn validate_body_json(&mut self) {
+        self.body_json_valid = self.body_mode != BodyMode::Raw || {
+            let body = self.request_body();
+            let declares_json = self
+                .enabled_headers()
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("content-type") && v.contains("json"));
+            !declares_json
+                || body.trim().is_empty()
+                || serde_json::from_str::<serde_json::Value>(&body).is_ok()
+        };
+    }
+
+    /// The content type implied by a raw body that parses as JSON. Other text
+    /// is sent unlabeled.
+    fn raw_content_type(&self) -> Option<&'static str> {
+        let body = self.request_body();
+        if !body.trim().is_empty() && serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+            Some("application/json")
+        } else {
+            None
+        }
+    }
+
+    /// The auth settings with `${VAR}` placeholders resolved.
+    fn resolved_auth(&self) -> HttpAuth {
+        HttpAuth {
+            auth_type: self.auth.auth_type,
+            token: self.resolve(&self.auth.token),
+            username: self.resolve(&self.auth.username),
+            password: self.resolve(&self.auth.password),
+            api_key_header: self.resolve(&self.auth.api_key_header),
+            api_key_value: self.resolve(&self.auth.api_key_value),
+        }
     }
 
     /// Get the body as a single string.
     pub fn body_text(&self) -> String {
         self.body_lines.join("\n")
     }
+
+    /// In file mode, the path the body is read from (placeholders resolved,
+    /// `~` expanded). None in other modes or when no path was entered.
+    pub fn body_file(&self) -> Option<PathBuf> {
+        if self.body_mode != BodyMode::File {
+            return None;
+        }
+        let path = self.resolve(self.body_text().trim());
+        (!path.is_empty()).then(|| PathBuf::from(rstools_core::paths::expand_tilde(&path)))
+    }
+
+    /// The method to send: GraphQL requests are always POSTed.
+    pub fn effective_method(&self) -> HttpMethod {
+        if self.is_graphql_body() {
+            HttpMethod::Post
+        } else {
+            self.method
+        }
+    }
+
+    /// In file mode, the size of the body file, or why it cannot be sent.
+    pub fn body_file_size(&self) -> Option<Result<u64, String>> {
+        if self.body_mode != BodyMode::File {
+            return None;
+        }
+        let Some(path) = self.body_file() else {
+            return Some(Err("No body file set".to_string()));
+        };
+        Some(match std::fs::metadata(&path) {
+            Ok(meta) if meta.is_file() => Ok(meta.len()),
+            Ok(_) => Err(format!("Body file is not a file: {}", path.display())),
+            Err(_) => Err(format!("Body file not found: {}", path.display())),
+        })
+    }
+
+    /// `body_file_size` for rendering: the file is checked again only when
+    /// the path changes or the last check is `BODY_FILE_RECHECK` old.
+    pub fn cached_body_file_size(&self) -> Option<Result<u64, String>> {
+        if self.body_mode != BodyMode::File {
+            return None;
+        }
+        let path = self.body_file();
+        let mut status = self.body_file_status.borrow_mut();
+        if let Some(last) = status.as_ref()
+            && last.path == path
+            && last.checked_at.elapsed() < BODY_FILE_RECHECK
+        {
+            return Some(last.result.clone());
+        }
+        let result = self.body_file_size()?;
+        *status = Some(BodyFileStatus {
+            path,
+            checked_at: Instant::now(),
+            result: result.clone(),
+        });
+        Some(result)
+    }
+
+    /// The body to send, with placeholders resolved. In form mode the
+    /// enabled fields with a key are percent-encoded as `a=1&b=2`; in GraphQL
+    /// mode the query and variables are wrapped as `{"query", "variables"}`
+    /// (invalid variables are sent as null — check `graphql_variables` first).
+    /// Empty in file mode: the executor reads `body_file` instead.
+    pub fn request_body(&self) -> String {
+        match self.body_mode {
+            BodyMode::Raw => return self.resolve(&self.body_text()),
+            BodyMode::File => return String::new(),
+            BodyMode::GraphQl => {
+                let payload = serde_json::json!({
+                    "query": self.resolve(&self.graphql_query_lines().join("\n")),
+                    "variables": self.graphql_variables().ok().flatten(),
+                });
+                return payload.to_string();
+            }
+            BodyMode::FormUrlEncoded => {}
+        }
+        self.form_fields
+            .iter()
+            .filter(|f| f.enabled && !f.key.is_empty())
+            .map(|f| {
+                format!(
+                    "{}={}",
+                    form_urlencode(&self.resolve(&f.key)),
+                    form_urlencode(&self.resolve(&f.value))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Serialize the request as a copy-pasteable cURL command, using the
+    /// resolved URL, enabled headers, and body. `insecure` (the session's
+    /// certificate setting) adds `-k`; the active proxy is passed with `-x`.
+    pub fn to_curl(&self, insecure: bool) -> String {
+        let mut parts = vec!["curl".to_string()];
+        let method = self.effective_method();
+        if method != HttpMethod::Get {
+            parts.push(format!("-X {}", method.as_str()));
+        }
+        if let Some(ms) = self.timeout_ms {
+            parts.push(format!("-m {}", ms as f64 / 1000.0));
+        }
+        if insecure {
+            parts.push("-k".to_string());
+        }
+        if let Some(proxy) = self.active_proxy() {
+            parts.push(format!("-x {}", shell_quote(proxy)));
+        }
+        parts.push(shell_quote(&self.build_url_with_params()));
+
+        for (key, value) in self.enabled_headers() {
+            parts.push(format!("-H {}", shell_quote(&format!("{key}: {value}"))));
+        }
+
+        let body = self.request_body();
+        if let Some(path) = self.body_file() {
+            parts.push(format!(
+                "--data-binary {}",
+                shell_quote(&format!("@{}", path.display()))
+            ));
+        } else if !body.is_empty() {
+            parts.push(format!("--data-raw {}", shell_quote(&body)));
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// Split stored text into editor lines (always at least one line).
+fn text_to_lines(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        vec![String::new()]
+    } else {
+        text.lines().map(|l| l.to_string()).collect()
+    }
+}
+
+/// Percent-encode a form key or value (`application/x-www-form-urlencoded`):
+/// alphanumerics and `*-._` are kept, spaces become `+`.
+fn form_urlencode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Quote a string for a POSIX shell using single quotes.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Replace `${VAR}` placeholders with values from `vars`.
+/// Returns the substituted text and the names of placeholders that could not
+/// be resolved; those are left literally in the output.
+pub fn substitute_vars(text: &str, vars: &[(String, String)]) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(text.len());
+    let mut unresolved = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return (out, unresolved);
+        };
+
+        let name = &after[..end];
+        match vars.iter().find(|(k, _)| k == name) {
+            Some((_, value)) if !name.is_empty() => out.push_str(value),
+            _ => {
+                out.push_str(&rest[start..start + 3 + end]);
+                if !name.is_empty() {
+                    unresolved.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    (out, unresolved)
 }
 
 #[cfg(test)]
@@ -937,12 +1952,67 @@ mod tests {
         panel.next_section();
         assert_eq!(panel.focused_section, Section::Headers);
         panel.next_section();
+        assert_eq!(panel.focused_section, Section::Auth);
+        panel.next_section();
         assert_eq!(panel.focused_section, Section::Body);
         panel.next_section();
         assert_eq!(panel.focused_section, Section::Url);
 
         panel.prev_section();
         assert_eq!(panel.focused_section, Section::Body);
+        panel.prev_section();
+        assert_eq!(panel.focused_section, Section::Auth);
+    }
+
+    #[test]
+    fn test_auth_form() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "secure", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "secure", &conn).unwrap();
+        assert_eq!(panel.auth_fields(), vec![AuthField::Type]);
+        assert!(!panel.auth_start_edit());
+
+        panel.auth_cycle_type_forward();
+        panel.auth_cycle_type_forward();
+        assert_eq!(panel.auth.auth_type, AuthType::Basic);
+        assert_eq!(
+            panel.auth_fields(),
+            vec![AuthField::Type, AuthField::Username, AuthField::Password]
+        );
+
+        panel.auth_move_down();
+        assert!(panel.auth_start_edit());
+        for c in "üser".chars() {
+            panel.auth_insert_char(c);
+        }
+        panel.auth_backspace();
+        panel.auth_cursor_left();
+        panel.auth_backspace();
+        assert_eq!(panel.auth.username, "üe");
+        panel.auth_move_down();
+        panel.auth_start_edit();
+        for c in "${PASS}".chars() {
+            panel.auth_insert_char(c);
+        }
+        assert!(panel.dirty);
+        assert_eq!(panel.unresolved_vars(), vec!["PASS".to_string()]);
+
+        // The auth header is injected with placeholders resolved, without
+        // appearing in the visible headers list
+        panel.set_environment(
+            Some("dev".to_string()),
+            vec![("PASS".to_string(), "pw".to_string())],
+        );
+        assert!(panel.headers.is_empty());
+        assert_eq!(
+            panel.enabled_headers(),
+            vec![("Authorization".to_string(), "Basic w7xlOnB3".to_string())]
+        );
+
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "secure", &conn).unwrap();
+        assert_eq!(reloaded.auth, panel.auth);
     }
 
     #[test]
@@ -976,6 +2046,520 @@ mod tests {
         assert_eq!(url, "https://api.example.com/users?page=1&limit=10");
     }
 
+    #[test]
+    fn test_substitute_vars() {
+        let vars = vec![
+            ("HOST".to_string(), "api.example.com".to_string()),
+            ("TOKEN".to_string(), "secret".to_string()),
+        ];
+
+        let (out, missing) = substitute_vars("https://${HOST}/users", &vars);
+        assert_eq!(out, "https://api.example.com/users");
+        assert!(missing.is_empty());
+
+        let (out, missing) = substitute_vars("${HOST}/${NOPE}?t=${TOKEN}", &vars);
+        assert_eq!(out, "api.example.com/${NOPE}?t=secret");
+        assert_eq!(missing, vec!["NOPE".to_string()]);
+
+        // Unterminated placeholders are kept verbatim
+        let (out, missing) = substitute_vars("a ${HOST", &vars);
+        assert_eq!(out, "a ${HOST");
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_env_resolution_in_request() {
+        let mut panel = RequestPanel::new();
+        panel.set_environment(
+            Some("dev".to_string()),
+            vec![
+                ("BASE".to_string(), "http://localhost".to_string()),
+                ("TOKEN".to_string(), "abc".to_string()),
+            ],
+        );
+        panel.url = "${BASE}/users".to_string();
+        panel.query_params.push(KvRow {
+            db_id: 0,
+            key: "token".to_string(),
+            value: "${TOKEN}".to_string(),
+            enabled: true,
+            cursor: 0,
+        });
+        panel.headers.push(KvRow {
+            db_id: 0,
+            key: "Authorization".to_string(),
+            value: "Bearer ${TOKEN}".to_string(),
+            enabled: true,
+            cursor: 0,
+        });
+        panel.body_lines = vec!["{\"id\": \"${MISSING}\"}".to_string()];
+
+        assert_eq!(
+            panel.build_url_with_params(),
+            "http://localhost/users?token=abc"
+        );
+        assert_eq!(
+            panel.enabled_headers(),
+            vec![
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ]
+        );
+        assert_eq!(panel.unresolved_vars(), vec!["MISSING".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_request_cmd() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "test", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "test", &conn).unwrap();
+
+        panel.apply_request_cmd(HttpRequestCmd {
+            method: HttpMethod::Post,
+            url: "https://example.com/search?q=rust&page=2&flag".to_string(),
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            body: "{\n  \"a\": 1\n}".to_string(),
+            body_file: None,
+            timeout_ms: Some(1500),
+        });
+
+        assert!(panel.dirty);
+        assert_eq!(panel.method, HttpMethod::Post);
+        assert_eq!(panel.url, "https://example.com/search");
+        assert_eq!(panel.query_params.len(), 3);
+        assert_eq!(panel.query_params[1].key, "page");
+        assert_eq!(panel.query_params[1].value, "2");
+        assert_eq!(panel.query_params[2].key, "flag");
+        assert_eq!(panel.query_params[2].value, "");
+        assert_eq!(panel.headers[0].key, "Accept");
+        assert_eq!(panel.body_lines.len(), 3);
+        assert_eq!(panel.timeout_ms, Some(1500));
+        assert_eq!(
+            panel.build_url_with_params(),
+            "https://example.com/search?q=rust&page=2&flag="
+        );
+
+        // Saving persists the imported request
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "test", &conn).unwrap();
+        assert_eq!(reloaded.url, "https://example.com/search");
+        assert_eq!(reloaded.query_params.len(), 3);
+        assert_eq!(reloaded.timeout_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_form_body() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "login", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "login", &conn).unwrap();
+        panel.set_environment(None, vec![("USER".to_string(), "ada l".to_string())]);
+
+        panel.focused_section = Section::Body;
+        assert!(!panel.in_kv_section());
+        panel.cycle_body_mode();
+        assert!(panel.in_kv_section());
+        assert!(panel.dirty);
+
+        for (key, value) in [
+            ("user", "${USER}"),
+            ("", "skipped"),
+            ("next", "/a?b=c&d"),
+            ("debug", "1"),
+        ] {
+            panel.kv_add_row();
+            let row = &mut panel.form_fields[panel.form_selected];
+            row.key = key.to_string();
+            row.value = value.to_string();
+        }
+        // Disable the last row, like `x`
+        panel.kv_toggle_enabled();
+
+        assert_eq!(panel.request_body(), "user=ada+l&next=%2Fa%3Fb%3Dc%26d");
+        assert!(panel.enabled_headers().contains(&(
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string()
+        )));
+
+        // A user-provided content type wins
+        panel.headers.push(KvRow {
+            key: "content-type".to_string(),
+            value: "text/plain".to_string(),
+            ..KvRow::new_empty()
+        });
+        let headers = panel.enabled_headers();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].1, "text/plain");
+
+        // Mode and fields survive a save/load roundtrip
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "login", &conn).unwrap();
+        assert_eq!(reloaded.body_mode, BodyMode::FormUrlEncoded);
+        assert_eq!(reloaded.form_fields.len(), 4);
+        assert!(!reloaded.form_fields[3].enabled);
+    }
+
+    #[test]
+    fn test_graphql_body() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "gql", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "gql", &conn).unwrap();
+        panel.set_environment(None, vec![("ID".to_string(), "7".to_string())]);
+
+        panel.focused_section = Section::Body;
+        panel.cycle_body_mode();
+        panel.cycle_body_mode();
+        assert!(panel.is_graphql_body());
+        assert_eq!(panel.effective_method(), HttpMethod::Post);
+
+        panel.body_insert_text("query { user(id: $id) { name } }");
+        panel.toggle_graphql_pane();
+        assert_eq!(panel.graphql_pane, GraphqlPane::Variables);
+        assert_eq!(panel.body_text(), "");
+        panel.body_insert_text("{\"id\": ${ID}}");
+
+        let payload: serde_json::Value = serde_json::from_str(&panel.request_body()).unwrap();
+        assert_eq!(payload["query"], "query { user(id: $id) { name } }");
+        assert_eq!(payload["variables"]["id"], 7);
+        assert!(
+            panel
+                .enabled_headers()
+                .contains(&("Content-Type".to_string(), "application/json".to_string()))
+        );
+
+        // Both panes persist, whichever one is active
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "gql", &conn).unwrap();
+        assert_eq!(reloaded.graphql_pane, GraphqlPane::Query);
+        assert_eq!(reloaded.body_text(), "query { user(id: $id) { name } }");
+        assert_eq!(reloaded.graphql_variables_lines(), ["{\"id\": ${ID}}"]);
+
+        // Invalid variables are reported
+        panel.body_insert_char(',');
+        assert!(panel.graphql_variables().is_err());
+
+        // Leaving GraphQL mode restores the query as the body text
+        panel.cycle_body_mode();
+        assert_eq!(panel.graphql_pane, GraphqlPane::Query);
+        assert_eq!(panel.body_text(), "query { user(id: $id) { name } }");
+    }
+
+    #[test]
+    fn test_file_body() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "upload", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "upload", &conn).unwrap();
+        let dir = std::env::temp_dir().join(format!("rstools-body-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("logo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        panel.set_environment(None, vec![("DIR".to_string(), dir.display().to_string())]);
+
+        panel.focused_section = Section::Body;
+        for _ in 0..3 {
+            panel.cycle_body_mode();
+        }
+        assert_eq!(panel.body_mode, BodyMode::File);
+        assert_eq!(
+            panel.body_file_size(),
+            Some(Err("No body file set".to_string()))
+        );
+
+        panel.body_insert_text("${DIR}/logo.png");
+        assert_eq!(panel.body_file(), Some(dir.join("logo.png")));
+        assert_eq!(panel.body_file_size(), Some(Ok(4)));
+        assert_eq!(panel.cached_body_file_size(), Some(Ok(4)));
+        assert_eq!(panel.request_body(), "");
+        assert!(
+            panel
+                .enabled_headers()
+                .contains(&("Content-Type".to_string(), "image/png".to_string()))
+        );
+        assert!(panel.to_curl(false).ends_with(&format!(
+            "--data-binary '@{}'",
+            dir.join("logo.png").display()
+        )));
+
+        // An explicit Content-Type wins over the guess
+        panel.headers = vec![KvRow {
+            key: "content-type".to_string(),
+            value: "application/octet-stream".to_string(),
+            ..KvRow::new_empty()
+        }];
+        let content_types: Vec<_> = panel
+            .enabled_headers()
+            .into_iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .collect();
+        assert_eq!(content_types.len(), 1);
+
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "upload", &conn).unwrap();
+        assert_eq!(reloaded.body_mode, BodyMode::File);
+        assert_eq!(reloaded.body_text(), "${DIR}/logo.png");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(panel.body_file_size().unwrap().is_err());
+        // Rendering reuses the last check for a moment
+        assert_eq!(panel.cached_body_file_size(), Some(Ok(4)));
+    }
+
+    #[test]
+    fn test_default_headers_merge() {
+        let (mut panel, conn) = setup();
+        let entry_id = model::add_entry(&conn, None, "q", model::EntryType::Query).unwrap();
+        panel.load(entry_id, "q", &conn).unwrap();
+        panel.set_default_headers(vec![
+            ("Accept".to_string(), "application/json".to_string()),
+            ("X-Trace".to_string(), "${TRACE}".to_string()),
+        ]);
+        panel.set_environment(None, vec![("TRACE".to_string(), "t-1".to_string())]);
+
+        assert_eq!(
+            panel.enabled_headers(),
+            vec![
+                ("Accept".to_string(), "application/json".to_string()),
+                ("X-Trace".to_string(), "t-1".to_string()),
+            ]
+        );
+
+        // A per-request header with the same name (any case) overrides the default
+        panel.headers.push(KvRow {
+            key: "accept".to_string(),
+            value: "text/html".to_string(),
+            ..KvRow::new_empty()
+        });
+        assert_eq!(
+            panel.applied_default_headers(),
+            vec![("X-Trace".to_string(), "${TRACE}".to_string())]
+        );
+        assert_eq!(
+            panel.enabled_headers(),
+            vec![
+                ("X-Trace".to_string(), "t-1".to_string()),
+                ("accept".to_string(), "text/html".to_string()),
+            ]
+        );
+
+        // ...unless it is disabled
+        panel.headers[0].enabled = false;
+        assert_eq!(panel.applied_default_headers().len(), 2);
+
+        // Opting out is persisted per query
+        panel.toggle_default_headers();
+        assert!(panel.enabled_headers().is_empty());
+        panel.save(&conn).unwrap();
+        let mut reloaded = RequestPanel::new();
+        reloaded.load(entry_id, "q", &conn).unwrap();
+        assert!(!reloaded.use_default_headers);
+    }
+
+    #[test]
+    fn test_folder_values_merge() {
+        let (mut panel, conn) = setup();
+        let folder = model::add_entry(&conn, None, "api", model::EntryType::Folder).unwrap();
+        let entry_id = model::add_entry(&conn, Some(folder), "q", model::EntryType::Query).unwrap();
+        let row = |k: &str, v: &str| (k.to_string(), v.to_string(), true);
+        model::replace_folder_headers(
+            &conn,
+            folder,
+            &[row("Accept", "application/json"), row("X-Team", "${TEAM}")],
+        )
+        .unwrap();
+        model::replace_folder_vars(&conn, folder, &[row("TEAM", "core")]).unwrap();
+        panel.load(entry_id, "q", &conn).unwrap();
+        panel.set_default_headers(vec![
+            ("accept".to_string(), "*/*".to_string()),
+            ("X-Client".to_string(), "rstools".to_string()),
+        ]);
+        panel.set_environment(
+            Some("dev".to_string()),
+            vec![
+                ("TEAM".to_string(), "env".to_string()),
+                ("HOST".to_string(), "localhost".to_string()),
+            ],
+        );
+        panel.url = "https://${HOST}/${TEAM}".to_string();
+
+        // Folder headers override defaults; folder variables shadow the environment
+        assert_eq!(
+            panel.enabled_headers(),
+            vec![
+                ("X-Client".to_string(), "rstools".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+                ("X-Team".to_string(), "core".to_string()),
+            ]
+        );
+        assert_eq!(panel.build_url_with_params(), "https://localhost/core");
+        assert!(panel.unresolved_vars().is_empty());
+
+        // The query's own header overrides the folder's
+        panel.headers.push(KvRow {
+            key: "X-TEAM".to_string(),
+            value: "${MISSING}".to_string(),
+            ..KvRow::new_empty()
+        });
+        assert_eq!(panel.applied_folder_headers().len(), 1);
+        assert_eq!(panel.enabled_headers()[2].0, "X-TEAM");
+        assert_eq!(panel.unresolved_vars(), vec!["MISSING".to_string()]);
+    }
+
+    #[test]
+    fn test_to_curl() {
+        let mut panel = RequestPanel::new();
+        panel.url = "https://${HOST}/users".to_string();
+        panel.set_environment(
+            Some("dev".to_string()),
+            vec![("HOST".to_string(), "localhost:8080".to_string())],
+        );
+        assert_eq!(panel.to_curl(false), "curl 'https://localhost:8080/users'");
+
+        panel.method = HttpMethod::Post;
+        panel.query_params.push(KvRow {
+            key: "q".to_string(),
+            value: "a b".to_string(),
+            ..KvRow::new_empty()
+        });
+        panel.headers.push(KvRow {
+            key: "Accept".to_string(),
+            value: "application/json".to_string(),
+            ..KvRow::new_empty()
+        });
+        panel.headers.push(KvRow {
+            key: "X-Debug".to_string(),
+            value: "1".to_string(),
+            enabled: false,
+            ..KvRow::new_empty()
+        });
+        panel.body_lines = vec!["{\"name\": \"O'Brien\"}".to_string()];
+
+        let curl = panel.to_curl(false);
+        assert_eq!(
+            curl,
+            "curl -X POST 'https://localhost:8080/users?q=a b' -H 'Accept: application/json' \
+             -H 'Content-Type: application/json' --data-raw '{\"name\": \"O'\\''Brien\"}'"
+        );
+
+        // The output parses back into the same request
+        let parsed = crate::executor::parse_curl(&curl).unwrap().request;
+        assert_eq!(parsed.method, HttpMethod::Post);
+        assert_eq!(parsed.url, "https://localhost:8080/users?q=a b");
+        assert_eq!(parsed.headers.len(), 2);
+        assert_eq!(parsed.body, "{\"name\": \"O'Brien\"}");
+
+        panel.method = HttpMethod::Get;
+        panel.query_params.clear();
+        panel.headers.clear();
+        panel.body_lines = vec![String::new()];
+        panel.timeout_ms = Some(2500);
+        assert_eq!(
+            panel.to_curl(false),
+            "curl -m 2.5 'https://localhost:8080/users'"
+        );
+
+        // Session settings: skipped certificate checks and the active proxy
+        panel.timeout_ms = None;
+        panel.proxy = ProxyConfig {
+            https: Some("http://proxy:3128".to_string()),
+            ..ProxyConfig::default()
+        };
+        let curl = panel.to_curl(true);
+        assert_eq!(
+            curl,
+            "curl -k -x 'http://proxy:3128' 'https://localhost:8080/users'"
+        );
+        let parsed = crate::executor::parse_curl(&curl).unwrap().request;
+        assert_eq!(parsed.url, "https://localhost:8080/users");
+    }
+
+    #[test]
+    fn test_body_method_warning_and_content_type() {
+        let mut panel = RequestPanel::new();
+        panel.url = "http://localhost".to_string();
+        assert_eq!(panel.body_method_warning(), None);
+
+        panel.body_lines = vec!["{\"a\": 1}".to_string()];
+        assert_eq!(
+            panel.body_method_warning().as_deref(),
+            Some("GET with body may be ignored")
+        );
+        assert!(
+            panel
+                .enabled_headers()
+                .contains(&("Content-Type".to_string(), "application/json".to_string()))
+        );
+
+        // Other text is left unlabeled; explicit headers win
+        panel.body_lines = vec!["hello".to_string()];
+        assert_eq!(panel.implied_content_type(), None);
+        assert!(panel.enabled_headers().is_empty());
+        panel.body_lines = vec!["{}".to_string()];
+        panel.headers.push(KvRow {
+            key: "content-type".to_string(),
+            value: "application/xml".to_string(),
+            ..KvRow::new_empty()
+        });
+        assert_eq!(panel.implied_content_type(), None);
+        assert_eq!(panel.enabled_headers().len(), 1);
+
+        // A disabled row does not count as set
+        panel.headers[0].enabled = false;
+        assert_eq!(panel.implied_content_type(), Some("application/json"));
+
+        // Cycling the method drops the warning but keeps other warnings
+        panel.error_message = Some(format!(
+            "Unresolved variables: ${{X}}{WARNING_SEPARATOR}GET with body may be ignored"
+        ));
+        panel.cycle_method_forward();
+        assert_eq!(panel.body_method_warning(), None);
+        assert_eq!(
+            panel.error_message.as_deref(),
+            Some("Unresolved variables: ${X}")
+        );
+        panel.error_message = Some("GET with body may be ignored".to_string());
+        panel.cycle_method_backward();
+        assert_eq!(panel.error_message, None);
+
+        // A file body only counts once a path is entered
+        panel.body_mode = BodyMode::File;
+        panel.body_lines = vec![String::new()];
+        assert_eq!(panel.body_method_warning(), None);
+        panel.body_lines = vec!["payload.json".to_string()];
+        assert_eq!(
+            panel.body_method_warning().as_deref(),
+            Some("GET with body may be ignored")
+        );
+    }
+
+    #[test]
+    fn test_validate_body_json() {
+        let mut panel = RequestPanel::new();
+        panel.body_lines = vec!["{\"a\": ".to_string()];
+
+        // Unlabeled text is never flagged
+        panel.validate_body_json();
+        assert!(panel.body_json_valid);
+
+        panel.headers.push(KvRow {
+            key: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+            ..KvRow::new_empty()
+        });
+        panel.validate_body_json();
+        assert!(!panel.body_json_valid);
+
+        panel.body_lines = vec!["{\"a\": 1}".to_string()];
+        panel.validate_body_json();
+        assert!(panel.body_json_valid);
+
+        // Form bodies are not checked
+        panel.body_lines = vec!["nope".to_string()];
+        panel.cycle_body_mode();
+        assert!(panel.body_json_valid);
+    }
+
     #[test]
     fn test_clear_panel() {
         let mut panel = RequestPanel::new();
@@ -989,6 +2573,100 @@ mod tests {
         assert!(panel.url.is_empty());
     }
 
+    #[test]
+    fn test_response_wrap_scrolling() {
+        assert!(wrap_breaks("short", 10).is_empty());
+        assert_eq!(wrap_breaks("abcdefghij", 4), vec![4, 8]);
+        // A wide character that does not fit moves to the next row
+        assert_eq!(wrap_breaks("abc日本語", 4), vec![3, 9]);
+
+        let mut panel = RequestPanel::new();
+        let body = format!("{}\nshort\n{}", "x".repeat(25), "y".repeat(10));
+        panel.response = Some(ResponseData {
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 1,
+            size_bytes: body.len(),
+            headers: Vec::new(),
+            raw_body: Some(body.as_bytes().to_vec()),
+            body,
+            language: BodyLanguage::Text,
+            pretty: true,
+            body_scroll: 0,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(10),
+        });
+
+        let resp = panel.response.as_mut().unwrap();
+        assert_eq!(resp.body_line_count(false), 3);
+        assert_eq!(resp.headers_text(), "");
+        resp.headers = vec![
+            ("content-type".to_string(), "text/plain".to_string()),
+            ("x-id".to_string(), "7".to_string()),
+        ];
+        assert_eq!(resp.headers_text(), "content-type: text/plain\nx-id: 7");
+        assert_eq!(resp.body_line_count(true), 5);
+        resp.scroll_body_down(100, true);
+        assert_eq!(resp.body_scroll, 4);
+        assert_eq!(resp.body_row_to_line(4, true), (2, 0));
+        assert_eq!(resp.body_row_to_line(2, true), (0, 2));
+
+        // Toggling keeps the top visible line: row 3 is "short"
+        panel.wrap_response = true;
+        panel.response.as_mut().unwrap().body_scroll = 3;
+        panel.toggle_response_wrap();
+        assert_eq!(panel.response.as_ref().unwrap().body_scroll, 1);
+        panel.toggle_response_wrap();
+        assert_eq!(panel.response.as_ref().unwrap().body_scroll, 3);
+
+        // The toggle survives switching queries
+        panel.clear();
+        assert!(panel.wrap_response);
+    }
+
+    #[test]
+    fn test_response_pretty_toggle() {
+        let raw = br#"{"id":1,"tags":["a","b"]}"#.to_vec();
+        let (language, body) = body_format::format_response(Some("application/json"), &raw);
+        let mut resp = ResponseData {
+            status_code: 200,
+            status_text: "OK".to_string(),
+            elapsed_ms: 1,
+            size_bytes: raw.len(),
+            headers: Vec::new(),
+            body,
+            raw_body: Some(raw),
+            language,
+            pretty: true,
+            body_scroll: 0,
+            headers_scroll: 0,
+            focused_section: ResponseSection::Body,
+            body_width: Cell::new(80),
+        };
+        assert_eq!(resp.body_line_count(false), 7);
+        resp.scroll_body_down(5, false);
+
+        assert!(resp.toggle_pretty());
+        assert_eq!(resp.shown_body(), r#"{"id":1,"tags":["a","b"]}"#);
+        assert_eq!(resp.body_line_count(false), 1);
+        assert_eq!(resp.body_scroll, 0);
+        resp.toggle_pretty();
+        assert!(resp.shown_body().starts_with("{\n  \"id\": 1,"));
+
+        // Bytes that are not text stay a hex dump
+        resp.raw_body = Some(vec![0xff, 0xfe]);
+        resp.body = body_format::hex_dump(&[0xff, 0xfe]);
+        resp.toggle_pretty();
+        assert_eq!(resp.shown_body(), resp.body);
+
+        // Without the raw bytes the formatted body stays
+        resp.raw_body = None;
+        resp.pretty = true;
+        assert!(!resp.toggle_pretty());
+        assert!(resp.pretty);
+    }
+
     #[test]
     fn test_kv_save_and_load_roundtrip() {
         let (mut panel, conn) = setup();
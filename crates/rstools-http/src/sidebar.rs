@@ -5,7 +5,7 @@ use crate::model::{self, EntryType, HttpEntry};
 use anyhow::Result;
 use rstools_core::tree_sidebar::TreeEntry;
 pub use rstools_core::tree_sidebar::{
-    ClipboardItem, ClipboardMode, FlatEntry, SidebarInput, TreeNode, TreeSidebar,
+    ClipboardItem, ClipboardMode, FlatEntry, LeafDecoration, SidebarInput, TreeNode, TreeSidebar,
     TreeSidebarRenderConfig, find_node, find_node_mut, find_parent_id, render_tree_sidebar,
 };
 use rusqlite::Connection;
@@ -27,6 +27,9 @@ impl TreeEntry for HttpEntry {
     fn is_expanded(&self) -> bool {
         self.expanded
     }
+    fn position(&self) -> i64 {
+        self.position
+    }
 }
 
 /// Type alias for the HTTP sidebar.
@@ -1,13 +1,21 @@
-use crate::model::HttpMethod;
-use crate::request_panel::{KvField, KvRow, PanelFocus, RequestPanel, ResponseSection, Section};
-use crate::sidebar::{SidebarState, TreeSidebarRenderConfig, render_tree_sidebar};
+use std::collections::HashMap;
+
+use crate::body_format::BodyLanguage;
+use crate::model::{BodyMode, HttpMethod, LastRun};
+use crate::request_panel::{
+    AuthField, GraphqlPane, KvField, KvRow, PanelFocus, RequestPanel, ResponseSection, Section,
+    wrap_breaks,
+};
+use crate::sidebar::{LeafDecoration, SidebarState, TreeSidebarRenderConfig, render_tree_sidebar};
+use crate::{InputPrompt, KvSetEditor, ResponseHistory};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
+use rstools_core::theme::theme;
 use unicode_width::UnicodeWidthStr;
 
 /// Fixed sidebar width in characters.
@@ -17,23 +25,33 @@ pub const SIDEBAR_WIDTH: u16 = 40;
 
 fn method_color(method: HttpMethod) -> Color {
     match method {
-        HttpMethod::Get => Color::Green,
-        HttpMethod::Post => Color::Yellow,
-        HttpMethod::Put => Color::Blue,
+        HttpMethod::Get => theme().success,
+        HttpMethod::Post => theme().accent,
+        HttpMethod::Put => theme().border,
         HttpMethod::Patch => Color::Rgb(255, 165, 0), // orange
-        HttpMethod::Delete => Color::Red,
-        HttpMethod::Head => Color::Cyan,
+        HttpMethod::Delete => theme().error,
+        HttpMethod::Head => theme().info,
         HttpMethod::Options => Color::Magenta,
     }
 }
 
+/// Sidebar dot color: green 2xx, yellow 3xx, red 4xx/5xx.
+fn last_run_color(code: u16) -> Color {
+    match code {
+        200..=299 => theme().success,
+        300..=399 => theme().accent,
+        400..=599 => theme().error,
+        _ => theme().muted,
+    }
+}
+
 fn status_color(code: u16) -> Color {
     match code {
-        200..=299 => Color::Green,
-        300..=399 => Color::Cyan,
-        400..=499 => Color::Yellow,
-        500..=599 => Color::Red,
-        _ => Color::White,
+        200..=299 => theme().success,
+        300..=399 => theme().info,
+        400..=499 => theme().accent,
+        500..=599 => theme().error,
+        _ => theme().text,
     }
 }
 
@@ -45,8 +63,8 @@ pub fn render_http_tool(
     area: Rect,
     sidebar: &SidebarState,
     panel: &RequestPanel,
+    last_runs: &HashMap<i64, LastRun>,
     sidebar_focused: bool,
-    notification: Option<&str>,
 ) {
     if sidebar.visible {
         let sidebar_width = SIDEBAR_WIDTH.min(area.width.saturating_sub(10));
@@ -63,28 +81,41 @@ pub fn render_http_tool(
             height: area.height,
         };
 
-        render_sidebar(frame, sidebar_area, sidebar, sidebar_focused);
+        render_sidebar(frame, sidebar_area, sidebar, last_runs, sidebar_focused);
         render_content_panel(frame, content_area, panel, !sidebar_focused);
     } else {
         render_content_panel(frame, area, panel, true);
     }
-
-    if let Some(message) = notification {
-        render_notification(frame, area, message);
-    }
 }
 
 // ── Sidebar ──────────────────────────────────────────────────────────
 
-const SELECTED_BG: Color = Color::Gray;
-
-fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused: bool) {
+/// The query tree; each query's dot is colored by the status of its last
+/// run, whose duration follows the name.
+fn render_sidebar(
+    frame: &mut Frame,
+    area: Rect,
+    sidebar: &SidebarState,
+    last_runs: &HashMap<i64, LastRun>,
+    focused: bool,
+) {
+    let decorate = |entry_id: i64| match last_runs.get(&entry_id) {
+        Some(run) => LeafDecoration {
+            icon_color: Some(last_run_color(run.status_code)),
+            suffix: Some(format!("{}ms", run.elapsed_ms)),
+        },
+        None => LeafDecoration {
+            icon_color: Some(theme().muted),
+            suffix: None,
+        },
+    };
     let config = TreeSidebarRenderConfig {
         title: " HTTP Explorer ",
         focused,
         leaf_icon: Some("\u{25CF} "),
-        leaf_style: Some(Style::default().fg(Color::White)),
-        folder_style: Some(Style::default().fg(Color::Blue)),
+        leaf_style: Some(Style::default().fg(theme().text)),
+        folder_style: Some(Style::default().fg(theme().border)),
+        leaf_decoration: Some(&decorate),
     };
     render_tree_sidebar(frame, area, sidebar, &config);
 }
@@ -134,14 +165,14 @@ fn render_content_panel(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
 fn render_empty_panel(frame: &mut Frame, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(theme().muted))
         .title(" Request ");
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     let text = Paragraph::new("Select a query to begin (Enter on a query in the sidebar)")
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme().muted))
         .alignment(ratatui::layout::Alignment::Center);
 
     if inner.height > 1 {
@@ -159,16 +190,41 @@ fn render_empty_panel(frame: &mut Frame, area: Rect) {
 // ── Request area ─────────────────────────────────────────────────────
 
 fn render_request_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
-    let title = if panel.dirty {
+    let name = if panel.dirty {
         format!(" {} [+] ", panel.active_entry_name)
     } else {
         format!(" {} ", panel.active_entry_name)
     };
+    let mut title_spans = vec![Span::raw(name)];
+    if let Some(ref env) = panel.active_env {
+        title_spans.push(Span::styled(
+            format!("[env: {}] ", env),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    if let Some(ms) = panel.timeout_ms {
+        title_spans.push(Span::styled(
+            format!("[timeout: {} ms] ", ms),
+            Style::default().fg(theme().muted),
+        ));
+    }
+    if let Some(proxy) = panel.active_proxy() {
+        title_spans.push(Span::styled(
+            format!("[proxy: {}] ", proxy),
+            Style::default().fg(theme().accent),
+        ));
+    } else if panel.proxy.is_enabled() {
+        title_spans.push(Span::styled(
+            "[proxy bypassed] ",
+            Style::default().fg(theme().muted),
+        ));
+    }
+    let title = Line::from(title_spans);
 
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -215,14 +271,19 @@ fn render_request_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, focu
                 );
             }
             Section::Headers => {
-                render_kv_section(
-                    frame,
-                    content_area,
-                    &panel.headers,
-                    panel.headers_selected,
-                    panel,
-                    focused && panel.focused_section == Section::Headers,
-                );
+                render_headers_section(frame, content_area, panel, focused);
+            }
+            Section::Auth => {
+                render_auth_section(frame, content_area, panel, focused);
+            }
+            Section::Body if panel.is_form_body() => {
+                render_form_body(frame, content_area, panel, focused);
+            }
+            Section::Body if panel.is_graphql_body() => {
+                render_graphql_body(frame, content_area, panel, focused);
+            }
+            Section::Body if panel.body_mode == BodyMode::File => {
+                render_file_body(frame, content_area, panel, focused);
             }
             Section::Body => {
                 render_body_editor(frame, content_area, panel, focused);
@@ -232,7 +293,7 @@ fn render_request_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, focu
 }
 
 fn render_method_url_bar(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
-    let method = panel.method;
+    let method = panel.effective_method();
     let color = method_color(method);
 
     let method_style = Style::default()
@@ -241,9 +302,9 @@ fn render_method_url_bar(frame: &mut Frame, area: Rect, panel: &RequestPanel, fo
         .add_modifier(Modifier::BOLD);
 
     let url_style = if focused && panel.focused_section == Section::Url {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme().text)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     let method_text = format!(" {} ", method.as_str());
@@ -254,7 +315,7 @@ fn render_method_url_bar(frame: &mut Frame, area: Rect, panel: &RequestPanel, fo
     };
 
     let url_fg = if panel.url.is_empty() && !(focused && panel.focused_section == Section::Url) {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     } else {
         url_style
     };
@@ -269,7 +330,7 @@ fn render_method_url_bar(frame: &mut Frame, area: Rect, panel: &RequestPanel, fo
 
     // Show cursor on URL when editing
     if focused && panel.focused_section == Section::Url && panel.editing {
-        let method_width = panel.method.as_str().len() + 3; // " METHOD " + " "
+        let method_width = method.as_str().len() + 3; // " METHOD " + " "
         let cursor_x = area.x + method_width as u16 + panel.url_cursor as u16;
         if cursor_x < area.x + area.width {
             frame.set_cursor_position((cursor_x, area.y));
@@ -278,29 +339,51 @@ fn render_method_url_bar(frame: &mut Frame, area: Rect, panel: &RequestPanel, fo
 }
 
 fn render_section_tabs(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
-    let sections = [Section::Params, Section::Headers, Section::Body];
+    let sections = [
+        Section::Params,
+        Section::Headers,
+        Section::Auth,
+        Section::Body,
+    ];
     let mut spans: Vec<Span> = Vec::new();
     spans.push(Span::raw(" "));
 
     for (i, section) in sections.iter().enumerate() {
         if i > 0 {
-            spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled(" │ ", Style::default().fg(theme().muted)));
         }
 
         let is_active = panel.focused_section == *section;
         let style = if is_active && focused {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
         } else if is_active {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::UNDERLINED)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         };
 
         spans.push(Span::styled(section.label(), style));
+        if *section == Section::Body {
+            let mode = match panel.body_mode {
+                BodyMode::Raw => None,
+                BodyMode::FormUrlEncoded => Some(" (form)"),
+                BodyMode::GraphQl => Some(" (graphql)"),
+                BodyMode::File => Some(" (file)"),
+            };
+            if let Some(mode) = mode {
+                spans.push(Span::styled(mode, Style::default().fg(theme().muted)));
+            }
+            if !panel.body_json_valid {
+                spans.push(Span::styled(
+                    " invalid JSON",
+                    Style::default().fg(theme().error),
+                ));
+            }
+        }
     }
 
     frame.render_widget(Paragraph::new(Line::from(spans)), area);
@@ -311,31 +394,31 @@ fn render_url_hints(frame: &mut Frame, area: Rect) {
         Span::styled(
             "i",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" edit URL  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" edit URL  ", Style::default().fg(theme().muted)),
         Span::styled(
             "m",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" cycle method  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" cycle method  ", Style::default().fg(theme().muted)),
         Span::styled(
             "Tab",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" next section  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" next section  ", Style::default().fg(theme().muted)),
         Span::styled(
             "Ctrl-Enter",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" send", Style::default().fg(Color::DarkGray)),
+        Span::styled(" send", Style::default().fg(theme().muted)),
     ])];
     let widget = Paragraph::new(hints);
     frame.render_widget(widget, area);
@@ -357,7 +440,7 @@ fn render_kv_section(
 
     if rows.is_empty() {
         let hint = Paragraph::new("  No entries. Press 'a' to add.")
-            .style(Style::default().fg(Color::DarkGray));
+            .style(Style::default().fg(theme().muted));
         frame.render_widget(hint, area);
         return;
     }
@@ -390,42 +473,42 @@ fn render_kv_section(
         // Toggle indicator
         let toggle = if row.enabled { "[x]" } else { "[ ]" };
         let toggle_style = if is_selected {
-            Style::default().fg(Color::Black).bg(SELECTED_BG)
+            Style::default().fg(Color::Black).bg(theme().selection_bg)
         } else if !row.enabled {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         } else {
-            Style::default().fg(Color::Green)
+            Style::default().fg(theme().success)
         };
 
         // Key
         let key_display = truncate_or_pad(&row.key, key_width as usize);
         let key_style = if is_editing && panel.editing_field == KvField::Key {
-            Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            Style::default().fg(theme().accent).bg(theme().muted)
         } else if is_selected {
-            Style::default().fg(Color::Black).bg(SELECTED_BG)
+            Style::default().fg(Color::Black).bg(theme().selection_bg)
         } else if !row.enabled {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         } else {
-            Style::default().fg(Color::Cyan)
+            Style::default().fg(theme().info)
         };
 
         // Separator
         let sep_style = if is_selected {
-            Style::default().fg(Color::Black).bg(SELECTED_BG)
+            Style::default().fg(Color::Black).bg(theme().selection_bg)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         };
 
         // Value
         let value_display = truncate_or_pad(&row.value, value_width as usize);
         let value_style = if is_editing && panel.editing_field == KvField::Value {
-            Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            Style::default().fg(theme().accent).bg(theme().muted)
         } else if is_selected {
-            Style::default().fg(Color::Black).bg(SELECTED_BG)
+            Style::default().fg(Color::Black).bg(theme().selection_bg)
         } else if !row.enabled {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme().text)
         };
 
         lines.push(Line::from(vec![
@@ -453,6 +536,156 @@ fn render_kv_section(
     frame.render_widget(Paragraph::new(lines), area);
 }
 
+/// Form-urlencoded body: a mode line followed by the form fields table.
+fn render_form_body(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    let mode_line = Line::from(vec![
+        Span::styled(
+            format!("  {}", panel.body_mode.label()),
+            Style::default().fg(theme().info),
+        ),
+        Span::styled("  (t: raw body)", Style::default().fg(theme().muted)),
+    ]);
+    frame.render_widget(Paragraph::new(mode_line), Rect { height: 1, ..area });
+
+    let fields_area = Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    };
+    render_kv_section(
+        frame,
+        fields_area,
+        &panel.form_fields,
+        panel.form_selected,
+        panel,
+        focused,
+    );
+}
+
+/// Request headers, followed by the merged global defaults, the headers
+/// inherited from parent folders and the implied `Content-Type`, greyed out.
+fn render_headers_section(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    let mut default_lines: Vec<Line> = if panel.use_default_headers {
+        panel
+            .applied_default_headers()
+            .into_iter()
+            .map(|(key, value)| {
+                Line::from(Span::styled(
+                    format!("    {key} = {value}  (default)"),
+                    Style::default().fg(theme().muted),
+                ))
+            })
+            .collect()
+    } else if !panel.default_headers.is_empty() {
+        vec![Line::from(Span::styled(
+            "    defaults disabled (D)",
+            Style::default().fg(theme().muted),
+        ))]
+    } else {
+        Vec::new()
+    };
+    default_lines.extend(panel.applied_folder_headers().into_iter().map(|h| {
+        Line::from(Span::styled(
+            format!("    {} = {}  ({})", h.key, h.value, h.folder),
+            Style::default().fg(theme().muted),
+        ))
+    }));
+    if let Some(content_type) = panel.implied_content_type() {
+        default_lines.push(Line::from(Span::styled(
+            format!("    Content-Type = {content_type}  (implied by body)"),
+            Style::default().fg(theme().muted),
+        )));
+    }
+
+    // Keep at least a few rows for the request's own headers
+    let max_defaults = area.height.saturating_sub(3) as usize;
+    default_lines.truncate(max_defaults);
+    let defaults_height = default_lines.len() as u16;
+
+    let [headers_area, defaults_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(defaults_height)]).areas(area);
+
+    render_kv_section(
+        frame,
+        headers_area,
+        &panel.headers,
+        panel.headers_selected,
+        panel,
+        focused,
+    );
+    if defaults_height > 0 {
+        frame.render_widget(Paragraph::new(default_lines), defaults_area);
+    }
+}
+
+// ── Auth section ─────────────────────────────────────────────────────
+
+fn render_auth_section(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    if area.height == 0 {
+        return;
+    }
+
+    let label_width: usize = 10;
+    let value_x = area.x + 2 + label_width as u16;
+    let value_width = area.width.saturating_sub(2 + label_width as u16) as usize;
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (i, field) in panel.auth_fields().into_iter().enumerate() {
+        let is_selected = focused && i == panel.auth_selected;
+        let is_editing = is_selected && panel.editing;
+
+        let label_style = if is_selected {
+            Style::default().fg(Color::Black).bg(theme().selection_bg)
+        } else {
+            Style::default().fg(theme().info)
+        };
+        let value_style = if is_editing {
+            Style::default().fg(theme().accent).bg(theme().muted)
+        } else {
+            Style::default().fg(theme().text)
+        };
+
+        let value = match panel.auth_value(field) {
+            None => format!("\u{25c2} {} \u{25b8}", panel.auth.auth_type.label()),
+            // Passwords are masked unless being edited
+            Some(v) if field == AuthField::Password && !is_editing => {
+                "\u{2022}".repeat(v.chars().count())
+            }
+            Some(v) => v.to_string(),
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}", truncate_or_pad(field.label(), label_width)),
+                label_style,
+            ),
+            Span::styled(truncate_or_pad(&value, value_width), value_style),
+        ]));
+
+        if is_editing {
+            let before = panel
+                .auth_value(field)
+                .map(|v| v[..panel.auth_cursor].width())
+                .unwrap_or(0);
+            let cursor_x = value_x + before as u16;
+            if cursor_x < area.x + area.width && (i as u16) < area.height {
+                frame.set_cursor_position((cursor_x, area.y + i as u16));
+            }
+        }
+    }
+
+    // Show which header the settings produce
+    if let Some((name, _)) = panel.auth.header() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  Adds header: {}", name),
+            Style::default().fg(theme().muted),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
 fn truncate_or_pad(s: &str, width: usize) -> String {
     let w = s.width();
     if w >= width {
@@ -480,9 +713,110 @@ fn truncate_or_pad(s: &str, width: usize) -> String {
 // ── Body editor ──────────────────────────────────────────────────────
 
 fn render_body_editor(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    render_line_editor(
+        frame,
+        area,
+        &panel.body_lines,
+        (panel.body_cursor_row, panel.body_cursor_col),
+        focused,
+        focused && panel.editing && panel.focused_section == Section::Body,
+    );
+}
+
+/// File body: the resolved path and its size (or why it cannot be read) on
+/// top, the path editor below.
+fn render_file_body(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    if area.height == 0 {
+        return;
+    }
+    let status = match (panel.body_file(), panel.cached_body_file_size()) {
+        (Some(path), Some(Ok(size))) => Span::styled(
+            format!("  {}  {}", path.display(), format_size(size as usize)),
+            Style::default().fg(theme().muted),
+        ),
+        (_, Some(Err(e))) => Span::styled(format!("  {e}"), Style::default().fg(theme().error)),
+        _ => Span::raw(""),
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(status)),
+        Rect { height: 1, ..area },
+    );
+    let editor_area = Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    };
+    render_body_editor(frame, editor_area, panel, focused);
+}
+
+/// GraphQL body: the Query editor on top, the Variables (JSON) editor below.
+/// Only the active pane is backed by `body_lines` and shows a cursor.
+fn render_graphql_body(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
+    let [query_area, variables_area] =
+        Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area);
+
+    for (pane, pane_area, lines) in [
+        (GraphqlPane::Query, query_area, panel.graphql_query_lines()),
+        (
+            GraphqlPane::Variables,
+            variables_area,
+            panel.graphql_variables_lines(),
+        ),
+    ] {
+        if pane_area.height == 0 {
+            continue;
+        }
+        let active = panel.graphql_pane == pane;
+        let (label, hint) = match pane {
+            GraphqlPane::Query => ("  Query", "  (v: variables, t: raw body)"),
+            GraphqlPane::Variables => ("  Variables (JSON)", "  (v: query)"),
+        };
+        let label_style = if active && focused {
+            Style::default()
+                .fg(theme().info)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().muted)
+        };
+        let mut spans = vec![Span::styled(label, label_style)];
+        if active {
+            spans.push(Span::styled(hint, Style::default().fg(theme().muted)));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)),
+            Rect {
+                height: 1,
+                ..pane_area
+            },
+        );
+
+        let editor_area = Rect {
+            y: pane_area.y + 1,
+            height: pane_area.height.saturating_sub(1),
+            ..pane_area
+        };
+        if active {
+            render_body_editor(frame, editor_area, panel, focused);
+        } else {
+            render_line_editor(frame, editor_area, lines, (0, 0), false, false);
+        }
+    }
+}
+
+/// Render lines with a line-number gutter, scrolled to keep the cursor row
+/// visible; `show_cursor` places the terminal cursor.
+fn render_line_editor(
+    frame: &mut Frame,
+    area: Rect,
+    lines: &[String],
+    cursor: (usize, usize),
+    focused: bool,
+    show_cursor: bool,
+) {
     if area.height == 0 {
         return;
     }
+    let (cursor_row, cursor_col) = cursor;
 
     let line_num_width: u16 = 4; // "123 "
     let text_area = Rect {
@@ -496,8 +830,8 @@ fn render_body_editor(frame: &mut Frame, area: Rect, panel: &RequestPanel, focus
     };
 
     let visible_lines = area.height as usize;
-    let scroll_offset = if panel.body_cursor_row >= visible_lines {
-        panel.body_cursor_row - visible_lines + 1
+    let scroll_offset = if cursor_row >= visible_lines {
+        cursor_row - visible_lines + 1
     } else {
         0
     };
@@ -506,21 +840,25 @@ fn render_body_editor(frame: &mut Frame, area: Rect, panel: &RequestPanel, focus
     let mut num_lines: Vec<Line> = Vec::new();
     let mut text_lines: Vec<Line> = Vec::new();
 
-    for i in scroll_offset..panel.body_lines.len().min(scroll_offset + visible_lines) {
-        let is_current = i == panel.body_cursor_row && focused;
+    for (i, line_text) in lines
+        .iter()
+        .enumerate()
+        .skip(scroll_offset)
+        .take(visible_lines)
+    {
+        let is_current = i == cursor_row && focused;
         let num_style = if is_current {
-            Style::default().fg(Color::Yellow)
+            Style::default().fg(theme().accent)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme().muted)
         };
         num_lines.push(Line::from(Span::styled(
             format!("{:>3} ", i + 1),
             num_style,
         )));
 
-        let line_text = &panel.body_lines[i];
         let text_style = if is_current {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme().text)
         } else {
             Style::default().fg(Color::Gray)
         };
@@ -531,9 +869,9 @@ fn render_body_editor(frame: &mut Frame, area: Rect, panel: &RequestPanel, focus
     frame.render_widget(Paragraph::new(text_lines), text_area);
 
     // Show cursor when editing body
-    if focused && panel.editing && panel.focused_section == Section::Body {
-        let visible_row = panel.body_cursor_row.saturating_sub(scroll_offset);
-        let cursor_x = text_area.x + panel.body_cursor_col as u16;
+    if show_cursor {
+        let visible_row = cursor_row.saturating_sub(scroll_offset);
+        let cursor_x = text_area.x + cursor_col as u16;
         let cursor_y = text_area.y + visible_row as u16;
         if cursor_x < text_area.x + text_area.width && cursor_y < text_area.y + text_area.height {
             frame.set_cursor_position((cursor_x, cursor_y));
@@ -545,9 +883,9 @@ fn render_body_editor(frame: &mut Frame, area: Rect, panel: &RequestPanel, focus
 
 fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, focused: bool) {
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -564,9 +902,22 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
     // Loading state
     if panel.request_in_flight {
         let spinner = panel.spinner_char();
-        let text = format!("{} Sending request...", spinner);
+        let text = format!("{} Sending request... (Ctrl-c to cancel)", spinner);
         let widget = Paragraph::new(text)
-            .style(Style::default().fg(Color::Yellow))
+            .style(Style::default().fg(theme().accent))
+            .alignment(ratatui::layout::Alignment::Center);
+        let centered = Rect {
+            y: inner.y + inner.height / 2,
+            height: 1,
+            ..inner
+        };
+        frame.render_widget(widget, centered);
+        return;
+    }
+
+    if panel.cancelled {
+        let widget = Paragraph::new("Cancelled")
+            .style(Style::default().fg(theme().muted))
             .alignment(ratatui::layout::Alignment::Center);
         let centered = Rect {
             y: inner.y + inner.height / 2,
@@ -577,15 +928,20 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
         return;
     }
 
-    // Error state
-    if let Some(ref error) = panel.error_message {
+    // Error state (a response with a warning is rendered below instead)
+    if let (Some(error), None) = (&panel.error_message, &panel.response) {
         let lines = vec![
             Line::from(Span::styled(
                 "Error",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(theme().error)
+                    .add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
-            Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+            Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(theme().error),
+            )),
         ];
         let widget = Paragraph::new(lines);
         frame.render_widget(widget, inner);
@@ -597,7 +953,7 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
         Some(r) => r,
         None => {
             let hint = Paragraph::new("Press Ctrl-Enter or <Space>s to send request")
-                .style(Style::default().fg(Color::DarkGray))
+                .style(Style::default().fg(theme().muted))
                 .alignment(ratatui::layout::Alignment::Center);
             if inner.height > 1 {
                 let centered = Rect {
@@ -641,10 +997,30 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw("  "),
-        Span::styled(time_text, Style::default().fg(Color::DarkGray)),
+        Span::styled(time_text, Style::default().fg(theme().muted)),
         Span::raw("  "),
-        Span::styled(size_text, Style::default().fg(Color::DarkGray)),
+        Span::styled(size_text, Style::default().fg(theme().muted)),
     ]);
+    let mut status_line = status_line;
+    if response.language == BodyLanguage::Binary {
+        status_line.spans.push(Span::raw("  "));
+        status_line.spans.push(Span::styled(
+            "binary (hex)",
+            Style::default().fg(theme().muted),
+        ));
+    } else if !response.pretty {
+        status_line.spans.push(Span::raw("  "));
+        status_line
+            .spans
+            .push(Span::styled("raw", Style::default().fg(theme().muted)));
+    }
+    if let Some(ref warning) = panel.error_message {
+        status_line.spans.push(Span::raw("  "));
+        status_line.spans.push(Span::styled(
+            format!("⚠ {}", warning),
+            Style::default().fg(theme().accent),
+        ));
+    }
     frame.render_widget(Paragraph::new(status_line), status_area);
 
     // Response tabs
@@ -654,33 +1030,36 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
     let body_style = if response.focused_section == ResponseSection::Body {
         if focused {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
         } else {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::UNDERLINED)
         }
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     let headers_style = if response.focused_section == ResponseSection::Headers {
         if focused {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
         } else {
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::UNDERLINED)
         }
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().muted)
     };
 
     tab_spans.push(Span::styled("Body", body_style));
-    tab_spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+    if panel.wrap_response {
+        tab_spans.push(Span::styled(" (wrap)", Style::default().fg(theme().muted)));
+    }
+    tab_spans.push(Span::styled(" │ ", Style::default().fg(theme().muted)));
     tab_spans.push(Span::styled(
         format!("Headers ({})", response.headers.len()),
         headers_style,
@@ -692,7 +1071,7 @@ fn render_response_area(frame: &mut Frame, area: Rect, panel: &RequestPanel, foc
     if content_area.height > 0 {
         match response.focused_section {
             ResponseSection::Body => {
-                render_response_body(frame, content_area, response);
+                render_response_body(frame, content_area, response, panel.wrap_response);
             }
             ResponseSection::Headers => {
                 render_response_headers(frame, content_area, response);
@@ -705,23 +1084,210 @@ fn render_response_body(
     frame: &mut Frame,
     area: Rect,
     response: &crate::request_panel::ResponseData,
+    wrap: bool,
 ) {
-    let lines: Vec<Line> = response
-        .body
+    response.body_width.set(area.width as usize);
+    let height = area.height as usize;
+
+    // Only the visible window is tokenized, one output line per body line,
+    // so `body_scroll` indexes the same rows whether highlighted or not.
+    // When wrapping, `body_scroll` counts display rows: start from the line
+    // holding that row and drop the rows of it scrolled past.
+    let (first_line, skip_rows) = response.body_row_to_line(response.body_scroll, wrap);
+    let visible: Vec<&str> = response
+        .shown_body()
         .lines()
-        .skip(response.body_scroll)
-        .take(area.height as usize)
-        .map(|l| {
-            Line::from(Span::styled(
-                l.to_string(),
-                Style::default().fg(Color::White),
-            ))
-        })
+        .skip(first_line)
+        .take(height)
         .collect();
 
+    let lines: Vec<Line> = match response.language {
+        BodyLanguage::Json => highlight_json(&visible.join("\n")),
+        BodyLanguage::Xml | BodyLanguage::Html => highlight_markup(&visible.join("\n")),
+        BodyLanguage::Text | BodyLanguage::Binary => visible
+            .iter()
+            .map(|l| {
+                Line::from(Span::styled(
+                    l.to_string(),
+                    Style::default().fg(theme().text),
+                ))
+            })
+            .collect(),
+    };
+
+    let lines: Vec<Line> = if wrap && area.width > 0 {
+        lines
+            .into_iter()
+            .flat_map(|l| wrap_line(l, area.width as usize))
+            .skip(skip_rows)
+            .take(height)
+            .collect()
+    } else {
+        lines
+    };
+
     frame.render_widget(Paragraph::new(lines), area);
 }
 
+/// Split a styled line into display rows of at most `width` columns,
+/// breaking at the same points as [`wrap_breaks`].
+fn wrap_line(line: Line<'static>, width: usize) -> Vec<Line<'static>> {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let breaks = wrap_breaks(&text, width);
+    if breaks.is_empty() {
+        return vec![line];
+    }
+
+    let mut rows: Vec<Vec<Span<'static>>> = vec![Vec::new()];
+    let mut next = breaks.iter().peekable();
+    let mut offset = 0;
+    for span in line.spans {
+        let content = span.content.as_ref();
+        let mut start = 0;
+        while let Some(&&b) = next.peek()
+            && b < offset + content.len()
+        {
+            let cut = b - offset;
+            if cut > start {
+                rows.last_mut()
+                    .unwrap()
+                    .push(Span::styled(content[start..cut].to_string(), span.style));
+            }
+            rows.push(Vec::new());
+            start = cut;
+            next.next();
+        }
+        if start < content.len() {
+            rows.last_mut()
+                .unwrap()
+                .push(Span::styled(content[start..].to_string(), span.style));
+        }
+        offset += content.len();
+    }
+    rows.into_iter().map(Line::from).collect()
+}
+
+/// Colorize pretty-printed JSON, producing exactly one `Line` per input line.
+///
+/// Each line is tokenized on its own; a line that does not tokenize as JSON
+/// (e.g. a body that failed to parse) is rendered as plain text.
+pub fn highlight_json(text: &str) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|l| {
+            highlight_json_line(l).unwrap_or_else(|| {
+                Line::from(Span::styled(
+                    l.to_string(),
+                    Style::default().fg(theme().text),
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Colorize XML/HTML tags, producing exactly one `Line` per input line.
+///
+/// Tags (including a tag left open at the end of a line) are colored; text
+/// between tags is left white.
+pub fn highlight_markup(text: &str) -> Vec<Line<'static>> {
+    let tag_style = Style::default().fg(theme().info);
+    let text_style = Style::default().fg(theme().text);
+
+    text.lines()
+        .map(|line| {
+            let mut spans = Vec::new();
+            let mut rest = line;
+            while !rest.is_empty() {
+                if rest.starts_with('<') {
+                    let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+                    spans.push(Span::styled(rest[..end].to_string(), tag_style));
+                    rest = &rest[end..];
+                } else {
+                    let end = rest.find('<').unwrap_or(rest.len());
+                    spans.push(Span::styled(rest[..end].to_string(), text_style));
+                    rest = &rest[end..];
+                }
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn highlight_json_line(line: &str) -> Option<Line<'static>> {
+    let key_style = Style::default().fg(theme().info);
+    let string_style = Style::default().fg(theme().success);
+    let number_style = Style::default().fg(theme().accent);
+    let literal_style = Style::default().fg(Color::Magenta);
+    let punct_style = Style::default().fg(theme().muted);
+
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => {
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+                spans.push(Span::raw(chars[start..i].iter().collect::<String>()));
+            }
+            '{' | '}' | '[' | ']' | ',' | ':' => {
+                i += 1;
+                spans.push(Span::styled(c.to_string(), punct_style));
+            }
+            '"' => {
+                i += 1;
+                let mut escaped = false;
+                loop {
+                    let ch = *chars.get(i)?;
+                    i += 1;
+                    if escaped {
+                        escaped = false;
+                    } else if ch == '\\' {
+                        escaped = true;
+                    } else if ch == '"' {
+                        break;
+                    }
+                }
+                let is_key = chars[i..]
+                    .iter()
+                    .find(|ch| !ch.is_whitespace())
+                    .is_some_and(|ch| *ch == ':');
+                let style = if is_key { key_style } else { string_style };
+                spans.push(Span::styled(
+                    chars[start..i].iter().collect::<String>(),
+                    style,
+                ));
+            }
+            '-' | '0'..='9' => {
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || matches!(chars[i], '.' | 'e' | 'E' | '+' | '-'))
+                {
+                    i += 1;
+                }
+                spans.push(Span::styled(
+                    chars[start..i].iter().collect::<String>(),
+                    number_style,
+                ));
+            }
+            _ => {
+                let word = ["true", "false", "null"].into_iter().find(|w| {
+                    chars[i..].len() >= w.len()
+                        && chars[i..i + w.len()].iter().copied().eq(w.chars())
+                })?;
+                i += word.len();
+                spans.push(Span::styled(word.to_string(), literal_style));
+            }
+        }
+    }
+
+    Some(Line::from(spans))
+}
+
 fn render_response_headers(
     frame: &mut Frame,
     area: Rect,
@@ -737,10 +1303,10 @@ fn render_response_headers(
                 Span::styled(
                     format!("{}: ", k),
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme().info)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(v.clone(), Style::default().fg(Color::White)),
+                Span::styled(v.clone(), Style::default().fg(theme().text)),
             ])
         })
         .collect();
@@ -758,22 +1324,314 @@ fn format_size(bytes: usize) -> String {
     }
 }
 
-fn render_notification(frame: &mut Frame, area: Rect, message: &str) {
-    let width = (message.len() as u16 + 4).min(area.width.saturating_sub(4));
-    let notification_area = Rect {
-        x: area.x + area.width.saturating_sub(width) - 1,
-        y: area.y + 1,
+// ── Input prompt (overlay) ───────────────────────────────────────────
+
+pub fn render_input_prompt(frame: &mut Frame, area: Rect, prompt: &InputPrompt) {
+    let buffer = prompt.buffer();
+    let (title, hint) = match prompt {
+        InputPrompt::CurlImport { .. } => (
+            " Import cURL ",
+            "Paste a cURL command \u{2022} Enter import \u{2022} Esc cancel",
+        ),
+        InputPrompt::JsonPath { .. } => (
+            " Copy JSON path ",
+            "Path like data.items.0.id \u{2022} Enter copy \u{2022} Esc cancel",
+        ),
+    };
+
+    let width = 80u16.min(area.width.saturating_sub(4));
+    let height = 4u16.min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
         width,
-        height: 1,
+        height,
     };
 
-    frame.render_widget(Clear, notification_area);
-    let paragraph = Paragraph::new(Line::from(Span::styled(
-        format!(" {} ", message),
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Green)
-            .add_modifier(Modifier::BOLD),
-    )));
-    frame.render_widget(paragraph, notification_area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().accent))
+        .title(title);
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    // Keep the end of long commands visible
+    let available = inner.width.saturating_sub(3) as usize;
+    let mut visible = buffer;
+    while visible.width() > available {
+        let mut chars = visible.chars();
+        chars.next();
+        visible = chars.as_str();
+    }
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(visible),
+        ]),
+        Line::from(Span::styled(
+            hint,
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+
+    if inner.height > 0 {
+        frame.set_cursor_position((inner.x + 2 + visible.width() as u16, inner.y));
+    }
+}
+
+// ── Response history (overlay) ───────────────────────────────────────
+
+pub fn render_history_overlay(frame: &mut Frame, area: Rect, history: &ResponseHistory) {
+    let popup_width = (area.width * 80 / 100)
+        .max(50)
+        .min(area.width.saturating_sub(4));
+    let popup_height = (area.height * 70 / 100)
+        .max(12)
+        .min(area.height.saturating_sub(4));
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let [list_area, preview_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .areas(popup_area);
+
+    let items: Vec<ListItem> = history
+        .records
+        .iter()
+        .map(|r| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", r.status_code),
+                    Style::default()
+                        .fg(status_color(r.status_code))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(r.created_at.clone()),
+                Span::styled(
+                    format!("  {}ms", r.elapsed_ms),
+                    Style::default().fg(theme().muted),
+                ),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    if !history.records.is_empty() {
+        list_state.select(Some(history.selected.min(history.records.len() - 1)));
+    }
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme().text))
+                .title(" Response History "),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    let (title, body) = match history.records.get(history.selected) {
+        Some(r) => (
+            format!(
+                " {} {} \u{2022} {} ",
+                r.status_code,
+                r.status_text,
+                format_size(r.size_bytes)
+            ),
+            r.body.as_str(),
+        ),
+        None => (" Preview ".to_string(), ""),
+    };
+    let preview_height = preview_area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = body
+        .lines()
+        .take(preview_height)
+        .map(|l| Line::from(l.to_string()))
+        .collect();
+    let preview = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(preview, preview_area);
+}
+
+// ── Key/value set overlay ────────────────────────────────────────────
+
+pub fn render_kv_set_overlay(frame: &mut Frame, area: Rect, editor: &KvSetEditor) {
+    let popup_width = (area.width * 60 / 100)
+        .max(50)
+        .min(area.width.saturating_sub(4));
+    let popup_height = (editor.rows.len() as u16 + 4)
+        .max(8)
+        .min(area.height.saturating_sub(4));
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let hint = if editor.editing.is_some() {
+        " Tab: key/value  ←/→: move  Enter/Esc: done "
+    } else {
+        " a: add  i: edit  x: toggle  d: delete  q: close "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().text))
+        .title(format!(" {} ", editor.target.title()))
+        .title_bottom(Line::from(hint).style(Style::default().fg(theme().muted)));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    if editor.rows.is_empty() {
+        let hint = Paragraph::new(format!("  {}", editor.target.empty_hint()))
+            .style(Style::default().fg(theme().muted));
+        frame.render_widget(hint, inner);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let scroll_offset = (editor.selected + 1).saturating_sub(visible);
+    let lines: Vec<Line> = editor
+        .rows
+        .iter()
+        .enumerate()
+        .skip(scroll_offset)
+        .take(visible)
+        .map(|(i, row)| {
+            let is_selected = i == editor.selected;
+            let field_style = |field: Option<KvField>, color: Color| {
+                if is_selected && field.is_some() && editor.editing == field {
+                    Style::default().fg(theme().accent).bg(theme().muted)
+                } else if is_selected {
+                    Style::default().fg(Color::Black).bg(theme().selection_bg)
+                } else if !row.enabled {
+                    Style::default().fg(theme().muted)
+                } else {
+                    Style::default().fg(color)
+                }
+            };
+            let toggle = if row.enabled { "[x] " } else { "[ ] " };
+            Line::from(vec![
+                Span::styled(toggle, field_style(None, theme().success)),
+                Span::styled(
+                    row.key.clone(),
+                    field_style(Some(KvField::Key), theme().info),
+                ),
+                Span::styled(" = ", field_style(None, theme().muted)),
+                Span::styled(
+                    row.value.clone(),
+                    field_style(Some(KvField::Value), theme().text),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+
+    // Place the cursor in the field being edited
+    if let Some(field) = editor.editing
+        && let Some(row) = editor.rows.get(editor.selected)
+    {
+        let toggle_width = 4;
+        let offset = match field {
+            KvField::Key => row.key[..row.cursor].width(),
+            KvField::Value => row.key.width() + 3 + row.value[..row.cursor].width(),
+        };
+        let cursor_x = inner.x + toggle_width + offset as u16;
+        let cursor_y = inner.y + (editor.selected - scroll_offset) as u16;
+        if cursor_x < inner.x + inner.width {
+            frame.set_cursor_position((cursor_x, cursor_y));
+        }
+    }
+
+    if let Some(prompt) = &editor.delete_prompt {
+        let prompt_area = Rect {
+            x: inner.x,
+            y: inner.y + inner.height.saturating_sub(1),
+            width: inner.width,
+            height: 1,
+        };
+        prompt.render(frame, prompt_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span_texts(line: &Line) -> Vec<(String, Option<Color>)> {
+        line.spans
+            .iter()
+            .filter(|s| !s.content.trim().is_empty())
+            .map(|s| (s.content.to_string(), s.style.fg))
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_json_tokens() {
+        let body =
+            "{\n  \"name\": \"a \\\"b\\\"\",\n  \"n\": -1.5e3,\n  \"ok\": true,\n  \"x\": null\n}";
+        let lines = highlight_json(body);
+        assert_eq!(lines.len(), 6);
+
+        assert_eq!(
+            span_texts(&lines[1]),
+            vec![
+                ("\"name\"".to_string(), Some(Color::Cyan)),
+                (":".to_string(), Some(Color::DarkGray)),
+                ("\"a \\\"b\\\"\"".to_string(), Some(Color::Green)),
+                (",".to_string(), Some(Color::DarkGray)),
+            ]
+        );
+        assert_eq!(
+            span_texts(&lines[2])[2],
+            ("-1.5e3".to_string(), Some(Color::Yellow))
+        );
+        assert_eq!(
+            span_texts(&lines[3])[2],
+            ("true".to_string(), Some(Color::Magenta))
+        );
+        assert_eq!(
+            span_texts(&lines[4])[2],
+            ("null".to_string(), Some(Color::Magenta))
+        );
+    }
+
+    #[test]
+    fn test_highlight_json_falls_back_per_line() {
+        let lines = highlight_json("{\n  not json here\n  \"unterminated\n}");
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            span_texts(&lines[1]),
+            vec![("  not json here".to_string(), Some(Color::White))]
+        );
+        assert_eq!(lines[2].spans.len(), 1);
+        assert_eq!(lines[2].spans[0].style.fg, Some(Color::White));
+    }
+
+    #[test]
+    fn test_highlight_markup() {
+        let lines = highlight_markup("<a href=\"x\">link</a>\n  <br/>");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            span_texts(&lines[0]),
+            vec![
+                ("<a href=\"x\">".to_string(), Some(Color::Cyan)),
+                ("link".to_string(), Some(Color::White)),
+                ("</a>".to_string(), Some(Color::Cyan)),
+            ]
+        );
+        assert_eq!(
+            span_texts(&lines[1]),
+            vec![("<br/>".to_string(), Some(Color::Cyan))]
+        );
+    }
 }
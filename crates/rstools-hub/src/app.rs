@@ -3,18 +3,39 @@ use crossterm::cursor::SetCursorStyle;
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
-use ratatui::{layout::Rect, Frame};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
 use rusqlite::Connection;
 
 use rstools_core::{
+    db,
     help_popup::{self, HelpPopup},
-    keybinds::{Action, InputMode, KeyState},
+    keybinds::{Action, InputMode, KeyAction, KeyState, keymap},
     telescope::{Telescope, TelescopeItem},
+    theme::theme,
+    toast::{Toast, ToastQueue},
     tool::Tool,
     ui,
-    which_key::{self, WhichKey},
+    which_key::{self, WHICH_KEY_DELAY, WhichKey},
 };
 
+/// `app_state` key holding the name of the last focused tool.
+const LAST_TOOL_KEY: &str = "last_tool";
+
+/// What a quit does once unsaved edits are dealt with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingQuit {
+    /// Close the active tool and go back to the dashboard.
+    CloseTool,
+    /// Quit rstools.
+    QuitApp,
+}
+
 /// The main application state.
 pub struct App {
     /// Registry of all available tools.
@@ -31,6 +52,10 @@ pub struct App {
     help_popup: HelpPopup,
     /// Telescope overlay state.
     telescope: Telescope,
+    /// Toasts raised by the tools, shown over the content area.
+    toasts: ToastQueue,
+    /// Open while a quit waits for the user to save, discard or cancel.
+    quit_prompt: Option<PendingQuit>,
     /// Command-line input buffer.
     command_input: String,
     /// Command-line cursor position.
@@ -40,11 +65,13 @@ pub struct App {
     /// Cached layout areas from the last render (for mouse hit-testing).
     last_tab_area: Rect,
     last_content_area: Rect,
+    /// Shared database, used for hub state such as the last active tool.
+    conn: Connection,
 }
 
 impl App {
     /// Create a new App with the given tools.
-    pub fn new(tools: Vec<Box<dyn Tool>>) -> Self {
+    pub fn new(tools: Vec<Box<dyn Tool>>, conn: Connection) -> Self {
         Self {
             tools,
             active_tool: None,
@@ -53,11 +80,14 @@ impl App {
             which_key: WhichKey::new(),
             help_popup: HelpPopup::new(),
             telescope: Telescope::new(),
+            toasts: ToastQueue::new(),
+            quit_prompt: None,
             command_input: String::new(),
             command_cursor: 0,
             key_state: KeyState::default(),
             last_tab_area: Rect::default(),
             last_content_area: Rect::default(),
+            conn,
         }
     }
 
@@ -70,27 +100,75 @@ impl App {
         }
     }
 
-    /// Initialize all tool databases.
-    pub fn init_db(&self, conn: &Connection) -> Result<()> {
+    /// Initialize all tool databases and the hub state table.
+    pub fn init_db(&self) -> Result<()> {
         for tool in &self.tools {
-            tool.init_db(conn)?;
+            tool.init_db(&self.conn)?;
+        }
+        db::init_app_state(&self.conn)
+    }
+
+    /// Focus the tool named `requested` (case-insensitive), or else the tool
+    /// that was active when rstools last quit.
+    pub fn restore_active_tool(&mut self, requested: Option<&str>) -> Result<()> {
+        let name = match requested {
+            Some(name) => name.to_string(),
+            None => match db::get_app_state(&self.conn, LAST_TOOL_KEY)? {
+                Some(name) => name,
+                None => return Ok(()),
+            },
+        };
+        match self
+            .tools
+            .iter()
+            .position(|t| t.name().eq_ignore_ascii_case(&name))
+        {
+            Some(idx) => self.switch_to_tool(idx),
+            None if requested.is_some() => {
+                let names: Vec<&str> = self.tools.iter().map(|t| t.name()).collect();
+                anyhow::bail!("Unknown tool '{name}' (available: {})", names.join(", "));
+            }
+            // The saved tool no longer exists: stay on the dashboard
+            None => {}
         }
         Ok(())
     }
 
-    /// Tick the active tool (called every ~50ms for async polling, animations, etc.).
+    /// Show a toast from outside the tools (config problems at startup).
+    pub fn notify(&mut self, toast: Toast) {
+        self.toasts.push(toast);
+    }
+
+    /// Tick the active tool (called every ~50ms for async polling, animations, etc.)
+    /// and collect the toasts of every tool.
     pub fn tick(&mut self) {
         if let Some(idx) = self.active_tool {
             self.tools[idx].tick();
         }
+        for tool in &mut self.tools {
+            for toast in tool.take_toasts() {
+                self.toasts.push(toast);
+            }
+        }
+        self.toasts.expire(std::time::Instant::now());
     }
 
     /// Handle a terminal event.
     pub fn handle_event(&mut self, event: Event) {
         if let Event::Key(key) = event {
-            // Ctrl-c always quits
+            // Ctrl-c quits, unless the active tool uses it to interrupt work
             if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
-                self.should_quit = true;
+                let interrupted = self
+                    .active_tool
+                    .is_some_and(|idx| self.tools[idx].interrupt());
+                if !interrupted {
+                    self.request_quit(PendingQuit::QuitApp);
+                }
+                return;
+            }
+
+            if self.quit_prompt.is_some() {
+                self.handle_quit_prompt_key(key);
                 return;
             }
 
@@ -101,7 +179,7 @@ impl App {
             }
 
             // Handle which-key if active
-            if self.which_key.visible {
+            if self.which_key.active {
                 self.handle_which_key_input(key);
                 return;
             }
@@ -137,15 +215,7 @@ impl App {
     fn process_action(&mut self, action: Action) {
         match action {
             Action::Quit => {
-                if self.active_tool.is_some() {
-                    // Close current tool, go back to dashboard
-                    if let Some(idx) = self.active_tool {
-                        self.tools[idx].on_blur();
-                    }
-                    self.active_tool = None;
-                } else {
-                    self.should_quit = true;
-                }
+                self.close_or_quit();
             }
             Action::LeaderKey => {
                 self.show_leader_menu();
@@ -194,7 +264,8 @@ impl App {
         }
     }
 
-    /// Show the leader key which-key menu.
+    /// Start a leader sequence; its which-key menu appears after
+    /// `WHICH_KEY_DELAY` unless the next key comes first.
     fn show_leader_menu(&mut self) {
         self.reset_all_key_state();
         let mut entries = which_key::hub_leader_entries();
@@ -205,7 +276,8 @@ impl App {
                 entries.insert(0, entry);
             }
         }
-        self.which_key.show("Leader", entries);
+        self.which_key
+            .show_after("Leader", entries, WHICH_KEY_DELAY);
     }
 
     /// Handle a key press after the leader key.
@@ -267,7 +339,17 @@ impl App {
                 self.which_key.hide();
                 self.reset_all_key_state();
             }
+            KeyCode::Char(_) if self.which_key.in_group => {
+                // The tool is waiting for the key that completes its group
+                self.which_key.hide();
+                if let Some(idx) = self.active_tool {
+                    let action = self.tools[idx].handle_key(key);
+                    self.process_action(action);
+                }
+            }
             KeyCode::Char(c) => {
+                let group = self.which_key.group(c).cloned();
+                let reveal_at = self.which_key.reveal_at();
                 self.which_key.hide();
                 self.reset_all_key_state();
 
@@ -281,6 +363,10 @@ impl App {
                     }
                 }
 
+                if !handled {
+                    handled = self.open_leader_picker(&key);
+                }
+
                 if !handled {
                     match c {
                         'q' => {
@@ -294,9 +380,6 @@ impl App {
                                 self.switch_to_tool(idx);
                             }
                         }
-                        'f' => {
-                            self.open_telescope();
-                        }
                         'h' => {
                             // Switch to HTTP tool
                             if let Some(idx) = self.tools.iter().position(|t| t.name() == "HTTP") {
@@ -331,9 +414,6 @@ impl App {
                         '?' => {
                             self.show_help();
                         }
-                        ' ' => {
-                            self.open_tool_picker();
-                        }
                         c @ '1'..='9' => {
                             let idx = (c as u8 - b'1') as usize;
                             self.switch_to_tool(idx);
@@ -341,14 +421,32 @@ impl App {
                         _ => {}
                     }
                 }
+
+                // A tool group stays open to list its sub-entries
+                if handled && let Some(group) = group {
+                    self.which_key.open_group(group, reveal_at);
+                }
             }
             _ => {
                 self.which_key.hide();
                 self.reset_all_key_state();
+                self.open_leader_picker(&key);
             }
         }
     }
 
+    /// Open the picker whose (possibly remapped) key follows the leader.
+    fn open_leader_picker(&mut self, key: &KeyEvent) -> bool {
+        if keymap().matches(KeyAction::ToolPicker, key) {
+            self.open_tool_picker();
+        } else if keymap().matches(KeyAction::Telescope, key) {
+            self.open_telescope();
+        } else {
+            return false;
+        }
+        true
+    }
+
     /// Handle telescope key events.
     fn handle_telescope_key(&mut self, key: KeyEvent) {
         match key.code {
@@ -378,12 +476,44 @@ impl App {
             }
             _ => {}
         }
+        self.refresh_telescope_preview();
+    }
+
+    /// Ask the tools for a preview of the highlighted telescope item.
+    fn refresh_telescope_preview(&mut self) {
+        if !self.telescope.visible || !self.telescope.preview_enabled {
+            return;
+        }
+        let preview = self.telescope.selected_id().and_then(|id| {
+            if let Some((idx, item_id)) = self.split_tool_id(id) {
+                let tool = &self.tools[idx];
+                return match item_id {
+                    Some(item_id) => tool.telescope_preview(item_id),
+                    None => Some(tool.description().to_string()),
+                };
+            }
+            self.tools
+                .iter()
+                .find_map(|tool| tool.telescope_preview(id))
+        });
+        self.telescope.preview = preview;
     }
 
     /// Handle a telescope selection.
     fn handle_telescope_selection(&mut self, id: &str) {
-        if let Some(tool_name) = id.strip_prefix("tool:") {
-            if let Some(idx) = self.tools.iter().position(|t| t.name() == tool_name) {
+        if let Some(key) = id.strip_prefix(help_popup::PALETTE_ID_PREFIX) {
+            // Run the help entry by replaying its keys
+            for key in help_popup::entry_keys(key).unwrap_or_default() {
+                self.handle_event(Event::Key(key));
+            }
+            return;
+        }
+
+        if id.starts_with("tool:") {
+            if let Some((idx, item_id)) = self.split_tool_id(id) {
+                if let Some(item_id) = item_id {
+                    self.tools[idx].handle_telescope_selection(item_id);
+                }
                 self.switch_to_tool(idx);
             }
             return;
@@ -398,6 +528,18 @@ impl App {
         }
     }
 
+    /// Resolve a `tool:<name>` or `tool:<name>:<item id>` telescope ID to
+    /// the tool index and the item ID within that tool.
+    fn split_tool_id<'a>(&self, id: &'a str) -> Option<(usize, Option<&'a str>)> {
+        let rest = id.strip_prefix("tool:")?;
+        let (tool_name, item_id) = match rest.split_once(':') {
+            Some((name, item_id)) => (name, Some(item_id)),
+            None => (rest, None),
+        };
+        let idx = self.tools.iter().position(|t| t.name() == tool_name)?;
+        Some((idx, item_id))
+    }
+
     /// Handle command-mode key events.
     fn handle_command_key(&mut self, key: KeyEvent) {
         match key.code {
@@ -465,32 +607,47 @@ impl App {
 
         match cmd {
             "q" | "quit" => {
-                if self.active_tool.is_some() {
-                    if let Some(idx) = self.active_tool {
-                        self.tools[idx].on_blur();
-                    }
-                    self.active_tool = None;
-                } else {
-                    self.should_quit = true;
+                self.close_or_quit();
+            }
+            "q!" | "quit!" => {
+                let pending = self.close_or_quit_target();
+                if let Some(idx) = self.active_tool {
+                    self.tools[idx].discard_changes();
                 }
+                self.finish_quit(pending);
             }
-            "qa" | "qa!" => {
-                self.should_quit = true;
+            "qa" => {
+                self.request_quit(PendingQuit::QuitApp);
+            }
+            "qa!" => {
+                for idx in self.dirty_tools(PendingQuit::QuitApp) {
+                    self.tools[idx].discard_changes();
+                }
+                self.finish_quit(PendingQuit::QuitApp);
+            }
+            "h" | "help" => {
+                self.open_help_palette();
             }
             "wq" | "x" => {
                 if let Some(idx) = self.active_tool {
+                    // A failed save leaves the tool dirty: ask instead of closing
                     self.tools[idx].handle_command("w");
-                    self.tools[idx].on_blur();
-                    self.active_tool = None;
+                    self.request_quit(PendingQuit::CloseTool);
                 } else {
-                    self.should_quit = true;
+                    self.request_quit(PendingQuit::QuitApp);
+                }
+            }
+            "wqa" | "xa" => {
+                if let Some(idx) = self.active_tool {
+                    self.tools[idx].handle_command("w");
                 }
+                self.request_quit(PendingQuit::QuitApp);
             }
-            "wqa" | "wqa!" | "xa" | "xa!" => {
+            "wqa!" | "xa!" => {
                 if let Some(idx) = self.active_tool {
                     self.tools[idx].handle_command("w");
                 }
-                self.should_quit = true;
+                self.finish_quit(PendingQuit::QuitApp);
             }
             _ => {
                 // Unknown command — could show an error message in the future
@@ -498,6 +655,97 @@ impl App {
         }
     }
 
+    /// `:q` / `q`: close the active tool, or quit from the dashboard.
+    fn close_or_quit(&mut self) {
+        self.request_quit(self.close_or_quit_target());
+    }
+
+    fn close_or_quit_target(&self) -> PendingQuit {
+        match self.active_tool {
+            Some(_) => PendingQuit::CloseTool,
+            None => PendingQuit::QuitApp,
+        }
+    }
+
+    /// Run `pending`, first asking what to do with unsaved edits it would drop.
+    fn request_quit(&mut self, pending: PendingQuit) {
+        if self.dirty_tools(pending).is_empty() {
+            self.finish_quit(pending);
+        } else {
+            self.reset_all_key_state();
+            self.quit_prompt = Some(pending);
+        }
+    }
+
+    /// Tools whose unsaved edits `pending` would drop: the active tool when
+    /// closing it, every tool when quitting.
+    fn dirty_tools(&self, pending: PendingQuit) -> Vec<usize> {
+        let candidates: Vec<usize> = match pending {
+            PendingQuit::CloseTool => self.active_tool.into_iter().collect(),
+            PendingQuit::QuitApp => (0..self.tools.len()).collect(),
+        };
+        candidates
+            .into_iter()
+            .filter(|&idx| self.tools[idx].is_dirty())
+            .collect()
+    }
+
+    /// Close the active tool or quit. Either way the active tool is blurred
+    /// first, so it saves what it keeps on leaving (e.g. the note cursor).
+    fn finish_quit(&mut self, pending: PendingQuit) {
+        if let Some(idx) = self.active_tool {
+            self.tools[idx].on_blur();
+        }
+        match pending {
+            PendingQuit::CloseTool => self.active_tool = None,
+            PendingQuit::QuitApp => self.should_quit = true,
+        }
+    }
+
+    /// Keys of the unsaved-changes prompt: `s` saves and quits, `d` discards
+    /// and quits, `c` / `Esc` cancels. If a save fails the prompt stays open.
+    fn handle_quit_prompt_key(&mut self, key: KeyEvent) {
+        let Some(pending) = self.quit_prompt else {
+            return;
+        };
+        let dirty = self.dirty_tools(pending);
+        match key.code {
+            KeyCode::Char('s') => {
+                for &idx in &dirty {
+                    self.tools[idx].handle_command("w");
+                }
+                if !self.dirty_tools(pending).is_empty() {
+                    return;
+                }
+            }
+            KeyCode::Char('d') => {
+                for &idx in &dirty {
+                    self.tools[idx].discard_changes();
+                }
+            }
+            KeyCode::Char('c') | KeyCode::Esc => {
+                self.quit_prompt = None;
+                return;
+            }
+            _ => return,
+        }
+        self.quit_prompt = None;
+        self.finish_quit(pending);
+    }
+
+    /// The unsaved-changes question, naming the tools involved.
+    fn quit_prompt_message(&self, pending: PendingQuit) -> String {
+        let names: Vec<&str> = self
+            .dirty_tools(pending)
+            .into_iter()
+            .map(|idx| self.tools[idx].name())
+            .collect();
+        format!(
+            "Unsaved changes in {} \u{2014} quit anyway?",
+            names.join(", ")
+        )
+    }
+
     /// Handle keys when on the dashboard (no tool active).
     fn handle_dashboard_key(&mut self, key: KeyEvent) {
         use rstools_core::keybinds::process_normal_key;
@@ -509,6 +757,11 @@ impl App {
     /// Handle a mouse event.
     /// Handle a bracketed paste event from the terminal.
     fn handle_paste_event(&mut self, text: &str) {
+        // The unsaved-changes prompt only answers keys
+        if self.quit_prompt.is_some() {
+            return;
+        }
+
         // If telescope is active, insert into the telescope input
         if self.telescope.visible {
             for c in text.chars() {
@@ -516,6 +769,7 @@ impl App {
                     self.telescope.insert_char(c);
                 }
             }
+            self.refresh_telescope_preview();
             return;
         }
 
@@ -541,12 +795,17 @@ impl App {
         let col = mouse.column;
         let row = mouse.row;
 
+        // The unsaved-changes prompt only answers keys
+        if self.quit_prompt.is_some() {
+            return;
+        }
+
         // Overlays intercept mouse events when visible
         if self.telescope.visible {
             self.handle_telescope_mouse(mouse);
             return;
         }
-        if self.which_key.visible {
+        if self.which_key.active {
             self.handle_which_key_mouse(mouse);
             return;
         }
@@ -615,6 +874,7 @@ impl App {
             self.active_tool = Some(idx);
             self.tools[idx].on_focus();
             self.mode = InputMode::Normal;
+            let _ = db::set_app_state(&self.conn, LAST_TOOL_KEY, self.tools[idx].name());
         }
     }
 
@@ -632,7 +892,9 @@ impl App {
         self.telescope.open("Tool Picker", items);
     }
 
-    /// Open the general telescope (search across all tools).
+    /// Open the general telescope: the tools, then every tool's items
+    /// tagged with their tool. Selecting an item switches to its tool and
+    /// hands it the item.
     fn open_telescope(&mut self) {
         let mut items: Vec<TelescopeItem> = Vec::new();
 
@@ -647,23 +909,48 @@ impl App {
 
         // Add items from each tool
         for tool in &self.tools {
-            items.extend(tool.telescope_items());
+            items.extend(
+                tool.telescope_items()
+                    .into_iter()
+                    .map(|item| TelescopeItem {
+                        label: format!("[{}] {}", tool.name(), item.label),
+                        description: item.description,
+                        id: format!("tool:{}:{}", tool.name(), item.id),
+                    }),
+            );
         }
 
-        self.telescope.open("Find", items);
+        self.telescope.open_with_preview("Find", items);
+        self.refresh_telescope_preview();
     }
 
     /// Show the help popup with global + tool-specific keybinds.
     fn show_help(&mut self) {
         self.reset_all_key_state();
+        let (title, entries) = self.help_entries();
+        self.help_popup.show(title, entries);
+    }
+
+    /// Open the help entries as a searchable palette; picking one runs it.
+    fn open_help_palette(&mut self) {
+        self.reset_all_key_state();
+        let (title, entries) = self.help_entries();
+        self.telescope
+            .open(title, help_popup::palette_items(&entries));
+    }
+
+    /// Title and entries of the help for the current context.
+    fn help_entries(&self) -> (String, Vec<help_popup::HelpEntry>) {
         let mut entries = Vec::new();
 
-        // Add tool-specific entries first (if a tool is active)
+        // Add tool-specific entries first (if a tool is active). They are
+        // written with the default leader; show the configured one.
         if let Some(idx) = self.active_tool {
-            let tool_entries = self.tools[idx].help_entries();
-            if !tool_entries.is_empty() {
-                entries.extend(tool_entries);
-            }
+            let leader = keymap().label(KeyAction::Leader);
+            entries.extend(self.tools[idx].help_entries().into_iter().map(|mut entry| {
+                entry.key = entry.key.replace("<Space>", &leader);
+                entry
+            }));
         }
 
         // Add global entries
@@ -673,8 +960,7 @@ impl App {
             Some(idx) => format!("{} Help", self.tools[idx].name()),
             None => "Help".to_string(),
         };
-
-        self.help_popup.show(title, entries);
+        (title, entries)
     }
 
     /// Handle mouse events while telescope is visible.
@@ -688,7 +974,8 @@ impl App {
             width: self.last_content_area.width.max(self.last_tab_area.width),
             height: self.last_content_area.y + self.last_content_area.height + 1,
         };
-        let popup_area = self.telescope_popup_rect(full_area);
+        let popup_area = self.telescope.popup_area(full_area);
+        let results_area = self.telescope.results_area(full_area);
 
         match mouse.kind {
             MouseEventKind::Down(MouseButton::Left) => {
@@ -697,12 +984,12 @@ impl App {
                     self.reset_all_key_state();
                 } else {
                     // Click inside: check if it's on a result item
-                    let results_y_start = popup_area.y + 3; // input area is 3 lines
-                    let results_y_end = popup_area.y + popup_area.height - 1; // -1 for bottom border
+                    let results_y_start = results_area.y;
+                    let results_y_end = results_area.y + results_area.height - 1; // -1 for bottom border
                     if mouse.row >= results_y_start
                         && mouse.row < results_y_end
-                        && mouse.column > popup_area.x
-                        && mouse.column < popup_area.x + popup_area.width - 1
+                        && mouse.column > results_area.x
+                        && mouse.column < results_area.x + results_area.width - 1
                     {
                         let clicked_idx = (mouse.row - results_y_start) as usize;
                         if clicked_idx < self.telescope.filtered.len() {
@@ -719,22 +1006,7 @@ impl App {
             }
             _ => {}
         }
-    }
-
-    /// Compute the telescope popup rect (same logic as Telescope::render).
-    fn telescope_popup_rect(&self, area: Rect) -> Rect {
-        use ratatui::layout::{Constraint, Flex, Layout};
-        let popup_width = (area.width * 60 / 100)
-            .max(40)
-            .min(area.width.saturating_sub(4));
-        let popup_height = (area.height * 60 / 100)
-            .max(10)
-            .min(area.height.saturating_sub(4));
-        let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
-        let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
-        let [popup_area] = vertical.areas(area);
-        let [popup_area] = horizontal.areas(popup_area);
-        popup_area
+        self.refresh_telescope_preview();
     }
 
     /// Handle mouse events while which-key is visible.
@@ -771,12 +1043,22 @@ impl App {
                 self.help_popup.hide();
                 self.reset_all_key_state();
             }
-            KeyCode::Char('j') | KeyCode::Down => {
+            _ if keymap().matches(KeyAction::Help, &key) => {
+                self.help_popup.hide();
+                self.reset_all_key_state();
+            }
+            KeyCode::Down => self.help_popup.scroll_down(),
+            KeyCode::Up => self.help_popup.scroll_up(),
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.help_popup.scroll_down();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.help_popup.scroll_up();
             }
+            KeyCode::Char('/') => {
+                self.help_popup.hide();
+                self.open_help_palette();
+            }
             KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => {
                 for _ in 0..10 {
                     self.help_popup.scroll_down();
@@ -814,8 +1096,22 @@ impl App {
             self.render_dashboard(frame, content_area);
         }
 
-        // Status bar or command line
-        if self.mode == InputMode::Command {
+        // Status bar, command line or the unsaved-changes prompt
+        if let Some(pending) = self.quit_prompt {
+            let line = Line::from(vec![
+                Span::styled(
+                    format!("{} ", self.quit_prompt_message(pending)),
+                    Style::default()
+                        .fg(theme().error)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    "[s]ave  [d]iscard  [c]ancel",
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]);
+            frame.render_widget(Paragraph::new(line), status_area);
+        } else if self.mode == InputMode::Command {
             ui::render_command_line(frame, status_area, &self.command_input, self.command_cursor);
         } else {
             let tool_name = self
@@ -825,16 +1121,36 @@ impl App {
 
             // Get mode from active tool for accurate status bar
             let mode = match self.active_tool {
-                Some(idx) => self.tools[idx].mode(),
-                None => self.mode,
+                Some(idx) => self.tools[idx].mode_label(),
+                None => self.mode.label(),
+            };
+            let quit = match self.active_tool {
+                Some(_) => "close",
+                None => "quit",
             };
-            let info = match self.active_tool {
-                Some(_) => "Space: leader  ?:help  :q: close",
-                None => "Space: leader  ?:help  :q: quit",
+            let leader = keymap().label(KeyAction::Leader);
+            let help = keymap().label(KeyAction::Help);
+            let info = format!("{leader}: leader  {help}:help  :q: {quit}");
+            let warning = self
+                .active_tool
+                .and_then(|i| self.tools[i].status_warning());
+            let pending = match self.active_tool {
+                Some(idx) => self.tools[idx].pending_keys(),
+                None => self.key_state.pending_keys(),
             };
-            ui::render_status_bar(frame, status_area, mode, tool_name, info);
+            ui::render_status_bar(
+                frame,
+                status_area,
+                mode,
+                tool_name,
+                warning.as_deref(),
+                &info,
+                &pending,
+            );
         }
 
+        self.toasts.render(frame, content_area);
+
         // Overlays (rendered last, on top)
         self.which_key.render(frame, area);
         self.help_popup.render(frame, area);
@@ -864,6 +1180,7 @@ impl App {
             widgets::Paragraph,
         };
 
+        let leader = keymap().label(KeyAction::Leader);
         let lines = vec![
             Line::from(""),
             Line::from(Span::styled(
@@ -878,18 +1195,24 @@ impl App {
             Line::from(""),
             Line::from(""),
             Line::from(vec![
-                Span::styled("  <Space> ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!("  {leader} "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
                 Span::raw("  Open leader menu"),
             ]),
             Line::from(vec![
                 Span::styled(
-                    "  <Space><Space> ",
+                    format!("  {leader}{} ", keymap().label(KeyAction::ToolPicker)),
                     Style::default().add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("  Tool picker"),
             ]),
             Line::from(vec![
-                Span::styled("  <Space>f ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!("  {leader}{} ", keymap().label(KeyAction::Telescope)),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
                 Span::raw("  Find (telescope)"),
             ]),
             Line::from(vec![
@@ -944,3 +1267,345 @@ impl App {
 fn rect_contains(rect: Rect, col: u16, row: u16) -> bool {
     col >= rect.x && col < rect.x + rect.width && row >= rect.y && row < rect.y + rect.height
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::keybinds::{Keymap, install_thread_keymap};
+    use rstools_notes::{NotesTool, model as notes_model};
+    use rstools_todo::TodoTool;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_event(Event::Key(KeyEvent::from(code)));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    fn todo_app() -> App {
+        let todo = TodoTool::new(db::open_memory_db().unwrap()).unwrap();
+        let app = App::new(vec![Box::new(todo)], db::open_memory_db().unwrap());
+        app.init_db().unwrap();
+        app
+    }
+
+    #[test]
+    fn test_restore_active_tool() {
+        let todo = TodoTool::new(db::open_memory_db().unwrap()).unwrap();
+        let notes = NotesTool::new(db::open_memory_db().unwrap()).unwrap();
+        let conn = db::open_memory_db().unwrap();
+        let mut app = App::new(vec![Box::new(todo), Box::new(notes)], conn);
+        app.init_db().unwrap();
+
+        // Nothing saved yet: stay on the dashboard
+        app.restore_active_tool(None).unwrap();
+        assert_eq!(app.active_tool, None);
+
+        // The last focused tool comes back
+        app.switch_to_tool(1);
+        app.active_tool = None;
+        app.restore_active_tool(None).unwrap();
+        assert_eq!(app.active_tool, Some(1));
+
+        // `--tool` wins over the saved tool, ignoring case
+        app.restore_active_tool(Some("TODO")).unwrap();
+        assert_eq!(app.active_tool, Some(0));
+        let err = app.restore_active_tool(Some("nope")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown tool 'nope' (available: Todo, Notes)"
+        );
+
+        // A saved tool that no longer exists is ignored
+        db::set_app_state(&app.conn, LAST_TOOL_KEY, "Gone").unwrap();
+        app.active_tool = None;
+        app.restore_active_tool(None).unwrap();
+        assert_eq!(app.active_tool, None);
+    }
+
+    #[test]
+    fn test_find_opens_items_in_their_tool() {
+        let todo_conn = db::open_memory_db().unwrap();
+        rstools_todo::model::init_db(&todo_conn).unwrap();
+        for title in ["Buy milk", "Pay rent"] {
+            rstools_todo::model::add_todo(&todo_conn, title, None).unwrap();
+        }
+        let todo = TodoTool::new(todo_conn).unwrap();
+        let mut app = App::new(vec![Box::new(todo)], db::open_memory_db().unwrap());
+        app.init_db().unwrap();
+
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(app.telescope.title, "Find");
+        let items = &app.telescope.items;
+        let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["Todo", "[Todo] Pay rent", "[Todo] Buy milk"]);
+
+        type_text(&mut app, "rent");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.active_tool, Some(0));
+        // The todo tool got the item: `dd` deletes it
+        type_text(&mut app, "dd");
+        let left: Vec<String> = app.tools[0]
+            .telescope_items()
+            .into_iter()
+            .map(|i| i.label)
+            .collect();
+        assert_eq!(left, vec!["Buy milk"]);
+    }
+
+    #[test]
+    fn test_help_palette_runs_entry() {
+        let mut app = todo_app();
+        type_text(&mut app, ":help");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.telescope.visible);
+        assert_eq!(app.telescope.title, "Help");
+
+        type_text(&mut app, "picker");
+        assert_eq!(app.telescope.selected_id(), Some("help:<Space><Space>"));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.telescope.visible);
+        assert_eq!(app.telescope.title, "Tool Picker");
+    }
+
+    #[test]
+    fn test_remapped_picker_key() {
+        let config = "[keys]\nleader = \",\"\ntool_picker = \"p\"\nhelp = \"<F1>\"\n";
+        install_thread_keymap(Keymap::from_toml(config).unwrap().0);
+        let mut app = todo_app();
+
+        press(&mut app, KeyCode::Char(','));
+        assert!(app.which_key.active);
+        assert!(
+            app.which_key
+                .entries
+                .iter()
+                .any(|e| e.key == "p" && e.description == "Tool picker")
+        );
+        press(&mut app, KeyCode::Char('p'));
+        assert!(app.telescope.visible);
+        assert_eq!(app.telescope.title, "Tool Picker");
+        press(&mut app, KeyCode::Esc);
+
+        // The default picker key is free again, also inside a tool
+        app.switch_to_tool(0);
+        press(&mut app, KeyCode::Char(','));
+        press(&mut app, KeyCode::Char(' '));
+        assert!(!app.telescope.visible);
+        press(&mut app, KeyCode::Char(','));
+        press(&mut app, KeyCode::Char('p'));
+        assert_eq!(app.telescope.title, "Tool Picker");
+        press(&mut app, KeyCode::Esc);
+
+        // Help lists the configured keys
+        press(&mut app, KeyCode::F(1));
+        assert!(app.help_popup.visible);
+        let (_, entries) = app.help_entries();
+        let picker = entries.iter().find(|e| e.description == "Tool picker");
+        assert_eq!(picker.unwrap().key, ",p");
+    }
+
+    #[test]
+    fn test_dashboard_shows_remapped_keys() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let config = "[keys]\nleader = \",\"\ntool_picker = \"p\"\ntelescope = \"g\"\n";
+        install_thread_keymap(Keymap::from_toml(config).unwrap().0);
+        let mut app = todo_app();
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect();
+        assert!(rows.iter().any(|r| r.contains(",p   Tool picker")));
+        assert!(rows.iter().any(|r| r.contains(",g   Find (telescope)")));
+
+        // The keys shown are the keys that work
+        type_text(&mut app, ",p");
+        assert_eq!(app.telescope.title, "Tool Picker");
+        press(&mut app, KeyCode::Esc);
+        type_text(&mut app, ",g");
+        assert_eq!(app.telescope.title, "Find");
+    }
+
+    #[test]
+    fn test_switching_away_saves_dirty_note() {
+        let dir = std::env::temp_dir().join(format!("rstools-hub-blur-{}", std::process::id()));
+        let path = dir.join("rstools.db");
+        let todo = TodoTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let notes = NotesTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let conn = db::open_db_at(&path).unwrap();
+        let entry_id =
+            notes_model::add_entry(&conn, None, "draft", notes_model::EntryType::Note).unwrap();
+        let mut app = App::new(vec![Box::new(todo), Box::new(notes)], conn);
+        app.init_db().unwrap();
+
+        app.switch_to_tool(1);
+        for code in [
+            KeyCode::Enter,
+            KeyCode::Char('i'),
+            KeyCode::Char('x'),
+            KeyCode::Esc,
+        ] {
+            press(&mut app, code);
+        }
+        assert!(app.tools[1].is_dirty());
+
+        app.switch_to_tool(0);
+        assert!(!app.tools[1].is_dirty());
+        let content = notes_model::get_note_content(&app.conn, entry_id).unwrap();
+        assert_eq!(content.body, "x");
+
+        drop(app);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quitting_saves_note_position() {
+        let dir = std::env::temp_dir().join(format!("rstools-hub-quit-{}", std::process::id()));
+        let path = dir.join("rstools.db");
+        let notes = NotesTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let conn = db::open_db_at(&path).unwrap();
+        let note = notes_model::EntryType::Note;
+        let entry_id = notes_model::add_entry(&conn, None, "long", note).unwrap();
+        notes_model::save_note_content(&conn, entry_id, "one\ntwo\nthree").unwrap();
+        let mut app = App::new(vec![Box::new(notes)], conn);
+        app.init_db().unwrap();
+        let row = |app: &App| {
+            let position = notes_model::get_note_position(&app.conn, entry_id).unwrap();
+            position.cursor_row
+        };
+
+        // Ctrl-c quits straight from an unedited note
+        app.switch_to_tool(0);
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "jj");
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        app.handle_event(Event::Key(ctrl_c));
+        assert!(app.should_quit);
+        assert_eq!(row(&app), 2);
+
+        // So does `:qa`
+        app.should_quit = false;
+        type_text(&mut app, "k:qa");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.should_quit);
+        assert_eq!(row(&app), 1);
+
+        drop(app);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quit_prompt_choices() {
+        let dir = std::env::temp_dir().join(format!("rstools-hub-prompt-{}", std::process::id()));
+        let path = dir.join("rstools.db");
+        let notes = NotesTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let conn = db::open_db_at(&path).unwrap();
+        let note = notes_model::EntryType::Note;
+        let entry_id = notes_model::add_entry(&conn, None, "draft", note).unwrap();
+        let mut app = App::new(vec![Box::new(notes)], conn);
+        app.init_db().unwrap();
+        let body = |app: &App| {
+            let content = notes_model::get_note_content(&app.conn, entry_id).unwrap();
+            content.body
+        };
+        let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+
+        app.switch_to_tool(0);
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "ix");
+        press(&mut app, KeyCode::Esc);
+        assert!(app.tools[0].is_dirty());
+
+        // Ctrl-c asks first; `c` cancels and keeps the edit
+        app.handle_event(ctrl_c.clone());
+        assert_eq!(app.quit_prompt, Some(PendingQuit::QuitApp));
+        assert!(!app.should_quit);
+        press(&mut app, KeyCode::Char('c'));
+        assert_eq!(app.quit_prompt, None);
+        assert!(!app.should_quit);
+        assert!(app.tools[0].is_dirty());
+
+        // `d` drops the edit and quits
+        type_text(&mut app, ":qa");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.quit_prompt, Some(PendingQuit::QuitApp));
+        press(&mut app, KeyCode::Char('d'));
+        assert!(app.should_quit);
+        assert!(!app.tools[0].is_dirty());
+        assert_eq!(body(&app), "");
+
+        // `:wq` on the dashboard asks too; `s` saves and quits
+        app.should_quit = false;
+        app.switch_to_tool(0);
+        type_text(&mut app, "iy");
+        press(&mut app, KeyCode::Esc);
+        app.active_tool = None;
+        type_text(&mut app, ":wq");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.quit_prompt, Some(PendingQuit::QuitApp));
+        press(&mut app, KeyCode::Char('s'));
+        assert!(app.should_quit);
+        assert_eq!(body(&app), "y");
+
+        drop(app);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quit_prompt_ignores_mouse_and_paste() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let dir = std::env::temp_dir().join(format!("rstools-hub-modal-{}", std::process::id()));
+        let path = dir.join("rstools.db");
+        let todo = TodoTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let notes = NotesTool::new(db::open_db_at(&path).unwrap()).unwrap();
+        let conn = db::open_db_at(&path).unwrap();
+        let note = notes_model::EntryType::Note;
+        notes_model::add_entry(&conn, None, "draft", note).unwrap();
+        let mut app = App::new(vec![Box::new(todo), Box::new(notes)], conn);
+        app.init_db().unwrap();
+
+        app.switch_to_tool(1);
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "ix");
+        press(&mut app, KeyCode::Esc);
+        type_text(&mut app, ":q");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.quit_prompt, Some(PendingQuit::CloseTool));
+
+        // A click on the Todo tab leaves the prompt and the note in place
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let tab = app.last_tab_area;
+        assert_eq!(app.tab_index_at(tab.x), Some(0));
+        app.handle_event(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: tab.x,
+            row: tab.y,
+            modifiers: KeyModifiers::NONE,
+        }));
+        assert_eq!(app.active_tool, Some(1));
+        assert_eq!(app.quit_prompt, Some(PendingQuit::CloseTool));
+
+        // So does a paste
+        app.handle_event(Event::Paste("pasted".to_string()));
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.active_tool, None);
+        assert!(!app.tools[1].is_dirty());
+
+        drop(app);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -12,11 +12,15 @@ use crossterm::{
         self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
     },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use directories::ProjectDirs;
+use ratatui::{Terminal, backend::CrosstermBackend};
 
 use rstools_core::db;
+use rstools_core::keybinds::{self, Keymap};
+use rstools_core::theme::{self, Theme};
+use rstools_core::toast::Toast;
 use rstools_database::DatabaseTool;
 use rstools_http::HttpTool;
 use rstools_keepass::KeePassTool;
@@ -27,15 +31,23 @@ use rstools_todo::TodoTool;
 use app::App;
 
 fn main() -> Result<()> {
-    let demo_mode = std::env::args().any(|arg| arg == "--demo");
+    let args: Vec<String> = std::env::args().collect();
+    let demo_mode = args.iter().any(|arg| arg == "--demo");
+    // `--tool <name>` opens that tool instead of the last active one
+    let tool_arg = flag_value(&args, "--tool", "a tool name")?;
+    // `--db <path>` overrides `$RSTOOLS_DB` and the default location
+    let db_arg = flag_value(&args, "--db", "a path")?;
+
+    // Before the tools are built, so they see the configured keymap and theme
+    let config_problems = load_config();
 
     // Open the shared database
-    let conn = if demo_mode {
-        let demo_path = demo_db_path()?;
-        db::open_db_at(&demo_path)?
-    } else {
-        db::open_db()?
+    let db_path = match db_arg {
+        Some(path) => PathBuf::from(path),
+        None if demo_mode => demo_db_path()?,
+        None => db::db_path()?,
     };
+    let conn = db::open_db_at(&db_path)?;
 
     if demo_mode {
         demo_seed::seed_demo_data(&conn)?;
@@ -43,58 +55,41 @@ fn main() -> Result<()> {
 
     // Create tools
     // Each tool gets its own connection to avoid borrow issues
-    let todo_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let todo_conn = db::open_db_at(&db_path)?;
     let todo = TodoTool::new(todo_conn)?;
 
-    let http_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let http_conn = db::open_db_at(&db_path)?;
     let http = HttpTool::new(http_conn)?;
 
-    let keepass_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let keepass_conn = db::open_db_at(&db_path)?;
     let keepass = KeePassTool::new(keepass_conn)?;
 
-    let notes_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let notes_conn = db::open_db_at(&db_path)?;
     let notes = NotesTool::new(notes_conn)?;
 
-    let merge_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let merge_conn = db::open_db_at(&db_path)?;
     let merge = MergeTool::new(merge_conn)?;
 
-    let database_conn = if demo_mode {
-        db::open_db_at(&demo_db_path()?)?
-    } else {
-        db::open_db()?
-    };
+    let database_conn = db::open_db_at(&db_path)?;
     let database = DatabaseTool::new(database_conn)?;
 
     // Build the app
-    let mut app = App::new(vec![
-        Box::new(todo),
-        Box::new(http),
-        Box::new(keepass),
-        Box::new(notes),
-        Box::new(merge),
-        Box::new(database),
-    ]);
-    app.init_db(&conn)?;
+    let mut app = App::new(
+        vec![
+            Box::new(todo),
+            Box::new(http),
+            Box::new(keepass),
+            Box::new(notes),
+            Box::new(merge),
+            Box::new(database),
+        ],
+        conn,
+    );
+    app.init_db()?;
+    app.restore_active_tool(tool_arg.as_deref())?;
+    for problem in config_problems {
+        app.notify(Toast::error(problem));
+    }
 
     // Setup terminal
     enable_raw_mode()?;
@@ -129,6 +124,58 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Install the keymap and theme from the user config, if there is one.
+/// Returns the problems found, to be shown as toasts; the affected settings
+/// keep their defaults.
+fn load_config() -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(path) = config_path() else {
+        return problems;
+    };
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return problems;
+    };
+    match Theme::from_toml(&text) {
+        Ok((theme, warnings)) => {
+            problems.extend(warnings);
+            theme::install_theme(theme);
+        }
+        Err(err) => problems.push(err.to_string()),
+    }
+    match Keymap::from_toml(&text) {
+        Ok((keymap, warnings)) => {
+            problems.extend(warnings);
+            keybinds::install_keymap(keymap);
+        }
+        Err(err) => problems.push(err.to_string()),
+    }
+    // Invalid TOML is reported by both parsers
+    problems.dedup();
+    problems
+        .into_iter()
+        .map(|problem| format!("config.toml: {problem}"))
+        .collect()
+}
+
+/// The value after `flag` on the command line, if the flag is given. The
+/// next flag (`--tool --demo`) is not taken as the value.
+fn flag_value(args: &[String], flag: &str, what: &str) -> Result<Option<String>> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .map(|i| {
+            args.get(i + 1)
+                .filter(|value| !value.starts_with('-'))
+                .cloned()
+                .with_context(|| format!("{flag} needs {what}"))
+        })
+        .transpose()
+}
+
+/// Location: `~/.config/rstools/config.toml` (XDG-compliant)
+fn config_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "rstools").map(|dirs| dirs.config_dir().join("config.toml"))
+}
+
 fn demo_db_path() -> Result<PathBuf> {
     let cwd = std::env::current_dir().context("Failed to get current directory")?;
     let demo_dir = cwd.join(".demo");
@@ -162,3 +209,23 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App)
         app.tick();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_value() {
+        let args = |line: &str| -> Vec<String> { line.split(' ').map(String::from).collect() };
+
+        let value = flag_value(&args("rstools --tool notes"), "--tool", "a tool name").unwrap();
+        assert_eq!(value.as_deref(), Some("notes"));
+        let value = flag_value(&args("rstools --demo"), "--tool", "a tool name").unwrap();
+        assert_eq!(value, None);
+
+        for line in ["rstools --tool", "rstools --tool --demo"] {
+            let err = flag_value(&args(line), "--tool", "a tool name").unwrap_err();
+            assert_eq!(err.to_string(), "--tool needs a tool name");
+        }
+    }
+}
@@ -1 +1,309 @@
 pub use rstools_core::crypto::*;
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+// ── Password generation ──────────────────────────────────────────────
+
+pub const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const DIGITS: &str = "0123456789";
+pub const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>?/~";
+
+/// Generate a random password of `length` characters drawn from `classes`,
+/// with at least one character of every class. Uses the OS CSPRNG.
+/// The length is raised to the number of classes if it is shorter.
+pub fn generate_password(length: usize, classes: &[&str]) -> String {
+    let classes: Vec<Vec<char>> = classes
+        .iter()
+        .map(|c| c.chars().collect::<Vec<_>>())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if classes.is_empty() {
+        return String::new();
+    }
+    let all: Vec<char> = classes.iter().flatten().copied().collect();
+
+    let mut rng = OsRng;
+    let mut chars: Vec<char> = classes
+        .iter()
+        .map(|class| class[rng.gen_range(0..class.len())])
+        .collect();
+    while chars.len() < length {
+        chars.push(all[rng.gen_range(0..all.len())]);
+    }
+    chars.shuffle(&mut rng);
+    chars.into_iter().collect()
+}
+
+// ── TOTP ─────────────────────────────────────────────────────────────
+
+/// HMAC hash used to derive TOTP codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// Time-based one-time password parameters (RFC 6238). The decoded secret
+/// is wiped from memory on drop.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Totp {
+    secret: Vec<u8>,
+    pub digits: u32,
+    pub period: u64,
+    #[zeroize(skip)]
+    pub algorithm: TotpAlgorithm,
+}
+
+impl Totp {
+    /// Parse an `otpauth://totp/...?secret=...` URI (as stored in the `otp`
+    /// field by KeePassXC). `digits`, `period` and `algorithm` are optional.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let rest = uri.trim().strip_prefix("otpauth://")?;
+        let (kind, rest) = rest.split_once('/')?;
+        if !kind.eq_ignore_ascii_case("totp") {
+            return None;
+        }
+        let (_, query) = rest.split_once('?')?;
+
+        let mut secret = None;
+        let mut totp = Totp {
+            secret: Vec::new(),
+            digits: 6,
+            period: 30,
+            algorithm: TotpAlgorithm::Sha1,
+        };
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key.to_ascii_lowercase().as_str() {
+                "secret" => {
+                    let value = Zeroizing::new(value.replace("%3D", "="));
+                    secret = decode_base32(&value);
+                }
+                "digits" => totp.digits = value.parse().ok()?,
+                "period" => totp.period = value.parse().ok()?,
+                "algorithm" => {
+                    totp.algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => TotpAlgorithm::Sha1,
+                        "SHA256" => TotpAlgorithm::Sha256,
+                        "SHA512" => TotpAlgorithm::Sha512,
+                        _ => return None,
+                    }
+                }
+                _ => {}
+            }
+        }
+        totp.secret = secret?;
+        totp.validate()
+    }
+
+    /// Build from KeePass 2's `TOTP Seed` / `TOTP Settings` fields. Settings
+    /// are `period;digits` (e.g. `30;6`); missing settings use 30s / 6 digits.
+    pub fn from_seed(seed: &str, settings: Option<&str>) -> Option<Self> {
+        let mut totp = Totp {
+            secret: decode_base32(seed)?,
+            digits: 6,
+            period: 30,
+            algorithm: TotpAlgorithm::Sha1,
+        };
+        if let Some(settings) = settings {
+            let mut parts = settings.split(';').map(str::trim);
+            if let Some(period) = parts.next().filter(|p| !p.is_empty()) {
+                totp.period = period.parse().ok()?;
+            }
+            if let Some(digits) = parts.next().filter(|d| !d.is_empty()) {
+                totp.digits = digits.parse().ok()?;
+            }
+        }
+        totp.validate()
+    }
+
+    fn validate(self) -> Option<Self> {
+        let valid = !self.secret.is_empty() && (1..=10).contains(&self.digits) && self.period > 0;
+        valid.then_some(self)
+    }
+
+    /// The code for the window containing `unix_secs`, zero-padded.
+    pub fn code_at(&self, unix_secs: u64) -> String {
+        let counter = (unix_secs / self.period).to_be_bytes();
+        let mut hash = match self.algorithm {
+            TotpAlgorithm::Sha1 => hmac_digest::<Hmac<sha1::Sha1>>(&self.secret, &counter),
+            TotpAlgorithm::Sha256 => hmac_digest::<Hmac<sha2::Sha256>>(&self.secret, &counter),
+            TotpAlgorithm::Sha512 => hmac_digest::<Hmac<sha2::Sha512>>(&self.secret, &counter),
+        };
+
+        // Dynamic truncation (RFC 4226 §5.3)
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        hash.zeroize();
+        let code = u64::from(binary) % 10u64.pow(self.digits);
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    /// Seconds left before the code at `unix_secs` changes.
+    pub fn seconds_remaining(&self, unix_secs: u64) -> u64 {
+        self.period - unix_secs % self.period
+    }
+}
+
+/// Never prints the secret.
+impl std::fmt::Debug for Totp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Totp")
+            .field("secret", &"***")
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+fn hmac_digest<M: Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac =
+        <M as hmac::digest::KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Current Unix time in seconds. A clock set before 1970 counts as 0
+/// rather than failing.
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Decode RFC 4648 base32, as used for TOTP secrets. Case, spaces, dashes
+/// and `=` padding are ignored; any other character fails. A partial result
+/// is wiped before failing.
+pub fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut out = Zeroizing::new(Vec::new());
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+    for c in input.chars() {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            ' ' | '-' | '=' => continue,
+            _ => {
+                buffer.zeroize();
+                return None;
+            }
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    buffer.zeroize();
+    Some(std::mem::take(&mut *out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_password() {
+        let password = generate_password(12, &[UPPERCASE, DIGITS]);
+        assert_eq!(password.chars().count(), 12);
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+        assert!(
+            password
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        );
+
+        // Every class is represented even when the length is too short
+        let short = generate_password(1, &[LOWERCASE, SYMBOLS]);
+        assert_eq!(short.chars().count(), 2);
+        assert!(short.chars().any(|c| SYMBOLS.contains(c)));
+        assert!(generate_password(8, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_base32() {
+        assert_eq!(decode_base32("MZXW6YTBOI").unwrap(), b"foobar");
+        assert_eq!(decode_base32("mzxw 6ytb oi======").unwrap(), b"foobar");
+        assert!(decode_base32("MZXW1").is_none());
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        // RFC 6238 appendix B, 8 digits
+        let ascii = "12345678901234567890";
+        let secret: String = base32_encode(ascii.as_bytes());
+        let totp = Totp::from_uri(&format!(
+            "otpauth://totp/Example:alice?secret={secret}&digits=8&issuer=Example"
+        ))
+        .unwrap();
+        assert_eq!(totp.code_at(59), "94287082");
+        assert_eq!(totp.code_at(1111111109), "07081804");
+        assert_eq!(totp.code_at(20000000000), "65353130");
+
+        let sha256 = Totp {
+            secret: b"12345678901234567890123456789012".to_vec(),
+            digits: 8,
+            period: 30,
+            algorithm: TotpAlgorithm::Sha256,
+        };
+        assert_eq!(sha256.code_at(59), "46119246");
+
+        assert_eq!(totp.seconds_remaining(59), 1);
+        assert_eq!(totp.seconds_remaining(60), 30);
+        assert!(!format!("{totp:?}").contains(&format!("{:?}", ascii.as_bytes())));
+        assert!(format!("{totp:?}").contains("secret: \"***\""));
+    }
+
+    #[test]
+    fn test_totp_parsing() {
+        let totp =
+            Totp::from_uri("otpauth://totp/x?secret=GEZDGNBV&period=60&algorithm=SHA512").unwrap();
+        assert_eq!(totp.period, 60);
+        assert_eq!(totp.digits, 6);
+        assert_eq!(totp.algorithm, TotpAlgorithm::Sha512);
+
+        assert!(Totp::from_uri("otpauth://hotp/x?secret=GEZDGNBV").is_none());
+        assert!(Totp::from_uri("otpauth://totp/x?digits=6").is_none());
+        assert!(Totp::from_uri("otpauth://totp/x?secret=GEZDGNBV&period=0").is_none());
+
+        let seeded = Totp::from_seed("GEZD GNBV", Some("45;8")).unwrap();
+        assert_eq!((seeded.period, seeded.digits), (45, 8));
+        assert_eq!(Totp::from_seed("GEZDGNBV", None).unwrap().digits, 6);
+        assert!(Totp::from_seed("not base32!", None).is_none());
+    }
+
+    fn base32_encode(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut out = String::new();
+        let mut buffer: u64 = 0;
+        let mut bits = 0;
+        for &byte in data {
+            buffer = (buffer << 8) | u64::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+        out
+    }
+}
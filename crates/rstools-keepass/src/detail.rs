@@ -1,5 +1,8 @@
 //! Detail panel state for displaying entry fields.
 
+use std::time::{Duration, Instant};
+
+use crate::crypto;
 use crate::vault::EntryDetails;
 
 /// Which field is focused in the detail panel.
@@ -23,12 +26,16 @@ pub struct DetailPanel {
     pub password_visible: bool,
     /// Which custom fields have their protected values revealed.
     pub revealed_custom: Vec<bool>,
+    /// When a sensitive field was last revealed (None while everything is masked).
+    pub revealed_at: Option<Instant>,
     /// Scroll offset for notes (which can be long).
     pub notes_scroll: usize,
     /// Currently focused field (for copy operations).
     pub focused_field: DetailField,
     /// Scroll offset for the overall detail view.
     pub scroll: usize,
+    /// Names of the groups leading to the selected node, and the node itself.
+    pub breadcrumb: Vec<String>,
 }
 
 impl DetailPanel {
@@ -37,15 +44,18 @@ impl DetailPanel {
             details: None,
             password_visible: false,
             revealed_custom: Vec::new(),
+            revealed_at: None,
             notes_scroll: 0,
             focused_field: DetailField::Title,
             scroll: 0,
+            breadcrumb: Vec::new(),
         }
     }
 
     /// Update the displayed entry.
     pub fn set_entry(&mut self, details: Option<EntryDetails>) {
         self.password_visible = false;
+        self.revealed_at = None;
         self.notes_scroll = 0;
         self.scroll = 0;
         self.focused_field = DetailField::Title;
@@ -60,11 +70,19 @@ impl DetailPanel {
     /// Clear the panel.
     pub fn clear(&mut self) {
         self.set_entry(None);
+        self.breadcrumb.clear();
+    }
+
+    /// The breadcrumb joined with ` / `, with its middle replaced by `…`
+    /// when it is wider than `width` characters.
+    pub fn breadcrumb_text(&self, width: usize) -> String {
+        truncate_middle(&self.breadcrumb.join(" / "), width)
     }
 
-    /// Toggle password visibility.
+    /// Toggle visibility of the password.
     pub fn toggle_password(&mut self) {
         self.password_visible = !self.password_visible;
+        self.update_revealed_at();
     }
 
     /// Toggle a custom field's protected value visibility.
@@ -72,6 +90,43 @@ impl DetailPanel {
         if let Some(revealed) = self.revealed_custom.get_mut(idx) {
             *revealed = !*revealed;
         }
+        self.update_revealed_at();
+    }
+
+    /// Whether the password or any protected custom field is revealed.
+    pub fn any_revealed(&self) -> bool {
+        self.password_visible || self.revealed_custom.contains(&true)
+    }
+
+    /// Reveal the password and all protected custom fields, or mask them all
+    /// if any of them is already revealed.
+    pub fn toggle_reveal_all(&mut self) {
+        let reveal = !self.any_revealed();
+        self.password_visible = reveal;
+        self.revealed_custom.fill(reveal);
+        self.update_revealed_at();
+    }
+
+    /// Mask every sensitive field.
+    pub fn mask_all(&mut self) {
+        self.password_visible = false;
+        self.revealed_custom.fill(false);
+        self.revealed_at = None;
+    }
+
+    /// Mask everything once `timeout` has passed since the last reveal.
+    /// Returns true if fields were re-masked.
+    pub fn remask_if_expired(&mut self, timeout: Duration) -> bool {
+        if self.revealed_at.is_some_and(|at| at.elapsed() >= timeout) {
+            self.mask_all();
+            return true;
+        }
+        false
+    }
+
+    /// Restart the re-mask timer on reveal, stop it once all is masked.
+    fn update_revealed_at(&mut self) {
+        self.revealed_at = self.any_revealed().then(Instant::now);
     }
 
     /// Scroll down in the detail view.
@@ -84,6 +139,13 @@ impl DetailPanel {
         self.scroll = self.scroll.saturating_sub(1);
     }
 
+    /// The entry's current TOTP code and the seconds until it changes.
+    pub fn current_totp(&self) -> Option<(String, u64)> {
+        let totp = self.details.as_ref()?.totp()?;
+        let now = crypto::unix_now();
+        Some((totp.code_at(now), totp.seconds_remaining(now)))
+    }
+
     /// Get the value of the currently focused field for clipboard copy.
     pub fn get_field_value(&self, field: DetailField) -> Option<String> {
         let details = self.details.as_ref()?;
@@ -98,3 +160,71 @@ impl DetailPanel {
         }
     }
 }
+
+/// Shorten `text` to at most `width` characters by replacing its middle
+/// with `…`, keeping the start and the (slightly longer) end.
+pub fn truncate_middle(text: &str, width: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let head = (width - 1) / 2;
+    let tail = width - 1 - head;
+    let mut out: String = chars[..head].iter().collect();
+    out.push('…');
+    out.extend(&chars[chars.len() - tail..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breadcrumb_truncation() {
+        let mut panel = DetailPanel::new();
+        panel.breadcrumb = ["Root", "Work", "Servers", "prod-db"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(panel.breadcrumb_text(80), "Root / Work / Servers / prod-db");
+        let short = panel.breadcrumb_text(16);
+        assert_eq!(short, "Root / … prod-db");
+        assert_eq!(short.chars().count(), 16);
+        assert_eq!(truncate_middle("abc", 0), "");
+        assert_eq!(truncate_middle("abcdef", 1), "…");
+    }
+
+    #[test]
+    fn test_reveal_all_and_remask() {
+        let mut panel = DetailPanel::new();
+        panel.set_entry(Some(EntryDetails {
+            password: "hunter2".into(),
+            custom_fields: vec![
+                ("PIN".into(), "1234".into(), true),
+                ("Note".into(), "plain".into(), false),
+            ],
+            ..Default::default()
+        }));
+
+        panel.toggle_password();
+        assert!(panel.password_visible);
+        assert_eq!(panel.revealed_custom, vec![false, false]);
+        assert!(panel.revealed_at.is_some());
+
+        // Something is revealed, so P masks everything
+        panel.toggle_reveal_all();
+        assert!(!panel.any_revealed());
+        assert!(panel.revealed_at.is_none());
+
+        panel.toggle_reveal_all();
+        assert!(panel.password_visible);
+        assert_eq!(panel.revealed_custom, vec![true, true]);
+
+        assert!(!panel.remask_if_expired(Duration::from_secs(60)));
+        assert!(panel.remask_if_expired(Duration::ZERO));
+        assert!(!panel.any_revealed());
+    }
+}
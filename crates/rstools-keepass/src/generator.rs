@@ -0,0 +1,145 @@
+//! State of the password generator overlay (`<Space>kg`).
+
+use crate::crypto;
+
+pub const MIN_LENGTH: usize = 4;
+pub const MAX_LENGTH: usize = 128;
+
+/// A row of the generator overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorRow {
+    Length,
+    Uppercase,
+    Lowercase,
+    Digits,
+    Symbols,
+}
+
+impl GeneratorRow {
+    pub const ALL: [GeneratorRow; 5] = [
+        GeneratorRow::Length,
+        GeneratorRow::Uppercase,
+        GeneratorRow::Lowercase,
+        GeneratorRow::Digits,
+        GeneratorRow::Symbols,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GeneratorRow::Length => "Length",
+            GeneratorRow::Uppercase => "Uppercase (A-Z)",
+            GeneratorRow::Lowercase => "Lowercase (a-z)",
+            GeneratorRow::Digits => "Digits (0-9)",
+            GeneratorRow::Symbols => "Symbols (!@#...)",
+        }
+    }
+}
+
+/// Generator options and the current preview. Nothing is written to the vault.
+pub struct PasswordGenerator {
+    pub length: usize,
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    /// Index into `GeneratorRow::ALL`.
+    pub selected: usize,
+    pub preview: String,
+}
+
+impl PasswordGenerator {
+    pub fn new() -> Self {
+        let mut generator = Self {
+            length: 20,
+            uppercase: true,
+            lowercase: true,
+            digits: true,
+            symbols: true,
+            selected: 0,
+            preview: String::new(),
+        };
+        generator.regenerate();
+        generator
+    }
+
+    pub fn selected_row(&self) -> GeneratorRow {
+        GeneratorRow::ALL[self.selected]
+    }
+
+    pub fn move_down(&mut self) {
+        self.selected = (self.selected + 1).min(GeneratorRow::ALL.len() - 1);
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Whether a character class row is enabled (Length is always "on").
+    pub fn is_enabled(&self, row: GeneratorRow) -> bool {
+        match row {
+            GeneratorRow::Length => true,
+            GeneratorRow::Uppercase => self.uppercase,
+            GeneratorRow::Lowercase => self.lowercase,
+            GeneratorRow::Digits => self.digits,
+            GeneratorRow::Symbols => self.symbols,
+        }
+    }
+
+    /// Change the length by `delta` (Length row) and regenerate.
+    pub fn adjust_length(&mut self, delta: isize) {
+        self.length = self
+            .length
+            .saturating_add_signed(delta)
+            .clamp(MIN_LENGTH, MAX_LENGTH);
+        self.regenerate();
+    }
+
+    /// Toggle the selected character class and regenerate. The last enabled
+    /// class cannot be turned off.
+    pub fn toggle_selected(&mut self) {
+        let enabled = self.classes().len();
+        let flag = match self.selected_row() {
+            GeneratorRow::Length => return,
+            GeneratorRow::Uppercase => &mut self.uppercase,
+            GeneratorRow::Lowercase => &mut self.lowercase,
+            GeneratorRow::Digits => &mut self.digits,
+            GeneratorRow::Symbols => &mut self.symbols,
+        };
+        if *flag && enabled == 1 {
+            return;
+        }
+        *flag = !*flag;
+        self.regenerate();
+    }
+
+    fn classes(&self) -> Vec<&'static str> {
+        [
+            (self.uppercase, crypto::UPPERCASE),
+            (self.lowercase, crypto::LOWERCASE),
+            (self.digits, crypto::DIGITS),
+            (self.symbols, crypto::SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, class)| class)
+        .collect()
+    }
+
+    /// Draw a new preview with the current options.
+    pub fn regenerate(&mut self) {
+        self.preview = crypto::generate_password(self.length, &self.classes());
+    }
+}
+
+impl Default for PasswordGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for PasswordGenerator {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.preview.zeroize();
+    }
+}
@@ -1,16 +1,22 @@
 pub mod crypto;
 pub mod detail;
+pub mod generator;
 pub mod model;
 pub mod sidebar;
+pub mod strength;
 pub mod ui;
 pub mod vault;
 
+use std::cell::Cell;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use rstools_core::confirm::ConfirmPrompt;
 use rstools_core::help_popup::HelpEntry;
-use rstools_core::keybinds::{Action, InputMode, KeyState};
-use rstools_core::telescope::TelescopeItem;
+use rstools_core::keybinds::{Action, InputMode, KeyAction, KeyState, keymap};
+use rstools_core::telescope::{self, FuzzyMatch, TelescopeItem};
+use rstools_core::theme::theme;
+use rstools_core::toast::Toast;
 use rstools_core::tool::Tool;
 use rstools_core::which_key::WhichKeyEntry;
 
@@ -20,16 +26,24 @@ use rusqlite::Connection;
 use zeroize::Zeroize;
 
 use detail::DetailPanel;
+use generator::{GeneratorRow, PasswordGenerator};
+use model::KeePassSettings;
 use sidebar::SidebarState;
 use vault::{EntryDetails, NodeType, SearchableEntry, VaultNode, VaultState};
 
-// ── Auto-lock timeout ────────────────────────────────────────────────
+/// Delay before `yl` replaces the copied username with the password.
+const LOGIN_STAGE_SECS: u64 = 10;
 
-/// Auto-lock after 15 minutes of inactivity.
-const AUTO_LOCK_SECS: u64 = 15 * 60;
+// ── Settings bounds ──────────────────────────────────────────────────
 
-/// Clipboard auto-clear after 30 seconds.
-const CLIPBOARD_CLEAR_SECS: u64 = 30;
+/// Accepted range for `:set autolock <secs>`.
+const AUTO_LOCK_RANGE: std::ops::RangeInclusive<u64> = 10..=24 * 60 * 60;
+
+/// Accepted range for `:set revealtimeout <secs>`.
+const REVEAL_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 1..=60 * 60;
+
+/// Accepted range for `:set clipclear <secs>`.
+const CLIPBOARD_CLEAR_RANGE: std::ops::RangeInclusive<u64> = 1..=60 * 60;
 
 // ── Input prompt types ───────────────────────────────────────────────
 
@@ -39,6 +53,8 @@ pub enum InputPrompt {
     MasterPassword {
         buffer: String,
         file_path: String,
+        /// Key file used together with the password, if any.
+        key_file: Option<String>,
         error: Option<String>,
         /// When true, focus is on the "Paste" button instead of the text input.
         paste_focused: bool,
@@ -92,23 +108,36 @@ pub struct KeePassTool {
     clipboard_set_at: Option<Instant>,
     /// Whether we copied a password (vs username/URL which don't need clearing).
     clipboard_is_sensitive: bool,
-    /// Notification message to show briefly.
-    pub clipboard_notification: Option<String>,
-    /// When the notification was shown.
-    notification_shown_at: Option<Instant>,
-    /// Pending multi-key state for y-prefixed sequences (yu, yp, yU).
+    /// Password queued by `yl` to replace the copied username, and when it was queued.
+    staged_password: Option<(String, Instant)>,
+    /// Toasts for the hub to show (`Tool::take_toasts`).
+    toasts: Vec<Toast>,
+    /// Pending multi-key state for y-prefixed sequences (yu, yp, yU, yt, yf, yl).
     pending_yank: bool,
     /// Search state.
     pub search_active: bool,
     pub search_query: String,
     pub search_results: Vec<SearchableEntry>,
     pub search_selected: usize,
+    /// Whether search matches username, URL and notes too (`Ctrl-f` toggles).
+    pub search_all_fields: bool,
     /// File picker state.
     file_picker_active: bool,
     file_picker_entries: Vec<PathBuf>,
     file_picker_query: String,
     file_picker_filtered: Vec<usize>,
+    /// Matched char positions in each filtered file name (parallel to `file_picker_filtered`).
+    file_picker_positions: Vec<Vec<usize>>,
     file_picker_selected: usize,
+
+    /// Selected row of the custom field picker opened by `yf` (None = closed).
+    field_picker: Option<usize>,
+
+    /// Password generator overlay opened by `<Space>kg` (None = closed).
+    password_generator: Option<PasswordGenerator>,
+
+    /// Auto-lock and clipboard settings (`:set`).
+    settings: KeePassSettings,
 }
 
 impl KeePassTool {
@@ -118,6 +147,7 @@ impl KeePassTool {
         sidebar.reload(&conn)?;
 
         let clipboard = arboard::Clipboard::new().ok();
+        let settings = model::load_settings(&conn)?;
 
         Ok(Self {
             sidebar,
@@ -133,18 +163,23 @@ impl KeePassTool {
             clipboard,
             clipboard_set_at: None,
             clipboard_is_sensitive: false,
-            clipboard_notification: None,
-            notification_shown_at: None,
+            staged_password: None,
+            toasts: Vec::new(),
             pending_yank: false,
             search_active: false,
             search_query: String::new(),
             search_results: Vec::new(),
             search_selected: 0,
+            search_all_fields: true,
             file_picker_active: false,
             file_picker_entries: Vec::new(),
             file_picker_query: String::new(),
             file_picker_filtered: Vec::new(),
+            file_picker_positions: Vec::new(),
             file_picker_selected: 0,
+            field_picker: None,
+            password_generator: None,
+            settings,
         })
     }
 
@@ -155,9 +190,13 @@ impl KeePassTool {
 
     // ── File opening ─────────────────────────────────────────────────
 
-    /// Start the process of opening a .kdbx file.
-    fn start_open_file(&mut self, file_path: &str) {
+    /// Start the process of opening a .kdbx file. Without an explicit key
+    /// file, the one remembered for this vault (if any) is used.
+    fn start_open_file(&mut self, file_path: &str, key_file: Option<&str>) {
         let path = shellexpand(file_path);
+        let key_file = key_file
+            .map(shellexpand)
+            .or_else(|| self.stored_key_file(&path));
 
         if is_demo_vault_path(&path) {
             self.open_demo_vault(&path);
@@ -183,6 +222,7 @@ impl KeePassTool {
                         self.input_prompt = Some(InputPrompt::MasterPassword {
                             buffer: String::new(),
                             file_path: path,
+                            key_file,
                             error: Some("PIN expired. Please enter your master password.".into()),
                             paste_focused: false,
                         });
@@ -196,11 +236,56 @@ impl KeePassTool {
         self.input_prompt = Some(InputPrompt::MasterPassword {
             buffer: String::new(),
             file_path: path,
+            key_file,
             error: None,
             paste_focused: false,
         });
     }
 
+    /// `:keyfile [clear]`: show or forget the key file remembered for the
+    /// open vault, or for the selected sidebar file when none is open.
+    fn apply_key_file_command(&mut self, args: &str) {
+        let path = match (&self.vault, self.sidebar.selected_file()) {
+            (Some(vault), _) => vault.file_path.clone(),
+            (None, Some(file)) => file.file_path.clone(),
+            (None, None) => {
+                self.notify_error("No vault selected");
+                return;
+            }
+        };
+        let file = match model::get_file_by_path(&self.conn, &path) {
+            Ok(Some(file)) => file,
+            Ok(None) => {
+                self.notify("No key file remembered");
+                return;
+            }
+            Err(e) => {
+                self.notify_error(format!("Key file lookup failed: {e}"));
+                return;
+            }
+        };
+        match (args, file.key_file_path) {
+            ("", Some(key_file)) => self.notify(format!("Key file: {key_file}")),
+            ("", None) | ("clear", None) => self.notify("No key file remembered"),
+            ("clear", Some(_)) => match model::set_key_file(&self.conn, file.id, None) {
+                Ok(()) => {
+                    let _ = self.sidebar.reload(&self.conn);
+                    self.notify("Key file forgotten");
+                }
+                Err(e) => self.notify_error(format!("Failed to clear key file: {e}")),
+            },
+            _ => self.notify_error("Usage: :keyfile [clear]"),
+        }
+    }
+
+    /// The key file path remembered for a vault, if any.
+    fn stored_key_file(&self, file_path: &str) -> Option<String> {
+        model::get_file_by_path(&self.conn, file_path)
+            .ok()
+            .flatten()
+            .and_then(|file| file.key_file_path)
+    }
+
     /// Open an in-memory demo vault for screenshot/demo mode.
     fn open_demo_vault(&mut self, file_path: &str) {
         let roots = vec![
@@ -260,6 +345,7 @@ impl KeePassTool {
             selected: 0,
             file_path: file_path.to_string(),
             vault_name: "Demo Vault".to_string(),
+            visible_lines: Cell::new(20),
         };
         vault.rebuild_flat_view();
 
@@ -279,9 +365,14 @@ impl KeePassTool {
         self.update_detail_from_selection();
     }
 
-    /// Actually open the vault with the given password.
-    fn open_vault_with_password(&mut self, file_path: &str, password: &str) -> Result<(), String> {
-        match VaultState::open(file_path, password) {
+    /// Actually open the vault with the given password and optional key file.
+    fn open_vault_with_password(
+        &mut self,
+        file_path: &str,
+        password: &str,
+        key_file: Option<&str>,
+    ) -> Result<(), String> {
+        match VaultState::open(file_path, password, key_file) {
             Ok(vault) => {
                 // Update the DB history
                 let display_name = Path::new(file_path)
@@ -293,6 +384,7 @@ impl KeePassTool {
                     .map_err(|e| e.to_string())?;
 
                 let _ = model::touch_file(&self.conn, file_id);
+                let _ = model::set_key_file(&self.conn, file_id, key_file);
                 let _ = self.sidebar.reload(&self.conn);
 
                 self.vault = Some(vault);
@@ -313,10 +405,24 @@ impl KeePassTool {
         }
     }
 
+    /// Lock right away (`<Space>kl` / `:lock`).
+    fn lock_now(&mut self) {
+        if self.vault.is_none() {
+            self.notify("No vault open");
+        } else if !self.locked {
+            self.lock_vault();
+            self.notify("Vault locked");
+        }
+    }
+
     /// Lock the vault (clear sensitive data from detail, keep tree structure).
     fn lock_vault(&mut self) {
         if self.vault.is_some() {
             self.locked = true;
+            self.field_picker = None;
+            self.password_generator = None;
+            self.cancel_staged_copy();
+            self.close_search();
             self.detail.clear();
             // Don't clear vault tree — we just prevent access until re-unlock
         }
@@ -329,29 +435,275 @@ impl KeePassTool {
         }
         if let Some(ref vault) = self.vault {
             let file_path = vault.file_path.clone();
-            self.start_open_file(&file_path);
+            self.start_open_file(&file_path, None);
         }
     }
 
     // ── Clipboard ────────────────────────────────────────────────────
 
     fn copy_to_clipboard(&mut self, text: &str, label: &str, sensitive: bool) {
+        // Any new copy supersedes a pending `yl` password
+        self.cancel_staged_copy();
         if let Some(ref mut cb) = self.clipboard {
             if cb.set_text(text.to_string()).is_ok() {
-                self.clipboard_notification = Some(format!("Copied {label}"));
-                self.notification_shown_at = Some(Instant::now());
+                self.toasts.push(Toast::success(format!("Copied {label}")));
                 self.clipboard_is_sensitive = sensitive;
                 if sensitive {
                     self.clipboard_set_at = Some(Instant::now());
                 }
             }
+        } else {
+            self.notify_error("Clipboard unavailable");
+        }
+    }
+
+    /// `yl`: copy the username now and queue the password to replace it
+    /// after `LOGIN_STAGE_SECS`. A second `yl` copies the password at once.
+    fn copy_login(&mut self) {
+        if self.staged_password.is_some() {
+            self.copy_staged_password();
+            return;
+        }
+        let Some((username, password)) = self
+            .detail
+            .details
+            .as_ref()
+            .map(|d| (d.username.clone(), d.password.clone()))
+        else {
+            return;
+        };
+        if self.clipboard.is_none() {
+            self.notify_error("Clipboard unavailable");
+            return;
+        }
+
+        self.copy_to_clipboard(&username, "username", false);
+        if !password.is_empty() {
+            self.staged_password = Some((password, Instant::now()));
+            self.notify(format!(
+                "Copied username, password in {LOGIN_STAGE_SECS}s (yl: now)"
+            ));
+        }
+    }
+
+    /// Copy the password queued by `yl` (sensitive: auto-clears).
+    fn copy_staged_password(&mut self) {
+        if let Some((mut password, _)) = self.staged_password.take() {
+            self.copy_to_clipboard(&password, "password", true);
+            password.zeroize();
+        }
+    }
+
+    fn cancel_staged_copy(&mut self) {
+        if let Some((mut password, _)) = self.staged_password.take() {
+            password.zeroize();
+        }
+    }
+
+    fn notify(&mut self, message: impl Into<String>) {
+        self.toasts.push(Toast::info(message));
+    }
+
+    fn notify_error(&mut self, message: impl Into<String>) {
+        self.toasts.push(Toast::error(message));
+    }
+
+    /// Copy the selected entry's current TOTP code (sensitive: auto-clears).
+    fn copy_totp(&mut self) {
+        match self.detail.current_totp() {
+            Some((code, _)) => self.copy_to_clipboard(&code, "TOTP code", true),
+            None if self.detail.details.is_some() => self.notify("No TOTP configured"),
+            None => {}
+        }
+    }
+
+    // ── Custom field picker ──────────────────────────────────────────
+
+    /// Open the picker listing the selected entry's custom fields.
+    fn open_field_picker(&mut self) {
+        match self.detail.details {
+            Some(ref details) if !details.custom_fields.is_empty() => {
+                self.field_picker = Some(0);
+            }
+            Some(_) => self.notify("No custom fields"),
+            None => {}
+        }
+    }
+
+    fn handle_field_picker_key(&mut self, key: KeyEvent, selected: usize) -> Action {
+        let count = self
+            .detail
+            .details
+            .as_ref()
+            .map_or(0, |d| d.custom_fields.len());
+        if count == 0 {
+            self.field_picker = None;
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.field_picker = None,
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
+                self.field_picker = Some((selected + 1) % count);
+            }
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => {
+                self.field_picker = Some((selected + count - 1) % count);
+            }
+            KeyCode::Enter => {
+                self.field_picker = None;
+                if let Some((name, value, is_protected)) = self
+                    .detail
+                    .details
+                    .as_ref()
+                    .and_then(|d| d.custom_fields.get(selected))
+                    .cloned()
+                {
+                    // Protected fields are cleared from the clipboard like passwords
+                    self.copy_to_clipboard(&value, &name, is_protected);
+                }
+            }
+            _ => {}
         }
+        Action::None
+    }
+
+    // ── Settings ─────────────────────────────────────────────────────
+
+    /// Handle `:set <name> <value>`. Invalid values are rejected with a
+    /// message and the current setting is kept.
+    fn apply_setting(&mut self, args: &str) {
+        let (name, value) = match args.split_once(' ') {
+            Some((name, value)) => (name, value.trim()),
+            None => (args, ""),
+        };
+        let mut settings = self.settings.clone();
+
+        match (name, value) {
+            ("", _) => {
+                let autolock = if self.settings.auto_lock_enabled {
+                    format!("{}s", self.settings.auto_lock_secs)
+                } else {
+                    "off".to_string()
+                };
+                let on_off = |on: bool| if on { "on" } else { "off" };
+                let reveal = match self.settings.reveal_timeout_secs {
+                    0 => "off".to_string(),
+                    secs => format!("{secs}s"),
+                };
+                self.notify(format!(
+                    "autolock={autolock} clipclear={}s lockonblur={} revealtimeout={reveal}",
+                    self.settings.clipboard_clear_secs,
+                    on_off(self.settings.lock_on_blur)
+                ));
+                return;
+            }
+            ("autolock", "off") => settings.auto_lock_enabled = false,
+            ("autolock", "on") => settings.auto_lock_enabled = true,
+            ("autolock", secs) => match parse_secs(secs, AUTO_LOCK_RANGE) {
+                Some(secs) => {
+                    settings.auto_lock_enabled = true;
+                    settings.auto_lock_secs = secs;
+                }
+                None => {
+                    self.notify_error(format!(
+                        "Invalid autolock: use on, off or {}-{} seconds",
+                        AUTO_LOCK_RANGE.start(),
+                        AUTO_LOCK_RANGE.end()
+                    ));
+                    return;
+                }
+            },
+            ("lockonblur", "on") => settings.lock_on_blur = true,
+            ("lockonblur", "off") => settings.lock_on_blur = false,
+            ("lockonblur", _) => {
+                self.notify_error("Invalid lockonblur: use on or off");
+                return;
+            }
+            ("revealtimeout", "off") => settings.reveal_timeout_secs = 0,
+            ("revealtimeout", secs) => match parse_secs(secs, REVEAL_TIMEOUT_RANGE) {
+                Some(secs) => settings.reveal_timeout_secs = secs,
+                None => {
+                    self.notify_error(format!(
+                        "Invalid revealtimeout: use off or {}-{} seconds",
+                        REVEAL_TIMEOUT_RANGE.start(),
+                        REVEAL_TIMEOUT_RANGE.end()
+                    ));
+                    return;
+                }
+            },
+            ("clipclear", secs) => match parse_secs(secs, CLIPBOARD_CLEAR_RANGE) {
+                Some(secs) => settings.clipboard_clear_secs = secs,
+                None => {
+                    self.notify_error(format!(
+                        "Invalid clipclear: use {}-{} seconds",
+                        CLIPBOARD_CLEAR_RANGE.start(),
+                        CLIPBOARD_CLEAR_RANGE.end()
+                    ));
+                    return;
+                }
+            },
+            _ => {
+                self.notify_error(format!("Unknown setting: {name}"));
+                return;
+            }
+        }
+
+        match model::save_settings(&self.conn, &settings) {
+            Ok(()) => {
+                self.settings = settings;
+                self.notify(format!("Set {name}"));
+            }
+            Err(e) => self.notify_error(format!("Failed to save setting: {e}")),
+        }
+    }
+
+    // ── Password generator ───────────────────────────────────────────
+
+    fn handle_generator_key(&mut self, key: KeyEvent) -> Action {
+        let Some(ref mut generator) = self.password_generator else {
+            return Action::None;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.password_generator = None,
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => generator.move_down(),
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => generator.move_up(),
+            KeyCode::Char('h') | KeyCode::Left
+                if generator.selected_row() == GeneratorRow::Length =>
+            {
+                generator.adjust_length(-1);
+            }
+            KeyCode::Char('l') | KeyCode::Right
+                if generator.selected_row() == GeneratorRow::Length =>
+            {
+                generator.adjust_length(1);
+            }
+            KeyCode::Char('H') if generator.selected_row() == GeneratorRow::Length => {
+                generator.adjust_length(-8);
+            }
+            KeyCode::Char('L') if generator.selected_row() == GeneratorRow::Length => {
+                generator.adjust_length(8);
+            }
+            KeyCode::Char(' ')
+            | KeyCode::Char('h')
+            | KeyCode::Char('l')
+            | KeyCode::Left
+            | KeyCode::Right => generator.toggle_selected(),
+            KeyCode::Char('r') => generator.regenerate(),
+            KeyCode::Enter => {
+                let password = generator.preview.clone();
+                self.password_generator = None;
+                self.copy_to_clipboard(&password, "password", true);
+            }
+            _ => {}
+        }
+        Action::None
     }
 
     fn clear_clipboard_if_expired(&mut self) {
         if self.clipboard_is_sensitive {
             if let Some(set_at) = self.clipboard_set_at {
-                if set_at.elapsed().as_secs() >= CLIPBOARD_CLEAR_SECS {
+                if set_at.elapsed().as_secs() >= self.settings.clipboard_clear_secs {
                     if let Some(ref mut cb) = self.clipboard {
                         let _ = cb.set_text(String::new());
                     }
@@ -375,17 +727,24 @@ impl KeePassTool {
 
     fn filter_search(&mut self) {
         if let Some(ref vault) = self.vault {
-            let query = self.search_query.to_lowercase();
-            self.search_results = vault
+            let query = &self.search_query;
+            let all_fields = self.search_all_fields;
+            let mut scored: Vec<(i64, SearchableEntry)> = vault
                 .collect_searchable_entries()
                 .into_iter()
-                .filter(|e| {
+                .filter_map(|mut e| {
                     if query.is_empty() {
-                        return true;
+                        return Some((0, e));
                     }
-                    e.title.to_lowercase().contains(&query)
+                    let (field, m) = e.best_match(query, all_fields)?;
+                    e.matched_field = Some(field);
+                    e.match_positions = m.positions;
+                    Some((m.score, e))
                 })
                 .collect();
+            // Stable: equal scores (and the empty query) keep tree order
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            self.search_results = scored.into_iter().map(|(_, e)| e).collect();
             if self.search_results.is_empty() {
                 self.search_selected = 0;
             } else if self.search_selected >= self.search_results.len() {
@@ -437,33 +796,32 @@ impl KeePassTool {
         self.file_picker_entries.clear();
         self.file_picker_query.clear();
         self.file_picker_filtered.clear();
+        self.file_picker_positions.clear();
         self.file_picker_selected = 0;
 
         // Recursively scan for .kdbx files
         if let Ok(entries) = scan_kdbx_files(&keepass_dir) {
             self.file_picker_entries = entries;
-            self.file_picker_filtered = (0..self.file_picker_entries.len()).collect();
+            self.filter_file_picker();
         }
 
         self.file_picker_active = true;
     }
 
     fn filter_file_picker(&mut self) {
-        let query = self.file_picker_query.to_lowercase();
-        self.file_picker_filtered = self
+        let mut scored: Vec<(usize, FuzzyMatch)> = self
             .file_picker_entries
             .iter()
             .enumerate()
-            .filter(|(_, path)| {
-                if query.is_empty() {
-                    return true;
-                }
-                path.file_name()
-                    .map(|n| n.to_string_lossy().to_lowercase().contains(&query))
-                    .unwrap_or(false)
+            .filter_map(|(i, path)| {
+                let name = path.file_name()?.to_string_lossy();
+                Some((i, telescope::fuzzy_match(&self.file_picker_query, &name)?))
             })
-            .map(|(i, _)| i)
             .collect();
+        // Stable: equal scores (and the empty query) keep scan order
+        scored.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+        (self.file_picker_filtered, self.file_picker_positions) =
+            scored.into_iter().map(|(i, m)| (i, m.positions)).unzip();
         if self.file_picker_filtered.is_empty() {
             self.file_picker_selected = 0;
         } else if self.file_picker_selected >= self.file_picker_filtered.len() {
@@ -477,6 +835,10 @@ impl KeePassTool {
         if let Some(ref vault) = self.vault {
             let details = vault.selected_details().cloned();
             self.detail.set_entry(details);
+            self.detail.breadcrumb = vault
+                .selected_node()
+                .map(|node| vault.breadcrumb(&node.path))
+                .unwrap_or_default();
         }
     }
 
@@ -492,6 +854,7 @@ impl KeePassTool {
             InputPrompt::MasterPassword {
                 mut buffer,
                 file_path,
+                key_file,
                 paste_focused,
                 ..
             } => match key.code {
@@ -503,6 +866,7 @@ impl KeePassTool {
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer,
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused: !paste_focused,
                     });
@@ -517,17 +881,20 @@ impl KeePassTool {
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer,
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused: false,
                     });
                 }
                 KeyCode::Enter => {
-                    let result = self.open_vault_with_password(&file_path, &buffer);
+                    let result =
+                        self.open_vault_with_password(&file_path, &buffer, key_file.as_deref());
                     buffer.zeroize();
                     if let Err(e) = result {
                         self.input_prompt = Some(InputPrompt::MasterPassword {
                             buffer: String::new(),
                             file_path,
+                            key_file,
                             error: Some(e),
                             paste_focused: false,
                         });
@@ -539,6 +906,7 @@ impl KeePassTool {
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer,
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused: false,
                     });
@@ -548,6 +916,7 @@ impl KeePassTool {
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer,
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused: false,
                     });
@@ -556,6 +925,7 @@ impl KeePassTool {
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer,
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused,
                     });
@@ -570,9 +940,11 @@ impl KeePassTool {
                 KeyCode::Esc => {
                     buffer.zeroize();
                     // Prompt dismissed, ask for full password instead
+                    let key_file = self.stored_key_file(&file_path);
                     self.input_prompt = Some(InputPrompt::MasterPassword {
                         buffer: String::new(),
                         file_path,
+                        key_file,
                         error: None,
                         paste_focused: false,
                     });
@@ -591,8 +963,11 @@ impl KeePassTool {
                             {
                                 match crypto::decrypt_with_pin(enc, salt, nonce, &pin) {
                                     Ok(mut password) => {
-                                        let result =
-                                            self.open_vault_with_password(&file_path, &password);
+                                        let result = self.open_vault_with_password(
+                                            &file_path,
+                                            &password,
+                                            file.key_file_path.as_deref(),
+                                        );
                                         password.zeroize();
                                         if let Err(e) = result {
                                             self.input_prompt = Some(InputPrompt::PinInput {
@@ -727,6 +1102,10 @@ impl KeePassTool {
             KeyCode::Enter => {
                 self.confirm_search_selection();
             }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search_all_fields = !self.search_all_fields;
+                self.filter_search();
+            }
             KeyCode::Char(c) => {
                 self.search_query.push(c);
                 self.filter_search();
@@ -764,7 +1143,7 @@ impl KeePassTool {
                     if let Some(path) = self.file_picker_entries.get(idx) {
                         let path_str = path.to_string_lossy().to_string();
                         self.file_picker_active = false;
-                        self.start_open_file(&path_str);
+                        self.start_open_file(&path_str, None);
                     }
                 }
             }
@@ -814,23 +1193,29 @@ impl KeePassTool {
         }
 
         // Handle confirm delete
-        if self.sidebar.confirm_delete {
-            match key.code {
-                KeyCode::Char('y') | KeyCode::Char('Y') => {
-                    if let Some(file) = self.sidebar.selected_file() {
-                        let id = file.id;
-                        let _ = model::delete_file(&self.conn, id);
-                        let _ = self.sidebar.reload(&self.conn);
-                    }
-                    self.sidebar.confirm_delete = false;
-                }
-                _ => {
-                    self.sidebar.confirm_delete = false;
+        if let Some(prompt) = &self.sidebar.delete_prompt {
+            if let Some(confirmed) = prompt.handle_key(key) {
+                self.sidebar.delete_prompt = None;
+                if confirmed && let Some(file) = self.sidebar.selected_file() {
+                    let id = file.id;
+                    let _ = model::delete_file(&self.conn, id);
+                    let _ = self.sidebar.reload(&self.conn);
                 }
             }
             return Action::None;
         }
 
+        // Pin / unpin the selected file
+        if key.code == KeyCode::Char('P') {
+            match self.sidebar.toggle_pin_selected(&self.conn) {
+                Ok(Some(true)) => self.notify("Pinned"),
+                Ok(Some(false)) => self.notify("Unpinned"),
+                Ok(None) => {}
+                Err(e) => self.notify_error(format!("Failed to pin file: {e}")),
+            }
+            return Action::None;
+        }
+
         // Standard normal-mode handling
         let action = rstools_core::keybinds::process_normal_key(key, &mut self.key_state);
 
@@ -852,24 +1237,24 @@ impl KeePassTool {
                 Action::None
             }
             Action::HalfPageDown => {
-                self.sidebar.half_page_down(20);
+                self.sidebar.half_page_down();
                 Action::None
             }
             Action::HalfPageUp => {
-                self.sidebar.half_page_up(20);
+                self.sidebar.half_page_up();
                 Action::None
             }
             Action::Confirm => {
                 // Open the selected file
                 if let Some(file) = self.sidebar.selected_file() {
                     let path = file.file_path.clone();
-                    self.start_open_file(&path);
+                    self.start_open_file(&path, None);
                 }
                 Action::None
             }
             Action::Delete => {
                 if self.sidebar.selected_file().is_some() {
-                    self.sidebar.confirm_delete = true;
+                    self.sidebar.delete_prompt = Some(ConfirmPrompt::new("Delete from history?"));
                 }
                 Action::None
             }
@@ -941,6 +1326,19 @@ impl KeePassTool {
                     }
                     return Action::None;
                 }
+                KeyCode::Char('t') => {
+                    // Yank the current TOTP code
+                    self.copy_totp();
+                    return Action::None;
+                }
+                KeyCode::Char('f') => {
+                    self.open_field_picker();
+                    return Action::None;
+                }
+                KeyCode::Char('l') => {
+                    self.copy_login();
+                    return Action::None;
+                }
                 _ => {
                     // Invalid yank sequence, fall through to normal processing
                 }
@@ -953,11 +1351,15 @@ impl KeePassTool {
             return Action::None;
         }
 
-        // Check for 'p' to toggle password visibility
+        // Check for 'p' to toggle password visibility, 'P' for all sensitive fields
         if key.code == KeyCode::Char('p') && key.modifiers == KeyModifiers::NONE {
             self.detail.toggle_password();
             return Action::None;
         }
+        if key.code == KeyCode::Char('P') {
+            self.detail.toggle_reveal_all();
+            return Action::None;
+        }
 
         let action = rstools_core::keybinds::process_normal_key(key, &mut self.key_state);
 
@@ -992,14 +1394,14 @@ impl KeePassTool {
             }
             Action::HalfPageDown => {
                 if let Some(ref mut vault) = self.vault {
-                    vault.half_page_down(20);
+                    vault.half_page_down();
                 }
                 self.update_detail_from_selection();
                 Action::None
             }
             Action::HalfPageUp => {
                 if let Some(ref mut vault) = self.vault {
-                    vault.half_page_up(20);
+                    vault.half_page_up();
                 }
                 self.update_detail_from_selection();
                 Action::None
@@ -1091,6 +1493,18 @@ impl KeePassTool {
                     }
                     return Action::None;
                 }
+                KeyCode::Char('t') => {
+                    self.copy_totp();
+                    return Action::None;
+                }
+                KeyCode::Char('f') => {
+                    self.open_field_picker();
+                    return Action::None;
+                }
+                KeyCode::Char('l') => {
+                    self.copy_login();
+                    return Action::None;
+                }
                 _ => {}
             }
         }
@@ -1104,17 +1518,21 @@ impl KeePassTool {
             self.detail.toggle_password();
             return Action::None;
         }
+        if key.code == KeyCode::Char('P') {
+            self.detail.toggle_reveal_all();
+            return Action::None;
+        }
 
         match key.code {
-            KeyCode::Char('j') => {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.detail.scroll_down();
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.detail.scroll_up();
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 // Scroll to bottom (large number)
                 self.detail.scroll = 999;
                 Action::None
@@ -1123,13 +1541,10 @@ impl KeePassTool {
                 self.open_search();
                 Action::None
             }
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::Command, &key) => Action::SetMode(InputMode::Command),
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
             _ => {
                 let action = rstools_core::keybinds::process_normal_key(key, &mut self.key_state);
                 match action {
@@ -1165,6 +1580,14 @@ impl Tool for KeePassTool {
         self.mode
     }
 
+    fn pending_keys(&self) -> String {
+        let mut keys = self.key_state.pending_keys();
+        if self.pending_yank {
+            keys.push('y');
+        }
+        keys
+    }
+
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()> {
         model::init_db(conn)
     }
@@ -1174,12 +1597,17 @@ impl Tool for KeePassTool {
             WhichKeyEntry::action('o', "Open file picker"),
             WhichKeyEntry::action('e', "Toggle sidebar"),
             WhichKeyEntry::action('s', "Search entries"),
+            WhichKeyEntry::action('g', "Password generator"),
+            WhichKeyEntry::action('l', "Lock vault"),
         ]
     }
 
     fn telescope_items(&self) -> Vec<TelescopeItem> {
         let mut items = Vec::new();
-        if let Some(ref vault) = self.vault {
+        // A locked vault lists nothing
+        if let Some(ref vault) = self.vault
+            && !self.locked
+        {
             for entry in vault.collect_searchable_entries() {
                 items.push(TelescopeItem {
                     label: entry.title.clone(),
@@ -1191,6 +1619,28 @@ impl Tool for KeePassTool {
         items
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let title = id.strip_prefix("keepass:")?;
+        if self.locked {
+            return None;
+        }
+        let entry = self
+            .vault
+            .as_ref()?
+            .collect_searchable_entries()
+            .into_iter()
+            .find(|entry| entry.title == title)?;
+        let mut text = format!(
+            "Group: {}\nUsername: {}\nURL: {}",
+            entry.group_path, entry.username, entry.url
+        );
+        if !entry.notes.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&telescope::preview_lines(&entry.notes));
+        }
+        Some(text)
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         let Some(title) = id.strip_prefix("keepass:") else {
             return false;
@@ -1215,6 +1665,7 @@ impl Tool for KeePassTool {
             HelpEntry::with_section("Sidebar", "j / k", "Navigate up / down"),
             HelpEntry::with_section("Sidebar", "Enter", "Open selected file"),
             HelpEntry::with_section("Sidebar", "dd", "Remove file from history"),
+            HelpEntry::with_section("Sidebar", "P", "Pin / unpin file (pinned first)"),
             HelpEntry::with_section("Sidebar", "gg / G", "Go to top / bottom"),
             HelpEntry::with_section("Sidebar", "Ctrl-l", "Move focus to tree"),
             // Tree
@@ -1225,19 +1676,31 @@ impl Tool for KeePassTool {
             HelpEntry::with_section("Tree", "Ctrl-h", "Focus sidebar"),
             HelpEntry::with_section("Tree", "Ctrl-l", "Focus details"),
             HelpEntry::with_section("Tree", "/", "Search entries"),
+            HelpEntry::with_section("Tree", "Ctrl-f", "Search: toggle title / all fields"),
             // Detail
             HelpEntry::with_section("Detail", "j / k", "Scroll up / down"),
-            HelpEntry::with_section("Detail", "p", "Toggle password visibility"),
+            HelpEntry::with_section("Detail", "p", "Toggle password"),
+            HelpEntry::with_section("Detail", "P", "Reveal / mask all sensitive fields"),
             HelpEntry::with_section("Detail", "Ctrl-h", "Focus tree"),
             // Copy
             HelpEntry::with_section("Copy", "yu", "Copy username"),
-            HelpEntry::with_section("Copy", "yp", "Copy password (auto-clears 30s)"),
+            HelpEntry::with_section("Copy", "yp", "Copy password (auto-clears)"),
             HelpEntry::with_section("Copy", "yU", "Copy URL"),
+            HelpEntry::with_section("Copy", "yt", "Copy TOTP code (auto-clears)"),
+            HelpEntry::with_section("Copy", "yf", "Pick a custom field to copy"),
+            HelpEntry::with_section("Copy", "yl", "Copy username, then password 10s later"),
             // General
             HelpEntry::with_section("General", "<Space>ko", "File picker (~/keepass)"),
             HelpEntry::with_section("General", "<Space>ke", "Toggle sidebar"),
             HelpEntry::with_section("General", "<Space>ks", "Search entries"),
+            HelpEntry::with_section("General", "<Space>kg", "Password generator"),
+            HelpEntry::with_section("General", "<Space>kl / :lock", "Lock vault now"),
             HelpEntry::with_section("General", ":open <path>", "Open .kdbx file"),
+            HelpEntry::with_section("General", ":keyfile [clear]", "Show/forget key file"),
+            HelpEntry::with_section("General", ":set autolock <s|on|off>", "Auto-lock delay"),
+            HelpEntry::with_section("General", ":set clipclear <s>", "Clipboard clear delay"),
+            HelpEntry::with_section("General", ":set lockonblur <on|off>", "Lock on tool switch"),
+            HelpEntry::with_section("General", ":set revealtimeout <s|off>", "Re-mask delay"),
         ]
     }
 
@@ -1259,6 +1722,16 @@ impl Tool for KeePassTool {
             return self.handle_file_picker_key(key);
         }
 
+        // Handle custom field picker overlay
+        if let Some(selected) = self.field_picker {
+            return self.handle_field_picker_key(key, selected);
+        }
+
+        // Handle password generator overlay
+        if self.password_generator.is_some() {
+            return self.handle_generator_key(key);
+        }
+
         // Handle locked state
         if self.locked {
             if key.code == KeyCode::Enter {
@@ -1283,7 +1756,12 @@ impl Tool for KeePassTool {
     fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) -> Action {
         self.touch_activity();
 
-        if self.input_prompt.is_some() || self.search_active || self.file_picker_active {
+        if self.input_prompt.is_some()
+            || self.search_active
+            || self.file_picker_active
+            || self.field_picker.is_some()
+            || self.password_generator.is_some()
+        {
             return Action::None;
         }
 
@@ -1363,6 +1841,12 @@ impl Tool for KeePassTool {
             return;
         }
         ui::render_keepass_tool(frame, area, self);
+        if let Some(selected) = self.field_picker {
+            render_field_picker(frame, area, self, selected);
+        }
+        if let Some(ref generator) = self.password_generator {
+            render_password_generator(frame, area, generator);
+        }
     }
 
     fn handle_leader_action(&mut self, key: char) -> Option<Action> {
@@ -1384,6 +1868,14 @@ impl Tool for KeePassTool {
                 self.open_search();
                 Some(Action::None)
             }
+            'g' => {
+                self.password_generator = Some(PasswordGenerator::new());
+                Some(Action::None)
+            }
+            'l' => {
+                self.lock_now();
+                Some(Action::None)
+            }
             _ => None,
         }
     }
@@ -1395,10 +1887,11 @@ impl Tool for KeePassTool {
 
     fn tick(&mut self) {
         // Auto-lock check
-        if self.vault.is_some()
+        if self.settings.auto_lock_enabled
+            && self.vault.is_some()
             && !self.locked
             && self.input_prompt.is_none()
-            && self.last_activity.elapsed().as_secs() >= AUTO_LOCK_SECS
+            && self.last_activity.elapsed().as_secs() >= self.settings.auto_lock_secs
         {
             self.lock_vault();
         }
@@ -1406,24 +1899,48 @@ impl Tool for KeePassTool {
         // Clipboard auto-clear
         self.clear_clipboard_if_expired();
 
-        // Clear notification after 2 seconds
-        if let Some(shown_at) = self.notification_shown_at {
-            if shown_at.elapsed().as_secs() >= 2 {
-                self.clipboard_notification = None;
-                self.notification_shown_at = None;
-            }
+        // Swap the username copied by `yl` for the password
+        if self
+            .staged_password
+            .as_ref()
+            .is_some_and(|(_, at)| at.elapsed().as_secs() >= LOGIN_STAGE_SECS)
+        {
+            self.copy_staged_password();
+        }
+
+        // Re-mask revealed passwords and protected fields
+        if self.settings.reveal_timeout_secs > 0 {
+            let timeout = std::time::Duration::from_secs(self.settings.reveal_timeout_secs);
+            self.detail.remask_if_expired(timeout);
         }
     }
 
+    fn take_toasts(&mut self) -> Vec<Toast> {
+        std::mem::take(&mut self.toasts)
+    }
+
     fn handle_command(&mut self, cmd: &str) -> bool {
         let parts: Vec<&str> = cmd.splitn(2, ' ').collect();
         match parts.first() {
             Some(&"open") => {
-                if let Some(path) = parts.get(1) {
-                    self.start_open_file(path.trim());
+                if let Some(args) = parts.get(1) {
+                    let (path, key_file) = parse_open_args(args);
+                    self.start_open_file(&path, key_file.as_deref());
                 }
                 true
             }
+            Some(&"lock") => {
+                self.lock_now();
+                true
+            }
+            Some(&"keyfile") => {
+                self.apply_key_file_command(parts.get(1).map_or("", |args| args.trim()));
+                true
+            }
+            Some(&"set") => {
+                self.apply_setting(parts.get(1).map_or("", |args| args.trim()));
+                true
+            }
             _ => false,
         }
     }
@@ -1432,7 +1949,155 @@ impl Tool for KeePassTool {
         self.touch_activity();
     }
 
-    fn on_blur(&mut self) {}
+    fn on_blur(&mut self) {
+        if self.settings.lock_on_blur {
+            self.lock_vault();
+        }
+    }
+}
+
+// ── Custom field picker rendering ────────────────────────────────────
+
+fn render_field_picker(frame: &mut Frame, area: Rect, tool: &KeePassTool, selected: usize) {
+    use ratatui::{
+        layout::{Constraint, Flex, Layout},
+        style::{Color, Modifier, Style},
+        text::{Line, Span},
+        widgets::{Block, Borders, Clear, Paragraph},
+    };
+
+    let Some(ref details) = tool.detail.details else {
+        return;
+    };
+
+    let popup_width = 40u16.min(area.width.saturating_sub(4));
+    let popup_height = (details.custom_fields.len() as u16 + 2).min(area.height.saturating_sub(4));
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Copy Field ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().info));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    // Keep the selected row visible in short popups
+    let height = inner.height as usize;
+    let offset = selected.saturating_sub(height.saturating_sub(1));
+    let lines: Vec<Line> = details
+        .custom_fields
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(height)
+        .map(|(i, (name, _, is_protected))| {
+            let style = if i == selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(theme().info)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            let mut spans = vec![Span::styled(format!(" {name}"), style)];
+            if *is_protected {
+                spans.push(Span::styled(
+                    " (protected)",
+                    Style::default().fg(theme().muted),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+// ── Password generator rendering ─────────────────────────────────────
+
+fn render_password_generator(frame: &mut Frame, area: Rect, generator: &PasswordGenerator) {
+    use ratatui::{
+        layout::{Constraint, Flex, Layout},
+        style::{Color, Modifier, Style},
+        text::{Line, Span},
+        widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+    };
+
+    let popup_width = 50u16.min(area.width.saturating_sub(4));
+    let popup_height = 14u16.min(area.height.saturating_sub(4));
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Password Generator ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().info));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(GeneratorRow::ALL.len() as u16),
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let rows: Vec<Line> = GeneratorRow::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let value = match row {
+                GeneratorRow::Length => format!("< {} >", generator.length),
+                _ if generator.is_enabled(*row) => "[x]".to_string(),
+                _ => "[ ]".to_string(),
+            };
+            let style = if i == generator.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(theme().info)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            Line::from(Span::styled(format!(" {:<20}{value}", row.label()), style))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(rows), chunks[0]);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            " Preview",
+            Style::default().fg(theme().muted),
+        ))),
+        chunks[1],
+    );
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            generator.preview.as_str(),
+            Style::default().fg(theme().accent),
+        ))
+        .wrap(Wrap { trim: false })
+        .block(Block::default().padding(Padding::horizontal(1))),
+        chunks[2],
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            " h/l adjust · Space toggle · r regenerate · Enter copy",
+            Style::default().fg(theme().muted),
+        ))),
+        chunks[3],
+    );
 }
 
 // ── File picker overlay rendering ────────────────────────────────────
@@ -1501,7 +2166,7 @@ fn render_file_picker(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
             let path = &tool.file_picker_entries[idx];
             let is_selected = i == tool.file_picker_selected;
             let bg = if is_selected {
-                ratatui::style::Color::DarkGray
+                theme().muted
             } else {
                 ratatui::style::Color::Reset
             };
@@ -1510,24 +2175,31 @@ fn render_file_picker(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default();
+            let positions = tool
+                .file_picker_positions
+                .get(i)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let style = Style::default()
+                .fg(theme().text)
+                .bg(bg)
+                .add_modifier(if is_selected {
+                    Modifier::BOLD
+                } else {
+                    Modifier::empty()
+                });
 
-            Line::from(vec![
-                Span::styled(
-                    prefix,
-                    Style::default().fg(ratatui::style::Color::Yellow).bg(bg),
-                ),
-                Span::styled(
-                    name,
-                    Style::default()
-                        .fg(ratatui::style::Color::White)
-                        .bg(bg)
-                        .add_modifier(if is_selected {
-                            Modifier::BOLD
-                        } else {
-                            Modifier::empty()
-                        }),
-                ),
-            ])
+            let mut spans = vec![Span::styled(
+                prefix,
+                Style::default().fg(theme().accent).bg(bg),
+            )];
+            spans.extend(ui::highlight_matches(
+                &name,
+                positions,
+                style,
+                style.fg(theme().accent),
+            ));
+            Line::from(spans)
         })
         .collect();
 
@@ -1547,6 +2219,70 @@ fn shellexpand(path: &str) -> String {
     path.to_string()
 }
 
+/// Split `:open` arguments into the vault path and an optional key file
+/// (`<kdbx> <keyfile>`). Either path may be wrapped in `"` or `'` to keep
+/// its spaces. An unquoted argument naming an existing file is taken
+/// whole, so vault paths containing spaces still open.
+fn parse_open_args(args: &str) -> (String, Option<String>) {
+    let args = args.trim();
+    if Path::new(&shellexpand(args)).is_file() {
+        return (args.to_string(), None);
+    }
+    let mut words = split_quoted(args);
+    match words.len() {
+        2 => {
+            let key_file = words.pop();
+            (words.remove(0), key_file)
+        }
+        1 => (words.remove(0), None),
+        // Unquoted spaces: the last word is the key file
+        _ => match args.rsplit_once(char::is_whitespace) {
+            Some((path, key_file)) if !path.trim().is_empty() => {
+                (path.trim().to_string(), Some(key_file.to_string()))
+            }
+            _ => (args.to_string(), None),
+        },
+    }
+}
+
+/// Split on whitespace, keeping `"..."` and `'...'` spans (quotes removed)
+/// in one word.
+fn split_quoted(args: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in args.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            None => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Parse a whole number of seconds within `range`.
+fn parse_secs(value: &str, range: std::ops::RangeInclusive<u64>) -> Option<u64> {
+    value.parse().ok().filter(|secs| range.contains(secs))
+}
+
 fn is_demo_vault_path(path: &str) -> bool {
     path.starts_with("/demo/vaults/") && path.ends_with(".kdbx")
 }
@@ -1575,6 +2311,7 @@ fn demo_entry(
                 ("Environment".to_string(), "Demo".to_string(), false),
                 ("Owner".to_string(), "rstools".to_string(), false),
             ],
+            expires_at: None,
         }),
     }
 }
@@ -1619,3 +2356,236 @@ fn scan_kdbx_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
     results.sort();
     Ok(results)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::db::open_memory_db;
+    use std::time::Duration;
+
+    fn press(tool: &mut KeePassTool, c: char) {
+        tool.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    fn last_toast(tool: &KeePassTool) -> Option<&str> {
+        tool.toasts.last().map(|t| t.message.as_str())
+    }
+
+    /// A tool with the in-memory demo vault open and no clipboard.
+    fn demo_tool() -> KeePassTool {
+        let mut tool = KeePassTool::new(open_memory_db().unwrap()).unwrap();
+        tool.clipboard = None;
+        tool.handle_command("open /demo/vaults/demo.kdbx");
+        assert!(tool.vault.is_some());
+        tool
+    }
+
+    fn ago(secs: u64) -> Instant {
+        Instant::now() - Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_yank_totp() {
+        let mut tool = KeePassTool::new(open_memory_db().unwrap()).unwrap();
+        tool.clipboard = None;
+        tool.focus = ToolFocus::Detail;
+
+        tool.detail.set_entry(Some(EntryDetails::default()));
+        press(&mut tool, 'y');
+        press(&mut tool, 't');
+        assert_eq!(last_toast(&tool), Some("No TOTP configured"));
+
+        let otp = "otpauth://totp/x?secret=GEZDGNBVGY3TQOJQ".to_string();
+        tool.detail.set_entry(Some(EntryDetails {
+            custom_fields: vec![("otp".into(), otp, true)],
+            ..Default::default()
+        }));
+        let (code, remaining) = tool.detail.current_totp().unwrap();
+        assert_eq!(code.len(), 6);
+        assert!((1..=30).contains(&remaining));
+
+        // The code reaches the copy path (no clipboard in tests)
+        press(&mut tool, 'y');
+        press(&mut tool, 't');
+        assert_eq!(last_toast(&tool), Some("Clipboard unavailable"));
+        assert!(!tool.pending_yank);
+    }
+
+    #[test]
+    fn test_auto_lock_and_clipboard_clear_delays() {
+        let mut tool = demo_tool();
+        tool.handle_command("set autolock 60");
+        assert_eq!(last_toast(&tool), Some("Set autolock"));
+        tool.handle_command("set clipclear 5");
+        tool.handle_command("set autolock 5");
+        assert_eq!(
+            last_toast(&tool),
+            Some("Invalid autolock: use on, off or 10-86400 seconds")
+        );
+        assert_eq!(tool.settings.auto_lock_secs, 60);
+
+        // Not idle long enough yet
+        tool.last_activity = ago(59);
+        tool.tick();
+        assert!(!tool.locked);
+        tool.last_activity = ago(60);
+        tool.tick();
+        assert!(tool.locked);
+
+        // A sensitive copy is forgotten after `clipclear`
+        tool.clipboard_is_sensitive = true;
+        tool.clipboard_set_at = Some(ago(4));
+        tool.tick();
+        assert!(tool.clipboard_set_at.is_some());
+        tool.clipboard_set_at = Some(ago(5));
+        tool.tick();
+        assert!(tool.clipboard_set_at.is_none());
+        assert!(!tool.clipboard_is_sensitive);
+
+        // Settings survive a restart, and `off` disables auto-lock
+        tool.handle_command("set autolock off");
+        let settings = model::load_settings(&tool.conn).unwrap();
+        assert!(!settings.auto_lock_enabled);
+        assert_eq!(settings.auto_lock_secs, 60);
+        assert_eq!(settings.clipboard_clear_secs, 5);
+    }
+
+    #[test]
+    fn test_manual_lock_and_lock_on_blur() {
+        let mut tool = KeePassTool::new(open_memory_db().unwrap()).unwrap();
+        tool.handle_command("lock");
+        assert_eq!(last_toast(&tool), Some("No vault open"));
+
+        let mut tool = demo_tool();
+        assert!(tool.detail.details.is_some());
+        tool.handle_command("lock");
+        assert!(tool.locked);
+        assert!(tool.detail.details.is_none());
+        assert_eq!(last_toast(&tool), Some("Vault locked"));
+
+        // `<Space>kl` takes the same path
+        let mut tool = demo_tool();
+        assert_eq!(tool.handle_leader_action('l'), Some(Action::None));
+        assert!(tool.locked);
+
+        // Switching tools only locks with `lockonblur` on
+        let mut tool = demo_tool();
+        tool.on_blur();
+        assert!(!tool.locked);
+        tool.handle_command("set lockonblur on");
+        tool.on_blur();
+        assert!(tool.locked);
+        // Locked: keys other than Enter are ignored
+        press(&mut tool, 'j');
+        assert!(tool.locked && tool.input_prompt.is_none());
+    }
+
+    #[test]
+    fn test_telescope_preview() {
+        let mut tool = demo_tool();
+        let vault = tool.vault.as_ref().unwrap();
+        let entry = vault.collect_searchable_entries().remove(0);
+        let id = format!("keepass:{}", entry.title);
+        let preview = tool.telescope_preview(&id).unwrap();
+        let expected = format!("Group: {}\nUsername: {}", entry.group_path, entry.username);
+        assert!(preview.starts_with(&expected));
+        assert_eq!(tool.telescope_preview("keepass:No such entry"), None);
+
+        tool.handle_command("lock");
+        assert_eq!(tool.telescope_preview(&id), None);
+        assert!(tool.telescope_items().is_empty());
+    }
+
+    #[test]
+    fn test_copy_login_stages_password() {
+        let mut tool = demo_tool();
+        press(&mut tool, 'y');
+        press(&mut tool, 'l');
+        assert_eq!(last_toast(&tool), Some("Clipboard unavailable"));
+        assert!(tool.staged_password.is_none());
+
+        // The staged password replaces the username after LOGIN_STAGE_SECS
+        tool.staged_password = Some(("hunter2".into(), ago(LOGIN_STAGE_SECS - 1)));
+        tool.tick();
+        assert!(tool.staged_password.is_some());
+        tool.staged_password = Some(("hunter2".into(), ago(LOGIN_STAGE_SECS)));
+        tool.tick();
+        assert!(tool.staged_password.is_none());
+
+        // A second `yl` copies it at once
+        tool.staged_password = Some(("hunter2".into(), Instant::now()));
+        press(&mut tool, 'y');
+        press(&mut tool, 'l');
+        assert!(tool.staged_password.is_none());
+
+        // Any other copy or locking drops it
+        tool.staged_password = Some(("hunter2".into(), Instant::now()));
+        press(&mut tool, 'y');
+        press(&mut tool, 'u');
+        assert!(tool.staged_password.is_none());
+        tool.staged_password = Some(("hunter2".into(), Instant::now()));
+        tool.handle_command("lock");
+        assert!(tool.staged_password.is_none());
+    }
+
+    #[test]
+    fn test_parse_open_args() {
+        let cases = [
+            (" a.kdbx ", "a.kdbx", None),
+            ("a.kdbx a.keyx", "a.kdbx", Some("a.keyx")),
+            ("\"my a.kdbx\" 'my a.keyx'", "my a.kdbx", Some("my a.keyx")),
+            ("a.kdbx \"my a.keyx\"", "a.kdbx", Some("my a.keyx")),
+            ("'my a.kdbx'", "my a.kdbx", None),
+            // Unquoted spaces: the last word is the key file
+            ("my a.kdbx a.keyx", "my a.kdbx", Some("a.keyx")),
+        ];
+        for (args, path, key_file) in cases {
+            let (parsed_path, parsed_key) = parse_open_args(args);
+            assert_eq!(parsed_path, path, "{args}");
+            assert_eq!(parsed_key.as_deref(), key_file, "{args}");
+        }
+    }
+
+    #[test]
+    fn test_keyfile_clear() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let id = model::upsert_file(&conn, "/vaults/work.kdbx", "work").unwrap();
+        model::set_key_file(&conn, id, Some("/keys/work.keyx")).unwrap();
+
+        let mut tool = KeePassTool::new(conn).unwrap();
+        tool.handle_command("keyfile");
+        assert_eq!(last_toast(&tool), Some("Key file: /keys/work.keyx"));
+        tool.handle_command("keyfile clear");
+        assert_eq!(last_toast(&tool), Some("Key file forgotten"));
+        assert_eq!(tool.stored_key_file("/vaults/work.kdbx"), None);
+        tool.handle_command("keyfile clear");
+        assert_eq!(last_toast(&tool), Some("No key file remembered"));
+    }
+
+    #[test]
+    fn test_delete_file_from_history() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        model::upsert_file(&conn, "/vaults/work.kdbx", "work").unwrap();
+        model::upsert_file(&conn, "/vaults/home.kdbx", "home").unwrap();
+        let mut tool = KeePassTool::new(conn).unwrap();
+        let selected = tool.sidebar.selected_file().unwrap().file_path.clone();
+
+        // `n` keeps the file, `y` removes it
+        press(&mut tool, 'd');
+        press(&mut tool, 'd');
+        assert!(tool.sidebar.delete_prompt.is_some());
+        press(&mut tool, 'n');
+        assert!(tool.sidebar.delete_prompt.is_none());
+        assert_eq!(model::list_files(&tool.conn).unwrap().len(), 2);
+
+        press(&mut tool, 'd');
+        press(&mut tool, 'd');
+        press(&mut tool, 'y');
+        let files = model::list_files(&tool.conn).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_ne!(files[0].file_path, selected);
+        assert_eq!(tool.sidebar.files.len(), 1);
+    }
+}
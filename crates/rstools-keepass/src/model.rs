@@ -1,4 +1,5 @@
 use anyhow::Result;
+use rstools_core::db::{self, Migration};
 use rusqlite::Connection;
 
 // ── Data model ───────────────────────────────────────────────────────
@@ -21,14 +22,68 @@ pub struct KeePassFile {
     pub pin_nonce: Option<String>,
     /// When the PIN expires (ISO 8601 string).
     pub pin_expires_at: Option<String>,
+    /// Path of the key file used with the master password (never its contents).
+    pub key_file_path: Option<String>,
+    /// Pinned files are listed first in the sidebar.
+    pub pinned: bool,
     /// When the file was last opened.
     pub last_opened_at: String,
     /// When the record was created.
     pub created_at: String,
 }
 
+/// Default auto-lock delay: 15 minutes of inactivity.
+pub const DEFAULT_AUTO_LOCK_SECS: u64 = 15 * 60;
+
+/// Default delay before a sensitive clipboard copy is cleared.
+pub const DEFAULT_CLIPBOARD_CLEAR_SECS: u64 = 30;
+
+/// Default delay before revealed passwords are masked again.
+pub const DEFAULT_REVEAL_TIMEOUT_SECS: u64 = 30;
+
+/// Tool-wide security settings, stored as key/value rows in `keepass_settings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeePassSettings {
+    /// Whether the vault locks itself after `auto_lock_secs` of inactivity.
+    pub auto_lock_enabled: bool,
+    pub auto_lock_secs: u64,
+    /// Seconds before a sensitive clipboard copy is cleared.
+    pub clipboard_clear_secs: u64,
+    /// Lock the vault when switching to another tool.
+    pub lock_on_blur: bool,
+    /// Seconds before revealed sensitive fields are masked again (0 = never).
+    pub reveal_timeout_secs: u64,
+}
+
+impl Default for KeePassSettings {
+    fn default() -> Self {
+        Self {
+            auto_lock_enabled: true,
+            auto_lock_secs: DEFAULT_AUTO_LOCK_SECS,
+            clipboard_clear_secs: DEFAULT_CLIPBOARD_CLEAR_SECS,
+            lock_on_blur: false,
+            reveal_timeout_secs: DEFAULT_REVEAL_TIMEOUT_SECS,
+        }
+    }
+}
+
 // ── Database schema ──────────────────────────────────────────────────
 
+/// Schema changes made after the tables below were first released, oldest
+/// first. Append new steps; never edit or reorder released ones.
+const MIGRATIONS: &[Migration] = &[
+    Migration::AddColumn {
+        table: "keepass_files",
+        column: "key_file_path",
+        definition: "TEXT",
+    },
+    Migration::AddColumn {
+        table: "keepass_files",
+        column: "pinned",
+        definition: "INTEGER NOT NULL DEFAULT 0",
+    },
+];
+
 pub fn init_db(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS keepass_files (
@@ -44,24 +99,31 @@ pub fn init_db(conn: &Connection) -> Result<()> {
             updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
         );
 
+        CREATE TABLE IF NOT EXISTS keepass_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
         CREATE TRIGGER IF NOT EXISTS keepass_files_updated_at
         AFTER UPDATE ON keepass_files
         BEGIN
             UPDATE keepass_files SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
         END;",
     )?;
-    Ok(())
+
+    db::migrate(conn, "keepass", MIGRATIONS)
 }
 
 // ── CRUD operations ──────────────────────────────────────────────────
 
-/// List all tracked KeePass files, ordered by most recently opened first.
+/// List all tracked KeePass files: pinned files first, then by most
+/// recently opened.
 pub fn list_files(conn: &Connection) -> Result<Vec<KeePassFile>> {
     let mut stmt = conn.prepare(
         "SELECT id, file_path, display_name, encrypted_password, pin_salt, pin_nonce,
-                pin_expires_at, last_opened_at, created_at
+                pin_expires_at, last_opened_at, created_at, key_file_path, pinned
          FROM keepass_files
-         ORDER BY last_opened_at DESC",
+         ORDER BY pinned DESC, last_opened_at DESC, id DESC",
     )?;
 
     let files = stmt
@@ -82,6 +144,8 @@ pub fn list_files(conn: &Connection) -> Result<Vec<KeePassFile>> {
                 pin_salt,
                 pin_nonce,
                 pin_expires_at,
+                key_file_path: row.get(9)?,
+                pinned: row.get(10)?,
                 last_opened_at: row.get(7)?,
                 created_at: row.get(8)?,
             })
@@ -95,7 +159,7 @@ pub fn list_files(conn: &Connection) -> Result<Vec<KeePassFile>> {
 pub fn get_file_by_path(conn: &Connection, path: &str) -> Result<Option<KeePassFile>> {
     let mut stmt = conn.prepare(
         "SELECT id, file_path, display_name, encrypted_password, pin_salt, pin_nonce,
-                pin_expires_at, last_opened_at, created_at
+                pin_expires_at, last_opened_at, created_at, key_file_path, pinned
          FROM keepass_files
          WHERE file_path = ?1",
     )?;
@@ -118,6 +182,8 @@ pub fn get_file_by_path(conn: &Connection, path: &str) -> Result<Option<KeePassF
                 pin_salt,
                 pin_nonce,
                 pin_expires_at,
+                key_file_path: row.get(9)?,
+                pinned: row.get(10)?,
                 last_opened_at: row.get(7)?,
                 created_at: row.get(8)?,
             })
@@ -182,6 +248,24 @@ pub fn clear_pin(conn: &Connection, file_id: i64) -> Result<()> {
     Ok(())
 }
 
+/// Remember (or forget, with `None`) the key file path for a file.
+pub fn set_key_file(conn: &Connection, file_id: i64, key_file_path: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE keepass_files SET key_file_path = ?1 WHERE id = ?2",
+        rusqlite::params![key_file_path, file_id],
+    )?;
+    Ok(())
+}
+
+/// Pin or unpin a file in the sidebar.
+pub fn set_pinned(conn: &Connection, file_id: i64, pinned: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE keepass_files SET pinned = ?1 WHERE id = ?2",
+        rusqlite::params![pinned, file_id],
+    )?;
+    Ok(())
+}
+
 /// Remove a file from the history entirely.
 pub fn delete_file(conn: &Connection, file_id: i64) -> Result<()> {
     conn.execute("DELETE FROM keepass_files WHERE id = ?1", [file_id])?;
@@ -196,3 +280,160 @@ pub fn touch_file(conn: &Connection, file_id: i64) -> Result<()> {
     )?;
     Ok(())
 }
+
+// ── Settings ─────────────────────────────────────────────────────────
+
+/// Load the settings. Missing or unparsable values fall back to defaults.
+pub fn load_settings(conn: &Connection) -> Result<KeePassSettings> {
+    let mut settings = KeePassSettings::default();
+    let mut stmt = conn.prepare("SELECT key, value FROM keepass_settings")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (key, value) in rows {
+        match key.as_str() {
+            "auto_lock_enabled" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.auto_lock_enabled = enabled;
+                }
+            }
+            "auto_lock_secs" => {
+                if let Ok(secs) = value.parse() {
+                    settings.auto_lock_secs = secs;
+                }
+            }
+            "clipboard_clear_secs" => {
+                if let Ok(secs) = value.parse() {
+                    settings.clipboard_clear_secs = secs;
+                }
+            }
+            "lock_on_blur" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.lock_on_blur = enabled;
+                }
+            }
+            "reveal_timeout_secs" => {
+                if let Ok(secs) = value.parse() {
+                    settings.reveal_timeout_secs = secs;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// Persist all settings.
+pub fn save_settings(conn: &Connection, settings: &KeePassSettings) -> Result<()> {
+    let values = [
+        ("auto_lock_enabled", settings.auto_lock_enabled.to_string()),
+        ("auto_lock_secs", settings.auto_lock_secs.to_string()),
+        (
+            "clipboard_clear_secs",
+            settings.clipboard_clear_secs.to_string(),
+        ),
+        ("lock_on_blur", settings.lock_on_blur.to_string()),
+        (
+            "reveal_timeout_secs",
+            settings.reveal_timeout_secs.to_string(),
+        ),
+    ];
+    for (key, value) in values {
+        conn.execute(
+            "INSERT INTO keepass_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_file_path_is_persisted() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        // Running the migration twice is a no-op
+        init_db(&conn).unwrap();
+
+        let id = upsert_file(&conn, "/vaults/work.kdbx", "work").unwrap();
+        let file = get_file_by_path(&conn, "/vaults/work.kdbx")
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.key_file_path, None);
+
+        set_key_file(&conn, id, Some("/keys/work.keyx")).unwrap();
+        let files = list_files(&conn).unwrap();
+        assert_eq!(files[0].key_file_path.as_deref(), Some("/keys/work.keyx"));
+
+        set_key_file(&conn, id, None).unwrap();
+        let file = get_file_by_path(&conn, "/vaults/work.kdbx")
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.key_file_path, None);
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), KeePassSettings::default());
+
+        let settings = KeePassSettings {
+            auto_lock_enabled: false,
+            auto_lock_secs: 300,
+            clipboard_clear_secs: 15,
+            lock_on_blur: true,
+            reveal_timeout_secs: 0,
+        };
+        save_settings(&conn, &settings).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), settings);
+
+        // A corrupt value keeps its default
+        conn.execute(
+            "UPDATE keepass_settings SET value = 'soon' WHERE key = 'auto_lock_secs'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            load_settings(&conn).unwrap().auto_lock_secs,
+            DEFAULT_AUTO_LOCK_SECS
+        );
+    }
+
+    #[test]
+    fn test_pinned_files_are_listed_first() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_db(&conn).unwrap();
+        let personal = upsert_file(&conn, "/vaults/personal.kdbx", "personal").unwrap();
+        let work = upsert_file(&conn, "/vaults/work.kdbx", "work").unwrap();
+        conn.execute(
+            "UPDATE keepass_files SET last_opened_at = '2020-01-01 00:00:00' WHERE id = ?1",
+            [personal],
+        )
+        .unwrap();
+
+        let names = |conn: &Connection| -> Vec<String> {
+            list_files(conn)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.display_name)
+                .collect()
+        };
+        assert_eq!(names(&conn), vec!["work", "personal"]);
+
+        set_pinned(&conn, personal, true).unwrap();
+        assert_eq!(names(&conn), vec!["personal", "work"]);
+        assert!(list_files(&conn).unwrap()[0].pinned);
+
+        set_pinned(&conn, personal, false).unwrap();
+        set_pinned(&conn, work, false).unwrap();
+        assert_eq!(names(&conn), vec!["work", "personal"]);
+    }
+}
@@ -1,17 +1,22 @@
+use std::cell::Cell;
+
 use crate::model::{self, KeePassFile};
 use anyhow::Result;
+use rstools_core::confirm::ConfirmPrompt;
 use rusqlite::Connection;
 
 /// The full sidebar state for KeePass file history.
 pub struct SidebarState {
-    /// List of tracked files, ordered by most recently opened.
+    /// List of tracked files, pinned first, then by most recently opened.
     pub files: Vec<KeePassFile>,
     /// Currently selected index.
     pub selected: usize,
     /// Whether the sidebar is visible.
     pub visible: bool,
-    /// Whether a delete confirmation is pending.
-    pub confirm_delete: bool,
+    /// Open while a delete from the history awaits confirmation.
+    pub delete_prompt: Option<ConfirmPrompt>,
+    /// Rows shown at the last render; sizes `Ctrl-d` / `Ctrl-u`.
+    pub visible_lines: Cell<usize>,
 }
 
 impl SidebarState {
@@ -20,7 +25,8 @@ impl SidebarState {
             files: Vec::new(),
             selected: 0,
             visible: true,
-            confirm_delete: false,
+            delete_prompt: None,
+            visible_lines: Cell::new(20),
         }
     }
 
@@ -34,6 +40,20 @@ impl SidebarState {
         Ok(())
     }
 
+    /// Pin or unpin the selected file, keeping it selected after it moves.
+    pub fn toggle_pin_selected(&mut self, conn: &Connection) -> Result<Option<bool>> {
+        let Some(file) = self.selected_file() else {
+            return Ok(None);
+        };
+        let (id, pinned) = (file.id, !file.pinned);
+        model::set_pinned(conn, id, pinned)?;
+        self.reload(conn)?;
+        if let Some(idx) = self.files.iter().position(|f| f.id == id) {
+            self.selected = idx;
+        }
+        Ok(Some(pinned))
+    }
+
     /// Get the currently selected file, if any.
     pub fn selected_file(&self) -> Option<&KeePassFile> {
         self.files.get(self.selected)
@@ -66,14 +86,14 @@ impl SidebarState {
     }
 
     /// Half-page down.
-    pub fn half_page_down(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_down(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = (self.selected + half).min(self.files.len().saturating_sub(1));
     }
 
     /// Half-page up.
-    pub fn half_page_up(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_up(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = self.selected.saturating_sub(half);
     }
 
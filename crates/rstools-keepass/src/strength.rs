@@ -0,0 +1,179 @@
+//! Password strength estimation for the detail panel (display only).
+
+/// Passwords that fall to any dictionary attack regardless of their
+/// character classes. Compared case-insensitively.
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "123456789",
+    "12345678",
+    "12345",
+    "1234567",
+    "1234567890",
+    "111111",
+    "000000",
+    "123123",
+    "654321",
+    "666666",
+    "121212",
+    "112233",
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "p@ssw0rd",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "azerty",
+    "asdfgh",
+    "asdfghjkl",
+    "zxcvbnm",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "abc123",
+    "abcdef",
+    "iloveyou",
+    "admin",
+    "admin123",
+    "root",
+    "toor",
+    "welcome",
+    "welcome1",
+    "letmein",
+    "login",
+    "monkey",
+    "dragon",
+    "master",
+    "football",
+    "baseball",
+    "sunshine",
+    "princess",
+    "shadow",
+    "superman",
+    "batman",
+    "trustno1",
+    "starwars",
+    "secret",
+    "changeme",
+    "default",
+    "guest",
+    "hello",
+    "freedom",
+    "whatever",
+    "michael",
+    "jordan23",
+    "soleil",
+];
+
+/// Bucketed strength label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl Strength {
+    /// Bucket an entropy estimate: below 40 bits is weak, below 70 fair.
+    pub fn from_entropy(bits: f64) -> Self {
+        if bits < 40.0 {
+            Strength::Weak
+        } else if bits < 70.0 {
+            Strength::Fair
+        } else {
+            Strength::Strong
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Strength::Weak => "Weak",
+            Strength::Fair => "Fair",
+            Strength::Strong => "Strong",
+        }
+    }
+}
+
+/// Whether the password appears in the embedded common-password list.
+pub fn is_common(password: &str) -> bool {
+    let lower = password.to_lowercase();
+    COMMON_PASSWORDS.contains(&lower.as_str())
+}
+
+/// Estimate the entropy of a password in bits.
+///
+/// The character pool is the sum of the classes present (lowercase,
+/// uppercase, digits, ASCII symbols, anything else). Each character adds
+/// `log2(pool)` bits, except repeats of an already used character, which
+/// count for half. Common passwords are capped to the size of the list.
+pub fn estimate_entropy(password: &str) -> f64 {
+    if password.is_empty() {
+        return 0.0;
+    }
+    if is_common(password) {
+        return (COMMON_PASSWORDS.len() as f64).log2();
+    }
+
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) =
+        (false, false, false, false, false);
+    let mut seen: Vec<char> = Vec::new();
+    let mut effective_length = 0.0;
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii_punctuation() || c == ' ' => symbol = true,
+            _ => other = true,
+        }
+        if seen.contains(&c) {
+            effective_length += 0.5;
+        } else {
+            seen.push(c);
+            effective_length += 1.0;
+        }
+    }
+
+    let pool: u32 = [
+        (lower, 26),
+        (upper, 26),
+        (digit, 10),
+        (symbol, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum();
+    effective_length * f64::from(pool).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_entropy() {
+        assert_eq!(estimate_entropy(""), 0.0);
+        // 8 distinct lowercase letters: 8 * log2(26)
+        assert!((estimate_entropy("abcdefgh") - 8.0 * 26f64.log2()).abs() < 1e-9);
+        // More classes and more length mean more entropy
+        assert!(estimate_entropy("abcdefgH1!") > estimate_entropy("abcdefghij"));
+        // Repeated characters count for less
+        assert!(estimate_entropy("aaaaaaaa") < estimate_entropy("abcdefgh"));
+
+        assert_eq!(
+            Strength::from_entropy(estimate_entropy("Password")),
+            Strength::Weak
+        );
+        assert!(is_common("QWERTY"));
+        assert_eq!(
+            Strength::from_entropy(estimate_entropy("k7#Qm2pX")),
+            Strength::Fair
+        );
+        assert_eq!(
+            Strength::from_entropy(estimate_entropy("xT9!vq2@Lm4#pZ")),
+            Strength::Strong
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::detail::DetailPanel;
 use crate::sidebar::SidebarState;
-use crate::vault::{FlatNode, NodeType, VaultState};
+use crate::strength::{self, Strength};
+use crate::vault::{FlatNode, NodeType, SearchField, VaultState};
 use crate::{InputPrompt, KeePassTool, ToolFocus};
 use ratatui::{
     Frame,
@@ -9,6 +10,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
+use rstools_core::theme::theme;
 
 /// Maximum sidebar width in characters.
 const MAX_SIDEBAR_WIDTH: u16 = 40;
@@ -25,21 +27,13 @@ pub fn sidebar_width(sidebar: &SidebarState) -> u16 {
     let longest = sidebar
         .files
         .iter()
-        .map(|f| f.display_name.len() as u16)
+        .map(|f| f.display_name.len() as u16 + if f.pinned { 2 } else { 0 })
         .max()
         .unwrap_or(0);
     // +4: 2 for block borders, 2 for inner padding (" name")
     (longest + 4).clamp(MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH)
 }
 
-// ── Colors ───────────────────────────────────────────────────────────
-
-const COLOR_GROUP: Color = Color::Blue;
-const COLOR_ENTRY: Color = Color::Green;
-const COLOR_LABEL: Color = Color::Cyan;
-const COLOR_MASKED: Color = Color::DarkGray;
-const COLOR_TAG: Color = Color::Magenta;
-
 // ── Main entry point ─────────────────────────────────────────────────
 
 /// Render the entire KeePass tool view.
@@ -76,20 +70,15 @@ pub fn render_keepass_tool(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     // Render overlays (popups)
     render_input_prompt(frame, base_area, tool);
     render_search_overlay(frame, base_area, tool);
-
-    // Render clipboard notification
-    if let Some(ref msg) = tool.clipboard_notification {
-        render_notification(frame, base_area, msg);
-    }
 }
 
 // ── Sidebar ──────────────────────────────────────────────────────────
 
 fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused: bool) {
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -102,6 +91,7 @@ fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused
     if inner.height == 0 || inner.width == 0 {
         return;
     }
+    sidebar.visible_lines.set(inner.height as usize);
 
     if sidebar.files.is_empty() {
         let help = Paragraph::new(vec![
@@ -142,7 +132,7 @@ fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused
         .map(|(i, file)| {
             let is_selected = i == sidebar.selected;
             let bg = if is_selected {
-                Color::DarkGray
+                theme().muted
             } else {
                 Color::Reset
             };
@@ -150,16 +140,20 @@ fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused
             // File name with left padding
             let name_style = if is_selected {
                 Style::default()
-                    .fg(Color::White)
+                    .fg(theme().text)
                     .bg(bg)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White).bg(bg)
+                Style::default().fg(theme().text).bg(bg)
             };
-            let spans = vec![
-                Span::styled(" ", Style::default().bg(bg)),
-                Span::styled(&file.display_name, name_style),
-            ];
+            let mut spans = vec![Span::styled(" ", Style::default().bg(bg))];
+            if file.pinned {
+                spans.push(Span::styled(
+                    "\u{2605} ",
+                    Style::default().fg(theme().accent).bg(bg),
+                ));
+            }
+            spans.push(Span::styled(&file.display_name, name_style));
 
             Line::from(spans)
         })
@@ -169,21 +163,14 @@ fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused
     frame.render_widget(paragraph, inner);
 
     // Render confirm delete prompt if active
-    if sidebar.confirm_delete {
+    if let Some(prompt) = &sidebar.delete_prompt {
         let prompt_area = Rect {
             x: inner.x,
             y: inner.y + inner.height.saturating_sub(1),
             width: inner.width,
             height: 1,
         };
-        let prompt = Paragraph::new(Line::from(vec![
-            Span::styled(
-                "Delete from history? ",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("(y/n)", Style::default().add_modifier(Modifier::DIM)),
-        ]));
-        frame.render_widget(prompt, prompt_area);
+        prompt.render(frame, prompt_area);
     }
 }
 
@@ -207,13 +194,33 @@ fn render_content_area(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
                 width: tree_width,
                 height: area.height,
             };
-            let detail_area = Rect {
+            let mut detail_area = Rect {
                 x: area.x + tree_width,
                 y: area.y,
                 width: area.width.saturating_sub(tree_width),
                 height: area.height,
             };
 
+            // Breadcrumb line above the detail panel
+            if !tool.detail.breadcrumb.is_empty() && detail_area.height > 3 {
+                let crumb_area = Rect {
+                    height: 1,
+                    ..detail_area
+                };
+                detail_area.y += 1;
+                detail_area.height -= 1;
+                let text = tool
+                    .detail
+                    .breadcrumb_text(crumb_area.width.saturating_sub(2) as usize);
+                frame.render_widget(
+                    Paragraph::new(Span::styled(
+                        format!(" {text}"),
+                        Style::default().fg(theme().muted),
+                    )),
+                    crumb_area,
+                );
+            }
+
             render_vault_tree(frame, tree_area, vault, tool.focus == ToolFocus::Tree);
             render_detail_panel(
                 frame,
@@ -231,7 +238,7 @@ fn render_content_area(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
 fn render_empty_content(frame: &mut Frame, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(theme().muted))
         .title(" KeePass ");
 
     let inner = block.inner(area);
@@ -243,7 +250,7 @@ fn render_empty_content(frame: &mut Frame, area: Rect) {
         Line::from(Span::styled(
             "No vault open",
             Style::default()
-                .fg(Color::DarkGray)
+                .fg(theme().muted)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -276,7 +283,7 @@ fn render_empty_content(frame: &mut Frame, area: Rect) {
 fn render_lock_screen(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme().accent))
         .title(" Locked ");
 
     let inner = block.inner(area);
@@ -294,14 +301,14 @@ fn render_lock_screen(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
         Line::from(Span::styled(
             "  Locked  ",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(Span::styled(
             vault_name,
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -319,21 +326,39 @@ fn render_lock_screen(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
 
 fn render_vault_tree(frame: &mut Frame, area: Rect, vault: &VaultState, focused: bool) {
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color))
         .title(format!(" {} ", vault.vault_name));
 
+    // Breakdown of the selected group
+    if let Some(stats) = vault.selected_group_stats() {
+        let mut status = format!(
+            " {} entries \u{00b7} {} groups ",
+            stats.entries, stats.subgroups
+        );
+        let mut spans = vec![Span::styled(
+            status.clone(),
+            Style::default().fg(theme().muted),
+        )];
+        if stats.expired > 0 {
+            status = format!("\u{00b7} {} expired ", stats.expired);
+            spans.push(Span::styled(status, Style::default().fg(theme().error)));
+        }
+        block = block.title_bottom(Line::from(spans));
+    }
+
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     if inner.height == 0 || inner.width == 0 {
         return;
     }
+    vault.visible_lines.set(inner.height as usize);
 
     if vault.flat_view.is_empty() {
         let empty = Paragraph::new(Span::styled(
@@ -367,7 +392,7 @@ fn render_vault_tree(frame: &mut Frame, area: Rect, vault: &VaultState, focused:
 
 fn render_tree_line(node: &FlatNode, is_selected: bool, _max_width: usize) -> Line<'static> {
     let bg = if is_selected {
-        Color::DarkGray
+        theme().muted
     } else {
         Color::Reset
     };
@@ -380,7 +405,7 @@ fn render_tree_line(node: &FlatNode, is_selected: bool, _max_width: usize) -> Li
             if has_guide {
                 spans.push(Span::styled(
                     " \u{2502} ",
-                    Style::default().fg(Color::DarkGray).bg(bg),
+                    Style::default().fg(theme().muted).bg(bg),
                 ));
             } else {
                 spans.push(Span::styled("   ", Style::default().bg(bg)));
@@ -402,12 +427,15 @@ fn render_tree_line(node: &FlatNode, is_selected: bool, _max_width: usize) -> Li
             } else {
                 "\u{25B6} "
             };
-            spans.push(Span::styled(icon, Style::default().fg(COLOR_GROUP).bg(bg)));
+            spans.push(Span::styled(
+                icon,
+                Style::default().fg(theme().border).bg(bg),
+            ));
         }
         NodeType::Entry => {
             spans.push(Span::styled(
                 "\u{25CF} ",
-                Style::default().fg(COLOR_ENTRY).bg(bg),
+                Style::default().fg(theme().success).bg(bg),
             ));
         }
     }
@@ -415,18 +443,26 @@ fn render_tree_line(node: &FlatNode, is_selected: bool, _max_width: usize) -> Li
     // Name
     let name_style = if is_selected {
         Style::default()
-            .fg(Color::White)
+            .fg(theme().text)
             .bg(bg)
             .add_modifier(Modifier::BOLD)
     } else {
         let fg = match node.node_type {
-            NodeType::Group => COLOR_GROUP,
-            NodeType::Entry => Color::White,
+            NodeType::Group => theme().border,
+            NodeType::Entry => theme().text,
         };
         Style::default().fg(fg).bg(bg)
     };
     spans.push(Span::styled(node.name.clone(), name_style));
 
+    // Entry count for collapsed groups
+    if node.node_type == NodeType::Group && !node.is_expanded {
+        spans.push(Span::styled(
+            format!(" ({})", node.entry_count),
+            Style::default().fg(theme().muted).bg(bg),
+        ));
+    }
+
     Line::from(spans)
 }
 
@@ -434,9 +470,9 @@ fn render_tree_line(node: &FlatNode, is_selected: bool, _max_width: usize) -> Li
 
 fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focused: bool) {
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -470,10 +506,10 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         Span::styled(
             "Title    ",
             Style::default()
-                .fg(COLOR_LABEL)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(&details.title, Style::default().fg(Color::White)),
+        Span::styled(&details.title, Style::default().fg(theme().text)),
     ]));
     lines.push(Line::from(""));
 
@@ -482,10 +518,10 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         Span::styled(
             "Username ",
             Style::default()
-                .fg(COLOR_LABEL)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(&details.username, Style::default().fg(Color::White)),
+        Span::styled(&details.username, Style::default().fg(theme().text)),
     ]));
     lines.push(Line::from(""));
 
@@ -493,7 +529,7 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
     let password_label = Span::styled(
         "Password ",
         Style::default()
-            .fg(COLOR_LABEL)
+            .fg(theme().info)
             .add_modifier(Modifier::BOLD),
     );
     if details.password.is_empty() {
@@ -512,9 +548,9 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
             Span::styled(
                 password_display,
                 Style::default().fg(if detail.password_visible {
-                    Color::White
+                    theme().text
                 } else {
-                    COLOR_MASKED
+                    theme().muted
                 }),
             ),
             Span::styled(
@@ -526,19 +562,51 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
                 Style::default().add_modifier(Modifier::DIM),
             ),
         ]));
+        // Strength is only shown once the password is revealed
+        if detail.password_visible {
+            lines.push(strength_line(&details.password));
+        }
     }
     lines.push(Line::from(""));
 
+    // TOTP
+    if let Some((code, remaining)) = detail.current_totp() {
+        let (first, second) = code.split_at(code.len() / 2);
+        lines.push(Line::from(vec![
+            Span::styled(
+                "TOTP     ",
+                Style::default()
+                    .fg(theme().info)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{first} {second}"),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {remaining}s"),
+                Style::default().fg(if remaining <= 5 {
+                    theme().error
+                } else {
+                    theme().muted
+                }),
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
+
     // URL
     if !details.url.is_empty() {
         lines.push(Line::from(vec![
             Span::styled(
                 "URL      ",
                 Style::default()
-                    .fg(COLOR_LABEL)
+                    .fg(theme().info)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(&details.url, Style::default().fg(Color::Cyan)),
+            Span::styled(&details.url, Style::default().fg(theme().info)),
         ]));
         lines.push(Line::from(""));
     }
@@ -548,14 +616,17 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         let mut tag_spans = vec![Span::styled(
             "Tags     ",
             Style::default()
-                .fg(COLOR_LABEL)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         )];
         for (i, tag) in details.tags.iter().enumerate() {
             if i > 0 {
-                tag_spans.push(Span::styled(", ", Style::default().fg(Color::DarkGray)));
+                tag_spans.push(Span::styled(", ", Style::default().fg(theme().muted)));
             }
-            tag_spans.push(Span::styled(tag.as_str(), Style::default().fg(COLOR_TAG)));
+            tag_spans.push(Span::styled(
+                tag.as_str(),
+                Style::default().fg(theme().accent),
+            ));
         }
         lines.push(Line::from(tag_spans));
         lines.push(Line::from(""));
@@ -566,7 +637,7 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         lines.push(Line::from(Span::styled(
             "--- Custom Fields ---",
             Style::default()
-                .fg(Color::DarkGray)
+                .fg(theme().muted)
                 .add_modifier(Modifier::DIM),
         )));
         lines.push(Line::from(""));
@@ -584,15 +655,15 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
                 Span::styled(
                     label,
                     Style::default()
-                        .fg(COLOR_LABEL)
+                        .fg(theme().info)
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
                     display_value,
                     Style::default().fg(if *is_protected {
-                        COLOR_MASKED
+                        theme().muted
                     } else {
-                        Color::White
+                        theme().text
                     }),
                 ),
             ]));
@@ -605,14 +676,14 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         lines.push(Line::from(Span::styled(
             "--- Notes ---",
             Style::default()
-                .fg(Color::DarkGray)
+                .fg(theme().muted)
                 .add_modifier(Modifier::DIM),
         )));
         lines.push(Line::from(""));
         for line in details.notes.lines() {
             lines.push(Line::from(Span::styled(
                 line.to_string(),
-                Style::default().fg(Color::White),
+                Style::default().fg(theme().text),
             )));
         }
     }
@@ -626,8 +697,17 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
         Span::styled(" pass  ", Style::default().add_modifier(Modifier::DIM)),
         Span::styled("yU", Style::default().add_modifier(Modifier::BOLD)),
         Span::styled(" URL  ", Style::default().add_modifier(Modifier::DIM)),
+        Span::styled("yt", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(" TOTP  ", Style::default().add_modifier(Modifier::DIM)),
+        Span::styled("yf", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(" field  ", Style::default().add_modifier(Modifier::DIM)),
         Span::styled("p", Style::default().add_modifier(Modifier::BOLD)),
-        Span::styled(" toggle pass", Style::default().add_modifier(Modifier::DIM)),
+        Span::styled(
+            " toggle pass  ",
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        Span::styled("P", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(" all", Style::default().add_modifier(Modifier::DIM)),
     ]));
 
     // Apply scroll
@@ -638,6 +718,50 @@ fn render_detail_panel(frame: &mut Frame, area: Rect, detail: &DetailPanel, focu
     frame.render_widget(paragraph, inner);
 }
 
+/// Width of the password strength bar in cells.
+const STRENGTH_BAR_WIDTH: usize = 12;
+
+/// Colored strength bar with label and entropy estimate for a revealed password.
+fn strength_line(password: &str) -> Line<'static> {
+    let bits = strength::estimate_entropy(password);
+    let rating = Strength::from_entropy(bits);
+    let color = match rating {
+        Strength::Weak => theme().error,
+        Strength::Fair => theme().accent,
+        Strength::Strong => theme().success,
+    };
+    // A full bar at 100 bits
+    let filled = ((bits / 100.0).min(1.0) * STRENGTH_BAR_WIDTH as f64).ceil() as usize;
+
+    let mut spans = vec![
+        Span::styled(
+            "Strength ",
+            Style::default()
+                .fg(theme().info)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("\u{2588}".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "\u{2591}".repeat(STRENGTH_BAR_WIDTH - filled),
+            Style::default().fg(theme().muted),
+        ),
+        Span::styled(format!(" {}", rating.label()), Style::default().fg(color)),
+        Span::styled(
+            format!(" (~{bits:.0} bits)"),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ];
+    if strength::is_common(password) {
+        spans.push(Span::styled(
+            "  common password",
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    Line::from(spans)
+}
+
 // ── Input prompts (overlays) ─────────────────────────────────────────
 
 fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
@@ -647,8 +771,10 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     };
 
     let popup_width = 50u16.min(area.width.saturating_sub(4));
-    let is_master = matches!(prompt, InputPrompt::MasterPassword { .. });
-    let popup_height = if is_master { 7u16 } else { 5u16 };
+    let popup_height = match prompt {
+        InputPrompt::MasterPassword { key_file, .. } => 7u16 + u16::from(key_file.is_some()),
+        _ => 5u16,
+    };
 
     let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
     let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
@@ -666,7 +792,7 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme().accent))
         .title(title);
 
     let inner = block.inner(popup_area);
@@ -675,6 +801,7 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     match prompt {
         InputPrompt::MasterPassword {
             buffer,
+            key_file,
             error,
             paste_focused,
             ..
@@ -684,10 +811,20 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
             if let Some(err) = error {
                 lines.push(Line::from(Span::styled(
                     err.as_str(),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(theme().error),
                 )));
             }
 
+            if let Some(key_file) = key_file {
+                lines.push(Line::from(vec![
+                    Span::styled("Key file ", Style::default().fg(theme().info)),
+                    Span::styled(
+                        key_file.as_str(),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ),
+                ]));
+            }
+
             // Show dots for each character
             let masked: String = "\u{2022}".repeat(buffer.len());
             let input_style = if *paste_focused {
@@ -705,11 +842,11 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
             let btn_style = if *paste_focused {
                 Style::default()
                     .fg(Color::Black)
-                    .bg(Color::Yellow)
+                    .bg(theme().accent)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme().accent)
                     .add_modifier(Modifier::DIM)
             };
             lines.push(Line::from(vec![
@@ -740,7 +877,7 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
             if let Some(err) = error {
                 lines.push(Line::from(Span::styled(
                     err.as_str(),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(theme().error),
                 )));
             }
 
@@ -758,7 +895,7 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
             }
             lines.push(Line::from(vec![
                 Span::styled("PIN: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::styled(pin_display, Style::default().fg(Color::Yellow)),
+                Span::styled(pin_display, Style::default().fg(theme().accent)),
             ]));
 
             let paragraph = Paragraph::new(lines);
@@ -795,7 +932,7 @@ fn render_input_prompt(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
                 Line::from(""),
                 Line::from(vec![
                     Span::styled("PIN: ", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::styled(pin_display, Style::default().fg(Color::Yellow)),
+                    Span::styled(pin_display, Style::default().fg(theme().accent)),
                 ]),
             ];
             let paragraph = Paragraph::new(lines);
@@ -831,10 +968,15 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
         Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(popup_area);
 
     // Search input
+    let scope = if tool.search_all_fields {
+        " Search Entries (all fields, Ctrl-f: title only) "
+    } else {
+        " Search Entries (title only, Ctrl-f: all fields) "
+    };
     let input_block = Block::default()
-        .title(" Search Entries ")
+        .title(scope)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(theme().accent));
 
     let input_text = Paragraph::new(Line::from(vec![
         Span::styled("> ", Style::default().add_modifier(Modifier::BOLD)),
@@ -867,7 +1009,7 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     // Results list
     let results_block = Block::default()
         .borders(Borders::LEFT | Borders::BOTTOM)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(theme().muted))
         .title(" Results ");
 
     let results_inner = results_block.inner(results_area);
@@ -889,25 +1031,49 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
         .map(|(i, entry)| {
             let is_selected = i == tool.search_selected;
             let bg = if is_selected {
-                Color::DarkGray
+                theme().muted
             } else {
                 Color::Reset
             };
             let prefix = if is_selected { "> " } else { "  " };
-            Line::from(vec![
-                Span::styled(prefix, Style::default().fg(Color::Yellow).bg(bg)),
-                Span::styled(
-                    entry.title.clone(),
-                    Style::default()
-                        .fg(Color::White)
-                        .bg(bg)
-                        .add_modifier(if is_selected {
-                            Modifier::BOLD
-                        } else {
-                            Modifier::empty()
-                        }),
-                ),
-            ])
+            let title_style =
+                Style::default()
+                    .fg(theme().text)
+                    .bg(bg)
+                    .add_modifier(if is_selected {
+                        Modifier::BOLD
+                    } else {
+                        Modifier::empty()
+                    });
+            let mut spans = vec![Span::styled(
+                prefix,
+                Style::default().fg(theme().accent).bg(bg),
+            )];
+            match entry.matched_field {
+                Some(SearchField::Title) => spans.extend(highlight_matches(
+                    &entry.title,
+                    &entry.match_positions,
+                    title_style,
+                    title_style.fg(theme().accent),
+                )),
+                _ => spans.push(Span::styled(entry.title.clone(), title_style)),
+            }
+            // Show which other field matched, highlighting the matched characters
+            if let Some(field) = entry.matched_field.filter(|f| *f != SearchField::Title) {
+                spans.push(Span::styled(
+                    format!("  {}: ", field.label()),
+                    Style::default().fg(theme().info).bg(bg),
+                ));
+                let (line, positions) = matched_line(entry.field(field), &entry.match_positions);
+                let style = Style::default().fg(theme().muted).bg(bg);
+                spans.extend(highlight_matches(
+                    line,
+                    &positions,
+                    style,
+                    style.fg(theme().accent),
+                ));
+            }
+            Line::from(spans)
         })
         .collect();
 
@@ -917,7 +1083,7 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     // Preview panel
     let preview_block = Block::default()
         .borders(Borders::RIGHT | Borders::BOTTOM)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(theme().muted))
         .title(" Preview ");
 
     let preview_inner = preview_block.inner(preview_area);
@@ -937,40 +1103,40 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
                     Span::styled(
                         "Title: ",
                         Style::default()
-                            .fg(COLOR_LABEL)
+                            .fg(theme().info)
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::styled(&details.title, Style::default().fg(Color::White)),
+                    Span::styled(&details.title, Style::default().fg(theme().text)),
                 ]));
                 if !entry.group_path.is_empty() {
                     preview_lines.push(Line::from(vec![
                         Span::styled(
                             "Group: ",
                             Style::default()
-                                .fg(COLOR_LABEL)
+                                .fg(theme().info)
                                 .add_modifier(Modifier::BOLD),
                         ),
-                        Span::styled(&entry.group_path, Style::default().fg(Color::DarkGray)),
+                        Span::styled(&entry.group_path, Style::default().fg(theme().muted)),
                     ]));
                 }
                 preview_lines.push(Line::from(vec![
                     Span::styled(
                         "User:  ",
                         Style::default()
-                            .fg(COLOR_LABEL)
+                            .fg(theme().info)
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::styled(&details.username, Style::default().fg(Color::White)),
+                    Span::styled(&details.username, Style::default().fg(theme().text)),
                 ]));
                 if !details.url.is_empty() {
                     preview_lines.push(Line::from(vec![
                         Span::styled(
                             "URL:   ",
                             Style::default()
-                                .fg(COLOR_LABEL)
+                                .fg(theme().info)
                                 .add_modifier(Modifier::BOLD),
                         ),
-                        Span::styled(&details.url, Style::default().fg(Color::Cyan)),
+                        Span::styled(&details.url, Style::default().fg(theme().info)),
                     ]));
                 }
                 if !details.tags.is_empty() {
@@ -978,10 +1144,29 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
                         Span::styled(
                             "Tags:  ",
                             Style::default()
-                                .fg(COLOR_LABEL)
+                                .fg(theme().info)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(details.tags.join(", "), Style::default().fg(theme().accent)),
+                    ]));
+                }
+                if !details.custom_fields.is_empty() {
+                    let names: Vec<&str> = details
+                        .custom_fields
+                        .iter()
+                        .map(|(k, _, _)| k.as_str())
+                        .collect();
+                    preview_lines.push(Line::from(vec![
+                        Span::styled(
+                            "Fields:",
+                            Style::default()
+                                .fg(theme().info)
                                 .add_modifier(Modifier::BOLD),
                         ),
-                        Span::styled(details.tags.join(", "), Style::default().fg(COLOR_TAG)),
+                        Span::styled(
+                            format!(" {}", names.join(", ")),
+                            Style::default().fg(theme().muted),
+                        ),
                     ]));
                 }
 
@@ -992,24 +1177,50 @@ fn render_search_overlay(frame: &mut Frame, area: Rect, tool: &KeePassTool) {
     }
 }
 
-// ── Notification ─────────────────────────────────────────────────────
-
-fn render_notification(frame: &mut Frame, area: Rect, message: &str) {
-    let width = (message.len() as u16 + 4).min(area.width.saturating_sub(4));
-    let notification_area = Rect {
-        x: area.x + area.width.saturating_sub(width) - 1,
-        y: area.y + 1,
-        width,
-        height: 1,
-    };
+/// Split `text` into spans, styling the chars at `positions` (char indices)
+/// with `matched` and the rest with `normal`.
+pub fn highlight_matches(
+    text: &str,
+    positions: &[usize],
+    normal: Style,
+    matched: Style,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_matched = positions.contains(&i);
+        if is_matched != current_matched && !current.is_empty() {
+            let style = if current_matched { matched } else { normal };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_matched = is_matched;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        let style = if current_matched { matched } else { normal };
+        spans.push(Span::styled(current, style));
+    }
+    spans
+}
 
-    frame.render_widget(Clear, notification_area);
-    let paragraph = Paragraph::new(Line::from(Span::styled(
-        format!(" {} ", message),
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Green)
-            .add_modifier(Modifier::BOLD),
-    )));
-    frame.render_widget(paragraph, notification_area);
+/// The line of a (possibly multi-line) field holding the first matched
+/// char, with the match positions made relative to that line.
+fn matched_line<'a>(value: &'a str, positions: &[usize]) -> (&'a str, Vec<usize>) {
+    let first = positions.first().copied().unwrap_or(0);
+    let mut start = 0;
+    for line in value.lines() {
+        let end = start + line.chars().count();
+        if first <= end {
+            let relative = positions
+                .iter()
+                .filter(|&&p| p >= start && p < end)
+                .map(|p| p - start)
+                .collect();
+            return (line, relative);
+        }
+        // Skip the line break
+        start = end + 1;
+    }
+    (value, Vec::new())
 }
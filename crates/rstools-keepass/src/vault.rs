@@ -3,10 +3,16 @@
 //! Converts the keepass crate's `Database` into our own tree structure
 //! for navigation and rendering. The Recycle Bin group is hidden.
 
+use std::cell::Cell;
 use std::path::Path;
 
+use chrono::NaiveDateTime;
+use keepass::error::{DatabaseKeyError, DatabaseOpenError};
 use keepass::{Database, DatabaseKey};
-use zeroize::Zeroize;
+use rstools_core::telescope::{FuzzyMatch, fuzzy_match};
+use zeroize::Zeroizing;
+
+use crate::crypto::Totp;
 
 // ── Tree node types ──────────────────────────────────────────────────
 
@@ -28,6 +34,8 @@ pub struct EntryDetails {
     pub tags: Vec<String>,
     /// Custom string fields (key, value, is_protected).
     pub custom_fields: Vec<(String, String, bool)>,
+    /// Expiry time (local time, as KeePass stores it), if the entry expires.
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 impl Default for EntryDetails {
@@ -40,10 +48,36 @@ impl Default for EntryDetails {
             notes: String::new(),
             tags: Vec::new(),
             custom_fields: Vec::new(),
+            expires_at: None,
         }
     }
 }
 
+impl EntryDetails {
+    /// Whether the entry has an expiry time in the past.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at <= chrono::Local::now().naive_local())
+    }
+
+    /// TOTP settings of the entry: the `otp` field (an `otpauth://` URI or a
+    /// bare base32 secret), or KeePass 2's `TOTP Seed` / `TOTP Settings`.
+    pub fn totp(&self) -> Option<Totp> {
+        let field = |name: &str| {
+            self.custom_fields
+                .iter()
+                .find(|(k, _, _)| k == name)
+                .map(|(_, v, _)| v.as_str())
+        };
+        if let Some(otp) = field("otp")
+            && let Some(totp) = Totp::from_uri(otp).or_else(|| Totp::from_seed(otp, None))
+        {
+            return Some(totp);
+        }
+        Totp::from_seed(field("TOTP Seed")?, field("TOTP Settings"))
+    }
+}
+
 /// A node in our in-memory vault tree.
 #[derive(Debug, Clone)]
 pub struct VaultNode {
@@ -59,6 +93,38 @@ pub struct VaultNode {
     pub expanded: bool,
 }
 
+/// Descendant counts of a group, for the tree's status line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupStats {
+    pub entries: usize,
+    pub subgroups: usize,
+    pub expired: usize,
+}
+
+impl VaultNode {
+    /// Count the entries, subgroups and expired entries below this node.
+    pub fn group_stats(&self) -> GroupStats {
+        let mut stats = GroupStats::default();
+        for child in &self.children {
+            match child.node_type {
+                NodeType::Entry => {
+                    stats.entries += 1;
+                    if child.details.as_ref().is_some_and(|d| d.is_expired()) {
+                        stats.expired += 1;
+                    }
+                }
+                NodeType::Group => {
+                    let sub = child.group_stats();
+                    stats.subgroups += 1 + sub.subgroups;
+                    stats.entries += sub.entries;
+                    stats.expired += sub.expired;
+                }
+            }
+        }
+        stats
+    }
+}
+
 /// A flattened entry for rendering — one visible line in the tree.
 #[derive(Debug, Clone)]
 pub struct FlatNode {
@@ -74,6 +140,8 @@ pub struct FlatNode {
     pub is_expanded: bool,
     /// Whether this node has children.
     pub has_children: bool,
+    /// Number of descendant entries (groups only).
+    pub entry_count: usize,
     /// For each depth level, whether a vertical guide line should be drawn.
     pub guide_depths: Vec<bool>,
 }
@@ -90,20 +158,44 @@ pub struct VaultState {
     pub file_path: String,
     /// Display name of the vault.
     pub vault_name: String,
+    /// Rows shown at the last render; sizes `Ctrl-d` / `Ctrl-u`.
+    pub visible_lines: Cell<usize>,
 }
 
 impl VaultState {
-    /// Open and parse a KeePass database file.
-    pub fn open(file_path: &str, password: &str) -> anyhow::Result<Self> {
+    /// Open and parse a KeePass database file, with an optional key file
+    /// combined with the password.
+    pub fn open(file_path: &str, password: &str, key_file: Option<&str>) -> anyhow::Result<Self> {
         let path = Path::new(file_path);
         if !path.exists() {
             anyhow::bail!("File not found: {file_path}");
         }
 
-        let mut password_owned = password.to_string();
-        let db_key = DatabaseKey::new().with_password(&password_owned);
-        let db = Database::open(&mut std::fs::File::open(path)?, db_key)?;
-        password_owned.zeroize();
+        // Wiped on drop, so every early return below clears it too
+        let password = Zeroizing::new(password.to_string());
+        let mut db_key = DatabaseKey::new().with_password(&password);
+        if let Some(key_file) = key_file {
+            if !Path::new(key_file).is_file() {
+                anyhow::bail!("Key file not found: {key_file}");
+            }
+            let mut file = std::fs::File::open(key_file)
+                .map_err(|e| anyhow::anyhow!("Cannot read key file {key_file}: {e}"))?;
+            db_key = db_key
+                .with_keyfile(&mut file)
+                .map_err(|e| anyhow::anyhow!("Cannot read key file {key_file}: {e}"))?;
+        }
+        let opened = Database::open(&mut std::fs::File::open(path)?, db_key);
+        drop(password);
+        let db = match opened {
+            Ok(db) => db,
+            Err(DatabaseOpenError::Key(DatabaseKeyError::IncorrectKey)) if key_file.is_some() => {
+                anyhow::bail!("Wrong master password or key file")
+            }
+            Err(DatabaseOpenError::Key(DatabaseKeyError::IncorrectKey)) => {
+                anyhow::bail!("Wrong master password (does this vault need a key file?)")
+            }
+            Err(e) => return Err(e.into()),
+        };
 
         // Get the recycle bin UUID to filter it out.
         // We store it as a 128-bit value to avoid needing the uuid crate as a dependency.
@@ -124,6 +216,7 @@ impl VaultState {
             selected: 0,
             file_path: file_path.to_string(),
             vault_name,
+            visible_lines: Cell::new(20),
         };
         state.rebuild_flat_view();
         Ok(state)
@@ -170,6 +263,20 @@ impl VaultState {
         Some(current)
     }
 
+    /// Names of the nodes along `path`, from the top-level group down.
+    pub fn breadcrumb(&self, path: &[usize]) -> Vec<String> {
+        (1..=path.len())
+            .filter_map(|depth| self.node_at_path(&path[..depth]))
+            .map(|node| node.name.clone())
+            .collect()
+    }
+
+    /// Descendant counts for the selected node, if it is a group.
+    pub fn selected_group_stats(&self) -> Option<GroupStats> {
+        let node = self.node_at_path(&self.selected_node()?.path)?;
+        (node.node_type == NodeType::Group).then(|| node.group_stats())
+    }
+
     /// Get the entry details for the selected node.
     pub fn selected_details(&self) -> Option<&EntryDetails> {
         let flat = self.selected_node()?;
@@ -264,13 +371,13 @@ impl VaultState {
         }
     }
 
-    pub fn half_page_down(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_down(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = (self.selected + half).min(self.flat_view.len().saturating_sub(1));
     }
 
-    pub fn half_page_up(&mut self, visible_lines: usize) {
-        let half = visible_lines / 2;
+    pub fn half_page_up(&mut self) {
+        let half = self.visible_lines.get() / 2;
         self.selected = self.selected.saturating_sub(half);
     }
 
@@ -291,8 +398,84 @@ pub struct SearchableEntry {
     pub group_path: String,
     /// The username, if available.
     pub username: String,
+    /// The URL, if available.
+    pub url: String,
+    /// The notes, if available.
+    pub notes: String,
     /// Path into the tree for navigation.
     pub tree_path: Vec<usize>,
+    /// Field that matched the current search query (set by the search overlay).
+    pub matched_field: Option<SearchField>,
+    /// Char indices of the matched characters in the matched field.
+    pub match_positions: Vec<usize>,
+}
+
+/// An entry field the search overlay can match against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Title,
+    Username,
+    Url,
+    Notes,
+}
+
+impl SearchField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchField::Title => "title",
+            SearchField::Username => "user",
+            SearchField::Url => "url",
+            SearchField::Notes => "notes",
+        }
+    }
+}
+
+impl SearchableEntry {
+    /// The value of a searchable field.
+    pub fn field(&self, field: SearchField) -> &str {
+        match field {
+            SearchField::Title => &self.title,
+            SearchField::Username => &self.username,
+            SearchField::Url => &self.url,
+            SearchField::Notes => &self.notes,
+        }
+    }
+
+    /// Match `query` against the entry, returning the matched field and its
+    /// match. Title, username and URL are matched fuzzily and the best
+    /// score wins (ties go to the earlier field); notes only match as a
+    /// case-insensitive substring, since long text matches almost any
+    /// subsequence. Only the title is checked unless `all_fields` is set.
+    pub fn best_match(&self, query: &str, all_fields: bool) -> Option<(SearchField, FuzzyMatch)> {
+        let fields: &[SearchField] = if all_fields {
+            &[SearchField::Title, SearchField::Username, SearchField::Url]
+        } else {
+            &[SearchField::Title]
+        };
+        let mut best: Option<(SearchField, FuzzyMatch)> = None;
+        for &field in fields {
+            if let Some(m) = fuzzy_match(query, self.field(field))
+                && best.as_ref().is_none_or(|(_, b)| m.score > b.score)
+            {
+                best = Some((field, m));
+            }
+        }
+        if best.is_none() && all_fields {
+            let query = query.to_lowercase();
+            let notes = self.notes.to_lowercase();
+            if let Some(byte) = notes.find(&query) {
+                let start = notes[..byte].chars().count();
+                best = Some((
+                    SearchField::Notes,
+                    FuzzyMatch {
+                        score: 0,
+                        positions: (start..start + query.chars().count()).collect(),
+                    },
+                ));
+            }
+        }
+        best
+    }
 }
 
 fn collect_entries_recursive(
@@ -311,7 +494,11 @@ fn collect_entries_recursive(
                         title: details.title.clone(),
                         group_path: String::new(), // Will be set by caller context
                         username: details.username.clone(),
+                        url: details.url.clone(),
+                        notes: details.notes.clone(),
                         tree_path: path,
+                        matched_field: None,
+                        match_positions: Vec::new(),
                     });
                 }
             }
@@ -435,6 +622,11 @@ fn extract_entry_details(entry: &keepass::db::Entry) -> EntryDetails {
         notes,
         tags,
         custom_fields,
+        expires_at: entry
+            .times
+            .expires
+            .then(|| entry.get_expiry_time().copied())
+            .flatten(),
     }
 }
 
@@ -468,6 +660,10 @@ fn flatten_nodes(
             depth,
             is_expanded: node.expanded,
             has_children: !node.children.is_empty(),
+            entry_count: match node.node_type {
+                NodeType::Group => node.group_stats().entries,
+                NodeType::Entry => 0,
+            },
             guide_depths: guide_depths.clone(),
         });
 
@@ -478,3 +674,94 @@ fn flatten_nodes(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_match() {
+        let entry = SearchableEntry {
+            title: "GitHub".into(),
+            group_path: "Internet".into(),
+            username: "octo-cat".into(),
+            url: "https://github.com/login".into(),
+            notes: "Recovery codes\nstored in the SAFE".into(),
+            tree_path: vec![0, 0],
+            matched_field: None,
+            match_positions: Vec::new(),
+        };
+        let field = |query: &str, all_fields: bool| {
+            entry.best_match(query, all_fields).map(|(field, _)| field)
+        };
+
+        assert_eq!(field("gh", true), Some(SearchField::Title));
+        assert_eq!(field("OCTO", true), Some(SearchField::Username));
+        assert_eq!(field("/login", true), Some(SearchField::Url));
+        assert_eq!(field("safe", true), Some(SearchField::Notes));
+        assert_eq!(field("octo", false), None);
+        assert_eq!(field("gitlab", true), None);
+        // Notes never match fuzzily
+        assert_eq!(field("rcvry", true), None);
+
+        let (_, m) = entry.best_match("safe", true).unwrap();
+        assert_eq!(m.positions, vec![29, 30, 31, 32]);
+    }
+
+    #[test]
+    fn test_group_stats() {
+        let entry = |name: &str, expires_at: Option<NaiveDateTime>| VaultNode {
+            name: name.into(),
+            node_type: NodeType::Entry,
+            children: Vec::new(),
+            details: Some(EntryDetails {
+                title: name.into(),
+                expires_at,
+                ..Default::default()
+            }),
+            expanded: false,
+        };
+        let group = |name: &str, children: Vec<VaultNode>| VaultNode {
+            name: name.into(),
+            node_type: NodeType::Group,
+            children,
+            details: None,
+            expanded: false,
+        };
+        let past = chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0);
+        let future = chrono::NaiveDate::from_ymd_opt(9999, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0);
+
+        let root = group(
+            "Work",
+            vec![
+                group("Servers", vec![entry("db", past), entry("web", None)]),
+                group("Empty", Vec::new()),
+                entry("vpn", future),
+            ],
+        );
+        assert_eq!(
+            root.group_stats(),
+            GroupStats {
+                entries: 3,
+                subgroups: 2,
+                expired: 1,
+            }
+        );
+
+        let mut vault = VaultState {
+            roots: vec![root],
+            flat_view: Vec::new(),
+            selected: 0,
+            file_path: String::new(),
+            vault_name: String::new(),
+            visible_lines: Cell::new(20),
+        };
+        vault.rebuild_flat_view();
+        assert_eq!(vault.flat_view[0].entry_count, 3);
+        assert_eq!(vault.selected_group_stats().unwrap().entries, 3);
+    }
+}
@@ -14,7 +14,7 @@ use ratatui::{
 };
 use rstools_core::{
     help_popup::HelpEntry,
-    keybinds::{process_normal_key, Action, InputMode, KeyState},
+    keybinds::{keymap, process_normal_key, Action, InputMode, KeyAction, KeyState},
     telescope::TelescopeItem,
     tool::Tool,
     vim_editor::{EditorAction, VimEditor, VimMode},
@@ -498,20 +498,10 @@ impl MergeTool {
     }
 
     fn handle_editor_normal_key(&mut self, key: KeyEvent) -> Action {
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
+        // A `/` or `?` search pattern is being typed: every key is text
+        if self.editor.is_searching() {
+            self.editor.handle_key(key);
+            return Action::None;
         }
 
         if self.pending_c_action {
@@ -567,21 +557,18 @@ impl MergeTool {
                 self.pending_c_action = true;
                 return Action::None;
             }
-            KeyCode::Char(' ') if key.modifiers == KeyModifiers::NONE => {
-                self.key_state.leader_active = true;
+            _ if keymap().matches(KeyAction::Leader, &key) => {
                 return Action::LeaderKey;
             }
             KeyCode::Char(':') if key.modifiers == KeyModifiers::NONE => {
                 return Action::SetMode(InputMode::Command);
             }
-            KeyCode::Char('?') if key.modifiers == KeyModifiers::NONE => {
-                return Action::Help;
-            }
+            // `?` falls through to the editor as backward search
             _ => {}
         }
 
         match self.editor.handle_key(key) {
-            EditorAction::ModeChanged(VimMode::Insert) => {
+            EditorAction::ModeChanged(VimMode::Insert | VimMode::Replace) => {
                 self.mode = InputMode::Insert;
                 self.save_current_draft();
                 self.sync_hunk_state();
@@ -613,22 +600,6 @@ impl MergeTool {
     }
 
     fn handle_binary_normal_key(&mut self, key: KeyEvent) -> Action {
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
-        }
-
         if self.pending_c_action {
             self.pending_c_action = false;
             return match key.code {
@@ -662,10 +633,7 @@ impl MergeTool {
                 self.pending_c_action = true;
                 Action::None
             }
-            KeyCode::Char(' ') if key.modifiers == KeyModifiers::NONE => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
-            }
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
             KeyCode::Char(':') if key.modifiers == KeyModifiers::NONE => {
                 Action::SetMode(InputMode::Command)
             }
@@ -693,6 +661,25 @@ impl Tool for MergeTool {
         self.mode
     }
 
+    fn mode_label(&self) -> &'static str {
+        if self.mode == InputMode::Insert && self.editor.mode == VimMode::Replace {
+            "REPLACE"
+        } else {
+            self.mode.label()
+        }
+    }
+
+    fn pending_keys(&self) -> String {
+        let mut keys = self.key_state.pending_keys();
+        if self.pending_c_action {
+            keys.push('c');
+        }
+        if !self.sidebar_focused {
+            keys.push_str(&self.editor.pending_keys());
+        }
+        keys
+    }
+
     fn init_db(&self, _conn: &Connection) -> anyhow::Result<()> {
         Ok(())
     }
@@ -712,6 +699,28 @@ impl Tool for MergeTool {
             .collect()
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let path = id.strip_prefix("merge:")?;
+        let file = self.files.iter().find(|f| f.path == path)?;
+        let mut text = format!("{}\nStatus: {}", file.path, file.status);
+        if file.kind == ConflictKind::Binary {
+            text.push_str("\nBinary conflict");
+            return Some(text);
+        }
+        let content = match self.drafts.get(path) {
+            Some(draft) => Some(draft.clone()),
+            None => self
+                .repo_root
+                .as_ref()
+                .and_then(|root| std::fs::read_to_string(root.join(path)).ok()),
+        };
+        if let Some(content) = content {
+            let hunks = parse_conflicts(&content).hunks.len();
+            text.push_str(&format!("\nConflict hunks: {hunks}"));
+        }
+        Some(text)
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         let Some(path) = id.strip_prefix("merge:") else {
             return false;
@@ -737,6 +746,8 @@ impl Tool for MergeTool {
                 "co / ct / cb",
                 "Accept current hunk: ours / theirs / both",
             ),
+            HelpEntry::with_section("Merge Editor", "/ / ?", "Search forward / backward"),
+            HelpEntry::with_section("Merge Editor", "n / N", "Next / previous match"),
             HelpEntry::with_section("Merge Editor", "Ctrl-h", "Move focus to sidebar"),
             HelpEntry::with_section("Merge", ":w", "Save file and stage if fully resolved"),
             HelpEntry::with_section("Merge", ":wq", "Save and close current tool"),
@@ -828,7 +839,7 @@ impl Tool for MergeTool {
         self.save_current_draft();
         self.sync_hunk_state();
         match self.editor.mode {
-            VimMode::Insert => {
+            VimMode::Insert | VimMode::Replace => {
                 self.mode = InputMode::Insert;
                 Action::SetMode(InputMode::Insert)
             }
@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::{conflict::HunkPreview, ConflictFile, ConflictKind};
+use rstools_core::theme::theme;
 use rstools_core::vim_editor::VimEditor;
 
 pub const SIDEBAR_WIDTH: u16 = 40;
@@ -78,9 +79,9 @@ fn render_sidebar(
     focused: bool,
 ) {
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let title = format!(" Conflicts ({}) ", files.len());
     let block = Block::default()
@@ -96,7 +97,7 @@ fn render_sidebar(
         };
         vec![ListItem::new(Line::from(Span::styled(
             empty_message,
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )))]
     } else {
         files
@@ -108,8 +109,8 @@ fn render_sidebar(
                 };
 
                 let kind_style = match file.kind {
-                    ConflictKind::Text => Style::default().fg(Color::Cyan),
-                    ConflictKind::Binary => Style::default().fg(Color::Yellow),
+                    ConflictKind::Text => Style::default().fg(theme().info),
+                    ConflictKind::Binary => Style::default().fg(theme().accent),
                 };
 
                 ListItem::new(Line::from(vec![
@@ -117,7 +118,7 @@ fn render_sidebar(
                     Span::raw(file.path.clone()),
                     Span::styled(
                         format!(" ({})", file.status),
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme().muted),
                     ),
                 ]))
             })
@@ -131,7 +132,7 @@ fn render_sidebar(
         .block(block)
         .highlight_style(
             Style::default()
-                .bg(Color::Gray)
+                .bg(theme().selection_bg)
                 .fg(Color::Black)
                 .add_modifier(Modifier::BOLD),
         )
@@ -144,7 +145,7 @@ fn render_empty_content(frame: &mut Frame, area: Rect, in_git_repo: bool) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(" Merge View ")
-        .border_style(Style::default().fg(Color::DarkGray));
+        .border_style(Style::default().fg(theme().muted));
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -168,9 +169,9 @@ fn render_empty_content(frame: &mut Frame, area: Rect, in_git_repo: bool) {
 
 fn render_binary_content(frame: &mut Frame, area: Rect, path: &str, focused: bool) {
     let border = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -182,16 +183,16 @@ fn render_binary_content(frame: &mut Frame, area: Rect, path: &str, focused: boo
     let lines = vec![
         Line::from(Span::styled(
             "Binary conflict detected.",
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme().accent),
         )),
         Line::from(""),
         Line::from("Accept changes with:"),
         Line::from(vec![
-            Span::styled("co", Style::default().fg(Color::Cyan)),
+            Span::styled("co", Style::default().fg(theme().info)),
             Span::raw(" accept ours"),
         ]),
         Line::from(vec![
-            Span::styled("ct", Style::default().fg(Color::Cyan)),
+            Span::styled("ct", Style::default().fg(theme().info)),
             Span::raw(" accept theirs"),
         ]),
         Line::from(""),
@@ -248,9 +249,9 @@ fn render_text_conflict_content(
     );
 
     let border_color = if focused {
-        Color::Blue
+        theme().border
     } else {
-        Color::DarkGray
+        theme().muted
     };
     let block = Block::default()
         .borders(Borders::ALL)
@@ -268,28 +269,28 @@ fn render_text_conflict_content(
         Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(inner);
 
     let hint = Line::from(vec![
-        Span::styled("Accept hunk: ", Style::default().fg(Color::DarkGray)),
+        Span::styled("Accept hunk: ", Style::default().fg(theme().muted)),
         Span::styled(
             "co",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" ours  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" ours  ", Style::default().fg(theme().muted)),
         Span::styled(
             "ct",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" theirs  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" theirs  ", Style::default().fg(theme().muted)),
         Span::styled(
             "cb",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme().info)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" both", Style::default().fg(Color::DarkGray)),
+        Span::styled(" both", Style::default().fg(theme().muted)),
     ]);
     frame.render_widget(Paragraph::new(hint), hint_area);
     editor.render(frame, editor_area, focused);
@@ -307,13 +308,13 @@ fn render_hunk_pane(
     let block = Block::default()
         .borders(Borders::ALL)
         .title(format!("{} {} ", title, hunk_title))
-        .border_style(Style::default().fg(Color::DarkGray));
+        .border_style(Style::default().fg(theme().muted));
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     let Some(preview) = preview else {
         let text = Paragraph::new("No conflict hunks in current draft")
-            .style(Style::default().fg(Color::DarkGray));
+            .style(Style::default().fg(theme().muted));
         frame.render_widget(text, inner);
         return;
     };
@@ -322,32 +323,32 @@ fn render_hunk_pane(
     for line in &preview.before {
         lines.push(Line::from(Span::styled(
             format!("  {line}"),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )));
     }
 
     lines.push(Line::from(Span::styled(
         "----------------",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme().muted),
     )));
 
     let body = if ours { &preview.ours } else { &preview.theirs };
     for line in body {
         lines.push(Line::from(Span::styled(
             line.clone(),
-            Style::default().fg(Color::White),
+            Style::default().fg(theme().text),
         )));
     }
 
     lines.push(Line::from(Span::styled(
         "----------------",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme().muted),
     )));
 
     for line in &preview.after {
         lines.push(Line::from(Span::styled(
             format!("  {line}"),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )));
     }
 
@@ -375,7 +376,7 @@ fn render_notification(frame: &mut Frame, area: Rect, message: &str) {
         format!(" {} ", message),
         Style::default()
             .fg(Color::Black)
-            .bg(Color::Green)
+            .bg(theme().success)
             .add_modifier(Modifier::BOLD),
     )));
     frame.render_widget(paragraph, notification_area);
@@ -0,0 +1,312 @@
+//! Export notes to Markdown files on disk and import them back.
+//!
+//! A note becomes `<name>.md` (names that already have an extension are kept
+//! as-is) and a folder becomes a directory, so an exported subtree mirrors the
+//! sidebar tree. Import is the inverse: `.md` is stripped from file names,
+//! binary files, symlinks and dotfiles are skipped and name clashes get a
+//! ` (2)` suffix.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+
+use crate::model::{self, EntryType, NoteEntry};
+
+/// Fail unless `name` is a single plain path component, so an entry named
+/// `..` (or `.`) cannot write outside the export directory.
+fn check_component(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => bail!("'{name}' is not a valid file name"),
+    }
+}
+
+/// File name used for a note called `name`.
+pub fn note_file_name(name: &str) -> String {
+    if Path::new(name).extension().is_some() {
+        name.to_string()
+    } else {
+        format!("{name}.md")
+    }
+}
+
+/// Write the body of note `entry_id` to `path`, creating parent directories.
+pub fn export_note(conn: &Connection, entry_id: i64, path: &Path) -> Result<()> {
+    let content = model::get_note_content(conn, entry_id)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content.body)?;
+    Ok(())
+}
+
+/// Write folder `folder_id` and everything below it into `dir/<folder name>`.
+/// Returns the number of notes written.
+pub fn export_folder(conn: &Connection, folder_id: i64, dir: &Path) -> Result<usize> {
+    let entries = model::list_entries(conn)?;
+    let mut children: HashMap<Option<i64>, Vec<&NoteEntry>> = HashMap::new();
+    for e in &entries {
+        children.entry(e.parent_id).or_default().push(e);
+    }
+    let folder = entries
+        .iter()
+        .find(|e| e.id == folder_id)
+        .ok_or_else(|| anyhow::anyhow!("folder {folder_id} not found"))?;
+
+    let mut count = 0;
+    write_entry(conn, &children, folder, dir, &mut count)?;
+    Ok(count)
+}
+
+fn write_entry(
+    conn: &Connection,
+    children: &HashMap<Option<i64>, Vec<&NoteEntry>>,
+    entry: &NoteEntry,
+    dir: &Path,
+    count: &mut usize,
+) -> Result<()> {
+    match entry.entry_type {
+        EntryType::Note => {
+            check_component(&entry.name)?;
+            export_note(conn, entry.id, &dir.join(note_file_name(&entry.name)))?;
+            *count += 1;
+        }
+        EntryType::Folder => {
+            check_component(&entry.name)?;
+            let sub_dir: PathBuf = dir.join(&entry.name);
+            std::fs::create_dir_all(&sub_dir)?;
+            for child in children.get(&Some(entry.id)).into_iter().flatten() {
+                write_entry(conn, children, child, &sub_dir, count)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Result of an import.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub notes: usize,
+    pub folders: usize,
+    /// Files that were not valid UTF-8 text, and symbolic links.
+    pub skipped: usize,
+}
+
+/// Import the file or directory at `path` under `parent_id`. A directory is
+/// recreated as a folder with its subtree. Returns what was created; on an
+/// error nothing is.
+pub fn import_path(
+    conn: &Connection,
+    path: &Path,
+    parent_id: Option<i64>,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    match path.symlink_metadata() {
+        Err(_) => bail!("{} not found", path.display()),
+        Ok(meta) if meta.file_type().is_symlink() => {
+            bail!("{} is a symbolic link", path.display())
+        }
+        Ok(_) => {}
+    }
+    let tx = conn.unchecked_transaction()?;
+    import_entry(&tx, path, parent_id, &mut summary)?;
+    tx.commit()?;
+    Ok(summary)
+}
+
+fn import_entry(
+    conn: &Connection,
+    path: &Path,
+    parent_id: Option<i64>,
+    summary: &mut ImportSummary,
+) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+
+    // Links could point anywhere (or loop back into the tree)
+    if path.symlink_metadata()?.file_type().is_symlink() {
+        summary.skipped += 1;
+        return Ok(());
+    }
+
+    if path.is_dir() {
+        let name = unique_name(conn, parent_id, &file_name)?;
+        let folder_id = model::add_entry(conn, parent_id, &name, EntryType::Folder)?;
+        summary.folders += 1;
+
+        let mut children: Vec<PathBuf> = std::fs::read_dir(path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .is_some_and(|n| !n.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        children.sort();
+        for child in children {
+            import_entry(conn, &child, Some(folder_id), summary)?;
+        }
+        return Ok(());
+    }
+
+    let Some(body) = read_text(path)? else {
+        summary.skipped += 1;
+        return Ok(());
+    };
+    let base = file_name.strip_suffix(".md").unwrap_or(&file_name);
+    let name = unique_name(conn, parent_id, base)?;
+    let entry_id = model::add_entry(conn, parent_id, &name, EntryType::Note)?;
+    model::save_note_content(conn, entry_id, &body)?;
+    summary.notes += 1;
+    Ok(())
+}
+
+/// File contents if they are UTF-8 text without NUL bytes.
+fn read_text(path: &Path) -> Result<Option<String>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.contains(&0) {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(bytes).ok())
+}
+
+/// `name`, or `name (2)`, `name (3)`, … if a sibling already uses it.
+fn unique_name(conn: &Connection, parent_id: Option<i64>, name: &str) -> Result<String> {
+    let taken = model::child_names(conn, parent_id)?;
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{name} ({n})");
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::db::open_memory_db;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rstools-notes-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_export_folder_mirrors_tree() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let work = model::add_entry(&conn, None, "work", EntryType::Folder).unwrap();
+        let plan = model::add_entry(&conn, Some(work), "plan", EntryType::Note).unwrap();
+        model::save_note_content(&conn, plan, "# Plan").unwrap();
+        let sub = model::add_entry(&conn, Some(work), "sub", EntryType::Folder).unwrap();
+        model::add_entry(&conn, Some(sub), "todo.txt", EntryType::Note).unwrap();
+        model::add_entry(&conn, None, "outside", EntryType::Note).unwrap();
+
+        let dir = temp_dir("export");
+        assert_eq!(export_folder(&conn, work, &dir).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("work/plan.md")).unwrap(),
+            "# Plan"
+        );
+        assert!(dir.join("work/sub/todo.txt").is_file());
+        assert!(!dir.join("outside.md").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_rejects_escaping_names() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let work = model::add_entry(&conn, None, "work", EntryType::Folder).unwrap();
+        let up = model::add_entry(&conn, Some(work), "..", EntryType::Folder).unwrap();
+        model::add_entry(&conn, Some(up), "escaped", EntryType::Note).unwrap();
+
+        let dir = temp_dir("export-escape");
+        let err = export_folder(&conn, work, &dir).unwrap_err();
+        assert_eq!(err.to_string(), "'..' is not a valid file name");
+        assert!(!dir.join("escaped.md").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_import_directory() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let src = temp_dir("import");
+        std::fs::create_dir_all(src.join("journal/.git")).unwrap();
+        std::fs::write(src.join("journal/day.md"), "# Day").unwrap();
+        std::fs::write(src.join("journal/list.txt"), "a\nb").unwrap();
+        std::fs::write(src.join("journal/logo.png"), [0x89, b'P', 0, 1]).unwrap();
+        std::fs::write(src.join("journal/.git/HEAD"), "ref").unwrap();
+
+        let summary = import_path(&conn, &src.join("journal"), None).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                notes: 2,
+                folders: 1,
+                skipped: 1,
+            }
+        );
+
+        // Importing again keeps both copies apart
+        import_path(&conn, &src.join("journal"), None).unwrap();
+        let entries = model::list_entries(&conn).unwrap();
+        let mut names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "day",
+                "day",
+                "journal",
+                "journal (2)",
+                "list.txt",
+                "list.txt"
+            ]
+        );
+        let day = entries.iter().find(|e| e.name == "day").unwrap();
+        assert_eq!(
+            model::get_note_content(&conn, day.id).unwrap().body,
+            "# Day"
+        );
+
+        assert!(import_path(&conn, &src.join("missing"), None).is_err());
+        std::fs::remove_dir_all(&src).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_import_skips_symlinks() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let src = temp_dir("import-links");
+        std::fs::create_dir_all(src.join("notes")).unwrap();
+        std::fs::write(src.join("secret.txt"), "outside").unwrap();
+        std::fs::write(src.join("notes/kept.md"), "kept").unwrap();
+        std::os::unix::fs::symlink(src.join("secret.txt"), src.join("notes/link.md")).unwrap();
+        // A link back to the parent would otherwise recurse forever
+        std::os::unix::fs::symlink(&src, src.join("notes/loop")).unwrap();
+
+        let summary = import_path(&conn, &src.join("notes"), None).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                notes: 1,
+                folders: 1,
+                skipped: 2,
+            }
+        );
+
+        // A link given directly is refused
+        let err = import_path(&conn, &src.join("notes/link.md"), None).unwrap_err();
+        assert!(err.to_string().ends_with("is a symbolic link"));
+        std::fs::remove_dir_all(&src).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! `:!cmd` / `:%!cmd`: pipe note lines through an external program.
+//!
+//! The command runs through the shell with the lines on stdin; its stdout
+//! replaces them. Pipes are drained on their own threads so a chatty command
+//! cannot block, and a command still running after the timeout is killed.
+
+use anyhow::{Result, bail};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the child is polled while waiting for it to exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Run `command` with `input` on stdin and return its stdout. Fails when the
+/// command cannot start, exits unsuccessfully (with the first stderr line)
+/// or is still running after `timeout`.
+pub fn run_filter(command: &str, input: &str, timeout: Duration) -> Result<String> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    thread::spawn(move || {
+        // A command that ignores its input closes the pipe early; that is fine.
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("timed out after {timeout:?}");
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // A background grandchild may keep the pipes open after the shell exits.
+    let collect = |rx: Receiver<Vec<u8>>| {
+        rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .map_err(|_| anyhow::anyhow!("timed out after {timeout:?}"))
+    };
+    let stdout = collect(stdout)?;
+    let stderr = collect(stderr)?;
+    if !status.success() {
+        match String::from_utf8_lossy(&stderr).lines().next() {
+            Some(line) if !line.trim().is_empty() => bail!("{}", line.trim()),
+            _ => bail!("{status}"),
+        }
+    }
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// `command` run by the platform shell.
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Read `pipe` to the end on a background thread.
+fn drain(mut pipe: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        let _ = tx.send(buf);
+    });
+    rx
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_filter_output() {
+        assert_eq!(run_filter("sort", "b\na\n", TIMEOUT).unwrap(), "a\nb\n");
+        assert_eq!(run_filter("tr a-z A-Z", "hi\n", TIMEOUT).unwrap(), "HI\n");
+    }
+
+    #[test]
+    fn test_filter_failures() {
+        let err = run_filter("echo oops >&2; exit 3", "", TIMEOUT).unwrap_err();
+        assert_eq!(err.to_string(), "oops");
+        let err = run_filter("sleep 5", "", Duration::from_millis(50)).unwrap_err();
+        assert_eq!(err.to_string(), "timed out after 50ms");
+        let err = run_filter("sleep 5 & echo started", "", Duration::from_millis(200)).unwrap_err();
+        assert_eq!(err.to_string(), "timed out after 200ms");
+    }
+}
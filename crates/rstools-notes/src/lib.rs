@@ -1,21 +1,35 @@
+pub mod files;
+pub mod filter;
+pub mod links;
+pub mod markdown;
 pub mod model;
 pub mod sidebar;
+pub mod tags;
+pub mod templates;
 pub mod ui;
 
 use rstools_core::help_popup::HelpEntry;
-use rstools_core::keybinds::{Action, InputMode, KeyState};
-use rstools_core::telescope::TelescopeItem;
+use rstools_core::keybinds::{Action, InputMode, KeyAction, KeyState, keymap};
+use rstools_core::paths::expand_tilde;
+use rstools_core::telescope::{self, TelescopeItem};
+use rstools_core::toast::Toast;
 use rstools_core::tool::Tool;
 use rstools_core::tree_sidebar::TreeEntry;
+use rstools_core::vim_editor::buffer::SortOptions;
+use rstools_core::vim_editor::history::History;
 use rstools_core::vim_editor::{EditorAction, VimEditor, VimMode};
 use rstools_core::which_key::WhichKeyEntry;
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{Frame, layout::Rect};
+use regex::{Regex, RegexBuilder};
 use rusqlite::Connection;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
-use model::EntryType;
+use model::{EntryType, NotesSettings};
 use sidebar::{ClipboardMode, NotesSidebarExt, SidebarInput, SidebarState, TreeNode};
+use tags::TagIndex;
 
 #[derive(Debug, Clone)]
 struct GrepCandidate {
@@ -32,8 +46,100 @@ struct GrepMatch {
     note_name: String,
     note_path: String,
     line_number: usize,
+    /// Byte offset and length of the match in the line.
     column: usize,
-    line_text: String,
+    match_len: usize,
+}
+
+/// Build the grep matcher for `query`: a regex with the regex toggle on,
+/// otherwise a literal string; `whole_word` wraps it in word boundaries.
+/// Always case-insensitive.
+fn build_grep_regex(
+    query: &str,
+    regex_mode: bool,
+    whole_word: bool,
+) -> Result<Regex, regex::Error> {
+    let pattern = if regex_mode {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let pattern = if whole_word {
+        format!(r"\b(?:{pattern})\b")
+    } else {
+        pattern
+    };
+    RegexBuilder::new(&pattern).case_insensitive(true).build()
+}
+
+/// A parsed `:s/old/new/[g]` or `:%s/old/new/[g]` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Substitution {
+    pattern: String,
+    replacement: String,
+    /// `%`: every line instead of the cursor line.
+    whole_buffer: bool,
+    /// `g` flag: every occurrence per line instead of the first.
+    global: bool,
+}
+
+/// Parse a substitute command. Patterns are literal strings and `\/`
+/// escapes the delimiter. Returns None unless the command is well-formed.
+fn parse_substitute(cmd: &str) -> Option<Substitution> {
+    let (whole_buffer, rest) = match cmd.strip_prefix('%') {
+        Some(rest) => (true, rest),
+        None => (false, cmd),
+    };
+    let rest = rest.strip_prefix("s/")?;
+
+    let mut parts = vec![String::new()];
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'/') => {
+                chars.next();
+                parts.last_mut()?.push('/');
+            }
+            '/' if parts.len() < 3 => parts.push(String::new()),
+            _ => parts.last_mut()?.push(c),
+        }
+    }
+
+    let mut parts = parts.into_iter();
+    let pattern = parts.next().filter(|p| !p.is_empty())?;
+    let replacement = parts.next().unwrap_or_default();
+    let global = match parts.next().as_deref() {
+        None | Some("") => false,
+        Some("g") => true,
+        Some(_) => return None,
+    };
+    Some(Substitution {
+        pattern,
+        replacement,
+        whole_buffer,
+        global,
+    })
+}
+
+/// Parse the `!` and flags after `:sort`: `!` reverses, `u` drops
+/// duplicates and `n` sorts numerically. Returns None for unknown flags.
+fn parse_sort(rest: &str) -> Option<SortOptions> {
+    let (reverse, flags) = match rest.strip_prefix('!') {
+        Some(flags) => (true, flags),
+        None => (false, rest),
+    };
+    let mut options = SortOptions {
+        reverse,
+        ..SortOptions::default()
+    };
+    for c in flags.chars().filter(|c| !c.is_whitespace()) {
+        match c {
+            'u' => options.unique = true,
+            'n' => options.numeric = true,
+            _ => return None,
+        }
+    }
+    Some(options)
 }
 
 pub struct NotesTool {
@@ -52,24 +158,90 @@ pub struct NotesTool {
     pending_s_group: bool,
     /// Whether full-note grep overlay is active.
     grep_active: bool,
+    /// Title of the grep overlay ("Grep Notes", "Backlinks: …", …).
+    grep_title: String,
     /// Current grep query.
     grep_query: String,
+    /// Treat the grep query as a regex (`Ctrl-r`).
+    grep_regex: bool,
+    /// Only match whole words (`Ctrl-w`).
+    grep_whole_word: bool,
+    /// Why the last query could not be used (invalid regex); the previous
+    /// results stay visible meanwhile.
+    grep_error: Option<String>,
     /// All searchable note lines.
     grep_candidates: Vec<GrepCandidate>,
     /// Filtered grep matches.
     grep_matches: Vec<GrepMatch>,
     /// Selected grep match index.
     grep_selected: usize,
+    /// `#tag` index over all note bodies, rebuilt when notes change.
+    tag_index: TagIndex,
+    /// Whether the tag picker overlay is active.
+    tag_picker_active: bool,
+    /// Filter typed in the tag picker.
+    tag_query: String,
+    /// Selected row in the filtered tag list.
+    tag_selected: usize,
+    /// Whether the editor panel shows the rendered Markdown preview.
+    preview_active: bool,
+    /// Scroll offset of the Markdown preview (in rendered lines).
+    preview_scroll: usize,
+    /// Rows of the Visual selection `:` was pressed in, the command's range.
+    command_rows: Option<(usize, usize)>,
+    /// Toasts raised since the hub last drained them.
+    toasts: Vec<Toast>,
+    settings: NotesSettings,
+    /// When the open note last became dirty (start of the auto-save interval).
+    dirty_since: Option<Instant>,
+    /// Last key handled by the editor, used to hold auto-save while typing.
+    last_edit_at: Option<Instant>,
+    /// Undo histories of recently open notes, most recent first, with the
+    /// text they were taken from.
+    recent_histories: VecDeque<(i64, String, History)>,
 }
 
+/// Lines scrolled by Ctrl-d / Ctrl-u in the Markdown preview.
+const PREVIEW_HALF_PAGE: usize = 10;
+
+/// Lines shown above and below each grep match in the results list.
+const GREP_CONTEXT_LINES: usize = 2;
+
+/// Notes whose undo history is kept after switching away from them.
+const RECENT_HISTORIES: usize = 8;
+
+/// Quiet time after the last keystroke before an auto-save may run.
+const AUTOSAVE_PAUSE: Duration = Duration::from_millis(1000);
+
+/// Accepted values for `:set autosave <secs>`.
+const AUTOSAVE_RANGE: std::ops::RangeInclusive<u64> = 1..=60 * 60;
+
+/// Accepted values for `:set tw=<width>`.
+const TEXT_WIDTH_RANGE: std::ops::RangeInclusive<usize> = 10..=1000;
+
+/// How long a `:!` filter may run before it is killed.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Accepted values for `:set sw=<width>`.
+const SHIFT_WIDTH_RANGE: std::ops::RangeInclusive<usize> = 1..=16;
+
 impl NotesTool {
     pub fn new(conn: Connection) -> anyhow::Result<Self> {
         model::init_db(&conn)?;
+        let settings = model::load_settings(&conn).unwrap_or_default();
         let mut sidebar = SidebarState::new();
         NotesSidebarExt::reload(&mut sidebar, &conn)?;
-        Ok(Self {
+        let mut editor = VimEditor::new();
+        editor.line_numbers = settings.line_numbers;
+        editor.relative_numbers = settings.relative_numbers;
+        editor.wrap = settings.wrap;
+        editor.text_width = settings.text_width;
+        editor.auto_pairs = settings.auto_pairs;
+        editor.shift_width = settings.shift_width;
+        editor.expand_tab = settings.expand_tab;
+        let mut tool = Self {
             sidebar,
-            editor: VimEditor::new(),
+            editor,
             mode: InputMode::Normal,
             key_state: KeyState::default(),
             conn,
@@ -78,23 +250,120 @@ impl NotesTool {
             active_note_name: None,
             pending_s_group: false,
             grep_active: false,
+            grep_title: String::new(),
             grep_query: String::new(),
+            grep_regex: false,
+            grep_whole_word: false,
+            grep_error: None,
             grep_candidates: Vec::new(),
             grep_matches: Vec::new(),
             grep_selected: 0,
-        })
+            tag_index: TagIndex::default(),
+            tag_picker_active: false,
+            tag_query: String::new(),
+            tag_selected: 0,
+            preview_active: false,
+            preview_scroll: 0,
+            command_rows: None,
+            toasts: Vec::new(),
+            settings,
+            dirty_since: None,
+            last_edit_at: None,
+            recent_histories: VecDeque::new(),
+        };
+        tool.rebuild_tag_index();
+        Ok(tool)
+    }
+
+    fn show_notification(&mut self, message: impl Into<String>) {
+        self.toasts.push(Toast::info(message));
+    }
+
+    /// Run a `:s` / `:%s` substitution on the open note.
+    fn run_substitute(&mut self, sub: &Substitution) {
+        if self.active_note_id.is_none() {
+            self.show_notification("No note open");
+            return;
+        }
+        let count =
+            self.editor
+                .substitute(&sub.pattern, &sub.replacement, sub.whole_buffer, sub.global);
+        match count {
+            0 => self.show_notification(format!("Pattern not found: {}", sub.pattern)),
+            1 => self.show_notification("1 substitution"),
+            n => self.show_notification(format!("{n} substitutions")),
+        }
+    }
+
+    /// Sort the lines selected when `:` was pressed, or the whole note.
+    fn run_sort(&mut self, options: SortOptions, rows: Option<(usize, usize)>) {
+        if self.active_note_id.is_none() {
+            self.show_notification("No note open");
+            return;
+        }
+        let (start_row, end_row) = rows.unwrap_or((0, self.editor.buffer.line_count() - 1));
+        let before = end_row - start_row + 1;
+        let after = self.editor.sort_lines(start_row, end_row, options);
+        self.track_edit();
+        let sorted = match before {
+            1 => "1 line sorted".to_string(),
+            n => format!("{n} lines sorted"),
+        };
+        self.show_notification(match before - after {
+            0 => sorted,
+            1 => format!("{sorted}, 1 duplicate removed"),
+            n => format!("{sorted}, {n} duplicates removed"),
+        });
+    }
+
+    /// Pipe the cursor line (or the whole note) through `command` and
+    /// replace it with the output. Disabled unless `:set shellfilter`.
+    /// Pipe rows `start_row..=end_row` through `command` (`:!`, `:%!`).
+    fn run_filter(&mut self, command: &str, (start_row, end_row): (usize, usize)) {
+        if self.active_note_id.is_none() {
+            self.show_notification("No note open");
+            return;
+        }
+        if !self.settings.shell_filter {
+            self.show_notification("Filters are off: enable with :set shellfilter");
+            return;
+        }
+        if command.is_empty() {
+            self.show_notification("Usage: :!<command> or :%!<command>");
+            return;
+        }
+        let mut input = self.editor.buffer.lines[start_row..=end_row].join("\n");
+        input.push('\n');
+        match filter::run_filter(command, &input, FILTER_TIMEOUT) {
+            Ok(output) => {
+                self.editor.replace_lines(start_row, end_row, &output);
+                self.track_edit();
+                let lines = end_row - start_row + 1;
+                self.show_notification(match lines {
+                    1 => "1 line filtered".to_string(),
+                    n => format!("{n} lines filtered"),
+                });
+            }
+            Err(e) => self.show_notification(format!("Filter failed: {e}")),
+        }
     }
 
     /// Open a note in the editor panel.
     fn open_note(&mut self, entry_id: i64, name: &str) {
         // Save current note if dirty
         self.auto_save_current();
+        self.save_position();
+        self.stash_history();
+        self.preview_scroll = 0;
+        self.dirty_since = None;
 
         // Load the new note's content
         match model::get_note_content(&self.conn, entry_id) {
             Ok(content) => {
                 self.editor.set_text(&content.body);
                 self.editor.mark_clean();
+                self.restore_history(entry_id, &content.body);
+                self.restore_position(entry_id);
                 self.active_note_id = Some(entry_id);
                 self.active_note_name = Some(name.to_string());
                 self.sidebar_focused = false;
@@ -110,12 +379,74 @@ impl NotesTool {
         }
     }
 
+    /// Remember where the cursor is in the open note.
+    fn save_position(&self) {
+        let Some(entry_id) = self.active_note_id else {
+            return;
+        };
+        let position = model::NotePosition {
+            cursor_row: self.editor.buffer.cursor_row,
+            cursor_col: self.editor.buffer.cursor_col,
+        };
+        let _ = model::save_note_position(&self.conn, entry_id, position);
+    }
+
+    /// Put the cursor back where it was left, clamped to the current text.
+    fn restore_position(&mut self, entry_id: i64) {
+        let Ok(position) = model::get_note_position(&self.conn, entry_id) else {
+            return;
+        };
+        let buffer = &mut self.editor.buffer;
+        buffer.cursor_row = position.cursor_row.min(buffer.line_count() - 1);
+        buffer.cursor_col = position.cursor_col;
+        buffer.clamp_cursor_col(false);
+        while !buffer.current_line().is_char_boundary(buffer.cursor_col) {
+            buffer.cursor_col -= 1;
+        }
+        buffer.desired_col = buffer.cursor_col;
+    }
+
+    /// Keep the open note's undo history for when it is reopened.
+    fn stash_history(&mut self) {
+        let Some(entry_id) = self.active_note_id else {
+            return;
+        };
+        self.recent_histories.retain(|(id, _, _)| *id != entry_id);
+        let history = self.editor.take_history();
+        self.recent_histories
+            .push_front((entry_id, self.editor.text(), history));
+        self.recent_histories.truncate(RECENT_HISTORIES);
+    }
+
+    /// Reinstall a stashed undo history, unless the note changed since.
+    fn restore_history(&mut self, entry_id: i64, body: &str) {
+        let Some(index) = self
+            .recent_histories
+            .iter()
+            .position(|(id, _, _)| *id == entry_id)
+        else {
+            return;
+        };
+        if let Some((_, text, history)) = self.recent_histories.remove(index)
+            && text == body
+        {
+            self.editor.set_history(history);
+        }
+    }
+
     /// Save the current note to the database.
     fn save_current_note(&mut self) -> bool {
         if let Some(entry_id) = self.active_note_id {
+            // Not while typing: an auto-save must not eat the space before
+            // the next word
+            if self.settings.trim_on_save && self.editor.mode == VimMode::Normal {
+                self.editor.tidy_whitespace();
+            }
             let text = self.editor.text();
             if model::save_note_content(&self.conn, entry_id, &text).is_ok() {
                 self.editor.mark_clean();
+                self.dirty_since = None;
+                self.rebuild_tag_index();
                 return true;
             }
         }
@@ -129,28 +460,341 @@ impl NotesTool {
         }
     }
 
-    /// Create entries from a path string (e.g., "folder/subfolder/note-name").
-    fn create_entries_from_path(&mut self, path: &str) {
-        let path = path.trim();
+    /// Record editor activity after a key or paste reached the editor.
+    fn track_edit(&mut self) {
+        let now = Instant::now();
+        self.last_edit_at = Some(now);
+        if self.editor.is_dirty() {
+            self.dirty_since.get_or_insert(now);
+        } else {
+            self.dirty_since = None;
+        }
+    }
+
+    /// Whether the dirty note should be auto-saved at `now`: the interval
+    /// has elapsed and the user paused typing for `AUTOSAVE_PAUSE`.
+    fn autosave_due(&self, now: Instant) -> bool {
+        let interval = Duration::from_secs(self.settings.autosave_secs);
+        self.settings.autosave_secs > 0
+            && self.active_note_id.is_some()
+            && self.editor.is_dirty()
+            && self
+                .dirty_since
+                .is_some_and(|since| now.duration_since(since) >= interval)
+            && self
+                .last_edit_at
+                .is_none_or(|edit| now.duration_since(edit) >= AUTOSAVE_PAUSE)
+    }
+
+    /// Handle `:set` for the notes settings.
+    fn apply_setting(&mut self, args: &str) {
+        let (name, value) = match args.split_once([' ', '=']) {
+            Some((name, value)) => (name, value.trim()),
+            None => (args, ""),
+        };
+        let mut settings = self.settings.clone();
+
+        match (name, value) {
+            ("", _) => {
+                let autosave = match self.settings.autosave_secs {
+                    0 => "off".to_string(),
+                    secs => format!("{secs}s"),
+                };
+                let flag = |on: bool, name: &str| {
+                    if on {
+                        name.to_string()
+                    } else {
+                        format!("no{name}")
+                    }
+                };
+                self.show_notification(format!(
+                    "autosave={autosave} {} {} {} tw={} {} sw={} {} {} {}",
+                    flag(self.settings.line_numbers, "nu"),
+                    flag(self.settings.relative_numbers, "rnu"),
+                    flag(self.settings.wrap, "wrap"),
+                    self.settings.text_width,
+                    flag(self.settings.auto_pairs, "autopairs"),
+                    self.settings.shift_width,
+                    flag(self.settings.expand_tab, "et"),
+                    flag(self.settings.trim_on_save, "trimonsave"),
+                    flag(self.settings.shell_filter, "shellfilter")
+                ));
+                return;
+            }
+            ("nu" | "number", "") => settings.line_numbers = true,
+            ("nonu" | "nonumber", "") => settings.line_numbers = false,
+            ("nu!" | "number!", "") => settings.line_numbers = !settings.line_numbers,
+            ("rnu" | "relativenumber", "") => settings.relative_numbers = true,
+            ("nornu" | "norelativenumber", "") => settings.relative_numbers = false,
+            ("rnu!" | "relativenumber!", "") => {
+                settings.relative_numbers = !settings.relative_numbers
+            }
+            ("wrap", "") => settings.wrap = true,
+            ("nowrap", "") => settings.wrap = false,
+            ("wrap!", "") => settings.wrap = !settings.wrap,
+            ("trimonsave", "") => settings.trim_on_save = true,
+            ("notrimonsave", "") => settings.trim_on_save = false,
+            ("trimonsave!", "") => settings.trim_on_save = !settings.trim_on_save,
+            ("shellfilter", "") => settings.shell_filter = true,
+            ("noshellfilter", "") => settings.shell_filter = false,
+            ("shellfilter!", "") => settings.shell_filter = !settings.shell_filter,
+            ("autopairs", "") => settings.auto_pairs = true,
+            ("noautopairs", "") => settings.auto_pairs = false,
+            ("autopairs!", "") => settings.auto_pairs = !settings.auto_pairs,
+            ("tw" | "textwidth", width) => {
+                match width.parse().ok().filter(|w| TEXT_WIDTH_RANGE.contains(w)) {
+                    Some(width) => settings.text_width = width,
+                    None => {
+                        self.show_notification(format!(
+                            "Invalid textwidth: use {}-{}",
+                            TEXT_WIDTH_RANGE.start(),
+                            TEXT_WIDTH_RANGE.end()
+                        ));
+                        return;
+                    }
+                }
+            }
+            ("et" | "expandtab", "") => settings.expand_tab = true,
+            ("noet" | "noexpandtab", "") => settings.expand_tab = false,
+            ("et!" | "expandtab!", "") => settings.expand_tab = !settings.expand_tab,
+            ("sw" | "shiftwidth", width) => {
+                match width.parse().ok().filter(|w| SHIFT_WIDTH_RANGE.contains(w)) {
+                    Some(width) => settings.shift_width = width,
+                    None => {
+                        self.show_notification(format!(
+                            "Invalid shiftwidth: use {}-{}",
+                            SHIFT_WIDTH_RANGE.start(),
+                            SHIFT_WIDTH_RANGE.end()
+                        ));
+                        return;
+                    }
+                }
+            }
+            ("autosave", "off") => settings.autosave_secs = 0,
+            ("autosave", secs) => match secs.parse().ok().filter(|s| AUTOSAVE_RANGE.contains(s)) {
+                Some(secs) => settings.autosave_secs = secs,
+                None => {
+                    self.show_notification(format!(
+                        "Invalid autosave: use off or {}-{} seconds",
+                        AUTOSAVE_RANGE.start(),
+                        AUTOSAVE_RANGE.end()
+                    ));
+                    return;
+                }
+            },
+            _ => {
+                self.show_notification(format!("Unknown setting: {name}"));
+                return;
+            }
+        }
+
+        match model::save_settings(&self.conn, &settings) {
+            Ok(()) => {
+                self.editor.line_numbers = settings.line_numbers;
+                self.editor.relative_numbers = settings.relative_numbers;
+                self.editor.wrap = settings.wrap;
+                self.editor.text_width = settings.text_width;
+                self.editor.auto_pairs = settings.auto_pairs;
+                self.editor.shift_width = settings.shift_width;
+                self.editor.expand_tab = settings.expand_tab;
+                self.settings = settings;
+                self.show_notification(format!("Set {name}"));
+            }
+            Err(e) => self.show_notification(format!("Failed to save setting: {e}")),
+        }
+    }
+
+    // ── Templates ────────────────────────────────────────────────────
+
+    /// Body of the template `name`: a note in the `templates` folder, else a
+    /// built-in template.
+    fn template_body(&self, name: &str) -> Option<String> {
+        model::list_folder_notes(&self.conn, templates::TEMPLATES_FOLDER)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, body)| body)
+            .or_else(|| templates::builtin(name).map(str::to_string))
+    }
+
+    /// Names of all available templates (user templates first).
+    fn template_names(&self) -> Vec<String> {
+        let mut names: Vec<String> =
+            model::list_folder_notes(&self.conn, templates::TEMPLATES_FOLDER)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect();
+        for (name, _) in templates::BUILTIN_TEMPLATES {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Expand template `name` for a note called `title`.
+    fn render_template(&mut self, name: &str, title: &str) -> Option<String> {
+        match self.template_body(name) {
+            Some(body) => Some(templates::expand(
+                &body,
+                title,
+                chrono::Local::now().naive_local(),
+            )),
+            None => {
+                self.notify_unknown_template(name);
+                None
+            }
+        }
+    }
+
+    fn notify_unknown_template(&mut self, name: &str) {
+        self.show_notification(format!(
+            "Unknown template: {name} (available: {})",
+            self.template_names().join(", ")
+        ));
+    }
+
+    /// Create a note from the sidebar prompt; a trailing ` @name` fills the
+    /// new note with that template and opens it. Nothing is created when
+    /// the template does not exist.
+    fn create_entries_with_template(&mut self, input: &str) {
+        let (path, template) = templates::split_template_suffix(input);
+        if let Some(name) = template
+            && self.template_body(name).is_none()
+        {
+            self.notify_unknown_template(name);
+            return;
+        }
+        let created = self.create_entries_from_path(path);
+
+        let (Some(template), Some(entry_id)) = (template, created) else {
+            return;
+        };
+        let Some(entry) = self.sidebar.selected_entry().filter(|e| !e.is_folder) else {
+            return;
+        };
+        let name = entry.name.clone();
+        if let Some(body) = self.render_template(template, &name) {
+            let _ = model::save_note_content(&self.conn, entry_id, &body);
+            self.rebuild_tag_index();
+            self.open_note(entry_id, &name);
+        }
+    }
+
+    /// `:tmpl <name>`: insert a template into the open note at the cursor.
+    fn insert_template(&mut self, name: &str) {
+        if name.is_empty() {
+            self.show_notification(format!("Templates: {}", self.template_names().join(", ")));
+            return;
+        }
+        let Some(title) = self.active_note_name.clone() else {
+            self.show_notification("No note open");
+            return;
+        };
+        if let Some(body) = self.render_template(name, &title) {
+            self.editor.paste_text(&body);
+            self.track_edit();
+        }
+    }
+
+    // ── Export ───────────────────────────────────────────────────────
+
+    /// `:export <path>`: write the folder selected in the focused sidebar
+    /// into the directory `path`, otherwise the open note to the file
+    /// `path` (or into it, when `path` is a directory or ends with `/`).
+    fn export(&mut self, path: &str) {
         if path.is_empty() {
+            self.show_notification("Usage: :export <path>");
             return;
         }
+        let path = expand_tilde(path);
 
-        let trailing_slash = path.ends_with('/');
-        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-        if segments.is_empty() {
+        let folder = self
+            .sidebar
+            .selected_entry()
+            .filter(|e| self.sidebar_focused && e.is_folder)
+            .map(|e| e.entry_id);
+        if let Some(folder_id) = folder {
+            match files::export_folder(&self.conn, folder_id, std::path::Path::new(&path)) {
+                Ok(count) => self.show_notification(format!("Exported {count} notes to {path}")),
+                Err(e) => self.show_notification(format!("Export failed: {e}")),
+            }
+            return;
+        }
+
+        let (Some(entry_id), Some(name)) = (self.active_note_id, self.active_note_name.clone())
+        else {
+            self.show_notification("No note open");
+            return;
+        };
+        self.auto_save_current();
+        let mut target = std::path::PathBuf::from(&path);
+        if path.ends_with('/') || target.is_dir() {
+            target.push(files::note_file_name(&name));
+        }
+        match files::export_note(&self.conn, entry_id, &target) {
+            Ok(()) => self.show_notification(format!("Exported {name} to {}", target.display())),
+            Err(e) => self.show_notification(format!("Export failed: {e}")),
+        }
+    }
+
+    /// `:import <path>`: recreate a file or directory as notes under the
+    /// sidebar selection (same rule as adding an entry).
+    fn import(&mut self, path: &str) {
+        if path.is_empty() {
+            self.show_notification("Usage: :import <path>");
             return;
         }
+        let path = expand_tilde(path);
+        let parent_id = self.creation_parent_id();
+        match files::import_path(&self.conn, std::path::Path::new(&path), parent_id) {
+            Ok(summary) => {
+                if let Some(parent_id) = parent_id {
+                    let _ = model::set_entry_expanded(&self.conn, parent_id, true);
+                }
+                let _ = NotesSidebarExt::reload(&mut self.sidebar, &self.conn);
+                self.rebuild_tag_index();
+                let mut message = format!("Imported {} notes from {path}", summary.notes);
+                if summary.skipped > 0 {
+                    message.push_str(&format!(
+                        " ({} non-text files or links skipped)",
+                        summary.skipped
+                    ));
+                }
+                self.show_notification(message);
+            }
+            Err(e) => self.show_notification(format!("Import failed: {e}")),
+        }
+    }
 
-        // Determine parent from selected folder
-        let mut parent_id: Option<i64> = self.sidebar.selected_entry().and_then(|e| {
+    /// Parent for new entries: the selected folder, or the folder of the
+    /// selected note.
+    fn creation_parent_id(&self) -> Option<i64> {
+        self.sidebar.selected_entry().and_then(|e| {
             if e.is_folder {
                 Some(e.entry_id)
             } else {
-                // If a note is selected, use its parent
                 sidebar::find_parent_id(&self.sidebar.roots, e.entry_id)
             }
-        });
+        })
+    }
+
+    /// Create entries from a path string (e.g., "folder/subfolder/note-name").
+    /// Returns the ID of the last entry created or reused.
+    fn create_entries_from_path(&mut self, path: &str) -> Option<i64> {
+        let path = path.trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        let trailing_slash = path.ends_with('/');
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            return None;
+        }
+
+        let mut parent_id = self.creation_parent_id();
 
         for (i, segment) in segments.iter().enumerate() {
             let is_last = i == segments.len() - 1;
@@ -188,6 +832,7 @@ impl NotesTool {
         if let Some(id) = parent_id {
             self.sidebar.select_entry(id);
         }
+        parent_id
     }
 
     /// Find a folder by name under a given parent.
@@ -218,7 +863,7 @@ impl NotesTool {
         match self.sidebar.input_mode {
             SidebarInput::Adding => {
                 if !text.is_empty() {
-                    self.create_entries_from_path(&text);
+                    self.create_entries_with_template(&text);
                 }
             }
             SidebarInput::Renaming => {
@@ -244,16 +889,19 @@ impl NotesTool {
         if let Some(entry) = self.sidebar.selected_entry() {
             let entry_id = entry.entry_id;
             let _ = model::delete_entry(&self.conn, entry_id);
+            self.recent_histories.retain(|(id, _, _)| *id != entry_id);
 
             // If we deleted the active note, clear the editor
             if self.active_note_id == Some(entry_id) {
                 self.active_note_id = None;
                 self.active_note_name = None;
+                self.preview_active = false;
                 self.editor.set_text("");
                 self.editor.mark_clean();
             }
 
             let _ = NotesSidebarExt::reload(&mut self.sidebar, &self.conn);
+            self.rebuild_tag_index();
         }
     }
 
@@ -278,6 +926,7 @@ impl NotesTool {
             }
 
             let _ = NotesSidebarExt::reload(&mut self.sidebar, &self.conn);
+            self.rebuild_tag_index();
         }
     }
 
@@ -307,12 +956,61 @@ impl NotesTool {
         }
     }
 
+    // ── Markdown preview ─────────────────────────────────────────────
+
+    /// Toggle the rendered Markdown view of the open note.
+    fn toggle_preview(&mut self) {
+        self.pending_s_group = false;
+        if self.active_note_id.is_none() || self.mode == InputMode::Insert {
+            return;
+        }
+        self.preview_active = !self.preview_active;
+        self.preview_scroll = 0;
+        if self.preview_active {
+            self.sidebar_focused = false;
+        }
+    }
+
+    /// Handle key events while the Markdown preview is shown.
+    fn handle_preview_key(&mut self, key: KeyEvent) -> Action {
+        let last_line = markdown::render_markdown(&self.editor.text())
+            .len()
+            .saturating_sub(1);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.preview_scroll = (self.preview_scroll + 1).min(last_line);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(1);
+            }
+            KeyCode::Char('d') if ctrl => {
+                self.preview_scroll = (self.preview_scroll + PREVIEW_HALF_PAGE).min(last_line);
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(PREVIEW_HALF_PAGE);
+            }
+            KeyCode::Char('g') => self.preview_scroll = 0,
+            KeyCode::Char('G') => self.preview_scroll = last_line,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.preview_active = false;
+                self.preview_scroll = 0;
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
     // ── Full grep search ─────────────────────────────────────────────
 
     fn open_grep(&mut self) {
         self.pending_s_group = false;
         self.grep_active = true;
+        self.grep_title = "Grep Notes".to_string();
         self.grep_query.clear();
+        self.grep_regex = false;
+        self.grep_whole_word = false;
+        self.grep_error = None;
         self.grep_selected = 0;
         self.grep_candidates = self.collect_grep_candidates();
         self.grep_matches.clear();
@@ -330,7 +1028,6 @@ impl NotesTool {
             return Vec::new();
         };
 
-        use std::collections::HashMap;
         let mut by_id: HashMap<i64, (Option<i64>, String, bool)> = HashMap::new();
         for e in &entries {
             by_id.insert(
@@ -381,27 +1078,60 @@ impl NotesTool {
         out
     }
 
+    /// Position of each `(entry_id, line_number)` in `grep_candidates`.
+    fn grep_line_index(&self) -> HashMap<(i64, usize), usize> {
+        self.grep_candidates
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| ((c.entry_id, c.line_number), idx))
+            .collect()
+    }
+
+    /// The match line of `m` with up to `GREP_CONTEXT_LINES` lines of the
+    /// same note on each side, as `(line number, text)`.
+    fn grep_context(
+        &self,
+        line_index: &HashMap<(i64, usize), usize>,
+        m: &GrepMatch,
+    ) -> Vec<(usize, String)> {
+        let first = m.line_number.saturating_sub(GREP_CONTEXT_LINES).max(1);
+        (first..=m.line_number + GREP_CONTEXT_LINES)
+            .filter_map(|line| {
+                let idx = line_index.get(&(m.entry_id, line))?;
+                Some((line, self.grep_candidates[*idx].line_text.clone()))
+            })
+            .collect()
+    }
+
     fn filter_grep(&mut self) {
-        let q = self.grep_query.to_lowercase();
-        if q.is_empty() {
+        if self.grep_query.is_empty() {
             self.grep_matches.clear();
             self.grep_selected = 0;
+            self.grep_error = None;
             return;
         }
 
+        let re = match build_grep_regex(&self.grep_query, self.grep_regex, self.grep_whole_word) {
+            Ok(re) => re,
+            Err(_) => {
+                self.grep_error = Some("invalid regex".to_string());
+                return;
+            }
+        };
+        self.grep_error = None;
+
         self.grep_matches = self
             .grep_candidates
             .iter()
             .filter_map(|c| {
-                let lower = c.line_text.to_lowercase();
-                let col = lower.find(&q)?;
+                let m = re.find_iter(&c.line_text).find(|m| !m.is_empty())?;
                 Some(GrepMatch {
                     entry_id: c.entry_id,
                     note_name: c.note_name.clone(),
                     note_path: c.note_path.clone(),
                     line_number: c.line_number,
-                    column: col,
-                    line_text: c.line_text.clone(),
+                    column: m.start(),
+                    match_len: m.len(),
                 })
             })
             .collect();
@@ -430,8 +1160,17 @@ impl NotesTool {
     }
 
     fn handle_grep_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         match key.code {
             KeyCode::Esc => self.close_grep(),
+            KeyCode::Char('r') if ctrl => {
+                self.grep_regex = !self.grep_regex;
+                self.filter_grep();
+            }
+            KeyCode::Char('w') if ctrl => {
+                self.grep_whole_word = !self.grep_whole_word;
+                self.filter_grep();
+            }
             KeyCode::Enter => self.confirm_grep_selection(),
             KeyCode::Down | KeyCode::Tab => {
                 if !self.grep_matches.is_empty() {
@@ -460,30 +1199,166 @@ impl NotesTool {
         Action::None
     }
 
+    // ── Tag picker ───────────────────────────────────────────────────
+
+    fn rebuild_tag_index(&mut self) {
+        let candidates = self.collect_grep_candidates();
+        self.tag_index = TagIndex::build(
+            candidates
+                .iter()
+                .map(|c| (c.entry_id, c.line_text.as_str())),
+        );
+    }
+
+    fn open_tag_picker(&mut self) {
+        self.pending_s_group = false;
+        if self.tag_index.is_empty() {
+            self.show_notification("No #tags in notes");
+            return;
+        }
+        self.tag_picker_active = true;
+        self.tag_query.clear();
+        self.tag_selected = 0;
+    }
+
+    fn close_tag_picker(&mut self) {
+        self.tag_picker_active = false;
+        self.tag_query.clear();
+        self.tag_selected = 0;
+    }
+
+    /// Tags (with note counts) matching the picker filter.
+    fn filtered_tags(&self) -> Vec<(String, usize)> {
+        let q = self.tag_query.to_lowercase();
+        self.tag_index
+            .tag_counts()
+            .into_iter()
+            .filter(|(tag, _)| tag.contains(&q))
+            .collect()
+    }
+
+    /// Open the grep overlay listing every line tagged with the selected tag.
+    fn confirm_tag_selection(&mut self) {
+        let Some((tag, _)) = self.filtered_tags().into_iter().nth(self.tag_selected) else {
+            return;
+        };
+        self.close_tag_picker();
+        self.open_grep();
+        self.grep_query = format!("#{tag}");
+        self.grep_matches = self
+            .grep_candidates
+            .iter()
+            .filter_map(|c| {
+                let (column, match_len) = tags::find_tag(&c.line_text, &tag)?;
+                Some(GrepMatch {
+                    entry_id: c.entry_id,
+                    note_name: c.note_name.clone(),
+                    note_path: c.note_path.clone(),
+                    line_number: c.line_number,
+                    column,
+                    match_len,
+                })
+            })
+            .collect();
+    }
+
+    fn handle_tag_picker_key(&mut self, key: KeyEvent) -> Action {
+        let count = self.filtered_tags().len();
+        match key.code {
+            KeyCode::Esc => self.close_tag_picker(),
+            KeyCode::Enter => self.confirm_tag_selection(),
+            KeyCode::Down | KeyCode::Tab if count > 0 => {
+                self.tag_selected = (self.tag_selected + 1) % count;
+            }
+            KeyCode::Up | KeyCode::BackTab if count > 0 => {
+                self.tag_selected = (self.tag_selected + count - 1) % count;
+            }
+            KeyCode::Char(c) => {
+                self.tag_query.push(c);
+                self.tag_selected = 0;
+            }
+            KeyCode::Backspace => {
+                self.tag_query.pop();
+                self.tag_selected = 0;
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    // ── Wiki links ───────────────────────────────────────────────────
+
+    /// Follow the `[[link]]` under the cursor (or the first on the line).
+    /// Several notes with the linked name are offered in the grep overlay.
+    fn follow_link(&mut self) {
+        self.pending_s_group = false;
+        if self.active_note_id.is_none() {
+            self.show_notification("No note open");
+            return;
+        }
+        let line = self.editor.buffer.current_line();
+        let Some(target) = links::link_at(line, self.editor.buffer.cursor_col) else {
+            self.show_notification("No [[link]] on this line");
+            return;
+        };
+
+        let target_lower = target.to_lowercase();
+        let notes: Vec<model::NoteEntry> = model::list_entries(&self.conn)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.entry_type == EntryType::Note && e.name.to_lowercase() == target_lower)
+            .collect();
+
+        match notes.as_slice() {
+            [] => self.show_notification(format!("No note named {target}")),
+            [note] => {
+                self.select_note_by_entry_id(note.id);
+            }
+            _ => {
+                self.open_grep();
+                self.grep_title = format!("Notes named {target}");
+                self.grep_matches = notes
+                    .iter()
+                    .map(|note| {
+                        let first = self.grep_candidates.iter().find(|c| c.entry_id == note.id);
+                        GrepMatch {
+                            entry_id: note.id,
+                            note_name: note.name.clone(),
+                            note_path: first
+                                .map_or_else(|| note.name.clone(), |c| c.note_path.clone()),
+                            line_number: 1,
+                            column: 0,
+                            match_len: 0,
+                        }
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    /// List the lines of other notes that link to the open note.
+    fn open_backlinks(&mut self) {
+        self.pending_s_group = false;
+        let (Some(entry_id), Some(name)) = (self.active_note_id, self.active_note_name.clone())
+        else {
+            self.show_notification("No note open");
+            return;
+        };
+        self.open_grep();
+        self.grep_title = format!("Backlinks: {name}");
+        self.grep_query = links::link_text(&name);
+        self.filter_grep();
+        self.grep_matches.retain(|m| m.entry_id != entry_id);
+    }
+
     // ── Key handling ─────────────────────────────────────────────────
 
     /// Handle key events when the sidebar is focused in Normal mode.
     fn handle_sidebar_normal_key(&mut self, key: KeyEvent) -> Action {
         // Handle pending two-key sequences (gg, gt, gT)
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
-        }
-
         if let Some(pending) = self.key_state.pending_key.take() {
             return match (pending, key.code) {
-                ('g', KeyCode::Char('g')) => {
+                ('g', _) if keymap().matches(KeyAction::GotoPrefix, &key) => {
                     self.sidebar.goto_top();
                     Action::None
                 }
@@ -495,11 +1370,11 @@ impl NotesTool {
 
         match key.code {
             // Navigation
-            KeyCode::Char('j') => {
+            _ if keymap().matches(KeyAction::MoveDown, &key) => {
                 self.sidebar.move_down();
                 Action::None
             }
-            KeyCode::Char('k') => {
+            _ if keymap().matches(KeyAction::MoveUp, &key) => {
                 self.sidebar.move_up();
                 Action::None
             }
@@ -527,20 +1402,20 @@ impl NotesTool {
                 }
                 Action::None
             }
-            KeyCode::Char('G') => {
+            _ if keymap().matches(KeyAction::GotoBottom, &key) => {
                 self.sidebar.goto_bottom();
                 Action::None
             }
-            KeyCode::Char('g') => {
+            _ if keymap().matches(KeyAction::GotoPrefix, &key) => {
                 self.key_state.pending_key = Some('g');
                 Action::None
             }
             KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => {
-                self.sidebar.half_page_down(20);
+                self.sidebar.half_page_down();
                 Action::None
             }
             KeyCode::Char('u') if key.modifiers == KeyModifiers::CONTROL => {
-                self.sidebar.half_page_up(20);
+                self.sidebar.half_page_up();
                 Action::None
             }
 
@@ -575,13 +1450,13 @@ impl NotesTool {
             }
 
             // Hub-level actions
-            KeyCode::Char(' ') => {
-                self.key_state.leader_active = true;
-                Action::LeaderKey
+            _ if keymap().matches(KeyAction::Leader, &key) => Action::LeaderKey,
+            _ if keymap().matches(KeyAction::Command, &key) => {
+                self.command_rows = None;
+                Action::SetMode(InputMode::Command)
             }
-            KeyCode::Char(':') => Action::SetMode(InputMode::Command),
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('q') => Action::Quit,
+            _ if keymap().matches(KeyAction::Help, &key) => Action::Help,
+            _ if keymap().matches(KeyAction::Quit, &key) => Action::Quit,
 
             _ => Action::None,
         }
@@ -637,21 +1512,11 @@ impl NotesTool {
 
     /// Handle key events for the editor panel in Normal mode.
     fn handle_editor_normal_key(&mut self, key: KeyEvent) -> Action {
-        // Handle leader key state
-        if self.key_state.leader_active {
-            self.key_state.leader_active = false;
-            return match key.code {
-                KeyCode::Char(' ') => Action::ToolPicker,
-                KeyCode::Char('f') => Action::Telescope,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as u8 - b'1') as usize;
-                    Action::SwitchTool(idx)
-                }
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char(c) => Action::LeaderSequence(c),
-                KeyCode::Esc => Action::None,
-                _ => Action::None,
-            };
+        // A `/` or `?` search pattern is being typed: every key is text
+        if self.editor.is_searching() {
+            self.editor.handle_key(key);
+            self.track_edit();
+            return Action::None;
         }
 
         // Ctrl-h: move focus to sidebar
@@ -673,23 +1538,34 @@ impl NotesTool {
 
         // Hub-level keys before passing to editor
         match key.code {
-            KeyCode::Char(' ') if key.modifiers == KeyModifiers::NONE => {
-                self.key_state.leader_active = true;
+            _ if keymap().matches(KeyAction::Leader, &key) => {
                 return Action::LeaderKey;
             }
             KeyCode::Char(':') if key.modifiers == KeyModifiers::NONE => {
+                // A Visual selection becomes the range of the command
+                self.command_rows = self.editor.selected_rows();
+                if self.command_rows.is_some() {
+                    self.editor.mode = VimMode::Normal;
+                }
                 return Action::SetMode(InputMode::Command);
             }
-            KeyCode::Char('?') if key.modifiers == KeyModifiers::NONE => {
+            // `?` is backward search in the raw editor
+            KeyCode::Char('?') if key.modifiers == KeyModifiers::NONE && self.preview_active => {
                 return Action::Help;
             }
             _ => {}
         }
 
+        // The preview is read-only; editing happens in the raw editor
+        if self.preview_active {
+            return self.handle_preview_key(key);
+        }
+
         // Pass key to VimEditor
         let action = self.editor.handle_key(key);
+        self.track_edit();
         match action {
-            EditorAction::ModeChanged(VimMode::Insert) => {
+            EditorAction::ModeChanged(VimMode::Insert | VimMode::Replace) => {
                 self.mode = InputMode::Insert;
                 Action::SetMode(InputMode::Insert)
             }
@@ -700,6 +1576,7 @@ impl NotesTool {
     /// Handle key events for the editor panel in Insert mode.
     fn handle_editor_insert_key(&mut self, key: KeyEvent) -> Action {
         let action = self.editor.handle_key(key);
+        self.track_edit();
         match action {
             EditorAction::ModeChanged(VimMode::Normal) => {
                 self.mode = InputMode::Normal;
@@ -758,6 +1635,25 @@ impl Tool for NotesTool {
         self.mode
     }
 
+    fn mode_label(&self) -> &'static str {
+        if !self.sidebar_focused && self.editor.mode == VimMode::Replace {
+            "REPLACE"
+        } else {
+            self.mode.label()
+        }
+    }
+
+    fn pending_keys(&self) -> String {
+        let mut keys = self.key_state.pending_keys();
+        if self.pending_s_group {
+            keys.push_str("<leader>s");
+        }
+        if !self.sidebar_focused {
+            keys.push_str(&self.editor.pending_keys());
+        }
+        keys
+    }
+
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()> {
         model::init_db(conn)
     }
@@ -765,7 +1661,14 @@ impl Tool for NotesTool {
     fn which_key_entries(&self) -> Vec<WhichKeyEntry> {
         vec![
             WhichKeyEntry::action('e', "Toggle sidebar"),
-            WhichKeyEntry::group('s', "Search/Save"),
+            WhichKeyEntry::group('s', "Search/Save").with_entries(vec![
+                WhichKeyEntry::action('s', "Save note"),
+                WhichKeyEntry::action('g', "Grep note contents"),
+                WhichKeyEntry::action('p', "Toggle Markdown preview"),
+                WhichKeyEntry::action('t', "Browse notes by #tag"),
+                WhichKeyEntry::action('l', "Follow [[link]] under cursor"),
+                WhichKeyEntry::action('b', "Backlinks to the open note"),
+            ]),
         ]
     }
 
@@ -773,6 +1676,12 @@ impl Tool for NotesTool {
         self.collect_telescope_items()
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let entry_id = id.strip_prefix("notes:")?.parse::<i64>().ok()?;
+        let content = model::get_note_content(&self.conn, entry_id).ok()?;
+        Some(telescope::preview_lines(&content.body))
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         let Some(raw_id) = id.strip_prefix("notes:") else {
             return false;
@@ -789,6 +1698,7 @@ impl Tool for NotesTool {
         vec![
             // Sidebar
             HelpEntry::with_section("Sidebar", "a", "Add entry (path with / for nesting)"),
+            HelpEntry::with_section("Sidebar", "a path @tmpl", "Add note filled from a template"),
             HelpEntry::with_section("Sidebar", "r", "Rename selected entry"),
             HelpEntry::with_section("Sidebar", "d", "Delete selected entry"),
             HelpEntry::with_section("Sidebar", "y", "Copy selected entry"),
@@ -801,18 +1711,79 @@ impl Tool for NotesTool {
             HelpEntry::with_section("Sidebar", "Ctrl-l", "Move focus to editor"),
             // Editor
             HelpEntry::with_section("Editor", "i / a / A / I", "Enter insert mode"),
+            HelpEntry::with_section("Editor", "R", "Replace mode (overtype)"),
             HelpEntry::with_section("Editor", "o / O", "Insert line below / above"),
-            HelpEntry::with_section("Editor", "v / V", "Visual / visual-line mode"),
+            HelpEntry::with_section("Editor", "v / V / Ctrl-v", "Visual / line / block mode"),
+            HelpEntry::with_section("Editor", "I / A (block)", "Insert / append on every line"),
             HelpEntry::with_section("Editor", "d/c/y + motion", "Delete/change/yank"),
             HelpEntry::with_section("Editor", "dd / yy / cc", "Line-wise operators"),
             HelpEntry::with_section("Editor", "u / Ctrl-r", "Undo / redo"),
+            HelpEntry::with_section("Editor", ".", "Repeat last change"),
+            HelpEntry::with_section("Editor", "q<reg> / q", "Record macro / stop recording"),
+            HelpEntry::with_section("Editor", "[count]@<reg> / @@", "Play macro / replay last"),
             HelpEntry::with_section("Editor", "p / P", "Paste after / before"),
+            HelpEntry::with_section("Editor", "\"a-\"z / \"0-\"9", "Named register / yank ring"),
+            HelpEntry::with_section("Editor", "/ / ?", "Search forward / backward"),
+            HelpEntry::with_section("Editor", "n / N", "Next / previous match"),
+            HelpEntry::with_section("Editor", "<Space>?", "Help (`?` searches in raw editor)"),
+            HelpEntry::with_section("Editor", "gj / gk", "Down / up one display line"),
+            HelpEntry::with_section("Editor", "m<a-z>", "Set a mark"),
+            HelpEntry::with_section("Editor", "`<a-z> / '<a-z>", "Jump to mark / its line"),
+            HelpEntry::with_section("Editor", "`` / ''", "Back to before the last jump"),
+            HelpEntry::with_section("Editor", "gq + motion / gqq", "Reflow to the text width"),
+            HelpEntry::with_section("Editor", ">> / <<", "Indent / dedent line"),
+            HelpEntry::with_section("Editor", "> / < + motion", "Indent / dedent lines"),
             HelpEntry::with_section("Editor", "Ctrl-h", "Move focus to sidebar"),
             HelpEntry::with_section("Editor", ":w", "Save note to database"),
+            HelpEntry::with_section(
+                "Editor",
+                ":export <path>",
+                "Write the note (or selected folder) to disk",
+            ),
+            HelpEntry::with_section(
+                "Editor",
+                ":import <path>",
+                "Import a Markdown file or directory",
+            ),
+            HelpEntry::with_section("Editor", ":tmpl <name>", "Insert a template at the cursor"),
+            HelpEntry::with_section("Editor", ":set nu / nonu", "Show / hide line numbers"),
+            HelpEntry::with_section("Editor", ":set wrap / nowrap", "Soft-wrap long lines"),
+            HelpEntry::with_section("Editor", ":set tw=<n>", "Text width for gq"),
+            HelpEntry::with_section("Editor", ":set sw=<n> / et", "Indent width / use spaces"),
+            HelpEntry::with_section("Editor", ":!cmd / :%!cmd", "Filter line / note through cmd"),
+            HelpEntry::with_section("Editor", ":sort[!] [u] [n]", "Sort selection or note"),
+            HelpEntry::with_section("Editor", ":set trimonsave", "Tidy whitespace when saving"),
+            HelpEntry::with_section(
+                "Editor",
+                ":set autopairs / noautopairs",
+                "Close brackets and quotes",
+            ),
+            HelpEntry::with_section(
+                "Editor",
+                ":set rnu / nornu",
+                "Relative line numbers on / off",
+            ),
+            HelpEntry::with_section(
+                "Editor",
+                ":set autosave <secs>|off",
+                "Auto-save interval for a dirty note",
+            ),
+            HelpEntry::with_section("Editor", ":s/old/new/[g]", "Substitute on the current line"),
+            HelpEntry::with_section("Editor", ":%s/old/new/[g]", "Substitute in the whole note"),
+            // Preview
+            HelpEntry::with_section("Preview", "j / k", "Scroll down / up"),
+            HelpEntry::with_section("Preview", "Ctrl-d / Ctrl-u", "Half-page down / up"),
+            HelpEntry::with_section("Preview", "g / G", "Go to top / bottom"),
+            HelpEntry::with_section("Preview", "Esc / q", "Back to the raw editor"),
             // General
             HelpEntry::with_section("General", "<Space>e", "Toggle sidebar"),
             HelpEntry::with_section("General", "<Space>s s", "Save note"),
             HelpEntry::with_section("General", "<Space>s g", "Grep note contents"),
+            HelpEntry::with_section("Grep", "Ctrl-r / Ctrl-w", "Toggle regex / whole word"),
+            HelpEntry::with_section("General", "<Space>s p", "Toggle Markdown preview"),
+            HelpEntry::with_section("General", "<Space>s t", "Browse notes by #tag"),
+            HelpEntry::with_section("General", "<Space>s l", "Follow [[link]] under cursor"),
+            HelpEntry::with_section("General", "<Space>s b", "Backlinks to the open note"),
         ]
     }
 
@@ -828,6 +1799,22 @@ impl Tool for NotesTool {
                     self.open_grep();
                     Action::None
                 }
+                KeyCode::Char('p') => {
+                    self.toggle_preview();
+                    Action::None
+                }
+                KeyCode::Char('t') => {
+                    self.open_tag_picker();
+                    Action::None
+                }
+                KeyCode::Char('l') => {
+                    self.follow_link();
+                    Action::None
+                }
+                KeyCode::Char('b') => {
+                    self.open_backlinks();
+                    Action::None
+                }
                 _ => Action::None,
             };
         }
@@ -836,6 +1823,10 @@ impl Tool for NotesTool {
             return self.handle_grep_key(key);
         }
 
+        if self.tag_picker_active {
+            return self.handle_tag_picker_key(key);
+        }
+
         match self.mode {
             InputMode::Normal => {
                 if self.sidebar.visible && self.sidebar_focused {
@@ -900,6 +1891,37 @@ impl Tool for NotesTool {
         }
     }
 
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) -> Action {
+        // Overlays, sidebar prompts and the read-only preview ignore the mouse
+        if self.grep_active
+            || self.tag_picker_active
+            || self.preview_active
+            || self.sidebar.input_mode != SidebarInput::None
+        {
+            return Action::None;
+        }
+
+        let editor_open = self.active_note_id.is_some();
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if editor_open && self.editor.mouse_down(mouse.column, mouse.row) {
+                    self.sidebar_focused = false;
+                } else if self.sidebar.visible
+                    && mouse.column < area.x + ui::SIDEBAR_WIDTH.min(area.width.saturating_sub(10))
+                    && self.mode == InputMode::Normal
+                {
+                    self.sidebar_focused = true;
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) if editor_open && !self.sidebar_focused => {
+                self.editor.mouse_drag(mouse.column, mouse.row);
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.editor.mouse_up(),
+            _ => {}
+        }
+        Action::None
+    }
+
     fn render(&self, frame: &mut Frame, area: Rect) {
         ui::render_notes_tool(
             frame,
@@ -908,22 +1930,31 @@ impl Tool for NotesTool {
             &self.editor,
             self.sidebar_focused,
             self.active_note_name.as_deref(),
+            self.preview_active.then_some(self.preview_scroll),
         );
 
         if self.grep_active {
-            let rows: Vec<String> = self
+            let line_index = self.grep_line_index();
+            let rows: Vec<ui::GrepResult> = self
                 .grep_matches
                 .iter()
-                .map(|m| format!("{}:{}  {}", m.note_path, m.line_number, m.line_text.trim()))
+                .map(|m| ui::GrepResult {
+                    location: format!("{}:{}", m.note_path, m.line_number),
+                    context: self.grep_context(&line_index, m),
+                    line_number: m.line_number,
+                    column: m.column,
+                    match_len: m.match_len,
+                })
                 .collect();
 
-            let (preview_title, preview_text, preview_target_line) =
+            let (preview_title, preview_text, preview_match) =
                 if let Some(m) = self.grep_matches.get(self.grep_selected) {
                     let title = m.note_path.clone();
                     let body = model::get_note_content(&self.conn, m.entry_id)
                         .map(|c| c.body)
                         .unwrap_or_else(|_| "(unable to load note content)".to_string());
-                    (title, body, Some(m.line_number.saturating_sub(1)))
+                    let target = (m.line_number.saturating_sub(1), m.column, m.match_len);
+                    (title, body, Some(target))
                 } else {
                     (
                         "No selection".to_string(),
@@ -932,17 +1963,46 @@ impl Tool for NotesTool {
                     )
                 };
 
+            let mut title = self.grep_title.clone();
+            if self.grep_regex {
+                title.push_str(" [regex]");
+            }
+            if self.grep_whole_word {
+                title.push_str(" [word]");
+            }
+
             ui::render_grep_overlay(
                 frame,
                 area,
+                &title,
                 &self.grep_query,
+                self.grep_error.as_deref(),
                 &rows,
                 self.grep_selected,
                 &preview_title,
                 &preview_text,
-                preview_target_line,
+                preview_match,
             );
         }
+
+        if self.tag_picker_active {
+            let rows: Vec<String> = self
+                .filtered_tags()
+                .iter()
+                .map(|(tag, count)| format!("#{tag}  ({count})"))
+                .collect();
+            ui::render_tag_picker(frame, area, &self.tag_query, &rows, self.tag_selected);
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.autosave_due(Instant::now()) {
+            self.save_current_note();
+        }
+    }
+
+    fn take_toasts(&mut self) -> Vec<Toast> {
+        std::mem::take(&mut self.toasts)
     }
 
     fn handle_leader_action(&mut self, key: char) -> Option<Action> {
@@ -968,11 +2028,14 @@ impl Tool for NotesTool {
     }
 
     fn handle_paste(&mut self, text: &str) -> Action {
-        if self.active_note_id.is_some() && !self.sidebar_focused {
+        if self.preview_active && !self.sidebar_focused {
+            Action::None
+        } else if self.active_note_id.is_some() && !self.sidebar_focused {
             self.editor.paste_text(text);
+            self.track_edit();
             // Sync mode: if editor ended up in Insert, update our mode
             match self.editor.mode {
-                VimMode::Insert => {
+                VimMode::Insert | VimMode::Replace => {
                     self.mode = InputMode::Insert;
                     Action::SetMode(InputMode::Insert)
                 }
@@ -996,10 +2059,76 @@ impl Tool for NotesTool {
 
     fn on_focus(&mut self) {
         let _ = NotesSidebarExt::reload(&mut self.sidebar, &self.conn);
+        self.rebuild_tag_index();
+    }
+
+    fn on_blur(&mut self) {
+        self.auto_save_current();
+        self.save_position();
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.active_note_id.is_some() && self.editor.is_dirty()
+    }
+
+    fn discard_changes(&mut self) {
+        if let Some(entry_id) = self.active_note_id
+            && let Ok(content) = model::get_note_content(&self.conn, entry_id)
+        {
+            self.editor.set_text(&content.body);
+            self.editor.mark_clean();
+            self.restore_position(entry_id);
+            self.dirty_since = None;
+        }
     }
 
     fn handle_command(&mut self, cmd: &str) -> bool {
-        match cmd.trim() {
+        let cmd = cmd.trim();
+        let selected_rows = self.command_rows.take();
+        if let Some(rest) = cmd.strip_prefix("sort")
+            && (rest.is_empty() || rest.starts_with(['!', ' ']))
+        {
+            match parse_sort(rest) {
+                Some(options) => self.run_sort(options, selected_rows),
+                None => self.show_notification(format!("Invalid sort flags: {}", rest.trim())),
+            }
+            return true;
+        }
+        if cmd.starts_with("s/") || cmd.starts_with("%s/") {
+            match parse_substitute(cmd) {
+                Some(sub) => self.run_substitute(&sub),
+                None => self.show_notification(format!("Invalid substitution: {cmd}")),
+            }
+            return true;
+        }
+        if let Some(command) = cmd.strip_prefix('!') {
+            // The cursor line, or the Visual selection `:` was pressed in
+            let cursor = self.editor.buffer.cursor_row;
+            self.run_filter(command.trim(), selected_rows.unwrap_or((cursor, cursor)));
+            return true;
+        }
+        if let Some(command) = cmd.strip_prefix("%!") {
+            let last = self.editor.buffer.line_count() - 1;
+            self.run_filter(command.trim(), (0, last));
+            return true;
+        }
+        if cmd == "export" || cmd.starts_with("export ") {
+            self.export(cmd["export".len()..].trim());
+            return true;
+        }
+        if cmd == "import" || cmd.starts_with("import ") {
+            self.import(cmd["import".len()..].trim());
+            return true;
+        }
+        if cmd == "tmpl" || cmd.starts_with("tmpl ") {
+            self.insert_template(cmd["tmpl".len()..].trim());
+            return true;
+        }
+        if cmd == "set" || cmd.starts_with("set ") {
+            self.apply_setting(cmd["set".len()..].trim());
+            return true;
+        }
+        match cmd {
             "w" | "write" => self.save_current_note(),
             _ => false,
         }
@@ -1016,6 +2145,10 @@ mod tests {
         NotesTool::new(conn).unwrap()
     }
 
+    fn last_toast(tool: &NotesTool) -> Option<&str> {
+        tool.toasts.last().map(|t| t.message.as_str())
+    }
+
     #[test]
     fn test_create_simple_note() {
         let mut tool = setup_tool();
@@ -1081,6 +2214,107 @@ mod tests {
         assert_eq!(content.body, "Hello, world!");
     }
 
+    #[test]
+    fn test_discard_changes() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("draft");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "draft");
+        assert!(!tool.is_dirty());
+
+        for code in [KeyCode::Char('i'), KeyCode::Char('x'), KeyCode::Esc] {
+            tool.editor
+                .handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        assert!(tool.is_dirty());
+
+        tool.discard_changes();
+        assert!(!tool.is_dirty());
+        assert_eq!(tool.editor.text(), "");
+        tool.on_blur();
+        let content = model::get_note_content(&tool.conn, entry_id).unwrap();
+        assert_eq!(content.body, "");
+    }
+
+    #[test]
+    fn test_replace_mode_label() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("draft");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "draft");
+
+        tool.handle_editor_normal_key(KeyEvent::new(KeyCode::Char('R'), KeyModifiers::NONE));
+        assert_eq!(tool.mode(), InputMode::Insert);
+        assert_eq!(tool.mode_label(), "REPLACE");
+        tool.handle_editor_insert_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(tool.mode_label(), "NORMAL");
+    }
+
+    #[test]
+    fn test_autosave_waits_for_interval_and_pause() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("draft");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "draft");
+
+        tool.handle_editor_normal_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE));
+        tool.handle_editor_insert_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        let typed_at = tool.last_edit_at.unwrap();
+        assert_eq!(tool.dirty_since, Some(typed_at));
+
+        // Not yet due, then due once the interval elapsed with a pause
+        assert!(!tool.autosave_due(typed_at + Duration::from_secs(1)));
+        let later = typed_at + Duration::from_secs(model::DEFAULT_AUTOSAVE_SECS);
+        assert!(tool.autosave_due(later));
+
+        // Still typing: hold the save until the pause
+        tool.last_edit_at = Some(later);
+        assert!(!tool.autosave_due(later + Duration::from_millis(200)));
+        assert!(tool.autosave_due(later + AUTOSAVE_PAUSE));
+
+        assert!(tool.handle_command("set autosave off"));
+        assert!(!tool.autosave_due(later + AUTOSAVE_PAUSE));
+        assert_eq!(model::load_settings(&tool.conn).unwrap().autosave_secs, 0);
+        assert!(tool.handle_command("set autosave 0"));
+        assert_eq!(
+            last_toast(&tool),
+            Some("Invalid autosave: use off or 1-3600 seconds")
+        );
+
+        // Gutter flags reach the editor and persist
+        assert!(tool.handle_command("set nornu"));
+        assert!(!tool.editor.relative_numbers);
+        assert!(tool.handle_command("set nu!"));
+        assert!(!tool.editor.line_numbers);
+        assert!(!model::load_settings(&tool.conn).unwrap().line_numbers);
+        assert!(tool.handle_command("set wrap"));
+        assert!(tool.editor.wrap);
+        assert!(model::load_settings(&tool.conn).unwrap().wrap);
+        assert!(tool.handle_command("set autopairs!"));
+        assert!(tool.editor.auto_pairs);
+        assert!(model::load_settings(&tool.conn).unwrap().auto_pairs);
+        assert!(tool.handle_command("set tw=72"));
+        assert_eq!(tool.editor.text_width, 72);
+        assert_eq!(model::load_settings(&tool.conn).unwrap().text_width, 72);
+        assert!(tool.handle_command("set textwidth 5"));
+        assert_eq!(last_toast(&tool), Some("Invalid textwidth: use 10-1000"));
+        assert!(tool.handle_command("set sw=2"));
+        assert!(tool.handle_command("set noet"));
+        assert_eq!(tool.editor.shift_width, 2);
+        assert!(!tool.editor.expand_tab);
+        let stored = model::load_settings(&tool.conn).unwrap();
+        assert_eq!((stored.shift_width, stored.expand_tab), (2, false));
+        assert!(tool.handle_command("set shiftwidth 0"));
+        assert_eq!(last_toast(&tool), Some("Invalid shiftwidth: use 1-16"));
+
+        // Leaving the tool saves immediately
+        tool.on_blur();
+        assert!(!tool.editor.is_dirty());
+        assert_eq!(tool.dirty_since, None);
+        let content = model::get_note_content(&tool.conn, entry_id).unwrap();
+        assert_eq!(content.body, "x");
+    }
+
     #[test]
     fn test_delete_active_note_clears_editor() {
         let mut tool = setup_tool();
@@ -1112,6 +2346,20 @@ mod tests {
         assert!(labels.contains(&"note-b"));
     }
 
+    #[test]
+    fn test_telescope_preview() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("note");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        model::save_note_content(&tool.conn, entry_id, "# Title\nbody").unwrap();
+
+        assert_eq!(
+            tool.telescope_preview(&format!("notes:{entry_id}")),
+            Some("# Title\nbody".to_string())
+        );
+        assert_eq!(tool.telescope_preview("http:1"), None);
+    }
+
     #[test]
     fn test_leader_s_group_for_save_and_grep() {
         let mut tool = setup_tool();
@@ -1127,6 +2375,423 @@ mod tests {
         assert!(tool.grep_active);
     }
 
+    #[test]
+    fn test_trim_on_save() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("draft");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "draft");
+        tool.editor.set_text("a  \nb");
+
+        // Off by default
+        assert!(tool.handle_command("w"));
+        assert_eq!(
+            model::get_note_content(&tool.conn, entry_id).unwrap().body,
+            "a  \nb"
+        );
+
+        assert!(tool.handle_command("set trimonsave"));
+        assert!(tool.handle_command("w"));
+        assert_eq!(
+            model::get_note_content(&tool.conn, entry_id).unwrap().body,
+            "a\nb\n"
+        );
+        assert_eq!(tool.editor.text(), "a\nb\n");
+        assert!(!tool.editor.is_dirty());
+
+        // The tidy-up is an undo step of its own
+        tool.editor
+            .handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(tool.editor.text(), "a  \nb");
+
+        // Auto-saves in Insert mode leave the line being typed alone
+        tool.editor
+            .handle_key(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::NONE));
+        tool.save_current_note();
+        assert_eq!(
+            model::get_note_content(&tool.conn, entry_id).unwrap().body,
+            "a  \nb"
+        );
+    }
+
+    #[test]
+    fn test_sort_command() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("list");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "list");
+        tool.editor.set_text("b\na\nc\na");
+
+        assert!(tool.handle_command("sort"));
+        assert_eq!(tool.editor.text(), "a\na\nb\nc");
+        assert_eq!(last_toast(&tool), Some("4 lines sorted"));
+        assert!(tool.handle_command("sort! u"));
+        assert_eq!(tool.editor.text(), "c\nb\na");
+        assert_eq!(
+            last_toast(&tool),
+            Some("4 lines sorted, 1 duplicate removed")
+        );
+        assert!(tool.handle_command("sort x"));
+        assert_eq!(last_toast(&tool), Some("Invalid sort flags: x"));
+
+        // `:` in Visual mode limits the sort to the selected lines
+        tool.editor.set_text("z\n10\n9\nx");
+        tool.handle_key(key('j'));
+        tool.handle_key(key('V'));
+        tool.handle_key(key('j'));
+        tool.handle_key(key(':'));
+        assert_eq!(tool.editor.mode, VimMode::Normal);
+        assert!(tool.handle_command("sort n"));
+        assert_eq!(tool.editor.text(), "z\n9\n10\nx");
+        assert!(tool.handle_command("sort"));
+        assert_eq!(tool.editor.text(), "10\n9\nx\nz");
+
+        // One undo step per sort
+        tool.editor.handle_key(key('u'));
+        assert_eq!(tool.editor.text(), "z\n9\n10\nx");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_filter() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("list");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "list");
+        tool.editor.set_text("pear\napple\nfig");
+
+        // Off by default: nothing runs
+        assert!(tool.handle_command("%!sort"));
+        assert_eq!(
+            last_toast(&tool),
+            Some("Filters are off: enable with :set shellfilter")
+        );
+        assert_eq!(tool.editor.text(), "pear\napple\nfig");
+
+        assert!(tool.handle_command("set shellfilter"));
+        assert!(tool.handle_command("%!sort"));
+        assert_eq!(tool.editor.text(), "apple\nfig\npear");
+        assert_eq!(last_toast(&tool), Some("3 lines filtered"));
+        assert!(tool.handle_command("! tr a-z A-Z"));
+        assert_eq!(tool.editor.text(), "APPLE\nfig\npear");
+        assert!(tool.editor.is_dirty());
+
+        // `:` in Visual mode filters the selected lines
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        for c in "jVj:".chars() {
+            tool.handle_key(key(c));
+        }
+        assert!(tool.handle_command("!tr a-z A-Z"));
+        assert_eq!(tool.editor.text(), "APPLE\nFIG\nPEAR");
+        tool.editor.handle_key(key('u'));
+
+        // A failing command leaves the note alone
+        assert!(tool.handle_command("%!exit 1"));
+        assert!(last_toast(&tool).unwrap().starts_with("Filter failed"));
+        assert_eq!(tool.editor.text(), "APPLE\nfig\npear");
+
+        // Each filter is one undo step
+        tool.editor
+            .handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(tool.editor.text(), "apple\nfig\npear");
+        tool.editor
+            .handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(tool.editor.text(), "pear\napple\nfig");
+    }
+
+    #[test]
+    fn test_cursor_position_restored() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("a");
+        tool.create_entries_from_path("b");
+        let id = |tool: &NotesTool, name: &str| {
+            tool.sidebar
+                .flat_view
+                .iter()
+                .find(|n| n.name == name)
+                .unwrap()
+                .entry_id
+        };
+        let (a, b) = (id(&tool, "a"), id(&tool, "b"));
+        model::save_note_content(&tool.conn, a, "one\ntwo\nthree words").unwrap();
+        model::save_note_content(&tool.conn, b, "needle here\nneedle").unwrap();
+
+        tool.open_note(a, "a");
+        tool.editor.buffer.cursor_row = 2;
+        tool.editor.buffer.cursor_col = 6;
+        tool.open_note(b, "b");
+        assert_eq!(tool.editor.buffer.cursor_row, 0);
+        tool.open_note(a, "a");
+        assert_eq!(
+            (tool.editor.buffer.cursor_row, tool.editor.buffer.cursor_col),
+            (2, 6)
+        );
+
+        // Leaving the tool remembers it too, clamped if the note shrank
+        tool.editor.buffer.cursor_row = 1;
+        tool.on_blur();
+        model::save_note_content(&tool.conn, a, "x").unwrap();
+        tool.open_note(b, "b");
+        tool.open_note(a, "a");
+        assert_eq!(
+            (tool.editor.buffer.cursor_row, tool.editor.buffer.cursor_col),
+            (0, 0)
+        );
+
+        // A grep jump wins over the remembered position
+        tool.open_note(b, "b");
+        tool.editor.buffer.cursor_row = 1;
+        tool.open_note(a, "a");
+        tool.open_grep();
+        tool.grep_query = "needle".to_string();
+        tool.filter_grep();
+        tool.confirm_grep_selection();
+        assert_eq!(tool.active_note_id, Some(b));
+        assert_eq!(tool.editor.buffer.cursor_row, 0);
+    }
+
+    #[test]
+    fn test_preview_toggle_is_read_only() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("readme");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "readme");
+        tool.editor.set_text("# Title\n\nbody");
+
+        tool.handle_leader_action('s');
+        tool.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert!(tool.preview_active);
+
+        // Editing keys are ignored while previewing
+        tool.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(tool.editor.text(), "# Title\n\nbody");
+        tool.handle_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE));
+        assert_eq!(tool.preview_scroll, 2);
+
+        tool.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!tool.preview_active);
+    }
+
+    #[test]
+    fn test_parse_substitute() {
+        assert_eq!(
+            parse_substitute("s/old/new/"),
+            Some(Substitution {
+                pattern: "old".into(),
+                replacement: "new".into(),
+                whole_buffer: false,
+                global: false,
+            })
+        );
+        let sub = parse_substitute("%s/a\\/b/c/g").unwrap();
+        assert_eq!(
+            (sub.pattern.as_str(), sub.replacement.as_str()),
+            ("a/b", "c")
+        );
+        assert!(sub.whole_buffer && sub.global);
+        assert_eq!(parse_substitute("s/gone").unwrap().replacement, "");
+        assert!(parse_substitute("s//x/").is_none());
+        assert!(parse_substitute("s/a/b/i").is_none());
+        assert!(parse_substitute("set").is_none());
+    }
+
+    #[test]
+    fn test_substitute_command() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("todo");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "todo");
+        tool.editor.set_text("cat cat\ncat");
+
+        assert!(tool.handle_command("%s/cat/dog/g"));
+        assert_eq!(tool.editor.text(), "dog dog\ndog");
+        assert_eq!(last_toast(&tool), Some("3 substitutions"));
+
+        assert!(tool.handle_command("s/bird/x/"));
+        assert_eq!(last_toast(&tool), Some("Pattern not found: bird"));
+    }
+
+    #[test]
+    fn test_tag_picker_lists_tags_and_opens_matches() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("a");
+        tool.create_entries_from_path("b");
+        let a_id = tool.sidebar.flat_view[0].entry_id;
+        let b_id = tool.sidebar.flat_view[1].entry_id;
+        model::save_note_content(&tool.conn, a_id, "intro\nplan for #Rust").unwrap();
+
+        // The index is refreshed when a note is saved
+        tool.open_note(b_id, "b");
+        tool.editor.set_text("#rust and #tui\n#rustacean");
+        assert!(tool.save_current_note());
+        assert_eq!(
+            tool.filtered_tags(),
+            vec![
+                ("rust".to_string(), 2),
+                ("rustacean".to_string(), 1),
+                ("tui".to_string(), 1),
+            ]
+        );
+
+        tool.handle_leader_action('s');
+        tool.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        assert!(tool.tag_picker_active);
+
+        tool.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        tool.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!tool.tag_picker_active);
+        assert!(tool.grep_active);
+        assert_eq!(tool.grep_query, "#rust");
+        // `#rustacean` is a different tag
+        let hits: Vec<(i64, usize)> = tool
+            .grep_matches
+            .iter()
+            .map(|m| (m.entry_id, m.line_number))
+            .collect();
+        assert_eq!(hits, vec![(a_id, 2), (b_id, 1)]);
+        assert_eq!(tool.grep_matches[0].column, 9);
+    }
+
+    #[test]
+    fn test_follow_link_and_backlinks() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("index");
+        tool.create_entries_from_path("work/Plan");
+        tool.create_entries_from_path("Todo");
+        let id_of = |tool: &NotesTool, name: &str| {
+            model::list_entries(&tool.conn)
+                .unwrap()
+                .into_iter()
+                .find(|e| e.name == name)
+                .unwrap()
+                .id
+        };
+        let index_id = id_of(&tool, "index");
+        let plan_id = id_of(&tool, "Plan");
+        model::save_note_content(&tool.conn, plan_id, "back to [[index]]").unwrap();
+
+        model::save_note_content(&tool.conn, index_id, "see [[plan]] and [[Missing]]").unwrap();
+        tool.open_note(index_id, "index");
+
+        // Case-insensitive match by name, regardless of folder
+        tool.follow_link();
+        assert_eq!(tool.active_note_id, Some(plan_id));
+
+        tool.open_note(index_id, "index");
+        tool.editor.buffer.cursor_col = 20;
+        tool.follow_link();
+        assert_eq!(tool.active_note_id, Some(index_id));
+        assert_eq!(last_toast(&tool), Some("No note named Missing"));
+
+        // The same name in two folders opens a picker
+        tool.create_entries_from_path("archive/Plan");
+        tool.editor.buffer.cursor_col = 0;
+        tool.follow_link();
+        assert!(tool.grep_active);
+        assert_eq!(tool.grep_matches.len(), 2);
+        assert_eq!(tool.grep_title, "Notes named plan");
+        tool.close_grep();
+
+        tool.open_backlinks();
+        assert_eq!(tool.grep_title, "Backlinks: index");
+        let hits: Vec<i64> = tool.grep_matches.iter().map(|m| m.entry_id).collect();
+        assert_eq!(hits, vec![plan_id]);
+    }
+
+    #[test]
+    fn test_templates_on_create_and_insert() {
+        let mut tool = setup_tool();
+
+        // Sidebar prompt with a built-in template
+        tool.create_entries_with_template("journal/today @daily");
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(tool.active_note_name.as_deref(), Some("today"));
+        assert!(tool.editor.text().starts_with(&format!("# {today}\n")));
+        let stored = model::get_note_content(&tool.conn, tool.active_note_id.unwrap()).unwrap();
+        assert_eq!(stored.body.trim_end(), tool.editor.text());
+
+        // `:tmpl` with a user template, inserted at the cursor
+        let folder = model::add_entry(&tool.conn, None, "templates", EntryType::Folder).unwrap();
+        let standup =
+            model::add_entry(&tool.conn, Some(folder), "standup", EntryType::Note).unwrap();
+        model::save_note_content(&tool.conn, standup, "## {{title}}\n- done:").unwrap();
+        tool.editor.set_text("x");
+        assert!(tool.handle_command("tmpl Standup"));
+        assert_eq!(tool.editor.text(), "## today\n- done:x");
+
+        assert!(tool.handle_command("tmpl nope"));
+        assert_eq!(
+            last_toast(&tool),
+            Some("Unknown template: nope (available: standup, daily, meeting)")
+        );
+
+        // An unknown template on create leaves the tree untouched
+        let entries = tool.sidebar.flat_view.len();
+        tool.create_entries_with_template("journal/later @nope");
+        assert_eq!(tool.sidebar.flat_view.len(), entries);
+        let message = last_toast(&tool).unwrap();
+        assert!(message.starts_with("Unknown template: nope"));
+    }
+
+    #[test]
+    fn test_undo_history_survives_switching_notes() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("a");
+        tool.create_entries_from_path("b");
+        let id_of = |tool: &NotesTool, name: &str| {
+            tool.sidebar
+                .flat_view
+                .iter()
+                .find(|e| e.name == name)
+                .unwrap()
+                .entry_id
+        };
+        let (a, b) = (id_of(&tool, "a"), id_of(&tool, "b"));
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        let u = KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE);
+
+        tool.open_note(a, "a");
+        tool.editor.set_text("abc");
+        tool.editor.handle_key(x);
+        tool.open_note(b, "b");
+        tool.open_note(a, "a");
+        assert_eq!(tool.editor.text(), "bc");
+        tool.editor.handle_key(u);
+        assert_eq!(tool.editor.text(), "abc");
+
+        // A note changed while closed starts with a fresh history
+        tool.editor.handle_key(x);
+        tool.open_note(b, "b");
+        model::save_note_content(&tool.conn, a, "other").unwrap();
+        tool.open_note(a, "a");
+        tool.editor.handle_key(u);
+        assert_eq!(tool.editor.text(), "other");
+    }
+
+    #[test]
+    fn test_export_open_note() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("draft");
+        let entry_id = tool.sidebar.flat_view[0].entry_id;
+        tool.open_note(entry_id, "draft");
+        tool.editor.set_text("unsaved body");
+        tool.editor
+            .handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+
+        let dir = std::env::temp_dir().join(format!("rstools-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(tool.handle_command(&format!("export {}/", dir.display())));
+
+        // The pending edit is saved first, then written as `<name>.md`
+        let written = std::fs::read_to_string(dir.join("draft.md")).unwrap();
+        assert_eq!(written, "nsaved body");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(tool.handle_command("export"));
+        assert_eq!(last_toast(&tool), Some("Usage: :export <path>"));
+    }
+
     #[test]
     fn test_grep_finds_note_content() {
         let mut tool = setup_tool();
@@ -1159,4 +2824,73 @@ mod tests {
         assert_eq!(tool.grep_matches[0].entry_id, note1_id);
         assert_eq!(tool.grep_matches[0].line_number, 1);
     }
+
+    #[test]
+    fn test_grep_regex_and_whole_word() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("n");
+        let id = tool.sidebar.flat_view[0].entry_id;
+        model::save_note_content(&tool.conn, id, "Café: Rusty\nrust v12\ntrust").unwrap();
+
+        let grep = |tool: &mut NotesTool, query: &str| {
+            tool.grep_query = query.to_string();
+            tool.filter_grep();
+            tool.grep_matches
+                .iter()
+                .map(|m| (m.line_number, m.column, m.match_len))
+                .collect::<Vec<_>>()
+        };
+        tool.open_grep();
+
+        // Literal and case-insensitive; columns are byte offsets of the match
+        assert_eq!(
+            grep(&mut tool, "rust"),
+            vec![(1, 7, 4), (2, 0, 4), (3, 1, 4)]
+        );
+        assert_eq!(grep(&mut tool, "v1."), vec![]);
+
+        // Slashes are literal text, not a regex
+        assert_eq!(grep(&mut tool, "/v\\d+/"), vec![]);
+
+        tool.handle_grep_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(grep(&mut tool, "rust"), vec![(2, 0, 4)]);
+
+        tool.handle_grep_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(grep(&mut tool, "v\\d+"), vec![(2, 5, 3)]);
+
+        // An invalid regex keeps the previous results and reports the error
+        assert_eq!(grep(&mut tool, "rust("), vec![(2, 5, 3)]);
+        assert_eq!(tool.grep_error.as_deref(), Some("invalid regex"));
+        assert_eq!(grep(&mut tool, "t.ust"), vec![(3, 0, 5)]);
+        assert_eq!(tool.grep_error, None);
+    }
+
+    #[test]
+    fn test_grep_context_lines() {
+        let mut tool = setup_tool();
+        tool.create_entries_from_path("a");
+        tool.create_entries_from_path("b");
+        let a_id = tool.sidebar.flat_view[0].entry_id;
+        let b_id = tool.sidebar.flat_view[1].entry_id;
+        model::save_note_content(&tool.conn, a_id, "1\n2\n3\nneedle\n5\n6\n7").unwrap();
+        model::save_note_content(&tool.conn, b_id, "needle\nx").unwrap();
+
+        tool.open_grep();
+        tool.grep_query = "needle".to_string();
+        tool.filter_grep();
+        let index = tool.grep_line_index();
+        let context: Vec<Vec<usize>> = tool
+            .grep_matches
+            .iter()
+            .map(|m| {
+                tool.grep_context(&index, m)
+                    .into_iter()
+                    .map(|(line, _)| line)
+                    .collect()
+            })
+            .collect();
+
+        // Context stays within the note and its bounds
+        assert_eq!(context, vec![vec![2, 3, 4, 5, 6], vec![1, 2]]);
+    }
 }
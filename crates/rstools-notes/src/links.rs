@@ -0,0 +1,78 @@
+//! `[[Note Name]]` wiki links between notes.
+//!
+//! Links are resolved by note name (case-insensitive), not by path, so a
+//! note can be moved between folders without breaking links to it.
+
+/// A `[[target]]` link on a line. `start..end` is the byte range of the
+/// whole link including the brackets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub start: usize,
+    pub end: usize,
+    pub target: String,
+}
+
+/// All wiki links on `line`, left to right. Empty targets are skipped.
+pub fn extract_links(line: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    let mut from = 0;
+
+    while let Some(open) = line[from..].find("[[").map(|i| from + i) {
+        let body_start = open + 2;
+        let Some(close) = line[body_start..].find("]]").map(|i| body_start + i) else {
+            break;
+        };
+        let target = line[body_start..close].trim();
+        if !target.is_empty() && !target.contains('[') {
+            links.push(WikiLink {
+                start: open,
+                end: close + 2,
+                target: target.to_string(),
+            });
+            from = close + 2;
+        } else {
+            from = open + 1;
+        }
+    }
+    links
+}
+
+/// The link under byte column `col`, or the first link on the line.
+pub fn link_at(line: &str, col: usize) -> Option<String> {
+    let links = extract_links(line);
+    links
+        .iter()
+        .find(|l| (l.start..l.end).contains(&col))
+        .or(links.first())
+        .map(|l| l.target.clone())
+}
+
+/// The text a note uses to link to `name`, as searched for by backlinks.
+pub fn link_text(name: &str) -> String {
+    format!("[[{name}]]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        let links = extract_links("see [[Alpha]] and [[ Beta Note ]], not [[]] or [x](y)");
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["Alpha", "Beta Note"]);
+        assert_eq!((links[0].start, links[0].end), (4, 13));
+
+        // Unclosed and nested brackets
+        assert!(extract_links("[[open").is_empty());
+        assert_eq!(extract_links("[[[x]]")[0].target, "x");
+    }
+
+    #[test]
+    fn test_link_at_prefers_cursor() {
+        let line = "[[One]] then [[Two]]";
+        assert_eq!(link_at(line, 15).as_deref(), Some("Two"));
+        assert_eq!(link_at(line, 9).as_deref(), Some("One"));
+        assert_eq!(link_at("plain", 0), None);
+    }
+}
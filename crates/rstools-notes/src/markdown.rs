@@ -0,0 +1,285 @@
+//! Minimal Markdown renderer for the note preview.
+//!
+//! Only the common constructs are handled: ATX headings, bold / italic,
+//! inline code, links and `[[wiki links]]`, bullet / numbered / task lists, block quotes,
+//! fenced code blocks and horizontal rules. Anything else is shown as-is.
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use rstools_core::theme::theme;
+
+/// Render Markdown `text` into styled lines, one per source line (fence
+/// lines of code blocks are dropped).
+pub fn render_markdown(text: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(Span::styled(
+                format!("  {raw}"),
+                Style::default().fg(theme().accent),
+            )));
+            continue;
+        }
+
+        lines.push(render_block_line(raw));
+    }
+
+    lines
+}
+
+/// Render a single line outside of a code block.
+fn render_block_line(raw: &str) -> Line<'static> {
+    let trimmed = raw.trim_start();
+    let indent = &raw[..raw.len() - trimmed.len()];
+
+    // Headings
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        let mut style = Style::default()
+            .fg(theme().info)
+            .add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Line::from(parse_inline(trimmed[level..].trim(), style));
+    }
+
+    // Horizontal rules
+    if is_rule(trimmed) {
+        return Line::from(Span::styled(
+            "\u{2500}".repeat(40),
+            Style::default().fg(theme().muted),
+        ));
+    }
+
+    // Block quotes
+    if let Some(rest) = trimmed.strip_prefix('>') {
+        let mut spans = vec![Span::styled(
+            format!("{indent}\u{2502} "),
+            Style::default().fg(theme().muted),
+        )];
+        spans.extend(parse_inline(
+            rest.trim_start(),
+            Style::default().add_modifier(Modifier::ITALIC),
+        ));
+        return Line::from(spans);
+    }
+
+    // Lists
+    if let Some((marker, rest)) = list_item(trimmed) {
+        let mut spans = vec![Span::styled(
+            format!("{indent}{marker}"),
+            Style::default().fg(theme().success),
+        )];
+        spans.extend(parse_inline(rest, Style::default()));
+        return Line::from(spans);
+    }
+
+    let mut spans = vec![Span::raw(indent.to_string())];
+    spans.extend(parse_inline(trimmed, Style::default()));
+    Line::from(spans)
+}
+
+/// `---`, `***` or `___` (three or more, spaces allowed).
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&m| marks.iter().all(|&c| c == m))
+}
+
+/// Split a list item into its rendered marker and the item text.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            if let Some(rest) = rest.strip_prefix("[ ] ") {
+                return Some(("\u{2610} ".to_string(), rest));
+            }
+            if let Some(rest) = rest
+                .strip_prefix("[x] ")
+                .or_else(|| rest.strip_prefix("[X] "))
+            {
+                return Some(("\u{2611} ".to_string(), rest));
+            }
+            return Some(("\u{2022} ".to_string(), rest));
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(text) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((format!("{}. ", &line[..digits]), text));
+        }
+    }
+    None
+}
+
+/// Parse inline emphasis, code spans and links on top of `base`.
+fn parse_inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let prev = text[..i].chars().next_back();
+
+        if let Some((span_len, inner, style)) = inline_token(rest, prev, base) {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), base));
+            }
+            match style {
+                InlineStyle::Code => {
+                    spans.push(Span::styled(inner.to_string(), base.fg(theme().accent)))
+                }
+                InlineStyle::Nested(style) => spans.extend(parse_inline(inner, style)),
+            }
+            i += span_len;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        plain.push(c);
+        i += c.len_utf8();
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+enum InlineStyle {
+    /// Rendered verbatim.
+    Code,
+    /// Parsed again with the given style.
+    Nested(Style),
+}
+
+/// Recognize an inline construct at the start of `rest`. Returns its byte
+/// length in the source, the inner text and how to style it.
+fn inline_token(rest: &str, prev: Option<char>, base: Style) -> Option<(usize, &str, InlineStyle)> {
+    if let Some(body) = rest.strip_prefix('`') {
+        let end = body.find('`').filter(|&end| end > 0)?;
+        return Some((end + 2, &body[..end], InlineStyle::Code));
+    }
+
+    for marker in ["**", "__"] {
+        if let Some(body) = rest.strip_prefix(marker) {
+            let end = body.find(marker).filter(|&end| end > 0)?;
+            let style = base.add_modifier(Modifier::BOLD);
+            return Some((end + 4, &body[..end], InlineStyle::Nested(style)));
+        }
+    }
+
+    for marker in ['*', '_'] {
+        // `_` inside a word (snake_case) is not emphasis
+        if marker == '_' && prev.is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+        if let Some(body) = rest.strip_prefix(marker) {
+            if body.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let end = body.find(marker).filter(|&end| end > 0)?;
+            let style = base.add_modifier(Modifier::ITALIC);
+            return Some((end + 2, &body[..end], InlineStyle::Nested(style)));
+        }
+    }
+
+    // `[[Note]]` wiki links
+    if let Some(body) = rest.strip_prefix("[[")
+        && let Some(end) = body.find("]]").filter(|&end| end > 0)
+    {
+        let style = base.fg(theme().border).add_modifier(Modifier::UNDERLINED);
+        return Some((end + 4, &body[..end], InlineStyle::Nested(style)));
+    }
+
+    if let Some(body) = rest.strip_prefix('[') {
+        let close = body.find("](")?;
+        let url_len = body[close + 2..].find(')')?;
+        let style = base.fg(theme().border).add_modifier(Modifier::UNDERLINED);
+        return Some((
+            close + url_len + 4,
+            &body[..close],
+            InlineStyle::Nested(style),
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_block_constructs() {
+        let lines = render_markdown(
+            "# Title\n- item\n- [x] done\n2. second\n> quoted\n---\n```\nlet x = 1;\n```\nplain",
+        );
+        let texts: Vec<String> = lines.iter().map(text_of).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Title",
+                "\u{2022} item",
+                "\u{2611} done",
+                "2. second",
+                "\u{2502} quoted",
+                &"\u{2500}".repeat(40),
+                "  let x = 1;",
+                "plain",
+            ]
+        );
+        assert!(
+            lines[0].spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+        assert_eq!(lines[6].spans[0].style.fg, Some(theme().accent));
+    }
+
+    #[test]
+    fn test_inline_constructs() {
+        let spans = parse_inline(
+            "a **bold** and *it* with `code` [link](http://x)",
+            Style::default(),
+        );
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(
+            texts,
+            vec!["a ", "bold", " and ", "it", " with ", "code", " ", "link"]
+        );
+        assert!(spans[1].style.add_modifier.contains(Modifier::BOLD));
+        assert!(spans[3].style.add_modifier.contains(Modifier::ITALIC));
+        assert_eq!(spans[5].style.fg, Some(theme().accent));
+        assert_eq!(spans[7].style.fg, Some(theme().border));
+
+        let spans = parse_inline("see [[Other Note]]", Style::default());
+        assert_eq!(spans[1].content, "Other Note");
+        assert_eq!(spans[1].style.fg, Some(theme().border));
+
+        // Unclosed markers and snake_case are left alone
+        let spans = parse_inline("snake_case_name and 2 * 3", Style::default());
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].content, "snake_case_name and 2 * 3");
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 
 // ── Entry types ──────────────────────────────────────────────────────
 
@@ -27,6 +27,52 @@ impl EntryType {
     }
 }
 
+// ── Settings ─────────────────────────────────────────────────────────
+
+/// Default auto-save interval for a dirty note, in seconds.
+pub const DEFAULT_AUTOSAVE_SECS: u64 = 5;
+
+/// Tool-wide settings, stored as key/value rows in `note_settings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotesSettings {
+    /// Seconds a note may stay dirty before it is saved (0 = never).
+    pub autosave_secs: u64,
+    /// Editor gutter: absolute (`nu`) and relative (`rnu`) line numbers.
+    pub line_numbers: bool,
+    pub relative_numbers: bool,
+    /// Soft-wrap long lines in the editor (`wrap`).
+    pub wrap: bool,
+    /// Line width for `gq` (`tw`).
+    pub text_width: usize,
+    /// Close brackets and quotes while typing (`autopairs`).
+    pub auto_pairs: bool,
+    /// Columns per `>>` / `<<` step (`sw`).
+    pub shift_width: usize,
+    /// Indent with spaces rather than tabs (`et`).
+    pub expand_tab: bool,
+    /// Allow `:!cmd` / `:%!cmd` to run external programs (`shellfilter`).
+    pub shell_filter: bool,
+    /// Strip trailing whitespace and end with one newline on save (`trimonsave`).
+    pub trim_on_save: bool,
+}
+
+impl Default for NotesSettings {
+    fn default() -> Self {
+        Self {
+            autosave_secs: DEFAULT_AUTOSAVE_SECS,
+            line_numbers: true,
+            relative_numbers: true,
+            wrap: false,
+            text_width: 80,
+            auto_pairs: false,
+            shift_width: 4,
+            expand_tab: true,
+            shell_filter: false,
+            trim_on_save: false,
+        }
+    }
+}
+
 // ── Data models ──────────────────────────────────────────────────────
 
 /// A single entry in the notes tree (folder or note).
@@ -51,6 +97,14 @@ pub struct NoteContent {
     pub updated_at: String,
 }
 
+/// Where the cursor was left in a note, restored when it is reopened. The
+/// editor keeps the cursor line centered, so this also restores the scroll.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotePosition {
+    pub cursor_row: usize,
+    pub cursor_col: usize,
+}
+
 // ── Database ─────────────────────────────────────────────────────────
 
 /// Initialize the database tables for the Notes tool.
@@ -84,7 +138,18 @@ pub fn init_db(conn: &Connection) -> Result<()> {
         AFTER UPDATE ON note_contents
         BEGIN
             UPDATE note_contents SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
-        END;",
+        END;
+
+        CREATE TABLE IF NOT EXISTS note_positions (
+            entry_id INTEGER PRIMARY KEY REFERENCES note_entries(id) ON DELETE CASCADE,
+            cursor_row INTEGER NOT NULL DEFAULT 0,
+            cursor_col INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS note_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
     )?;
     Ok(())
 }
@@ -252,6 +317,161 @@ pub fn save_note_content(conn: &Connection, entry_id: i64, body: &str) -> Result
     Ok(())
 }
 
+/// Saved cursor position of a note (the default if it was never saved).
+pub fn get_note_position(conn: &Connection, entry_id: i64) -> Result<NotePosition> {
+    let position = conn
+        .query_row(
+            "SELECT cursor_row, cursor_col FROM note_positions WHERE entry_id = ?1",
+            rusqlite::params![entry_id],
+            |row| {
+                Ok(NotePosition {
+                    cursor_row: row.get::<_, i64>(0)? as usize,
+                    cursor_col: row.get::<_, i64>(1)? as usize,
+                })
+            },
+        )
+        .optional()?;
+    Ok(position.unwrap_or_default())
+}
+
+/// Remember the cursor position of a note. Kept apart from `note_contents`
+/// so moving around does not bump the note's `updated_at`.
+pub fn save_note_position(conn: &Connection, entry_id: i64, position: NotePosition) -> Result<()> {
+    conn.execute(
+        "INSERT INTO note_positions (entry_id, cursor_row, cursor_col) VALUES (?1, ?2, ?3)
+         ON CONFLICT(entry_id) DO UPDATE SET
+             cursor_row = excluded.cursor_row,
+             cursor_col = excluded.cursor_col",
+        rusqlite::params![
+            entry_id,
+            position.cursor_row as i64,
+            position.cursor_col as i64
+        ],
+    )?;
+    Ok(())
+}
+
+/// Names of the entries directly under `parent_id` (`None` = top level).
+pub fn child_names(conn: &Connection, parent_id: Option<i64>) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM note_entries WHERE parent_id IS ?1")?;
+    let names = stmt
+        .query_map(rusqlite::params![parent_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Names and bodies of the notes directly inside the top-level `folder`
+/// (the templates folder), sorted by name.
+pub fn list_folder_notes(conn: &Connection, folder: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT n.name, c.body
+         FROM note_entries n
+         JOIN note_entries f ON n.parent_id = f.id
+         JOIN note_contents c ON c.entry_id = n.id
+         WHERE f.parent_id IS NULL AND f.entry_type = 'folder' AND f.name = ?1
+           AND n.entry_type = 'note'
+         ORDER BY n.name ASC",
+    )?;
+    let notes = stmt
+        .query_map(rusqlite::params![folder], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+/// Load the tool settings; missing or unparsable values keep their defaults.
+pub fn load_settings(conn: &Connection) -> Result<NotesSettings> {
+    let mut settings = NotesSettings::default();
+    let mut stmt = conn.prepare("SELECT key, value FROM note_settings")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (key, value) in rows {
+        match key.as_str() {
+            "autosave_secs" => {
+                if let Ok(secs) = value.parse() {
+                    settings.autosave_secs = secs;
+                }
+            }
+            "line_numbers" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.line_numbers = enabled;
+                }
+            }
+            "relative_numbers" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.relative_numbers = enabled;
+                }
+            }
+            "wrap" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.wrap = enabled;
+                }
+            }
+            "text_width" => {
+                if let Ok(width) = value.parse() {
+                    settings.text_width = width;
+                }
+            }
+            "auto_pairs" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.auto_pairs = enabled;
+                }
+            }
+            "shift_width" => {
+                if let Ok(width) = value.parse() {
+                    settings.shift_width = width;
+                }
+            }
+            "expand_tab" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.expand_tab = enabled;
+                }
+            }
+            "shell_filter" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.shell_filter = enabled;
+                }
+            }
+            "trim_on_save" => {
+                if let Ok(enabled) = value.parse() {
+                    settings.trim_on_save = enabled;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// Persist the tool settings.
+pub fn save_settings(conn: &Connection, settings: &NotesSettings) -> Result<()> {
+    let values = [
+        ("autosave_secs", settings.autosave_secs.to_string()),
+        ("line_numbers", settings.line_numbers.to_string()),
+        ("relative_numbers", settings.relative_numbers.to_string()),
+        ("wrap", settings.wrap.to_string()),
+        ("text_width", settings.text_width.to_string()),
+        ("auto_pairs", settings.auto_pairs.to_string()),
+        ("shift_width", settings.shift_width.to_string()),
+        ("expand_tab", settings.expand_tab.to_string()),
+        ("shell_filter", settings.shell_filter.to_string()),
+        ("trim_on_save", settings.trim_on_save.to_string()),
+    ];
+    for (key, value) in values {
+        conn.execute(
+            "INSERT INTO note_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
 // ── Tests ────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -305,6 +525,30 @@ mod tests {
         assert_eq!(content.body, "Hello, world!");
     }
 
+    #[test]
+    fn test_note_position() {
+        let conn = setup_db();
+        let id = add_entry(&conn, None, "long", EntryType::Note).unwrap();
+        assert_eq!(
+            get_note_position(&conn, id).unwrap(),
+            NotePosition::default()
+        );
+
+        let position = NotePosition {
+            cursor_row: 40,
+            cursor_col: 3,
+        };
+        save_note_position(&conn, id, position).unwrap();
+        save_note_position(&conn, id, position).unwrap();
+        assert_eq!(get_note_position(&conn, id).unwrap(), position);
+
+        delete_entry(&conn, id).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM note_positions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
     #[test]
     fn test_rename_entry() {
         let conn = setup_db();
@@ -379,4 +623,51 @@ mod tests {
         let moved = entries.iter().find(|e| e.id == note_id).unwrap();
         assert_eq!(moved.parent_id, Some(folder_b));
     }
+
+    #[test]
+    fn test_list_folder_notes() {
+        let conn = setup_db();
+        let folder = add_entry(&conn, None, "templates", EntryType::Folder).unwrap();
+        let weekly = add_entry(&conn, Some(folder), "weekly", EntryType::Note).unwrap();
+        save_note_content(&conn, weekly, "# Week").unwrap();
+        add_entry(&conn, Some(folder), "nested", EntryType::Folder).unwrap();
+        let other = add_entry(&conn, None, "other", EntryType::Folder).unwrap();
+        add_entry(&conn, Some(other), "daily", EntryType::Note).unwrap();
+
+        assert_eq!(
+            list_folder_notes(&conn, "templates").unwrap(),
+            vec![("weekly".to_string(), "# Week".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let conn = setup_db();
+        assert_eq!(load_settings(&conn).unwrap(), NotesSettings::default());
+
+        let changed = NotesSettings {
+            autosave_secs: 0,
+            relative_numbers: false,
+            wrap: true,
+            text_width: 72,
+            auto_pairs: true,
+            shift_width: 2,
+            expand_tab: false,
+            shell_filter: true,
+            trim_on_save: true,
+            ..NotesSettings::default()
+        };
+        save_settings(&conn, &changed).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), changed);
+
+        conn.execute(
+            "UPDATE note_settings SET value = 'soon' WHERE key = 'autosave_secs'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            load_settings(&conn).unwrap().autosave_secs,
+            DEFAULT_AUTOSAVE_SECS
+        );
+    }
 }
@@ -0,0 +1,122 @@
+//! `#tag` extraction and the tag index behind the tag picker.
+//!
+//! A tag is `#` followed by a letter and then letters, digits, `_`, `-` or
+//! `/`, at the start of a line or after whitespace. Tags are case-insensitive
+//! and stored lowercase; Markdown headings (`# Title`) are not tags.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// All tags on `line`, lowercase and without the `#`, in order of appearance.
+pub fn extract_tags(line: &str) -> Vec<String> {
+    tag_spans(line).into_iter().map(|(_, _, tag)| tag).collect()
+}
+
+/// Every tag on `line` as (byte offset of the `#`, byte length including
+/// the `#`, lowercase tag without the `#`).
+fn tag_spans(line: &str) -> Vec<(usize, usize, String)> {
+    let mut tags = Vec::new();
+    let mut prev: Option<char> = None;
+
+    for (idx, c) in line.char_indices() {
+        if c == '#' && prev.is_none_or(char::is_whitespace) {
+            let rest = &line[idx + 1..];
+            if rest.starts_with(char::is_alphabetic) {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '/')))
+                    .unwrap_or(rest.len());
+                let tag = rest[..len].trim_end_matches(['-', '/']);
+                tags.push((idx, tag.len() + 1, tag.to_lowercase()));
+            }
+        }
+        prev = Some(c);
+    }
+    tags
+}
+
+/// Whether `line` carries `tag` (lowercase, without `#`).
+pub fn line_has_tag(line: &str, tag: &str) -> bool {
+    find_tag(line, tag).is_some()
+}
+
+/// Byte offset and length in `line` of the first occurrence of `tag`
+/// (lowercase, without `#`), whatever its case there.
+pub fn find_tag(line: &str, tag: &str) -> Option<(usize, usize)> {
+    tag_spans(line)
+        .into_iter()
+        .find(|(_, _, t)| t == tag)
+        .map(|(start, len, _)| (start, len))
+}
+
+/// Tags found across all notes, with the notes that use each one.
+#[derive(Debug, Clone, Default)]
+pub struct TagIndex {
+    notes_by_tag: BTreeMap<String, BTreeSet<i64>>,
+}
+
+impl TagIndex {
+    /// Build the index from `(entry_id, line)` pairs.
+    pub fn build<'a>(lines: impl IntoIterator<Item = (i64, &'a str)>) -> Self {
+        let mut notes_by_tag: BTreeMap<String, BTreeSet<i64>> = BTreeMap::new();
+        for (entry_id, line) in lines {
+            for tag in extract_tags(line) {
+                notes_by_tag.entry(tag).or_default().insert(entry_id);
+            }
+        }
+        Self { notes_by_tag }
+    }
+
+    /// Tags with the number of notes using them, most used first (ties by name).
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self
+            .notes_by_tag
+            .iter()
+            .map(|(tag, notes)| (tag.clone(), notes.len()))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes_by_tag.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tags() {
+        assert_eq!(
+            extract_tags("#Project work on #rust-lang/tui, see #todo-"),
+            vec!["project", "rust-lang/tui", "todo"]
+        );
+        // Headings, issue numbers, anchors and mid-word hashes are not tags
+        assert!(extract_tags("# Title").is_empty());
+        assert!(extract_tags("## Sub").is_empty());
+        assert!(extract_tags("fixes #42 and C#sharp url#frag").is_empty());
+    }
+
+    #[test]
+    fn test_index_counts_notes_once() {
+        let index = TagIndex::build([
+            (1, "#rust and #rust again"),
+            (1, "#tui"),
+            (2, "more #rust"),
+            (3, "#alpha"),
+        ]);
+        assert_eq!(
+            index.tag_counts(),
+            vec![
+                ("rust".to_string(), 2),
+                ("alpha".to_string(), 1),
+                ("tui".to_string(), 1),
+            ]
+        );
+        assert!(line_has_tag("more #Rust", "rust"));
+        assert!(!line_has_tag("more #rustacean", "rust"));
+        // Offsets point into the original line, not a lowercased copy
+        assert_eq!(find_tag("#rustacean, Éte #RUST", "rust"), Some((17, 5)));
+        assert_eq!(find_tag("#Straße", "straße"), Some((0, 8)));
+    }
+}
@@ -0,0 +1,80 @@
+//! Note templates.
+//!
+//! Templates are the notes inside the top-level `templates` folder, looked up
+//! by note name. A few built-in templates are used when no note of that name
+//! exists. Placeholders are expanded when a template is applied:
+//! `{{date}}` (YYYY-MM-DD), `{{time}}` (HH:MM) and `{{title}}` (note name).
+
+use chrono::NaiveDateTime;
+
+/// Name of the top-level folder holding user templates.
+pub const TEMPLATES_FOLDER: &str = "templates";
+
+/// Templates available without a `templates` folder.
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("daily", "# {{date}}\n\n## Tasks\n\n- [ ] \n\n## Notes\n"),
+    (
+        "meeting",
+        "# {{title}}\n\n{{date}} {{time}}\n\n## Attendees\n\n## Notes\n\n## Actions\n",
+    ),
+];
+
+/// The built-in template called `name` (case-insensitive).
+pub fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, body)| *body)
+}
+
+/// Expand the placeholders of a template body.
+pub fn expand(body: &str, title: &str, now: NaiveDateTime) -> String {
+    body.replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+        .replace("{{title}}", title)
+}
+
+/// Split a trailing ` @template` off a new-entry path:
+/// `"journal/today @daily"` → `("journal/today", Some("daily"))`.
+pub fn split_template_suffix(input: &str) -> (&str, Option<&str>) {
+    let input = input.trim();
+    match input.rsplit_once(" @") {
+        Some((path, name)) if !name.is_empty() && !name.contains(char::is_whitespace) => {
+            (path.trim_end(), Some(name))
+        }
+        _ => (input, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_expand_placeholders() {
+        let now = NaiveDate::from_ymd_opt(2024, 3, 9)
+            .unwrap()
+            .and_hms_opt(8, 5, 0)
+            .unwrap();
+        assert_eq!(
+            expand("# {{title}} {{date}} {{time}} {{other}}", "Standup", now),
+            "# Standup 2024-03-09 08:05 {{other}}"
+        );
+        assert!(builtin("Daily").unwrap().starts_with("# {{date}}"));
+        assert_eq!(builtin("nope"), None);
+    }
+
+    #[test]
+    fn test_split_template_suffix() {
+        assert_eq!(
+            split_template_suffix("journal/today @daily"),
+            ("journal/today", Some("daily"))
+        );
+        assert_eq!(split_template_suffix(" plain note "), ("plain note", None));
+        assert_eq!(
+            split_template_suffix("mail me @ home"),
+            ("mail me @ home", None)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::markdown::render_markdown;
 use crate::sidebar::{SidebarState, TreeSidebarRenderConfig, render_tree_sidebar};
 use ratatui::{
     Frame,
@@ -6,6 +7,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
+use rstools_core::theme::theme;
 use rstools_core::vim_editor::VimEditor;
 
 /// Fixed sidebar width in characters.
@@ -13,7 +15,8 @@ pub const SIDEBAR_WIDTH: u16 = 40;
 
 // ── Main entry point ─────────────────────────────────────────────────
 
-/// Render the entire Notes tool view.
+/// Render the entire Notes tool view. `preview_scroll` is set while the
+/// rendered Markdown preview replaces the raw editor.
 pub fn render_notes_tool(
     frame: &mut Frame,
     area: Rect,
@@ -21,6 +24,7 @@ pub fn render_notes_tool(
     editor: &VimEditor,
     sidebar_focused: bool,
     active_note_name: Option<&str>,
+    preview_scroll: Option<usize>,
 ) {
     if sidebar.visible {
         let sidebar_width = SIDEBAR_WIDTH.min(area.width.saturating_sub(10));
@@ -44,9 +48,10 @@ pub fn render_notes_tool(
             editor,
             !sidebar_focused,
             active_note_name,
+            preview_scroll,
         );
     } else {
-        render_editor_panel(frame, area, editor, true, active_note_name);
+        render_editor_panel(frame, area, editor, true, active_note_name, preview_scroll);
     }
 }
 
@@ -57,8 +62,9 @@ fn render_sidebar(frame: &mut Frame, area: Rect, sidebar: &SidebarState, focused
         title: " Notes ",
         focused,
         leaf_icon: Some("\u{25A0} "), // filled square
-        leaf_style: Some(Style::default().fg(Color::White)),
-        folder_style: Some(Style::default().fg(Color::Blue)),
+        leaf_style: Some(Style::default().fg(theme().text)),
+        folder_style: Some(Style::default().fg(theme().border)),
+        leaf_decoration: None,
     };
     render_tree_sidebar(frame, area, sidebar, &config);
 }
@@ -71,18 +77,24 @@ fn render_editor_panel(
     editor: &VimEditor,
     focused: bool,
     note_name: Option<&str>,
+    preview_scroll: Option<usize>,
 ) {
     match note_name {
         Some(name) => {
-            // Build title with dirty indicator
+            // Build title with dirty and preview indicators
             let dirty = if editor.is_dirty() { " [+]" } else { "" };
-            let title = format!(" {}{} ", name, dirty);
-
-            let border_color = if focused {
-                Color::White
+            let preview = if preview_scroll.is_some() {
+                " [preview]"
             } else {
-                Color::DarkGray
+                ""
             };
+            let recording = editor
+                .recording_macro()
+                .map(|reg| format!(" recording @{reg}"))
+                .unwrap_or_default();
+            let title = format!(" {}{}{}{} ", name, dirty, preview, recording);
+
+            let border_color = if focused { theme().text } else { theme().muted };
             let block = Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(border_color))
@@ -91,6 +103,14 @@ fn render_editor_panel(
             let inner = block.inner(area);
             frame.render_widget(block, area);
 
+            if let Some(scroll) = preview_scroll {
+                let preview = Paragraph::new(render_markdown(&editor.text()))
+                    .scroll((scroll as u16, 0))
+                    .wrap(ratatui::widgets::Wrap { trim: false });
+                frame.render_widget(preview, inner);
+                return;
+            }
+
             // Render the vim editor inside the block
             editor.render(frame, inner, focused);
         }
@@ -103,14 +123,14 @@ fn render_editor_panel(
 fn render_empty_panel(frame: &mut Frame, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray));
+        .border_style(Style::default().fg(theme().muted));
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     let msg = "Select a note to edit";
     let text = Paragraph::new(Line::from(vec![Span::styled(
         msg,
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme().muted),
     )]))
     .alignment(ratatui::layout::Alignment::Center);
 
@@ -127,15 +147,63 @@ fn render_empty_panel(frame: &mut Frame, area: Rect) {
     }
 }
 
+/// One grep result: its location and the lines around the match.
+pub struct GrepResult {
+    /// `path:line` of the match.
+    pub location: String,
+    /// `(line number, text)` of the match line and its neighbours, in order.
+    pub context: Vec<(usize, String)>,
+    pub line_number: usize,
+    /// Byte span of the match in the match line.
+    pub column: usize,
+    pub match_len: usize,
+}
+
+impl GrepResult {
+    fn to_list_item(&self) -> ListItem<'static> {
+        let width = self
+            .context
+            .last()
+            .map_or(1, |(number, _)| number.to_string().len());
+        let dim = Style::default().fg(theme().muted);
+
+        let mut lines = vec![Line::from(Span::styled(
+            self.location.clone(),
+            Style::default().fg(theme().info),
+        ))];
+        for (number, text) in &self.context {
+            let mut spans = vec![Span::styled(format!("  {number:>width$} \u{2502} "), dim)];
+            match split_match(text, self.column, self.match_len) {
+                Some((before, matched, after)) if *number == self.line_number => {
+                    spans.push(Span::raw(before.to_string()));
+                    spans.push(Span::styled(
+                        matched.to_string(),
+                        Style::default()
+                            .fg(theme().accent)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                    spans.push(Span::raw(after.to_string()));
+                }
+                _ if *number == self.line_number => spans.push(Span::raw(text.clone())),
+                _ => spans.push(Span::styled(text.clone(), dim)),
+            }
+            lines.push(Line::from(spans));
+        }
+        ListItem::new(lines)
+    }
+}
+
 pub fn render_grep_overlay(
     frame: &mut Frame,
     area: Rect,
+    title: &str,
     query: &str,
-    results: &[String],
+    error: Option<&str>,
+    results: &[GrepResult],
     selected: usize,
     preview_title: &str,
     preview_text: &str,
-    preview_target_line: Option<usize>,
+    preview_match: Option<(usize, usize, usize)>,
 ) {
     let popup_width = (area.width * 80 / 100)
         .max(50)
@@ -158,15 +226,21 @@ pub fn render_grep_overlay(
         Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
             .areas(content_area);
 
-    let input = Paragraph::new(Line::from(vec![
+    let mut input_spans = vec![
         Span::styled("> ", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(query),
-    ]))
-    .block(
+    ];
+    if let Some(error) = error {
+        input_spans.push(Span::styled(
+            format!("  ({error})"),
+            Style::default().fg(theme().error),
+        ));
+    }
+    let input = Paragraph::new(Line::from(input_spans)).block(
         Block::default()
-            .title(" Grep Notes ")
+            .title(format!(" {title} "))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::White)),
+            .border_style(Style::default().fg(theme().text)),
     );
     frame.render_widget(input, input_area);
 
@@ -175,13 +249,10 @@ pub fn render_grep_overlay(
     let items: Vec<ListItem> = if results.is_empty() {
         vec![ListItem::new(Line::from(Span::styled(
             "No matches",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )))]
     } else {
-        results
-            .iter()
-            .map(|r| ListItem::new(Line::from(Span::raw(r))))
-            .collect()
+        results.iter().map(GrepResult::to_list_item).collect()
     };
 
     let mut list_state = ListState::default();
@@ -197,6 +268,7 @@ pub fn render_grep_overlay(
     frame.render_stateful_widget(list, results_area, &mut list_state);
 
     let preview_height = preview_area.height.saturating_sub(2) as usize; // account for borders
+    let preview_target_line = preview_match.map(|(line, _, _)| line);
     let preview_scroll = if let Some(target_line) = preview_target_line {
         if preview_height == 0 {
             0
@@ -212,14 +284,10 @@ pub fn render_grep_overlay(
         .lines()
         .enumerate()
         .map(|(i, line)| {
-            if Some(i) == preview_target_line {
-                Line::from(Span::styled(
-                    line.to_string(),
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                ))
+            if let Some((target, start, len)) = preview_match
+                && target == i
+            {
+                highlight_match(line, start, len)
             } else {
                 Line::from(Span::raw(line.to_string()))
             }
@@ -239,3 +307,94 @@ pub fn render_grep_overlay(
         .wrap(ratatui::widgets::Wrap { trim: false });
     frame.render_widget(preview, preview_area);
 }
+
+/// Split `line` around the byte span `start..start + len`, if it is a
+/// non-empty span on char boundaries.
+fn split_match(line: &str, start: usize, len: usize) -> Option<(&str, &str, &str)> {
+    let end = start + len;
+    match (line.get(..start), line.get(start..end), line.get(end..)) {
+        (Some(before), Some(matched), Some(after)) if len > 0 => Some((before, matched, after)),
+        _ => None,
+    }
+}
+
+/// A grep preview line: the matched byte span stands out on a dimmed line.
+fn highlight_match(line: &str, start: usize, len: usize) -> Line<'static> {
+    let line_style = Style::default().bg(theme().muted);
+    let match_style = Style::default()
+        .fg(Color::Black)
+        .bg(theme().accent)
+        .add_modifier(Modifier::BOLD);
+
+    match split_match(line, start, len) {
+        Some((before, matched, after)) => Line::from(vec![
+            Span::styled(before.to_string(), line_style),
+            Span::styled(matched.to_string(), match_style),
+            Span::styled(after.to_string(), line_style),
+        ]),
+        None => Line::from(Span::styled(line.to_string(), match_style)),
+    }
+}
+
+/// Render the `#tag` picker: a filter input above the tags and their note counts.
+pub fn render_tag_picker(
+    frame: &mut Frame,
+    area: Rect,
+    query: &str,
+    rows: &[String],
+    selected: usize,
+) {
+    let popup_width = (area.width * 40 / 100)
+        .max(30)
+        .min(area.width.saturating_sub(4));
+    let popup_height = (area.height * 60 / 100)
+        .max(10)
+        .min(area.height.saturating_sub(4));
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(popup_width)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let [input_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(popup_area);
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled("# ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(query),
+    ]))
+    .block(
+        Block::default()
+            .title(" Tags ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().text)),
+    );
+    frame.render_widget(input, input_area);
+
+    frame.set_cursor_position((input_area.x + 3 + query.len() as u16, input_area.y + 1));
+
+    let items: Vec<ListItem> = if rows.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "No matching tags",
+            Style::default().fg(theme().muted),
+        )))]
+    } else {
+        rows.iter()
+            .map(|r| ListItem::new(Line::from(Span::raw(r))))
+            .collect()
+    };
+
+    let mut list_state = ListState::default();
+    if !rows.is_empty() {
+        list_state.select(Some(selected.min(rows.len() - 1)));
+    }
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+}
@@ -0,0 +1,99 @@
+//! Due dates: parsing the trailing `@date` token of a todo title.
+
+use chrono::{Days, NaiveDate};
+
+/// Format used to store and display due dates.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// How a due date relates to today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueStatus {
+    Overdue,
+    Today,
+    Upcoming,
+}
+
+impl DueStatus {
+    pub fn of(due: NaiveDate, today: NaiveDate) -> Self {
+        match due.cmp(&today) {
+            std::cmp::Ordering::Less => DueStatus::Overdue,
+            std::cmp::Ordering::Equal => DueStatus::Today,
+            std::cmp::Ordering::Greater => DueStatus::Upcoming,
+        }
+    }
+}
+
+/// Split a trailing `@YYYY-MM-DD`, `@today` or `@tomorrow` off `input`.
+/// Returns the remaining title and the date; an unrecognized `@token` is
+/// left in the title.
+pub fn parse_due(input: &str, today: NaiveDate) -> (String, Option<NaiveDate>) {
+    let input = input.trim();
+    let Some((title, token)) = input.rsplit_once('@') else {
+        return (input.to_string(), None);
+    };
+    if !title.is_empty() && !title.ends_with(char::is_whitespace) {
+        return (input.to_string(), None);
+    }
+
+    let date = match token.to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.checked_add_days(Days::new(1)),
+        other => NaiveDate::parse_from_str(other, DATE_FORMAT).ok(),
+    };
+    match date {
+        Some(date) => (title.trim_end().to_string(), Some(date)),
+        None => (input.to_string(), None),
+    }
+}
+
+/// `title @YYYY-MM-DD`, the editable form of a todo with a due date.
+pub fn with_due(title: &str, due: Option<NaiveDate>) -> String {
+    match due {
+        Some(due) => format!("{title} @{}", due.format(DATE_FORMAT)),
+        None => title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_due() {
+        let today = date(2024, 2, 28);
+        assert_eq!(
+            parse_due("Pay rent @2024-03-01", today),
+            ("Pay rent".to_string(), Some(date(2024, 3, 1)))
+        );
+        assert_eq!(
+            parse_due("Call bob @Today", today),
+            ("Call bob".to_string(), Some(today))
+        );
+        assert_eq!(
+            parse_due("Renew @tomorrow", today),
+            ("Renew".to_string(), Some(date(2024, 2, 29)))
+        );
+        // Not a date, or not a separate token: left alone
+        assert_eq!(parse_due("Mail a@b.c", today).1, None);
+        assert_eq!(
+            parse_due("Ping @team", today),
+            ("Ping @team".to_string(), None)
+        );
+        assert_eq!(
+            with_due("Pay rent", Some(date(2024, 3, 1))),
+            "Pay rent @2024-03-01"
+        );
+    }
+
+    #[test]
+    fn test_due_status() {
+        let today = date(2024, 2, 28);
+        assert_eq!(DueStatus::of(date(2024, 2, 27), today), DueStatus::Overdue);
+        assert_eq!(DueStatus::of(today, today), DueStatus::Today);
+        assert_eq!(DueStatus::of(date(2025, 1, 1), today), DueStatus::Upcoming);
+    }
+}
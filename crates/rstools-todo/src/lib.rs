@@ -1,6 +1,10 @@
+pub mod due;
 pub mod model;
 pub mod ui;
 
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
 use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
@@ -19,6 +23,31 @@ use rstools_core::{
 
 use model::Todo;
 
+/// The local calendar date, for resolving `@today` and highlighting due dates.
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Number of actions `u` can undo.
+const UNDO_LIMIT: usize = 20;
+
+/// A mutating action, with what is needed to reverse it.
+#[derive(Debug, Clone)]
+enum UndoOp {
+    /// A todo was added.
+    Added(i64),
+    /// Todos were deleted (full rows, so they come back unchanged).
+    Deleted(Vec<Todo>),
+    /// Todos were completed or reopened; holds each one's previous state.
+    Completion(Vec<(i64, bool)>),
+    /// A todo's title or due date was edited; holds the previous values.
+    Edited {
+        id: i64,
+        title: String,
+        due_date: Option<NaiveDate>,
+    },
+}
+
 /// The editing context when in Insert mode.
 #[derive(Debug, Clone)]
 enum EditContext {
@@ -50,6 +79,16 @@ pub struct TodoTool {
     edit_context: Option<EditContext>,
     /// Current filter string.
     filter: Option<String>,
+    /// Persisted settings (sort mode, hide completed).
+    settings: model::TodoSettings,
+    /// Multi-select (visual) mode is active.
+    visual: bool,
+    /// Ids of the todos picked in visual mode.
+    marked: HashSet<i64>,
+    /// Recent actions, most recent last.
+    undo_stack: Vec<UndoOp>,
+    /// Completed todos matching the filter but hidden by `hide_completed`.
+    hidden_count: usize,
     /// Database connection.
     conn: Connection,
 }
@@ -66,22 +105,35 @@ impl TodoTool {
             input_cursor: 0,
             edit_context: None,
             filter: None,
+            settings: model::TodoSettings::default(),
+            visual: false,
+            marked: HashSet::new(),
+            undo_stack: Vec::new(),
+            hidden_count: 0,
             conn,
         };
         model::init_db(&tool.conn)?;
+        tool.settings = model::load_settings(&tool.conn)?;
         tool.reload()?;
         Ok(tool)
     }
 
     /// Reload todos from the database.
     fn reload(&mut self) -> anyhow::Result<()> {
+        let selected_id = self.selected_todo_id();
         self.todos = model::list_todos(&self.conn)?;
-        self.apply_filter();
+        self.apply_filter_keeping(selected_id);
         Ok(())
     }
 
-    /// Apply the current filter to the todo list.
+    /// Apply the current filter and sort mode to the todo list.
     fn apply_filter(&mut self) {
+        let selected_id = self.selected_todo_id();
+        self.apply_filter_keeping(selected_id);
+    }
+
+    /// Rebuild `filtered`, keeping `selected_id` selected if it is still visible.
+    fn apply_filter_keeping(&mut self, selected_id: Option<i64>) {
         if let Some(ref filter) = self.filter {
             let f = filter.to_lowercase();
             self.filtered = self
@@ -95,8 +147,26 @@ impl TodoTool {
             self.filtered = (0..self.todos.len()).collect();
         }
 
+        if self.settings.hide_completed {
+            let before = self.filtered.len();
+            self.filtered.retain(|&i| !self.todos[i].completed);
+            self.hidden_count = before - self.filtered.len();
+        } else {
+            self.hidden_count = 0;
+        }
+
+        let todos = &self.todos;
+        let mode = self.settings.sort_mode;
+        self.filtered
+            .sort_by(|&a, &b| mode.compare(&todos[a], &todos[b]));
+
+        let selected_pos =
+            selected_id.and_then(|id| self.filtered.iter().position(|&i| self.todos[i].id == id));
+
         // Keep selection in bounds
-        if self.filtered.is_empty() {
+        if let Some(pos) = selected_pos {
+            self.list_state.select(Some(pos));
+        } else if self.filtered.is_empty() {
             self.list_state.select(None);
         } else {
             let sel = self.list_state.selected().unwrap_or(0);
@@ -109,6 +179,210 @@ impl TodoTool {
         }
     }
 
+    /// Move the selected todo one step up or down the visible list, switching
+    /// to manual order first if another sort mode is active.
+    fn move_selected_todo(&mut self, down: bool) {
+        let Some(todo_id) = self.selected_todo_id() else {
+            return;
+        };
+        if self.settings.sort_mode != model::SortMode::Manual {
+            self.settings.sort_mode = model::SortMode::Manual;
+            let _ = model::save_settings(&self.conn, &self.settings);
+            self.apply_filter();
+        }
+
+        let Some(sel) = self.list_state.selected() else {
+            return;
+        };
+        let target = if down {
+            sel + 1
+        } else {
+            match sel.checked_sub(1) {
+                Some(target) => target,
+                None => return,
+            }
+        };
+        let Some(neighbour_id) = self.filtered.get(target).map(|&i| self.todos[i].id) else {
+            return;
+        };
+
+        // Swap within the full manual order so hidden (filtered out) todos keep their place
+        let mut ids: Vec<i64> = self.todos.iter().map(|t| t.id).collect();
+        let (Some(a), Some(b)) = (
+            ids.iter().position(|&id| id == todo_id),
+            ids.iter().position(|&id| id == neighbour_id),
+        ) else {
+            return;
+        };
+        ids.swap(a, b);
+        let _ = model::set_todo_positions(&self.conn, &ids);
+        let _ = self.reload();
+    }
+
+    /// Show or hide completed todos and persist the choice.
+    fn toggle_hide_completed(&mut self) {
+        self.settings.hide_completed = !self.settings.hide_completed;
+        let _ = model::save_settings(&self.conn, &self.settings);
+        self.apply_filter();
+    }
+
+    /// List title: sort mode, hidden count, selection and filter.
+    fn list_title(&self) -> String {
+        let mut status = self.settings.sort_mode.as_str().to_string();
+        if self.settings.hide_completed {
+            status.push_str(&format!(", {} done hidden", self.hidden_count));
+        }
+        if self.visual {
+            status.push_str(&format!(", VISUAL {} selected", self.marked.len()));
+        }
+        match &self.filter {
+            Some(f) => format!(" Todo [{}] [/{}] ", status, f),
+            None => format!(" Todo [{}] ", status),
+        }
+    }
+
+    /// Record an action for `u`, dropping the oldest past `UNDO_LIMIT`.
+    fn push_undo(&mut self, op: UndoOp) {
+        self.undo_stack.push(op);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Revert the most recent action and select the todo it touched.
+    fn undo(&mut self) {
+        let Some(op) = self.undo_stack.pop() else {
+            return;
+        };
+        let selected = match op {
+            UndoOp::Added(id) => {
+                let _ = model::delete_todo(&self.conn, id);
+                None
+            }
+            UndoOp::Deleted(todos) => {
+                let _ = model::restore_todos(&self.conn, &todos);
+                todos.first().map(|t| t.id)
+            }
+            UndoOp::Completion(previous) => {
+                let _ = model::set_completed(&self.conn, &previous);
+                previous.first().map(|&(id, _)| id)
+            }
+            UndoOp::Edited {
+                id,
+                title,
+                due_date,
+            } => {
+                let description = self
+                    .todos
+                    .iter()
+                    .find(|t| t.id == id)
+                    .and_then(|t| t.description.clone());
+                let _ = model::update_todo(&self.conn, id, &title, description.as_deref());
+                let _ = model::set_due_date(&self.conn, id, due_date);
+                Some(id)
+            }
+        };
+        let _ = self.reload();
+        if let Some(id) = selected
+            && let Some(pos) = self.filtered.iter().position(|&i| self.todos[i].id == id)
+        {
+            self.list_state.select(Some(pos));
+        }
+    }
+
+    /// Enter visual mode with the selected todo marked.
+    fn start_visual(&mut self) {
+        self.visual = true;
+        self.marked.clear();
+        if let Some(id) = self.selected_todo_id() {
+            self.marked.insert(id);
+        }
+    }
+
+    /// Leave visual mode and clear the selection.
+    fn clear_visual(&mut self) {
+        self.visual = false;
+        self.marked.clear();
+    }
+
+    /// Keys in visual mode: `j`/`k` move and extend the selection, `Space`
+    /// toggles the current todo, `Enter`/`x` completes and `d` deletes the
+    /// marked todos, `Esc` clears the selection.
+    fn handle_visual_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.select_offset(1);
+                if let Some(id) = self.selected_todo_id() {
+                    self.marked.insert(id);
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.select_offset(-1);
+                if let Some(id) = self.selected_todo_id() {
+                    self.marked.insert(id);
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(id) = self.selected_todo_id()
+                    && !self.marked.remove(&id)
+                {
+                    self.marked.insert(id);
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('x') => {
+                let previous: Vec<(i64, bool)> = self
+                    .todos
+                    .iter()
+                    .filter(|t| self.marked.contains(&t.id))
+                    .map(|t| (t.id, t.completed))
+                    .collect();
+                let changes: Vec<(i64, bool)> =
+                    previous.iter().map(|&(id, _)| (id, true)).collect();
+                if model::set_completed(&self.conn, &changes).is_ok() {
+                    self.push_undo(UndoOp::Completion(previous));
+                }
+                self.clear_visual();
+                let _ = self.reload();
+            }
+            KeyCode::Char('d') => {
+                let deleted: Vec<Todo> = self
+                    .todos
+                    .iter()
+                    .filter(|t| self.marked.contains(&t.id))
+                    .cloned()
+                    .collect();
+                let ids: Vec<i64> = deleted.iter().map(|t| t.id).collect();
+                if model::delete_todos(&self.conn, &ids).is_ok() {
+                    self.push_undo(UndoOp::Deleted(deleted));
+                }
+                self.clear_visual();
+                let _ = self.reload();
+            }
+            KeyCode::Esc | KeyCode::Char('v') => self.clear_visual(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Move the selection by `delta` rows, clamped to the list.
+    fn select_offset(&mut self, delta: isize) {
+        if self.filtered.is_empty() {
+            return;
+        }
+        let sel = self.list_state.selected().unwrap_or(0);
+        let next = sel
+            .saturating_add_signed(delta)
+            .min(self.filtered.len() - 1);
+        self.list_state.select(Some(next));
+    }
+
+    /// Switch to the next sort mode and persist it.
+    fn cycle_sort_mode(&mut self) {
+        self.settings.sort_mode = self.settings.sort_mode.next();
+        let _ = model::save_settings(&self.conn, &self.settings);
+        self.apply_filter();
+    }
+
     /// Get the currently selected todo (if any).
     fn selected_todo(&self) -> Option<&Todo> {
         let sel = self.list_state.selected()?;
@@ -151,7 +425,9 @@ impl TodoTool {
     /// Start editing the selected todo.
     fn start_edit(&mut self) {
         let sel = self.list_state.selected().unwrap_or(0);
-        let title = self.selected_todo().map(|t| t.title.clone());
+        let title = self
+            .selected_todo()
+            .map(|t| due::with_due(&t.title, t.due_date));
         if let Some(title) = title {
             self.mode = InputMode::Insert;
             self.input_cursor = title.len();
@@ -173,21 +449,32 @@ impl TodoTool {
         let input = self.input.trim().to_string();
         match self.edit_context.take() {
             Some(EditContext::Adding) => {
-                if !input.is_empty() {
-                    let _ = model::add_todo(&self.conn, &input, None);
+                let (title, due_date) = due::parse_due(&input, today());
+                if !title.is_empty() {
+                    if let Ok(id) = model::add_todo(&self.conn, &title, None) {
+                        let _ = model::set_due_date(&self.conn, id, due_date);
+                        self.push_undo(UndoOp::Added(id));
+                    }
                     let _ = self.reload();
                 }
             }
             Some(EditContext::Editing(idx)) => {
+                let (input, due_date) = due::parse_due(&input, today());
                 if !input.is_empty() {
                     if let Some(&todo_idx) = self.filtered.get(idx) {
-                        if let Some(todo) = self.todos.get(todo_idx) {
+                        if let Some(todo) = self.todos.get(todo_idx).cloned() {
+                            self.push_undo(UndoOp::Edited {
+                                id: todo.id,
+                                title: todo.title.clone(),
+                                due_date: todo.due_date,
+                            });
                             let _ = model::update_todo(
                                 &self.conn,
                                 todo.id,
                                 &input,
                                 todo.description.as_deref(),
                             );
+                            let _ = model::set_due_date(&self.conn, todo.id, due_date);
                             let _ = self.reload();
                         }
                     }
@@ -317,6 +604,10 @@ impl Tool for TodoTool {
         self.mode
     }
 
+    fn pending_keys(&self) -> String {
+        self.key_state.pending_keys()
+    }
+
     fn init_db(&self, conn: &Connection) -> anyhow::Result<()> {
         model::init_db(conn)
     }
@@ -335,6 +626,21 @@ impl Tool for TodoTool {
             HelpEntry::with_section("Todo", "Enter", "Toggle complete"),
             HelpEntry::with_section("Todo", "dd", "Delete todo"),
             HelpEntry::with_section("Todo", "/", "Filter todos"),
+            HelpEntry::with_section(
+                "Todo",
+                "s",
+                "Cycle sort mode (manual, created, a-z, completed last, due)",
+            ),
+            HelpEntry::with_section("Todo", "v", "Multi-select (j/k extend, Space toggle)"),
+            HelpEntry::with_section("Todo", "v Enter / v d", "Complete / delete selected todos"),
+            HelpEntry::with_section("Todo", "u", "Undo last add / delete / toggle / edit"),
+            HelpEntry::with_section("Todo", "H", "Hide / show completed todos"),
+            HelpEntry::with_section("Todo", "J / K", "Move todo down / up (manual order)"),
+            HelpEntry::with_section(
+                "Todo",
+                "@date",
+                "Due date suffix (YYYY-MM-DD, today, tomorrow)",
+            ),
             HelpEntry::with_section("Todo", "Esc", "Cancel input / clear filter"),
         ]
     }
@@ -354,6 +660,24 @@ impl Tool for TodoTool {
             .collect()
     }
 
+    fn telescope_preview(&self, id: &str) -> Option<String> {
+        let todo_id = id.strip_prefix("todo:")?.parse::<i64>().ok()?;
+        let todo = self.todos.iter().find(|t| t.id == todo_id)?;
+        let mut text = format!(
+            "{}\n\nStatus: {}",
+            todo.title,
+            if todo.completed { "done" } else { "open" }
+        );
+        if let Some(due) = todo.due_date {
+            text.push_str(&format!("\nDue: {}", due.format(due::DATE_FORMAT)));
+        }
+        text.push_str(&format!("\nCreated: {}", todo.created_at));
+        if let Some(description) = todo.description.as_deref().filter(|d| !d.is_empty()) {
+            text.push_str(&format!("\n\n{description}"));
+        }
+        Some(text)
+    }
+
     fn handle_telescope_selection(&mut self, id: &str) -> bool {
         let Some(raw_id) = id.strip_prefix("todo:") else {
             return false;
@@ -370,6 +694,40 @@ impl Tool for TodoTool {
         match self.mode {
             InputMode::Insert => self.handle_insert_key(key),
             InputMode::Normal => {
+                if self.visual {
+                    return self.handle_visual_key(key);
+                }
+
+                if self.key_state.pending_key.is_none() {
+                    match key.code {
+                        KeyCode::Char('s') => {
+                            self.cycle_sort_mode();
+                            return Action::None;
+                        }
+                        KeyCode::Char('u') => {
+                            self.undo();
+                            return Action::None;
+                        }
+                        KeyCode::Char('v') => {
+                            self.start_visual();
+                            return Action::None;
+                        }
+                        KeyCode::Char('H') => {
+                            self.toggle_hide_completed();
+                            return Action::None;
+                        }
+                        KeyCode::Char('J') => {
+                            self.move_selected_todo(true);
+                            return Action::None;
+                        }
+                        KeyCode::Char('K') => {
+                            self.move_selected_todo(false);
+                            return Action::None;
+                        }
+                        _ => {}
+                    }
+                }
+
                 let action = process_normal_key(key, &mut self.key_state);
                 match action {
                     Action::MoveDown(n) => {
@@ -418,15 +776,17 @@ impl Tool for TodoTool {
                         Action::None
                     }
                     Action::Confirm => {
-                        if let Some(id) = self.selected_todo_id() {
-                            let _ = model::toggle_todo(&self.conn, id);
+                        if let Some(todo) = self.selected_todo().cloned() {
+                            let _ = model::toggle_todo(&self.conn, todo.id);
+                            self.push_undo(UndoOp::Completion(vec![(todo.id, todo.completed)]));
                             let _ = self.reload();
                         }
                         Action::None
                     }
                     Action::Delete => {
-                        if let Some(id) = self.selected_todo_id() {
-                            let _ = model::delete_todo(&self.conn, id);
+                        if let Some(todo) = self.selected_todo().cloned() {
+                            let _ = model::delete_todo(&self.conn, todo.id);
+                            self.push_undo(UndoOp::Deleted(vec![todo]));
                             let _ = self.reload();
                         }
                         Action::None
@@ -543,7 +903,9 @@ impl Tool for TodoTool {
                 list_area,
                 &visible,
                 &mut state,
-                self.filter.as_deref(),
+                &self.list_title(),
+                &self.marked,
+                today(),
             );
 
             let prompt = match &self.edit_context {
@@ -555,7 +917,15 @@ impl Tool for TodoTool {
             ui::render_todo_input(frame, input_area, prompt, &self.input, self.input_cursor);
         } else {
             let mut state = self.list_state.clone();
-            ui::render_todo_list(frame, area, &visible, &mut state, self.filter.as_deref());
+            ui::render_todo_list(
+                frame,
+                area,
+                &visible,
+                &mut state,
+                &self.list_title(),
+                &self.marked,
+                today(),
+            );
         }
     }
 
@@ -566,4 +936,188 @@ impl Tool for TodoTool {
     fn on_focus(&mut self) {
         let _ = self.reload();
     }
+
+    fn on_blur(&mut self) {
+        // Keep a half-typed todo or edit instead of dropping it
+        if self.edit_context.is_some() {
+            self.submit_input();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstools_core::db::open_memory_db;
+
+    #[test]
+    fn test_hide_completed_composes_with_filter() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let done = model::add_todo(&conn, "buy milk", None).unwrap();
+        model::add_todo(&conn, "buy bread", None).unwrap();
+        model::add_todo(&conn, "call mom", None).unwrap();
+        model::toggle_todo(&conn, done).unwrap();
+
+        let mut tool = TodoTool::new(conn).unwrap();
+        tool.toggle_hide_completed();
+        tool.filter = Some("buy".to_string());
+        tool.apply_filter();
+        let titles: Vec<&str> = tool
+            .visible_todos()
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["buy bread"]);
+        assert_eq!(tool.hidden_count, 1);
+
+        // The setting survives a restart
+        let tool = TodoTool::new(tool.conn).unwrap();
+        assert!(tool.settings.hide_completed);
+    }
+
+    fn press(tool: &mut TodoTool, code: KeyCode) {
+        tool.handle_key(KeyEvent::from(code));
+    }
+
+    #[test]
+    fn test_blur_flushes_pending_edit() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let mut tool = TodoTool::new(conn).unwrap();
+        press(&mut tool, KeyCode::Char('a'));
+        for c in "water plants".chars() {
+            press(&mut tool, KeyCode::Char(c));
+        }
+
+        tool.on_blur();
+        assert_eq!(tool.mode, InputMode::Normal);
+        let todos = model::list_todos(&tool.conn).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title, "water plants");
+    }
+
+    #[test]
+    fn test_move_todo_with_shift_j_k() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        for title in ["b", "a", "c"] {
+            model::add_todo(&conn, title, None).unwrap();
+        }
+        let mut tool = TodoTool::new(conn).unwrap();
+        tool.settings.sort_mode = model::SortMode::Alphabetical;
+        tool.apply_filter();
+        tool.list_state.select(Some(0)); // a
+
+        let titles = |tool: &TodoTool| -> Vec<String> {
+            tool.visible_todos()
+                .iter()
+                .map(|t| t.title.clone())
+                .collect()
+        };
+
+        // Moving switches to manual order (newest first) with the todo still
+        // selected; it is last there, so it cannot move further down
+        press(&mut tool, KeyCode::Char('J'));
+        assert_eq!(tool.settings.sort_mode, model::SortMode::Manual);
+        assert_eq!(titles(&tool), vec!["c", "b", "a"]);
+        assert_eq!(tool.list_state.selected(), Some(2));
+
+        // The selection follows the moved todo and stops at the top
+        press(&mut tool, KeyCode::Char('K'));
+        press(&mut tool, KeyCode::Char('K'));
+        press(&mut tool, KeyCode::Char('K'));
+        assert_eq!(titles(&tool), vec!["a", "c", "b"]);
+        assert_eq!(tool.list_state.selected(), Some(0));
+        press(&mut tool, KeyCode::Char('J'));
+        assert_eq!(titles(&tool), vec!["c", "a", "b"]);
+        assert_eq!(tool.list_state.selected(), Some(1));
+
+        // The order is persisted
+        let tool = TodoTool::new(tool.conn).unwrap();
+        assert_eq!(titles(&tool), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_visual_bulk_complete_and_delete() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        for title in ["one", "two", "three", "four"] {
+            model::add_todo(&conn, title, None).unwrap();
+        }
+        let mut tool = TodoTool::new(conn).unwrap();
+        tool.settings.sort_mode = model::SortMode::Alphabetical;
+        tool.apply_filter();
+        tool.list_state.select(Some(0)); // four
+
+        // four + one marked, then one toggled off again
+        press(&mut tool, KeyCode::Char('v'));
+        press(&mut tool, KeyCode::Char('j'));
+        press(&mut tool, KeyCode::Char(' '));
+        press(&mut tool, KeyCode::Enter);
+        assert!(!tool.visual && tool.marked.is_empty());
+        let done: Vec<&str> = tool
+            .todos
+            .iter()
+            .filter(|t| t.completed)
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(done, vec!["four"]);
+
+        tool.list_state.select(Some(2)); // three
+        press(&mut tool, KeyCode::Char('v'));
+        press(&mut tool, KeyCode::Char('j'));
+        press(&mut tool, KeyCode::Char('d'));
+        let titles: Vec<&str> = tool
+            .visible_todos()
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["four", "one"]);
+
+        press(&mut tool, KeyCode::Char('v'));
+        press(&mut tool, KeyCode::Esc);
+        assert!(!tool.visual && tool.marked.is_empty());
+    }
+
+    #[test]
+    fn test_undo_restores_deleted_todo() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        let id = model::add_todo(&conn, "keep me", Some("details")).unwrap();
+        let due = NaiveDate::from_ymd_opt(2024, 6, 1);
+        model::set_due_date(&conn, id, due).unwrap();
+        let mut tool = TodoTool::new(conn).unwrap();
+        let original = tool.todos[0].clone();
+
+        press(&mut tool, KeyCode::Enter);
+        assert!(tool.todos[0].completed);
+        press(&mut tool, KeyCode::Char('d'));
+        press(&mut tool, KeyCode::Char('d'));
+        assert!(tool.todos.is_empty());
+
+        press(&mut tool, KeyCode::Char('u'));
+        let restored = &tool.todos[0];
+        assert_eq!(restored.id, original.id);
+        assert_eq!(restored.description.as_deref(), Some("details"));
+        assert_eq!(restored.due_date, due);
+        assert_eq!(restored.created_at, original.created_at);
+        assert!(restored.completed);
+
+        press(&mut tool, KeyCode::Char('u'));
+        assert!(!tool.todos[0].completed);
+        assert!(tool.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn test_undo_stack_is_capped() {
+        let conn = open_memory_db().unwrap();
+        model::init_db(&conn).unwrap();
+        model::add_todo(&conn, "flip", None).unwrap();
+        let mut tool = TodoTool::new(conn).unwrap();
+        for _ in 0..UNDO_LIMIT + 5 {
+            press(&mut tool, KeyCode::Enter);
+        }
+        assert_eq!(tool.undo_stack.len(), UNDO_LIMIT);
+    }
 }
@@ -1,6 +1,12 @@
+use std::cmp::{Ordering, Reverse};
+
 use anyhow::Result;
+use chrono::NaiveDate;
+use rstools_core::db::{self, Migration};
 use rusqlite::Connection;
 
+use crate::due::DATE_FORMAT;
+
 /// A single todo item.
 #[derive(Debug, Clone)]
 pub struct Todo {
@@ -8,10 +14,107 @@ pub struct Todo {
     pub title: String,
     pub completed: bool,
     pub description: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    /// Manual order (ascending); ties fall back to newest first.
+    pub position: i64,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Order of the todo list, cycled with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// Hand-curated order, changed with `J`/`K`.
+    Manual,
+    /// Newest first.
+    Created,
+    /// By title, case-insensitive.
+    Alphabetical,
+    /// Open todos first, newest first within each group.
+    CompletedLast,
+    /// Open todos first, soonest due first, undated last.
+    DueDate,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Manual,
+        SortMode::Created,
+        SortMode::Alphabetical,
+        SortMode::CompletedLast,
+        SortMode::DueDate,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Created => "created",
+            SortMode::Alphabetical => "alphabetical",
+            SortMode::CompletedLast => "completed-last",
+            SortMode::DueDate => "due",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s)
+    }
+
+    /// The mode after this one in the `s` cycle.
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Compare two todos under this mode. Ties fall back to newest first.
+    pub fn compare(self, a: &Todo, b: &Todo) -> Ordering {
+        let newest = |t: &Todo| (Reverse(t.created_at.clone()), Reverse(t.id));
+        match self {
+            SortMode::Manual => a.position.cmp(&b.position),
+            SortMode::Created => Ordering::Equal,
+            SortMode::Alphabetical => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortMode::CompletedLast => a.completed.cmp(&b.completed),
+            SortMode::DueDate => (a.completed, a.due_date.is_none(), a.due_date).cmp(&(
+                b.completed,
+                b.due_date.is_none(),
+                b.due_date,
+            )),
+        }
+        .then_with(|| newest(a).cmp(&newest(b)))
+    }
+}
+
+/// Tool-wide settings, stored as key/value rows in `todo_settings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoSettings {
+    pub sort_mode: SortMode,
+    /// Leave completed todos out of the list.
+    pub hide_completed: bool,
+}
+
+impl Default for TodoSettings {
+    fn default() -> Self {
+        Self {
+            sort_mode: SortMode::CompletedLast,
+            hide_completed: false,
+        }
+    }
+}
+
+/// Schema changes made after the tables below were first released, oldest
+/// first. Append new steps; never edit or reorder released ones.
+const MIGRATIONS: &[Migration] = &[
+    Migration::AddColumn {
+        table: "todos",
+        column: "due_date",
+        definition: "TEXT",
+    },
+    Migration::AddColumn {
+        table: "todos",
+        column: "position",
+        definition: "INTEGER NOT NULL DEFAULT 0",
+    },
+];
+
 /// Initialize the todos table.
 pub fn init_db(conn: &Connection) -> Result<()> {
     conn.execute_batch(
@@ -28,17 +131,23 @@ pub fn init_db(conn: &Connection) -> Result<()> {
         AFTER UPDATE ON todos
         BEGIN
             UPDATE todos SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
-        END;",
+        END;
+
+        CREATE TABLE IF NOT EXISTS todo_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
     )?;
-    Ok(())
+
+    db::migrate(conn, "todo", MIGRATIONS)
 }
 
-/// Fetch all todos, ordered by creation date (newest first for incomplete, then completed).
+/// Fetch all todos in manual order (newest first among equal positions).
 pub fn list_todos(conn: &Connection) -> Result<Vec<Todo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, title, completed, description, created_at, updated_at
+        "SELECT id, title, completed, description, due_date, position, created_at, updated_at
          FROM todos
-         ORDER BY completed ASC, created_at DESC",
+         ORDER BY position ASC, created_at DESC, id DESC",
     )?;
 
     let todos = stmt
@@ -48,8 +157,12 @@ pub fn list_todos(conn: &Connection) -> Result<Vec<Todo>> {
                 title: row.get(1)?,
                 completed: row.get(2)?,
                 description: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
+                due_date: row
+                    .get::<_, Option<String>>(4)?
+                    .and_then(|d| NaiveDate::parse_from_str(&d, DATE_FORMAT).ok()),
+                position: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -57,10 +170,11 @@ pub fn list_todos(conn: &Connection) -> Result<Vec<Todo>> {
     Ok(todos)
 }
 
-/// Insert a new todo. Returns the new todo's id.
+/// Insert a new todo at the top of the manual order. Returns the new todo's id.
 pub fn add_todo(conn: &Connection, title: &str, description: Option<&str>) -> Result<i64> {
     conn.execute(
-        "INSERT INTO todos (title, description) VALUES (?1, ?2)",
+        "INSERT INTO todos (title, description, position)
+         VALUES (?1, ?2, (SELECT COALESCE(MIN(position), 0) - 1 FROM todos))",
         rusqlite::params![title, description],
     )?;
     Ok(conn.last_insert_rowid())
@@ -75,6 +189,20 @@ pub fn toggle_todo(conn: &Connection, id: i64) -> Result<()> {
     Ok(())
 }
 
+/// Mark todos as completed or open, given (id, completed) pairs. All or
+/// none are updated.
+pub fn set_completed(conn: &Connection, changes: &[(i64, bool)]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for &(id, completed) in changes {
+        tx.execute(
+            "UPDATE todos SET completed = ?1 WHERE id = ?2",
+            rusqlite::params![completed, id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
 /// Update a todo's title (and optionally description).
 pub fn update_todo(
     conn: &Connection,
@@ -89,17 +217,147 @@ pub fn update_todo(
     Ok(())
 }
 
+/// Set or clear a todo's due date.
+pub fn set_due_date(conn: &Connection, id: i64, due_date: Option<NaiveDate>) -> Result<()> {
+    let due_date = due_date.map(|d| d.format(DATE_FORMAT).to_string());
+    conn.execute(
+        "UPDATE todos SET due_date = ?1 WHERE id = ?2",
+        rusqlite::params![due_date, id],
+    )?;
+    Ok(())
+}
+
+/// Persist a manual order: each todo's position becomes its index in `ids`.
+pub fn set_todo_positions(conn: &Connection, ids: &[i64]) -> Result<()> {
+    // One transaction, so a failure cannot leave a half-renumbered list
+    let tx = conn.unchecked_transaction()?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE todos SET position = ?1 WHERE id = ?2",
+            rusqlite::params![position as i64, id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Re-insert deleted todos with their original ids and fields; all or none
+/// are restored.
+pub fn restore_todos(conn: &Connection, todos: &[Todo]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for todo in todos {
+        let due_date = todo.due_date.map(|d| d.format(DATE_FORMAT).to_string());
+        tx.execute(
+            "INSERT INTO todos
+                (id, title, completed, description, due_date, position, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                todo.id,
+                todo.title,
+                todo.completed,
+                todo.description,
+                due_date,
+                todo.position,
+                todo.created_at,
+                todo.updated_at,
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
 /// Delete a todo by id.
 pub fn delete_todo(conn: &Connection, id: i64) -> Result<()> {
     conn.execute("DELETE FROM todos WHERE id = ?1", [id])?;
     Ok(())
 }
 
+/// Delete several todos; all or none are removed.
+pub fn delete_todos(conn: &Connection, ids: &[i64]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for id in ids {
+        tx.execute("DELETE FROM todos WHERE id = ?1", [id])?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Load settings, falling back to defaults for missing or invalid rows.
+pub fn load_settings(conn: &Connection) -> Result<TodoSettings> {
+    let mut settings = TodoSettings::default();
+    let mut stmt = conn.prepare("SELECT key, value FROM todo_settings")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (key, value) in rows {
+        match key.as_str() {
+            "sort_mode" => {
+                if let Some(mode) = SortMode::parse(&value) {
+                    settings.sort_mode = mode;
+                }
+            }
+            "hide_completed" => {
+                if let Ok(hide) = value.parse() {
+                    settings.hide_completed = hide;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// Persist settings.
+pub fn save_settings(conn: &Connection, settings: &TodoSettings) -> Result<()> {
+    let values = [
+        ("sort_mode", settings.sort_mode.as_str().to_string()),
+        ("hide_completed", settings.hide_completed.to_string()),
+    ];
+    for (key, value) in values {
+        conn.execute(
+            "INSERT INTO todo_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstools_core::db::open_memory_db;
 
+    #[test]
+    fn test_migrate_old_schema() {
+        let conn = open_memory_db().unwrap();
+        // The first released schema, before due dates and manual order
+        conn.execute_batch(
+            "CREATE TABLE todos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                completed BOOLEAN NOT NULL DEFAULT 0,
+                description TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO todos (title, completed) VALUES ('Keep me', 1);",
+        )
+        .unwrap();
+
+        init_db(&conn).unwrap();
+        init_db(&conn).unwrap();
+        let todos = list_todos(&conn).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title, "Keep me");
+        assert!(todos[0].completed);
+        assert_eq!(todos[0].due_date, None);
+    }
+
     #[test]
     fn test_crud_operations() {
         let conn = open_memory_db().unwrap();
@@ -127,9 +385,85 @@ mod tests {
         assert_eq!(todos[0].title, "Updated");
         assert_eq!(todos[0].description.as_deref(), Some("A description"));
 
+        // Due date
+        let due = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        set_due_date(&conn, id, Some(due)).unwrap();
+        assert_eq!(list_todos(&conn).unwrap()[0].due_date, Some(due));
+        set_due_date(&conn, id, None).unwrap();
+        assert_eq!(list_todos(&conn).unwrap()[0].due_date, None);
+
         // Delete
         delete_todo(&conn, id).unwrap();
         let todos = list_todos(&conn).unwrap();
         assert!(todos.is_empty());
     }
+
+    #[test]
+    fn test_bulk_operations() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+        let a = add_todo(&conn, "a", None).unwrap();
+        let b = add_todo(&conn, "b", None).unwrap();
+
+        set_completed(&conn, &[(a, true), (b, true)]).unwrap();
+        assert!(list_todos(&conn).unwrap().iter().all(|t| t.completed));
+
+        let saved = list_todos(&conn).unwrap();
+        delete_todos(&conn, &[a, b]).unwrap();
+        assert!(list_todos(&conn).unwrap().is_empty());
+
+        // Restoring is all or nothing: a clashing id rolls the batch back
+        add_todo(&conn, "clash", None).unwrap();
+        let clash = list_todos(&conn).unwrap()[0].clone();
+        let mut batch = saved.clone();
+        batch.push(clash);
+        assert!(restore_todos(&conn, &batch).is_err());
+        assert_eq!(list_todos(&conn).unwrap().len(), 1);
+
+        delete_todos(&conn, &[list_todos(&conn).unwrap()[0].id]).unwrap();
+        restore_todos(&conn, &saved).unwrap();
+        assert_eq!(list_todos(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+        let b = add_todo(&conn, "banana", None).unwrap();
+        let a = add_todo(&conn, "Apple", None).unwrap();
+        let c = add_todo(&conn, "cherry", None).unwrap();
+        toggle_todo(&conn, c).unwrap();
+        set_due_date(&conn, b, NaiveDate::from_ymd_opt(2024, 1, 2)).unwrap();
+
+        let todos = list_todos(&conn).unwrap();
+        let sorted = |mode: SortMode| {
+            let mut ids: Vec<&Todo> = todos.iter().collect();
+            ids.sort_by(|x, y| mode.compare(x, y));
+            ids.iter().map(|t| t.id).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(SortMode::Created), vec![c, a, b]);
+        assert_eq!(sorted(SortMode::Alphabetical), vec![a, b, c]);
+        assert_eq!(sorted(SortMode::CompletedLast), vec![a, b, c]);
+        assert_eq!(sorted(SortMode::DueDate), vec![b, a, c]);
+        assert_eq!(sorted(SortMode::Manual), vec![c, a, b]);
+        assert_eq!(SortMode::DueDate.next(), SortMode::Manual);
+
+        set_todo_positions(&conn, &[b, c, a]).unwrap();
+        let ids: Vec<i64> = list_todos(&conn).unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![b, c, a]);
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let conn = open_memory_db().unwrap();
+        init_db(&conn).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), TodoSettings::default());
+
+        let settings = TodoSettings {
+            sort_mode: SortMode::Alphabetical,
+            hide_completed: true,
+        };
+        save_settings(&conn, &settings).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), settings);
+    }
 }
@@ -6,15 +6,24 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
 
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::due::{DATE_FORMAT, DueStatus};
 use crate::model::Todo;
+use rstools_core::theme::theme;
 
-/// Render the todo list.
+/// Render the todo list. Todos whose id is in `marked` (multi-select)
+/// get a marker in front of the checkbox.
 pub fn render_todo_list(
     frame: &mut Frame,
     area: Rect,
     todos: &[Todo],
     list_state: &mut ListState,
-    filter: Option<&str>,
+    title: &str,
+    marked: &HashSet<i64>,
+    today: NaiveDate,
 ) {
     let [list_area, info_area] =
         Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(area);
@@ -30,7 +39,16 @@ pub fn render_todo_list(
                 Style::default()
             };
 
+            let marker = if marked.contains(&todo.id) {
+                Span::styled("* ", Style::default().fg(theme().info))
+            } else if marked.is_empty() {
+                Span::raw("")
+            } else {
+                Span::raw("  ")
+            };
+
             let mut spans = vec![
+                marker,
                 Span::styled(
                     format!("{} ", checkbox),
                     if todo.completed {
@@ -42,6 +60,19 @@ pub fn render_todo_list(
                 Span::styled(&todo.title, style),
             ];
 
+            if let Some(due) = todo.due_date {
+                let due_style = match DueStatus::of(due, today) {
+                    _ if todo.completed => Style::default().add_modifier(Modifier::DIM),
+                    DueStatus::Overdue => Style::default().fg(theme().error),
+                    DueStatus::Today => Style::default().fg(theme().accent),
+                    DueStatus::Upcoming => Style::default().add_modifier(Modifier::DIM),
+                };
+                spans.push(Span::styled(
+                    format!("  @{}", due.format(DATE_FORMAT)),
+                    due_style,
+                ));
+            }
+
             if let Some(desc) = &todo.description {
                 if !desc.is_empty() {
                     spans.push(Span::styled(
@@ -55,12 +86,6 @@ pub fn render_todo_list(
         })
         .collect();
 
-    let title = if let Some(f) = filter {
-        format!(" Todo [/{}] ", f)
-    } else {
-        " Todo ".to_string()
-    };
-
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
@@ -85,7 +110,7 @@ pub fn render_todo_list(
             Style::default().add_modifier(Modifier::DIM),
         ),
         Span::styled(
-            "  a:add  e:edit  dd:del  Enter:toggle  /:filter",
+            "  a:add  e:edit  dd:del  Enter:toggle  /:filter  s:sort  H:hide done",
             Style::default().add_modifier(Modifier::DIM),
         ),
     ]));